@@ -13,6 +13,8 @@ mod m20251217_000010_add_connection_environment;
 mod m20251219_000011_schema_cache;
 mod m20251220_000012_create_saved_filters;
 mod m20251222_000013_add_connection_status_and_tags;
+mod m20251223_000014_add_saved_filter_tree;
+mod m20251224_000015_add_connection_query_limits;
 
 pub struct Migrator;
 
@@ -33,6 +35,8 @@ impl MigratorTrait for Migrator {
             Box::new(m20251219_000011_schema_cache::Migration),
             Box::new(m20251220_000012_create_saved_filters::Migration),
             Box::new(m20251222_000013_add_connection_status_and_tags::Migration),
+            Box::new(m20251223_000014_add_saved_filter_tree::Migration),
+            Box::new(m20251224_000015_add_connection_query_limits::Migration),
         ]
     }
 }
@@ -0,0 +1,73 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Connections::Table)
+                    .add_column(ColumnDef::new(Connections::StatementTimeoutMs).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Connections::Table)
+                    .add_column(ColumnDef::new(Connections::MaxResultRows).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Connections::Table)
+                    .add_column(ColumnDef::new(Connections::MaxResultBytes).big_integer())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Connections::Table)
+                    .drop_column(Connections::StatementTimeoutMs)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Connections::Table)
+                    .drop_column(Connections::MaxResultRows)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Connections::Table)
+                    .drop_column(Connections::MaxResultBytes)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Connections {
+    Table,
+    StatementTimeoutMs,
+    MaxResultRows,
+    MaxResultBytes,
+}
@@ -1,13 +1,18 @@
+use crate::services::connection_service::ConnectionService;
+use crate::services::driver_registry::DriverRegistry;
+use crate::services::query_cancel::{CancelOutcome, QueryHandle};
+use crate::services::transaction_manager::TransactionManager;
 use dashmap::DashMap;
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
-use tokio_util::sync::CancellationToken;
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: DatabaseConnection,
-    pub queries: Arc<DashMap<String, CancellationToken>>,
+    pub queries: Arc<DashMap<String, Arc<QueryHandle>>>,
     pub schema_cache: Arc<crate::services::autocomplete::SchemaCacheService>,
+    pub drivers: Arc<DriverRegistry>,
+    pub transactions: Arc<TransactionManager>,
 }
 
 impl AppState {
@@ -19,6 +24,40 @@ impl AppState {
             db,
             queries: Arc::new(DashMap::new()),
             schema_cache,
+            drivers: Arc::new(DriverRegistry::new()),
+            transactions: Arc::new(TransactionManager::new()),
+        }
+    }
+
+    /// A `ConnectionService` that reuses the shared driver registry and
+    /// open transactions.
+    pub fn connection_service(&self) -> anyhow::Result<ConnectionService> {
+        Ok(ConnectionService::new(self.db.clone())?
+            .with_driver_registry(self.drivers.clone())
+            .with_transaction_manager(self.transactions.clone()))
+    }
+
+    /// Registers a cancellable query under the client-supplied id.
+    pub fn register_query(&self, query_id: &str) -> Arc<QueryHandle> {
+        let handle = Arc::new(QueryHandle::new());
+        self.queries.insert(query_id.to_string(), handle.clone());
+        handle
+    }
+
+    pub fn finish_query(&self, query_id: &str) {
+        self.queries.remove(query_id);
+    }
+
+    /// Stops a registered query on the server (where the engine supports it)
+    /// and wakes up the request waiting for it.
+    pub async fn cancel_query(&self, query_id: &str) -> CancelOutcome {
+        let handle = self
+            .queries
+            .get(query_id)
+            .map(|entry| entry.value().clone());
+        match handle {
+            Some(handle) => handle.cancel().await,
+            None => CancelOutcome::not_found(),
         }
     }
 }
@@ -30,7 +69,7 @@ impl axum::extract::FromRef<AppState> for DatabaseConnection {
     }
 }
 
-impl axum::extract::FromRef<AppState> for Arc<DashMap<String, CancellationToken>> {
+impl axum::extract::FromRef<AppState> for Arc<DashMap<String, Arc<QueryHandle>>> {
     fn from_ref(state: &AppState) -> Self {
         state.queries.clone()
     }
@@ -41,3 +80,15 @@ impl axum::extract::FromRef<AppState> for Arc<crate::services::autocomplete::Sch
         state.schema_cache.clone()
     }
 }
+
+impl axum::extract::FromRef<AppState> for Arc<DriverRegistry> {
+    fn from_ref(state: &AppState) -> Self {
+        state.drivers.clone()
+    }
+}
+
+impl axum::extract::FromRef<AppState> for Arc<TransactionManager> {
+    fn from_ref(state: &AppState) -> Self {
+        state.transactions.clone()
+    }
+}
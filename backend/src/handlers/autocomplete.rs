@@ -1,6 +1,5 @@
 use crate::app_state::AppState;
 use crate::services::autocomplete::{AutocompleteEngine, AutocompleteRequest, Suggestion};
-use crate::services::connection_service::ConnectionService;
 use crate::services::db_driver::DatabaseDriver;
 use crate::services::postgres_driver::PostgresDriver;
 use axum::{extract::State, http::StatusCode, Json};
@@ -16,7 +15,8 @@ pub async fn get_suggestions(
         req.sql,
         req.cursor_pos
     );
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state
+        .connection_service()
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
     let (connection, password) = conn_service
@@ -1,6 +1,5 @@
 use crate::app_state::AppState;
 use crate::models::entities::connection;
-use crate::services::connection_service::ConnectionService;
 use crate::services::driver::QueryDriver;
 use axum::{
     extract::{Path, State},
@@ -67,6 +66,12 @@ pub struct CreateConnectionRequest {
     pub status_color: Option<String>,
     #[serde(default)]
     pub tags: Option<String>,
+    #[serde(default)]
+    pub statement_timeout_ms: Option<i32>,
+    #[serde(default)]
+    pub max_result_rows: Option<i32>,
+    #[serde(default)]
+    pub max_result_bytes: Option<i64>,
 }
 
 #[derive(Deserialize)]
@@ -76,7 +81,7 @@ pub struct SwitchDatabaseRequest {
 
 // List all connections
 pub async fn list_connections(State(state): State<AppState>) -> impl IntoResponse {
-    let service = match ConnectionService::new(state.db.clone()) {
+    let service = match state.connection_service() {
         Ok(service) => service,
         Err(e) => {
             tracing::error!("Failed to create ConnectionService: {}", e);
@@ -115,7 +120,7 @@ pub async fn get_connection(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
 ) -> impl IntoResponse {
-    let service = match ConnectionService::new(state.db.clone()) {
+    let service = match state.connection_service() {
         Ok(service) => service,
         Err(e) => {
             tracing::error!("Failed to create ConnectionService: {}", e);
@@ -150,7 +155,7 @@ pub async fn create_connection(
     State(state): State<AppState>,
     Json(payload): Json<CreateConnectionRequest>,
 ) -> impl IntoResponse {
-    let service = match ConnectionService::new(state.db.clone()) {
+    let service = match state.connection_service() {
         Ok(service) => service,
         Err(e) => {
             tracing::error!("Failed to create ConnectionService: {}", e);
@@ -233,6 +238,9 @@ pub async fn create_connection(
             .environment
             .unwrap_or_else(|| "development".to_string()),
         safe_mode_level: payload.safe_mode_level.unwrap_or(1),
+        statement_timeout_ms: payload.statement_timeout_ms,
+        max_result_rows: payload.max_result_rows,
+        max_result_bytes: payload.max_result_bytes,
         last_used: None,
         created_at: Utc::now().into(),
         updated_at: Utc::now().into(),
@@ -250,7 +258,7 @@ pub async fn update_connection(
     Path(id): Path<Uuid>,
     Json(payload): Json<CreateConnectionRequest>,
 ) -> impl IntoResponse {
-    let service = match ConnectionService::new(state.db.clone()) {
+    let service = match state.connection_service() {
         Ok(service) => service,
         Err(e) => {
             tracing::error!("Failed to create ConnectionService: {}", e);
@@ -309,6 +317,9 @@ pub async fn update_connection(
             .environment
             .unwrap_or_else(|| "development".to_string()),
         safe_mode_level: payload.safe_mode_level.unwrap_or(1),
+        statement_timeout_ms: payload.statement_timeout_ms,
+        max_result_rows: payload.max_result_rows,
+        max_result_bytes: payload.max_result_bytes,
         last_used: None,
         created_at: Utc::now().into(),
         updated_at: Utc::now().into(),
@@ -338,7 +349,7 @@ pub async fn switch_database(
             .into_response();
     }
 
-    let service = match ConnectionService::new(state.db.clone()) {
+    let service = match state.connection_service() {
         Ok(service) => service,
         Err(e) => {
             tracing::error!("Failed to create ConnectionService: {}", e);
@@ -361,7 +372,7 @@ pub async fn delete_connection(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
 ) -> impl IntoResponse {
-    let service = match ConnectionService::new(state.db.clone()) {
+    let service = match state.connection_service() {
         Ok(service) => service,
         Err(e) => {
             tracing::error!("Failed to create ConnectionService: {}", e);
@@ -389,7 +400,7 @@ pub async fn test_connection(
     State(state): State<AppState>,
     Json(payload): Json<CreateConnectionRequest>,
 ) -> impl IntoResponse {
-    let service = match ConnectionService::new(state.db.clone()) {
+    let service = match state.connection_service() {
         Ok(service) => service,
         Err(e) => {
             tracing::error!("Failed to create ConnectionService: {}", e);
@@ -455,6 +466,9 @@ pub async fn test_connection(
             .environment
             .unwrap_or_else(|| "development".to_string()),
         safe_mode_level: payload.safe_mode_level.unwrap_or(1),
+        statement_timeout_ms: payload.statement_timeout_ms,
+        max_result_rows: payload.max_result_rows,
+        max_result_bytes: payload.max_result_bytes,
         last_used: None,
         created_at: Utc::now().into(),
         updated_at: Utc::now().into(),
@@ -513,7 +527,7 @@ pub async fn test_connection_by_id(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
 ) -> impl IntoResponse {
-    let service = match ConnectionService::new(state.db.clone()) {
+    let service = match state.connection_service() {
         Ok(service) => service,
         Err(e) => {
             tracing::error!("Failed to create ConnectionService: {}", e);
@@ -601,7 +615,7 @@ pub async fn get_connection_version(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
 ) -> impl IntoResponse {
-    let service = match ConnectionService::new(state.db.clone()) {
+    let service = match state.connection_service() {
         Ok(s) => s,
         Err(e) => {
             tracing::error!("Failed to create ConnectionService: {}", e);
@@ -690,11 +704,22 @@ pub async fn get_connection_version(
     }
 }
 
+/// SSL modes and certificate options each database type supports.
+pub async fn get_tls_support() -> impl IntoResponse {
+    Json(crate::services::tls::tls_support())
+}
+
+/// Pool statistics of the cached drivers in the registry.
+pub async fn get_pool_stats(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.drivers.stats().await)
+}
+
 pub async fn list_sessions(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
 ) -> impl IntoResponse {
-    let result = ConnectionService::new(state.db.clone())
+    let result = state
+        .connection_service()
         .expect("Failed to create connection service")
         .get_active_sessions(id)
         .await;
@@ -716,7 +741,8 @@ pub async fn kill_session(
     State(state): State<AppState>,
     Path((id, pid)): Path<(Uuid, i32)>,
 ) -> impl IntoResponse {
-    let result = ConnectionService::new(state.db.clone())
+    let result = state
+        .connection_service()
         .expect("Failed to create connection service")
         .kill_session(id, pid)
         .await;
@@ -14,7 +14,7 @@ pub async fn test_connection_by_id(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
 ) -> impl IntoResponse {
-    let service = match ConnectionService::new(state.db.clone()) {
+    let service = match state.connection_service() {
         Ok(service) => service,
         Err(e) => {
             tracing::error!("Failed to create ConnectionService: {}", e);
@@ -0,0 +1,28 @@
+use crate::app_state::AppState;
+use crate::services::data_diff::DataDiffRequest;
+use axum::{
+    extract::{Json, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde_json::json;
+
+/// Compares the rows of two tables and returns the statements syncing the
+/// target.
+pub async fn compare_table_data(
+    State(state): State<AppState>,
+    Json(payload): Json<DataDiffRequest>,
+) -> impl IntoResponse {
+    let service = state
+        .connection_service()
+        .expect("Failed to create service");
+
+    match service.compare_table_data(&payload).await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
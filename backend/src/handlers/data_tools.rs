@@ -1,5 +1,6 @@
 use crate::app_state::AppState;
-use crate::services::connection_service::ConnectionService;
+use crate::services::dump::DumpOptions;
+use crate::utils::http_file;
 use axum::{
     body::Body,
     extract::{Json, Path, Query, State},
@@ -21,13 +22,20 @@ pub struct BackupSqlParams {
     pub clean: Option<bool>,
 }
 
+#[derive(Deserialize)]
+pub struct DumpDatabaseParams {
+    #[serde(flatten)]
+    pub options: DumpOptions,
+}
+
 pub async fn execute_script(
     State(state): State<AppState>,
     headers: HeaderMap,
     Path(connection_id): Path<Uuid>,
     Json(body): Json<ExecuteScriptBody>,
 ) -> impl IntoResponse {
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -56,7 +64,8 @@ pub async fn backup_postgres_sql(
     Query(params): Query<BackupSqlParams>,
 ) -> impl IntoResponse {
     let header_db_override = crate::utils::request::database_override_from_headers(&headers);
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -151,3 +160,44 @@ pub async fn backup_postgres_sql(
     );
     resp
 }
+
+/// Dumps a MySQL or SQLite database without external tools and sends the
+/// file as a download.
+pub async fn dump_database(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(connection_id): Path<Uuid>,
+    Json(payload): Json<DumpDatabaseParams>,
+) -> impl IntoResponse {
+    let service = state
+        .connection_service()
+        .expect("Failed to create service")
+        .with_database_override(crate::utils::request::database_override_from_headers(
+            &headers,
+        ));
+
+    let (suffix, content_type) = if payload.options.gzip {
+        (".sql.gz", "application/gzip")
+    } else {
+        (".sql", "application/sql")
+    };
+    let result = match http_file::temp_path(suffix) {
+        Ok(file) => match service
+            .dump_database(connection_id, file.to_path_buf(), payload.options)
+            .await
+        {
+            Ok(_) => http_file::download(file, &format!("dump{}", suffix), content_type).await,
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e.into()),
+    };
+
+    match result {
+        Ok(response) => response,
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
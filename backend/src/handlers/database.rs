@@ -1,5 +1,4 @@
 use crate::app_state::AppState;
-use crate::services::connection_service::ConnectionService;
 use axum::{
     extract::{Path, State},
     http::StatusCode,
@@ -14,7 +13,9 @@ pub async fn list_databases(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
 ) -> impl IntoResponse {
-    let service = ConnectionService::new(state.db.clone()).expect("Failed to create service");
+    let service = state
+        .connection_service()
+        .expect("Failed to create service");
     match service.get_databases(id).await {
         Ok(databases) => (StatusCode::OK, Json(databases)).into_response(),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
@@ -79,7 +80,9 @@ pub async fn create_database(
             .into_response();
     }
 
-    let service = ConnectionService::new(state.db.clone()).expect("Failed to create service");
+    let service = state
+        .connection_service()
+        .expect("Failed to create service");
     match service.create_database(id, name, payload.options).await {
         Ok(_) => (
             StatusCode::CREATED,
@@ -109,7 +112,9 @@ pub async fn drop_database(
         return (StatusCode::BAD_REQUEST, "Database name cannot be empty").into_response();
     }
 
-    let service = ConnectionService::new(state.db.clone()).expect("Failed to create service");
+    let service = state
+        .connection_service()
+        .expect("Failed to create service");
     match service.drop_database(id, &name).await {
         Ok(_) => (StatusCode::NO_CONTENT, ()).into_response(),
         Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
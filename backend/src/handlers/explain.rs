@@ -8,8 +8,6 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::services::connection_service::ConnectionService;
-
 #[derive(Debug, Deserialize)]
 pub struct ExplainRequest {
     pub sql: String,
@@ -27,7 +25,8 @@ pub async fn explain_query(
     Path(connection_id): Path<String>,
     Json(payload): Json<ExplainRequest>,
 ) -> Result<Json<ExplainResponse>, (StatusCode, String)> {
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
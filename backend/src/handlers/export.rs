@@ -0,0 +1,153 @@
+use crate::app_state::AppState;
+use crate::services::export::{ExportFormat, ExportOptions, ExportSummary, TableExport};
+use crate::utils::http_file;
+use axum::{
+    extract::{Json, Path, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::future::Future;
+use tempfile::TempPath;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct ExportQueryParams {
+    query: String,
+    #[serde(default)]
+    options: ExportOptions,
+    confirmed_unsafe: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct ExportTableParams {
+    #[serde(flatten)]
+    source: TableExport,
+    #[serde(default)]
+    options: ExportOptions,
+}
+
+/// Exports the result of a query and sends the file as a download.
+pub async fn export_query(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(connection_id): Path<Uuid>,
+    Json(payload): Json<ExportQueryParams>,
+) -> impl IntoResponse {
+    let service = state
+        .connection_service()
+        .expect("Failed to create service")
+        .with_database_override(crate::utils::request::database_override_from_headers(
+            &headers,
+        ));
+
+    let format = payload.options.format;
+    let file = match temp_export_file(format) {
+        Ok(file) => file,
+        Err(e) => return error_response(e),
+    };
+    let export = service.export_query(
+        connection_id,
+        &payload.query,
+        payload.confirmed_unsafe.unwrap_or(false),
+        file.to_path_buf(),
+        payload.options,
+    );
+    respond(
+        run_cancellable(&state, &headers, export).await,
+        file,
+        "export",
+    )
+    .await
+}
+
+/// Exports a table, filtered and sorted like the data grid, and sends the
+/// file as a download.
+pub async fn export_table(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(connection_id): Path<Uuid>,
+    Json(payload): Json<ExportTableParams>,
+) -> impl IntoResponse {
+    let service = state
+        .connection_service()
+        .expect("Failed to create service")
+        .with_database_override(crate::utils::request::database_override_from_headers(
+            &headers,
+        ));
+
+    let file = match temp_export_file(payload.options.format) {
+        Ok(file) => file,
+        Err(e) => return error_response(e),
+    };
+    let name = payload.source.table.clone();
+    let export = service.export_table(
+        connection_id,
+        payload.source,
+        file.to_path_buf(),
+        payload.options,
+    );
+    respond(run_cancellable(&state, &headers, export).await, file, &name).await
+}
+
+fn temp_export_file(format: ExportFormat) -> anyhow::Result<TempPath> {
+    Ok(http_file::temp_path(&format!(".{}", format.extension()))?)
+}
+
+/// Runs `export` until it ends or the query in `X-Query-ID` is cancelled.
+/// Cancelling stops reading rows and removes the partial file.
+async fn run_cancellable(
+    state: &AppState,
+    headers: &HeaderMap,
+    export: impl Future<Output = anyhow::Result<ExportSummary>>,
+) -> anyhow::Result<ExportSummary> {
+    let query_id = headers
+        .get("X-Query-ID")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let cancellation_token = query_id
+        .as_deref()
+        .map(|qid| state.register_query(qid).token().clone())
+        .unwrap_or_default();
+
+    let result = tokio::select! {
+        res = export => res,
+        _ = cancellation_token.cancelled() => Err(anyhow::anyhow!("Export cancelled")),
+    };
+
+    if let Some(qid) = &query_id {
+        state.finish_query(qid);
+    }
+    result
+}
+
+/// Sends the exported file named `name`, with its row count in `X-Row-Count`.
+async fn respond(
+    result: anyhow::Result<ExportSummary>,
+    file: TempPath,
+    name: &str,
+) -> axum::response::Response {
+    let summary = match result {
+        Ok(summary) => summary,
+        Err(e) => return error_response(e),
+    };
+    let file_name = format!("{}.{}", name, summary.format.extension());
+    match http_file::download(file, &file_name, summary.format.content_type()).await {
+        Ok(mut response) => {
+            response
+                .headers_mut()
+                .insert("X-Row-Count", HeaderValue::from(summary.row_count));
+            response
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+fn error_response(e: anyhow::Error) -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({ "message": e.to_string() })),
+    )
+        .into_response()
+}
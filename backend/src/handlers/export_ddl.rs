@@ -1,7 +1,6 @@
 use crate::app_state::AppState;
 use crate::models::export_ddl::{ExportDdlOptions, ExportDdlResult};
 use crate::services::clickhouse::ClickHouseDriver;
-use crate::services::connection_service::ConnectionService;
 use crate::services::mysql::MySqlDriver;
 use crate::services::pg_dump::{is_pg_dump_available, run_pg_dump};
 use crate::services::postgres::PostgresDriver;
@@ -17,7 +16,7 @@ pub async fn export_ddl(
     Path(id): Path<Uuid>,
     Json(options): Json<ExportDdlOptions>,
 ) -> Result<Json<ExportDdlResult>, String> {
-    let service = ConnectionService::new(state.db.clone()).map_err(|e| e.to_string())?;
+    let service = state.connection_service().map_err(|e| e.to_string())?;
 
     // Get connection details
     let (conn, password) = service
@@ -1,5 +1,4 @@
 use crate::app_state::AppState;
-use crate::services::connection_service::ConnectionService;
 use axum::{
     extract::{Path, Query, State},
     http::HeaderMap,
@@ -23,7 +22,8 @@ pub async fn list_extensions(
     Query(params): Query<DatabaseOverrideParams>,
 ) -> impl IntoResponse {
     tracing::info!("[API] GET /extensions - connection_id: {}", connection_id);
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
@@ -1,5 +1,4 @@
 use crate::app_state::AppState;
-use crate::services::connection_service::ConnectionService;
 use crate::services::postgres::{ForeignKeyInfo, PostgresDriver};
 use axum::{
     extract::{Path, Query, State},
@@ -19,7 +18,7 @@ pub async fn get_foreign_keys(
     Path(id): Path<Uuid>,
     Query(params): Query<SchemaParams>,
 ) -> Result<Json<Vec<ForeignKeyInfo>>, String> {
-    let service = ConnectionService::new(state.db.clone()).map_err(|e| e.to_string())?;
+    let service = state.connection_service().map_err(|e| e.to_string())?;
 
     // Get connection details
     let (conn, password) = service
@@ -0,0 +1,137 @@
+use crate::app_state::AppState;
+use crate::services::import::{ImportOptions, ImportSettings, ImportTarget};
+use crate::utils::http_file;
+use axum::{
+    body::Body,
+    extract::{Json, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use std::path::Path as FilePath;
+use tempfile::TempPath;
+use uuid::Uuid;
+
+/// The file is sent as the request body; the parameters go JSON-encoded in
+/// the `request` query parameter.
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    request: String,
+}
+
+#[derive(Deserialize)]
+pub struct PreviewImportParams {
+    #[serde(flatten)]
+    target: ImportTarget,
+    /// Name of the uploaded file; its extension hints at the format.
+    #[serde(default)]
+    file_name: Option<String>,
+    #[serde(default)]
+    options: ImportOptions,
+}
+
+#[derive(Deserialize)]
+pub struct ImportFileParams {
+    #[serde(flatten)]
+    target: ImportTarget,
+    #[serde(default)]
+    file_name: Option<String>,
+    #[serde(flatten)]
+    settings: ImportSettings,
+}
+
+/// Sniffs an uploaded file and suggests how its columns map to the table.
+pub async fn preview_import(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(connection_id): Path<Uuid>,
+    Query(query): Query<ImportQuery>,
+    body: Body,
+) -> impl IntoResponse {
+    let service = state
+        .connection_service()
+        .expect("Failed to create service")
+        .with_database_override(crate::utils::request::database_override_from_headers(
+            &headers,
+        ));
+
+    let result = async {
+        let payload: PreviewImportParams = parse_request(&query)?;
+        let file = receive_upload(body, payload.file_name.as_deref()).await?;
+        service
+            .preview_import(
+                connection_id,
+                file.to_path_buf(),
+                payload.target,
+                payload.options,
+            )
+            .await
+    }
+    .await;
+
+    match result {
+        Ok(preview) => (StatusCode::OK, Json(preview)).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
+/// Loads an uploaded file into a table and reports the rows that failed.
+pub async fn import_file(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(connection_id): Path<Uuid>,
+    Query(query): Query<ImportQuery>,
+    body: Body,
+) -> impl IntoResponse {
+    let service = state
+        .connection_service()
+        .expect("Failed to create service")
+        .with_database_override(crate::utils::request::database_override_from_headers(
+            &headers,
+        ));
+
+    let result = async {
+        let payload: ImportFileParams = parse_request(&query)?;
+        let file = receive_upload(body, payload.file_name.as_deref()).await?;
+        service
+            .import_file(
+                connection_id,
+                file.to_path_buf(),
+                payload.target,
+                payload.settings,
+            )
+            .await
+    }
+    .await;
+
+    match result {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
+
+fn parse_request<T: DeserializeOwned>(query: &ImportQuery) -> anyhow::Result<T> {
+    serde_json::from_str(&query.request)
+        .map_err(|e| anyhow::anyhow!("Invalid request parameter: {}", e))
+}
+
+/// Saves the upload to a temporary file, keeping the extension of `file_name`
+/// for format detection. The file is removed when the returned path drops.
+async fn receive_upload(body: Body, file_name: Option<&str>) -> anyhow::Result<TempPath> {
+    let suffix = file_name
+        .and_then(|name| FilePath::new(name).extension())
+        .and_then(|extension| extension.to_str())
+        .map(|extension| format!(".{}", extension))
+        .unwrap_or_default();
+    http_file::receive(body, &suffix).await
+}
@@ -43,3 +43,36 @@ pub async fn generate_mock_data_sql(
         Err(e) => Json(serde_json::json!({ "success": false, "error": e.to_string() })),
     }
 }
+
+#[derive(Deserialize)]
+pub struct InsertMockDataRequest {
+    pub connection_id: Uuid,
+    #[serde(default)]
+    pub schema: String,
+    pub config: GenerationConfig,
+}
+
+pub async fn insert_mock_data(
+    State(state): State<AppState>,
+    Json(payload): Json<InsertMockDataRequest>,
+) -> impl IntoResponse {
+    let service = MockDataService::new(state.db.clone());
+    let connections = match state.connection_service() {
+        Ok(connections) => connections,
+        Err(e) => return Json(serde_json::json!({ "success": false, "error": e.to_string() })),
+    };
+
+    match service
+        .insert_data(
+            &connections,
+            payload.connection_id,
+            &payload.schema,
+            payload.config,
+            None,
+        )
+        .await
+    {
+        Ok(report) => Json(serde_json::json!({ "success": true, "report": report })),
+        Err(e) => Json(serde_json::json!({ "success": false, "error": e.to_string() })),
+    }
+}
@@ -1,13 +1,16 @@
 pub mod autocomplete;
 pub mod connection;
 pub mod dashboard;
+pub mod data_diff;
 pub mod data_tools;
 pub mod database;
 pub mod explain;
+pub mod export;
 pub mod export_ddl;
 pub mod extensions;
 pub mod foreign_key;
 pub mod history;
+pub mod import;
 pub mod mock_data;
 pub mod query;
 pub mod query_stream;
@@ -23,3 +26,5 @@ pub mod settings;
 pub mod snippet;
 pub mod sqlite_tools;
 pub mod table_info;
+pub mod transaction;
+pub mod transfer;
@@ -1,5 +1,7 @@
 use crate::app_state::AppState;
-use crate::services::connection_service::ConnectionService;
+use crate::services::connection_service::QueryOptions;
+use crate::services::query_params::QueryParams;
+use crate::services::script_execution::{ErrorPolicy, StatementStatus};
 use axum::{
     extract::{Json, Path, State},
     http::HeaderMap,
@@ -8,7 +10,6 @@ use axum::{
 };
 use serde::Deserialize;
 use serde_json::json;
-use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 #[derive(Deserialize)]
@@ -18,6 +19,17 @@ pub struct ExecuteQueryParams {
     offset: Option<i64>,
     include_total_count: Option<bool>,
     confirmed_unsafe: Option<bool>,
+    /// Values for `$1`/`?` (an array) or `:name` (an object) placeholders.
+    params: Option<QueryParams>,
+}
+
+#[derive(Deserialize)]
+pub struct ExecuteStatementsParams {
+    script: String,
+    /// `stop_on_error` (default) or `continue_on_error`.
+    #[serde(default)]
+    on_error: ErrorPolicy,
+    confirmed_unsafe: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -29,10 +41,13 @@ pub async fn cancel_query(
     State(state): State<AppState>,
     Json(payload): Json<CancelQueryParams>,
 ) -> impl IntoResponse {
-    if let Some(entry) = state.queries.get(&payload.query_id) {
-        entry.value().cancel();
-    }
-    StatusCode::OK
+    let outcome = state.cancel_query(&payload.query_id).await;
+    let status = if outcome.found {
+        StatusCode::OK
+    } else {
+        StatusCode::NOT_FOUND
+    };
+    (status, Json(outcome))
 }
 
 fn find_postgres_db_error<'a>(
@@ -61,28 +76,32 @@ pub async fn execute_query(
         .and_then(|h| h.to_str().ok())
         .map(|s| s.to_string());
 
-    // 2. Setup Cancellation Token
-    let cancellation_token = CancellationToken::new();
-    if let Some(qid) = &query_id {
-        state
-            .queries
-            .insert(qid.clone(), cancellation_token.clone());
-    }
+    // 2. Register a handle so cancel_query can stop the statement on the server
+    let query_handle = query_id.as_deref().map(|qid| state.register_query(qid));
+    let cancellation_token = query_handle
+        .as_ref()
+        .map(|handle| handle.token().clone())
+        .unwrap_or_default();
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
-        ));
+        ))
+        .with_query_handle(query_handle);
 
     // 3. Wrap execution in select!
     let execution_future = service.execute_query_with_options(
         connection_id,
         &payload.query,
-        payload.limit,
-        payload.offset,
-        payload.include_total_count.unwrap_or(false),
-        payload.confirmed_unsafe.unwrap_or(false),
+        QueryOptions {
+            limit: payload.limit,
+            offset: payload.offset,
+            include_total_count: payload.include_total_count.unwrap_or(false),
+            confirmed_unsafe: payload.confirmed_unsafe.unwrap_or(false),
+            params: payload.params.as_ref(),
+        },
     );
 
     let result = tokio::select! {
@@ -94,7 +113,7 @@ pub async fn execute_query(
 
     // 4. Cleanup
     if let Some(qid) = &query_id {
-        state.queries.remove(qid);
+        state.finish_query(qid);
     }
 
     match result {
@@ -190,3 +209,72 @@ pub async fn execute_query(
         }
     }
 }
+
+/// Runs a script statement by statement, returning one result per statement.
+pub async fn execute_statements(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(connection_id): Path<Uuid>,
+    Json(payload): Json<ExecuteStatementsParams>,
+) -> impl IntoResponse {
+    let query_id = headers
+        .get("X-Query-ID")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let query_handle = query_id.as_deref().map(|qid| state.register_query(qid));
+    let cancellation_token = query_handle
+        .as_ref()
+        .map(|handle| handle.token().clone())
+        .unwrap_or_default();
+
+    let service = state
+        .connection_service()
+        .expect("Failed to create service")
+        .with_database_override(crate::utils::request::database_override_from_headers(
+            &headers,
+        ))
+        .with_query_handle(query_handle);
+
+    // The token fires once the server-side cancel has been sent; dropping
+    // the session then closes its connection.
+    let result = tokio::select! {
+        res = service.execute_statements(
+            connection_id,
+            &payload.script,
+            payload.on_error,
+            payload.confirmed_unsafe.unwrap_or(false),
+        ) => res,
+        _ = cancellation_token.cancelled() => Err(anyhow::anyhow!("Query cancelled")),
+    };
+
+    if let Some(qid) = &query_id {
+        state.finish_query(qid);
+    }
+
+    match result {
+        Ok(result) => {
+            let database_name = headers
+                .get("X-Database")
+                .and_then(|h| h.to_str().ok())
+                .unwrap_or("postgres");
+            for statement in &result.statements {
+                if statement.status != StatementStatus::Success {
+                    continue;
+                }
+                if let Err(e) = state
+                    .schema_cache
+                    .invalidate_from_ddl(connection_id, database_name, &statement.sql)
+                    .await
+                {
+                    tracing::warn!("Failed to invalidate cache after DDL: {}", e);
+                }
+            }
+            (StatusCode::OK, Json(result)).into_response()
+        }
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
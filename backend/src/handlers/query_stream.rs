@@ -1,5 +1,5 @@
 use crate::app_state::AppState;
-use crate::services::connection_service::ConnectionService;
+use crate::services::query_stream::{QueryStreamSink, StreamOptions};
 use axum::{
     body::Body,
     extract::{Json, Path, State},
@@ -8,10 +8,8 @@ use axum::{
 };
 use bytes::Bytes;
 use futures_util::StreamExt;
-use serde::Deserialize;
-use serde_json::json;
+use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
-use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 use uuid::Uuid;
 
@@ -21,6 +19,15 @@ pub struct ExecuteQueryStreamParams {
     limit: Option<i64>,
     offset: Option<i64>,
     include_total_count: Option<bool>,
+    confirmed_unsafe: Option<bool>,
+    batch_size: Option<usize>,
+}
+
+pub(crate) fn ndjson_line<T: Serialize>(event: &T) -> Bytes {
+    let mut buf = serde_json::to_vec(event)
+        .unwrap_or_else(|_| b"{\"type\":\"error\",\"message\":\"serialization failed\"}".to_vec());
+    buf.push(b'\n');
+    Bytes::from(buf)
 }
 
 pub async fn execute_query_stream(
@@ -29,74 +36,52 @@ pub async fn execute_query_stream(
     Path(connection_id): Path<Uuid>,
     Json(payload): Json<ExecuteQueryStreamParams>,
 ) -> impl IntoResponse {
-    let service = ConnectionService::new(state.db.clone())
+    // Registered so that cancel_query can stop the statement on the server
+    let query_id = headers
+        .get("X-Query-ID")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let query_handle = query_id.as_deref().map(|qid| state.register_query(qid));
+    let cancellation_token = query_handle
+        .as_ref()
+        .map(|handle| handle.token().clone())
+        .unwrap_or_default();
+
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
-        ));
+        ))
+        .with_query_handle(query_handle);
 
-    let (connection, password) = match service.get_connection_with_password(connection_id).await {
-        Ok(v) => v,
-        Err(e) => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(json!({ "message": e.to_string() })),
-            )
-                .into_response();
-        }
+    let (sink, rx) = QueryStreamSink::channel(payload.batch_size);
+    let options = StreamOptions {
+        limit: payload.limit,
+        offset: payload.offset,
+        include_total_count: payload.include_total_count.unwrap_or(false),
     };
-
-    let (tx, rx) = mpsc::channel::<Bytes>(64);
-    let sql = payload.query;
-    let limit = payload.limit;
-    let offset = payload.offset;
-    let include_total_count = payload.include_total_count.unwrap_or(false);
+    let confirmed_unsafe = payload.confirmed_unsafe.unwrap_or(false);
 
     tokio::spawn(async move {
-        async fn send_line(tx: &mpsc::Sender<Bytes>, value: serde_json::Value) {
-            let mut buf = serde_json::to_vec(&value).unwrap_or_else(|_| {
-                b"{\"type\":\"error\",\"message\":\"serialization failed\"}".to_vec()
-            });
-            buf.push(b'\n');
-            let _ = tx.send(Bytes::from(buf)).await;
+        let streaming = service.stream_query(
+            connection_id,
+            &payload.query,
+            &options,
+            confirmed_unsafe,
+            sink,
+        );
+        tokio::select! {
+            _ = streaming => {}
+            _ = cancellation_token.cancelled() => {}
         }
-
-        let result = match connection.db_type.as_str() {
-            "postgres" => {
-                use crate::services::postgres::PostgresConnection;
-                use crate::services::postgres::PostgresQuery;
-
-                async {
-                    let conn = PostgresConnection::new(&connection, &password).await?;
-                    let query = PostgresQuery::new(conn.pool().clone());
-                    query
-                        .stream_ndjson(&sql, limit, offset, include_total_count, tx.clone())
-                        .await
-                }
-                .await
-            }
-            "sqlite" => {
-                use crate::services::sqlite::SQLiteConnection;
-                use crate::services::sqlite::SQLiteQuery;
-
-                async {
-                    let conn = SQLiteConnection::new(&connection, &password).await?;
-                    let query = SQLiteQuery::new(conn.pool().clone());
-                    query
-                        .stream_ndjson(&sql, limit, offset, include_total_count, tx.clone())
-                        .await
-                }
-                .await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
-        };
-
-        if let Err(e) = result {
-            send_line(&tx, json!({ "type": "error", "message": e.to_string() })).await;
+        if let Some(qid) = &query_id {
+            state.finish_query(qid);
         }
     });
 
-    let body_stream = ReceiverStream::new(rx).map(Ok::<Bytes, Infallible>);
+    let body_stream =
+        ReceiverStream::new(rx).map(|event| Ok::<Bytes, Infallible>(ndjson_line(&event)));
     let mut resp = (StatusCode::OK, Body::from_stream(body_stream)).into_response();
     resp.headers_mut().insert(
         axum::http::header::CONTENT_TYPE,
@@ -1,5 +1,5 @@
 use crate::app_state::AppState;
-use crate::services::connection_service::ConnectionService;
+use crate::services::bulk::RowChanges;
 use axum::{
     extract::{Path, State},
     http::HeaderMap,
@@ -44,7 +44,7 @@ pub async fn update_result_row(
     }
 
     // Use ConnectionService
-    let service = match ConnectionService::new(state.db.clone()) {
+    let service = match state.connection_service() {
         Ok(s) => s.with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
         )),
@@ -154,7 +154,7 @@ pub async fn delete_result_row(
     }
 
     // Use ConnectionService
-    let service = match ConnectionService::new(state.db.clone()) {
+    let service = match state.connection_service() {
         Ok(s) => s.with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
         )),
@@ -222,3 +222,42 @@ pub async fn delete_result_row(
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
+
+#[derive(Deserialize)]
+pub struct SaveChangesRequest {
+    pub schema: Option<String>,
+    pub table: String,
+    #[serde(flatten)]
+    pub changes: RowChanges,
+}
+
+/// Saves all edited and deleted rows of a result in one request, through
+/// the engine's bulk update and delete paths.
+pub async fn save_result_changes(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(connection_id): Path<Uuid>,
+    Json(payload): Json<SaveChangesRequest>,
+) -> impl IntoResponse {
+    let service = match state.connection_service() {
+        Ok(s) => s.with_database_override(crate::utils::request::database_override_from_headers(
+            &headers,
+        )),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+
+    let schema = payload.schema.unwrap_or_default();
+    match service
+        .save_row_changes(
+            connection_id,
+            &schema,
+            &payload.table,
+            &payload.changes,
+            None,
+        )
+        .await
+    {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
@@ -1,5 +1,6 @@
 use crate::app_state::AppState;
 use crate::services::saved_filter_service::SavedFilterService;
+use crate::services::table_filter::{FilterNode, SortSpec};
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
@@ -14,7 +15,10 @@ pub struct CreateSavedFilterParams {
     schema: String,
     table: String,
     name: String,
-    filter: String,
+    #[serde(default)]
+    filter: Option<FilterNode>,
+    #[serde(default)]
+    sort: Vec<SortSpec>,
 }
 
 #[derive(Deserialize)]
@@ -51,6 +55,7 @@ pub async fn create_saved_filter(
             payload.table,
             payload.name,
             payload.filter,
+            payload.sort,
         )
         .await
     {
@@ -1,12 +1,14 @@
 use crate::app_state::AppState;
+use crate::services::connection_service::QueryOptions;
 use crate::services::saved_query_service::SavedQueryService;
 use axum::{
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
     Json,
 };
 use serde::Deserialize;
+use serde_json::{json, Map, Value};
 use uuid::Uuid;
 
 #[derive(Deserialize)]
@@ -29,6 +31,17 @@ pub struct UpdateSavedQueryParams {
     metadata: Option<serde_json::Value>,
 }
 
+#[derive(Deserialize)]
+pub struct RunSavedQueryParams {
+    /// Values for the query's `{{var}}` placeholders, by name.
+    #[serde(default)]
+    variables: Map<String, Value>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    include_total_count: Option<bool>,
+    confirmed_unsafe: Option<bool>,
+}
+
 pub async fn list_saved_queries(
     State(state): State<AppState>,
     Path(connection_id): Path<Uuid>,
@@ -96,3 +109,40 @@ pub async fn delete_saved_query(
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
     }
 }
+
+pub async fn run_saved_query(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path((connection_id, query_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RunSavedQueryParams>,
+) -> impl IntoResponse {
+    let service = match state.connection_service() {
+        Ok(service) => service.with_database_override(
+            crate::utils::request::database_override_from_headers(&headers),
+        ),
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    };
+
+    match service
+        .run_saved_query(
+            connection_id,
+            query_id,
+            &payload.variables,
+            QueryOptions {
+                limit: payload.limit,
+                offset: payload.offset,
+                include_total_count: payload.include_total_count.unwrap_or(false),
+                confirmed_unsafe: payload.confirmed_unsafe.unwrap_or(false),
+                params: None,
+            },
+        )
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
@@ -1,5 +1,5 @@
 use crate::app_state::AppState;
-use crate::services::connection_service::ConnectionService;
+use crate::services::table_filter::{FilterNode, SortSpec};
 use axum::{
     extract::{Path, Query, State},
     http::HeaderMap,
@@ -52,7 +52,8 @@ pub async fn list_schemas(
     Query(params): Query<DatabaseOverrideParams>,
 ) -> impl IntoResponse {
     tracing::info!("[API] GET /schemas - connection_id: {}", connection_id);
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
@@ -103,7 +104,8 @@ pub async fn create_schema(
             .into_response();
     }
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
@@ -141,7 +143,8 @@ pub async fn drop_schema(
         return (StatusCode::BAD_REQUEST, "Schema name cannot be empty").into_response();
     }
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
@@ -165,7 +168,8 @@ pub async fn list_schema_metadata(
         connection_id,
         params.schema
     );
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
@@ -203,7 +207,8 @@ pub async fn list_tables(
         connection_id,
         params.schema
     );
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
@@ -245,7 +250,8 @@ pub async fn create_table(
         return (StatusCode::BAD_REQUEST, "Table name cannot be empty").into_response();
     }
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             payload
@@ -268,7 +274,8 @@ pub async fn drop_table(
     Path(connection_id): Path<Uuid>,
     Query(params): Query<ColumnParams>,
 ) -> impl IntoResponse {
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
@@ -297,7 +304,8 @@ pub async fn list_columns(
         params.schema,
         params.table
     );
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
@@ -331,7 +339,10 @@ pub struct TableDataParams {
     offset: Option<i64>,
     #[serde(default)]
     database: Option<String>,
+    /// JSON-encoded `FilterNode`.
     filter: Option<String>,
+    /// JSON-encoded list of `SortSpec`.
+    sort: Option<String>,
     document_id: Option<String>,
     fields: Option<String>,
 }
@@ -355,7 +366,8 @@ pub async fn get_table_data(
         params.document_id
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
@@ -369,6 +381,25 @@ pub async fn get_table_data(
         .as_ref()
         .and_then(|f| serde_json::from_str(f).ok());
 
+    let filter: Option<FilterNode> = match params.filter.as_deref().map(str::trim) {
+        Some(f) if !f.is_empty() => match serde_json::from_str(f) {
+            Ok(filter) => Some(filter),
+            Err(e) => {
+                return (StatusCode::BAD_REQUEST, format!("Invalid filter: {}", e)).into_response()
+            }
+        },
+        _ => None,
+    };
+    let sort: Vec<SortSpec> = match params.sort.as_deref().map(str::trim) {
+        Some(s) if !s.is_empty() => match serde_json::from_str(s) {
+            Ok(sort) => sort,
+            Err(e) => {
+                return (StatusCode::BAD_REQUEST, format!("Invalid sort: {}", e)).into_response()
+            }
+        },
+        _ => Vec::new(),
+    };
+
     match service
         .get_table_data(
             connection_id,
@@ -376,7 +407,8 @@ pub async fn get_table_data(
             &params.table,
             limit,
             offset,
-            params.filter,
+            filter,
+            sort,
             params.document_id,
             fields_vec,
         )
@@ -420,7 +452,8 @@ pub async fn add_column(
         params.table,
         column_def.name
     );
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
@@ -472,7 +505,8 @@ pub async fn alter_column(
         params.schema,
         params.table
     );
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
@@ -523,7 +557,8 @@ pub async fn drop_column(
         params.schema,
         params.table
     );
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
@@ -577,7 +612,8 @@ pub async fn list_views(
         connection_id,
         params.schema
     );
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -606,7 +642,8 @@ pub async fn get_view_definition(
         params.schema,
         params.view
     );
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -648,7 +685,8 @@ pub async fn list_functions(
         connection_id,
         params.schema
     );
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -680,7 +718,8 @@ pub async fn get_function_definition(
         params.schema,
         params.function
     );
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -711,7 +750,8 @@ pub async fn get_schema_foreign_keys(
         connection_id,
         params.schema
     );
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
@@ -1,10 +1,16 @@
-use axum::Json;
+use axum::{
+    extract::{Json, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
 use serde::Deserialize;
+use serde_json::json;
 use uuid::Uuid;
 
+use crate::app_state::AppState;
 use crate::services::schema_diff::{
-    ColumnDefinition, DatabaseType, MigrationGenerator, MigrationOptions, SchemaDiffer,
-    SchemaSnapshot, TableDefinition,
+    ColumnDefinition, DatabaseType, MigrationGenerator, MigrationOptions, RenameDecisions,
+    SchemaDiffer, SchemaSnapshot, TableDefinition,
 };
 
 /// Request to compare two schemas
@@ -12,6 +18,9 @@ use crate::services::schema_diff::{
 pub struct CompareRequest {
     pub source: SchemaSource,
     pub target: SchemaSource,
+    /// Suspected renames accepted or rejected by the user
+    #[serde(default)]
+    pub renames: RenameDecisions,
 }
 
 #[derive(Debug, Deserialize)]
@@ -26,37 +35,67 @@ pub struct GenerateMigrationRequest {
     pub source: SchemaSource,
     pub target: SchemaSource,
     pub options: MigrationOptions,
+    /// Suspected renames accepted or rejected by the user
+    #[serde(default)]
+    pub renames: RenameDecisions,
 }
 
 /// Compare two schemas and return differences
-pub async fn compare_schemas(Json(_req): Json<CompareRequest>) -> Json<serde_json::Value> {
-    // TODO: Implement actual schema extraction from database
-    Json(serde_json::json!({
-        "message": "Schema comparison not yet implemented",
-        "status": "pending"
-    }))
+pub async fn compare_schemas(
+    State(state): State<AppState>,
+    Json(req): Json<CompareRequest>,
+) -> impl IntoResponse {
+    let service = state
+        .connection_service()
+        .expect("Failed to create service");
+
+    match service
+        .compare_schemas(
+            req.source.connection_id,
+            req.target.connection_id,
+            req.source.schema,
+            req.target.schema,
+            &req.renames,
+        )
+        .await
+    {
+        Ok(diff) => (StatusCode::OK, Json(diff)).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": e.to_string() })),
+        )
+            .into_response(),
+    }
 }
 
-/// Generate migration script from comparison
+/// Generate migration script from comparison, renaming only the tables and
+/// columns whose renames were accepted
 pub async fn generate_migration(
+    State(state): State<AppState>,
     Json(req): Json<GenerateMigrationRequest>,
-) -> Json<serde_json::Value> {
-    let options = &req.options;
-
-    // Create sample snapshots for demonstration
-    let source = SchemaSnapshot::new(req.source.schema.clone());
-    let target = SchemaSnapshot::new(req.target.schema.clone());
-
-    // Compare schemas
-    let diff_result = SchemaDiffer::compare(&source, &target);
-
-    // Generate migration
-    let migration = MigrationGenerator::generate(&diff_result.diffs, options);
+) -> impl IntoResponse {
+    let service = state
+        .connection_service()
+        .expect("Failed to create service");
 
-    Json(serde_json::json!({
-        "diff": diff_result,
-        "migration": migration
-    }))
+    match service
+        .generate_migration(
+            req.source.connection_id,
+            req.target.connection_id,
+            req.source.schema,
+            req.target.schema,
+            &req.renames,
+            Some(req.options),
+        )
+        .await
+    {
+        Ok(sql) => (StatusCode::OK, Json(json!({ "sql": sql }))).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "message": e.to_string() })),
+        )
+            .into_response(),
+    }
 }
 
 /// Test endpoint to verify schema diff is working
@@ -75,6 +114,7 @@ pub async fn test_schema_diff() -> Json<serde_json::Value> {
                 character_maximum_length: None,
                 numeric_precision: None,
                 numeric_scale: None,
+                comment: None,
             },
             ColumnDefinition {
                 name: "name".to_string(),
@@ -85,9 +125,11 @@ pub async fn test_schema_diff() -> Json<serde_json::Value> {
                 character_maximum_length: Some(255),
                 numeric_precision: None,
                 numeric_scale: None,
+                comment: None,
             },
         ],
         primary_key: Some(vec!["id".to_string()]),
+        definition: None,
     });
 
     // Create sample target schema with changes
@@ -104,6 +146,7 @@ pub async fn test_schema_diff() -> Json<serde_json::Value> {
                 character_maximum_length: None,
                 numeric_precision: None,
                 numeric_scale: None,
+                comment: None,
             },
             ColumnDefinition {
                 name: "name".to_string(),
@@ -114,6 +157,7 @@ pub async fn test_schema_diff() -> Json<serde_json::Value> {
                 character_maximum_length: Some(255),
                 numeric_precision: None,
                 numeric_scale: None,
+                comment: None,
             },
             ColumnDefinition {
                 name: "email".to_string(),
@@ -124,9 +168,11 @@ pub async fn test_schema_diff() -> Json<serde_json::Value> {
                 character_maximum_length: Some(255),
                 numeric_precision: None,
                 numeric_scale: None,
+                comment: None,
             },
         ],
         primary_key: Some(vec!["id".to_string()]),
+        definition: None,
     });
 
     // Compare
@@ -1,6 +1,5 @@
 use crate::app_state::AppState;
 use crate::services::autocomplete::RefreshScope;
-use crate::services::connection_service::ConnectionService;
 use axum::{
     extract::{Json, Path, State},
     http::StatusCode,
@@ -85,7 +84,7 @@ pub async fn refresh_schema(
     };
 
     // Get connection and password
-    let service = match ConnectionService::new(state.db.clone()) {
+    let service = match state.connection_service() {
         Ok(s) => s,
         Err(e) => {
             return (
@@ -1,5 +1,4 @@
 use crate::app_state::AppState;
-use crate::services::connection_service::ConnectionService;
 use axum::{
     extract::{Path, Query, State},
     http::HeaderMap,
@@ -38,7 +37,8 @@ pub async fn search_objects(
         query
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(
             params
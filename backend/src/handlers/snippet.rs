@@ -1,5 +1,7 @@
 use crate::app_state::AppState;
+use crate::services::driver_factory;
 use crate::services::snippet_service::SnippetService;
+use crate::services::sql_template::TemplateVariable;
 use axum::{
     extract::{Path, State},
     http::StatusCode,
@@ -7,6 +9,7 @@ use axum::{
     Json,
 };
 use serde::Deserialize;
+use serde_json::{json, Map, Value};
 use uuid::Uuid;
 
 #[derive(Deserialize)]
@@ -15,6 +18,7 @@ pub struct CreateSnippetParams {
     description: Option<String>,
     sql: String,
     tags: Option<Vec<String>>,
+    variables: Option<Vec<TemplateVariable>>,
 }
 
 #[derive(Deserialize)]
@@ -23,6 +27,15 @@ pub struct UpdateSnippetParams {
     description: Option<String>,
     sql: Option<String>,
     tags: Option<Vec<String>>,
+    variables: Option<Vec<TemplateVariable>>,
+}
+
+#[derive(Deserialize)]
+pub struct RenderSnippetParams {
+    /// `connection.db_type` of the editor the snippet is inserted into.
+    db_type: String,
+    #[serde(default)]
+    variables: Map<String, Value>,
 }
 
 pub async fn list_snippets(State(state): State<AppState>) -> impl IntoResponse {
@@ -39,7 +52,13 @@ pub async fn create_snippet(
 ) -> impl IntoResponse {
     let service = SnippetService::new(state.db.clone());
     match service
-        .create_snippet(payload.name, payload.description, payload.sql, payload.tags)
+        .create_snippet(
+            payload.name,
+            payload.description,
+            payload.sql,
+            payload.tags,
+            payload.variables,
+        )
         .await
     {
         Ok(snippet) => (StatusCode::CREATED, Json(snippet)).into_response(),
@@ -60,6 +79,7 @@ pub async fn update_snippet(
             payload.description,
             payload.sql,
             payload.tags,
+            payload.variables,
         )
         .await
     {
@@ -68,6 +88,29 @@ pub async fn update_snippet(
     }
 }
 
+pub async fn render_snippet(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RenderSnippetParams>,
+) -> impl IntoResponse {
+    let dialect = match driver_factory::spec_for(&payload.db_type).and_then(|spec| {
+        spec.sql_dialect
+            .ok_or_else(|| anyhow::anyhow!("Snippets are not supported for {}", spec.name))
+    }) {
+        Ok(dialect) => dialect,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+
+    let service = SnippetService::new(state.db.clone());
+    match service
+        .render_snippet(id, &payload.variables, dialect)
+        .await
+    {
+        Ok(sql) => (StatusCode::OK, Json(json!({ "sql": sql }))).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    }
+}
+
 pub async fn delete_snippet(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
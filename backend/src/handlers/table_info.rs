@@ -1,5 +1,4 @@
 use crate::app_state::AppState;
-use crate::services::connection_service::ConnectionService;
 use axum::{
     extract::{Path, Query, State},
     http::HeaderMap,
@@ -47,7 +46,8 @@ pub async fn get_table_constraints(
         params.table
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -86,7 +86,8 @@ pub async fn get_table_statistics(
         params.table
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -124,7 +125,8 @@ pub async fn get_table_indexes(
         params.table
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -161,7 +163,8 @@ pub async fn get_table_triggers(
         params.table
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -198,7 +201,8 @@ pub async fn get_table_comment(
         params.table
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -237,7 +241,8 @@ pub async fn set_table_comment(
         body.table
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -276,7 +281,8 @@ pub async fn get_table_permissions(
         params.table
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -315,7 +321,8 @@ pub async fn get_table_dependencies(
         params.table
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -354,7 +361,8 @@ pub async fn get_storage_bloat_info(
         params.table
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -393,7 +401,8 @@ pub async fn get_partitions(
         params.table
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -426,7 +435,8 @@ pub async fn list_roles(
 ) -> impl IntoResponse {
     tracing::info!("[API] GET /roles - connection_id: {}", connection_id);
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -463,7 +473,8 @@ pub async fn set_table_permissions(
         body.grantee
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -519,7 +530,8 @@ pub async fn get_schema_permissions(
         params.schema
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -558,7 +570,8 @@ pub async fn get_function_permissions(
         params.function
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -597,7 +610,8 @@ pub async fn get_fk_orphans(
         params.table
     );
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state
+        .connection_service()
         .expect("Failed to create service")
         .with_database_override(crate::utils::request::database_override_from_headers(
             &headers,
@@ -0,0 +1,125 @@
+use crate::app_state::AppState;
+use axum::{
+    extract::{Json, Path, State},
+    http::HeaderMap,
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(Deserialize, Default)]
+pub struct BeginTransactionParams {
+    idle_timeout_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct ExecuteInTransactionParams {
+    query: String,
+    confirmed_unsafe: Option<bool>,
+}
+
+fn error_response(status: StatusCode, e: anyhow::Error) -> axum::response::Response {
+    (status, Json(json!({ "error": e.to_string() }))).into_response()
+}
+
+pub async fn begin_transaction(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(connection_id): Path<Uuid>,
+    payload: Option<Json<BeginTransactionParams>>,
+) -> impl IntoResponse {
+    let Json(payload) = payload.unwrap_or_default();
+    let service = match state.connection_service() {
+        Ok(service) => service.with_database_override(
+            crate::utils::request::database_override_from_headers(&headers),
+        ),
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+
+    match service
+        .begin_transaction(
+            connection_id,
+            payload.idle_timeout_secs.map(Duration::from_secs),
+        )
+        .await
+    {
+        Ok(info) => (StatusCode::CREATED, Json(info)).into_response(),
+        Err(e) => error_response(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+pub async fn list_transactions(
+    State(state): State<AppState>,
+    Path(connection_id): Path<Uuid>,
+) -> impl IntoResponse {
+    match state
+        .connection_service()
+        .and_then(|service| service.list_transactions(connection_id))
+    {
+        Ok(transactions) => (StatusCode::OK, Json(transactions)).into_response(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+pub async fn execute_in_transaction(
+    State(state): State<AppState>,
+    Path((connection_id, transaction_id)): Path<(Uuid, String)>,
+    Json(payload): Json<ExecuteInTransactionParams>,
+) -> impl IntoResponse {
+    let service = match state.connection_service() {
+        Ok(service) => service,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+
+    match service
+        .execute_in_transaction(
+            connection_id,
+            &transaction_id,
+            &payload.query,
+            payload.confirmed_unsafe.unwrap_or(false),
+        )
+        .await
+    {
+        Ok(result) => (StatusCode::OK, Json(result)).into_response(),
+        Err(e) => error_response(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+pub async fn commit_transaction(
+    State(state): State<AppState>,
+    Path((connection_id, transaction_id)): Path<(Uuid, String)>,
+) -> impl IntoResponse {
+    let service = match state.connection_service() {
+        Ok(service) => service,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+
+    match service
+        .commit_transaction(connection_id, &transaction_id)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => error_response(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+pub async fn rollback_transaction(
+    State(state): State<AppState>,
+    Path((connection_id, transaction_id)): Path<(Uuid, String)>,
+) -> impl IntoResponse {
+    let service = match state.connection_service() {
+        Ok(service) => service,
+        Err(e) => return error_response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+
+    match service
+        .rollback_transaction(connection_id, &transaction_id)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => error_response(StatusCode::BAD_REQUEST, e),
+    }
+}
@@ -0,0 +1,67 @@
+use super::query_stream::ndjson_line;
+use crate::app_state::AppState;
+use crate::services::transfer::{TransferEvent, TransferProgress, TransferRequest};
+use axum::{
+    body::Body,
+    extract::{Json, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    response::IntoResponse,
+};
+use bytes::Bytes;
+use futures_util::StreamExt;
+use std::convert::Infallible;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+/// Copies tables from one connection to another, streaming NDJSON progress
+/// lines and then the report or the error. Cancelling the query in
+/// `X-Query-ID` stops after the batch being written; earlier batches stay.
+pub async fn transfer_tables(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<TransferRequest>,
+) -> impl IntoResponse {
+    let query_id = headers
+        .get("X-Query-ID")
+        .and_then(|h| h.to_str().ok())
+        .map(|s| s.to_string());
+    let cancellation_token = query_id
+        .as_deref()
+        .map(|qid| state.register_query(qid).token().clone())
+        .unwrap_or_default();
+
+    let service = state
+        .connection_service()
+        .expect("Failed to create service");
+
+    // Progress is throttled per table, so the channel stays short.
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let progress_tx = tx.clone();
+        let progress = move |progress: TransferProgress| {
+            let _ = progress_tx.send(TransferEvent::Progress(progress));
+        };
+        let event = tokio::select! {
+            res = service.transfer_tables(&payload, Some(&progress)) => match res {
+                Ok(report) => TransferEvent::Done(report),
+                Err(e) => TransferEvent::Error { message: e.to_string() },
+            },
+            _ = cancellation_token.cancelled() => TransferEvent::Error {
+                message: "Transfer cancelled".to_string(),
+            },
+        };
+        let _ = tx.send(event);
+        if let Some(qid) = &query_id {
+            state.finish_query(qid);
+        }
+    });
+
+    let body_stream =
+        UnboundedReceiverStream::new(rx).map(|event| Ok::<Bytes, Infallible>(ndjson_line(&event)));
+    let mut resp = (StatusCode::OK, Body::from_stream(body_stream)).into_response();
+    resp.headers_mut().insert(
+        axum::http::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/x-ndjson; charset=utf-8"),
+    );
+    resp
+}
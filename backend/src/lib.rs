@@ -6,6 +6,7 @@ pub mod app_state;
 pub mod config;
 pub mod handlers;
 pub mod models;
+pub mod routes;
 pub mod services;
 pub mod utils;
 
@@ -5,7 +5,9 @@
 )]
 
 // Import from library
-use dbplus_backend::init_database;
+use dbplus_backend::{init_app_state, init_database, routes};
+
+const LISTEN_ADDR: &str = "127.0.0.1:19999";
 
 #[tokio::main]
 async fn main() {
@@ -29,12 +31,67 @@ async fn main() {
     tracing::info!("Initializing database connection...");
 
     // Initialize database and run migrations
-    match init_database(&database_url).await {
-        Ok(_) => {
+    let db = match init_database(&database_url).await {
+        Ok(db) => {
             tracing::info!("Database initialized and migrations run successfully.");
+            db
         }
         Err(e) => {
             tracing::error!("Failed to initialize database: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let app = routes::app(init_app_state(db));
+
+    let listener = match tokio::net::TcpListener::bind(LISTEN_ADDR).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!("Failed to bind {}: {}", LISTEN_ADDR, e);
+            std::process::exit(1);
+        }
+    };
+    tracing::info!("Listening on http://{}", LISTEN_ADDR);
+
+    if let Err(e) = axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await
+    {
+        tracing::error!("Server error: {}", e);
+    }
+
+    tracing::info!("Server stopped.");
+}
+
+/// Resolves on Ctrl+C, or SIGTERM on unix (sent when the sidecar is killed).
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
         }
     };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("Shutdown signal received, draining connections...");
 }
@@ -39,6 +39,10 @@ pub struct Model {
     pub environment: String,
     #[sea_orm(default_value = "1")]
     pub safe_mode_level: i32,
+    /// Unset (or zero) means no limit.
+    pub statement_timeout_ms: Option<i32>,
+    pub max_result_rows: Option<i32>,
+    pub max_result_bytes: Option<i64>,
     pub last_used: Option<DateTimeWithTimeZone>,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
@@ -10,7 +10,12 @@ pub struct Model {
     pub schema: String,
     pub table_ref: String, // 'table' is reserved keyword in Rust sometimes, safer to use table_ref or table_name, but sea-orm handles it. 'table' is fine as struct field if migration uses 'table_ref'. Wait, migration used 'TableRef'.
     pub name: String,
+    /// Readable form of `filter_tree`; the raw filter text for rows saved before it existed.
     pub filter: String,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub filter_tree: Option<serde_json::Value>,
+    #[sea_orm(column_type = "Json", nullable)]
+    pub sort: Option<serde_json::Value>,
     pub created_at: DateTimeWithTimeZone,
 }
 
@@ -1 +1,342 @@
-// API routes module - placeholder
+use crate::app_state::AppState;
+use crate::handlers::{
+    autocomplete, connection, dashboard, data_diff, data_tools, database, explain, export, export_ddl,
+    extensions, foreign_key, history, import, mock_data, query, query_stream, result_edit, saved_filter,
+    saved_query, saved_query_folder, schema, schema_diff, schema_refresh, search, settings,
+    snippet, sqlite_tools, table_info, transaction, transfer,
+};
+use axum::routing::{delete, get, patch, post, put};
+use axum::Router;
+
+/// Every HTTP endpoint, relative to `/api`. Paths match the ones the frontend
+/// `api.ts` proxy maps onto Tauri commands.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .merge(connection_routes())
+        .merge(schema_routes())
+        .merge(table_info_routes())
+        .merge(query_routes())
+        .merge(workspace_routes())
+        .merge(global_routes())
+}
+
+fn connection_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/connections",
+            get(connection::list_connections).post(connection::create_connection),
+        )
+        .route("/connections/test", post(connection::test_connection))
+        .route("/connections/tls-support", get(connection::get_tls_support))
+        .route("/connections/pool-stats", get(connection::get_pool_stats))
+        .route(
+            "/connections/:id",
+            get(connection::get_connection)
+                .put(connection::update_connection)
+                .delete(connection::delete_connection),
+        )
+        .route(
+            "/connections/:id/test",
+            post(connection::test_connection_by_id),
+        )
+        .route(
+            "/connections/:id/version",
+            get(connection::get_connection_version),
+        )
+        .route(
+            "/connections/:id/switch-database",
+            post(connection::switch_database),
+        )
+        .route(
+            "/connections/:id/database",
+            patch(connection::switch_database),
+        )
+        .route("/connections/:id/sessions", get(connection::list_sessions))
+        .route(
+            "/connections/:id/sessions/:pid",
+            delete(connection::kill_session),
+        )
+        .route(
+            "/connections/:id/databases",
+            get(database::list_databases).post(database::create_database),
+        )
+        .route(
+            "/connections/:id/databases/:name",
+            delete(database::drop_database),
+        )
+        .route(
+            "/connections/:id/sqlite/attachments",
+            get(sqlite_tools::list_sqlite_attachments).post(sqlite_tools::create_sqlite_attachment),
+        )
+        .route(
+            "/connections/:id/sqlite/attachments/:name",
+            delete(sqlite_tools::delete_sqlite_attachment),
+        )
+}
+
+fn schema_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/connections/:id/schemas",
+            get(schema::list_schemas).post(schema::create_schema),
+        )
+        .route(
+            "/connections/:id/schemas/:name",
+            delete(schema::drop_schema),
+        )
+        .route(
+            "/connections/:id/schema-metadata",
+            get(schema::list_schema_metadata),
+        )
+        .route(
+            "/connections/:id/tables",
+            get(schema::list_tables)
+                .post(schema::create_table)
+                .delete(schema::drop_table),
+        )
+        .route(
+            "/connections/:id/columns",
+            get(schema::list_columns).post(schema::add_column),
+        )
+        .route(
+            "/connections/:id/columns/:name",
+            put(schema::alter_column).delete(schema::drop_column),
+        )
+        .route("/connections/:id/query", get(schema::get_table_data))
+        .route("/connections/:id/views", get(schema::list_views))
+        .route(
+            "/connections/:id/view-definition",
+            get(schema::get_view_definition),
+        )
+        .route("/connections/:id/functions", get(schema::list_functions))
+        .route(
+            "/connections/:id/function-definition",
+            get(schema::get_function_definition),
+        )
+        .route(
+            "/connections/:id/foreign-keys",
+            get(schema::get_schema_foreign_keys),
+        )
+        .route(
+            "/connections/:id/table-foreign-keys",
+            get(foreign_key::get_foreign_keys),
+        )
+        .route(
+            "/connections/:id/extensions",
+            get(extensions::list_extensions),
+        )
+        .route(
+            "/connections/:id/refresh-schema",
+            post(schema_refresh::refresh_schema),
+        )
+        .route("/connections/:id/search", get(search::search_objects))
+        .route("/connections/:id/export-ddl", post(export_ddl::export_ddl))
+}
+
+fn table_info_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/connections/:id/constraints",
+            get(table_info::get_table_constraints),
+        )
+        .route(
+            "/connections/:id/table-stats",
+            get(table_info::get_table_statistics),
+        )
+        .route(
+            "/connections/:id/indexes",
+            get(table_info::get_table_indexes),
+        )
+        .route(
+            "/connections/:id/triggers",
+            get(table_info::get_table_triggers),
+        )
+        .route(
+            "/connections/:id/table-comment",
+            get(table_info::get_table_comment).put(table_info::set_table_comment),
+        )
+        .route(
+            "/connections/:id/permissions",
+            get(table_info::get_table_permissions).put(table_info::set_table_permissions),
+        )
+        .route(
+            "/connections/:id/permissions/table",
+            get(table_info::get_table_permissions),
+        )
+        .route(
+            "/connections/:id/permissions/schema",
+            get(table_info::get_schema_permissions),
+        )
+        .route(
+            "/connections/:id/permissions/function",
+            get(table_info::get_function_permissions),
+        )
+        .route("/connections/:id/roles", get(table_info::list_roles))
+        .route(
+            "/connections/:id/dependencies",
+            get(table_info::get_table_dependencies),
+        )
+        .route(
+            "/connections/:id/storage-info",
+            get(table_info::get_storage_bloat_info),
+        )
+        .route(
+            "/connections/:id/partitions",
+            get(table_info::get_partitions),
+        )
+        .route(
+            "/connections/:id/fk-orphans",
+            get(table_info::get_fk_orphans),
+        )
+}
+
+fn query_routes() -> Router<AppState> {
+    Router::new()
+        .route("/connections/:id/execute", post(query::execute_query))
+        .route(
+            "/connections/:id/execute/statements",
+            post(query::execute_statements),
+        )
+        .route(
+            "/connections/:id/execute/stream",
+            post(query_stream::execute_query_stream),
+        )
+        .route("/connections/:id/export/query", post(export::export_query))
+        .route("/connections/:id/export/table", post(export::export_table))
+        .route("/connections/:id/import/preview", post(import::preview_import))
+        .route("/connections/:id/import", post(import::import_file))
+        .route("/queries/cancel", post(query::cancel_query))
+        .route(
+            "/connections/:id/transactions",
+            get(transaction::list_transactions).post(transaction::begin_transaction),
+        )
+        .route(
+            "/connections/:id/transactions/:tx_id/execute",
+            post(transaction::execute_in_transaction),
+        )
+        .route(
+            "/connections/:id/transactions/:tx_id/commit",
+            post(transaction::commit_transaction),
+        )
+        .route(
+            "/connections/:id/transactions/:tx_id/rollback",
+            post(transaction::rollback_transaction),
+        )
+        .route("/connections/:id/explain", post(explain::explain_query))
+        .route(
+            "/connections/:id/execute-script",
+            post(data_tools::execute_script),
+        )
+        .route(
+            "/connections/:id/backup/sql",
+            get(data_tools::backup_postgres_sql),
+        )
+        .route("/connections/:id/dump", post(data_tools::dump_database))
+        .route(
+            "/connections/:id/query-results",
+            patch(result_edit::update_result_row).delete(result_edit::delete_result_row),
+        )
+        .route(
+            "/connections/:id/query-results/batch",
+            post(result_edit::save_result_changes),
+        )
+}
+
+fn workspace_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/connections/:id/history",
+            get(history::get_history)
+                .post(history::add_history)
+                .delete(history::clear_history),
+        )
+        .route(
+            "/connections/:id/history/delete",
+            post(history::delete_history_entries),
+        )
+        .route(
+            "/connections/:id/history/:entry_id",
+            delete(history::delete_history_entry),
+        )
+        .route(
+            "/connections/:id/saved-queries",
+            get(saved_query::list_saved_queries).post(saved_query::create_saved_query),
+        )
+        .route(
+            "/connections/:id/saved-queries/:query_id",
+            put(saved_query::update_saved_query).delete(saved_query::delete_saved_query),
+        )
+        .route(
+            "/connections/:id/saved-queries/:query_id/run",
+            post(saved_query::run_saved_query),
+        )
+        .route(
+            "/connections/:id/saved-query-folders",
+            get(saved_query_folder::list_folders).post(saved_query_folder::create_folder),
+        )
+        .route(
+            "/connections/:id/saved-query-folders/:folder_id",
+            put(saved_query_folder::update_folder).delete(saved_query_folder::delete_folder),
+        )
+        .route(
+            "/connections/:id/saved-filters",
+            get(saved_filter::list_saved_filters).post(saved_filter::create_saved_filter),
+        )
+        .route(
+            "/connections/:id/saved-filters/:filter_id",
+            delete(saved_filter::delete_saved_filter),
+        )
+        .route(
+            "/connections/:id/dashboards",
+            get(dashboard::list_dashboards).post(dashboard::create_dashboard),
+        )
+        .route(
+            "/connections/:id/dashboards/:dashboard_id",
+            get(dashboard::get_dashboard).delete(dashboard::delete_dashboard),
+        )
+        .route(
+            "/connections/:id/dashboards/:dashboard_id/charts",
+            get(dashboard::list_charts).post(dashboard::add_chart),
+        )
+        .route(
+            "/connections/:id/dashboards/:dashboard_id/charts/:chart_id",
+            delete(dashboard::delete_chart),
+        )
+}
+
+fn global_routes() -> Router<AppState> {
+    Router::new()
+        .route("/autocomplete", post(autocomplete::get_suggestions))
+        .route("/settings", get(settings::get_all_settings))
+        .route("/settings/reset", post(settings::reset_settings))
+        .route(
+            "/settings/pg-dump/check",
+            get(export_ddl::check_pg_dump_status),
+        )
+        .route(
+            "/settings/:key",
+            get(settings::get_setting)
+                .put(settings::update_setting)
+                .delete(settings::delete_setting),
+        )
+        .route(
+            "/snippets",
+            get(snippet::list_snippets).post(snippet::create_snippet),
+        )
+        .route(
+            "/snippets/:id",
+            put(snippet::update_snippet).delete(snippet::delete_snippet),
+        )
+        .route("/snippets/:id/render", post(snippet::render_snippet))
+        .route("/mock/preview", post(mock_data::preview_mock_data))
+        .route("/mock/sql", post(mock_data::generate_mock_data_sql))
+        .route("/mock/insert", post(mock_data::insert_mock_data))
+        .route("/schema-diff/compare", post(schema_diff::compare_schemas))
+        .route(
+            "/schema-diff/migration",
+            post(schema_diff::generate_migration),
+        )
+        .route("/schema-diff/test", get(schema_diff::test_schema_diff))
+        .route("/data-diff", post(data_diff::compare_table_data))
+        .route("/transfer", post(transfer::transfer_tables))
+}
@@ -1 +1,118 @@
+// HTTP routes for the standalone backend binary
 pub mod api;
+
+use crate::app_state::AppState;
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+/// Origins of the app's own pages: the Tauri webview (`tauri://localhost` on
+/// macOS and Linux, `http(s)://tauri.localhost` on Windows) and the Vite dev
+/// server.
+const APP_ORIGINS: &[&str] = &[
+    "tauri://localhost",
+    "http://tauri.localhost",
+    "https://tauri.localhost",
+    "http://localhost:1420",
+    "http://127.0.0.1:1420",
+];
+
+/// Builds the full HTTP application with every handler mounted under `/api`.
+pub fn app(state: AppState) -> Router {
+    let origins = allowed_origins();
+    Router::new()
+        .nest("/api", api::routes())
+        .layer(middleware::from_fn_with_state(
+            origins.clone(),
+            reject_foreign_origin,
+        ))
+        .layer(cors_layer(&origins))
+        .with_state(state)
+}
+
+/// `CORS_ORIGIN` (comma-separated) replaces the app's own origins.
+fn allowed_origins() -> Arc<Vec<HeaderValue>> {
+    let configured: Vec<HeaderValue> = std::env::var("CORS_ORIGIN")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .filter_map(|origin| HeaderValue::from_str(origin).ok())
+        .collect();
+    if configured.is_empty() {
+        Arc::new(
+            APP_ORIGINS
+                .iter()
+                .map(|origin| HeaderValue::from_static(origin))
+                .collect(),
+        )
+    } else {
+        Arc::new(configured)
+    }
+}
+
+/// The API is unauthenticated, so only the app's pages may call it from a
+/// browser; any other page the user opens could otherwise run queries.
+fn cors_layer(origins: &[HeaderValue]) -> CorsLayer {
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::list(origins.iter().cloned()))
+        .allow_methods(Any)
+        .allow_headers(Any)
+}
+
+/// CORS only hides responses; requests that need no preflight would still
+/// run. Browsers always send `Origin` on those, so other origins are refused
+/// here. Clients without an `Origin` (the Tauri process, scripts) are let in.
+async fn reject_foreign_origin(
+    State(origins): State<Arc<Vec<HeaderValue>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match request.headers().get(header::ORIGIN) {
+        Some(origin) if !origins.contains(origin) => (
+            StatusCode::FORBIDDEN,
+            Json(json!({ "message": "Origin not allowed" })),
+        )
+            .into_response(),
+        _ => next.run(request).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get};
+    use tower::Service;
+
+    async fn status_for(origin: Option<&str>) -> StatusCode {
+        let origins = Arc::new(vec![HeaderValue::from_static("tauri://localhost")]);
+        let mut app: Router = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(origins, reject_foreign_origin));
+        let mut request = Request::builder().uri("/");
+        if let Some(origin) = origin {
+            request = request.header(header::ORIGIN, origin);
+        }
+        app.call(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn refuses_requests_from_other_origins() {
+        assert_eq!(status_for(Some("tauri://localhost")).await, StatusCode::OK);
+        assert_eq!(status_for(None).await, StatusCode::OK);
+        assert_eq!(
+            status_for(Some("https://example.com")).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+}
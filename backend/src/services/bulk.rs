@@ -0,0 +1,264 @@
+use crate::services::table_filter::{SqlDialect, SqlFragment};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Progress is reported at most this often, plus once at the end.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Called by drivers with the number of rows written so far.
+pub type BulkProgressFn<'a> = dyn Fn(u64) + Send + Sync + 'a;
+
+/// Receives throttled progress of a bulk write.
+pub type OnBulkProgress<'a> = Option<&'a (dyn Fn(BulkProgress) + Send + Sync)>;
+
+/// One edited row: the values identifying it and the new column values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RowChange {
+    pub primary_key: HashMap<String, Value>,
+    pub updates: HashMap<String, Value>,
+}
+
+/// Edits to save to one table at once.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RowChanges {
+    #[serde(default)]
+    pub updates: Vec<RowChange>,
+    /// Primary keys of the rows to delete.
+    #[serde(default)]
+    pub deletes: Vec<HashMap<String, Value>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkProgress {
+    pub rows_written: u64,
+    pub total_rows: u64,
+    pub elapsed_ms: u64,
+    pub rows_per_second: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BulkReport {
+    pub rows_affected: u64,
+    pub execution_time_ms: u64,
+    pub rows_per_second: f64,
+}
+
+impl BulkReport {
+    /// Adds the rows and time of a later step.
+    pub fn merge(&mut self, other: BulkReport) {
+        self.rows_affected += other.rows_affected;
+        self.execution_time_ms += other.execution_time_ms;
+        self.rows_per_second = rows_per_second(
+            self.rows_affected,
+            Duration::from_millis(self.execution_time_ms),
+        );
+    }
+}
+
+/// Turns the row counts reported by a driver into throttled `BulkProgress`
+/// events and the final `BulkReport`.
+pub struct ProgressMeter<'a> {
+    total_rows: u64,
+    started: Instant,
+    last_sent: Mutex<Option<Instant>>,
+    on_progress: OnBulkProgress<'a>,
+}
+
+impl<'a> ProgressMeter<'a> {
+    pub fn new(total_rows: usize, on_progress: OnBulkProgress<'a>) -> Self {
+        Self {
+            total_rows: total_rows as u64,
+            started: Instant::now(),
+            last_sent: Mutex::new(None),
+            on_progress,
+        }
+    }
+
+    pub fn tick(&self, rows_written: u64) {
+        let Some(on_progress) = self.on_progress else {
+            return;
+        };
+        let now = Instant::now();
+        {
+            let mut last_sent = self.last_sent.lock().unwrap();
+            let due = !matches!(*last_sent, Some(at) if now - at < PROGRESS_INTERVAL);
+            if !due && rows_written < self.total_rows {
+                return;
+            }
+            *last_sent = Some(now);
+        }
+        on_progress(self.progress(rows_written));
+    }
+
+    pub fn finish(&self, rows_affected: u64) -> BulkReport {
+        let elapsed = self.started.elapsed();
+        BulkReport {
+            rows_affected,
+            execution_time_ms: elapsed.as_millis() as u64,
+            rows_per_second: rows_per_second(rows_affected, elapsed),
+        }
+    }
+
+    fn progress(&self, rows_written: u64) -> BulkProgress {
+        let elapsed = self.started.elapsed();
+        BulkProgress {
+            rows_written,
+            total_rows: self.total_rows,
+            elapsed_ms: elapsed.as_millis() as u64,
+            rows_per_second: rows_per_second(rows_written, elapsed),
+        }
+    }
+}
+
+pub fn rows_per_second(rows: u64, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64();
+    if seconds > 0.0 {
+        rows as f64 / seconds
+    } else {
+        0.0
+    }
+}
+
+/// `schema.table`, or just `table` when the schema is empty.
+pub fn table_ref(dialect: SqlDialect, schema: &str, table: &str) -> Result<String> {
+    let table = dialect.quote_ident(table)?;
+    if schema.is_empty() {
+        Ok(table)
+    } else {
+        Ok(format!("{}.{}", dialect.quote_ident(schema)?, table))
+    }
+}
+
+/// `UPDATE t SET a = $1 WHERE id = $2`. Columns are sorted so that changes
+/// to the same columns produce the same statement.
+pub fn update_statement(
+    dialect: SqlDialect,
+    table_ref: &str,
+    change: &RowChange,
+) -> Result<SqlFragment> {
+    if change.updates.is_empty() {
+        return Err(anyhow!("Row change has no updated columns"));
+    }
+    let mut params = Vec::new();
+    let mut assignments = Vec::with_capacity(change.updates.len());
+    for (column, value) in sorted(&change.updates) {
+        params.push(value.clone());
+        assignments.push(format!(
+            "{} = {}",
+            dialect.quote_ident(column)?,
+            dialect.placeholder(params.len())
+        ));
+    }
+    let condition = key_condition(dialect, &change.primary_key, &mut params)?;
+    Ok(SqlFragment {
+        sql: format!(
+            "UPDATE {} SET {} WHERE {}",
+            table_ref,
+            assignments.join(", "),
+            condition
+        ),
+        params,
+    })
+}
+
+/// `DELETE FROM t WHERE id = $1`.
+pub fn delete_statement(
+    dialect: SqlDialect,
+    table_ref: &str,
+    primary_key: &HashMap<String, Value>,
+) -> Result<SqlFragment> {
+    let mut params = Vec::new();
+    let condition = key_condition(dialect, primary_key, &mut params)?;
+    Ok(SqlFragment {
+        sql: format!("DELETE FROM {} WHERE {}", table_ref, condition),
+        params,
+    })
+}
+
+/// `id = $1 AND tenant IS NULL`, for engines that build their own statements.
+pub fn key_filter(
+    dialect: SqlDialect,
+    primary_key: &HashMap<String, Value>,
+) -> Result<SqlFragment> {
+    let mut params = Vec::new();
+    let sql = key_condition(dialect, primary_key, &mut params)?;
+    Ok(SqlFragment { sql, params })
+}
+
+fn key_condition(
+    dialect: SqlDialect,
+    primary_key: &HashMap<String, Value>,
+    params: &mut Vec<Value>,
+) -> Result<String> {
+    if primary_key.is_empty() {
+        return Err(anyhow!("Primary key cannot be empty"));
+    }
+    let mut terms = Vec::with_capacity(primary_key.len());
+    for (column, value) in sorted(primary_key) {
+        let column = dialect.quote_ident(column)?;
+        if value.is_null() {
+            terms.push(format!("{} IS NULL", column));
+        } else {
+            params.push(value.clone());
+            terms.push(format!(
+                "{} = {}",
+                column,
+                dialect.placeholder(params.len())
+            ));
+        }
+    }
+    Ok(terms.join(" AND "))
+}
+
+fn sorted(values: &HashMap<String, Value>) -> Vec<(&String, &Value)> {
+    let mut entries: Vec<_> = values.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn map(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn builds_update_and_delete_statements() {
+        let table = table_ref(SqlDialect::POSTGRES, "public", "users").unwrap();
+        let change = RowChange {
+            primary_key: map(json!({ "tenant": null, "id": 7 })),
+            updates: map(json!({ "name": "Ann", "age": 30 })),
+        };
+        let update = update_statement(SqlDialect::POSTGRES, &table, &change).unwrap();
+        assert_eq!(
+            update.sql,
+            r#"UPDATE "public"."users" SET "age" = $1, "name" = $2 WHERE "id" = $3 AND "tenant" IS NULL"#
+        );
+        assert_eq!(update.params, vec![json!(30), json!("Ann"), json!(7)]);
+
+        let table = table_ref(SqlDialect::MYSQL, "", "users").unwrap();
+        let delete =
+            delete_statement(SqlDialect::MYSQL, &table, &map(json!({ "id": 7, "a": 1 }))).unwrap();
+        assert_eq!(delete.sql, "DELETE FROM `users` WHERE `a` = ? AND `id` = ?");
+        assert!(delete_statement(SqlDialect::MYSQL, &table, &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn throttles_progress_but_reports_the_last_rows() {
+        let seen = Mutex::new(Vec::new());
+        let on_progress = |progress: BulkProgress| seen.lock().unwrap().push(progress.rows_written);
+        let meter = ProgressMeter::new(30, Some(&on_progress));
+        meter.tick(10);
+        meter.tick(20);
+        meter.tick(30);
+        assert_eq!(*seen.lock().unwrap(), vec![10, 30]);
+        assert_eq!(meter.finish(30).rows_affected, 30);
+    }
+}
@@ -5,18 +5,33 @@ use super::db_driver::{
     ViewInfo,
 };
 use super::driver::{
-    ColumnManagement, ConnectionDriver, DdlExportDriver, FunctionOperations, QueryDriver,
-    SchemaIntrospection, TableOperations, ViewOperations,
+    BulkOperationsDriver, ColumnManagement, ConnectionDriver, ConnectionPoolDriver,
+    DdlExportDriver, FunctionOperations, PreparedStatementDriver, QueryDriver, SchemaIntrospection,
+    TableOperations, TransactionDriver, TransactionSession, ViewOperations,
 };
 use crate::models::entities::connection as ConnectionModel;
 use crate::models::export_ddl::{DdlObjectType, DdlScope, ExportDdlOptions};
+use crate::services::bulk::{self, BulkProgressFn, RowChange};
 use crate::services::db_driver::SessionInfo;
 use crate::services::driver::extension::DatabaseManagementDriver;
+use crate::services::query_cancel::{QueryHandle, ServerCancel};
+use crate::services::query_limits::{self, QueryLimits};
+use crate::services::query_paging::count_sql;
+use crate::services::query_params::{self, QueryParams};
+use crate::services::query_stream::{base_query, is_select_like, QueryStreamSink, StreamOptions};
+use crate::services::table_filter::{self, FilterNode, SortSpec, SqlDialect};
+use crate::services::tls::TlsSettings;
 use anyhow::Result;
 use async_trait::async_trait;
 use clickhouse::Client;
+use hyper_rustls::HttpsConnectorBuilder;
+use hyper_util::client::legacy::Client as HyperClient;
+use hyper_util::rt::TokioExecutor;
 use serde::Deserialize;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
 pub struct ClickHouseDriver {
     client: Client,
@@ -25,14 +40,25 @@ pub struct ClickHouseDriver {
 
 impl ClickHouseDriver {
     pub async fn new(connection: &ConnectionModel::Model, password: &str) -> Result<Self> {
-        let url = if connection.ssl {
-            format!("https://{}:{}", connection.host, connection.port)
+        let tls = TlsSettings::from_model(connection)?;
+        tls.ensure_supported("clickhouse")?;
+
+        let client = if tls.is_enabled() {
+            let https = HttpsConnectorBuilder::new()
+                .with_tls_config(tls.rustls_client_config()?)
+                .https_only()
+                .enable_http1()
+                .build();
+            let http_client = HyperClient::builder(TokioExecutor::new())
+                .pool_idle_timeout(Duration::from_secs(2))
+                .build(https);
+            Client::with_http_client(http_client)
+                .with_url(format!("https://{}:{}", connection.host, connection.port))
         } else {
-            format!("http://{}:{}", connection.host, connection.port)
+            Client::default().with_url(format!("http://{}:{}", connection.host, connection.port))
         };
 
-        let client = Client::default()
-            .with_url(url)
+        let mut client = client
             .with_user(&connection.username)
             .with_password(password)
             .with_database(if connection.database.is_empty() {
@@ -40,73 +66,53 @@ impl ClickHouseDriver {
             } else {
                 &connection.database
             });
+        // Whole seconds, which every server version accepts.
+        if let Some(ms) = QueryLimits::from_connection(connection).timeout_ms() {
+            client = client.with_option("max_execution_time", ms.div_ceil(1000).to_string());
+        }
 
         Ok(Self {
             client,
             database: connection.database.clone(),
         })
     }
-}
-
-#[async_trait]
-impl ConnectionDriver for ClickHouseDriver {
-    async fn test_connection(&self) -> Result<()> {
-        let _ = self
-            .client
-            .query("SELECT 1")
-            .fetch_one::<u8>()
-            .await
-            .map_err(|e| anyhow::anyhow!(e))?;
-        Ok(())
-    }
-}
-
-#[async_trait]
-impl QueryDriver for ClickHouseDriver {
-    async fn execute(&self, query: &str) -> Result<u64> {
-        self.client
-            .query(query)
-            .execute()
-            .await
-            .map_err(|e| anyhow::anyhow!(e))?;
-        Ok(0)
-    }
-
-    async fn execute_script(&self, script: &str) -> Result<u64> {
-        self.execute(script).await
-    }
 
-    async fn query(&self, query: &str) -> Result<QueryResult> {
+    /// Runs `query`, tagging it with `query_id` so it can be killed while running.
+    /// `params` are bound to the `?` placeholders in order.
+    async fn run_query(
+        &self,
+        query: &str,
+        params: &[Value],
+        query_id: Option<&str>,
+    ) -> Result<QueryResult> {
         let trimmed = query.trim();
         let q_upper = trimmed.to_uppercase();
 
         let is_select = q_upper.starts_with("SELECT") || q_upper.starts_with("WITH");
 
-        let mut cursor = if is_select {
-            let clean_query = trimmed.trim_end_matches(';');
-            // Wrap in subquery with alias to be safe and handle limits/offsets correctly
-            // Use formatRow('JSONEachRow', *) to serialize the entire row as a JSON object string
-            let wrapped = format!(
-                "SELECT formatRow('JSONEachRow', *) FROM ({}) AS _data",
-                clean_query
-            );
-            self.client
-                .query(&wrapped)
-                .fetch::<Vec<u8>>()
-                .map_err(|e| anyhow::anyhow!(e))?
+        let sql = if is_select {
+            json_each_row(trimmed.trim_end_matches(';'))
         } else {
-            self.client
-                .query(query)
-                .fetch::<Vec<u8>>()
-                .map_err(|e| anyhow::anyhow!(e))?
+            query.to_string()
         };
 
+        let mut request = self.client.query(&sql);
+        for param in params {
+            request = request.bind(param);
+        }
+        if let Some(query_id) = query_id {
+            request = request.with_option("query_id", query_id);
+        }
+        let mut cursor = request.fetch::<Vec<u8>>().map_err(|e| anyhow::anyhow!(e))?;
+
         let mut rows = Vec::new();
         let mut columns = Vec::new();
+        let mut budget = query_limits::read_budget();
+        let mut truncated = None;
 
         while let Some(row_bytes) = cursor.next().await.map_err(|e| anyhow::anyhow!(e))? {
             let row_str = String::from_utf8_lossy(&row_bytes);
-            if let Ok(val) = serde_json::from_str::<serde_json::Value>(&row_str) {
+            let row_values = if let Ok(val) = serde_json::from_str::<serde_json::Value>(&row_str) {
                 if let Some(obj) = val.as_object() {
                     if columns.is_empty() {
                         columns = obj.keys().cloned().collect();
@@ -115,19 +121,25 @@ impl QueryDriver for ClickHouseDriver {
                     for col in &columns {
                         row_values.push(obj.get(col).cloned().unwrap_or(Value::Null));
                     }
-                    rows.push(row_values);
+                    row_values
                 } else {
                     if columns.is_empty() {
                         columns = vec!["Result".to_string()];
                     }
-                    rows.push(vec![val]);
+                    vec![val]
                 }
             } else {
                 if columns.is_empty() {
                     columns = vec!["Result".to_string()];
                 }
-                rows.push(vec![Value::String(row_str.to_string())]);
+                vec![Value::String(row_str.to_string())]
+            };
+            // Dropping the cursor closes the response.
+            if !budget.admit(&row_values) {
+                truncated = Some(true);
+                break;
             }
+            rows.push(row_values);
         }
 
         Ok(QueryResult {
@@ -143,13 +155,227 @@ impl QueryDriver for ClickHouseDriver {
             execution_time_ms: None,
             json: None,
             display_mode: None,
+            truncated,
         })
     }
 
+    /// Runs a statement of any kind. Only SELECTs go through `run_query`;
+    /// its fetch path is read-only, so writes must be sent with `execute`.
+    async fn run_statement(&self, query: &str) -> Result<QueryResult> {
+        if is_select_like(query) {
+            return self.run_query(query, &[], None).await;
+        }
+        QueryDriver::execute(self, query).await?;
+        Ok(QueryResult {
+            columns: vec![],
+            rows: vec![],
+            affected_rows: 0,
+            column_metadata: None,
+            total_count: None,
+            limit: None,
+            offset: None,
+            has_more: None,
+            row_metadata: None,
+            execution_time_ms: None,
+            json: None,
+            display_mode: None,
+            truncated: None,
+        })
+    }
+
+    /// Streams a SELECT's rows, started with `query_id` when given so that
+    /// it can be killed; other statements are sent in one go.
+    async fn stream_rows(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+        query_id: Option<&str>,
+    ) -> Result<u64> {
+        if !is_select_like(query) {
+            let result = self.run_query(query, &[], query_id).await?;
+            sink.send_result(result).await;
+            return Ok(0);
+        }
+
+        let base = base_query(query);
+        let (sql_to_run, limit, offset) = options.paged_sql(base);
+        let total_count = if options.include_total_count {
+            let count = self
+                .client
+                .query(&count_sql(base))
+                .fetch_one::<u64>()
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+            Some(count as i64)
+        } else {
+            None
+        };
+
+        let mut request = self.client.query(&json_each_row(&sql_to_run));
+        if let Some(query_id) = query_id {
+            request = request.with_option("query_id", query_id);
+        }
+        let mut cursor = request.fetch::<Vec<u8>>().map_err(|e| anyhow::anyhow!(e))?;
+
+        let mut columns: Option<Vec<String>> = None;
+        while let Some(row_bytes) = cursor.next().await.map_err(|e| anyhow::anyhow!(e))? {
+            let object = match serde_json::from_slice::<Value>(&row_bytes)? {
+                Value::Object(object) => object,
+                other => {
+                    let mut object = serde_json::Map::new();
+                    object.insert("Result".to_string(), other);
+                    object
+                }
+            };
+            if columns.is_none() {
+                let names: Vec<String> = object.keys().cloned().collect();
+                if !sink.meta(names.clone(), total_count, limit, offset).await {
+                    return Ok(0);
+                }
+                columns = Some(names);
+            }
+            let row = columns
+                .iter()
+                .flatten()
+                .map(|c| object.get(c).cloned().unwrap_or(Value::Null))
+                .collect();
+            if !sink.row(row).await {
+                break;
+            }
+        }
+
+        if columns.is_none() {
+            sink.meta(vec![], total_count, limit, offset).await;
+        }
+        Ok(0)
+    }
+}
+
+#[async_trait]
+impl ConnectionPoolDriver for ClickHouseDriver {
+    async fn get_pool_size(&self) -> Result<usize> {
+        Err(anyhow::anyhow!(
+            "The ClickHouse HTTP client does not expose pool statistics"
+        ))
+    }
+
+    async fn get_active_connections(&self) -> Result<usize> {
+        self.get_pool_size().await
+    }
+
+    async fn get_idle_connections(&self) -> Result<usize> {
+        self.get_pool_size().await
+    }
+}
+
+#[async_trait]
+impl ConnectionDriver for ClickHouseDriver {
+    async fn test_connection(&self) -> Result<()> {
+        let _ = self
+            .client
+            .query("SELECT 1")
+            .fetch_one::<u8>()
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+        Ok(())
+    }
+}
+
+/// Wraps a SELECT so every row comes back as one JSON object string. The
+/// subquery alias keeps outer LIMIT/OFFSET wrappers valid.
+fn json_each_row(select: &str) -> String {
+    format!(
+        "SELECT formatRow('JSONEachRow', *) FROM ({}) AS _data",
+        select
+    )
+}
+
+/// Kills a running statement by the `query_id` it was started with.
+struct ClickHouseKill {
+    client: Client,
+    query_id: String,
+}
+
+#[async_trait]
+impl ServerCancel for ClickHouseKill {
+    async fn cancel(&self) -> Result<()> {
+        self.client
+            .query("KILL QUERY WHERE query_id = ?")
+            .bind(&self.query_id)
+            .execute()
+            .await
+            .map_err(|e| anyhow::anyhow!(e))
+    }
+}
+
+#[async_trait]
+impl QueryDriver for ClickHouseDriver {
+    async fn execute(&self, query: &str) -> Result<u64> {
+        self.client
+            .query(query)
+            .execute()
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+        Ok(0)
+    }
+
+    async fn execute_script(&self, script: &str) -> Result<u64> {
+        self.execute(script).await
+    }
+
+    async fn query(&self, query: &str) -> Result<QueryResult> {
+        self.run_query(query, &[], None).await
+    }
+
     async fn execute_query(&self, query: &str) -> Result<QueryResult> {
         self.query(query).await
     }
 
+    async fn stream_query(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+    ) -> Result<u64> {
+        self.stream_rows(query, options, sink, None).await
+    }
+
+    async fn stream_query_cancellable(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+        handle: &QueryHandle,
+    ) -> Result<u64> {
+        let query_id = uuid::Uuid::new_v4().to_string();
+        let kill = Arc::new(ClickHouseKill {
+            client: self.client.clone(),
+            query_id: query_id.clone(),
+        });
+        handle
+            .run(
+                kill,
+                self.stream_rows(query, options, sink, Some(&query_id)),
+            )
+            .await
+    }
+
+    async fn execute_query_cancellable(
+        &self,
+        query: &str,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let query_id = uuid::Uuid::new_v4().to_string();
+        let kill = Arc::new(ClickHouseKill {
+            client: self.client.clone(),
+            query_id: query_id.clone(),
+        });
+        handle
+            .run(kill, self.run_query(query, &[], Some(&query_id)))
+            .await
+    }
+
     async fn explain(&self, query: &str, _analyze: bool) -> Result<Value> {
         let explain_query = format!("EXPLAIN JSON {}", query);
         // Explain in CH usually returns a string or structured output depending on settings.
@@ -399,7 +625,8 @@ impl TableOperations for ClickHouseDriver {
         table: &str,
         limit: i64,
         offset: i64,
-        _filter: Option<String>,
+        filter: Option<FilterNode>,
+        sort: Vec<SortSpec>,
         _document_id: Option<String>,
         _fields: Option<Vec<String>>,
     ) -> Result<QueryResult> {
@@ -421,11 +648,13 @@ impl TableOperations for ClickHouseDriver {
             "*".to_string()
         };
 
+        let where_clause = table_filter::where_clause(filter.as_ref(), SqlDialect::CLICKHOUSE)?;
+        let order_by = table_filter::order_by_clause(&sort, SqlDialect::CLICKHOUSE)?;
         let sql = format!(
-            "SELECT {} FROM `{}`.`{}` LIMIT {} OFFSET {}",
-            select_clause, db, table, limit, offset
+            "SELECT {} FROM `{}`.`{}`{}{} LIMIT {} OFFSET {}",
+            select_clause, db, table, where_clause.sql, order_by, limit, offset
         );
-        self.query(&sql).await
+        self.run_query(&sql, &where_clause.params, None).await
     }
 
     async fn get_table_constraints(&self, _schema: &str, _table: &str) -> Result<TableConstraints> {
@@ -1009,6 +1238,241 @@ impl DatabaseManagementDriver for ClickHouseDriver {
     }
 }
 
+/// ClickHouse keeps a transaction in an HTTP session, so "pinning" means
+/// sending every statement with the same `session_id`. Needs a server with
+/// experimental transactions enabled; tables must be MergeTree.
+pub struct ClickHouseTransaction {
+    session: ClickHouseDriver,
+}
+
+/// Long enough to outlive `TransactionManager`'s idle rollback. The server
+/// rolls back a transaction whose session expires.
+const TRANSACTION_SESSION_TIMEOUT_SECS: u64 = 3600;
+
+#[async_trait]
+impl TransactionSession for ClickHouseTransaction {
+    async fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
+        self.session.run_statement(query).await
+    }
+
+    async fn commit(self: Box<Self>) -> Result<()> {
+        QueryDriver::execute(&self.session, "COMMIT").await?;
+        Ok(())
+    }
+
+    async fn rollback(self: Box<Self>) -> Result<()> {
+        QueryDriver::execute(&self.session, "ROLLBACK").await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TransactionDriver for ClickHouseDriver {
+    async fn begin_transaction(&self) -> Result<Box<dyn TransactionSession>> {
+        let session = ClickHouseDriver {
+            client: self
+                .client
+                .clone()
+                .with_option("session_id", uuid::Uuid::new_v4().to_string())
+                .with_option(
+                    "session_timeout",
+                    TRANSACTION_SESSION_TIMEOUT_SECS.to_string(),
+                ),
+            database: self.database.clone(),
+        };
+        QueryDriver::execute(&session, "BEGIN TRANSACTION").await?;
+        Ok(Box::new(ClickHouseTransaction { session }))
+    }
+}
+
+/// The HTTP interface has no binds for `?`, so values are inlined as escaped
+/// literals, which the server converts to the types they are used as.
+#[async_trait]
+impl PreparedStatementDriver for ClickHouseDriver {
+    async fn execute_with_params(&self, query: &str, params: &QueryParams) -> Result<QueryResult> {
+        let sql = query_params::inline(query, params, SqlDialect::CLICKHOUSE, literal)?;
+        // The client binds its own `?` placeholders; `??` is a literal `?`.
+        self.run_statement(&sql.replace('?', "??")).await
+    }
+}
+
+/// Data sent with one `INSERT`.
+const INSERT_CHUNK_BYTES: usize = 8 << 20;
+/// Row changes per `ALTER TABLE`, which the server runs as one mutation.
+const MUTATION_CHUNK: usize = 500;
+
+/// ClickHouse has no multi-statement transactions, so a failed chunk leaves
+/// the earlier ones applied. It does not report affected rows either; the
+/// counts returned are the rows sent.
+#[async_trait]
+impl BulkOperationsDriver for ClickHouseDriver {
+    /// Sends the rows as `JSONEachRow` data after the `INSERT`, which the
+    /// server parses straight into native blocks.
+    async fn bulk_insert(
+        &self,
+        schema: &str,
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<Value>],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        let names = columns
+            .iter()
+            .map(|column| SqlDialect::CLICKHOUSE.quote_ident(column))
+            .collect::<Result<Vec<_>>>()?;
+        let prefix = format!(
+            "INSERT INTO {} ({}) FORMAT JSONEachRow\n",
+            self.bulk_table(schema, table)?,
+            names.join(", ")
+        );
+        let mut body = prefix.clone();
+        for (i, row) in rows.iter().enumerate() {
+            if row.len() != columns.len() {
+                return Err(anyhow::anyhow!(
+                    "Row {} has {} values for {} columns",
+                    i + 1,
+                    row.len(),
+                    columns.len()
+                ));
+            }
+            let object: serde_json::Map<String, Value> =
+                columns.iter().cloned().zip(row.iter().cloned()).collect();
+            body.push_str(&Value::Object(object).to_string());
+            body.push('\n');
+            if body.len() >= INSERT_CHUNK_BYTES {
+                self.execute_bulk(&body).await?;
+                progress((i + 1) as u64);
+                body.truncate(prefix.len());
+            }
+        }
+        if body.len() > prefix.len() {
+            self.execute_bulk(&body).await?;
+        }
+        progress(rows.len() as u64);
+        Ok(rows.len() as u64)
+    }
+
+    async fn bulk_update(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let mut commands = Vec::with_capacity(changes.len());
+        for change in changes {
+            if change.updates.is_empty() {
+                return Err(anyhow::anyhow!("Row change has no updated columns"));
+            }
+            let mut updates: Vec<_> = change.updates.iter().collect();
+            updates.sort_by(|a, b| a.0.cmp(b.0));
+            let assignments = updates
+                .into_iter()
+                .map(|(column, value)| {
+                    Ok(format!(
+                        "{} = {}",
+                        SqlDialect::CLICKHOUSE.quote_ident(column)?,
+                        literal(value)
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            commands.push(format!(
+                "UPDATE {} WHERE {}",
+                assignments.join(", "),
+                key_filter(&change.primary_key)?
+            ));
+        }
+        self.run_mutations(schema, table, &commands, progress).await
+    }
+
+    async fn bulk_delete(
+        &self,
+        schema: &str,
+        table: &str,
+        primary_keys: &[HashMap<String, Value>],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let commands = primary_keys
+            .iter()
+            .map(|key| Ok(format!("DELETE WHERE {}", key_filter(key)?)))
+            .collect::<Result<Vec<_>>>()?;
+        self.run_mutations(schema, table, &commands, progress).await
+    }
+
+    async fn bulk_insert_is_atomic(&self, _schema: &str, _table: &str) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+impl ClickHouseDriver {
+    /// `db.table`, in the connection's database when `schema` is empty.
+    fn bulk_table(&self, schema: &str, table: &str) -> Result<String> {
+        let schema = if schema.is_empty() {
+            &self.database
+        } else {
+            schema
+        };
+        bulk::table_ref(SqlDialect::CLICKHOUSE, schema, table)
+    }
+
+    /// Runs `ALTER TABLE` commands in chunks and waits for each mutation.
+    async fn run_mutations(
+        &self,
+        schema: &str,
+        table: &str,
+        commands: &[String],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let table_ref = self.bulk_table(schema, table)?;
+        let mut written = 0;
+        for chunk in commands.chunks(MUTATION_CHUNK) {
+            self.execute_bulk(&format!(
+                "ALTER TABLE {} {} SETTINGS mutations_sync = 1",
+                table_ref,
+                chunk.join(", ")
+            ))
+            .await?;
+            written += chunk.len();
+            progress(written as u64);
+        }
+        Ok(written as u64)
+    }
+
+    async fn execute_bulk(&self, sql: &str) -> Result<()> {
+        // The client binds its own `?` placeholders; `??` is a literal `?`.
+        QueryDriver::execute(self, &sql.replace('?', "??")).await?;
+        Ok(())
+    }
+}
+
+/// `id = 1 AND tenant IS NULL`
+fn key_filter(primary_key: &HashMap<String, Value>) -> Result<String> {
+    let filter = bulk::key_filter(SqlDialect::CLICKHOUSE, primary_key)?;
+    query_params::inline(
+        &filter.sql,
+        &QueryParams::Positional(filter.params),
+        SqlDialect::CLICKHOUSE,
+        literal,
+    )
+}
+
+fn literal(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'")),
+        Value::Array(items) => format!(
+            "[{}]",
+            items.iter().map(literal).collect::<Vec<_>>().join(", ")
+        ),
+        Value::Object(_) => literal(&Value::String(value.to_string())),
+    }
+}
+
 #[async_trait]
 impl DdlExportDriver for ClickHouseDriver {
     async fn export_ddl(&self, options: &ExportDdlOptions) -> Result<String> {
@@ -1097,3 +1561,6 @@ impl DdlExportDriver for ClickHouseDriver {
         }
     }
 }
+
+#[async_trait]
+impl crate::services::driver::DumpDriver for ClickHouseDriver {}
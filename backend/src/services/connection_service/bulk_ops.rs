@@ -0,0 +1,95 @@
+use super::ConnectionService;
+use crate::services::bulk::{BulkReport, OnBulkProgress, ProgressMeter, RowChanges};
+use crate::services::driver::capabilities::DriverCapability;
+use crate::services::driver::{BulkOperationsDriver, TableOperations};
+use crate::services::driver_factory;
+use anyhow::Result;
+use serde_json::Value;
+use uuid::Uuid;
+
+impl ConnectionService {
+    /// Inserts `rows` through the engine's bulk path (`COPY`, multi-row
+    /// `INSERT`s, `insert_many`, ...) and reports the throughput.
+    pub async fn bulk_insert(
+        &self,
+        connection_id: Uuid,
+        schema: &str,
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<Value>],
+        on_progress: OnBulkProgress<'_>,
+    ) -> Result<BulkReport> {
+        let (connection, password) = self.get_connection_with_password(connection_id).await?;
+        driver_factory::spec_for(&connection.db_type)?.require(DriverCapability::BulkOperations)?;
+        let driver = self.driver(&connection, &password).await?;
+
+        let meter = ProgressMeter::new(rows.len(), on_progress);
+        let progress = |rows_written| meter.tick(rows_written);
+        let rows_affected =
+            BulkOperationsDriver::bulk_insert(&*driver, schema, table, columns, rows, &progress)
+                .await?;
+        Ok(meter.finish(rows_affected))
+    }
+
+    /// Saves edited and deleted rows of one table: updates first, then
+    /// deletes. Engines without a bulk path get one call per row.
+    pub async fn save_row_changes(
+        &self,
+        connection_id: Uuid,
+        schema: &str,
+        table: &str,
+        changes: &RowChanges,
+        on_progress: OnBulkProgress<'_>,
+    ) -> Result<BulkReport> {
+        let (connection, password) = self.get_connection_with_password(connection_id).await?;
+        let spec = driver_factory::spec_for(&connection.db_type)?;
+        let driver = self.driver(&connection, &password).await?;
+
+        let meter = ProgressMeter::new(changes.updates.len() + changes.deletes.len(), on_progress);
+        let updated = changes.updates.len() as u64;
+        let mut rows_affected = 0;
+        if spec.supports(DriverCapability::BulkOperations) {
+            if !changes.updates.is_empty() {
+                let progress = |rows_written| meter.tick(rows_written);
+                rows_affected += BulkOperationsDriver::bulk_update(
+                    &*driver,
+                    schema,
+                    table,
+                    &changes.updates,
+                    &progress,
+                )
+                .await?;
+            }
+            if !changes.deletes.is_empty() {
+                let progress = |rows_written| meter.tick(updated + rows_written);
+                rows_affected += BulkOperationsDriver::bulk_delete(
+                    &*driver,
+                    schema,
+                    table,
+                    &changes.deletes,
+                    &progress,
+                )
+                .await?;
+            }
+        } else {
+            for (i, change) in changes.updates.iter().enumerate() {
+                rows_affected += TableOperations::update_row(
+                    &*driver,
+                    schema,
+                    table,
+                    &change.primary_key,
+                    &change.updates,
+                    None,
+                )
+                .await?;
+                meter.tick(i as u64 + 1);
+            }
+            for (i, primary_key) in changes.deletes.iter().enumerate() {
+                rows_affected +=
+                    TableOperations::delete_row(&*driver, schema, table, primary_key, None).await?;
+                meter.tick(updated + i as u64 + 1);
+            }
+        }
+        Ok(meter.finish(rows_affected))
+    }
+}
@@ -1,6 +1,8 @@
 use super::ConnectionService;
 use crate::models::entities::{connection, connection::Entity as Connection};
 use crate::services::driver::ConnectionDriver;
+use crate::services::driver_factory;
+use crate::services::ssh_tunnel::SshTunnelManager;
 use anyhow::Result;
 use chrono::Utc;
 use sea_orm::*;
@@ -26,7 +28,6 @@ impl ConnectionService {
             .await?
             .ok_or(anyhow::anyhow!("Connection not found"))?;
 
-        // Try getting from keychain first
         // Try getting from keychain first
         let password = if let Ok(Some(p)) = self.credentials.get_password(&id, "password") {
             p
@@ -44,6 +45,21 @@ impl ConnectionService {
         };
 
         let connection = self.apply_database_override(connection);
+
+        let ssh_password = self.stored_ssh_secret(
+            &connection,
+            "ssh_password",
+            connection.ssh_password.as_ref(),
+        );
+        let ssh_key_passphrase = self.stored_ssh_secret(
+            &connection,
+            "ssh_key_passphrase",
+            connection.ssh_key_passphrase.as_ref(),
+        );
+        let connection = self
+            .apply_ssh_tunnel(connection, ssh_password, ssh_key_passphrase)
+            .await?;
+
         Ok((connection, password))
     }
 
@@ -127,6 +143,9 @@ impl ConnectionService {
             is_read_only: Set(data.is_read_only),
             environment: Set(data.environment),
             safe_mode_level: Set(data.safe_mode_level),
+            statement_timeout_ms: Set(data.statement_timeout_ms),
+            max_result_rows: Set(data.max_result_rows),
+            max_result_bytes: Set(data.max_result_bytes),
             created_at: Set(Utc::now().into()),
             updated_at: Set(Utc::now().into()),
             ..Default::default()
@@ -187,6 +206,9 @@ impl ConnectionService {
             is_read_only: Set(data.is_read_only),
             environment: Set(data.environment),
             safe_mode_level: Set(data.safe_mode_level),
+            statement_timeout_ms: Set(data.statement_timeout_ms),
+            max_result_rows: Set(data.max_result_rows),
+            max_result_bytes: Set(data.max_result_bytes),
             updated_at: Set(Utc::now().into()),
             ..Default::default()
         };
@@ -225,10 +247,16 @@ impl ConnectionService {
 
         active_model.ssh_key_file = Set(data.ssh_key_file);
 
-        active_model
+        let updated = active_model
             .update(&self.db)
             .await
-            .map_err(|e| anyhow::anyhow!(e))
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        // Endpoints or credentials may have changed; the next call reconnects.
+        self.evict_drivers(id);
+        SshTunnelManager::global().close(id).await;
+
+        Ok(updated)
     }
 
     pub async fn delete_connection(&self, id: Uuid) -> Result<()> {
@@ -241,6 +269,9 @@ impl ConnectionService {
             .exec(&self.db)
             .await
             .map_err(|e| anyhow::anyhow!(e))?;
+
+        self.evict_drivers(id);
+        SshTunnelManager::global().close(id).await;
         Ok(())
     }
 
@@ -272,68 +303,62 @@ impl ConnectionService {
         connection: connection::Model,
         password: &str,
     ) -> Result<()> {
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                // Try connecting to the specified database first
-                match PostgresDriver::new(&connection, password).await {
-                    Ok(driver) => ConnectionDriver::test_connection(&driver).await,
-                    Err(e) => {
-                        tracing::error!("Initial Postgres connection failed: {}", e);
-                        let err_msg = e.to_string().to_lowercase();
-                        // If database does not exist, try connecting to 'postgres' to verify credentials
-                        if err_msg.contains("does not exist")
-                            || err_msg.contains("3d000")
-                            || (err_msg.contains("database") && err_msg.contains("not exist"))
-                        {
-                            match PostgresDriver::new_for_test(&connection, password).await {
-                                Ok(driver) => {
-                                    match ConnectionDriver::test_connection(&driver).await {
-                                        Ok(_) => {
-                                            // If we can connect to 'postgres', but not the target DB,
-                                            // we return a specific error to let the user know credentials are good.
-                                            Err(anyhow::anyhow!("Credentials valid, but database '{}' does not exist.", connection.database))
-                                        }
-                                        Err(_) => Err(e), // Return original error if fallback test fails
-                                    }
-                                }
-                                Err(_) => Err(e), // Return original error if fallback creation fails
-                            }
-                        } else {
-                            Err(e)
-                        }
-                    }
-                }
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, password).await?;
-                ConnectionDriver::test_connection(&driver).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, password)
-                        .await?;
-                ConnectionDriver::test_connection(&driver).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, password).await?;
-                ConnectionDriver::test_connection(&driver).await
-            }
-            "couchbase" => {
-                let driver =
-                    crate::services::couchbase::CouchbaseDriver::new(&connection, password).await?;
-                ConnectionDriver::test_connection(&driver).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, password).await?;
-                ConnectionDriver::test_connection(&driver).await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
+        // Unsaved connections carry their SSH secrets in plain text on the model.
+        let tunnel_id = connection.ssh_enabled.then_some(connection.id);
+        let ssh_password = connection.ssh_password.clone();
+        let ssh_key_passphrase = connection.ssh_key_passphrase.clone();
+        let connection = self
+            .apply_ssh_tunnel(connection, ssh_password, ssh_key_passphrase)
+            .await?;
+
+        let result = self.test_connection_direct(connection, password).await;
+
+        if let Some(id) = tunnel_id {
+            SshTunnelManager::global().close(id).await;
         }
+        result
     }
+
+    async fn test_connection_direct(
+        &self,
+        connection: connection::Model,
+        password: &str,
+    ) -> Result<()> {
+        // Not cached: the connection may not be saved yet.
+        let spec = driver_factory::spec_for(&connection.db_type)?;
+        let err = match self.create_driver(spec, &connection, password).await {
+            Ok(driver) => return ConnectionDriver::test_connection(&*driver).await,
+            Err(e) => e,
+        };
+        tracing::error!("Initial {} connection failed: {}", spec.name, err);
+
+        // If the database does not exist, connect to the maintenance database to verify credentials
+        let Some(maintenance_database) = spec.maintenance_database else {
+            return Err(err);
+        };
+        let err_msg = err.to_string().to_lowercase();
+        if !(err_msg.contains("does not exist")
+            || err_msg.contains("3d000")
+            || (err_msg.contains("database") && err_msg.contains("not exist")))
+        {
+            return Err(err);
+        }
+
+        let mut maintenance = connection.clone();
+        maintenance.database = maintenance_database.to_string();
+        match self.create_driver(spec, &maintenance, password).await {
+            Ok(driver) => match ConnectionDriver::test_connection(&*driver).await {
+                // Credentials are good, only the target database is missing.
+                Ok(_) => Err(anyhow::anyhow!(
+                    "Credentials valid, but database '{}' does not exist.",
+                    connection.database
+                )),
+                Err(_) => Err(err),
+            },
+            Err(_) => Err(err),
+        }
+    }
+
     pub async fn list_sqlite_attachments(
         &self,
         connection_id: Uuid,
@@ -383,6 +408,8 @@ impl ConnectionService {
             .insert(&self.db)
             .await
             .map_err(|e| anyhow::anyhow!(e))?;
+        // Attachments are applied when the pool is created.
+        self.evict_drivers(connection_id);
         Ok(())
     }
 
@@ -394,6 +421,7 @@ impl ConnectionService {
             .exec(&self.db)
             .await
             .map_err(|e| anyhow::anyhow!(e))?;
+        self.evict_drivers(connection_id);
         Ok(())
     }
 }
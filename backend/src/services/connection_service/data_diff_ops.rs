@@ -0,0 +1,470 @@
+use super::export_ops::{end_stream, page_table_data, table_select};
+use super::ConnectionService;
+use crate::services::bulk;
+use crate::services::data_diff::{
+    self, DataDiffReport, DataDiffRequest, DataDiffStrategy, KeyRange, RowMatcher,
+    CHUNK_HASH_MIN_ROWS, DATA_DIFF_CHUNK_SIZE,
+};
+use crate::services::db_driver::{DatabaseDriver, TableColumn};
+use crate::services::driver::capabilities::DriverCapability;
+use crate::services::driver::{QueryDriver, SchemaIntrospection};
+use crate::services::driver_factory::{self, DriverSpec};
+use crate::services::export::TableExport;
+use crate::services::query_stream::{QueryStreamEvent, QueryStreamSink, StreamOptions};
+use crate::services::table_filter::{SortDirection, SortSpec, SqlDialect};
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+/// One of the compared tables, with its columns lined up with the other's.
+struct DiffSide<'a> {
+    driver: &'a dyn DatabaseDriver,
+    spec: &'static DriverSpec,
+    dialect: SqlDialect,
+    /// The MySQL session reads backslashes in strings literally.
+    no_backslash_escapes: bool,
+    schema: &'a str,
+    table: &'a str,
+    /// Compared columns, by their names in this table.
+    fields: Vec<String>,
+    /// Key columns, by their names in this table.
+    keys: Vec<String>,
+}
+
+impl DiffSide<'_> {
+    fn table_ref(&self) -> Result<String> {
+        bulk::table_ref(self.dialect, self.schema, self.table)
+    }
+}
+
+impl ConnectionService {
+    /// Compares the rows of two tables by key, on any SQL engines, and
+    /// generates the statements that make the target match the source.
+    /// Both tables are read in key order and their rows paired as they
+    /// arrive. Large tables on the same engine are compared in chunks of
+    /// keys instead, reading only the rows of chunks whose hashes differ.
+    pub async fn compare_table_data(&self, request: &DataDiffRequest) -> Result<DataDiffReport> {
+        let started = Instant::now();
+        let (source_connection, source_password) = self
+            .get_connection_with_password(request.source.connection_id)
+            .await?;
+        let (target_connection, target_password) = self
+            .get_connection_with_password(request.target.connection_id)
+            .await?;
+        let source_spec = driver_factory::spec_for(&source_connection.db_type)?;
+        let target_spec = driver_factory::spec_for(&target_connection.db_type)?;
+        let sql_dialect = |spec: &DriverSpec| {
+            spec.sql_dialect.ok_or_else(|| {
+                anyhow!(
+                    "Data diffs can only compare SQL databases, not {}",
+                    spec.name
+                )
+            })
+        };
+        let source_dialect = sql_dialect(source_spec)?;
+        let target_dialect = sql_dialect(target_spec)?;
+        let source_driver = self.driver(&source_connection, &source_password).await?;
+        let target_driver = self.driver(&target_connection, &target_password).await?;
+
+        let source_columns = SchemaIntrospection::get_columns(
+            &*source_driver,
+            &request.source.schema,
+            &request.source.table,
+        )
+        .await?;
+        if source_columns.is_empty() {
+            return Err(anyhow!(
+                "Table {} not found in the source",
+                request.source.table
+            ));
+        }
+        let target_columns = SchemaIntrospection::get_columns(
+            &*target_driver,
+            &request.target.schema,
+            &request.target.table,
+        )
+        .await?;
+        if target_columns.is_empty() {
+            return Err(anyhow!(
+                "Table {} not found in the target",
+                request.target.table
+            ));
+        }
+
+        let key_columns: Vec<String> = if request.key_columns.is_empty() {
+            source_columns
+                .iter()
+                .filter(|column| column.is_primary_key)
+                .map(|column| column.name.clone())
+                .collect()
+        } else {
+            request.key_columns.clone()
+        };
+        if key_columns.is_empty() {
+            return Err(anyhow!(
+                "Table {} has no primary key; choose the key columns to compare by",
+                request.source.table
+            ));
+        }
+
+        // Compared columns are matched by name, the keys always among them.
+        let wanted: Vec<&TableColumn> = match &request.columns {
+            Some(names) => names
+                .iter()
+                .chain(&key_columns)
+                .map(|name| {
+                    find_column(&source_columns, name)
+                        .ok_or_else(|| anyhow!("Column {} not found in the source", name))
+                })
+                .collect::<Result<_>>()?,
+            None => source_columns.iter().collect(),
+        };
+        let mut fields = Vec::new();
+        let mut columns = Vec::new();
+        for column in wanted {
+            if fields.contains(&column.name) {
+                continue;
+            }
+            if let Some(other) = find_column(&target_columns, &column.name) {
+                fields.push(column.name.clone());
+                columns.push(other.name.clone());
+            } else if request.columns.is_some() || key_columns.contains(&column.name) {
+                return Err(anyhow!("Column {} not found in the target", column.name));
+            }
+        }
+        let source_keys: Vec<String> = key_columns
+            .iter()
+            .map(|key| {
+                find_column(&source_columns, key)
+                    .map(|column| column.name.clone())
+                    .ok_or_else(|| anyhow!("Key column {} not found in the source", key))
+            })
+            .collect::<Result<_>>()?;
+        let target_keys: Vec<String> = source_keys
+            .iter()
+            .map(|key| {
+                fields
+                    .iter()
+                    .position(|field| field == key)
+                    .map(|i| columns[i].clone())
+                    .ok_or_else(|| anyhow!("Key column {} not found in the target", key))
+            })
+            .collect::<Result<_>>()?;
+
+        let source = DiffSide {
+            driver: &*source_driver,
+            spec: source_spec,
+            dialect: source_dialect,
+            no_backslash_escapes: no_backslash_escapes(&*source_driver, source_spec).await?,
+            schema: &request.source.schema,
+            table: &request.source.table,
+            fields,
+            keys: source_keys,
+        };
+        let target = DiffSide {
+            driver: &*target_driver,
+            spec: target_spec,
+            dialect: target_dialect,
+            no_backslash_escapes: no_backslash_escapes(&*target_driver, target_spec).await?,
+            schema: &request.target.schema,
+            table: &request.target.table,
+            fields: columns.clone(),
+            keys: target_keys.clone(),
+        };
+
+        let chunk_hash = source_spec.name == target_spec.name
+            && data_diff::supports_chunk_hash(source_spec.name);
+        let strategy = match request.strategy {
+            DataDiffStrategy::ChunkHash if !chunk_hash => {
+                return Err(anyhow!(
+                    "Chunk hashes need both tables on Postgres or both on MySQL, not {} and {}",
+                    source_spec.name,
+                    target_spec.name
+                ));
+            }
+            DataDiffStrategy::Auto if chunk_hash => {
+                let count = QueryDriver::query(
+                    source.driver,
+                    &format!("SELECT COUNT(*) FROM {}", source.table_ref()?),
+                )
+                .await?;
+                let rows = count.rows.first().and_then(|row| row.first());
+                if rows.map_or(0, as_count) >= CHUNK_HASH_MIN_ROWS {
+                    DataDiffStrategy::ChunkHash
+                } else {
+                    DataDiffStrategy::Stream
+                }
+            }
+            DataDiffStrategy::Auto => DataDiffStrategy::Stream,
+            strategy => strategy,
+        };
+
+        let mut matcher = RowMatcher::new(columns.clone(), target_keys)?;
+        match strategy {
+            DataDiffStrategy::ChunkHash => {
+                let chunk_size = request.chunk_size.unwrap_or(DATA_DIFF_CHUNK_SIZE);
+                compare_chunks(&source, &target, chunk_size, &mut matcher).await?;
+            }
+            _ => compare_streams(&source, &target, &mut matcher).await?,
+        }
+
+        let mut report = matcher.finish();
+        report.strategy = strategy;
+        report.sync_sql = data_diff::sync_statements(
+            target_dialect,
+            target.no_backslash_escapes,
+            &target.table_ref()?,
+            &columns,
+            &report.rows,
+        )?;
+        report.execution_time_ms = started.elapsed().as_millis() as u64;
+        Ok(report)
+    }
+}
+
+fn find_column<'a>(columns: &'a [TableColumn], name: &str) -> Option<&'a TableColumn> {
+    columns
+        .iter()
+        .find(|column| column.name == name)
+        .or_else(|| {
+            columns
+                .iter()
+                .find(|column| column.name.eq_ignore_ascii_case(name))
+        })
+}
+
+/// Whether a MySQL server runs with `NO_BACKSLASH_ESCAPES` in its
+/// `sql_mode`, so that literals are escaped the way it parses them.
+async fn no_backslash_escapes(driver: &dyn DatabaseDriver, spec: &DriverSpec) -> Result<bool> {
+    if spec.name != "mysql" {
+        return Ok(false);
+    }
+    let result = QueryDriver::query(driver, "SELECT @@sql_mode").await?;
+    Ok(result
+        .rows
+        .first()
+        .and_then(|row| row.first())
+        .and_then(Value::as_str)
+        .is_some_and(|mode| mode.to_uppercase().contains("NO_BACKSLASH_ESCAPES")))
+}
+
+/// `COUNT(*)` as returned by any engine: a number or a numeric string.
+fn as_count(value: &Value) -> u64 {
+    match value {
+        Value::Number(n) => n.as_u64().unwrap_or(0),
+        Value::String(s) => s.parse().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// Reads both tables whole, in key order, and pairs their rows.
+async fn compare_streams(
+    source: &DiffSide<'_>,
+    target: &DiffSide<'_>,
+    matcher: &mut RowMatcher,
+) -> Result<()> {
+    let (source_rx, read_source) = read_ordered(source)?;
+    let (target_rx, read_target) = read_ordered(target)?;
+    let (matched, (), ()) = tokio::join!(
+        match_streams(
+            matcher,
+            (source_rx, source.fields.as_slice()),
+            (target_rx, target.fields.as_slice())
+        ),
+        read_source,
+        read_target
+    );
+    matched
+}
+
+/// The rows of `side` streamed in key order, and the future reading them.
+fn read_ordered<'a>(
+    side: &'a DiffSide<'_>,
+) -> Result<(
+    mpsc::Receiver<QueryStreamEvent>,
+    impl std::future::Future<Output = ()> + 'a,
+)> {
+    let export = TableExport {
+        schema: side.schema.to_string(),
+        table: side.table.to_string(),
+        filter: None,
+        sort: side
+            .keys
+            .iter()
+            .map(|key| SortSpec {
+                column: key.clone(),
+                direction: SortDirection::Asc,
+            })
+            .collect(),
+        fields: Some(side.fields.clone()),
+    };
+    let select = if side.spec.supports(DriverCapability::Streaming) {
+        Some(table_select(&export, side.dialect)?)
+    } else {
+        None
+    };
+    let (mut sink, rx) = QueryStreamSink::channel(None);
+    let reader: &'a dyn DatabaseDriver = side.driver;
+    let reading = async move {
+        let result = match select {
+            Some(sql) => {
+                QueryDriver::stream_query(reader, &sql, &StreamOptions::default(), &mut sink).await
+            }
+            None => page_table_data(reader, export, &mut sink).await,
+        };
+        end_stream(sink, result).await;
+    };
+    Ok((rx, reading))
+}
+
+/// Hands the rows of both streams to `matcher` as they arrive, lined up
+/// with each side's compared columns.
+async fn match_streams(
+    matcher: &mut RowMatcher,
+    source: (mpsc::Receiver<QueryStreamEvent>, &[String]),
+    target: (mpsc::Receiver<QueryStreamEvent>, &[String]),
+) -> Result<()> {
+    let (mut source_rx, source_fields) = source;
+    let (mut target_rx, target_fields) = target;
+    let mut source_positions = Vec::new();
+    let mut target_positions = Vec::new();
+    let (mut source_open, mut target_open) = (true, true);
+    while source_open || target_open {
+        tokio::select! {
+            event = source_rx.recv(), if source_open => {
+                match event {
+                    Some(QueryStreamEvent::Meta { columns, .. }) => {
+                        source_positions = positions(source_fields, &columns);
+                    }
+                    Some(QueryStreamEvent::Rows { rows }) => {
+                        for row in rows {
+                            matcher.add_source(line_up(&source_positions, row))?;
+                        }
+                    }
+                    Some(QueryStreamEvent::Progress { .. }) => {}
+                    Some(QueryStreamEvent::Error { message }) => {
+                        return Err(anyhow!("Reading the source failed: {}", message));
+                    }
+                    Some(QueryStreamEvent::Done { .. }) | None => source_open = false,
+                }
+            }
+            event = target_rx.recv(), if target_open => {
+                match event {
+                    Some(QueryStreamEvent::Meta { columns, .. }) => {
+                        target_positions = positions(target_fields, &columns);
+                    }
+                    Some(QueryStreamEvent::Rows { rows }) => {
+                        for row in rows {
+                            matcher.add_target(line_up(&target_positions, row))?;
+                        }
+                    }
+                    Some(QueryStreamEvent::Progress { .. }) => {}
+                    Some(QueryStreamEvent::Error { message }) => {
+                        return Err(anyhow!("Reading the target failed: {}", message));
+                    }
+                    Some(QueryStreamEvent::Done { .. }) | None => target_open = false,
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn positions(fields: &[String], columns: &[String]) -> Vec<Option<usize>> {
+    fields
+        .iter()
+        .map(|field| columns.iter().position(|column| column == field))
+        .collect()
+}
+
+fn line_up(positions: &[Option<usize>], row: Vec<Value>) -> Vec<Value> {
+    positions
+        .iter()
+        .map(|position| {
+            position
+                .and_then(|i| row.get(i).cloned())
+                .unwrap_or(Value::Null)
+        })
+        .collect()
+}
+
+/// Splits the source's keys into chunks of `chunk_size` rows and compares
+/// each chunk's row count and hash on both sides, reading the rows of a
+/// chunk only when they differ.
+async fn compare_chunks(
+    source: &DiffSide<'_>,
+    target: &DiffSide<'_>,
+    chunk_size: usize,
+    matcher: &mut RowMatcher,
+) -> Result<()> {
+    let source_ref = source.table_ref()?;
+    let target_ref = target.table_ref()?;
+    let bounds = QueryDriver::query(
+        source.driver,
+        &data_diff::chunk_bounds_sql(source.dialect, &source_ref, &source.keys, chunk_size)?,
+    )
+    .await?
+    .rows;
+
+    for i in 0..=bounds.len() {
+        let range = KeyRange {
+            after: i.checked_sub(1).map(|previous| bounds[previous].as_slice()),
+            until: bounds.get(i).map(Vec::as_slice),
+        };
+        let source_hash = chunk_hash(source, &source_ref, range).await?;
+        let target_hash = chunk_hash(target, &target_ref, range).await?;
+        if source_hash.len() == 2
+            && source_hash.len() == target_hash.len()
+            && source_hash
+                .iter()
+                .zip(&target_hash)
+                .all(|(a, b)| data_diff::values_equal(a, b))
+        {
+            matcher.add_unchanged(as_count(&source_hash[0]));
+            continue;
+        }
+
+        for row in chunk_rows(source, &source_ref, range).await? {
+            matcher.add_source(row)?;
+        }
+        for row in chunk_rows(target, &target_ref, range).await? {
+            matcher.add_target(row)?;
+        }
+    }
+    Ok(())
+}
+
+async fn chunk_hash(
+    side: &DiffSide<'_>,
+    table_ref: &str,
+    range: KeyRange<'_>,
+) -> Result<Vec<Value>> {
+    let sql = data_diff::chunk_hash_sql(
+        side.spec.name,
+        side.dialect,
+        side.no_backslash_escapes,
+        table_ref,
+        &side.fields,
+        &side.keys,
+        range,
+    )?;
+    let result = QueryDriver::query(side.driver, &sql).await?;
+    Ok(result.rows.into_iter().next().unwrap_or_default())
+}
+
+async fn chunk_rows(
+    side: &DiffSide<'_>,
+    table_ref: &str,
+    range: KeyRange<'_>,
+) -> Result<Vec<Vec<Value>>> {
+    let sql = data_diff::chunk_rows_sql(
+        side.dialect,
+        side.no_backslash_escapes,
+        table_ref,
+        &side.fields,
+        &side.keys,
+        range,
+    )?;
+    Ok(QueryDriver::query(side.driver, &sql).await?.rows)
+}
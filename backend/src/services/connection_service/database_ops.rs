@@ -1,47 +1,14 @@
 use super::ConnectionService;
+use crate::services::db_driver::DatabaseDriver;
+use crate::services::driver::extension::DatabaseManagementDriver;
 use anyhow::Result;
 use uuid::Uuid;
 
 impl ConnectionService {
     pub async fn get_databases(&self, connection_id: Uuid) -> Result<Vec<String>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::db_driver::DatabaseDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new_for_test(&connection, &password).await?;
-                DatabaseDriver::get_databases(&driver).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                DatabaseDriver::get_databases(&driver).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                DatabaseDriver::get_databases(&driver).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                DatabaseDriver::get_databases(&driver).await
-            }
-            "couchbase" => {
-                let driver =
-                    crate::services::couchbase::CouchbaseDriver::new(&connection, &password)
-                        .await?;
-                DatabaseDriver::get_databases(&driver).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                DatabaseDriver::get_databases(&driver).await
-            }
-            _ => Ok(vec![]),
-        }
+        let driver = self.maintenance_driver(&connection, &password).await?;
+        DatabaseDriver::get_databases(&*driver).await
     }
 
     pub async fn create_database(
@@ -51,87 +18,13 @@ impl ConnectionService {
         options: Option<crate::handlers::database::CreateDatabaseOptions>,
     ) -> Result<()> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new_for_test(&connection, &password).await?;
-                driver.create_database_with_options(name, options).await
-            }
-            "sqlite" => Err(anyhow::anyhow!(
-                "SQLite does not support creating separate databases via this API"
-            )),
-            "couchbase" => {
-                use crate::services::couchbase::CouchbaseDriver;
-                use crate::services::driver::extension::DatabaseManagementDriver;
-                let driver = CouchbaseDriver::new(&connection, &password).await?;
-                driver.create_database(name).await
-            }
-            "mongodb" | "mongo" => {
-                use crate::services::driver::extension::DatabaseManagementDriver;
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                driver.create_database(name).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                use crate::services::driver::extension::DatabaseManagementDriver;
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                driver.create_database(name).await
-            }
-            "clickhouse" => {
-                use crate::services::driver::extension::DatabaseManagementDriver;
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                driver.create_database(name).await
-            }
-            _ => Err(anyhow::anyhow!(
-                "Unsupported database type for create_database"
-            )),
-        }
+        let driver = self.maintenance_driver(&connection, &password).await?;
+        DatabaseManagementDriver::create_database_with_options(&*driver, name, options).await
     }
 
     pub async fn drop_database(&self, connection_id: Uuid, name: &str) -> Result<()> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::driver::extension::DatabaseManagementDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new_for_test(&connection, &password).await?;
-                driver.drop_database(name).await
-            }
-            "sqlite" => Err(anyhow::anyhow!(
-                "SQLite does not support dropping databases via this API"
-            )),
-            "couchbase" => {
-                use crate::services::couchbase::CouchbaseDriver;
-                let driver = CouchbaseDriver::new(&connection, &password).await?;
-                driver.drop_database(name).await
-            }
-            "mongodb" | "mongo" => {
-                use crate::services::driver::extension::DatabaseManagementDriver;
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                driver.drop_database(name).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                driver.drop_database(name).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                driver.drop_database(name).await
-            }
-            _ => Err(anyhow::anyhow!(
-                "Unsupported database type for drop_database"
-            )),
-        }
+        let driver = self.maintenance_driver(&connection, &password).await?;
+        DatabaseManagementDriver::drop_database(&*driver, name).await
     }
 }
@@ -0,0 +1,198 @@
+use super::ConnectionService;
+use crate::models::export_ddl::{DdlObjectSpec, DdlObjectType, DdlScope, ExportDdlOptions};
+use crate::services::db_driver::DatabaseDriver;
+use crate::services::driver::capabilities::DriverCapability;
+use crate::services::driver::{DdlExportDriver, DumpDriver, SchemaIntrospection};
+use crate::services::driver_factory;
+use crate::services::dump::{self, DumpEvent, DumpOptions, DumpSummary};
+use crate::services::table_filter::SqlDialect;
+use anyhow::{anyhow, Result};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Tables and views of one dump, each sorted by name.
+struct DumpObjects {
+    schema: String,
+    tables: Vec<String>,
+    views: Vec<String>,
+}
+
+impl ConnectionService {
+    /// Writes a logical dump of a MySQL or SQLite database to `path`: the
+    /// DDL of its tables, their rows as multi-row `INSERT`s, then their
+    /// indexes and triggers and the views, each after the views it selects
+    /// from. Rows of all tables are read in one snapshot. Routines and events
+    /// are not included.
+    pub async fn dump_database(
+        &self,
+        connection_id: Uuid,
+        path: PathBuf,
+        options: DumpOptions,
+    ) -> Result<DumpSummary> {
+        options.validate()?;
+        let (connection, password) = self.get_connection_with_password(connection_id).await?;
+        let spec = driver_factory::spec_for(&connection.db_type)?;
+        spec.require(DriverCapability::NativeDump)?;
+        let dialect = spec
+            .sql_dialect
+            .ok_or_else(|| anyhow!("{} has no SQL dialect", spec.name))?;
+        let driver = self.driver(&connection, &password).await?;
+
+        let schema = match spec.name {
+            "sqlite" => "main".to_string(),
+            _ => connection.database.clone(),
+        };
+        if schema.trim().is_empty() {
+            return Err(anyhow!("Missing database"));
+        }
+        let objects = dump_objects(&*driver, schema, options.tables.as_deref()).await?;
+
+        let gzip = options.gzip;
+        let (tx, rx) = mpsc::channel(8);
+        let reading = async move {
+            let result = write_dump(&*driver, spec.name, dialect, &objects, &options, &tx).await;
+            drop(tx);
+            result
+        };
+        let (summary, read) = tokio::join!(dump::write_events(rx, path.clone(), gzip), reading);
+        let summary = summary?;
+        if let Err(e) = read {
+            let _ = std::fs::remove_file(&path);
+            return Err(e);
+        }
+        Ok(summary)
+    }
+}
+
+/// The tables and views of `schema`, or those of them named in `selected`.
+async fn dump_objects(
+    driver: &dyn DatabaseDriver,
+    schema: String,
+    selected: Option<&[String]>,
+) -> Result<DumpObjects> {
+    let mut all = SchemaIntrospection::get_tables(driver, &schema).await?;
+    all.sort_by(|a, b| a.name.cmp(&b.name));
+    if let Some(missing) = selected.and_then(|names| {
+        names
+            .iter()
+            .find(|name| !all.iter().any(|t| &t.name == *name))
+    }) {
+        return Err(anyhow!("Table {} not found in {}", missing, schema));
+    }
+
+    let mut tables = Vec::new();
+    let mut views = Vec::new();
+    for info in all {
+        if selected.is_some_and(|names| !names.contains(&info.name)) {
+            continue;
+        }
+        match info.table_type.as_str() {
+            "BASE TABLE" => tables.push(info.name),
+            "VIEW" => views.push(info.name),
+            _ => {}
+        }
+    }
+    Ok(DumpObjects {
+        schema,
+        tables,
+        views,
+    })
+}
+
+async fn write_dump(
+    driver: &dyn DatabaseDriver,
+    engine: &str,
+    dialect: SqlDialect,
+    objects: &DumpObjects,
+    options: &DumpOptions,
+    tx: &mpsc::Sender<DumpEvent>,
+) -> Result<()> {
+    let sql = |text: String| dump::send(tx, DumpEvent::Sql(text));
+    let schema = objects.schema.as_str();
+
+    sql(format!(
+        "-- dbplus dump of {}\n-- {}\n",
+        schema,
+        chrono::Utc::now().to_rfc3339()
+    ))
+    .await?;
+    sql(dump::preamble(engine).to_string()).await?;
+
+    if !options.data_only {
+        for table in &objects.tables {
+            let mut text = format!("\n-- Table {}\n", table);
+            if options.include_drop {
+                text.push_str(&format!(
+                    "DROP TABLE IF EXISTS {};\n",
+                    dialect.quote_ident(table)?
+                ));
+            }
+            text.push_str(
+                object_ddl(driver, DdlObjectType::Table, schema, table)
+                    .await?
+                    .trim(),
+            );
+            sql(text).await?;
+        }
+    }
+
+    if !options.schema_only && !objects.tables.is_empty() {
+        sql("\n-- Data".to_string()).await?;
+        DumpDriver::dump_rows(driver, schema, &objects.tables, tx).await?;
+    }
+
+    if !options.data_only {
+        for table in &objects.tables {
+            let extras = DumpDriver::dump_table_extras(driver, schema, table).await?;
+            if !extras.is_empty() {
+                sql(format!("\n{}", extras.join("\n"))).await?;
+            }
+        }
+        let mut views = Vec::with_capacity(objects.views.len());
+        for view in &objects.views {
+            let ddl = object_ddl(driver, DdlObjectType::View, schema, view).await?;
+            views.push((view.clone(), ddl));
+        }
+        for (view, ddl) in dump::order_views(views) {
+            let mut text = format!("\n-- View {}\n", view);
+            if options.include_drop {
+                text.push_str(&format!(
+                    "DROP VIEW IF EXISTS {};\n",
+                    dialect.quote_ident(&view)?
+                ));
+            }
+            text.push_str(ddl.trim());
+            sql(text).await?;
+        }
+    }
+
+    sql(format!("\n{}", dump::postamble(engine))).await
+}
+
+/// `CREATE` statement of one table or view, from the engine's DDL export.
+async fn object_ddl(
+    driver: &dyn DatabaseDriver,
+    object_type: DdlObjectType,
+    schema: &str,
+    name: &str,
+) -> Result<String> {
+    let options = ExportDdlOptions {
+        scope: DdlScope::Objects,
+        database: None,
+        schemas: None,
+        objects: Some(vec![DdlObjectSpec {
+            object_type,
+            schema: schema.to_string(),
+            name: name.to_string(),
+        }]),
+        include_drop: false,
+        if_exists: false,
+        include_owner_privileges: false,
+        include_comments: false,
+        prefer_pg_dump: false,
+        export_method: Some("driver".into()),
+        pg_dump_path: None,
+    };
+    DdlExportDriver::export_ddl(driver, &options).await
+}
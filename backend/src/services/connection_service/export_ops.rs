@@ -0,0 +1,204 @@
+use super::query_ops::check_unsafe_statements;
+use super::ConnectionService;
+use crate::services::db_driver::DatabaseDriver;
+use crate::services::driver::capabilities::DriverCapability;
+use crate::services::driver::{QueryDriver, TableOperations};
+use crate::services::driver_factory;
+use crate::services::export::{self, ExportOptions, ExportSummary, TableExport, EXPORT_BATCH_SIZE};
+use crate::services::query_params::{self, QueryParams};
+use crate::services::query_stream::{QueryStreamSink, StreamOptions};
+use crate::services::sql_template;
+use crate::services::table_filter::{self, SqlDialect};
+use anyhow::Result;
+use serde_json::Value;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+impl ConnectionService {
+    /// Streams the rows of `query` into a file at `path`. The connection's
+    /// result limits are for what the app displays and do not cap exports;
+    /// its statement timeout still applies.
+    pub async fn export_query(
+        &self,
+        connection_id: Uuid,
+        query: &str,
+        confirmed_unsafe: bool,
+        path: PathBuf,
+        options: ExportOptions,
+    ) -> Result<ExportSummary> {
+        let (connection, password) = self.get_connection_with_password(connection_id).await?;
+        check_unsafe_statements(&connection, query, confirmed_unsafe)?;
+        let dialect = export_dialect(&connection.db_type)?;
+        let driver = self.driver(&connection, &password).await?;
+
+        let (mut sink, rx) = QueryStreamSink::channel(Some(EXPORT_BATCH_SIZE));
+        let reading = async move {
+            let result =
+                QueryDriver::stream_query(&*driver, query, &StreamOptions::default(), &mut sink)
+                    .await;
+            end_stream(sink, result).await;
+        };
+        let (summary, ()) = tokio::join!(export::write_events(rx, path, options, dialect), reading);
+        summary
+    }
+
+    /// Exports a table, with the filter, sort and columns of the data grid.
+    /// Engines that read rows with a cursor run one `SELECT`; the others are
+    /// read page by page through `get_table_data`.
+    pub async fn export_table(
+        &self,
+        connection_id: Uuid,
+        source: TableExport,
+        path: PathBuf,
+        mut options: ExportOptions,
+    ) -> Result<ExportSummary> {
+        let (connection, password) = self.get_connection_with_password(connection_id).await?;
+        let spec = driver_factory::spec_for(&connection.db_type)?;
+        let dialect = export_dialect(&connection.db_type)?;
+        let driver = self.driver(&connection, &password).await?;
+        if options.table_name.is_none() {
+            options.table_name = Some(source.table.clone());
+        }
+
+        let select = match spec.sql_dialect {
+            Some(dialect) if spec.supports(DriverCapability::Streaming) => {
+                Some(table_select(&source, dialect)?)
+            }
+            _ => None,
+        };
+
+        let (mut sink, rx) = QueryStreamSink::channel(Some(EXPORT_BATCH_SIZE));
+        let reading = async move {
+            let result = match select {
+                Some(sql) => {
+                    QueryDriver::stream_query(&*driver, &sql, &StreamOptions::default(), &mut sink)
+                        .await
+                }
+                None => page_table_data(&*driver, source, &mut sink).await,
+            };
+            end_stream(sink, result).await;
+        };
+        let (summary, ()) = tokio::join!(export::write_events(rx, path, options, dialect), reading);
+        summary
+    }
+}
+
+/// Quoting used for `INSERT` output; engines without SQL get Postgres quoting.
+fn export_dialect(db_type: &str) -> Result<SqlDialect> {
+    Ok(driver_factory::spec_for(db_type)?
+        .sql_dialect
+        .unwrap_or(SqlDialect::POSTGRES))
+}
+
+pub(super) async fn end_stream(sink: QueryStreamSink, result: Result<u64>) {
+    match result {
+        Ok(affected_rows) => {
+            sink.done(affected_rows).await;
+        }
+        Err(e) => sink.error(e.to_string()).await,
+    }
+}
+
+/// `SELECT` of the whole table, with the filter values written inline since
+/// streamed queries take no parameters.
+pub(super) fn table_select(source: &TableExport, dialect: SqlDialect) -> Result<String> {
+    let columns = match source.fields.as_deref() {
+        Some(fields) if !fields.is_empty() => fields
+            .iter()
+            .map(|field| dialect.quote_ident(field))
+            .collect::<Result<Vec<_>>>()?
+            .join(", "),
+        _ => "*".to_string(),
+    };
+    let table = if source.schema.is_empty() {
+        dialect.quote_ident(&source.table)?
+    } else {
+        format!(
+            "{}.{}",
+            dialect.quote_ident(&source.schema)?,
+            dialect.quote_ident(&source.table)?
+        )
+    };
+    let where_clause = table_filter::where_clause(source.filter.as_ref(), dialect)?;
+    let where_sql = query_params::inline(
+        &where_clause.sql,
+        &QueryParams::Positional(where_clause.params),
+        dialect,
+        |value| sql_template::literal(value, dialect),
+    )?;
+    let order_by = table_filter::order_by_clause(&source.sort, dialect)?;
+    Ok(format!(
+        "SELECT {} FROM {}{}{}",
+        columns, table, where_sql, order_by
+    ))
+}
+
+/// Reads the table through `get_table_data` one page at a time. Documents
+/// can differ in their fields from page to page, so rows are lined up with
+/// the columns of the first page.
+pub(super) async fn page_table_data(
+    driver: &dyn DatabaseDriver,
+    source: TableExport,
+    sink: &mut QueryStreamSink,
+) -> Result<u64> {
+    let page_size = EXPORT_BATCH_SIZE as i64;
+    let mut columns: Option<Vec<String>> = None;
+    let mut offset = 0;
+    loop {
+        let page = TableOperations::get_table_data(
+            driver,
+            &source.schema,
+            &source.table,
+            page_size,
+            offset,
+            source.filter.clone(),
+            source.sort.clone(),
+            None,
+            source.fields.clone(),
+        )
+        .await?;
+        let read = page.rows.len() as i64;
+
+        if columns.is_none() {
+            if !sink.meta(page.columns.clone(), None, None, None).await {
+                return Ok(0);
+            }
+            columns = Some(page.columns.clone());
+        }
+        let first = columns.as_deref().unwrap_or_default();
+        let rows = if first == page.columns.as_slice() {
+            page.rows
+        } else {
+            realign(first, &page.columns, page.rows)
+        };
+        for row in rows {
+            if !sink.row(row).await {
+                return Ok(0);
+            }
+        }
+
+        if read < page_size {
+            return Ok(0);
+        }
+        offset += page_size;
+    }
+}
+
+fn realign(columns: &[String], page_columns: &[String], rows: Vec<Vec<Value>>) -> Vec<Vec<Value>> {
+    let positions: Vec<Option<usize>> = columns
+        .iter()
+        .map(|column| page_columns.iter().position(|c| c == column))
+        .collect();
+    rows.into_iter()
+        .map(|row| {
+            positions
+                .iter()
+                .map(|&position| {
+                    position
+                        .and_then(|i| row.get(i).cloned())
+                        .unwrap_or(Value::Null)
+                })
+                .collect()
+        })
+        .collect()
+}
@@ -1,4 +1,7 @@
 use super::ConnectionService;
+use crate::services::db_driver::DatabaseDriver;
+use crate::services::driver::capabilities::DriverCapability;
+use crate::services::driver_factory;
 use anyhow::Result;
 use uuid::Uuid;
 
@@ -9,32 +12,11 @@ impl ConnectionService {
         schema: &str,
     ) -> Result<Vec<crate::services::db_driver::FunctionInfo>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::db_driver::DatabaseDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                driver.list_functions(schema).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                driver.list_functions(schema).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                driver.list_functions(schema).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                driver.list_functions(schema).await
-            }
-            _ => Ok(vec![]),
+        if !driver_factory::spec_for(&connection.db_type)?.supports(DriverCapability::Functions) {
+            return Ok(vec![]);
         }
+        let driver = self.driver(&connection, &password).await?;
+        DatabaseDriver::list_functions(&*driver, schema).await
     }
 
     pub async fn get_function_definition(
@@ -44,32 +26,9 @@ impl ConnectionService {
         function_name: &str,
     ) -> Result<crate::services::db_driver::FunctionInfo> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::db_driver::DatabaseDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                driver.get_function_definition(schema, function_name).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                driver.get_function_definition(schema, function_name).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                driver.get_function_definition(schema, function_name).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                driver.get_function_definition(schema, function_name).await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
-        }
+        driver_factory::spec_for(&connection.db_type)?.require(DriverCapability::Functions)?;
+        let driver = self.driver(&connection, &password).await?;
+        DatabaseDriver::get_function_definition(&*driver, schema, function_name).await
     }
 
     pub async fn get_function_permissions(
@@ -79,31 +38,10 @@ impl ConnectionService {
         function_name: &str,
     ) -> Result<Vec<crate::services::db_driver::TableGrant>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::driver::FunctionOperations;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                driver.get_function_permissions(schema, function_name).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                driver.get_function_permissions(schema, function_name).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                driver.get_function_permissions(schema, function_name).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                driver.get_function_permissions(schema, function_name).await
-            }
-            _ => Ok(vec![]),
+        if !driver_factory::spec_for(&connection.db_type)?.supports(DriverCapability::Functions) {
+            return Ok(vec![]);
         }
+        let driver = self.driver(&connection, &password).await?;
+        DatabaseDriver::get_function_permissions(&*driver, schema, function_name).await
     }
 }
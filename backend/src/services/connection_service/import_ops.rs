@@ -0,0 +1,171 @@
+use super::ConnectionService;
+use crate::services::db_driver::{DatabaseDriver, TableColumn};
+use crate::services::driver::capabilities::DriverCapability;
+use crate::services::driver::{BulkOperationsDriver, QueryDriver, SchemaIntrospection};
+use crate::services::driver_factory::{self, DriverSpec};
+use crate::services::import::{
+    self, BatchInserter, ImportOptions, ImportPreview, ImportReport, ImportSettings, ImportTarget,
+};
+use crate::services::table_filter::SqlDialect;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::Arc;
+use uuid::Uuid;
+
+impl ConnectionService {
+    /// Reads the layout and first rows of `path` and suggests which table
+    /// columns its columns go to. The table does not have to exist yet.
+    pub async fn preview_import(
+        &self,
+        connection_id: Uuid,
+        path: PathBuf,
+        target: ImportTarget,
+        options: ImportOptions,
+    ) -> Result<ImportPreview> {
+        let (connection, password) = self.get_connection_with_password(connection_id).await?;
+        let (spec, dialect) = import_spec(&connection.db_type)?;
+        let driver = self.driver(&connection, &password).await?;
+
+        let source = import::inspect(path, options).await?;
+        let targets = table_columns(&*driver, &target).await?;
+        let mappings = import::suggest_mappings(&source.columns, targets.as_deref());
+        let create_table_sql = if targets.is_none() && !mappings.is_empty() {
+            let columns = import::mapped_columns(&source, &mappings, None)?;
+            Some(import::create_table_sql(
+                spec.name, dialect, &target, &columns,
+            )?)
+        } else {
+            None
+        };
+        Ok(ImportPreview {
+            layout: source.layout,
+            columns: source.columns,
+            rows: source.rows,
+            table_exists: targets.is_some(),
+            target_columns: targets.unwrap_or_default(),
+            mappings,
+            create_table_sql,
+        })
+    }
+
+    /// Loads `path` into `target` in batches through the engine's bulk insert
+    /// path and reports the rows that could not be converted or inserted.
+    pub async fn import_file(
+        &self,
+        connection_id: Uuid,
+        path: PathBuf,
+        target: ImportTarget,
+        settings: ImportSettings,
+    ) -> Result<ImportReport> {
+        let (connection, password) = self.get_connection_with_password(connection_id).await?;
+        let (spec, dialect) = import_spec(&connection.db_type)?;
+        let driver = self.driver(&connection, &password).await?;
+
+        let source = import::inspect(path.clone(), settings.options.clone()).await?;
+        let mut targets = table_columns(&*driver, &target).await?;
+        let mappings = settings
+            .mappings
+            .clone()
+            .unwrap_or_else(|| import::suggest_mappings(&source.columns, targets.as_deref()));
+
+        let mut create_table_sql = None;
+        if targets.is_none() {
+            if !settings.create_table {
+                return Err(anyhow!("Table {} does not exist", target.table));
+            }
+            let columns = import::mapped_columns(&source, &mappings, None)?;
+            let sql = import::create_table_sql(spec.name, dialect, &target, &columns)?;
+            if !settings.dry_run {
+                QueryDriver::execute(&*driver, &sql).await?;
+                if let Some(cache) = &self.schema_cache {
+                    let _ = cache
+                        .invalidate_schema(connection_id, &connection.database, &target.schema)
+                        .await;
+                }
+                // Values are converted for the types the engine settled on.
+                targets = table_columns(&*driver, &target).await?;
+            }
+            create_table_sql = Some(sql);
+        }
+
+        let columns = import::mapped_columns(&source, &mappings, targets.as_deref())?;
+        let atomic = if settings.dry_run {
+            true
+        } else {
+            BulkOperationsDriver::bulk_insert_is_atomic(&*driver, &target.schema, &target.table)
+                .await?
+        };
+        let mut inserter = BulkInserter {
+            driver,
+            schema: target.schema.clone(),
+            table: target.table.clone(),
+            columns: columns.iter().map(|column| column.target.clone()).collect(),
+            atomic,
+        };
+        let inserter: Option<&mut dyn BatchInserter> = if settings.dry_run {
+            None
+        } else {
+            Some(&mut inserter)
+        };
+        let mut report = import::load(path, source.layout, &settings, &columns, inserter).await?;
+        report.created_table = create_table_sql.is_some() && !settings.dry_run;
+        report.create_table_sql = create_table_sql;
+        Ok(report)
+    }
+}
+
+fn import_spec(db_type: &str) -> Result<(&'static DriverSpec, SqlDialect)> {
+    let spec = driver_factory::spec_for(db_type)?;
+    spec.require(DriverCapability::BulkOperations)?;
+    let dialect = spec.sql_dialect.ok_or_else(|| {
+        anyhow!(
+            "Importing files is not supported for {} connections",
+            spec.name
+        )
+    })?;
+    Ok((spec, dialect))
+}
+
+/// The columns of `target`, or `None` when there is no such table.
+async fn table_columns(
+    driver: &dyn DatabaseDriver,
+    target: &ImportTarget,
+) -> Result<Option<Vec<TableColumn>>> {
+    let columns = SchemaIntrospection::get_columns(driver, &target.schema, &target.table).await?;
+    Ok(if columns.is_empty() {
+        None
+    } else {
+        Some(columns)
+    })
+}
+
+/// Hands each batch to the engine's bulk insert path.
+struct BulkInserter {
+    driver: Arc<dyn DatabaseDriver>,
+    schema: String,
+    table: String,
+    columns: Vec<String>,
+    atomic: bool,
+}
+
+#[async_trait]
+impl BatchInserter for BulkInserter {
+    async fn insert(&mut self, rows: &[Vec<Value>]) -> Result<()> {
+        BulkOperationsDriver::bulk_insert(
+            &*self.driver,
+            &self.schema,
+            &self.table,
+            &self.columns,
+            rows,
+            &|_| {},
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn atomic(&self) -> bool {
+        self.atomic
+    }
+}
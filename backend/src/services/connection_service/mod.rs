@@ -1,18 +1,33 @@
 // Connection operations module
+mod bulk_ops;
 mod connection_ops;
+mod data_diff_ops;
 mod database_ops;
+mod dump_ops;
+mod export_ops;
 mod function_ops;
+mod import_ops;
 mod query_ops;
 mod schema_diff_ops;
 mod schema_ops;
 mod session_ops;
 mod table_ops;
+mod transaction_ops;
+mod transfer_ops;
 mod view_ops;
 
+pub use query_ops::QueryOptions;
+
 use crate::models::entities::connection;
 use crate::models::entities::sqlite_attached_db;
 use crate::services::credential_service::CredentialService;
+use crate::services::db_driver::DatabaseDriver;
+use crate::services::driver_factory::{self, DriverContext, DriverSpec};
+use crate::services::driver_registry::DriverRegistry;
 use crate::services::encryption_service::EncryptionService;
+use crate::services::query_cancel::QueryHandle;
+use crate::services::ssh_tunnel::{SshTunnelConfig, SshTunnelManager};
+use crate::services::transaction_manager::TransactionManager;
 use anyhow::Result;
 
 use sea_orm::*;
@@ -28,6 +43,9 @@ pub struct ConnectionService {
     credentials: CredentialService,
     database_override: Option<String>,
     schema_cache: Option<Arc<SchemaCacheService>>,
+    drivers: Option<Arc<DriverRegistry>>,
+    query_handle: Option<Arc<QueryHandle>>,
+    transactions: Option<Arc<TransactionManager>>,
 }
 
 impl ConnectionService {
@@ -39,6 +57,9 @@ impl ConnectionService {
             credentials: CredentialService::new(),
             database_override: None,
             schema_cache: None,
+            drivers: None,
+            query_handle: None,
+            transactions: None,
         })
     }
 
@@ -47,6 +68,25 @@ impl ConnectionService {
         self
     }
 
+    /// Reuses drivers (and their pools) from the shared registry instead of
+    /// building a new one per call.
+    pub fn with_driver_registry(mut self, drivers: Arc<DriverRegistry>) -> Self {
+        self.drivers = Some(drivers);
+        self
+    }
+
+    /// Lets `cancel_query` stop the statement on the server, not just stop waiting for it.
+    pub fn with_query_handle(mut self, handle: Option<Arc<QueryHandle>>) -> Self {
+        self.query_handle = handle;
+        self
+    }
+
+    /// Where transactions started through this service are kept between calls.
+    pub fn with_transaction_manager(mut self, transactions: Arc<TransactionManager>) -> Self {
+        self.transactions = Some(transactions);
+        self
+    }
+
     pub fn with_database_override(mut self, database: Option<String>) -> Self {
         self.database_override = database;
         self
@@ -59,6 +99,64 @@ impl ConnectionService {
         connection
     }
 
+    /// Reads an SSH secret from the keychain, falling back to the encrypted column.
+    fn stored_ssh_secret(
+        &self,
+        connection: &connection::Model,
+        field: &str,
+        encrypted: Option<&String>,
+    ) -> Option<String> {
+        if let Ok(Some(secret)) = self.credentials.get_password(&connection.id, field) {
+            return Some(secret);
+        }
+        encrypted
+            .filter(|s| !s.is_empty())
+            .and_then(|s| match self.encryption.decrypt(s) {
+                Ok(secret) => Some(secret),
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to decrypt {} for connection {}: {}",
+                        field,
+                        connection.id,
+                        e
+                    );
+                    None
+                }
+            })
+    }
+
+    /// Routes the connection through a shared SSH port-forward when `ssh_enabled` is set.
+    /// The returned model points at the local end of the tunnel and has `ssh_enabled`
+    /// cleared, so it can be handed to any driver constructor as-is.
+    async fn apply_ssh_tunnel(
+        &self,
+        mut connection: connection::Model,
+        ssh_password: Option<String>,
+        ssh_key_passphrase: Option<String>,
+    ) -> Result<connection::Model> {
+        if !connection.ssh_enabled || connection.db_type == "sqlite" {
+            return Ok(connection);
+        }
+
+        // Connecting directly would go around the bastion the user configured.
+        if connection.host.contains("://") {
+            return Err(anyhow::anyhow!(
+                "SSH tunneling is not supported for connection-string hosts; \
+                 enter the database host and port instead"
+            ));
+        }
+
+        let config = SshTunnelConfig::from_model(&connection, ssh_password, ssh_key_passphrase)?;
+        let local_port = SshTunnelManager::global()
+            .get_or_open(connection.id, &config)
+            .await?;
+
+        connection.host = "127.0.0.1".to_string();
+        connection.port = local_port as i32;
+        connection.ssh_enabled = false;
+        Ok(connection)
+    }
+
     async fn load_sqlite_attachments(
         &self,
         connection_id: Uuid,
@@ -78,17 +176,69 @@ impl ConnectionService {
             .collect())
     }
 
-    async fn sqlite_driver(
+    /// Drops cached drivers of a connection so the next call reconnects with fresh settings.
+    fn evict_drivers(&self, connection_id: Uuid) {
+        if let Some(drivers) = &self.drivers {
+            drivers.evict(connection_id);
+        }
+    }
+
+    /// Driver for `connection`, which must already have the database override and
+    /// SSH tunnel applied. Shared through the registry when one is configured.
+    async fn driver(
+        &self,
+        connection: &connection::Model,
+        password: &str,
+    ) -> Result<Arc<dyn DatabaseDriver>> {
+        let spec = driver_factory::spec_for(&connection.db_type)?;
+        match &self.drivers {
+            Some(drivers) => {
+                drivers
+                    .get_or_create(connection, password, || {
+                        self.create_driver(spec, connection, password)
+                    })
+                    .await
+            }
+            None => Ok(Arc::from(
+                self.create_driver(spec, connection, password).await?,
+            )),
+        }
+    }
+
+    /// Driver bound to the engine's maintenance database (`postgres` for Postgres),
+    /// for operations that cannot run inside the target database.
+    async fn maintenance_driver(
+        &self,
+        connection: &connection::Model,
+        password: &str,
+    ) -> Result<Arc<dyn DatabaseDriver>> {
+        match driver_factory::spec_for(&connection.db_type)?.maintenance_database {
+            Some(database) => {
+                let mut maintenance = connection.clone();
+                maintenance.database = database.to_string();
+                self.driver(&maintenance, password).await
+            }
+            None => self.driver(connection, password).await,
+        }
+    }
+
+    /// Builds a new driver, bypassing the registry.
+    async fn create_driver(
         &self,
+        spec: &DriverSpec,
         connection: &connection::Model,
         password: &str,
-    ) -> Result<crate::services::sqlite::SQLiteDriver> {
-        let attachments = self.load_sqlite_attachments(connection.id).await?;
-        crate::services::sqlite::SQLiteDriver::new_with_attachments(
+    ) -> Result<Box<dyn DatabaseDriver>> {
+        let sqlite_attachments = if spec.uses_sqlite_attachments {
+            self.load_sqlite_attachments(connection.id).await?
+        } else {
+            Vec::new()
+        };
+        spec.create(DriverContext {
             connection,
             password,
-            attachments,
-        )
+            sqlite_attachments,
+        })
         .await
     }
 }
@@ -1,236 +1,297 @@
 use super::ConnectionService;
-use crate::services::driver::QueryDriver;
+use crate::models::entities::connection;
+use crate::services::db_driver::DatabaseDriver;
+use crate::services::driver::capabilities::DriverCapability;
+use crate::services::driver::{PreparedStatementDriver, QueryDriver, SchemaIntrospection};
+use crate::services::driver_factory;
+use crate::services::query_limits::QueryLimits;
+use crate::services::query_paging;
+use crate::services::query_params::QueryParams;
+use crate::services::query_stream::{QueryStreamSink, StreamOptions};
+use crate::services::saved_query_service::SavedQueryService;
+use crate::services::script_execution::{self, ErrorPolicy, ScriptResult};
+use crate::services::sql_template::RenderMode;
 use anyhow::Result;
+use serde_json::{Map, Value};
 use sqlparser::ast::Statement;
-use sqlparser::dialect::{Dialect, GenericDialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect};
+use sqlparser::dialect::{Dialect, GenericDialect};
 use sqlparser::parser::Parser;
 use uuid::Uuid;
 
+/// How a query is run by `execute_query_with_options` and `run_saved_query`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueryOptions<'a> {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub include_total_count: bool,
+    /// The user confirmed running statements `check_unsafe_statements` flags.
+    pub confirmed_unsafe: bool,
+    /// Fill the query's `$1`/`?` or `:name` placeholders.
+    pub params: Option<&'a QueryParams>,
+}
+
 impl ConnectionService {
     pub async fn execute_query(
         &self,
         connection_id: Uuid,
         query: &str,
     ) -> Result<crate::services::db_driver::QueryResult> {
-        self.execute_query_with_options(connection_id, query, None, None, false, false)
+        self.execute_query_with_options(connection_id, query, QueryOptions::default())
             .await
     }
 
     pub async fn execute_script(&self, connection_id: Uuid, script: &str) -> Result<u64> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::driver::QueryDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                QueryDriver::execute(&driver, script).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                QueryDriver::execute_script(&driver, script).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                QueryDriver::execute_script(&driver, script).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                QueryDriver::execute_script(&driver, script).await
-            }
-            "couchbase" => {
-                let driver =
-                    crate::services::couchbase::CouchbaseDriver::new(&connection, &password)
-                        .await?;
-                QueryDriver::execute_script(&driver, script).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                QueryDriver::execute_script(&driver, script).await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        QueryDriver::execute_script(&*driver, script).await
     }
 
+    /// `options.params` are bound by the driver, also in the paged and count
+    /// queries built from the query. Results are cut to the connection's
+    /// `max_result_rows` and `max_result_bytes`.
     pub async fn execute_query_with_options(
         &self,
         connection_id: Uuid,
         query: &str,
-        limit: Option<i64>,
-        offset: Option<i64>,
-        include_total_count: bool,
-        confirmed_unsafe: bool,
+        options: QueryOptions<'_>,
     ) -> Result<crate::services::db_driver::QueryResult> {
+        let QueryOptions {
+            limit,
+            offset,
+            include_total_count,
+            confirmed_unsafe,
+            params,
+        } = options;
         let connection = self
             .get_connection_by_id(connection_id)
             .await?
             .ok_or(anyhow::anyhow!("Connection not found"))?;
 
-        // Guardrails Logic
-        let is_prod = connection.environment.to_lowercase() == "production";
-        let safe_level = if is_prod && connection.safe_mode_level == 0 {
-            1 // Default to warning for prod if off
-        } else {
-            connection.safe_mode_level
-        };
-
-        if safe_level > 0 && !confirmed_unsafe {
-            let dialect: Box<dyn Dialect> = match connection.db_type.as_str() {
-                "postgres" | "cockroachdb" | "cockroach" => Box::new(PostgreSqlDialect {}),
-                "sqlite" => Box::new(SQLiteDialect {}),
-                "mysql" | "mariadb" | "tidb" => Box::new(MySqlDialect {}),
-                "clickhouse" => Box::new(GenericDialect {}), // Generic for now
-                "couchbase" => Box::new(GenericDialect {}),
-                _ => Box::new(GenericDialect {}),
-            };
-
-            // Non-blocking parse error (if we can't parse, we let DB handle it, or we could be strict)
-            if let Ok(statements) = Parser::parse_sql(&*dialect, query) {
-                for stmt in statements {
-                    let mut warning: Option<String> = None;
-                    match stmt {
-                        Statement::Delete { selection, .. } => {
-                            if selection.is_none() {
-                                warning = Some("DELETE without WHERE clause".to_string());
-                            }
-                        }
-                        Statement::Update { selection, .. } => {
-                            if selection.is_none() {
-                                warning = Some("UPDATE without WHERE clause".to_string());
-                            }
-                        }
-                        Statement::Drop { object_type, .. } => match object_type {
-                            sqlparser::ast::ObjectType::Table => {
-                                warning = Some("DROP TABLE detected".to_string())
-                            }
-                            _ => {}
-                        },
-                        Statement::Truncate { .. } => {
-                            warning = Some("TRUNCATE TABLE detected".to_string());
-                        }
-                        _ => {}
-                    }
-
-                    if let Some(msg) = warning {
-                        if safe_level == 2 {
-                            return Err(anyhow::anyhow!(
-                                "UNSAFE_OPERATION_BLOCKED: {} (Strict Mode)",
-                                msg
-                            ));
-                        } else {
-                            return Err(anyhow::anyhow!("UNSAFE_CONFIRMATION_REQUIRED: {}", msg));
-                        }
-                    }
-                }
-            }
-        }
+        check_unsafe_statements(&connection, query, confirmed_unsafe)?;
 
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
+        let driver = self.driver(&connection, &password).await?;
+        let limits = QueryLimits::from_connection(&connection);
 
-        use crate::services::db_driver::DatabaseDriver;
-        use crate::services::postgres_driver::PostgresDriver;
+        // Plain SELECTs are paged and counted by the database; anything else
+        // is run as-is and sliced below.
+        let paged = match (limit, offset) {
+            (Some(limit_val), Some(offset_val)) => {
+                query_paging::pageable_query(&connection.db_type, query).map(|pageable| {
+                    (
+                        pageable,
+                        limits.page_limit(limit_val.max(0)),
+                        offset_val.max(0),
+                    )
+                })
+            }
+            _ => None,
+        };
+        // Unpaged SELECTs still get a LIMIT, one past the row cap so that
+        // cutting them off is noticed.
+        let capped = match (&paged, limits.row_cap()) {
+            (None, Some(cap)) => query_paging::pageable_query(&connection.db_type, query)
+                .map(|pageable| (pageable, cap as i64 + 1)),
+            _ => None,
+        };
 
         let start_time = std::time::Instant::now();
 
-        let mut result = match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                DatabaseDriver::execute_query(&driver, query).await?
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                DatabaseDriver::execute_query(&driver, query).await?
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                DatabaseDriver::execute_query(&driver, query).await?
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                DatabaseDriver::execute_query(&driver, query).await?
-            }
-            "couchbase" => {
-                let driver =
-                    crate::services::couchbase::CouchbaseDriver::new(&connection, &password)
-                        .await?;
-                DatabaseDriver::execute_query(&driver, query).await?
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                DatabaseDriver::execute_query(&driver, query).await?
-            }
-            _ => return Err(anyhow::anyhow!("Unsupported database type")),
+        let driver = &*driver;
+        let run =
+            |sql: String| async move { limits.enforce(self.run_query(driver, &sql, params)).await };
+        // Queries whose page the database cannot read are paged below.
+        let (mut result, paged) = match (paged, capped) {
+            // One extra row tells whether there is a next page.
+            (Some((pageable, limit_val, offset_val)), _) => {
+                let (result, by_database) =
+                    pageable.run_page(limit_val + 1, offset_val, run).await?;
+                (
+                    result,
+                    by_database.then_some((pageable, limit_val, offset_val)),
+                )
+            }
+            (None, Some((pageable, cap))) => (pageable.run_page(cap, 0, run).await?.0, None),
+            (None, None) => (run(query.to_string()).await?, None),
         };
 
         let duration = start_time.elapsed();
         result.execution_time_ms = Some(duration.as_millis() as u64);
 
-        // Apply pagination if specified
-        if let (Some(limit_val), Some(offset_val)) = (limit, offset) {
-            let total_rows = result.rows.len();
-            let start = offset_val.min(total_rows as i64) as usize;
-            let end = (offset_val + limit_val).min(total_rows as i64) as usize;
+        match paged {
+            Some((pageable, limit_val, offset_val)) => {
+                let has_more =
+                    result.rows.len() as i64 > limit_val || result.truncated == Some(true);
+                result.rows.truncate(limit_val as usize);
+                result.limit = Some(limit_val);
+                result.offset = Some(offset_val);
+                result.has_more = Some(has_more);
+                if has_more && limit.is_some_and(|requested| requested > limit_val) {
+                    result.truncated = Some(true);
+                }
 
-            result.rows = result.rows[start..end].to_vec();
-            result.limit = Some(limit_val);
-            result.offset = Some(offset_val);
-            result.has_more = Some(end < total_rows);
+                if include_total_count {
+                    result.total_count = pageable
+                        .run_count(|sql| async move { self.run_query(driver, &sql, params).await })
+                        .await;
+                }
+            }
+            None => {
+                // Apply pagination if specified
+                if let (Some(limit_val), Some(offset_val)) = (limit, offset) {
+                    let total_rows = result.rows.len();
+                    let start = offset_val.min(total_rows as i64) as usize;
+                    let end = (offset_val + limit_val).min(total_rows as i64) as usize;
+
+                    result.rows = result.rows[start..end].to_vec();
+                    result.limit = Some(limit_val);
+                    result.offset = Some(offset_val);
+                    result.has_more = Some(end < total_rows);
 
-            if include_total_count {
-                result.total_count = Some(total_rows as i64);
+                    if include_total_count {
+                        result.total_count = Some(total_rows as i64);
+                    }
+                }
             }
         }
 
+        limits.apply(&mut result);
         Ok(result)
     }
 
-    pub async fn execute(&self, connection_id: Uuid, query: &str) -> Result<u64> {
-        let (connection, password) = self.get_connection_with_password(connection_id).await?;
+    /// Runs a saved query with its `{{var}}` placeholders filled from
+    /// `variables`: bound as parameters where the engine takes them, written
+    /// in as literals otherwise. They replace `options.params`.
+    pub async fn run_saved_query(
+        &self,
+        connection_id: Uuid,
+        query_id: Uuid,
+        variables: &Map<String, Value>,
+        options: QueryOptions<'_>,
+    ) -> Result<crate::services::db_driver::QueryResult> {
+        let connection = self
+            .get_connection_by_id(connection_id)
+            .await?
+            .ok_or(anyhow::anyhow!("Connection not found"))?;
+        let spec = driver_factory::spec_for(&connection.db_type)?;
+        let dialect = spec.sql_dialect.ok_or_else(|| {
+            anyhow::anyhow!("Saved queries cannot be run on {} connections", spec.name)
+        })?;
+        let mode = if spec.supports(DriverCapability::PreparedStatements) {
+            RenderMode::Bind
+        } else {
+            RenderMode::Inline
+        };
 
-        use crate::services::postgres_driver::PostgresDriver;
+        let rendered = SavedQueryService::new(self.db.clone())
+            .render_saved_query(query_id, variables, dialect, mode)
+            .await?;
+        let params = QueryParams::Positional(rendered.params);
+        let options = QueryOptions {
+            params: Some(&params),
+            ..options
+        };
+        self.execute_query_with_options(connection_id, &rendered.sql, options)
+            .await
+    }
 
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                QueryDriver::execute(&driver, query).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                QueryDriver::execute(&driver, query).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                QueryDriver::execute(&driver, query).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                QueryDriver::execute(&driver, query).await
+    /// Runs through the cancellable path when a query handle is set, so a
+    /// cancel also stops the statement on the server.
+    async fn run_query(
+        &self,
+        driver: &dyn DatabaseDriver,
+        query: &str,
+        params: Option<&QueryParams>,
+    ) -> Result<crate::services::db_driver::QueryResult> {
+        if let Some(params) = params.filter(|params| !params.is_empty()) {
+            return match &self.query_handle {
+                Some(handle) => {
+                    PreparedStatementDriver::execute_with_params_cancellable(
+                        driver, query, params, handle,
+                    )
+                    .await
+                }
+                None => PreparedStatementDriver::execute_with_params(driver, query, params).await,
+            };
+        }
+        match &self.query_handle {
+            Some(handle) => QueryDriver::execute_query_cancellable(driver, query, handle).await,
+            None => DatabaseDriver::execute_query(driver, query).await,
+        }
+    }
+
+    /// Streams the rows of `query` to `sink` in batches, ending with a `done`
+    /// or `error` event.
+    pub async fn stream_query(
+        &self,
+        connection_id: Uuid,
+        query: &str,
+        options: &StreamOptions,
+        confirmed_unsafe: bool,
+        mut sink: QueryStreamSink,
+    ) {
+        let result = async {
+            let (connection, password) = self.get_connection_with_password(connection_id).await?;
+            check_unsafe_statements(&connection, query, confirmed_unsafe)?;
+            sink.set_limits(&QueryLimits::from_connection(&connection));
+            let driver = self.driver(&connection, &password).await?;
+            match &self.query_handle {
+                Some(handle) => {
+                    QueryDriver::stream_query_cancellable(
+                        &*driver, query, options, &mut sink, handle,
+                    )
+                    .await
+                }
+                None => QueryDriver::stream_query(&*driver, query, options, &mut sink).await,
             }
-            "couchbase" => {
-                let driver =
-                    crate::services::couchbase::CouchbaseDriver::new(&connection, &password)
-                        .await?;
-                QueryDriver::execute(&driver, query).await
+        }
+        .await;
+
+        match result {
+            Ok(affected_rows) => {
+                sink.done(affected_rows).await;
             }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                QueryDriver::execute(&driver, query).await
+            Err(e) => sink.error(e.to_string()).await,
+        }
+    }
+
+    /// Runs the statements of `script` one by one on a connection of their
+    /// own and returns a result per statement. Statement errors are reported
+    /// in the result rather than returned. A query handle, if set, can stop
+    /// the running statement on the server.
+    pub async fn execute_statements(
+        &self,
+        connection_id: Uuid,
+        script: &str,
+        policy: ErrorPolicy,
+        confirmed_unsafe: bool,
+    ) -> Result<ScriptResult> {
+        let (connection, password) = self.get_connection_with_password(connection_id).await?;
+        check_unsafe_statements(&connection, script, confirmed_unsafe)?;
+        let driver = self.driver(&connection, &password).await?;
+        let limits = QueryLimits::from_connection(&connection);
+        let mut session = QueryDriver::open_session(&*driver).await?;
+        let mut result = limits
+            .enforce(script_execution::run_script(
+                &mut *session,
+                script,
+                policy,
+                self.query_handle.as_deref(),
+            ))
+            .await;
+
+        for statement in &mut result.statements {
+            if let Some(statement_result) = &mut statement.result {
+                limits.apply(statement_result);
             }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
         }
+        Ok(result)
+    }
+
+    pub async fn execute(&self, connection_id: Uuid, query: &str) -> Result<u64> {
+        let (connection, password) = self.get_connection_with_password(connection_id).await?;
+        let driver = self.driver(&connection, &password).await?;
+        QueryDriver::execute(&*driver, query).await
     }
 
     pub async fn explain_query(
@@ -240,42 +301,8 @@ impl ConnectionService {
         analyze: bool,
     ) -> Result<serde_json::Value> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                QueryDriver::explain(&driver, query, analyze).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                QueryDriver::explain(&driver, query, analyze).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                QueryDriver::explain(&driver, query, analyze).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                QueryDriver::explain(&driver, query, analyze).await
-            }
-            "couchbase" => {
-                let driver =
-                    crate::services::couchbase::CouchbaseDriver::new(&connection, &password)
-                        .await?;
-                QueryDriver::explain(&driver, query, analyze).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                QueryDriver::explain(&driver, query, analyze).await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        QueryDriver::explain(&*driver, query, analyze).await
     }
 
     pub async fn search_objects(
@@ -284,42 +311,70 @@ impl ConnectionService {
         query: &str,
     ) -> Result<Vec<crate::services::db_driver::SearchResult>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
+        let driver = self.driver(&connection, &password).await?;
+        SchemaIntrospection::search_objects(&*driver, query).await
+    }
+}
 
-        use crate::services::driver::SchemaIntrospection;
-        use crate::services::postgres_driver::PostgresDriver;
+/// Safe-mode guardrails: blocks or asks to confirm destructive statements on
+/// connections with `safe_mode_level` set (production defaults to a warning).
+pub(super) fn check_unsafe_statements(
+    connection: &connection::Model,
+    query: &str,
+    confirmed_unsafe: bool,
+) -> Result<()> {
+    // Guardrails Logic
+    let is_prod = connection.environment.to_lowercase() == "production";
+    let safe_level = if is_prod && connection.safe_mode_level == 0 {
+        1 // Default to warning for prod if off
+    } else {
+        connection.safe_mode_level
+    };
 
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                driver.search_objects(query).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                driver.search_objects(query).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                driver.search_objects(query).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                driver.search_objects(query).await
-            }
-            "couchbase" => {
-                let driver =
-                    crate::services::couchbase::CouchbaseDriver::new(&connection, &password)
-                        .await?;
-                driver.search_objects(query).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                crate::services::driver::SchemaIntrospection::search_objects(&driver, query).await
+    if safe_level > 0 && !confirmed_unsafe {
+        let dialect: Box<dyn Dialect> = query_paging::sql_dialect(&connection.db_type)
+            .unwrap_or_else(|| Box::new(GenericDialect {}));
+
+        // Non-blocking parse error (if we can't parse, we let DB handle it, or we could be strict)
+        if let Ok(statements) = Parser::parse_sql(&*dialect, query) {
+            for stmt in statements {
+                let mut warning: Option<String> = None;
+                match stmt {
+                    Statement::Delete { selection, .. } => {
+                        if selection.is_none() {
+                            warning = Some("DELETE without WHERE clause".to_string());
+                        }
+                    }
+                    Statement::Update { selection, .. } => {
+                        if selection.is_none() {
+                            warning = Some("UPDATE without WHERE clause".to_string());
+                        }
+                    }
+                    Statement::Drop { object_type, .. } => match object_type {
+                        sqlparser::ast::ObjectType::Table => {
+                            warning = Some("DROP TABLE detected".to_string())
+                        }
+                        _ => {}
+                    },
+                    Statement::Truncate { .. } => {
+                        warning = Some("TRUNCATE TABLE detected".to_string());
+                    }
+                    _ => {}
+                }
+
+                if let Some(msg) = warning {
+                    if safe_level == 2 {
+                        return Err(anyhow::anyhow!(
+                            "UNSAFE_OPERATION_BLOCKED: {} (Strict Mode)",
+                            msg
+                        ));
+                    } else {
+                        return Err(anyhow::anyhow!("UNSAFE_CONFIRMATION_REQUIRED: {}", msg));
+                    }
+                }
             }
-            _ => Ok(vec![]),
         }
     }
+
+    Ok(())
 }
@@ -1,105 +1,123 @@
 use super::ConnectionService;
+use crate::services::driver::DdlExportDriver;
+use crate::services::driver_factory;
 use crate::services::schema_diff::{
     differ::SchemaDiffResult,
     generator::{DatabaseType, MigrationOptions},
-    postgres_extractor::PostgresSchemaExtractor,
-    MigrationGenerator, SchemaDiffer,
+    MigrationGenerator, RenameDecisions, SchemaDiffer, SchemaSnapshot,
 };
 use anyhow::Result;
-use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
-use std::time::Duration;
-use tokio_postgres::{Config, NoTls};
 use uuid::Uuid;
 
 impl ConnectionService {
-    async fn get_postgres_pool(&self, connection_id: Uuid) -> Result<Pool> {
+    /// Snapshot of one schema (a database, for MySQL) and the dialect its
+    /// migrations are written in.
+    async fn extract_schema_snapshot(
+        &self,
+        connection_id: Uuid,
+        schema: &str,
+    ) -> Result<(SchemaSnapshot, DatabaseType)> {
         let (conn, password) = self.get_connection_with_password(connection_id).await?;
 
-        if conn.db_type != "postgres"
-            && conn.db_type != "cockroachdb"
-            && conn.db_type != "cockroach"
-        {
-            return Err(anyhow::anyhow!(
-                "Schema comparison currently only supported for PostgreSQL"
-            ));
-        }
-
-        let mut config = Config::new();
-        config.host(&conn.host);
-        config.port(conn.port as u16);
-        config.user(&conn.username);
-        config.password(password);
-        if !conn.database.is_empty() {
-            config.dbname(&conn.database);
-        }
-
-        // Basic SSL handling stub - assuming NoTls for simplicity in this implementation phase
-        // TODO: Implement proper SSL support matching sqlx implementation
-
-        let mgr_config = ManagerConfig {
-            recycling_method: RecyclingMethod::Fast,
+        let database_type = match driver_factory::spec_for(&conn.db_type)?.name {
+            "postgres" => DatabaseType::PostgreSQL,
+            "mysql" => DatabaseType::MySQL,
+            "sqlite" => DatabaseType::SQLite,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Schema comparison is only supported for PostgreSQL, MySQL and SQLite"
+                ))
+            }
         };
-        let mgr = Manager::from_config(config, NoTls, mgr_config);
-
-        // 🔥 OPTIMIZED: Increased from 4 to 8 for schema operations
-        let pool = Pool::builder(mgr)
-            .max_size(8)
-            .wait_timeout(Some(Duration::from_secs(5)))
-            .build()?;
-
-        Ok(pool)
+        let driver = self.driver(&conn, &password).await?;
+        let snapshot = DdlExportDriver::schema_snapshot(&*driver, schema).await?;
+        Ok((snapshot, database_type))
     }
 
-    pub async fn compare_schemas(
+    /// Snapshots of both schemas, which must be of the same database type.
+    async fn schema_snapshots(
         &self,
         source_connection_id: Uuid,
         target_connection_id: Uuid,
-        source_schema: String,
-        target_schema: String,
-    ) -> Result<SchemaDiffResult> {
+        source_schema: &str,
+        target_schema: &str,
+    ) -> Result<(SchemaSnapshot, SchemaSnapshot, DatabaseType)> {
         // Source
-        let source_pool = self.get_postgres_pool(source_connection_id).await?;
-        let source_extractor = PostgresSchemaExtractor::new(source_pool);
-        let source_snapshot = source_extractor
-            .extract_schema(&source_schema)
+        let (source_snapshot, source_type) = self
+            .extract_schema_snapshot(source_connection_id, source_schema)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to extract source schema: {}", e))?;
 
         // Target
-        let target_pool = self.get_postgres_pool(target_connection_id).await?;
-        let target_extractor = PostgresSchemaExtractor::new(target_pool);
-        let target_snapshot = target_extractor
-            .extract_schema(&target_schema)
+        let (target_snapshot, target_type) = self
+            .extract_schema_snapshot(target_connection_id, target_schema)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to extract target schema: {}", e))?;
 
-        // Compare
-        Ok(SchemaDiffer::compare(&source_snapshot, &target_snapshot))
+        if source_type != target_type {
+            return Err(anyhow::anyhow!(
+                "Schema comparison requires both connections to use the same database type"
+            ));
+        }
+
+        Ok((source_snapshot, target_snapshot, target_type))
     }
 
+    pub async fn compare_schemas(
+        &self,
+        source_connection_id: Uuid,
+        target_connection_id: Uuid,
+        source_schema: String,
+        target_schema: String,
+        renames: &RenameDecisions,
+    ) -> Result<SchemaDiffResult> {
+        let (source, target, _) = self
+            .schema_snapshots(
+                source_connection_id,
+                target_connection_id,
+                &source_schema,
+                &target_schema,
+            )
+            .await?;
+        Ok(SchemaDiffer::compare_with_renames(
+            &source, &target, renames,
+        ))
+    }
+
+    /// Migration script from the source schema to the target. Only accepted
+    /// renames are renamed; suspected ones are dropped and added. `options`
+    /// default to drops in safe mode.
     pub async fn generate_migration(
         &self,
         source_connection_id: Uuid,
         target_connection_id: Uuid,
         source_schema: String,
         target_schema: String,
+        renames: &RenameDecisions,
+        options: Option<MigrationOptions>,
     ) -> Result<String> {
-        let diff_result = self
-            .compare_schemas(
+        let (source, target, database_type) = self
+            .schema_snapshots(
                 source_connection_id,
                 target_connection_id,
-                source_schema.clone(),
-                target_schema.clone(),
+                &source_schema,
+                &target_schema,
             )
             .await?;
-
-        // TODO: Infer DatabaseType from connection, assuming Postgres for now as we only support Postgres diff
-        let options = MigrationOptions {
+        let options = options.unwrap_or(MigrationOptions {
             include_drops: true,
             safe_mode: true,
-            database_type: DatabaseType::PostgreSQL,
-        };
+            database_type: database_type.clone(),
+        });
+        if options.database_type != database_type {
+            return Err(anyhow::anyhow!(
+                "Migration options are for {:?} but the connections are {:?}",
+                options.database_type,
+                database_type
+            ));
+        }
 
+        let diff_result = SchemaDiffer::compare_for_migration(&source, &target, renames);
         let script = MigrationGenerator::generate(&diff_result.diffs, &options);
 
         // Format as string
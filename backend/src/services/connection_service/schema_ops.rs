@@ -1,95 +1,31 @@
 use super::ConnectionService;
+use crate::services::db_driver::DatabaseDriver;
+use crate::services::driver::capabilities::DriverCapability;
+use crate::services::driver::extension::DatabaseManagementDriver;
+use crate::services::driver::{DdlExportDriver, SchemaIntrospection};
+use crate::services::driver_factory;
 use anyhow::Result;
 use uuid::Uuid;
 
 impl ConnectionService {
     pub async fn get_schemas(&self, connection_id: Uuid) -> Result<Vec<String>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::db_driver::DatabaseDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                DatabaseDriver::get_schemas(&driver).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                DatabaseDriver::get_schemas(&driver).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                DatabaseDriver::get_schemas(&driver).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                DatabaseDriver::get_schemas(&driver).await
-            }
-            "couchbase" => {
-                let driver =
-                    crate::services::couchbase::CouchbaseDriver::new(&connection, &password)
-                        .await?;
-                DatabaseDriver::get_schemas(&driver).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                DatabaseDriver::get_schemas(&driver).await
-            }
-            _ => Ok(vec![]),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        DatabaseDriver::get_schemas(&*driver).await
     }
 
     pub async fn create_schema(&self, connection_id: Uuid, name: &str) -> Result<()> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::driver::extension::DatabaseManagementDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                driver.create_schema(name).await
-            }
-            "sqlite" => Err(anyhow::anyhow!(
-                "SQLite does not support schemas via this API"
-            )),
-            "couchbase" => {
-                use crate::services::couchbase::CouchbaseDriver;
-                let driver = CouchbaseDriver::new(&connection, &password).await?;
-                driver.create_schema(name).await
-            }
-            _ => Err(anyhow::anyhow!(
-                "Unsupported database type for create_schema"
-            )),
-        }
+        driver_factory::spec_for(&connection.db_type)?.require(DriverCapability::Schemas)?;
+        let driver = self.driver(&connection, &password).await?;
+        DatabaseManagementDriver::create_schema(&*driver, name).await
     }
 
     pub async fn drop_schema(&self, connection_id: Uuid, name: &str) -> Result<()> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::driver::extension::DatabaseManagementDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                driver.drop_schema(name).await
-            }
-            "sqlite" => Err(anyhow::anyhow!(
-                "SQLite does not support schemas via this API"
-            )),
-            "couchbase" => {
-                use crate::services::couchbase::CouchbaseDriver;
-                let driver = CouchbaseDriver::new(&connection, &password).await?;
-                driver.drop_schema(name).await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type for drop_schema")),
-        }
+        driver_factory::spec_for(&connection.db_type)?.require(DriverCapability::Schemas)?;
+        let driver = self.driver(&connection, &password).await?;
+        DatabaseManagementDriver::drop_schema(&*driver, name).await
     }
 
     pub async fn get_schema_metadata(
@@ -99,30 +35,7 @@ impl ConnectionService {
     ) -> Result<Vec<crate::services::db_driver::TableMetadata>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
         let database_name = connection.database.clone();
-
-        use crate::services::db_driver::DatabaseDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-        use std::sync::Arc;
-
-        let driver: Arc<dyn DatabaseDriver> = match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                Arc::new(PostgresDriver::new(&connection, &password).await?)
-            }
-            "sqlite" => Arc::new(self.sqlite_driver(&connection, &password).await?),
-            "clickhouse" => Arc::new(
-                crate::services::clickhouse::ClickHouseDriver::new(&connection, &password).await?,
-            ),
-            "mysql" | "mariadb" | "tidb" => Arc::new(
-                crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?,
-            ),
-            "couchbase" => Arc::new(
-                crate::services::couchbase::CouchbaseDriver::new(&connection, &password).await?,
-            ),
-            "mongodb" | "mongo" => {
-                Arc::new(crate::services::mongo::MongoDriver::new(&connection, &password).await?)
-            }
-            _ => return Ok(vec![]),
-        };
+        let driver = self.driver(&connection, &password).await?;
 
         if let Some(cache) = &self.schema_cache {
             return cache
@@ -139,98 +52,30 @@ impl ConnectionService {
         schema: &str,
     ) -> Result<Vec<crate::services::db_driver::SchemaForeignKey>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::driver::SchemaIntrospection;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                driver.get_schema_foreign_keys(schema).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                driver.get_schema_foreign_keys(schema).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                driver.get_schema_foreign_keys(schema).await
-            }
-            "couchbase" => {
-                let driver =
-                    crate::services::couchbase::CouchbaseDriver::new(&connection, &password)
-                        .await?;
-                driver.get_schema_foreign_keys(schema).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                driver.get_schema_foreign_keys(schema).await
-            }
-            _ => Ok(vec![]),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        SchemaIntrospection::get_schema_foreign_keys(&*driver, schema).await
     }
+
     pub async fn get_schema_permissions(
         &self,
         connection_id: Uuid,
         schema: &str,
     ) -> Result<Vec<crate::services::db_driver::TableGrant>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::db_driver::DatabaseDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                DatabaseDriver::get_schema_permissions(&driver, schema).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                DatabaseDriver::get_schema_permissions(&driver, schema).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                DatabaseDriver::get_schema_permissions(&driver, schema).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                DatabaseDriver::get_schema_permissions(&driver, schema).await
-            }
-            "couchbase" => {
-                let driver =
-                    crate::services::couchbase::CouchbaseDriver::new(&connection, &password)
-                        .await?;
-                DatabaseDriver::get_schema_permissions(&driver, schema).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                DatabaseDriver::get_schema_permissions(&driver, schema).await
-            }
-            _ => Ok(vec![]),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        DatabaseDriver::get_schema_permissions(&*driver, schema).await
     }
+
     pub async fn list_extensions(
         &self,
         connection_id: Uuid,
     ) -> Result<Vec<crate::services::db_driver::ExtensionInfo>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::driver::SchemaIntrospection;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                driver.get_extensions().await
-            }
-            _ => Ok(vec![]),
+        if !driver_factory::spec_for(&connection.db_type)?.supports(DriverCapability::Extensions) {
+            return Ok(vec![]);
         }
+        let driver = self.driver(&connection, &password).await?;
+        SchemaIntrospection::get_extensions(&*driver).await
     }
 
     pub async fn install_extension(
@@ -241,56 +86,26 @@ impl ConnectionService {
         version: Option<&str>,
     ) -> Result<()> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::driver::extension::DatabaseManagementDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                driver.install_extension(name, schema, version).await
-            }
-            _ => Err(anyhow::anyhow!(
-                "Unsupported database type for install_extension"
-            )),
-        }
+        driver_factory::spec_for(&connection.db_type)?.require(DriverCapability::Extensions)?;
+        let driver = self.driver(&connection, &password).await?;
+        DatabaseManagementDriver::install_extension(&*driver, name, schema, version).await
     }
 
     pub async fn drop_extension(&self, connection_id: Uuid, name: &str) -> Result<()> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::driver::extension::DatabaseManagementDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                driver.drop_extension(name).await
-            }
-            _ => Err(anyhow::anyhow!(
-                "Unsupported database type for drop_extension"
-            )),
-        }
+        driver_factory::spec_for(&connection.db_type)?.require(DriverCapability::Extensions)?;
+        let driver = self.driver(&connection, &password).await?;
+        DatabaseManagementDriver::drop_extension(&*driver, name).await
     }
+
     pub async fn export_ddl(
         &self,
         connection_id: Uuid,
         options: crate::models::export_ddl::ExportDdlOptions,
     ) -> Result<String> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::driver::ddl_export::DdlExportDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                driver.export_ddl(&options).await
-            }
-            // Add other drivers if they implement DdlExportDriver
-            _ => Err(anyhow::anyhow!(
-                "DDL export not supported for this database type"
-            )),
-        }
+        driver_factory::spec_for(&connection.db_type)?.require(DriverCapability::DdlExport)?;
+        let driver = self.driver(&connection, &password).await?;
+        DdlExportDriver::export_ddl(&*driver, &options).await
     }
 }
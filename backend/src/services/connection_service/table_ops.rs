@@ -1,6 +1,8 @@
 use super::ConnectionService;
-use crate::services::db_driver::{DatabaseDriver, QueryResult};
+use crate::services::driver::capabilities::DriverCapability;
 use crate::services::driver::{ColumnManagement, SchemaIntrospection, TableOperations};
+use crate::services::driver_factory;
+use crate::services::table_filter::{FilterNode, SortSpec};
 use anyhow::Result;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -13,128 +15,20 @@ impl ConnectionService {
         schema: &str,
     ) -> Result<Vec<crate::services::db_driver::TableInfo>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                SchemaIntrospection::get_tables(&driver, schema).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                SchemaIntrospection::get_tables(&driver, schema).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                SchemaIntrospection::get_tables(&driver, schema).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                SchemaIntrospection::get_tables(&driver, schema).await
-            }
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-                DatabaseDriver::get_tables(&driver, schema).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                DatabaseDriver::get_tables(&driver, schema).await
-            }
-            _ => Ok(vec![]),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        SchemaIntrospection::get_tables(&*driver, schema).await
     }
 
     pub async fn create_table(&self, connection_id: Uuid, schema: &str, table: &str) -> Result<()> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::create_table(&driver, schema, table).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::create_table(&driver, schema, table).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::create_table(&driver, schema, table).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::create_table(&driver, schema, table).await
-            }
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-                TableOperations::create_table(&driver, schema, table).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                TableOperations::create_table(&driver, schema, table).await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::create_table(&*driver, schema, table).await
     }
 
     pub async fn drop_table(&self, connection_id: Uuid, schema: &str, table: &str) -> Result<()> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::drop_table(&driver, schema, table).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::drop_table(&driver, schema, table).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::drop_table(&driver, schema, table).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::drop_table(&driver, schema, table).await
-            }
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-                TableOperations::drop_table(&driver, schema, table).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                TableOperations::drop_table(&driver, schema, table).await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::drop_table(&*driver, schema, table).await
     }
 
     pub async fn get_columns(
@@ -144,44 +38,8 @@ impl ConnectionService {
         table: &str,
     ) -> Result<Vec<crate::services::db_driver::TableColumn>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                SchemaIntrospection::get_columns(&driver, schema, table).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                SchemaIntrospection::get_columns(&driver, schema, table).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                SchemaIntrospection::get_columns(&driver, schema, table).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                SchemaIntrospection::get_columns(&driver, schema, table).await
-            }
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-                DatabaseDriver::get_columns(&driver, schema, table).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                DatabaseDriver::get_columns(&driver, schema, table).await
-            }
-            _ => Ok(vec![]),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        SchemaIntrospection::get_columns(&*driver, schema, table).await
     }
 
     pub async fn get_table_data(
@@ -191,124 +49,28 @@ impl ConnectionService {
         table: &str,
         limit: i64,
         offset: i64,
-        filter: Option<String>,
+        filter: Option<FilterNode>,
+        sort: Vec<SortSpec>,
         document_id: Option<String>,
         fields: Option<Vec<String>>,
     ) -> Result<crate::services::db_driver::QueryResult> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
+        let driver = self.driver(&connection, &password).await?;
 
         let start_time = std::time::Instant::now();
 
-        let result = match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::get_table_data(
-                    &driver,
-                    schema,
-                    table,
-                    limit,
-                    offset,
-                    filter,
-                    document_id,
-                    fields,
-                )
-                .await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::get_table_data(
-                    &driver,
-                    schema,
-                    table,
-                    limit,
-                    offset,
-                    filter,
-                    document_id,
-                    fields,
-                )
-                .await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::get_table_data(
-                    &driver,
-                    schema,
-                    table,
-                    limit,
-                    offset,
-                    filter,
-                    document_id,
-                    fields,
-                )
-                .await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::get_table_data(
-                    &driver,
-                    schema,
-                    table,
-                    limit,
-                    offset,
-                    filter,
-                    document_id,
-                    fields,
-                )
-                .await
-            }
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-                TableOperations::get_table_data(
-                    &driver,
-                    schema,
-                    table,
-                    limit,
-                    offset,
-                    filter,
-                    document_id,
-                    fields,
-                )
-                .await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                TableOperations::get_table_data(
-                    &driver,
-                    schema,
-                    table,
-                    limit,
-                    offset,
-                    filter,
-                    document_id,
-                    fields,
-                )
-                .await
-            }
-            _ => Ok(QueryResult {
-                columns: vec![],
-                rows: vec![],
-                affected_rows: 0,
-                column_metadata: None,
-                total_count: None,
-                limit: Some(limit),
-                offset: Some(offset as i64),
-                has_more: None,
-                row_metadata: None,
-                execution_time_ms: None,
-                json: None,
-                display_mode: None,
-            }),
-        };
+        let result = TableOperations::get_table_data(
+            &*driver,
+            schema,
+            table,
+            limit,
+            offset,
+            filter,
+            sort,
+            document_id,
+            fields,
+        )
+        .await;
 
         let duration = start_time.elapsed();
         result.map(|mut res| {
@@ -325,31 +87,10 @@ impl ConnectionService {
         column: &crate::services::db_driver::ColumnDefinition,
     ) -> Result<()> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                ColumnManagement::add_column(&driver, schema, table, column).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                ColumnManagement::add_column(&driver, schema, table, column).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                ColumnManagement::add_column(&driver, schema, table, column).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                ColumnManagement::add_column(&driver, schema, table, column).await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
-        }
+        driver_factory::spec_for(&connection.db_type)?
+            .require(DriverCapability::ColumnManagement)?;
+        let driver = self.driver(&connection, &password).await?;
+        ColumnManagement::add_column(&*driver, schema, table, column).await
     }
 
     pub async fn alter_column(
@@ -361,31 +102,10 @@ impl ConnectionService {
         new_def: &crate::services::db_driver::ColumnDefinition,
     ) -> Result<()> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                ColumnManagement::alter_column(&driver, schema, table, column_name, new_def).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                ColumnManagement::alter_column(&driver, schema, table, column_name, new_def).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                ColumnManagement::alter_column(&driver, schema, table, column_name, new_def).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                ColumnManagement::alter_column(&driver, schema, table, column_name, new_def).await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
-        }
+        driver_factory::spec_for(&connection.db_type)?
+            .require(DriverCapability::ColumnManagement)?;
+        let driver = self.driver(&connection, &password).await?;
+        ColumnManagement::alter_column(&*driver, schema, table, column_name, new_def).await
     }
 
     pub async fn drop_column(
@@ -396,31 +116,10 @@ impl ConnectionService {
         column_name: &str,
     ) -> Result<()> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                ColumnManagement::drop_column(&driver, schema, table, column_name).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                ColumnManagement::drop_column(&driver, schema, table, column_name).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                ColumnManagement::drop_column(&driver, schema, table, column_name).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                ColumnManagement::drop_column(&driver, schema, table, column_name).await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
-        }
+        driver_factory::spec_for(&connection.db_type)?
+            .require(DriverCapability::ColumnManagement)?;
+        let driver = self.driver(&connection, &password).await?;
+        ColumnManagement::drop_column(&*driver, schema, table, column_name).await
     }
 
     pub async fn get_table_constraints(
@@ -430,48 +129,8 @@ impl ConnectionService {
         table: &str,
     ) -> Result<crate::services::db_driver::TableConstraints> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::get_table_constraints(&driver, schema, table).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::get_table_constraints(&driver, schema, table).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::get_table_constraints(&driver, schema, table).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::get_table_constraints(&driver, schema, table).await
-            }
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-                DatabaseDriver::get_table_constraints(&driver, schema, table).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                DatabaseDriver::get_table_constraints(&driver, schema, table).await
-            }
-            _ => Ok(crate::services::db_driver::TableConstraints {
-                foreign_keys: vec![],
-                check_constraints: vec![],
-                unique_constraints: vec![],
-            }),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::get_table_constraints(&*driver, schema, table).await
     }
 
     pub async fn get_table_statistics(
@@ -481,51 +140,8 @@ impl ConnectionService {
         table: &str,
     ) -> Result<crate::services::db_driver::TableStatistics> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::get_table_statistics(&driver, schema, table).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::get_table_statistics(&driver, schema, table).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::get_table_statistics(&driver, schema, table).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::get_table_statistics(&driver, schema, table).await
-            }
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-                DatabaseDriver::get_table_statistics(&driver, schema, table).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                DatabaseDriver::get_table_statistics(&driver, schema, table).await
-            }
-            _ => Ok(crate::services::db_driver::TableStatistics {
-                row_count: None,
-                table_size: None,
-                index_size: None,
-                total_size: None,
-                created_at: None,
-                last_modified: None,
-            }),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::get_table_statistics(&*driver, schema, table).await
     }
 
     pub async fn get_table_indexes(
@@ -535,44 +151,8 @@ impl ConnectionService {
         table: &str,
     ) -> Result<Vec<crate::services::db_driver::IndexInfo>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::get_table_indexes(&driver, schema, table).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::get_table_indexes(&driver, schema, table).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::get_table_indexes(&driver, schema, table).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::get_table_indexes(&driver, schema, table).await
-            }
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-                DatabaseDriver::get_table_indexes(&driver, schema, table).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                DatabaseDriver::get_table_indexes(&driver, schema, table).await
-            }
-            _ => Ok(vec![]),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::get_table_indexes(&*driver, schema, table).await
     }
 
     pub async fn get_table_triggers(
@@ -582,44 +162,8 @@ impl ConnectionService {
         table: &str,
     ) -> Result<Vec<crate::services::db_driver::TriggerInfo>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::get_table_triggers(&driver, schema, table).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::get_table_triggers(&driver, schema, table).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::get_table_triggers(&driver, schema, table).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::get_table_triggers(&driver, schema, table).await
-            }
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-                DatabaseDriver::get_table_triggers(&driver, schema, table).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                DatabaseDriver::get_table_triggers(&driver, schema, table).await
-            }
-            _ => Ok(vec![]),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::get_table_triggers(&*driver, schema, table).await
     }
 
     pub async fn get_table_comment(
@@ -629,44 +173,8 @@ impl ConnectionService {
         table: &str,
     ) -> Result<crate::services::db_driver::TableComment> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::get_table_comment(&driver, schema, table).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::get_table_comment(&driver, schema, table).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::get_table_comment(&driver, schema, table).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::get_table_comment(&driver, schema, table).await
-            }
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-                DatabaseDriver::get_table_comment(&driver, schema, table).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                DatabaseDriver::get_table_comment(&driver, schema, table).await
-            }
-            _ => Ok(crate::services::db_driver::TableComment { comment: None }),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::get_table_comment(&*driver, schema, table).await
     }
 
     pub async fn set_table_comment(
@@ -677,44 +185,8 @@ impl ConnectionService {
         comment: Option<String>,
     ) -> Result<()> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::set_table_comment(&driver, schema, table, comment).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::set_table_comment(&driver, schema, table, comment).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::set_table_comment(&driver, schema, table, comment).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::set_table_comment(&driver, schema, table, comment).await
-            }
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-                DatabaseDriver::set_table_comment(&driver, schema, table, comment).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                DatabaseDriver::set_table_comment(&driver, schema, table, comment).await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::set_table_comment(&*driver, schema, table, comment).await
     }
 
     pub async fn get_table_permissions(
@@ -724,44 +196,8 @@ impl ConnectionService {
         table: &str,
     ) -> Result<Vec<crate::services::db_driver::TableGrant>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::get_table_permissions(&driver, schema, table).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::get_table_permissions(&driver, schema, table).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::get_table_permissions(&driver, schema, table).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::get_table_permissions(&driver, schema, table).await
-            }
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-                DatabaseDriver::get_table_permissions(&driver, schema, table).await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                DatabaseDriver::get_table_permissions(&driver, schema, table).await
-            }
-            _ => Ok(vec![]),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::get_table_permissions(&*driver, schema, table).await
     }
 
     pub async fn list_roles(
@@ -769,31 +205,8 @@ impl ConnectionService {
         connection_id: Uuid,
     ) -> Result<Vec<crate::services::db_driver::RoleInfo>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::list_roles(&driver).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::list_roles(&driver).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::list_roles(&driver).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::list_roles(&driver).await
-            }
-            _ => Ok(vec![]),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::list_roles(&*driver).await
     }
 
     pub async fn set_table_permissions(
@@ -806,63 +219,16 @@ impl ConnectionService {
         grant_option: bool,
     ) -> Result<()> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::set_table_permissions(
-                    &driver,
-                    schema,
-                    table,
-                    grantee,
-                    privileges,
-                    grant_option,
-                )
-                .await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::set_table_permissions(
-                    &driver,
-                    schema,
-                    table,
-                    grantee,
-                    privileges,
-                    grant_option,
-                )
-                .await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::set_table_permissions(
-                    &driver,
-                    schema,
-                    table,
-                    grantee,
-                    privileges,
-                    grant_option,
-                )
-                .await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::set_table_permissions(
-                    &driver,
-                    schema,
-                    table,
-                    grantee,
-                    privileges,
-                    grant_option,
-                )
-                .await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::set_table_permissions(
+            &*driver,
+            schema,
+            table,
+            grantee,
+            privileges,
+            grant_option,
+        )
+        .await
     }
 
     pub async fn detect_fk_orphans(
@@ -872,39 +238,8 @@ impl ConnectionService {
         table: &str,
     ) -> Result<Vec<crate::services::db_driver::FkOrphanInfo>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::detect_fk_orphans(&driver, schema, table).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::detect_fk_orphans(&driver, schema, table).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::detect_fk_orphans(&driver, schema, table).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::detect_fk_orphans(&driver, schema, table).await
-            }
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-                TableOperations::detect_fk_orphans(&driver, schema, table).await
-            }
-            _ => Ok(vec![]),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::detect_fk_orphans(&*driver, schema, table).await
     }
 
     pub async fn get_table_dependencies(
@@ -914,35 +249,8 @@ impl ConnectionService {
         table: &str,
     ) -> Result<crate::services::db_driver::TableDependencies> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::get_table_dependencies(&driver, schema, table).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::get_table_dependencies(&driver, schema, table).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::get_table_dependencies(&driver, schema, table).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::get_table_dependencies(&driver, schema, table).await
-            }
-            _ => Ok(crate::services::db_driver::TableDependencies {
-                views: vec![],
-                routines: vec![],
-                referencing_foreign_keys: vec![],
-            }),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::get_table_dependencies(&*driver, schema, table).await
     }
 
     pub async fn get_storage_bloat_info(
@@ -952,42 +260,8 @@ impl ConnectionService {
         table: &str,
     ) -> Result<crate::services::db_driver::StorageBloatInfo> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::get_storage_bloat_info(&driver, schema, table).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::get_storage_bloat_info(&driver, schema, table).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::get_storage_bloat_info(&driver, schema, table).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::get_storage_bloat_info(&driver, schema, table).await
-            }
-            _ => Ok(crate::services::db_driver::StorageBloatInfo {
-                live_tuples: None,
-                dead_tuples: None,
-                dead_tuple_pct: None,
-                table_size: None,
-                index_size: None,
-                total_size: None,
-                last_vacuum: None,
-                last_autovacuum: None,
-                last_analyze: None,
-                last_autoanalyze: None,
-            }),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::get_storage_bloat_info(&*driver, schema, table).await
     }
 
     pub async fn get_partitions(
@@ -997,37 +271,10 @@ impl ConnectionService {
         table: &str,
     ) -> Result<crate::services::db_driver::PartitionInfo> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                TableOperations::get_partitions(&driver, schema, table).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                TableOperations::get_partitions(&driver, schema, table).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                TableOperations::get_partitions(&driver, schema, table).await
-            }
-            "mysql" | "mariadb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                TableOperations::get_partitions(&driver, schema, table).await
-            }
-            _ => Ok(crate::services::db_driver::PartitionInfo {
-                is_partitioned: false,
-                strategy: None,
-                key: None,
-                partitions: vec![],
-            }),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::get_partitions(&*driver, schema, table).await
     }
+
     pub async fn update_row(
         &self,
         connection_id: Uuid,
@@ -1038,43 +285,16 @@ impl ConnectionService {
         row_metadata: Option<HashMap<String, Value>>,
     ) -> Result<u64> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        // For now only Couchbase supports update_row via TableOperations
-        match connection.db_type.as_str() {
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-
-                TableOperations::update_row(
-                    &driver,
-                    schema,
-                    table,
-                    &primary_key,
-                    &updates,
-                    row_metadata.as_ref(),
-                )
-                .await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                TableOperations::update_row(
-                    &driver,
-                    schema,
-                    table,
-                    &primary_key,
-                    &updates,
-                    row_metadata.as_ref(),
-                )
-                .await
-            }
-            _ => Err(anyhow::anyhow!(
-                "Update row via TableOperations not supported for this driver yet"
-            )),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::update_row(
+            &*driver,
+            schema,
+            table,
+            &primary_key,
+            &updates,
+            row_metadata.as_ref(),
+        )
+        .await
     }
 
     pub async fn delete_row(
@@ -1086,39 +306,8 @@ impl ConnectionService {
         row_metadata: Option<HashMap<String, Value>>,
     ) -> Result<u64> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        match connection.db_type.as_str() {
-            "couchbase" => {
-                let driver = crate::services::couchbase::connection::CouchbaseDriver::new(
-                    &connection,
-                    &password,
-                )
-                .await?;
-
-                TableOperations::delete_row(
-                    &driver,
-                    schema,
-                    table,
-                    &primary_key,
-                    row_metadata.as_ref(),
-                )
-                .await
-            }
-            "mongodb" | "mongo" => {
-                let driver =
-                    crate::services::mongo::MongoDriver::new(&connection, &password).await?;
-                TableOperations::delete_row(
-                    &driver,
-                    schema,
-                    table,
-                    &primary_key,
-                    row_metadata.as_ref(),
-                )
-                .await
-            }
-            _ => Err(anyhow::anyhow!(
-                "Delete row via TableOperations not supported for this driver yet"
-            )),
-        }
+        let driver = self.driver(&connection, &password).await?;
+        TableOperations::delete_row(&*driver, schema, table, &primary_key, row_metadata.as_ref())
+            .await
     }
 }
@@ -0,0 +1,89 @@
+use super::query_ops::check_unsafe_statements;
+use super::ConnectionService;
+use crate::services::db_driver::QueryResult;
+use crate::services::driver::capabilities::DriverCapability;
+use crate::services::driver::TransactionDriver;
+use crate::services::driver_factory;
+use crate::services::query_limits::QueryLimits;
+use crate::services::transaction_manager::{TransactionInfo, TransactionManager};
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+impl ConnectionService {
+    fn transaction_manager(&self) -> Result<&Arc<TransactionManager>> {
+        self.transactions
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Transactions are not available in this context"))
+    }
+
+    /// Pins a pooled connection and starts a transaction on it. Statements run
+    /// through `execute_in_transaction` until it is committed or rolled back.
+    pub async fn begin_transaction(
+        &self,
+        connection_id: Uuid,
+        idle_timeout: Option<Duration>,
+    ) -> Result<TransactionInfo> {
+        let transactions = self.transaction_manager()?;
+        let (connection, password) = self.get_connection_with_password(connection_id).await?;
+        driver_factory::spec_for(&connection.db_type)?.require(DriverCapability::Transactions)?;
+        let driver = self.driver(&connection, &password).await?;
+        let session = TransactionDriver::begin_transaction(&*driver).await?;
+        Ok(transactions.register(
+            connection_id,
+            connection.database,
+            connection.db_type,
+            session,
+            idle_timeout,
+        ))
+    }
+
+    pub async fn execute_in_transaction(
+        &self,
+        connection_id: Uuid,
+        transaction_id: &str,
+        query: &str,
+        confirmed_unsafe: bool,
+    ) -> Result<QueryResult> {
+        let transactions = self.transaction_manager()?;
+        let connection = self
+            .get_connection_by_id(connection_id)
+            .await?
+            .ok_or(anyhow::anyhow!("Connection not found"))?;
+        check_unsafe_statements(&connection, query, confirmed_unsafe)?;
+
+        let limits = QueryLimits::from_connection(&connection);
+        let start_time = std::time::Instant::now();
+        let mut result = limits
+            .enforce(transactions.execute(connection_id, transaction_id, query))
+            .await?;
+        result.execution_time_ms = Some(start_time.elapsed().as_millis() as u64);
+        limits.apply(&mut result);
+        Ok(result)
+    }
+
+    pub async fn commit_transaction(
+        &self,
+        connection_id: Uuid,
+        transaction_id: &str,
+    ) -> Result<()> {
+        self.transaction_manager()?
+            .commit(connection_id, transaction_id)
+            .await
+    }
+
+    pub async fn rollback_transaction(
+        &self,
+        connection_id: Uuid,
+        transaction_id: &str,
+    ) -> Result<()> {
+        self.transaction_manager()?
+            .rollback(connection_id, transaction_id)
+            .await
+    }
+
+    pub fn list_transactions(&self, connection_id: Uuid) -> Result<Vec<TransactionInfo>> {
+        Ok(self.transaction_manager()?.list(connection_id))
+    }
+}
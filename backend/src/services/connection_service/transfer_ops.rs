@@ -0,0 +1,567 @@
+use super::export_ops::{end_stream, page_table_data, table_select};
+use super::ConnectionService;
+use crate::models::entities::connection;
+use crate::models::export_ddl::{DdlObjectSpec, DdlObjectType, DdlScope, ExportDdlOptions};
+use crate::services::bulk;
+use crate::services::db_driver::{DatabaseDriver, TableColumn};
+use crate::services::driver::capabilities::DriverCapability;
+use crate::services::driver::{
+    BulkOperationsDriver, DdlExportDriver, PreparedStatementDriver, QueryDriver,
+    SchemaIntrospection, TableOperations,
+};
+use crate::services::driver_factory::{self, DriverSpec};
+use crate::services::export::TableExport;
+use crate::services::query_params::QueryParams;
+use crate::services::query_stream::{QueryStreamEvent, QueryStreamSink, StreamOptions};
+use crate::services::table_filter::SqlDialect;
+use crate::services::transfer::{
+    self, OnTransferProgress, TransferMeter, TransferMode, TransferReport, TransferRequest,
+    TRANSFER_BATCH_SIZE,
+};
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+/// The connection tables are read from.
+struct TransferSide<'a> {
+    driver: &'a dyn DatabaseDriver,
+    spec: &'static DriverSpec,
+    schema: &'a str,
+}
+
+/// Where and how the rows of one table are written.
+struct TableWriter<'a> {
+    driver: &'a dyn DatabaseDriver,
+    engine: &'static str,
+    dialect: SqlDialect,
+    schema: &'a str,
+    table: &'a str,
+    /// Source columns that are copied.
+    fields: Vec<String>,
+    /// Their names in the target.
+    columns: Vec<String>,
+    /// Which of them the source declares as booleans.
+    booleans: Vec<bool>,
+    mode: TransferMode,
+    /// Primary key of the target table, for upserts.
+    keys: Vec<String>,
+}
+
+impl ConnectionService {
+    /// Copies tables, optionally filtered, from one connection to another.
+    /// Missing target tables are created first: with the source engine's own
+    /// DDL when both sides run the same engine and schema, otherwise from
+    /// `get_columns` and `get_table_constraints` with types mapped to the
+    /// target. Rows are streamed in batches; each batch is written on its
+    /// own, so a failed transfer leaves the batches written before it.
+    pub async fn transfer_tables(
+        &self,
+        request: &TransferRequest,
+        on_progress: OnTransferProgress<'_>,
+    ) -> Result<TransferReport> {
+        let started = Instant::now();
+        let (source_connection, source_password) = self
+            .get_connection_with_password(request.source.connection_id)
+            .await?;
+        let (target_connection, target_password) = self
+            .get_connection_with_password(request.target.connection_id)
+            .await?;
+        let source_spec = driver_factory::spec_for(&source_connection.db_type)?;
+        let target_spec = driver_factory::spec_for(&target_connection.db_type)?;
+        target_spec.require(DriverCapability::BulkOperations)?;
+        let target_dialect = target_spec.sql_dialect.ok_or_else(|| {
+            anyhow!(
+                "Transfers can only write to SQL databases, not {}",
+                target_spec.name
+            )
+        })?;
+        let source = self.driver(&source_connection, &source_password).await?;
+        let target = self.driver(&target_connection, &target_password).await?;
+
+        let source_schema = request.source.schema.as_str();
+        let target_schema = match &request.target.schema {
+            Some(schema) => schema.clone(),
+            // Postgres keeps schemas apart within a database; elsewhere the
+            // schema is the database the target connection opened.
+            None if source_spec.name == "postgres" && target_spec.name == "postgres" => {
+                source_schema.to_string()
+            }
+            None => default_schema(target_spec, &target_connection),
+        };
+        // The MySQL and SQLite exporters leave the schema out of their DDL.
+        let native_ddl = source_spec.name == target_spec.name
+            && source_schema == target_schema
+            && (matches!(target_spec.name, "postgres" | "clickhouse")
+                || target_schema == default_schema(target_spec, &target_connection));
+        let batch_size = request.batch_size.unwrap_or(TRANSFER_BATCH_SIZE);
+
+        let tables = if source_spec.supports(DriverCapability::Constraints) {
+            let mut references = HashMap::new();
+            for table in &request.source.tables {
+                let constraints =
+                    TableOperations::get_table_constraints(&*source, source_schema, table).await?;
+                let referenced: Vec<String> = constraints
+                    .foreign_keys
+                    .into_iter()
+                    .filter(|fk| fk.foreign_schema.is_empty() || fk.foreign_schema == source_schema)
+                    .map(|fk| fk.foreign_table)
+                    .collect();
+                references.insert(table.clone(), referenced);
+            }
+            transfer::dependency_order(&request.source.tables, &references)
+        } else {
+            request.source.tables.clone()
+        };
+
+        // Every table is checked before any target is emptied, so that a
+        // transfer that cannot run leaves the target as it was.
+        let mut plans = Vec::with_capacity(tables.len());
+        for table in &tables {
+            let columns = SchemaIntrospection::get_columns(&*source, source_schema, table).await?;
+            if columns.is_empty() {
+                return Err(anyhow!("Table {} not found in the source", table));
+            }
+            let existing =
+                SchemaIntrospection::get_columns(&*target, &target_schema, table).await?;
+            if existing.is_empty() {
+                if !request.create_tables {
+                    return Err(anyhow!("Table {} does not exist in the target", table));
+                }
+            } else {
+                match_columns(table, source_spec, &columns, &existing, request.mode)?;
+            }
+            plans.push((columns, existing));
+        }
+
+        if request.mode == TransferMode::Truncate {
+            // Children first, so that foreign keys between the copied tables
+            // do not stop their parents from being emptied.
+            let mut existing = Vec::new();
+            for (table, (_, columns)) in tables.iter().zip(&plans).rev() {
+                if !columns.is_empty() {
+                    existing.push(bulk::table_ref(target_dialect, &target_schema, table)?);
+                }
+            }
+            for sql in transfer::truncate_sql(target_spec.name, &existing) {
+                QueryDriver::execute(&*target, &sql).await?;
+            }
+        }
+
+        let mut report = TransferReport::default();
+        for (i, (table, (columns, mut existing))) in tables.iter().zip(plans).enumerate() {
+            let mut meter = TransferMeter::new(table, i + 1, tables.len(), on_progress);
+            let target_ref = bulk::table_ref(target_dialect, &target_schema, table)?;
+            let created = existing.is_empty();
+            if created {
+                let from = TransferSide {
+                    driver: &*source,
+                    spec: source_spec,
+                    schema: source_schema,
+                };
+                let ddl =
+                    create_table_ddl(&from, table, &columns, target_spec, &target_ref, native_ddl)
+                        .await?;
+                if let Err(e) = QueryDriver::execute_script(&*target, &ddl.native).await {
+                    let Some(mapped) = &ddl.mapped else {
+                        return Err(e);
+                    };
+                    // Foreign keys or sequences of the source may be missing here.
+                    tracing::warn!(
+                        "Creating {} with the source DDL failed ({}), using mapped types",
+                        table,
+                        e
+                    );
+                    QueryDriver::execute_script(&*target, mapped).await?;
+                }
+                existing =
+                    SchemaIntrospection::get_columns(&*target, &target_schema, table).await?;
+            }
+
+            let matched = match_columns(table, source_spec, &columns, &existing, request.mode)?;
+            let fields = matched.fields.clone();
+            let writer = TableWriter {
+                driver: &*target,
+                engine: target_spec.name,
+                dialect: target_dialect,
+                schema: &target_schema,
+                table,
+                fields: matched.fields,
+                columns: matched.columns,
+                booleans: matched.booleans,
+                mode: request.mode,
+                keys: matched.keys,
+            };
+
+            let export = TableExport {
+                schema: source_schema.to_string(),
+                table: table.clone(),
+                filter: request.source.filter.clone(),
+                sort: Vec::new(),
+                fields: Some(fields),
+            };
+            let select = match source_spec.sql_dialect {
+                Some(dialect) if source_spec.supports(DriverCapability::Streaming) => {
+                    Some(table_select(&export, dialect)?)
+                }
+                _ => None,
+            };
+            let (mut sink, rx) = QueryStreamSink::channel(Some(batch_size));
+            let reader = &*source;
+            let reading = async move {
+                let result = match select {
+                    Some(sql) => {
+                        QueryDriver::stream_query(
+                            reader,
+                            &sql,
+                            &StreamOptions::default(),
+                            &mut sink,
+                        )
+                        .await
+                    }
+                    None => page_table_data(reader, export, &mut sink).await,
+                };
+                end_stream(sink, result).await;
+            };
+            let (written, ()) = tokio::join!(write_rows(&writer, rx, &mut meter), reading);
+            let written = written?;
+
+            report.rows_written += written;
+            report.tables.push(meter.finish(created, written));
+        }
+        report.execution_time_ms = started.elapsed().as_millis() as u64;
+        Ok(report)
+    }
+}
+
+/// Source columns of one table paired with the target columns they are
+/// written to.
+struct ColumnMatch {
+    fields: Vec<String>,
+    columns: Vec<String>,
+    booleans: Vec<bool>,
+    keys: Vec<String>,
+}
+
+/// Matches the source columns to those the target has, by name and then
+/// case-insensitively; only matched columns are copied.
+fn match_columns(
+    table: &str,
+    source_spec: &DriverSpec,
+    columns: &[TableColumn],
+    existing: &[TableColumn],
+    mode: TransferMode,
+) -> Result<ColumnMatch> {
+    let mut matched = ColumnMatch {
+        fields: Vec::new(),
+        columns: Vec::new(),
+        booleans: Vec::new(),
+        keys: existing
+            .iter()
+            .filter(|column| column.is_primary_key)
+            .map(|column| column.name.clone())
+            .collect(),
+    };
+    for column in columns {
+        let other = existing
+            .iter()
+            .find(|other| other.name == column.name)
+            .or_else(|| {
+                existing
+                    .iter()
+                    .find(|other| other.name.eq_ignore_ascii_case(&column.name))
+            });
+        if let Some(other) = other {
+            matched.fields.push(column.name.clone());
+            matched.columns.push(other.name.clone());
+            matched
+                .booleans
+                .push(transfer::is_boolean(source_spec.name, &column.data_type));
+        }
+    }
+    if matched.fields.is_empty() {
+        return Err(anyhow!(
+            "Table {} has no columns in common with the target",
+            table
+        ));
+    }
+    if mode == TransferMode::Upsert && matched.keys.is_empty() {
+        return Err(anyhow!(
+            "Upsert needs a primary key on the target table {}",
+            table
+        ));
+    }
+    Ok(matched)
+}
+
+/// DDL for a table missing in the target. `mapped` is the fallback when the
+/// source engine's own DDL cannot be run there.
+struct TableDdl {
+    native: String,
+    mapped: Option<String>,
+}
+
+async fn create_table_ddl(
+    source: &TransferSide<'_>,
+    table: &str,
+    columns: &[TableColumn],
+    target_spec: &DriverSpec,
+    target_ref: &str,
+    native_ddl: bool,
+) -> Result<TableDdl> {
+    let unique_constraints = if source.spec.supports(DriverCapability::Constraints) {
+        TableOperations::get_table_constraints(source.driver, source.schema, table)
+            .await?
+            .unique_constraints
+    } else {
+        Vec::new()
+    };
+    let mapped = transfer::create_table_sql(
+        source.spec.name,
+        target_spec.name,
+        target_spec.sql_dialect.unwrap_or(SqlDialect::POSTGRES),
+        target_ref,
+        columns,
+        &unique_constraints,
+    )?;
+
+    if native_ddl && source.spec.supports(DriverCapability::DdlExport) {
+        let options = ExportDdlOptions {
+            scope: DdlScope::Objects,
+            database: None,
+            schemas: None,
+            objects: Some(vec![DdlObjectSpec {
+                object_type: DdlObjectType::Table,
+                schema: source.schema.to_string(),
+                name: table.to_string(),
+            }]),
+            include_drop: false,
+            if_exists: false,
+            include_owner_privileges: false,
+            include_comments: false,
+            prefer_pg_dump: false,
+            export_method: Some("driver".to_string()),
+            pg_dump_path: None,
+        };
+        if let Ok(native) = DdlExportDriver::export_ddl(source.driver, &options).await {
+            if !native.trim().is_empty() {
+                return Ok(TableDdl {
+                    native,
+                    mapped: Some(mapped),
+                });
+            }
+        }
+    }
+    Ok(TableDdl {
+        native: mapped,
+        mapped: None,
+    })
+}
+
+/// Schema of the target connection that unqualified names resolve to.
+fn default_schema(spec: &DriverSpec, connection: &connection::Model) -> String {
+    match spec.name {
+        "postgres" => "public".to_string(),
+        "sqlite" => "main".to_string(),
+        _ => connection.database.clone(),
+    }
+}
+
+/// Writes the streamed rows of one table and returns how many were written.
+async fn write_rows(
+    writer: &TableWriter<'_>,
+    mut rx: mpsc::Receiver<QueryStreamEvent>,
+    meter: &mut TransferMeter<'_>,
+) -> Result<u64> {
+    let mut positions: Vec<Option<usize>> = Vec::new();
+    let mut written = 0;
+    while let Some(event) = rx.recv().await {
+        match event {
+            QueryStreamEvent::Meta { columns, .. } => {
+                positions = writer
+                    .fields
+                    .iter()
+                    .map(|field| columns.iter().position(|column| column == field))
+                    .collect();
+            }
+            QueryStreamEvent::Rows { rows } => {
+                let rows: Vec<Vec<Value>> = rows
+                    .into_iter()
+                    .map(|row| {
+                        positions
+                            .iter()
+                            .zip(&writer.booleans)
+                            .map(|(position, boolean)| {
+                                let value = position
+                                    .and_then(|i| row.get(i).cloned())
+                                    .unwrap_or(Value::Null);
+                                if *boolean {
+                                    transfer::boolean_value(value)
+                                } else {
+                                    value
+                                }
+                            })
+                            .collect()
+                    })
+                    .collect();
+                written += write_batch(writer, &rows).await?;
+                meter.tick(written, false);
+            }
+            QueryStreamEvent::Progress { .. } => {}
+            QueryStreamEvent::Done { .. } => break,
+            QueryStreamEvent::Error { message } => return Err(anyhow!(message)),
+        }
+    }
+    meter.tick(written, true);
+    Ok(written)
+}
+
+async fn write_batch(writer: &TableWriter<'_>, rows: &[Vec<Value>]) -> Result<u64> {
+    if rows.is_empty() {
+        return Ok(0);
+    }
+    if writer.mode != TransferMode::Upsert {
+        BulkOperationsDriver::bulk_insert(
+            writer.driver,
+            writer.schema,
+            writer.table,
+            &writer.columns,
+            rows,
+            &|_| {},
+        )
+        .await?;
+        return Ok(rows.len() as u64);
+    }
+
+    let table_ref = bulk::table_ref(writer.dialect, writer.schema, writer.table)?;
+    let rows_per_statement = (transfer::max_params(writer.engine) / writer.columns.len()).max(1);
+    for chunk in rows.chunks(rows_per_statement) {
+        let sql = transfer::upsert_sql(
+            writer.engine,
+            writer.dialect,
+            &table_ref,
+            &writer.columns,
+            &writer.keys,
+            chunk.len(),
+        )?;
+        let params = QueryParams::Positional(chunk.iter().flatten().cloned().collect());
+        PreparedStatementDriver::execute_with_params(writer.driver, &sql, &params).await?;
+    }
+    Ok(rows.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::transfer::{TransferSource, TransferTarget};
+    use sea_orm::{ActiveModelTrait, IntoActiveModel};
+    use sqlx::sqlite::SqlitePool;
+    use sqlx::Executor;
+    use std::path::Path;
+    use uuid::Uuid;
+
+    fn sqlite_connection(path: &Path) -> connection::Model {
+        connection::Model {
+            id: Uuid::new_v4(),
+            name: "transfer".to_string(),
+            db_type: "sqlite".to_string(),
+            host: String::new(),
+            port: 0,
+            database: path.to_string_lossy().into_owned(),
+            username: String::new(),
+            password: String::new(),
+            ssl: false,
+            ssl_cert: None,
+            ssl_mode: None,
+            ssl_ca_file: None,
+            ssl_key_file: None,
+            ssl_cert_file: None,
+            status_color: None,
+            tags: None,
+            ssh_enabled: false,
+            ssh_host: None,
+            ssh_port: None,
+            ssh_user: None,
+            ssh_auth_type: None,
+            ssh_password: None,
+            ssh_key_file: None,
+            ssh_key_passphrase: None,
+            is_read_only: false,
+            environment: "development".to_string(),
+            safe_mode_level: 1,
+            statement_timeout_ms: None,
+            max_result_rows: None,
+            max_result_bytes: None,
+            last_used: None,
+            created_at: chrono::Utc::now().into(),
+            updated_at: chrono::Utc::now().into(),
+        }
+    }
+
+    async fn sqlite_file(path: &Path, script: &str) -> SqlitePool {
+        let url = format!("sqlite://{}?mode=rwc", path.display());
+        let pool = SqlitePool::connect(&url).await.unwrap();
+        pool.execute(script).await.unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn missing_source_table_leaves_the_target_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = crate::init_database(&format!(
+            "sqlite://{}?mode=rwc",
+            dir.path().join("app.db").display()
+        ))
+        .await
+        .unwrap();
+        sqlite_file(
+            &dir.path().join("source.db"),
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+             INSERT INTO users VALUES (1, 'Ann');",
+        )
+        .await;
+        let target = sqlite_file(
+            &dir.path().join("target.db"),
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+             CREATE TABLE orders (id INTEGER PRIMARY KEY);
+             INSERT INTO users VALUES (7, 'Bob');
+             INSERT INTO orders VALUES (8);",
+        )
+        .await;
+
+        let source_model = sqlite_connection(&dir.path().join("source.db"));
+        let target_model = sqlite_connection(&dir.path().join("target.db"));
+        let request = TransferRequest {
+            source: TransferSource {
+                connection_id: source_model.id,
+                schema: "main".to_string(),
+                tables: vec!["users".to_string(), "orders".to_string()],
+                filter: None,
+            },
+            target: TransferTarget {
+                connection_id: target_model.id,
+                schema: None,
+            },
+            mode: TransferMode::Truncate,
+            create_tables: true,
+            batch_size: None,
+        };
+        source_model.into_active_model().insert(&db).await.unwrap();
+        target_model.into_active_model().insert(&db).await.unwrap();
+
+        let service = ConnectionService::new(db).unwrap();
+        let err = service.transfer_tables(&request, None).await.unwrap_err();
+        assert_eq!(err.to_string(), "Table orders not found in the source");
+
+        let users: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&target)
+            .await
+            .unwrap();
+        let orders: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM orders")
+            .fetch_one(&target)
+            .await
+            .unwrap();
+        assert_eq!((users, orders), (1, 1));
+    }
+}
@@ -1,4 +1,7 @@
 use super::ConnectionService;
+use crate::services::db_driver::DatabaseDriver;
+use crate::services::driver::capabilities::DriverCapability;
+use crate::services::driver_factory;
 use anyhow::Result;
 use uuid::Uuid;
 
@@ -9,32 +12,11 @@ impl ConnectionService {
         schema: &str,
     ) -> Result<Vec<crate::services::db_driver::ViewInfo>> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::db_driver::DatabaseDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                driver.list_views(schema).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                driver.list_views(schema).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                driver.list_views(schema).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                driver.list_views(schema).await
-            }
-            _ => Ok(vec![]),
+        if !driver_factory::spec_for(&connection.db_type)?.supports(DriverCapability::Views) {
+            return Ok(vec![]);
         }
+        let driver = self.driver(&connection, &password).await?;
+        DatabaseDriver::list_views(&*driver, schema).await
     }
 
     pub async fn get_view_definition(
@@ -44,31 +26,8 @@ impl ConnectionService {
         view_name: &str,
     ) -> Result<crate::services::db_driver::ViewInfo> {
         let (connection, password) = self.get_connection_with_password(connection_id).await?;
-
-        use crate::services::db_driver::DatabaseDriver;
-        use crate::services::postgres_driver::PostgresDriver;
-
-        match connection.db_type.as_str() {
-            "postgres" | "cockroachdb" | "cockroach" => {
-                let driver = PostgresDriver::new(&connection, &password).await?;
-                driver.get_view_definition(schema, view_name).await
-            }
-            "sqlite" => {
-                let driver = self.sqlite_driver(&connection, &password).await?;
-                driver.get_view_definition(schema, view_name).await
-            }
-            "clickhouse" => {
-                let driver =
-                    crate::services::clickhouse::ClickHouseDriver::new(&connection, &password)
-                        .await?;
-                driver.get_view_definition(schema, view_name).await
-            }
-            "mysql" | "mariadb" | "tidb" => {
-                let driver =
-                    crate::services::mysql::MySqlDriver::from_model(&connection, &password).await?;
-                driver.get_view_definition(schema, view_name).await
-            }
-            _ => Err(anyhow::anyhow!("Unsupported database type")),
-        }
+        driver_factory::spec_for(&connection.db_type)?.require(DriverCapability::Views)?;
+        let driver = self.driver(&connection, &password).await?;
+        DatabaseDriver::get_view_definition(&*driver, schema, view_name).await
     }
 }
@@ -1,5 +1,5 @@
 use crate::models::entities::connection as ConnectionModel;
-use crate::services::driver::ConnectionDriver;
+use crate::services::driver::{ConnectionDriver, ConnectionPoolDriver};
 use anyhow::Result;
 use async_trait::async_trait;
 use couchbase::authenticator::PasswordAuthenticator;
@@ -62,6 +62,23 @@ impl CouchbaseDriver {
     }
 }
 
+#[async_trait]
+impl ConnectionPoolDriver for CouchbaseDriver {
+    async fn get_pool_size(&self) -> Result<usize> {
+        Err(anyhow::anyhow!(
+            "The Couchbase SDK does not expose pool statistics"
+        ))
+    }
+
+    async fn get_active_connections(&self) -> Result<usize> {
+        self.get_pool_size().await
+    }
+
+    async fn get_idle_connections(&self) -> Result<usize> {
+        self.get_pool_size().await
+    }
+}
+
 #[async_trait]
 impl ConnectionDriver for CouchbaseDriver {
     async fn test_connection(&self) -> Result<()> {
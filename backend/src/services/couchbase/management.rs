@@ -76,3 +76,18 @@ impl DatabaseManagementDriver for CouchbaseDriver {
         ))
     }
 }
+
+#[async_trait]
+impl crate::services::driver::DdlExportDriver for CouchbaseDriver {}
+
+#[async_trait]
+impl crate::services::driver::TransactionDriver for CouchbaseDriver {}
+
+#[async_trait]
+impl crate::services::driver::PreparedStatementDriver for CouchbaseDriver {}
+
+#[async_trait]
+impl crate::services::driver::BulkOperationsDriver for CouchbaseDriver {}
+
+#[async_trait]
+impl crate::services::driver::DumpDriver for CouchbaseDriver {}
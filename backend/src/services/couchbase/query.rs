@@ -1,7 +1,9 @@
 use crate::services::db_driver::QueryResult;
 use crate::services::driver::QueryDriver;
+use crate::services::query_limits;
 use anyhow::Result;
 use async_trait::async_trait;
+use couchbase::options::query_options::QueryOptions;
 use futures_util::stream::TryStreamExt;
 use serde_json::Value;
 
@@ -76,6 +78,7 @@ impl QueryDriver for CouchbaseDriver {
                 execution_time_ms: None,
                 json: None,
                 display_mode: None,
+                truncated: None,
             });
         }
 
@@ -87,17 +90,63 @@ impl QueryDriver for CouchbaseDriver {
         // Execute the last statement and return its result
         let last_stmt = &statements[statements.len() - 1];
 
+        self.run_statement(last_stmt, None).await
+    }
+
+    async fn execute_script(&self, script: &str) -> Result<u64> {
+        let statements = split_query(script);
+        let mut total_affected = 0;
+        for stmt in statements {
+            total_affected += self.execute(&stmt).await?;
+        }
+        Ok(total_affected)
+    }
+
+    async fn explain(&self, query: &str, _analyze: bool) -> Result<serde_json::Value> {
+        let explain_stmt = format!("EXPLAIN {}", query);
         let mut result = self
             .cluster
-            .query(last_stmt, None)
+            .query(explain_stmt, None)
             .await
-            .map_err(|e| super::normalize_error(e, "Query failed"))?;
+            .map_err(|e| anyhow::anyhow!("Explain failed: {}", e))?;
 
         let rows: Vec<Value> = result
             .rows::<Value>()
             .try_collect()
             .await
-            .map_err(|e| anyhow::anyhow!("Failed to decode rows: {}", e))?;
+            .map_err(|e| anyhow::anyhow!("Failed to fetch explain rows: {}", e))?;
+        Ok(serde_json::json!(rows))
+    }
+}
+
+impl CouchbaseDriver {
+    /// Runs one N1QL statement and shapes its rows into a grid.
+    pub(super) async fn run_statement(
+        &self,
+        statement: &str,
+        options: Option<QueryOptions>,
+    ) -> Result<QueryResult> {
+        let mut result = self
+            .cluster
+            .query(statement, options)
+            .await
+            .map_err(|e| super::normalize_error(e, "Query failed"))?;
+
+        let mut budget = query_limits::read_budget();
+        let mut truncated = None;
+        let mut rows: Vec<Value> = Vec::new();
+        let mut stream = result.rows::<Value>();
+        while let Some(row) = stream
+            .try_next()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to decode rows: {}", e))?
+        {
+            if !budget.admit(std::slice::from_ref(&row)) {
+                truncated = Some(true);
+                break;
+            }
+            rows.push(row);
+        }
 
         let rows_clone = rows.clone();
 
@@ -206,31 +255,7 @@ impl QueryDriver for CouchbaseDriver {
             execution_time_ms: None,
             json: Some(Value::Array(rows_clone)),
             display_mode: Some("table".to_string()),
+            truncated,
         })
     }
-
-    async fn execute_script(&self, script: &str) -> Result<u64> {
-        let statements = split_query(script);
-        let mut total_affected = 0;
-        for stmt in statements {
-            total_affected += self.execute(&stmt).await?;
-        }
-        Ok(total_affected)
-    }
-
-    async fn explain(&self, query: &str, _analyze: bool) -> Result<serde_json::Value> {
-        let explain_stmt = format!("EXPLAIN {}", query);
-        let mut result = self
-            .cluster
-            .query(explain_stmt, None)
-            .await
-            .map_err(|e| anyhow::anyhow!("Explain failed: {}", e))?;
-
-        let rows: Vec<Value> = result
-            .rows::<Value>()
-            .try_collect()
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to fetch explain rows: {}", e))?;
-        Ok(serde_json::json!(rows))
-    }
 }
@@ -1,11 +1,13 @@
 use super::connection::CouchbaseDriver;
 use crate::services::db_driver::*;
 use crate::services::driver::{
-    ColumnManagement, FunctionOperations, QueryDriver, TableOperations, ViewOperations,
+    ColumnManagement, FunctionOperations, TableOperations, ViewOperations,
 };
+use crate::services::table_filter::{self, FilterNode, SortSpec, SqlDialect};
 use anyhow::Result;
 use async_trait::async_trait;
 use couchbase::options::kv_options::{MutateInOptions, RemoveOptions};
+use couchbase::options::query_options::QueryOptions;
 use couchbase::subdoc::mutate_in_specs::MutateInSpec;
 use serde_json::Value;
 use tokio::time::{timeout, Duration};
@@ -56,7 +58,8 @@ impl TableOperations for CouchbaseDriver {
         table: &str,
         limit: i64,
         offset: i64,
-        filter: Option<String>,
+        filter: Option<FilterNode>,
+        sort: Vec<SortSpec>,
         document_id: Option<String>,
         fields: Option<Vec<String>>,
     ) -> Result<QueryResult> {
@@ -103,16 +106,18 @@ impl TableOperations for CouchbaseDriver {
             )
         };
 
-        if let Some(f) = filter {
-            if !f.trim().is_empty() {
-                query.push_str(" WHERE ");
-                query.push_str(&f);
-            }
-        }
-
+        let where_clause = table_filter::where_clause(filter.as_ref(), SqlDialect::N1QL)?;
+        query.push_str(&where_clause.sql);
+        query.push_str(&table_filter::order_by_clause(&sort, SqlDialect::N1QL)?);
         query.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
 
-        QueryDriver::execute_query(self, &query).await
+        let mut options = QueryOptions::new();
+        for value in where_clause.params {
+            options = options
+                .add_positional_parameter(value)
+                .map_err(|e| super::normalize_error(e, "Invalid filter value"))?;
+        }
+        self.run_statement(&query, Some(options)).await
     }
 
     async fn get_table_constraints(&self, _schema: &str, _table: &str) -> Result<TableConstraints> {
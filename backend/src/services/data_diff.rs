@@ -0,0 +1,721 @@
+use crate::services::sql_template;
+use crate::services::table_filter::SqlDialect;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Differences listed, and synced, per comparison at most.
+pub const DATA_DIFF_MAX_ROWS: usize = 10_000;
+/// Rows per chunk when comparing chunk hashes.
+pub const DATA_DIFF_CHUNK_SIZE: usize = 10_000;
+/// Source rows from which `Auto` compares chunk hashes, where it can.
+pub const CHUNK_HASH_MIN_ROWS: u64 = 100_000;
+
+/// How the rows of both tables are compared.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataDiffStrategy {
+    /// Chunk hashes for large tables on the same engine, streaming otherwise.
+    #[default]
+    Auto,
+    /// Read every row of both tables, ordered by key.
+    Stream,
+    /// Hash chunks of rows on both sides and read only the chunks that
+    /// differ. Both tables must be on Postgres, or both on MySQL.
+    ChunkHash,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DataDiffTable {
+    pub connection_id: Uuid,
+    #[serde(default)]
+    pub schema: String,
+    pub table: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DataDiffRequest {
+    pub source: DataDiffTable,
+    pub target: DataDiffTable,
+    /// Columns identifying a row on both sides; the source's primary key
+    /// when empty.
+    #[serde(default)]
+    pub key_columns: Vec<String>,
+    /// Columns to compare; all those both tables have when unset.
+    #[serde(default)]
+    pub columns: Option<Vec<String>>,
+    #[serde(default)]
+    pub strategy: DataDiffStrategy,
+    #[serde(default)]
+    pub chunk_size: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValueChange {
+    pub column: String,
+    pub source: Value,
+    pub target: Value,
+}
+
+/// A row to change in the target for it to match the source. Keys and
+/// values are named as in the target.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RowDiff {
+    /// Only in the source.
+    Inserted {
+        key: Map<String, Value>,
+        values: Map<String, Value>,
+    },
+    /// In both, with different values.
+    Updated {
+        key: Map<String, Value>,
+        changes: Vec<ValueChange>,
+    },
+    /// Only in the target.
+    Deleted { key: Map<String, Value> },
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DataDiffReport {
+    pub key_columns: Vec<String>,
+    pub columns: Vec<String>,
+    /// The strategy used, never `Auto`.
+    pub strategy: DataDiffStrategy,
+    pub source_rows: u64,
+    pub target_rows: u64,
+    pub inserted: u64,
+    pub updated: u64,
+    pub deleted: u64,
+    pub unchanged: u64,
+    /// The differences, up to `DATA_DIFF_MAX_ROWS`.
+    pub rows: Vec<RowDiff>,
+    /// More rows differ than are listed; the counts cover all of them.
+    pub truncated: bool,
+    /// Statements making the target match the source, for the listed rows.
+    pub sync_sql: Vec<String>,
+    pub execution_time_ms: u64,
+}
+
+/// A row waiting for the row with the same key on the other side, with the
+/// order it arrived in.
+type Pending = HashMap<Vec<String>, (u64, Vec<Value>)>;
+
+/// Pairs the rows of both tables by key and records how they differ. Rows
+/// may arrive in any order: each waits until the other side's row with the
+/// same key arrives, so reading both sides in key order keeps few waiting.
+pub struct RowMatcher {
+    columns: Vec<String>,
+    key_positions: Vec<usize>,
+    source: Pending,
+    target: Pending,
+    /// Key of the row added last on each side, to catch duplicates of a key
+    /// that was already paired when rows arrive in key order.
+    last_source_key: Option<Vec<String>>,
+    last_target_key: Option<Vec<String>>,
+    arrived: u64,
+    report: DataDiffReport,
+}
+
+impl RowMatcher {
+    /// Rows are given with the values of `columns`, in that order; the key
+    /// columns are among them.
+    pub fn new(columns: Vec<String>, key_columns: Vec<String>) -> Result<Self> {
+        if key_columns.is_empty() {
+            return Err(anyhow!("Comparing rows needs at least one key column"));
+        }
+        let key_positions = key_columns
+            .iter()
+            .map(|key| {
+                columns
+                    .iter()
+                    .position(|column| column == key)
+                    .ok_or_else(|| anyhow!("Key column {} is not compared", key))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            report: DataDiffReport {
+                key_columns,
+                columns: columns.clone(),
+                ..Default::default()
+            },
+            columns,
+            key_positions,
+            source: HashMap::new(),
+            target: HashMap::new(),
+            last_source_key: None,
+            last_target_key: None,
+            arrived: 0,
+        })
+    }
+
+    /// Fails when the row's key repeats one of an earlier source row.
+    pub fn add_source(&mut self, row: Vec<Value>) -> Result<()> {
+        self.report.source_rows += 1;
+        let key = self.key_of(&row);
+        if self.source.contains_key(&key) || self.last_source_key.as_ref() == Some(&key) {
+            return Err(self.duplicate_key("source", &row));
+        }
+        self.last_source_key = Some(key.clone());
+        match self.target.remove(&key) {
+            Some((_, target)) => self.compare(row, target),
+            None => {
+                self.arrived += 1;
+                self.source.insert(key, (self.arrived, row));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fails when the row's key repeats one of an earlier target row.
+    pub fn add_target(&mut self, row: Vec<Value>) -> Result<()> {
+        self.report.target_rows += 1;
+        let key = self.key_of(&row);
+        if self.target.contains_key(&key) || self.last_target_key.as_ref() == Some(&key) {
+            return Err(self.duplicate_key("target", &row));
+        }
+        self.last_target_key = Some(key.clone());
+        match self.source.remove(&key) {
+            Some((_, source)) => self.compare(source, row),
+            None => {
+                self.arrived += 1;
+                self.target.insert(key, (self.arrived, row));
+            }
+        }
+        Ok(())
+    }
+
+    fn duplicate_key(&self, side: &str, row: &[Value]) -> anyhow::Error {
+        anyhow!(
+            "Key {} appears more than once in the {} table; the key columns must identify each row",
+            Value::Object(self.key_map(row)),
+            side
+        )
+    }
+
+    /// Counts rows found equal on both sides without reading them.
+    pub fn add_unchanged(&mut self, rows: u64) {
+        self.report.source_rows += rows;
+        self.report.target_rows += rows;
+        self.report.unchanged += rows;
+    }
+
+    /// Rows still waiting have no counterpart: source rows are inserted and
+    /// target rows deleted, in the order they arrived.
+    pub fn finish(mut self) -> DataDiffReport {
+        let mut left: Vec<(u64, bool, Vec<Value>)> = self
+            .source
+            .drain()
+            .map(|(_, (order, row))| (order, true, row))
+            .chain(
+                self.target
+                    .drain()
+                    .map(|(_, (order, row))| (order, false, row)),
+            )
+            .collect();
+        left.sort_by_key(|(order, _, _)| *order);
+        for (_, inserted, row) in left {
+            let key = self.key_map(&row);
+            let diff = if inserted {
+                RowDiff::Inserted {
+                    key,
+                    values: self.columns.iter().cloned().zip(row).collect(),
+                }
+            } else {
+                RowDiff::Deleted { key }
+            };
+            self.record(diff);
+        }
+        self.report
+    }
+
+    fn compare(&mut self, source: Vec<Value>, target: Vec<Value>) {
+        let changes: Vec<ValueChange> = self
+            .columns
+            .iter()
+            .zip(source.iter().zip(&target))
+            .enumerate()
+            .filter(|(i, (_, (a, b)))| !self.key_positions.contains(i) && !values_equal(a, b))
+            .map(|(_, (column, (a, b)))| ValueChange {
+                column: column.clone(),
+                source: a.clone(),
+                target: b.clone(),
+            })
+            .collect();
+        if changes.is_empty() {
+            self.report.unchanged += 1;
+        } else {
+            let key = self.key_map(&target);
+            self.record(RowDiff::Updated { key, changes });
+        }
+    }
+
+    fn record(&mut self, diff: RowDiff) {
+        match diff {
+            RowDiff::Inserted { .. } => self.report.inserted += 1,
+            RowDiff::Updated { .. } => self.report.updated += 1,
+            RowDiff::Deleted { .. } => self.report.deleted += 1,
+        }
+        if self.report.rows.len() < DATA_DIFF_MAX_ROWS {
+            self.report.rows.push(diff);
+        } else {
+            self.report.truncated = true;
+        }
+    }
+
+    fn key_of(&self, row: &[Value]) -> Vec<String> {
+        self.key_positions
+            .iter()
+            .map(|&i| key_text(row.get(i).unwrap_or(&Value::Null)))
+            .collect()
+    }
+
+    fn key_map(&self, row: &[Value]) -> Map<String, Value> {
+        self.key_positions
+            .iter()
+            .map(|&i| {
+                (
+                    self.columns[i].clone(),
+                    row.get(i).cloned().unwrap_or(Value::Null),
+                )
+            })
+            .collect()
+    }
+}
+
+/// Text identifying a key value. Numbers and booleans are written the same
+/// whatever the engine returned (decimals as strings, booleans as `1`/`0`),
+/// so they match across engines; strings are kept exactly.
+fn key_text(value: &Value) -> String {
+    match value {
+        Value::Null => "\u{0}NULL".to_string(),
+        Value::Bool(b) => (if *b { "1" } else { "0" }).to_string(),
+        Value::Number(n) => canonical_number(&n.to_string()),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// The value as a number, for comparing it with a JSON number or boolean.
+fn as_number(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(b) => Some((if *b { "1" } else { "0" }).to_string()),
+        Value::Number(n) => Some(canonical_number(&n.to_string())),
+        Value::String(s) if is_decimal(s) => Some(canonical_number(s)),
+        _ => None,
+    }
+}
+
+fn canonical_number(number: &str) -> String {
+    let number = number.trim();
+    let (sign, digits) = match number.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", number),
+    };
+    if digits.contains(['e', 'E']) {
+        return number
+            .parse::<f64>()
+            .map_or(number.to_string(), |f| f.to_string());
+    }
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    let whole = whole.trim_start_matches('0');
+    let fraction = fraction.trim_end_matches('0');
+    let whole = if whole.is_empty() { "0" } else { whole };
+    if whole == "0" && fraction.is_empty() {
+        "0".to_string()
+    } else if fraction.is_empty() {
+        format!("{}{}", sign, whole)
+    } else {
+        format!("{}{}.{}", sign, whole, fraction)
+    }
+}
+
+fn is_decimal(s: &str) -> bool {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, "0"));
+    !whole.is_empty()
+        && !fraction.is_empty()
+        && whole.bytes().all(|b| b.is_ascii_digit())
+        && fraction.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Whether two values are the same, allowing for how engines return them:
+/// a number or boolean equals a string holding the same number. Strings are
+/// compared to strings exactly.
+pub fn values_equal(a: &Value, b: &Value) -> bool {
+    if a == b {
+        return true;
+    }
+    let numeric = |v: &Value| matches!(v, Value::Number(_) | Value::Bool(_));
+    if !numeric(a) && !numeric(b) {
+        return false;
+    }
+    match (as_number(a), as_number(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Statements applying `rows` to the target: deletes, then updates, then
+/// inserts, with values written inline. `no_backslash_escapes` is whether
+/// the target is a MySQL session with `NO_BACKSLASH_ESCAPES` set.
+pub fn sync_statements(
+    dialect: SqlDialect,
+    no_backslash_escapes: bool,
+    table_ref: &str,
+    columns: &[String],
+    rows: &[RowDiff],
+) -> Result<Vec<String>> {
+    let mut deletes = Vec::new();
+    let mut updates = Vec::new();
+    let mut inserts = Vec::new();
+    for row in rows {
+        match row {
+            RowDiff::Deleted { key } => deletes.push(format!(
+                "DELETE FROM {} WHERE {};",
+                table_ref,
+                key_condition(dialect, no_backslash_escapes, key)?
+            )),
+            RowDiff::Updated { key, changes } => {
+                let assignments = changes
+                    .iter()
+                    .map(|change| {
+                        Ok(format!(
+                            "{} = {}",
+                            dialect.quote_ident(&change.column)?,
+                            sql_template::literal_in_mode(
+                                &change.source,
+                                dialect,
+                                no_backslash_escapes
+                            )
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                updates.push(format!(
+                    "UPDATE {} SET {} WHERE {};",
+                    table_ref,
+                    assignments.join(", "),
+                    key_condition(dialect, no_backslash_escapes, key)?
+                ));
+            }
+            RowDiff::Inserted { values, .. } => {
+                let names = columns
+                    .iter()
+                    .map(|column| dialect.quote_ident(column))
+                    .collect::<Result<Vec<_>>>()?;
+                let values: Vec<String> = columns
+                    .iter()
+                    .map(|column| {
+                        sql_template::literal_in_mode(
+                            values.get(column).unwrap_or(&Value::Null),
+                            dialect,
+                            no_backslash_escapes,
+                        )
+                    })
+                    .collect();
+                inserts.push(format!(
+                    "INSERT INTO {} ({}) VALUES ({});",
+                    table_ref,
+                    names.join(", "),
+                    values.join(", ")
+                ));
+            }
+        }
+    }
+    deletes.extend(updates);
+    deletes.extend(inserts);
+    Ok(deletes)
+}
+
+fn key_condition(
+    dialect: SqlDialect,
+    no_backslash_escapes: bool,
+    key: &Map<String, Value>,
+) -> Result<String> {
+    let terms = key
+        .iter()
+        .map(|(column, value)| {
+            let column = dialect.quote_ident(column)?;
+            Ok(match value {
+                Value::Null => format!("{} IS NULL", column),
+                value => format!(
+                    "{} = {}",
+                    column,
+                    sql_template::literal_in_mode(value, dialect, no_backslash_escapes)
+                ),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(terms.join(" AND "))
+}
+
+/// Engines whose chunks of rows can be hashed in SQL.
+pub fn supports_chunk_hash(engine: &str) -> bool {
+    matches!(engine, "postgres" | "mysql")
+}
+
+/// Rows with keys after `after` and up to `until`; unbounded where unset.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeyRange<'a> {
+    pub after: Option<&'a [Value]>,
+    pub until: Option<&'a [Value]>,
+}
+
+/// The keys of every `chunk_size`th row in key order, which end the chunks
+/// but the last.
+pub fn chunk_bounds_sql(
+    dialect: SqlDialect,
+    table_ref: &str,
+    keys: &[String],
+    chunk_size: usize,
+) -> Result<String> {
+    let keys = quote_all(dialect, keys)?.join(", ");
+    Ok(format!(
+        "SELECT {keys} FROM (SELECT {keys}, ROW_NUMBER() OVER (ORDER BY {keys}) AS dbplus_row \
+         FROM {table}) AS numbered WHERE dbplus_row % {chunk} = 0 ORDER BY {keys}",
+        keys = keys,
+        table = table_ref,
+        chunk = chunk_size.max(1)
+    ))
+}
+
+/// The row count and a hash of the `columns` of the rows in `range`.
+pub fn chunk_hash_sql(
+    engine: &str,
+    dialect: SqlDialect,
+    no_backslash_escapes: bool,
+    table_ref: &str,
+    columns: &[String],
+    keys: &[String],
+    range: KeyRange<'_>,
+) -> Result<String> {
+    let columns = quote_all(dialect, columns)?;
+    let hash = match engine {
+        "postgres" => format!(
+            "md5(coalesce(string_agg(md5(ROW({})::text), '' ORDER BY {}), ''))",
+            columns.join(", "),
+            quote_all(dialect, keys)?.join(", ")
+        ),
+        // `CONCAT_WS` skips NULLs, so each value is followed by whether it
+        // is one.
+        "mysql" => format!(
+            "COALESCE(SUM(CRC32(CONCAT_WS('|', {}))), 0)",
+            columns
+                .iter()
+                .map(|column| format!("{}, ISNULL({})", column, column))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        other => return Err(anyhow!("Chunk hashes are not supported on {}", other)),
+    };
+    Ok(format!(
+        "SELECT COUNT(*), {} FROM {}{}",
+        hash,
+        table_ref,
+        range_condition(dialect, no_backslash_escapes, keys, range)?
+    ))
+}
+
+/// The `columns` of the rows in `range`, in key order.
+pub fn chunk_rows_sql(
+    dialect: SqlDialect,
+    no_backslash_escapes: bool,
+    table_ref: &str,
+    columns: &[String],
+    keys: &[String],
+    range: KeyRange<'_>,
+) -> Result<String> {
+    Ok(format!(
+        "SELECT {} FROM {}{} ORDER BY {}",
+        quote_all(dialect, columns)?.join(", "),
+        table_ref,
+        range_condition(dialect, no_backslash_escapes, keys, range)?,
+        quote_all(dialect, keys)?.join(", ")
+    ))
+}
+
+fn range_condition(
+    dialect: SqlDialect,
+    no_backslash_escapes: bool,
+    keys: &[String],
+    range: KeyRange<'_>,
+) -> Result<String> {
+    let keys = format!("({})", quote_all(dialect, keys)?.join(", "));
+    let tuple = |values: &[Value]| {
+        let values: Vec<String> = values
+            .iter()
+            .map(|value| sql_template::literal_in_mode(value, dialect, no_backslash_escapes))
+            .collect();
+        format!("({})", values.join(", "))
+    };
+    let mut terms = Vec::new();
+    if let Some(after) = range.after {
+        terms.push(format!("{} > {}", keys, tuple(after)));
+    }
+    if let Some(until) = range.until {
+        terms.push(format!("{} <= {}", keys, tuple(until)));
+    }
+    Ok(if terms.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", terms.join(" AND "))
+    })
+}
+
+fn quote_all(dialect: SqlDialect, names: &[String]) -> Result<Vec<String>> {
+    names.iter().map(|name| dialect.quote_ident(name)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row(values: Value) -> Vec<Value> {
+        values.as_array().unwrap().clone()
+    }
+
+    #[test]
+    fn matches_rows_by_key_across_engines() {
+        let mut matcher = RowMatcher::new(
+            vec!["id".into(), "name".into(), "price".into()],
+            vec!["id".into()],
+        )
+        .unwrap();
+        matcher.add_source(row(json!([1, "a", "1.50"]))).unwrap();
+        matcher.add_source(row(json!([2, "b", "2.00"]))).unwrap();
+        matcher.add_source(row(json!([3, "c", "3.00"]))).unwrap();
+        matcher.add_target(row(json!(["1", "a", 1.5]))).unwrap();
+        matcher.add_target(row(json!([2, "B", 2]))).unwrap();
+        matcher.add_target(row(json!([4, "d", 4]))).unwrap();
+        let report = matcher.finish();
+
+        assert_eq!(
+            (
+                report.inserted,
+                report.updated,
+                report.deleted,
+                report.unchanged
+            ),
+            (1, 1, 1, 1)
+        );
+        assert_eq!(
+            report.rows[0],
+            RowDiff::Updated {
+                key: Map::from_iter([("id".to_string(), json!(2))]),
+                changes: vec![ValueChange {
+                    column: "name".into(),
+                    source: json!("b"),
+                    target: json!("B"),
+                }],
+            }
+        );
+
+        let sql = sync_statements(
+            SqlDialect::MYSQL,
+            false,
+            "`items`",
+            &report.columns,
+            &report.rows,
+        )
+        .unwrap();
+        assert_eq!(
+            sql,
+            vec![
+                "DELETE FROM `items` WHERE `id` = 4;",
+                "UPDATE `items` SET `name` = 'b' WHERE `id` = 2;",
+                "INSERT INTO `items` (`id`, `name`, `price`) VALUES (3, 'c', '3.00');",
+            ]
+        );
+    }
+
+    #[test]
+    fn writes_backslashes_the_way_the_mysql_session_reads_them() {
+        let rows = vec![RowDiff::Inserted {
+            key: Map::from_iter([("id".to_string(), json!(1))]),
+            values: Map::from_iter([
+                ("id".to_string(), json!(1)),
+                ("path".to_string(), json!("C:\\tmp\\it's")),
+            ]),
+        }];
+        let columns = vec!["id".to_string(), "path".to_string()];
+
+        let sql = sync_statements(SqlDialect::MYSQL, false, "`t`", &columns, &rows).unwrap();
+        assert_eq!(
+            sql,
+            vec!["INSERT INTO `t` (`id`, `path`) VALUES (1, 'C:\\\\tmp\\\\it''s');"]
+        );
+        let sql = sync_statements(SqlDialect::MYSQL, true, "`t`", &columns, &rows).unwrap();
+        assert_eq!(
+            sql,
+            vec!["INSERT INTO `t` (`id`, `path`) VALUES (1, 'C:\\tmp\\it''s');"]
+        );
+    }
+
+    #[test]
+    fn compares_numbers_loosely_and_strings_exactly() {
+        assert!(values_equal(&json!(true), &json!(1)));
+        assert!(values_equal(&json!("007.10"), &json!(7.1)));
+        assert!(!values_equal(&json!(null), &json!("")));
+        assert!(!values_equal(&json!("1.5"), &json!(15)));
+        assert!(!values_equal(&json!("1.0"), &json!("1")));
+        assert!(!values_equal(&json!("007"), &json!("7")));
+        assert!(!values_equal(
+            &json!("2024-01-02T03:04:05"),
+            &json!("2024-01-02 03:04:05")
+        ));
+    }
+
+    #[test]
+    fn keeps_string_keys_apart_and_rejects_duplicates() {
+        let mut matcher =
+            RowMatcher::new(vec!["code".into(), "n".into()], vec!["code".into()]).unwrap();
+        matcher.add_source(row(json!(["004", 1]))).unwrap();
+        matcher.add_source(row(json!(["4", 2]))).unwrap();
+        matcher.add_target(row(json!(["4", 2]))).unwrap();
+        let report = matcher.finish();
+        assert_eq!((report.inserted, report.unchanged), (1, 1));
+
+        let mut matcher =
+            RowMatcher::new(vec!["code".into(), "n".into()], vec!["code".into()]).unwrap();
+        matcher.add_source(row(json!(["a", 1]))).unwrap();
+        matcher.add_target(row(json!(["a", 1]))).unwrap();
+        assert!(matcher.add_source(row(json!(["a", 2]))).is_err());
+        matcher.add_target(row(json!(["b", 1]))).unwrap();
+        assert!(matcher.add_target(row(json!(["b", 2]))).is_err());
+    }
+
+    #[test]
+    fn builds_chunk_queries() {
+        let keys = vec!["id".to_string()];
+        let columns = vec!["id".to_string(), "name".to_string()];
+        let bound = [json!(100)];
+        let range = KeyRange {
+            after: Some(&bound),
+            until: None,
+        };
+        assert_eq!(
+            chunk_hash_sql("postgres", SqlDialect::POSTGRES, false, "\"t\"", &columns, &keys, range)
+                .unwrap(),
+            "SELECT COUNT(*), md5(coalesce(string_agg(md5(ROW(\"id\", \"name\")::text), '' ORDER BY \"id\"), '')) FROM \"t\" WHERE (\"id\") > (100)"
+        );
+        assert_eq!(
+            chunk_hash_sql("mysql", SqlDialect::MYSQL, false, "`t`", &columns, &keys, range).unwrap(),
+            "SELECT COUNT(*), COALESCE(SUM(CRC32(CONCAT_WS('|', `id`, ISNULL(`id`), `name`, ISNULL(`name`)))), 0) FROM `t` WHERE (`id`) > (100)"
+        );
+        assert_eq!(
+            chunk_rows_sql(
+                SqlDialect::MYSQL,
+                false,
+                "`t`",
+                &columns,
+                &keys,
+                KeyRange::default()
+            )
+            .unwrap(),
+            "SELECT `id`, `name` FROM `t` ORDER BY `id`"
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::services::driver::SessionOperations;
+use crate::services::table_filter::{FilterNode, SortSpec};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -59,6 +60,10 @@ pub struct QueryResult {
     pub json: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub display_mode: Option<String>,
+    /// Set when rows were dropped to stay within the connection's
+    /// `max_result_rows` or `max_result_bytes`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub truncated: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -286,11 +291,16 @@ pub struct SessionInfo {
     pub state_change: Option<String>,
 }
 
+use crate::services::driver::extension::DatabaseManagementDriver;
 use crate::services::driver::{
-    ColumnManagement, ConnectionDriver, FunctionOperations, NoSQLOperations, QueryDriver,
-    SchemaIntrospection, TableOperations, ViewOperations,
+    BulkOperationsDriver, ColumnManagement, ConnectionDriver, ConnectionPoolDriver,
+    DdlExportDriver, DumpDriver, FunctionOperations, NoSQLOperations, PreparedStatementDriver,
+    QueryDriver, SchemaIntrospection, TableOperations, TransactionDriver, ViewOperations,
 };
 
+/// Everything `ConnectionService` needs from an engine. Drivers are built by
+/// `driver_factory` and used as `Arc<dyn DatabaseDriver>`; operations an engine
+/// lacks answer with the trait defaults (usually "not supported").
 #[async_trait]
 pub trait DatabaseDriver:
     ConnectionDriver
@@ -300,6 +310,13 @@ pub trait DatabaseDriver:
     + ColumnManagement
     + ViewOperations
     + FunctionOperations
+    + DatabaseManagementDriver
+    + DdlExportDriver
+    + ConnectionPoolDriver
+    + TransactionDriver
+    + PreparedStatementDriver
+    + BulkOperationsDriver
+    + DumpDriver
     + Send
     + Sync
 {
@@ -317,7 +334,8 @@ pub trait DatabaseDriver:
         table: &str,
         limit: i64,
         offset: i64,
-        filter: Option<String>,
+        filter: Option<FilterNode>,
+        sort: Vec<SortSpec>,
         document_id: Option<String>,
         fields: Option<Vec<String>>,
     ) -> Result<QueryResult>;
@@ -403,6 +421,13 @@ where
         + ViewOperations
         + FunctionOperations
         + SessionOperations
+        + DatabaseManagementDriver
+        + DdlExportDriver
+        + ConnectionPoolDriver
+        + TransactionDriver
+        + PreparedStatementDriver
+        + BulkOperationsDriver
+        + DumpDriver
         + Send
         + Sync,
 {
@@ -444,7 +469,8 @@ where
         table: &str,
         limit: i64,
         offset: i64,
-        filter: Option<String>,
+        filter: Option<FilterNode>,
+        sort: Vec<SortSpec>,
         document_id: Option<String>,
         fields: Option<Vec<String>>,
     ) -> Result<QueryResult> {
@@ -455,6 +481,7 @@ where
             limit,
             offset,
             filter,
+            sort,
             document_id,
             fields,
         )
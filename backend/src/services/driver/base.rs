@@ -1,4 +1,6 @@
 use crate::services::db_driver::QueryResult;
+use crate::services::query_cancel::QueryHandle;
+use crate::services::query_stream::{QueryStreamSink, StreamOptions};
 use anyhow::Result;
 use async_trait::async_trait;
 
@@ -12,8 +14,85 @@ pub trait QueryDriver: Send + Sync {
     async fn execute(&self, query: &str) -> Result<u64>;
     async fn query(&self, query: &str) -> Result<QueryResult>;
     async fn execute_query(&self, query: &str) -> Result<QueryResult>;
+    /// Like `execute_query`, but attaches a server-side cancel to `handle` while
+    /// the statement runs. Engines without one are only cancelled client-side.
+    async fn execute_query_cancellable(
+        &self,
+        query: &str,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let _ = handle;
+        self.execute_query(query).await
+    }
+    /// Sends the result to `sink` in row batches instead of buffering it, and
+    /// returns the affected row count. Stops early when the sink's consumer is
+    /// gone. The default buffers `execute_query` and replays it.
+    async fn stream_query(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+    ) -> Result<u64> {
+        let _ = options;
+        let result = self.execute_query(query).await?;
+        let affected_rows = result.affected_rows;
+        sink.send_result(result).await;
+        Ok(affected_rows)
+    }
+    /// Like `stream_query`, but attaches a server-side cancel to `handle`
+    /// while the statement runs.
+    async fn stream_query_cancellable(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+        handle: &QueryHandle,
+    ) -> Result<u64> {
+        let _ = handle;
+        self.stream_query(query, options, sink).await
+    }
+    /// A connection of its own for running statements one after another, so
+    /// that `SET`, temporary tables and the like carry over between them. It
+    /// is closed rather than returned to the pool when dropped. The default
+    /// runs each statement through `execute_query` on a pooled connection.
+    async fn open_session(&self) -> Result<Box<dyn StatementSession + '_>> {
+        Ok(Box::new(PooledSession(self)))
+    }
     /// Execute a SQL script that may contain multiple statements.
     /// Returns number of statements executed (best-effort).
     async fn execute_script(&self, script: &str) -> Result<u64>;
     async fn explain(&self, query: &str, analyze: bool) -> Result<serde_json::Value>;
 }
+
+/// Statements run in order on one connection; see `QueryDriver::open_session`.
+#[async_trait]
+pub trait StatementSession: Send {
+    async fn execute_query(&mut self, query: &str) -> Result<QueryResult>;
+    /// Like `execute_query`, but attaches a server-side cancel for the
+    /// session's connection to `handle` while the statement runs.
+    async fn execute_query_cancellable(
+        &mut self,
+        query: &str,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let _ = handle;
+        self.execute_query(query).await
+    }
+}
+
+struct PooledSession<'a, D: ?Sized>(&'a D);
+
+#[async_trait]
+impl<D: QueryDriver + ?Sized> StatementSession for PooledSession<'_, D> {
+    async fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
+        self.0.execute_query(query).await
+    }
+
+    async fn execute_query_cancellable(
+        &mut self,
+        query: &str,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        self.0.execute_query_cancellable(query, handle).await
+    }
+}
@@ -1,7 +1,12 @@
+use crate::services::db_driver::QueryResult;
+use crate::services::query_cancel::QueryHandle;
+use crate::services::query_params::QueryParams;
 use anyhow::Result;
 use async_trait::async_trait;
+use serde::Serialize;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DriverCapability {
     Schemas,
     Views,
@@ -13,6 +18,11 @@ pub enum DriverCapability {
     PreparedStatements,
     Streaming,
     BulkOperations,
+    Sessions,
+    Extensions,
+    DdlExport,
+    /// Logical dumps written by the driver itself (`services::dump`).
+    NativeDump,
 }
 
 pub trait DriverCapabilities {
@@ -22,21 +32,47 @@ pub trait DriverCapabilities {
     }
 }
 
-#[allow(dead_code)]
+/// Starts manual-commit transactions. Open transactions are tracked by
+/// `TransactionManager`, which hands out their ids.
 #[async_trait]
 pub trait TransactionDriver: Send + Sync {
-    async fn begin_transaction(&self) -> Result<String>;
-    async fn commit_transaction(&self, transaction_id: &str) -> Result<()>;
-    async fn rollback_transaction(&self, transaction_id: &str) -> Result<()>;
+    /// Takes a connection out of the pool and runs `BEGIN` on it. The
+    /// connection stays with the session until it is committed or rolled back.
+    async fn begin_transaction(&self) -> Result<Box<dyn TransactionSession>> {
+        Err(anyhow::anyhow!(
+            "Transactions are not supported for this database type"
+        ))
+    }
+}
+
+/// An open transaction on a pinned connection. Dropping a session without
+/// committing must not hand its connection back to the pool mid-transaction.
+#[async_trait]
+pub trait TransactionSession: Send {
+    async fn execute_query(&mut self, query: &str) -> Result<QueryResult>;
+    async fn commit(self: Box<Self>) -> Result<()>;
+    async fn rollback(self: Box<Self>) -> Result<()>;
 }
 
-#[allow(dead_code)]
+/// Runs queries with `$1`/`?` or `:name` placeholders. Values are bound by
+/// the driver and converted to the types the database expects for them.
 #[async_trait]
 pub trait PreparedStatementDriver: Send + Sync {
-    async fn prepare(&self, query: &str) -> Result<String>;
-    async fn execute_prepared(
+    async fn execute_with_params(&self, query: &str, params: &QueryParams) -> Result<QueryResult> {
+        let _ = (query, params);
+        Err(anyhow::anyhow!(
+            "Query parameters are not supported for this database type"
+        ))
+    }
+    /// Like `execute_with_params`, but attaches a server-side cancel to
+    /// `handle` while the statement runs.
+    async fn execute_with_params_cancellable(
         &self,
-        statement_id: &str,
-        params: &[&dyn ToString],
-    ) -> Result<crate::services::db_driver::QueryResult>;
+        query: &str,
+        params: &QueryParams,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let _ = handle;
+        self.execute_with_params(query, params).await
+    }
 }
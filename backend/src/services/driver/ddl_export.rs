@@ -1,8 +1,20 @@
 use crate::models::export_ddl::ExportDdlOptions;
+use crate::services::schema_diff::SchemaSnapshot;
 use anyhow::Result;
 use async_trait::async_trait;
 
 #[async_trait]
 pub trait DdlExportDriver: Send + Sync {
-    async fn export_ddl(&self, options: &ExportDdlOptions) -> Result<String>;
+    async fn export_ddl(&self, _options: &ExportDdlOptions) -> Result<String> {
+        Err(anyhow::anyhow!(
+            "DDL export not supported for this database type"
+        ))
+    }
+
+    /// Tables, indexes and foreign keys of `schema`, for schema comparison.
+    async fn schema_snapshot(&self, _schema: &str) -> Result<SchemaSnapshot> {
+        Err(anyhow::anyhow!(
+            "Schema comparison is only supported for PostgreSQL, MySQL and SQLite"
+        ))
+    }
 }
@@ -1,6 +1,10 @@
+use crate::services::bulk::{BulkProgressFn, RowChange};
+use crate::services::dump::DumpEvent;
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
 
 #[allow(dead_code)]
 #[async_trait]
@@ -8,7 +12,9 @@ pub trait StreamingDriver: Send + Sync {
     async fn stream_query(&self, query: &str, batch_size: usize) -> Result<Vec<Vec<Value>>>;
 }
 
-#[allow(dead_code)]
+/// Writes many rows at once through the engine's fastest path (`COPY`,
+/// multi-row `INSERT`s, `insert_many`, ...). `progress` is called with the
+/// number of rows written so far after each chunk.
 #[async_trait]
 pub trait BulkOperationsDriver: Send + Sync {
     async fn bulk_insert(
@@ -17,22 +23,87 @@ pub trait BulkOperationsDriver: Send + Sync {
         table: &str,
         columns: &[String],
         rows: &[Vec<Value>],
-    ) -> Result<u64>;
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let _ = (schema, table, columns, rows, progress);
+        Err(anyhow::anyhow!(
+            "Bulk operations are not supported for this database type"
+        ))
+    }
 
     async fn bulk_update(
         &self,
         schema: &str,
         table: &str,
-        updates: &[(String, Value)],
-        where_clause: &str,
-    ) -> Result<u64>;
+        changes: &[RowChange],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let _ = (schema, table, changes, progress);
+        Err(anyhow::anyhow!(
+            "Bulk operations are not supported for this database type"
+        ))
+    }
 
-    async fn bulk_delete(&self, schema: &str, table: &str, where_clause: &str) -> Result<u64>;
+    async fn bulk_delete(
+        &self,
+        schema: &str,
+        table: &str,
+        primary_keys: &[HashMap<String, Value>],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let _ = (schema, table, primary_keys, progress);
+        Err(anyhow::anyhow!(
+            "Bulk operations are not supported for this database type"
+        ))
+    }
+
+    /// Whether a failed `bulk_insert` into `table` leaves none of its rows
+    /// written. Tables without transactions keep the chunks sent before the
+    /// failure.
+    async fn bulk_insert_is_atomic(&self, schema: &str, table: &str) -> Result<bool> {
+        let _ = (schema, table);
+        Ok(true)
+    }
+}
+
+/// Reads tables for a logical dump (`services::dump`).
+#[async_trait]
+pub trait DumpDriver: Send + Sync {
+    /// Sends each table as `DumpEvent::Table` followed by its rows as SQL
+    /// literals, reading all of them in one snapshot. Generated columns are
+    /// left out. Returns the number of rows sent.
+    async fn dump_rows(
+        &self,
+        schema: &str,
+        tables: &[String],
+        tx: &mpsc::Sender<DumpEvent>,
+    ) -> Result<u64> {
+        let _ = (schema, tables, tx);
+        Err(anyhow::anyhow!(
+            "Native dumps are not supported for this database type"
+        ))
+    }
+
+    /// Statements of the indexes and triggers of `table` that `export_ddl`
+    /// leaves out. They are written after the rows, so that the rows load
+    /// without firing triggers or updating indexes one by one.
+    async fn dump_table_extras(&self, schema: &str, table: &str) -> Result<Vec<String>> {
+        let _ = (schema, table);
+        Ok(Vec::new())
+    }
 }
 
 #[async_trait]
 pub trait DatabaseManagementDriver: Send + Sync {
     async fn create_database(&self, name: &str) -> Result<()>;
+    /// Engines without creation options (owner, encoding, ...) ignore them.
+    async fn create_database_with_options(
+        &self,
+        name: &str,
+        _options: Option<crate::handlers::database::CreateDatabaseOptions>,
+    ) -> Result<()> {
+        self.create_database(name).await
+    }
     async fn drop_database(&self, name: &str) -> Result<()>;
     async fn create_schema(&self, name: &str) -> Result<()>;
     async fn drop_schema(&self, name: &str) -> Result<()>;
@@ -11,7 +11,8 @@ pub mod table;
 pub mod traits;
 pub mod view;
 
-pub use base::{ConnectionDriver, QueryDriver};
+pub use base::{ConnectionDriver, QueryDriver, StatementSession};
+pub use capabilities::{PreparedStatementDriver, TransactionDriver, TransactionSession};
 pub use column::ColumnManagement;
 pub use ddl_export::DdlExportDriver;
 pub use function::FunctionOperations;
@@ -19,7 +20,9 @@ pub use nosql::NoSQLOperations;
 pub use schema::SchemaIntrospection;
 pub use session::SessionOperations;
 pub use table::TableOperations;
+pub use traits::ConnectionPoolDriver;
 pub use view::ViewOperations;
 // pub use nosql::{CollectionInfo, DocumentResult};
-// pub use capabilities::{DriverCapability, DriverCapabilities, TransactionDriver, PreparedStatementDriver};
-// pub use extension::{StreamingDriver, BulkOperationsDriver, DatabaseManagementDriver};
+// pub use capabilities::{DriverCapability, DriverCapabilities};
+pub use extension::{BulkOperationsDriver, DumpDriver};
+// pub use extension::{StreamingDriver, DatabaseManagementDriver};
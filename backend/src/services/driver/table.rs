@@ -2,6 +2,7 @@ use crate::services::db_driver::{
     IndexInfo, PartitionInfo, QueryResult, RoleInfo, StorageBloatInfo, TableComment,
     TableConstraints, TableDependencies, TableGrant, TableStatistics, TriggerInfo,
 };
+use crate::services::table_filter::{FilterNode, SortSpec};
 use anyhow::Result;
 use async_trait::async_trait;
 
@@ -13,7 +14,8 @@ pub trait TableOperations: Send + Sync {
         table: &str,
         limit: i64,
         offset: i64,
-        filter: Option<String>,
+        filter: Option<FilterNode>,
+        sort: Vec<SortSpec>,
         document_id: Option<String>,
         fields: Option<Vec<String>>,
     ) -> Result<QueryResult>;
@@ -10,7 +10,8 @@ pub trait DriverMetadata {
     fn supports_prepared_statements(&self) -> bool;
 }
 
-#[allow(dead_code)]
+/// Pool statistics for drivers held in the driver registry. Drivers whose client
+/// does not expose its pool return an error.
 #[async_trait]
 pub trait ConnectionPoolDriver: Send + Sync {
     async fn get_pool_size(&self) -> Result<usize>;
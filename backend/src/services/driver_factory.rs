@@ -0,0 +1,283 @@
+use crate::models::entities::connection as ConnectionModel;
+use crate::services::clickhouse::ClickHouseDriver;
+use crate::services::couchbase::CouchbaseDriver;
+use crate::services::db_driver::DatabaseDriver;
+use crate::services::driver::capabilities::DriverCapability;
+use crate::services::mongo::MongoDriver;
+use crate::services::mysql::MySqlDriver;
+use crate::services::postgres::PostgresDriver;
+use crate::services::sqlite::{SQLiteDriver, SqliteAttachedDatabase};
+use crate::services::table_filter::SqlDialect;
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use sqlparser::dialect::{
+    ClickHouseDialect, Dialect, MySqlDialect, PostgreSqlDialect, SQLiteDialect,
+};
+
+use DriverCapability::*;
+
+/// Inputs for building a driver.
+pub struct DriverContext<'a> {
+    pub connection: &'a ConnectionModel::Model,
+    pub password: &'a str,
+    /// Only read by SQLite; empty for other engines.
+    pub sqlite_attachments: Vec<SqliteAttachedDatabase>,
+}
+
+type CreateFn = fn(DriverContext<'_>) -> BoxFuture<'_, Result<Box<dyn DatabaseDriver>>>;
+type ParserDialectFn = fn() -> Box<dyn Dialect>;
+
+/// One supported engine. Adding an engine means adding an entry to `DRIVERS`.
+pub struct DriverSpec {
+    pub name: &'static str,
+    /// `connection.db_type` values handled by this driver.
+    pub db_types: &'static [&'static str],
+    pub capabilities: &'static [DriverCapability],
+    /// Identifier quoting and placeholders of the engine's query language;
+    /// `None` for engines without SQL.
+    pub sql_dialect: Option<SqlDialect>,
+    /// `sqlparser` dialect for paging and checking statements; `None` where
+    /// queries are not parsed.
+    pub parser_dialect: Option<ParserDialectFn>,
+    /// Database to connect to for server-level work (listing, creating and
+    /// dropping databases) instead of the connection's own database.
+    pub maintenance_database: Option<&'static str>,
+    /// Whether attached databases must be loaded into `DriverContext`.
+    pub uses_sqlite_attachments: bool,
+    create: CreateFn,
+}
+
+impl DriverSpec {
+    pub fn supports(&self, capability: DriverCapability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    /// Errors with a consistent message when the engine lacks `capability`.
+    pub fn require(&self, capability: DriverCapability) -> Result<()> {
+        if self.supports(capability) {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "{:?} is not supported for {} connections",
+                capability,
+                self.name
+            ))
+        }
+    }
+
+    /// Builds a new, uncached driver.
+    pub async fn create(&self, ctx: DriverContext<'_>) -> Result<Box<dyn DatabaseDriver>> {
+        (self.create)(ctx).await
+    }
+}
+
+static DRIVERS: &[DriverSpec] = &[
+    DriverSpec {
+        name: "postgres",
+        db_types: &["postgres", "cockroachdb", "cockroach"],
+        capabilities: &[
+            Schemas,
+            Views,
+            Functions,
+            Indexes,
+            Constraints,
+            ColumnManagement,
+            Transactions,
+            PreparedStatements,
+            BulkOperations,
+            Streaming,
+            Sessions,
+            Extensions,
+            DdlExport,
+        ],
+        sql_dialect: Some(SqlDialect::POSTGRES),
+        parser_dialect: Some(postgres_parser),
+        maintenance_database: Some("postgres"),
+        uses_sqlite_attachments: false,
+        create: create_postgres,
+    },
+    DriverSpec {
+        name: "sqlite",
+        db_types: &["sqlite"],
+        capabilities: &[
+            Views,
+            Functions,
+            Indexes,
+            Constraints,
+            ColumnManagement,
+            Transactions,
+            PreparedStatements,
+            BulkOperations,
+            Streaming,
+            DdlExport,
+            NativeDump,
+        ],
+        sql_dialect: Some(SqlDialect::SQLITE),
+        parser_dialect: Some(sqlite_parser),
+        maintenance_database: None,
+        uses_sqlite_attachments: true,
+        create: create_sqlite,
+    },
+    DriverSpec {
+        name: "clickhouse",
+        db_types: &["clickhouse"],
+        capabilities: &[
+            Views,
+            Functions,
+            Indexes,
+            ColumnManagement,
+            // Only on servers with experimental transactions enabled.
+            Transactions,
+            PreparedStatements,
+            BulkOperations,
+            Streaming,
+            Sessions,
+            DdlExport,
+        ],
+        sql_dialect: Some(SqlDialect::CLICKHOUSE),
+        parser_dialect: Some(clickhouse_parser),
+        maintenance_database: None,
+        uses_sqlite_attachments: false,
+        create: create_clickhouse,
+    },
+    DriverSpec {
+        name: "mysql",
+        db_types: &["mysql", "mariadb", "tidb"],
+        capabilities: &[
+            Views,
+            Functions,
+            Indexes,
+            Constraints,
+            ColumnManagement,
+            Transactions,
+            PreparedStatements,
+            BulkOperations,
+            Streaming,
+            Sessions,
+            DdlExport,
+            NativeDump,
+        ],
+        sql_dialect: Some(SqlDialect::MYSQL),
+        parser_dialect: Some(mysql_parser),
+        maintenance_database: None,
+        uses_sqlite_attachments: false,
+        create: create_mysql,
+    },
+    DriverSpec {
+        name: "couchbase",
+        db_types: &["couchbase"],
+        capabilities: &[Schemas, Indexes, ColumnManagement],
+        sql_dialect: Some(SqlDialect::N1QL),
+        parser_dialect: None,
+        maintenance_database: None,
+        uses_sqlite_attachments: false,
+        create: create_couchbase,
+    },
+    DriverSpec {
+        name: "mongodb",
+        db_types: &["mongodb", "mongo"],
+        capabilities: &[Indexes, Streaming, BulkOperations],
+        sql_dialect: None,
+        parser_dialect: None,
+        maintenance_database: None,
+        uses_sqlite_attachments: false,
+        create: create_mongo,
+    },
+];
+
+pub fn drivers() -> &'static [DriverSpec] {
+    DRIVERS
+}
+
+pub fn spec_for(db_type: &str) -> Result<&'static DriverSpec> {
+    DRIVERS
+        .iter()
+        .find(|spec| spec.db_types.contains(&db_type))
+        .ok_or_else(|| anyhow::anyhow!("Unsupported database type: {}", db_type))
+}
+
+fn postgres_parser() -> Box<dyn Dialect> {
+    Box::new(PostgreSqlDialect {})
+}
+
+fn sqlite_parser() -> Box<dyn Dialect> {
+    Box::new(SQLiteDialect {})
+}
+
+fn clickhouse_parser() -> Box<dyn Dialect> {
+    Box::new(ClickHouseDialect {})
+}
+
+fn mysql_parser() -> Box<dyn Dialect> {
+    Box::new(MySqlDialect {})
+}
+
+fn create_postgres(ctx: DriverContext<'_>) -> BoxFuture<'_, Result<Box<dyn DatabaseDriver>>> {
+    Box::pin(async move {
+        let driver = PostgresDriver::new(ctx.connection, ctx.password).await?;
+        Ok(Box::new(driver) as Box<dyn DatabaseDriver>)
+    })
+}
+
+fn create_sqlite(ctx: DriverContext<'_>) -> BoxFuture<'_, Result<Box<dyn DatabaseDriver>>> {
+    Box::pin(async move {
+        let driver = SQLiteDriver::new_with_attachments(
+            ctx.connection,
+            ctx.password,
+            ctx.sqlite_attachments,
+        )
+        .await?;
+        Ok(Box::new(driver) as Box<dyn DatabaseDriver>)
+    })
+}
+
+fn create_clickhouse(ctx: DriverContext<'_>) -> BoxFuture<'_, Result<Box<dyn DatabaseDriver>>> {
+    Box::pin(async move {
+        let driver = ClickHouseDriver::new(ctx.connection, ctx.password).await?;
+        Ok(Box::new(driver) as Box<dyn DatabaseDriver>)
+    })
+}
+
+fn create_mysql(ctx: DriverContext<'_>) -> BoxFuture<'_, Result<Box<dyn DatabaseDriver>>> {
+    Box::pin(async move {
+        let driver = MySqlDriver::from_model(ctx.connection, ctx.password).await?;
+        Ok(Box::new(driver) as Box<dyn DatabaseDriver>)
+    })
+}
+
+fn create_couchbase(ctx: DriverContext<'_>) -> BoxFuture<'_, Result<Box<dyn DatabaseDriver>>> {
+    Box::pin(async move {
+        let driver = CouchbaseDriver::new(ctx.connection, ctx.password).await?;
+        Ok(Box::new(driver) as Box<dyn DatabaseDriver>)
+    })
+}
+
+fn create_mongo(ctx: DriverContext<'_>) -> BoxFuture<'_, Result<Box<dyn DatabaseDriver>>> {
+    Box::pin(async move {
+        let driver = MongoDriver::new(ctx.connection, ctx.password).await?;
+        Ok(Box::new(driver) as Box<dyn DatabaseDriver>)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aliases_resolve_to_one_driver() {
+        assert_eq!(spec_for("cockroach").unwrap().name, "postgres");
+        assert_eq!(spec_for("tidb").unwrap().name, "mysql");
+        assert_eq!(spec_for("mongo").unwrap().name, "mongodb");
+        assert!(spec_for("oracle").is_err());
+    }
+
+    #[test]
+    fn db_types_are_registered_once() {
+        let mut seen = std::collections::HashSet::new();
+        for spec in drivers() {
+            for db_type in spec.db_types {
+                assert!(seen.insert(*db_type), "{} registered twice", db_type);
+            }
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use crate::models::entities::connection as ConnectionModel;
+use crate::services::db_driver::DatabaseDriver;
+use crate::services::driver::ConnectionPoolDriver;
+use crate::services::reaper::spawn_reaper;
+use anyhow::Result;
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::future::Future;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Drivers that have not been handed out for this long are dropped, closing their pools.
+const DRIVER_IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+const REAPER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A driver is shared per connection and effective database, so switching
+/// databases through the `x-dbplus-database` override gets its own pool.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DriverKey {
+    connection_id: Uuid,
+    database: String,
+}
+
+struct RegistryEntry {
+    driver: Arc<dyn DatabaseDriver>,
+    db_type: String,
+    fingerprint: u64,
+    created_at: Instant,
+    last_used: Mutex<Instant>,
+}
+
+impl RegistryEntry {
+    fn touch(&self) {
+        *self.last_used.lock().unwrap() = Instant::now();
+    }
+
+    fn idle_for(&self) -> Duration {
+        self.last_used.lock().unwrap().elapsed()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DriverPoolStats {
+    pub connection_id: Uuid,
+    pub database: String,
+    pub db_type: String,
+    pub pool_size: Option<usize>,
+    pub active_connections: Option<usize>,
+    pub idle_connections: Option<usize>,
+    pub age_secs: u64,
+    pub idle_secs: u64,
+}
+
+/// Long-lived drivers (and therefore connection pools) shared by every request.
+pub struct DriverRegistry {
+    entries: DashMap<DriverKey, RegistryEntry>,
+    /// Serializes driver creation per key so unrelated connections build in parallel.
+    create_locks: DashMap<DriverKey, Arc<tokio::sync::Mutex<()>>>,
+    reaper_started: AtomicBool,
+}
+
+impl Default for DriverRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DriverRegistry {
+    pub fn new() -> Self {
+        Self {
+            entries: DashMap::new(),
+            create_locks: DashMap::new(),
+            reaper_started: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the cached driver for `connection`, building it with `create` on a miss.
+    /// `connection` must already have the database override and SSH tunnel applied.
+    pub async fn get_or_create<F, Fut>(
+        self: &Arc<Self>,
+        connection: &ConnectionModel::Model,
+        password: &str,
+        create: F,
+    ) -> Result<Arc<dyn DatabaseDriver>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Box<dyn DatabaseDriver>>>,
+    {
+        self.start_reaper();
+
+        let key = DriverKey {
+            connection_id: connection.id,
+            database: connection.database.clone(),
+        };
+        let fingerprint = fingerprint(connection, password);
+
+        if let Some(driver) = self.lookup(&key, fingerprint) {
+            return Ok(driver);
+        }
+
+        let create_lock = self.create_locks.entry(key.clone()).or_default().clone();
+        let _guard = create_lock.lock().await;
+        // Another caller may have built it while we waited.
+        if let Some(driver) = self.lookup(&key, fingerprint) {
+            return Ok(driver);
+        }
+
+        let driver: Arc<dyn DatabaseDriver> = Arc::from(create().await?);
+        tracing::info!(
+            "[DriverRegistry] Created {} driver for connection {} (database '{}')",
+            connection.db_type,
+            connection.id,
+            key.database
+        );
+
+        let now = Instant::now();
+        self.entries.insert(
+            key,
+            RegistryEntry {
+                driver: driver.clone(),
+                db_type: connection.db_type.clone(),
+                fingerprint,
+                created_at: now,
+                last_used: Mutex::new(now),
+            },
+        );
+        Ok(driver)
+    }
+
+    fn lookup(&self, key: &DriverKey, fingerprint: u64) -> Option<Arc<dyn DatabaseDriver>> {
+        let entry = self.entries.get(key)?;
+        if entry.fingerprint != fingerprint {
+            // Host, port or credentials changed (e.g. the SSH tunnel was reopened on a
+            // new port, or the password or certificates were edited).
+            drop(entry);
+            self.entries.remove(key);
+            return None;
+        }
+        entry.touch();
+        Some(entry.driver.clone())
+    }
+
+    /// Drops every cached driver of a connection (all database overrides).
+    pub fn evict(&self, connection_id: Uuid) {
+        let before = self.entries.len();
+        self.entries
+            .retain(|key, _| key.connection_id != connection_id);
+        self.create_locks
+            .retain(|key, _| key.connection_id != connection_id);
+        let removed = before.saturating_sub(self.entries.len());
+        if removed > 0 {
+            tracing::info!(
+                "[DriverRegistry] Evicted {} driver(s) for connection {}",
+                removed,
+                connection_id
+            );
+        }
+    }
+
+    pub async fn stats(&self) -> Vec<DriverPoolStats> {
+        let snapshot: Vec<_> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.key().clone(),
+                    entry.value().driver.clone(),
+                    entry.value().db_type.clone(),
+                    entry.value().created_at.elapsed(),
+                    entry.value().idle_for(),
+                )
+            })
+            .collect();
+
+        let mut stats = Vec::with_capacity(snapshot.len());
+        for (key, driver, db_type, age, idle) in snapshot {
+            stats.push(DriverPoolStats {
+                connection_id: key.connection_id,
+                database: key.database,
+                db_type,
+                pool_size: ConnectionPoolDriver::get_pool_size(&*driver).await.ok(),
+                active_connections: ConnectionPoolDriver::get_active_connections(&*driver)
+                    .await
+                    .ok(),
+                idle_connections: ConnectionPoolDriver::get_idle_connections(&*driver)
+                    .await
+                    .ok(),
+                age_secs: age.as_secs(),
+                idle_secs: idle.as_secs(),
+            });
+        }
+        stats
+    }
+
+    fn start_reaper(self: &Arc<Self>) {
+        spawn_reaper(
+            &self.reaper_started,
+            self,
+            REAPER_INTERVAL,
+            |registry| async move { registry.evict_idle(DRIVER_IDLE_TIMEOUT) },
+        );
+    }
+
+    fn evict_idle(&self, timeout: Duration) {
+        self.entries.retain(|key, entry| {
+            let keep = entry.idle_for() < timeout;
+            if !keep {
+                tracing::info!(
+                    "[DriverRegistry] Closing idle {} driver for connection {}",
+                    entry.db_type,
+                    key.connection_id
+                );
+            }
+            keep
+        });
+        // Keep locks that are held or still guard a cached driver.
+        self.create_locks
+            .retain(|key, lock| Arc::strong_count(lock) > 1 || self.entries.contains_key(key));
+    }
+}
+
+/// Hash of everything a driver is built from, so editing credentials or TLS
+/// settings replaces the cached driver. Hashed to keep secrets out of the registry.
+fn fingerprint(connection: &ConnectionModel::Model, password: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (
+        &connection.db_type,
+        &connection.host,
+        connection.port,
+        &connection.username,
+        password,
+        connection.ssl,
+        &connection.ssl_mode,
+        &connection.ssl_cert,
+        &connection.ssl_ca_file,
+        &connection.ssl_key_file,
+        &connection.ssl_cert_file,
+        connection.statement_timeout_ms,
+    )
+        .hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,356 @@
+//! Logical dumps of MySQL and SQLite databases written by the app itself,
+//! without `mysqldump` or the `sqlite3` shell: DDL from the engine's
+//! `DdlExportDriver` and rows from its `DumpDriver`, as multi-row `INSERT`s.
+
+use anyhow::{anyhow, Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+/// Rows read from the database per batch while dumping.
+pub const DUMP_BATCH_SIZE: usize = 1000;
+/// Rows per `INSERT` statement.
+const ROWS_PER_INSERT: usize = 500;
+/// Size at which an `INSERT` is ended early, well under the 4 MB
+/// `max_allowed_packet` of older MySQL servers.
+const MAX_INSERT_BYTES: usize = 1 << 20;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DumpOptions {
+    /// Only the DDL, without rows.
+    pub schema_only: bool,
+    /// Only the rows, for tables that already exist.
+    pub data_only: bool,
+    /// Tables and views to dump; all of them when unset.
+    pub tables: Option<Vec<String>>,
+    /// Write `DROP ... IF EXISTS` before each `CREATE`.
+    pub include_drop: bool,
+    /// Compress the file with gzip.
+    pub gzip: bool,
+}
+
+impl DumpOptions {
+    pub fn validate(&self) -> Result<()> {
+        if self.schema_only && self.data_only {
+            return Err(anyhow!("A dump cannot be both schema-only and data-only"));
+        }
+        if self.tables.as_ref().is_some_and(|tables| tables.is_empty()) {
+            return Err(anyhow!("No tables selected"));
+        }
+        Ok(())
+    }
+}
+
+/// What a dump is written from, in file order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DumpEvent {
+    /// Statements and comments written as they are.
+    Sql(String),
+    /// The rows that follow go to `table`, with values for `columns`. Both
+    /// are quoted already.
+    Table { table: String, columns: Vec<String> },
+    /// Values of rows of the last table, each one a SQL literal.
+    Rows(Vec<Vec<String>>),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DumpSummary {
+    pub path: String,
+    /// Tables whose rows were dumped.
+    pub tables: usize,
+    pub row_count: u64,
+    pub bytes_written: u64,
+    pub execution_time_ms: u64,
+}
+
+/// Sends `event` to the writer, failing when it has stopped.
+pub async fn send(tx: &mpsc::Sender<DumpEvent>, event: DumpEvent) -> Result<()> {
+    tx.send(event)
+        .await
+        .map_err(|_| anyhow!("The dump file stopped accepting rows"))
+}
+
+/// Settings at the top of the dump that let it load tables in any order and
+/// keep values as they were dumped.
+pub fn preamble(engine: &str) -> &'static str {
+    match engine {
+        // `NO_AUTO_VALUE_ON_ZERO` keeps zeros in AUTO_INCREMENT columns, and
+        // replacing the mode also turns off `NO_BACKSLASH_ESCAPES`, which
+        // the escaped strings rely on.
+        "mysql" => concat!(
+            "SET NAMES utf8mb4;\n",
+            "SET @OLD_FOREIGN_KEY_CHECKS = @@FOREIGN_KEY_CHECKS, FOREIGN_KEY_CHECKS = 0;\n",
+            "SET @OLD_SQL_MODE = @@SQL_MODE, SQL_MODE = 'NO_AUTO_VALUE_ON_ZERO';\n",
+            "SET @OLD_TIME_ZONE = @@TIME_ZONE, TIME_ZONE = '+00:00';\n",
+        ),
+        _ => "PRAGMA foreign_keys = OFF;\nBEGIN TRANSACTION;\n",
+    }
+}
+
+/// Restores what `preamble` changed.
+pub fn postamble(engine: &str) -> &'static str {
+    match engine {
+        "mysql" => concat!(
+            "SET TIME_ZONE = @OLD_TIME_ZONE;\n",
+            "SET SQL_MODE = @OLD_SQL_MODE;\n",
+            "SET FOREIGN_KEY_CHECKS = @OLD_FOREIGN_KEY_CHECKS;\n",
+        ),
+        _ => "COMMIT;\n",
+    }
+}
+
+/// Writes the events of a dump to `path` as they arrive, on a blocking
+/// thread, until the sender is dropped. Dropping `rx` on a write error stops
+/// the reading side. The file is removed when writing fails.
+pub async fn write_events(
+    rx: mpsc::Receiver<DumpEvent>,
+    path: PathBuf,
+    gzip: bool,
+) -> Result<DumpSummary> {
+    tokio::task::spawn_blocking(move || {
+        let started = Instant::now();
+        let result = write_file(rx, &path, gzip);
+        if result.is_err() {
+            let _ = std::fs::remove_file(&path);
+        }
+        let (tables, row_count) = result?;
+        Ok(DumpSummary {
+            path: path.to_string_lossy().to_string(),
+            tables,
+            row_count,
+            bytes_written: std::fs::metadata(&path)?.len(),
+            execution_time_ms: started.elapsed().as_millis() as u64,
+        })
+    })
+    .await?
+}
+
+fn write_file(rx: mpsc::Receiver<DumpEvent>, path: &Path, gzip: bool) -> Result<(usize, u64)> {
+    let file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let out = BufWriter::new(file);
+    if gzip {
+        let mut writer = DumpWriter::new(GzEncoder::new(out, Compression::default()));
+        let counts = writer.write_all(rx)?;
+        writer.into_inner().finish()?.flush()?;
+        Ok(counts)
+    } else {
+        let mut writer = DumpWriter::new(out);
+        let counts = writer.write_all(rx)?;
+        writer.into_inner().flush()?;
+        Ok(counts)
+    }
+}
+
+/// Groups rows into multi-row `INSERT`s.
+struct DumpWriter<W: Write> {
+    out: W,
+    /// `INSERT INTO ... VALUES` of the current table.
+    prefix: String,
+    /// The open statement and how many rows it has.
+    insert: String,
+    insert_rows: usize,
+    tables: usize,
+    rows: u64,
+}
+
+impl<W: Write> DumpWriter<W> {
+    fn new(out: W) -> Self {
+        Self {
+            out,
+            prefix: String::new(),
+            insert: String::new(),
+            insert_rows: 0,
+            tables: 0,
+            rows: 0,
+        }
+    }
+
+    fn write_all(&mut self, mut rx: mpsc::Receiver<DumpEvent>) -> Result<(usize, u64)> {
+        while let Some(event) = rx.blocking_recv() {
+            self.event(event)?;
+        }
+        self.end_insert()?;
+        Ok((self.tables, self.rows))
+    }
+
+    fn event(&mut self, event: DumpEvent) -> Result<()> {
+        match event {
+            DumpEvent::Sql(sql) => {
+                self.end_insert()?;
+                self.out.write_all(sql.as_bytes())?;
+                if !sql.ends_with('\n') {
+                    self.out.write_all(b"\n")?;
+                }
+            }
+            DumpEvent::Table { table, columns } => {
+                self.end_insert()?;
+                self.prefix = format!("INSERT INTO {} ({}) VALUES\n", table, columns.join(", "));
+                self.tables += 1;
+            }
+            DumpEvent::Rows(rows) => {
+                if self.prefix.is_empty() {
+                    return Err(anyhow!("Rows arrived before their table"));
+                }
+                for row in rows {
+                    self.row(&row)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn row(&mut self, values: &[String]) -> Result<()> {
+        if self.insert_rows == 0 {
+            self.insert.push_str(&self.prefix);
+        } else {
+            self.insert.push_str(",\n");
+        }
+        self.insert.push('(');
+        self.insert.push_str(&values.join(", "));
+        self.insert.push(')');
+        self.insert_rows += 1;
+        self.rows += 1;
+        if self.insert_rows >= ROWS_PER_INSERT || self.insert.len() >= MAX_INSERT_BYTES {
+            self.end_insert()?;
+        }
+        Ok(())
+    }
+
+    fn end_insert(&mut self) -> Result<()> {
+        if self.insert_rows > 0 {
+            self.insert.push_str(";\n");
+            self.out.write_all(self.insert.as_bytes())?;
+            self.insert.clear();
+            self.insert_rows = 0;
+        }
+        Ok(())
+    }
+
+    fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// Orders views, given with their `CREATE` statements, so that each comes
+/// after the views it selects from. A view is taken to depend on another
+/// when its statement names it; views left in a cycle keep their order.
+pub fn order_views(views: Vec<(String, String)>) -> Vec<(String, String)> {
+    let depends_on: Vec<Vec<usize>> = views
+        .iter()
+        .enumerate()
+        .map(|(i, (_, ddl))| {
+            views
+                .iter()
+                .enumerate()
+                .filter(|(j, (name, _))| *j != i && mentions(ddl, name))
+                .map(|(j, _)| j)
+                .collect()
+        })
+        .collect();
+
+    let mut placed = vec![false; views.len()];
+    let mut order = Vec::with_capacity(views.len());
+    while let Some(next) =
+        (0..views.len()).find(|&i| !placed[i] && depends_on[i].iter().all(|&j| placed[j]))
+    {
+        placed[next] = true;
+        order.push(next);
+    }
+    order.extend((0..views.len()).filter(|&i| !placed[i]));
+
+    let mut views: Vec<Option<(String, String)>> = views.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|i| views[i].take()).collect()
+}
+
+/// Whether `name` appears in `sql` as a whole identifier, quoted or not.
+fn mentions(sql: &str, name: &str) -> bool {
+    let sql = sql.to_lowercase();
+    let name = name.to_lowercase();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    !name.is_empty()
+        && sql.match_indices(&name).any(|(at, _)| {
+            !sql[..at].chars().next_back().is_some_and(is_word)
+                && !sql[at + name.len()..].chars().next().is_some_and(is_word)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_rows_into_inserts() {
+        let (tx, rx) = mpsc::channel(8);
+        let rows = (1..=ROWS_PER_INSERT + 1)
+            .map(|i| vec![i.to_string(), "'a''b'".to_string()])
+            .collect();
+        let events = vec![
+            DumpEvent::Sql("CREATE TABLE \"t\" (\"id\" INTEGER, \"name\" TEXT);".into()),
+            DumpEvent::Table {
+                table: "\"t\"".into(),
+                columns: vec!["\"id\"".into(), "\"name\"".into()],
+            },
+            DumpEvent::Rows(rows),
+            DumpEvent::Sql("COMMIT;\n".into()),
+        ];
+        for event in events {
+            tx.try_send(event).unwrap();
+        }
+        drop(tx);
+
+        let mut writer = DumpWriter::new(Vec::new());
+        let counts = writer.write_all(rx).unwrap();
+        assert_eq!(counts, (1, ROWS_PER_INSERT as u64 + 1));
+        let sql = String::from_utf8(writer.into_inner()).unwrap();
+        let statements: Vec<&str> = sql.split(";\n").collect();
+        assert_eq!(statements.len(), 5);
+        assert!(statements[1]
+            .starts_with("INSERT INTO \"t\" (\"id\", \"name\") VALUES\n(1, 'a''b'),\n(2, 'a''b')"));
+        assert_eq!(
+            statements[2],
+            format!(
+                "INSERT INTO \"t\" (\"id\", \"name\") VALUES\n({}, 'a''b')",
+                ROWS_PER_INSERT + 1
+            )
+        );
+        assert_eq!(statements[3], "COMMIT");
+
+        assert!(DumpOptions {
+            schema_only: true,
+            data_only: true,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn orders_views_after_the_views_they_use() {
+        let views = vec![
+            (
+                "a_totals".to_string(),
+                "CREATE VIEW `a_totals` AS SELECT SUM(n) FROM `b_orders`".to_string(),
+            ),
+            (
+                "b_orders".to_string(),
+                "CREATE VIEW `b_orders` AS SELECT n FROM `orders`".to_string(),
+            ),
+            (
+                "c_report".to_string(),
+                "CREATE VIEW c_report AS SELECT * FROM a_totals, b_orders_old".to_string(),
+            ),
+        ];
+        let names: Vec<String> = order_views(views)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, ["b_orders", "a_totals", "c_report"]);
+    }
+}
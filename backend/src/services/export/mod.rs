@@ -0,0 +1,349 @@
+mod text;
+mod xlsx;
+
+use crate::services::query_stream::QueryStreamEvent;
+use crate::services::table_filter::{FilterNode, SortSpec, SqlDialect};
+use anyhow::{anyhow, Context, Result};
+use chrono::format::{Item, StrftimeItems};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+/// Rows read from the database per batch while exporting.
+pub const EXPORT_BATCH_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Tsv,
+    /// One array of objects keyed by column name.
+    Json,
+    /// One object per line.
+    Ndjson,
+    SqlInsert,
+    Markdown,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Tsv => "tsv",
+            ExportFormat::Json => "json",
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::SqlInsert => "sql",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Tsv => "text/tab-separated-values; charset=utf-8",
+            ExportFormat::Json => "application/json",
+            ExportFormat::Ndjson => "application/x-ndjson",
+            ExportFormat::SqlInsert => "application/sql",
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+        }
+    }
+}
+
+/// When CSV and TSV fields are wrapped in the quote character.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStyle {
+    /// Fields containing the delimiter, the quote or a line break, and text
+    /// that reads the same as `null_value`.
+    #[default]
+    Necessary,
+    Always,
+    /// Every field except numbers, booleans and NULL.
+    NonNumeric,
+    /// Fields are written as-is.
+    Never,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    /// Field separator for CSV and TSV; `,` and a tab when unset.
+    pub delimiter: Option<char>,
+    pub quote: char,
+    pub quote_style: QuoteStyle,
+    /// Write the column names first (CSV, TSV, XLSX). Markdown always has them.
+    pub header: bool,
+    /// Text written for NULL in CSV, TSV, Markdown and XLSX.
+    pub null_value: String,
+    /// chrono format such as `%d/%m/%Y %H:%M` for values that are dates or
+    /// timestamps. They are left as the database returned them when unset.
+    pub date_format: Option<String>,
+    /// Table the `INSERT` statements of `SqlInsert` go to.
+    pub table_name: Option<String>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Csv,
+            delimiter: None,
+            quote: '"',
+            quote_style: QuoteStyle::Necessary,
+            header: true,
+            null_value: String::new(),
+            date_format: None,
+            table_name: None,
+        }
+    }
+}
+
+impl ExportOptions {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(format) = &self.date_format {
+            if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                return Err(anyhow!("Invalid date format: {}", format));
+            }
+        }
+        if self
+            .delimiter
+            .is_some_and(|d| d == self.quote || d == '\n' || d == '\r')
+        {
+            return Err(anyhow!(
+                "The delimiter must differ from the quote and line breaks"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A table to export, read like `get_table_data` but without a page limit.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TableExport {
+    #[serde(default)]
+    pub schema: String,
+    pub table: String,
+    #[serde(default)]
+    pub filter: Option<FilterNode>,
+    #[serde(default)]
+    pub sort: Vec<SortSpec>,
+    /// Columns to export; all of them when unset.
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportSummary {
+    pub path: String,
+    pub format: ExportFormat,
+    pub row_count: u64,
+    pub bytes_written: u64,
+    pub execution_time_ms: u64,
+}
+
+/// Writes rows in one export format as they are read.
+trait RowWriter: Send {
+    fn begin(&mut self, columns: &[String]) -> Result<()>;
+    fn row(&mut self, row: &[Value]) -> Result<()>;
+    fn finish(self: Box<Self>) -> Result<()>;
+}
+
+fn writer<W: Write + Send + 'static>(
+    options: &ExportOptions,
+    dialect: SqlDialect,
+    out: W,
+) -> Box<dyn RowWriter> {
+    match options.format {
+        ExportFormat::Csv => Box::new(text::CsvWriter::new(out, options, ',')),
+        ExportFormat::Tsv => Box::new(text::CsvWriter::new(out, options, '\t')),
+        ExportFormat::Json => Box::new(text::JsonWriter::new(out, options, false)),
+        ExportFormat::Ndjson => Box::new(text::JsonWriter::new(out, options, true)),
+        ExportFormat::SqlInsert => Box::new(text::SqlInsertWriter::new(out, options, dialect)),
+        ExportFormat::Markdown => Box::new(text::MarkdownWriter::new(out, options)),
+        ExportFormat::Xlsx => Box::new(xlsx::XlsxWriter::new(out, options)),
+    }
+}
+
+/// Writes the events of a streamed query to `path` as they arrive, on a
+/// blocking thread. Dropping `rx` on a write error stops the query. The file
+/// is removed when the export fails. `dialect` quotes `SqlInsert` output.
+pub async fn write_events(
+    rx: mpsc::Receiver<QueryStreamEvent>,
+    path: PathBuf,
+    options: ExportOptions,
+    dialect: SqlDialect,
+) -> Result<ExportSummary> {
+    options.validate()?;
+    tokio::task::spawn_blocking(move || {
+        let started = Instant::now();
+        let result = write_file(rx, &path, &options, dialect);
+        if result.is_err() {
+            let _ = std::fs::remove_file(&path);
+        }
+        let row_count = result?;
+        Ok(ExportSummary {
+            path: path.to_string_lossy().to_string(),
+            format: options.format,
+            row_count,
+            bytes_written: std::fs::metadata(&path)?.len(),
+            execution_time_ms: started.elapsed().as_millis() as u64,
+        })
+    })
+    .await?
+}
+
+fn write_file(
+    rx: mpsc::Receiver<QueryStreamEvent>,
+    path: &Path,
+    options: &ExportOptions,
+    dialect: SqlDialect,
+) -> Result<u64> {
+    let file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+    let out = writer(options, dialect, BufWriter::new(file));
+    write_rows(rx, out)
+}
+
+fn write_rows(
+    mut rx: mpsc::Receiver<QueryStreamEvent>,
+    mut out: Box<dyn RowWriter>,
+) -> Result<u64> {
+    let mut started = false;
+    let mut row_count = 0;
+    while let Some(event) = rx.blocking_recv() {
+        match event {
+            QueryStreamEvent::Meta { columns, .. } => {
+                // Statements without a result set report no columns.
+                if !started && !columns.is_empty() {
+                    out.begin(&columns)?;
+                    started = true;
+                }
+            }
+            QueryStreamEvent::Rows { rows } => {
+                if !started {
+                    return Err(anyhow!("Rows arrived before the columns"));
+                }
+                for row in &rows {
+                    out.row(row)?;
+                }
+                row_count += rows.len() as u64;
+            }
+            QueryStreamEvent::Progress { .. } => {}
+            QueryStreamEvent::Done { .. } => {
+                if !started {
+                    return Err(anyhow!("The statement did not return rows to export"));
+                }
+                out.finish()?;
+                return Ok(row_count);
+            }
+            QueryStreamEvent::Error { message } => return Err(anyhow!(message)),
+        }
+    }
+    Err(anyhow!("The query stopped before all rows were read"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    /// Keeps what was written after `finish` has consumed the writer.
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn export(options: ExportOptions, dialect: SqlDialect) -> String {
+        let (tx, rx) = mpsc::channel(8);
+        let events = vec![
+            QueryStreamEvent::Meta {
+                columns: vec!["id".into(), "name".into(), "born".into()],
+                total_count: None,
+                limit: None,
+                offset: None,
+            },
+            QueryStreamEvent::Rows {
+                rows: vec![
+                    vec![json!(1), json!("O'Brien, \"Pat\""), json!("1990-05-17")],
+                    vec![json!(2), json!(""), Value::Null],
+                ],
+            },
+            QueryStreamEvent::Done {
+                row_count: 2,
+                affected_rows: 0,
+                execution_time_ms: 0,
+                truncated: false,
+            },
+        ];
+        for event in events {
+            tx.try_send(event).unwrap();
+        }
+        drop(tx);
+
+        let buf = Shared::default();
+        let rows = write_rows(rx, writer(&options, dialect, buf.clone())).unwrap();
+        assert_eq!(rows, 2);
+        let bytes = buf.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn writes_csv_and_inserts() {
+        let csv = export(
+            ExportOptions {
+                date_format: Some("%d/%m/%Y".into()),
+                ..Default::default()
+            },
+            SqlDialect::POSTGRES,
+        );
+        assert_eq!(
+            csv,
+            "id,name,born\n1,\"O'Brien, \"\"Pat\"\"\",17/05/1990\n2,\"\",\n"
+        );
+
+        let sql = export(
+            ExportOptions {
+                format: ExportFormat::SqlInsert,
+                table_name: Some("app.people".into()),
+                ..Default::default()
+            },
+            SqlDialect::MYSQL,
+        );
+        assert_eq!(
+            sql,
+            "INSERT INTO `app`.`people` (`id`, `name`, `born`) VALUES (1, 'O''Brien, \"Pat\"', '1990-05-17');\n\
+             INSERT INTO `app`.`people` (`id`, `name`, `born`) VALUES (2, '', NULL);\n"
+        );
+
+        let ndjson = export(
+            ExportOptions {
+                format: ExportFormat::Ndjson,
+                ..Default::default()
+            },
+            SqlDialect::POSTGRES,
+        );
+        assert_eq!(
+            ndjson.lines().nth(1),
+            Some(r#"{"id":2,"name":"","born":null}"#)
+        );
+    }
+}
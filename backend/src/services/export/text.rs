@@ -0,0 +1,344 @@
+use super::{ExportOptions, QuoteStyle, RowWriter};
+use crate::services::sql_template;
+use crate::services::table_filter::SqlDialect;
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::io::Write;
+
+/// Table named in `INSERT` statements when the options give none.
+const DEFAULT_TABLE_NAME: &str = "exported_rows";
+
+/// How values become cell text: NULL and date handling shared by the formats.
+pub(super) struct Cells {
+    null_value: String,
+    date_format: Option<String>,
+}
+
+impl Cells {
+    pub(super) fn new(options: &ExportOptions) -> Self {
+        Self {
+            null_value: options.null_value.clone(),
+            date_format: options.date_format.clone(),
+        }
+    }
+
+    pub(super) fn null_value(&self) -> &str {
+        &self.null_value
+    }
+
+    /// `value`, with dates and timestamps in `date_format`.
+    pub(super) fn value<'a>(&self, value: &'a Value) -> Cow<'a, Value> {
+        match (value, &self.date_format) {
+            (Value::String(s), Some(format)) => match format_date(s, format) {
+                Some(formatted) => Cow::Owned(Value::String(formatted)),
+                None => Cow::Borrowed(value),
+            },
+            _ => Cow::Borrowed(value),
+        }
+    }
+
+    /// Cell text of `value`; arrays and objects are written as JSON.
+    pub(super) fn text(&self, value: &Value) -> String {
+        match self.value(value).as_ref() {
+            Value::Null => self.null_value.clone(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+}
+
+/// `value` rewritten with `format` when it parses as a timestamp or a date.
+/// Formats asking for a time of a plain date leave it unchanged.
+fn format_date(value: &str, format: &str) -> Option<String> {
+    let mut out = String::new();
+    let written = if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        write!(out, "{}", timestamp.format(format))
+    } else if let Ok(timestamp) = DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f%#z") {
+        write!(out, "{}", timestamp.format(format))
+    } else if let Some(timestamp) = ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|pattern| NaiveDateTime::parse_from_str(value, pattern).ok())
+    {
+        write!(out, "{}", timestamp.format(format))
+    } else if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        write!(out, "{}", date.format(format))
+    } else {
+        return None;
+    };
+    written.ok().map(|_| out)
+}
+
+pub(super) struct CsvWriter<W> {
+    out: W,
+    cells: Cells,
+    delimiter: char,
+    quote: char,
+    quote_style: QuoteStyle,
+    header: bool,
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub(super) fn new(out: W, options: &ExportOptions, default_delimiter: char) -> Self {
+        Self {
+            out,
+            cells: Cells::new(options),
+            delimiter: options.delimiter.unwrap_or(default_delimiter),
+            quote: options.quote,
+            quote_style: options.quote_style,
+            header: options.header,
+        }
+    }
+
+    fn write_record<'a>(&mut self, fields: impl Iterator<Item = Field<'a>>) -> Result<()> {
+        let mut line = String::new();
+        for (i, field) in fields.enumerate() {
+            if i > 0 {
+                line.push(self.delimiter);
+            }
+            if self.needs_quotes(&field) {
+                let quote = self.quote.to_string();
+                line.push(self.quote);
+                line.push_str(&field.text.replace(&quote, &quote.repeat(2)));
+                line.push(self.quote);
+            } else {
+                line.push_str(&field.text);
+            }
+        }
+        line.push('\n');
+        self.out.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// NULL is never quoted, so that it stays apart from text that reads the same.
+    fn needs_quotes(&self, field: &Field) -> bool {
+        if field.null {
+            return false;
+        }
+        match self.quote_style {
+            QuoteStyle::Always => true,
+            QuoteStyle::Never => false,
+            QuoteStyle::NonNumeric => !field.numeric,
+            QuoteStyle::Necessary => {
+                field.text == self.cells.null_value()
+                    || field.text.contains(|c: char| {
+                        c == self.delimiter || c == self.quote || c == '\n' || c == '\r'
+                    })
+            }
+        }
+    }
+}
+
+struct Field<'a> {
+    text: Cow<'a, str>,
+    null: bool,
+    numeric: bool,
+}
+
+impl<W: Write + Send> RowWriter for CsvWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> Result<()> {
+        if !self.header {
+            return Ok(());
+        }
+        self.write_record(columns.iter().map(|column| Field {
+            text: Cow::Borrowed(column.as_str()),
+            null: false,
+            numeric: false,
+        }))
+    }
+
+    fn row(&mut self, row: &[Value]) -> Result<()> {
+        let fields: Vec<Field> = row
+            .iter()
+            .map(|value| Field {
+                text: Cow::Owned(self.cells.text(value)),
+                null: value.is_null(),
+                numeric: value.is_number() || value.is_boolean(),
+            })
+            .collect();
+        self.write_record(fields.into_iter())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// A JSON array of objects, or with `lines` one object per line (NDJSON).
+pub(super) struct JsonWriter<W> {
+    out: W,
+    cells: Cells,
+    lines: bool,
+    /// Column names, already serialized as JSON strings.
+    keys: Vec<String>,
+    rows: u64,
+}
+
+impl<W: Write> JsonWriter<W> {
+    pub(super) fn new(out: W, options: &ExportOptions, lines: bool) -> Self {
+        Self {
+            out,
+            cells: Cells::new(options),
+            lines,
+            keys: Vec::new(),
+            rows: 0,
+        }
+    }
+}
+
+impl<W: Write + Send> RowWriter for JsonWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> Result<()> {
+        self.keys = columns
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<serde_json::Result<_>>()?;
+        if !self.lines {
+            self.out.write_all(b"[")?;
+        }
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[Value]) -> Result<()> {
+        // Written by hand to keep the column order of the result.
+        let mut object = String::from("{");
+        for (i, (key, value)) in self.keys.iter().zip(row).enumerate() {
+            if i > 0 {
+                object.push(',');
+            }
+            object.push_str(key);
+            object.push(':');
+            object.push_str(&serde_json::to_string(self.cells.value(value).as_ref())?);
+        }
+        object.push('}');
+
+        let separator: &[u8] = match (self.lines, self.rows) {
+            (true, _) => b"",
+            (false, 0) => b"\n",
+            (false, _) => b",\n",
+        };
+        self.out.write_all(separator)?;
+        self.out.write_all(object.as_bytes())?;
+        if self.lines {
+            self.out.write_all(b"\n")?;
+        }
+        self.rows += 1;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        if !self.lines {
+            let end: &[u8] = if self.rows > 0 { b"\n]\n" } else { b"]\n" };
+            self.out.write_all(end)?;
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// One `INSERT` statement per row.
+pub(super) struct SqlInsertWriter<W> {
+    out: W,
+    cells: Cells,
+    dialect: SqlDialect,
+    table_name: String,
+    prefix: String,
+}
+
+impl<W: Write> SqlInsertWriter<W> {
+    pub(super) fn new(out: W, options: &ExportOptions, dialect: SqlDialect) -> Self {
+        Self {
+            out,
+            cells: Cells::new(options),
+            dialect,
+            table_name: options
+                .table_name
+                .clone()
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_TABLE_NAME.to_string()),
+            prefix: String::new(),
+        }
+    }
+}
+
+impl<W: Write + Send> RowWriter for SqlInsertWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> Result<()> {
+        let columns = columns
+            .iter()
+            .map(|column| self.dialect.quote_ident(column))
+            .collect::<Result<Vec<_>>>()?;
+        self.prefix = format!(
+            "INSERT INTO {} ({}) VALUES (",
+            sql_template::quote_identifier(&self.table_name, self.dialect)?,
+            columns.join(", ")
+        );
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[Value]) -> Result<()> {
+        let values: Vec<String> = row
+            .iter()
+            .map(|value| sql_template::literal(self.cells.value(value).as_ref(), self.dialect))
+            .collect();
+        let statement = format!("{}{});\n", self.prefix, values.join(", "));
+        self.out.write_all(statement.as_bytes())?;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// A GitHub-flavored Markdown table.
+pub(super) struct MarkdownWriter<W> {
+    out: W,
+    cells: Cells,
+}
+
+impl<W: Write> MarkdownWriter<W> {
+    pub(super) fn new(out: W, options: &ExportOptions) -> Self {
+        Self {
+            out,
+            cells: Cells::new(options),
+        }
+    }
+
+    fn write_line<'a>(&mut self, cells: impl Iterator<Item = Cow<'a, str>>) -> Result<()> {
+        let mut line = String::from("|");
+        for cell in cells {
+            line.push(' ');
+            line.push_str(&markdown_escape(&cell));
+            line.push_str(" |");
+        }
+        line.push('\n');
+        self.out.write_all(line.as_bytes())?;
+        Ok(())
+    }
+}
+
+fn markdown_escape(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+}
+
+impl<W: Write + Send> RowWriter for MarkdownWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> Result<()> {
+        self.write_line(columns.iter().map(|c| Cow::Borrowed(c.as_str())))?;
+        self.write_line(columns.iter().map(|_| Cow::Borrowed("---")))
+    }
+
+    fn row(&mut self, row: &[Value]) -> Result<()> {
+        let cells: Vec<String> = row.iter().map(|value| self.cells.text(value)).collect();
+        self.write_line(cells.into_iter().map(Cow::Owned))
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
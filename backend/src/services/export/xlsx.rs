@@ -0,0 +1,137 @@
+//! XLSX written as it is read: the worksheet is in constant memory mode, so
+//! rows go to a temporary file with inline strings instead of being held in
+//! memory, and the archive uses zip64 so sheets may pass 4 GB.
+
+use super::text::Cells;
+use super::{ExportOptions, RowWriter};
+use anyhow::{anyhow, Result};
+use rust_xlsxwriter::{Workbook, Worksheet};
+use serde_json::Value;
+use std::io::Write;
+
+const MAX_ROWS: u32 = 1_048_576;
+const MAX_COLUMNS: usize = 16_384;
+/// Excel rejects longer cell text.
+const MAX_CELL_CHARS: usize = 32_767;
+
+pub(super) struct XlsxWriter<W: Write> {
+    out: W,
+    workbook: Workbook,
+    worksheet: Worksheet,
+    cells: Cells,
+    header: bool,
+    rows: u32,
+}
+
+impl<W: Write> XlsxWriter<W> {
+    pub(super) fn new(out: W, options: &ExportOptions) -> Self {
+        let mut workbook = Workbook::new();
+        workbook.use_zip_large_file(true);
+        let worksheet = workbook.new_worksheet_with_constant_memory();
+        Self {
+            out,
+            workbook,
+            worksheet,
+            cells: Cells::new(options),
+            header: options.header,
+            rows: 0,
+        }
+    }
+
+    fn write_row<'a>(&mut self, cells: impl Iterator<Item = Cell<'a>>) -> Result<()> {
+        if self.rows >= MAX_ROWS {
+            return Err(anyhow!("XLSX sheets hold at most {} rows", MAX_ROWS));
+        }
+        let row = self.rows;
+        self.rows += 1;
+        for (column, cell) in (0u16..).zip(cells) {
+            match cell {
+                Cell::Empty => continue,
+                Cell::Number(n) => self.worksheet.write_number(row, column, n)?,
+                Cell::Bool(b) => self.worksheet.write_boolean(row, column, b)?,
+                Cell::Text(text) => self.worksheet.write_string(row, column, truncate(&text))?,
+            };
+        }
+        Ok(())
+    }
+}
+
+enum Cell<'a> {
+    Empty,
+    Number(f64),
+    Bool(bool),
+    Text(std::borrow::Cow<'a, str>),
+}
+
+impl<W: Write + Send> RowWriter for XlsxWriter<W> {
+    fn begin(&mut self, columns: &[String]) -> Result<()> {
+        if columns.len() > MAX_COLUMNS {
+            return Err(anyhow!("XLSX sheets hold at most {} columns", MAX_COLUMNS));
+        }
+        if self.header {
+            self.write_row(columns.iter().map(|c| Cell::Text(c.as_str().into())))?;
+        }
+        Ok(())
+    }
+
+    fn row(&mut self, row: &[Value]) -> Result<()> {
+        let cells: Vec<Cell> = row
+            .iter()
+            .map(|value| match self.cells.value(value).as_ref() {
+                Value::Null if self.cells.null_value().is_empty() => Cell::Empty,
+                Value::Number(n) => match n.as_f64() {
+                    Some(n) => Cell::Number(n),
+                    None => Cell::Text(n.to_string().into()),
+                },
+                Value::Bool(b) => Cell::Bool(*b),
+                _ => Cell::Text(self.cells.text(value).into()),
+            })
+            .collect();
+        self.write_row(cells.into_iter())
+    }
+
+    fn finish(self: Box<Self>) -> Result<()> {
+        let XlsxWriter {
+            mut out,
+            mut workbook,
+            worksheet,
+            ..
+        } = *self;
+        workbook.push_worksheet(worksheet);
+        workbook.save_to_writer(&mut out)?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// The text cut at Excel's cell limit.
+fn truncate(text: &str) -> &str {
+    match text.char_indices().nth(MAX_CELL_CHARS) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_a_zipped_workbook() {
+        assert_eq!(truncate("abc"), "abc");
+        let long = "é".repeat(MAX_CELL_CHARS + 5);
+        assert_eq!(truncate(&long).chars().count(), MAX_CELL_CHARS);
+
+        let mut out = Vec::new();
+        let mut writer = Box::new(XlsxWriter::new(&mut out, &ExportOptions::default()));
+        writer.begin(&["id".into(), "name".into()]).unwrap();
+        writer
+            .row(&[serde_json::json!(1), serde_json::json!("Ann")])
+            .unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(&out[..4], b"PK\x03\x04");
+        let end = &out[out.len() - 22..];
+        assert_eq!(&end[..4], &0x0605_4b50u32.to_le_bytes());
+    }
+}
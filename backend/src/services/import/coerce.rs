@@ -0,0 +1,382 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+
+/// The kind of value a column holds, as far as reading a file is concerned.
+/// Target columns get theirs from the database type name; file columns are
+/// inferred from their values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnKind {
+    Boolean,
+    Integer,
+    Float,
+    Decimal,
+    Date,
+    Time,
+    Timestamp,
+    TimestampTz,
+    Uuid,
+    Json,
+    Text,
+}
+
+impl ColumnKind {
+    /// Maps a type name from `get_columns` of any engine, such as `bigint`,
+    /// `Nullable(DateTime64(3))` or `tinyint(1)`.
+    pub fn from_data_type(data_type: &str) -> Self {
+        let mut name = data_type.trim().to_lowercase();
+        for wrapper in ["nullable(", "lowcardinality("] {
+            if let Some(inner) = name.strip_prefix(wrapper) {
+                name = inner.strip_suffix(')').unwrap_or(inner).to_string();
+            }
+        }
+        if name == "tinyint(1)" || name == "bit(1)" {
+            return ColumnKind::Boolean;
+        }
+        let base = name
+            .split('(')
+            .next()
+            .unwrap_or_default()
+            .trim_end_matches(" unsigned")
+            .trim();
+
+        match base {
+            "bool" | "boolean" => ColumnKind::Boolean,
+            "smallint" | "integer" | "int" | "bigint" | "tinyint" | "mediumint" | "int2"
+            | "int4" | "int8" | "serial" | "bigserial" | "smallserial" | "serial4" | "serial8"
+            | "year" => ColumnKind::Integer,
+            "real" | "float" | "double" | "double precision" | "float4" | "float8" | "float32"
+            | "float64" => ColumnKind::Float,
+            "numeric" | "decimal" | "money" => ColumnKind::Decimal,
+            "date" | "date32" => ColumnKind::Date,
+            "timestamptz" | "timestamp with time zone" => ColumnKind::TimestampTz,
+            "json" | "jsonb" => ColumnKind::Json,
+            "uuid" => ColumnKind::Uuid,
+            _ if base.starts_with("decimal") => ColumnKind::Decimal,
+            // ClickHouse Int8 ... UInt256
+            _ if is_sized_int(base) => ColumnKind::Integer,
+            _ if base.starts_with("timestamp") || base.starts_with("datetime") => {
+                ColumnKind::Timestamp
+            }
+            _ if base.starts_with("time") && !base.contains("zone") => ColumnKind::Time,
+            _ => ColumnKind::Text,
+        }
+    }
+
+    /// The narrowest kind every non-null value fits, `Text` when there are none.
+    pub fn infer<'a>(values: impl Iterator<Item = &'a Value>, date_format: Option<&str>) -> Self {
+        let candidates = [
+            ColumnKind::Integer,
+            ColumnKind::Float,
+            ColumnKind::Boolean,
+            ColumnKind::Date,
+            ColumnKind::Timestamp,
+            ColumnKind::TimestampTz,
+            ColumnKind::Json,
+        ];
+        let mut fits = candidates.map(|kind| (kind, true));
+        let mut seen = false;
+        for value in values.filter(|v| !v.is_null()) {
+            seen = true;
+            for (kind, fit) in fits.iter_mut().filter(|(_, fit)| *fit) {
+                *fit = kind.accepts_inferred(value, date_format);
+            }
+        }
+        if !seen {
+            return ColumnKind::Text;
+        }
+        fits.iter()
+            .find(|(_, fit)| *fit)
+            .map(|(kind, _)| *kind)
+            .unwrap_or(ColumnKind::Text)
+    }
+
+    /// Stricter than `coerce`: `1` is an integer, not a boolean, and
+    /// timestamps are `TimestampTz` exactly when they carry an offset.
+    fn accepts_inferred(self, value: &Value, date_format: Option<&str>) -> bool {
+        match (self, value) {
+            (ColumnKind::Boolean, Value::String(s)) => parse_bool(s).is_some() && !is_digit(s),
+            (ColumnKind::TimestampTz, Value::String(s)) => parse_timestamp_tz(s).is_some(),
+            (ColumnKind::Timestamp, Value::String(s)) if parse_timestamp_tz(s).is_some() => false,
+            (ColumnKind::Json, value) => value.is_object() || value.is_array(),
+            (_, Value::Object(_) | Value::Array(_)) => false,
+            (kind, value) => kind.coerce(value, date_format).is_ok(),
+        }
+    }
+
+    /// Converts a value read from a file to what the driver binds for a
+    /// column of this kind: numbers and booleans as JSON, temporal values as
+    /// ISO 8601 text.
+    pub fn coerce(self, value: &Value, date_format: Option<&str>) -> Result<Value, String> {
+        let text = match value {
+            Value::Null => return Ok(Value::Null),
+            Value::String(s) => s.trim().to_string(),
+            other => other.to_string(),
+        };
+        let invalid = || format!("{} is not a valid {}", value, self.label());
+
+        let coerced = match self {
+            ColumnKind::Boolean => match value {
+                Value::Bool(b) => Value::Bool(*b),
+                _ => Value::Bool(parse_bool(&text).ok_or_else(invalid)?),
+            },
+            ColumnKind::Integer => match value {
+                Value::Bool(b) => Value::from(*b as i64),
+                Value::Number(n) if n.is_f64() => {
+                    let f = n.as_f64().unwrap_or(f64::NAN);
+                    if f.fract() != 0.0 || f.abs() > i64::MAX as f64 {
+                        return Err(invalid());
+                    }
+                    Value::from(f as i64)
+                }
+                Value::Number(n) => Value::Number(n.clone()),
+                _ => Value::from(text.parse::<i64>().map_err(|_| invalid())?),
+            },
+            ColumnKind::Float => {
+                let f = text.parse::<f64>().map_err(|_| invalid())?;
+                serde_json::Number::from_f64(f)
+                    .map(Value::Number)
+                    .ok_or_else(invalid)?
+            }
+            ColumnKind::Decimal => Value::String(
+                Decimal::from_str(&text)
+                    .or_else(|_| Decimal::from_scientific(&text))
+                    .map_err(|_| invalid())?
+                    .to_string(),
+            ),
+            ColumnKind::Date => {
+                let date = parse_date(&text, date_format).ok_or_else(invalid)?;
+                Value::String(date.format("%Y-%m-%d").to_string())
+            }
+            ColumnKind::Time => {
+                let time = ["%H:%M:%S%.f", "%H:%M"]
+                    .iter()
+                    .find_map(|format| NaiveTime::parse_from_str(&text, format).ok())
+                    .ok_or_else(invalid)?;
+                Value::String(time.format("%H:%M:%S%.f").to_string())
+            }
+            ColumnKind::Timestamp => {
+                let timestamp = parse_timestamp(&text, date_format).ok_or_else(invalid)?;
+                Value::String(timestamp.format("%Y-%m-%d %H:%M:%S%.f").to_string())
+            }
+            ColumnKind::TimestampTz => {
+                // Without an offset the time is taken to be UTC.
+                let timestamp = parse_timestamp_tz(&text)
+                    .or_else(|| parse_timestamp(&text, date_format).map(|ts| ts.and_utc()))
+                    .ok_or_else(invalid)?;
+                Value::String(timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
+            ColumnKind::Uuid => Value::String(
+                uuid::Uuid::parse_str(&text)
+                    .map_err(|_| invalid())?
+                    .to_string(),
+            ),
+            ColumnKind::Json => match value {
+                Value::String(s) => serde_json::from_str(s).map_err(|_| invalid())?,
+                other => other.clone(),
+            },
+            ColumnKind::Text => match value {
+                Value::String(s) => Value::String(s.clone()),
+                other => Value::String(other.to_string()),
+            },
+        };
+        Ok(coerced)
+    }
+
+    /// Column type for `CREATE TABLE` on the engine named `engine`.
+    pub fn sql_type(self, engine: &str) -> &'static str {
+        use ColumnKind::*;
+        match engine {
+            "mysql" => match self {
+                Boolean => "BOOLEAN",
+                Integer => "BIGINT",
+                Float => "DOUBLE",
+                Decimal => "DECIMAL(38, 10)",
+                Date => "DATE",
+                Time => "TIME(6)",
+                Timestamp | TimestampTz => "DATETIME(6)",
+                Uuid => "CHAR(36)",
+                Json => "JSON",
+                Text => "TEXT",
+            },
+            "sqlite" => match self {
+                Boolean => "BOOLEAN",
+                Integer => "INTEGER",
+                Float => "REAL",
+                Decimal => "NUMERIC",
+                Date => "DATE",
+                Time => "TIME",
+                Timestamp | TimestampTz => "DATETIME",
+                Uuid | Json | Text => "TEXT",
+            },
+            "clickhouse" => match self {
+                Boolean => "Nullable(Bool)",
+                Integer => "Nullable(Int64)",
+                Float => "Nullable(Float64)",
+                Decimal => "Nullable(Decimal(38, 10))",
+                Date => "Nullable(Date32)",
+                Timestamp | TimestampTz => "Nullable(DateTime64(6))",
+                Uuid => "Nullable(UUID)",
+                Time | Json | Text => "Nullable(String)",
+            },
+            _ => match self {
+                Boolean => "BOOLEAN",
+                Integer => "BIGINT",
+                Float => "DOUBLE PRECISION",
+                Decimal => "NUMERIC",
+                Date => "DATE",
+                Time => "TIME",
+                Timestamp => "TIMESTAMP",
+                TimestampTz => "TIMESTAMPTZ",
+                Uuid => "UUID",
+                Json => "JSONB",
+                Text => "TEXT",
+            },
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ColumnKind::Boolean => "boolean",
+            ColumnKind::Integer => "integer",
+            ColumnKind::Float => "number",
+            ColumnKind::Decimal => "decimal",
+            ColumnKind::Date => "date",
+            ColumnKind::Time => "time",
+            ColumnKind::Timestamp | ColumnKind::TimestampTz => "timestamp",
+            ColumnKind::Uuid => "UUID",
+            ColumnKind::Json => "JSON value",
+            ColumnKind::Text => "text",
+        }
+    }
+}
+
+fn is_sized_int(base: &str) -> bool {
+    let digits = base
+        .strip_prefix("uint")
+        .or_else(|| base.strip_prefix("int"));
+    digits.is_some_and(|d| !d.is_empty() && d.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn is_digit(s: &str) -> bool {
+    s.trim().chars().all(|c| c.is_ascii_digit())
+}
+
+fn parse_bool(s: &str) -> Option<bool> {
+    match s.trim().to_lowercase().as_str() {
+        "true" | "t" | "yes" | "y" | "1" => Some(true),
+        "false" | "f" | "no" | "n" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_date(s: &str, date_format: Option<&str>) -> Option<NaiveDate> {
+    date_format
+        .and_then(|format| NaiveDate::parse_from_str(s, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok())
+}
+
+fn parse_timestamp(s: &str, date_format: Option<&str>) -> Option<NaiveDateTime> {
+    if let Some(format) = date_format {
+        if let Ok(timestamp) = NaiveDateTime::parse_from_str(s, format) {
+            return Some(timestamp);
+        }
+    }
+    if let Some(timestamp) = parse_timestamp_tz(s) {
+        return Some(timestamp.naive_utc());
+    }
+    [
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+    .or_else(|| parse_date(s, date_format).and_then(|d| d.and_hms_opt(0, 0, 0)))
+}
+
+fn parse_timestamp_tz(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .or_else(|_| DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f%#z"))
+        .ok()
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn maps_type_names_and_coerces_values() {
+        assert_eq!(
+            ColumnKind::from_data_type("character varying(255)"),
+            ColumnKind::Text
+        );
+        assert_eq!(
+            ColumnKind::from_data_type("Nullable(UInt32)"),
+            ColumnKind::Integer
+        );
+        assert_eq!(
+            ColumnKind::from_data_type("tinyint(1)"),
+            ColumnKind::Boolean
+        );
+        assert_eq!(ColumnKind::from_data_type("interval"), ColumnKind::Text);
+        assert_eq!(
+            ColumnKind::from_data_type("timestamp with time zone"),
+            ColumnKind::TimestampTz
+        );
+        assert_eq!(
+            ColumnKind::from_data_type("DateTime64(3)"),
+            ColumnKind::Timestamp
+        );
+
+        assert_eq!(
+            ColumnKind::Integer.coerce(&json!(" 42 "), None),
+            Ok(json!(42))
+        );
+        assert_eq!(
+            ColumnKind::Boolean.coerce(&json!("Yes"), None),
+            Ok(json!(true))
+        );
+        assert_eq!(
+            ColumnKind::Decimal.coerce(&json!("1.50"), None),
+            Ok(json!("1.50"))
+        );
+        assert_eq!(
+            ColumnKind::Date.coerce(&json!("17/05/1990"), Some("%d/%m/%Y")),
+            Ok(json!("1990-05-17"))
+        );
+        assert_eq!(
+            ColumnKind::TimestampTz.coerce(&json!("2024-01-02 03:04:05"), None),
+            Ok(json!("2024-01-02T03:04:05Z"))
+        );
+        assert_eq!(
+            ColumnKind::Integer.coerce(&json!("4.5"), None),
+            Err("\"4.5\" is not a valid integer".to_string())
+        );
+    }
+
+    #[test]
+    fn infers_the_narrowest_kind() {
+        let infer = |values: Value| ColumnKind::infer(values.as_array().unwrap().iter(), None);
+        assert_eq!(infer(json!(["1", null, "20"])), ColumnKind::Integer);
+        assert_eq!(infer(json!(["1", "2.5"])), ColumnKind::Float);
+        assert_eq!(infer(json!(["yes", "no"])), ColumnKind::Boolean);
+        assert_eq!(
+            infer(json!(["2024-01-02", "2024-01-03 10:00"])),
+            ColumnKind::Timestamp
+        );
+        assert_eq!(
+            infer(json!(["2024-01-02T10:00:00+02:00"])),
+            ColumnKind::TimestampTz
+        );
+        assert_eq!(infer(json!([{ "a": 1 }])), ColumnKind::Json);
+        assert_eq!(infer(json!(["1", "x"])), ColumnKind::Text);
+        assert_eq!(infer(json!([null])), ColumnKind::Text);
+    }
+}
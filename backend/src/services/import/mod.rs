@@ -0,0 +1,855 @@
+mod coerce;
+mod reader;
+mod sniff;
+
+pub use coerce::ColumnKind;
+
+use crate::services::db_driver::TableColumn;
+use crate::services::script_execution::ErrorPolicy;
+use crate::services::table_filter::SqlDialect;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::format::{Item, StrftimeItems};
+use reader::{Record, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+/// Rows of the file shown in a preview.
+pub const PREVIEW_ROWS: usize = 20;
+/// Rows read to infer the column types.
+const SAMPLE_ROWS: usize = 1000;
+pub const DEFAULT_BATCH_SIZE: usize = 500;
+/// Row errors kept in a report; the rest are only counted.
+const MAX_REPORTED_ERRORS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    Csv,
+    Tsv,
+    /// An array of objects or of arrays.
+    Json,
+    /// One object or array per line.
+    Ndjson,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// What spreadsheets on Windows save as "CSV" unless told otherwise.
+    Windows1252,
+}
+
+impl Encoding {
+    fn bom(self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8 => &[0xEF, 0xBB, 0xBF],
+            Encoding::Utf16Le => &[0xFF, 0xFE],
+            Encoding::Utf16Be => &[0xFE, 0xFF],
+            Encoding::Windows1252 => &[],
+        }
+    }
+
+    fn is_utf16(self) -> bool {
+        matches!(self, Encoding::Utf16Le | Encoding::Utf16Be)
+    }
+}
+
+/// How to read the file. Whatever is left unset is guessed from its content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImportOptions {
+    pub format: Option<ImportFormat>,
+    pub encoding: Option<Encoding>,
+    /// Field separator for CSV and TSV.
+    pub delimiter: Option<char>,
+    pub quote: char,
+    pub has_header: Option<bool>,
+    /// Unquoted field values read as NULL, besides empty fields.
+    pub null_values: Vec<String>,
+    /// chrono format such as `%d/%m/%Y` for date and timestamp values that
+    /// are not ISO 8601.
+    pub date_format: Option<String>,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            format: None,
+            encoding: None,
+            delimiter: None,
+            quote: '"',
+            has_header: None,
+            null_values: vec!["NULL".to_string()],
+            date_format: None,
+        }
+    }
+}
+
+impl ImportOptions {
+    pub fn validate(&self) -> Result<()> {
+        if let Some(format) = &self.date_format {
+            if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                return Err(anyhow!("Invalid date format: {}", format));
+            }
+        }
+        let delimiter = self.delimiter.unwrap_or(',');
+        if !delimiter.is_ascii() || !self.quote.is_ascii() {
+            return Err(anyhow!(
+                "The delimiter and the quote must be ASCII characters"
+            ));
+        }
+        if delimiter == self.quote || matches!(delimiter, '\n' | '\r') {
+            return Err(anyhow!(
+                "The delimiter must differ from the quote and line breaks"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// How the file was read, as given or as guessed.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileLayout {
+    pub format: ImportFormat,
+    pub encoding: Encoding,
+    pub delimiter: char,
+    pub quote: char,
+    pub has_header: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceColumn {
+    pub name: String,
+    /// Position in CSV records and JSON arrays.
+    pub index: usize,
+    /// Inferred from the first rows of the file.
+    pub kind: ColumnKind,
+}
+
+/// The file column that fills a table column.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    pub source: String,
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportTarget {
+    #[serde(default)]
+    pub schema: String,
+    pub table: String,
+}
+
+/// The layout and columns of a file, read without touching the database.
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub layout: FileLayout,
+    pub columns: Vec<SourceColumn>,
+    /// The first rows, in the order of `columns`.
+    pub rows: Vec<Vec<Value>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub layout: FileLayout,
+    pub columns: Vec<SourceColumn>,
+    pub rows: Vec<Vec<Value>>,
+    pub table_exists: bool,
+    pub target_columns: Vec<TableColumn>,
+    pub mappings: Vec<ColumnMapping>,
+    /// What `create_table` would run, when the table does not exist yet.
+    pub create_table_sql: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ImportSettings {
+    pub options: ImportOptions,
+    /// The suggested mappings when unset.
+    pub mappings: Option<Vec<ColumnMapping>>,
+    /// Create the table from the inferred column types if it does not exist.
+    pub create_table: bool,
+    /// Read and convert every row without writing anything.
+    pub dry_run: bool,
+    /// `StopOnError` keeps the rows imported before the failing one.
+    pub on_error: ErrorPolicy,
+    pub batch_size: usize,
+}
+
+impl Default for ImportSettings {
+    fn default() -> Self {
+        Self {
+            options: ImportOptions::default(),
+            mappings: None,
+            create_table: false,
+            dry_run: false,
+            on_error: ErrorPolicy::StopOnError,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RowError {
+    /// 1-based position among the data rows of the file.
+    pub row: usize,
+    pub line: Option<usize>,
+    /// Table column whose value could not be converted.
+    pub column: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub rows_read: u64,
+    /// Rows written, or that would be written on a dry run.
+    pub rows_imported: u64,
+    pub rows_failed: u64,
+    pub errors: Vec<RowError>,
+    /// More rows failed than `errors` lists.
+    pub errors_truncated: bool,
+    pub created_table: bool,
+    pub create_table_sql: Option<String>,
+    pub dry_run: bool,
+    pub execution_time_ms: u64,
+}
+
+impl ImportReport {
+    fn fail(&mut self, error: RowError) {
+        self.rows_failed += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(error);
+        } else {
+            self.errors_truncated = true;
+        }
+    }
+}
+
+/// A file column mapped to a table column, with the kind values are converted to.
+#[derive(Debug, Clone)]
+pub struct MappedColumn {
+    pub source: SourceColumn,
+    pub target: String,
+    pub kind: ColumnKind,
+}
+
+/// Writes converted rows to the target table.
+#[async_trait]
+pub trait BatchInserter: Send {
+    /// Inserts `rows`, whose values follow the order of the mapped columns.
+    async fn insert(&mut self, rows: &[Vec<Value>]) -> Result<()>;
+
+    /// Whether a failed `insert` writes none of its rows, so that they can
+    /// be tried again one by one.
+    fn atomic(&self) -> bool {
+        true
+    }
+}
+
+/// Works out the layout of `path` and reads its first rows.
+pub async fn inspect(path: PathBuf, options: ImportOptions) -> Result<SourceFile> {
+    options.validate()?;
+    tokio::task::spawn_blocking(move || inspect_file(&path, &options)).await?
+}
+
+fn inspect_file(path: &Path, options: &ImportOptions) -> Result<SourceFile> {
+    let mut sample = Vec::new();
+    File::open(path)
+        .map_err(|e| anyhow!("Failed to open {}: {}", path.display(), e))?
+        .take(sniff::SAMPLE_BYTES as u64)
+        .read_to_end(&mut sample)?;
+    let encoding = options.encoding.unwrap_or_else(|| sniff::encoding(&sample));
+    let text = reader::decode(&sample, encoding);
+    let format = options.format.unwrap_or_else(|| sniff::format(path, &text));
+    let delimiter = options.delimiter.unwrap_or(match format {
+        ImportFormat::Tsv => '\t',
+        ImportFormat::Csv => sniff::delimiter(&text, options.quote),
+        _ => ',',
+    });
+    let mut layout = FileLayout {
+        format,
+        encoding,
+        delimiter,
+        quote: options.quote,
+        has_header: false,
+    };
+
+    let mut records = Vec::new();
+    reader::read_records(path, &layout, &options.null_values, |record| {
+        if let Ok(row) = record.row {
+            records.push(row);
+        }
+        records.len() <= SAMPLE_ROWS
+    })?;
+
+    let tabular = matches!(format, ImportFormat::Csv | ImportFormat::Tsv);
+    let fields: Vec<Vec<Value>> = records
+        .iter()
+        .filter_map(|row| match row {
+            Row::Fields(fields) => Some(fields.clone()),
+            Row::Object(_) => None,
+        })
+        .take(sniff::SAMPLE_RECORDS)
+        .collect();
+    layout.has_header = tabular
+        && options
+            .has_header
+            .unwrap_or_else(|| sniff::has_header(&fields));
+    let header = if layout.has_header && !records.is_empty() {
+        match records.remove(0) {
+            Row::Fields(fields) => Some(fields),
+            Row::Object(_) => None,
+        }
+    } else {
+        records.truncate(SAMPLE_ROWS);
+        None
+    };
+
+    let columns = source_columns(&records, header.as_deref(), options.date_format.as_deref());
+    let rows = records
+        .iter()
+        .take(PREVIEW_ROWS)
+        .map(|row| columns.iter().map(|column| row.value(column)).collect())
+        .collect();
+    Ok(SourceFile {
+        layout,
+        columns,
+        rows,
+    })
+}
+
+impl Row {
+    fn value(&self, column: &SourceColumn) -> Value {
+        let value = match self {
+            Row::Fields(fields) => fields.get(column.index),
+            Row::Object(object) => object.get(&column.name),
+        };
+        value.cloned().unwrap_or(Value::Null)
+    }
+}
+
+/// Names the columns after the header, the keys of JSON objects, or
+/// `column_1`, `column_2`, ... and infers their kinds from `records`.
+fn source_columns(
+    records: &[Row],
+    header: Option<&[Value]>,
+    date_format: Option<&str>,
+) -> Vec<SourceColumn> {
+    let mut names: Vec<String> = Vec::new();
+    let mut width = header.map_or(0, |h| h.len());
+    for row in records {
+        match row {
+            Row::Fields(fields) => width = width.max(fields.len()),
+            Row::Object(object) => {
+                for key in object.keys() {
+                    if !names.contains(key) {
+                        names.push(key.clone());
+                    }
+                }
+            }
+        }
+    }
+    let positional = (0..width).map(|i| {
+        header
+            .and_then(|h| h.get(i))
+            .and_then(|v| v.as_str())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("column_{}", i + 1))
+    });
+    // Object keys keep their names; positional columns go first.
+    let mut seen = HashSet::new();
+    let mut columns = Vec::new();
+    for (index, name) in positional.enumerate().chain(names.into_iter().enumerate()) {
+        let mut unique = name.clone();
+        let mut n = 1;
+        while !seen.insert(unique.clone()) {
+            n += 1;
+            unique = format!("{}_{}", name, n);
+        }
+        columns.push(SourceColumn {
+            name: unique,
+            index,
+            kind: ColumnKind::Text,
+        });
+    }
+    for column in &mut columns {
+        let values: Vec<Value> = records.iter().map(|row| row.value(column)).collect();
+        column.kind = ColumnKind::infer(values.iter(), date_format);
+    }
+    columns
+}
+
+/// Pairs each file column with the table column of the same name, compared
+/// exactly, then ignoring case, then as snake_case. Without a table, every
+/// file column gets a column named after it.
+pub fn suggest_mappings(
+    columns: &[SourceColumn],
+    targets: Option<&[TableColumn]>,
+) -> Vec<ColumnMapping> {
+    let Some(targets) = targets else {
+        return columns
+            .iter()
+            .map(|column| ColumnMapping {
+                source: column.name.clone(),
+                target: snake_case(&column.name),
+            })
+            .collect();
+    };
+    let matchers: [fn(&str, &str) -> bool; 3] = [
+        |a, b| a == b,
+        |a, b| a.eq_ignore_ascii_case(b),
+        |a, b| snake_case(a) == snake_case(b),
+    ];
+    let mut used = HashSet::new();
+    let mut mappings: Vec<Option<ColumnMapping>> = vec![None; columns.len()];
+    for matches in matchers {
+        for (column, mapping) in columns.iter().zip(mappings.iter_mut()) {
+            if mapping.is_some() {
+                continue;
+            }
+            let found = targets
+                .iter()
+                .find(|target| !used.contains(&target.name) && matches(&column.name, &target.name));
+            if let Some(target) = found {
+                used.insert(target.name.clone());
+                *mapping = Some(ColumnMapping {
+                    source: column.name.clone(),
+                    target: target.name.clone(),
+                });
+            }
+        }
+    }
+    mappings.into_iter().flatten().collect()
+}
+
+/// `First Name` -> `first_name`.
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.trim().chars() {
+        if c.is_alphanumeric() {
+            out.extend(c.to_lowercase());
+        } else if !out.is_empty() && !out.ends_with('_') {
+            out.push('_');
+        }
+    }
+    let out = out.trim_end_matches('_');
+    if out.is_empty() {
+        "column".to_string()
+    } else {
+        out.to_string()
+    }
+}
+
+/// Resolves `mappings` against the file columns and the kinds of the table
+/// columns, or the inferred kinds for a table that is yet to be created.
+pub fn mapped_columns(
+    source: &SourceFile,
+    mappings: &[ColumnMapping],
+    targets: Option<&[TableColumn]>,
+) -> Result<Vec<MappedColumn>> {
+    if mappings.is_empty() {
+        return Err(anyhow!("No file column is mapped to a table column"));
+    }
+    let mut seen = HashSet::new();
+    mappings
+        .iter()
+        .map(|mapping| {
+            if !seen.insert(mapping.target.as_str()) {
+                return Err(anyhow!(
+                    "Column {} is mapped more than once",
+                    mapping.target
+                ));
+            }
+            let column = source
+                .columns
+                .iter()
+                .find(|c| c.name == mapping.source)
+                .ok_or_else(|| anyhow!("The file has no column {}", mapping.source))?;
+            let kind = match targets {
+                Some(targets) => targets
+                    .iter()
+                    .find(|t| t.name == mapping.target)
+                    .map(|t| ColumnKind::from_data_type(&t.data_type))
+                    .ok_or_else(|| anyhow!("The table has no column {}", mapping.target))?,
+                None => column.kind,
+            };
+            Ok(MappedColumn {
+                source: column.clone(),
+                target: mapping.target.clone(),
+                kind,
+            })
+        })
+        .collect()
+}
+
+/// `CREATE TABLE` for the mapped columns, typed after their inferred kinds.
+pub fn create_table_sql(
+    engine: &str,
+    dialect: SqlDialect,
+    target: &ImportTarget,
+    columns: &[MappedColumn],
+) -> Result<String> {
+    let mut table = dialect.quote_ident(&target.table)?;
+    if !target.schema.is_empty() {
+        table = format!("{}.{}", dialect.quote_ident(&target.schema)?, table);
+    }
+    let definitions = columns
+        .iter()
+        .map(|column| {
+            Ok(format!(
+                "    {} {}",
+                dialect.quote_ident(&column.target)?,
+                column.kind.sql_type(engine)
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let engine_clause = if engine == "clickhouse" {
+        " ENGINE = MergeTree ORDER BY tuple()"
+    } else {
+        ""
+    };
+    Ok(format!(
+        "CREATE TABLE {} (\n{}\n){}",
+        table,
+        definitions.join(",\n"),
+        engine_clause
+    ))
+}
+
+/// Reads `path` and writes its rows through `inserter` in batches, converting
+/// each value to the kind of its column. Nothing is written when `inserter`
+/// is `None`. A failed batch is retried row by row to find the failing rows
+/// when the inserter is atomic; otherwise all of its rows are reported failed,
+/// as retrying could write some of them twice.
+pub async fn load(
+    path: PathBuf,
+    layout: FileLayout,
+    settings: &ImportSettings,
+    columns: &[MappedColumn],
+    mut inserter: Option<&mut dyn BatchInserter>,
+) -> Result<ImportReport> {
+    let started = Instant::now();
+    let batch_size = settings.batch_size.max(1);
+    let (tx, mut rx) = mpsc::channel::<Record>(batch_size);
+    let null_values = settings.options.null_values.clone();
+    let reading = tokio::task::spawn_blocking(move || {
+        reader::read_records(&path, &layout, &null_values, |record| {
+            tx.blocking_send(record).is_ok()
+        })
+    });
+
+    let date_format = settings.options.date_format.as_deref();
+    let stop_on_error = settings.on_error == ErrorPolicy::StopOnError;
+    let mut report = ImportReport {
+        dry_run: settings.dry_run,
+        ..Default::default()
+    };
+    let mut batch: Vec<(RowError, Vec<Value>)> = Vec::with_capacity(batch_size);
+    let mut stopped = false;
+    while let Some(record) = rx.recv().await {
+        report.rows_read += 1;
+        match convert(record, columns, date_format) {
+            Ok(row) => batch.push(row),
+            Err(error) => {
+                report.fail(error);
+                if stop_on_error {
+                    stopped = true;
+                    break;
+                }
+            }
+        }
+        if batch.len() >= batch_size {
+            let rows = std::mem::take(&mut batch);
+            if !flush(&mut inserter, rows, stop_on_error, &mut report).await {
+                stopped = true;
+                break;
+            }
+        }
+    }
+    if !batch.is_empty() {
+        stopped |= !flush(&mut inserter, batch, stop_on_error, &mut report).await;
+    }
+    drop(rx);
+
+    let read = reading.await?;
+    if !stopped {
+        if let Err(e) = read {
+            report.errors.push(RowError {
+                row: report.rows_read as usize + 1,
+                line: None,
+                column: None,
+                message: format!("Reading stopped: {}", e),
+            });
+        }
+    }
+    report.execution_time_ms = started.elapsed().as_millis() as u64;
+    Ok(report)
+}
+
+/// The values of `record` in the order of `columns`. The error of a row that
+/// converts carries where the row is, for reporting it if its insert fails.
+fn convert(
+    record: Record,
+    columns: &[MappedColumn],
+    date_format: Option<&str>,
+) -> Result<(RowError, Vec<Value>), RowError> {
+    let mut error = RowError {
+        row: record.number,
+        line: record.line,
+        column: None,
+        message: String::new(),
+    };
+    let row = match record.row {
+        Ok(row) => row,
+        Err(message) => {
+            error.message = message;
+            return Err(error);
+        }
+    };
+    let mut values = Vec::with_capacity(columns.len());
+    for column in columns {
+        match column.kind.coerce(&row.value(&column.source), date_format) {
+            Ok(value) => values.push(value),
+            Err(message) => {
+                error.column = Some(column.target.clone());
+                error.message = message;
+                return Err(error);
+            }
+        }
+    }
+    Ok((error, values))
+}
+
+/// Inserts `batch` and returns whether to go on.
+async fn flush(
+    inserter: &mut Option<&mut dyn BatchInserter>,
+    batch: Vec<(RowError, Vec<Value>)>,
+    stop_on_error: bool,
+    report: &mut ImportReport,
+) -> bool {
+    let Some(inserter) = inserter else {
+        report.rows_imported += batch.len() as u64;
+        return true;
+    };
+    let (locations, rows): (Vec<RowError>, Vec<Vec<Value>>) = batch.into_iter().unzip();
+    let error = match inserter.insert(&rows).await {
+        Ok(()) => {
+            report.rows_imported += rows.len() as u64;
+            return true;
+        }
+        Err(e) => e,
+    };
+    if !inserter.atomic() {
+        for mut location in locations {
+            location.message = format!("The batch of this row failed: {}", error);
+            report.fail(location);
+        }
+        return !stop_on_error;
+    }
+    for (mut location, row) in locations.into_iter().zip(rows) {
+        match inserter.insert(std::slice::from_ref(&row)).await {
+            Ok(()) => report.rows_imported += 1,
+            Err(e) => {
+                location.message = e.to_string();
+                report.fail(location);
+                if stop_on_error {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Fails rows whose first value is negative.
+    #[derive(Default)]
+    struct Recorder(Vec<Vec<Value>>);
+
+    #[async_trait]
+    impl BatchInserter for Recorder {
+        async fn insert(&mut self, rows: &[Vec<Value>]) -> Result<()> {
+            if rows
+                .iter()
+                .any(|row| row[0].as_i64().is_some_and(|n| n < 0))
+            {
+                return Err(anyhow!("negative id"));
+            }
+            self.0.extend_from_slice(rows);
+            Ok(())
+        }
+    }
+
+    /// A `Recorder` whose batches may be partly written when they fail.
+    #[derive(Default)]
+    struct Chunked(Recorder);
+
+    #[async_trait]
+    impl BatchInserter for Chunked {
+        async fn insert(&mut self, rows: &[Vec<Value>]) -> Result<()> {
+            self.0.insert(rows).await
+        }
+
+        fn atomic(&self) -> bool {
+            false
+        }
+    }
+
+    fn column(name: &str, index: usize, kind: ColumnKind) -> SourceColumn {
+        SourceColumn {
+            name: name.into(),
+            index,
+            kind,
+        }
+    }
+
+    #[tokio::test]
+    async fn sniffs_maps_and_loads_a_csv_file() {
+        let path = std::env::temp_dir().join(format!("import-{}.csv", uuid::Uuid::new_v4()));
+        std::fs::write(
+            &path,
+            "Id;First Name;Joined\n1;Ann;2024-01-02\n-2;Bob;2024-01-03\nx;Cy;2024-01-04\n3;Di;\n",
+        )
+        .unwrap();
+
+        let source = inspect(path.clone(), ImportOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(source.layout.delimiter, ';');
+        assert!(source.layout.has_header);
+        let names: Vec<_> = source.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["Id", "First Name", "Joined"]);
+        assert_eq!(source.columns[2].kind, ColumnKind::Date);
+        assert_eq!(source.rows[3], vec![json!("3"), json!("Di"), Value::Null]);
+
+        let targets: Vec<TableColumn> = ["id", "first_name", "joined"]
+            .iter()
+            .zip(["bigint", "text", "date"])
+            .map(|(name, data_type)| TableColumn {
+                name: name.to_string(),
+                data_type: data_type.to_string(),
+                is_nullable: true,
+                is_primary_key: false,
+                is_foreign_key: false,
+                default_value: None,
+            })
+            .collect();
+        let mappings = suggest_mappings(&source.columns, Some(&targets));
+        assert_eq!(mappings[1].target, "first_name");
+        let columns = mapped_columns(&source, &mappings, Some(&targets)).unwrap();
+
+        let settings = ImportSettings {
+            on_error: ErrorPolicy::ContinueOnError,
+            batch_size: 2,
+            ..Default::default()
+        };
+        let mut recorder = Recorder::default();
+        let report = load(
+            path.clone(),
+            source.layout.clone(),
+            &settings,
+            &columns,
+            Some(&mut recorder),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            recorder.0,
+            vec![
+                vec![json!(1), json!("Ann"), json!("2024-01-02")],
+                vec![json!(3), json!("Di"), Value::Null],
+            ]
+        );
+        assert_eq!(
+            (report.rows_read, report.rows_imported, report.rows_failed),
+            (4, 2, 2)
+        );
+        assert_eq!(report.errors[0].message, "negative id");
+        assert_eq!(report.errors[0].line, Some(3));
+        assert_eq!(report.errors[1].column.as_deref(), Some("id"));
+
+        let settings = ImportSettings::default();
+        let report = load(path.clone(), source.layout, &settings, &columns, None)
+            .await
+            .unwrap();
+        assert_eq!((report.rows_imported, report.rows_failed), (2, 1));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_batches_that_may_be_partly_written() {
+        let path = std::env::temp_dir().join(format!("import-{}.csv", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "id\n1\n-2\n3\n").unwrap();
+        let source = inspect(path.clone(), ImportOptions::default())
+            .await
+            .unwrap();
+        let mappings = suggest_mappings(&source.columns, None);
+        let columns = mapped_columns(&source, &mappings, None).unwrap();
+        let settings = ImportSettings {
+            on_error: ErrorPolicy::ContinueOnError,
+            batch_size: 2,
+            ..Default::default()
+        };
+
+        let mut inserter = Chunked::default();
+        let report = load(
+            path.clone(),
+            source.layout,
+            &settings,
+            &columns,
+            Some(&mut inserter),
+        )
+        .await
+        .unwrap();
+        assert_eq!(inserter.0 .0, vec![vec![json!(3)]]);
+        assert_eq!((report.rows_imported, report.rows_failed), (1, 2));
+        assert_eq!(
+            report.errors[0].message,
+            "The batch of this row failed: negative id"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn creates_tables_from_inferred_kinds() {
+        let source = SourceFile {
+            layout: FileLayout {
+                format: ImportFormat::Json,
+                encoding: Encoding::Utf8,
+                delimiter: ',',
+                quote: '"',
+                has_header: false,
+            },
+            columns: vec![
+                column("Order ID", 0, ColumnKind::Integer),
+                column("placed", 1, ColumnKind::TimestampTz),
+            ],
+            rows: Vec::new(),
+        };
+        let mappings = suggest_mappings(&source.columns, None);
+        let columns = mapped_columns(&source, &mappings, None).unwrap();
+        let target = ImportTarget {
+            schema: "staging".into(),
+            table: "orders".into(),
+        };
+        assert_eq!(
+            create_table_sql("postgres", SqlDialect::POSTGRES, &target, &columns).unwrap(),
+            "CREATE TABLE \"staging\".\"orders\" (\n    \"order_id\" BIGINT,\n    \"placed\" TIMESTAMPTZ\n)"
+        );
+    }
+}
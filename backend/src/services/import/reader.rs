@@ -0,0 +1,472 @@
+//! Streaming readers for the import formats. Files are decoded to UTF-8 on the
+//! fly, so only the record being read is held in memory.
+
+use super::{Encoding, FileLayout, ImportFormat};
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+/// One record of the file, before it is mapped to the table.
+pub(super) struct Record {
+    /// 1-based position among the data rows.
+    pub number: usize,
+    /// Line the record starts on; JSON arrays do not track lines.
+    pub line: Option<usize>,
+    pub row: Result<Row, String>,
+}
+
+pub(super) enum Row {
+    /// CSV fields or a JSON array, by position.
+    Fields(Vec<Value>),
+    Object(Map<String, Value>),
+}
+
+/// Reads the records of `path` in order and hands them to `on_record` until it
+/// returns `false` or the file ends. Blocking.
+pub(super) fn read_records(
+    path: &Path,
+    layout: &FileLayout,
+    null_values: &[String],
+    on_record: impl FnMut(Record) -> bool,
+) -> anyhow::Result<()> {
+    let file = File::open(path)?;
+    read_from(BufReader::new(file), layout, null_values, on_record)
+}
+
+pub(super) fn read_from(
+    input: impl Read,
+    layout: &FileLayout,
+    null_values: &[String],
+    mut on_record: impl FnMut(Record) -> bool,
+) -> anyhow::Result<()> {
+    let input = BufReader::new(Transcoder::new(input, layout.encoding));
+    match layout.format {
+        ImportFormat::Csv | ImportFormat::Tsv => {
+            let mut reader = CsvReader::new(input, layout.delimiter, layout.quote);
+            let mut number = 0;
+            let mut header = layout.has_header;
+            while let Some((line, fields)) = reader.next_record()? {
+                if std::mem::take(&mut header) {
+                    continue;
+                }
+                number += 1;
+                let row = fields.map(|fields| {
+                    Row::Fields(
+                        fields
+                            .into_iter()
+                            .map(|field| field.into_value(null_values))
+                            .collect(),
+                    )
+                });
+                let record = Record {
+                    number,
+                    line: Some(line),
+                    row,
+                };
+                if !on_record(record) {
+                    break;
+                }
+            }
+            Ok(())
+        }
+        ImportFormat::Ndjson => {
+            let mut number = 0;
+            for (index, line) in input.split(b'\n').enumerate() {
+                let line = line?;
+                let text = String::from_utf8_lossy(&line);
+                if text.trim().is_empty() {
+                    continue;
+                }
+                number += 1;
+                let row = serde_json::from_str::<Value>(&text)
+                    .map_err(|e| format!("Invalid JSON: {}", e))
+                    .and_then(json_row);
+                let record = Record {
+                    number,
+                    line: Some(index + 1),
+                    row,
+                };
+                if !on_record(record) {
+                    break;
+                }
+            }
+            Ok(())
+        }
+        ImportFormat::Json => {
+            let mut de = serde_json::Deserializer::from_reader(input);
+            let mut visitor = DocumentVisitor {
+                on_record: &mut on_record,
+                stopped: false,
+            };
+            let result = (&mut de).deserialize_any(&mut visitor);
+            // Stopping early leaves the array unfinished, which serde reports.
+            if visitor.stopped {
+                return Ok(());
+            }
+            result.map_err(|e| anyhow::anyhow!("Invalid JSON: {}", e))?;
+            de.end().map_err(|e| anyhow::anyhow!("Invalid JSON: {}", e))
+        }
+    }
+}
+
+/// `bytes` decoded as text, for sniffing a sample of the file.
+pub(super) fn decode(bytes: &[u8], encoding: Encoding) -> String {
+    let mut text = Vec::new();
+    // Reading from a slice does not fail.
+    let _ = Transcoder::new(bytes, encoding).read_to_end(&mut text);
+    String::from_utf8_lossy(&text).into_owned()
+}
+
+fn json_row(value: Value) -> Result<Row, String> {
+    match value {
+        Value::Object(object) => Ok(Row::Object(object)),
+        Value::Array(items) => Ok(Row::Fields(items)),
+        other => Err(format!("Expected an object or an array, found {}", other)),
+    }
+}
+
+struct DocumentVisitor<'a, F> {
+    on_record: &'a mut F,
+    stopped: bool,
+}
+
+impl<'de, F: FnMut(Record) -> bool> Visitor<'de> for &mut DocumentVisitor<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of objects or one object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<(), A::Error> {
+        let object = Map::deserialize(MapAccessDeserializer::new(map))?;
+        let record = Record {
+            number: 1,
+            line: Some(1),
+            row: Ok(Row::Object(object)),
+        };
+        (self.on_record)(record);
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let mut number = 0;
+        while let Some(value) = seq.next_element::<Value>()? {
+            number += 1;
+            let record = Record {
+                number,
+                line: None,
+                row: json_row(value),
+            };
+            if !(self.on_record)(record) {
+                self.stopped = true;
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(super) struct Field {
+    text: String,
+    quoted: bool,
+}
+
+impl Field {
+    /// Unquoted empty fields and the `null_values` are NULL; `""` is an empty
+    /// string.
+    fn into_value(self, null_values: &[String]) -> Value {
+        if !self.quoted && (self.text.is_empty() || null_values.contains(&self.text)) {
+            Value::Null
+        } else {
+            Value::String(self.text)
+        }
+    }
+}
+
+/// The line a record starts on and its fields.
+pub(super) type CsvRecord = (usize, Result<Vec<Field>, String>);
+
+/// RFC 4180 records: quoted fields may hold the delimiter, doubled quotes and
+/// line breaks.
+pub(super) struct CsvReader<R> {
+    input: R,
+    delimiter: u8,
+    quote: u8,
+    line: usize,
+}
+
+impl<R: BufRead> CsvReader<R> {
+    pub(super) fn new(input: R, delimiter: char, quote: char) -> Self {
+        Self {
+            input,
+            delimiter: delimiter as u8,
+            quote: quote as u8,
+            line: 0,
+        }
+    }
+
+    /// The next non-empty record and the line it starts on. A record that
+    /// is not valid UTF-8 is returned as an error.
+    pub(super) fn next_record(&mut self) -> io::Result<Option<CsvRecord>> {
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            if self.input.read_until(b'\n', &mut buf)? == 0 {
+                return Ok(None);
+            }
+            self.line += 1;
+            if !buf.iter().all(|b| b.is_ascii_whitespace()) || buf.contains(&self.delimiter) {
+                break;
+            }
+        }
+        let start = self.line;
+
+        let mut fields = Vec::new();
+        let mut field = Vec::new();
+        let mut quoted = false;
+        let mut in_quotes = false;
+        let mut i = 0;
+        loop {
+            if i == buf.len() {
+                if !in_quotes {
+                    break;
+                }
+                // The line break belongs to the quoted field.
+                let before = buf.len();
+                if self.input.read_until(b'\n', &mut buf)? == 0 {
+                    break;
+                }
+                self.line += 1;
+                i = before;
+                continue;
+            }
+            let b = buf[i];
+            i += 1;
+            if in_quotes {
+                if b == self.quote {
+                    if buf.get(i) == Some(&self.quote) {
+                        field.push(b);
+                        i += 1;
+                    } else {
+                        in_quotes = false;
+                    }
+                } else {
+                    field.push(b);
+                }
+            } else if b == self.quote && field.is_empty() && !quoted {
+                in_quotes = true;
+                quoted = true;
+            } else if b == self.delimiter {
+                fields.push((std::mem::take(&mut field), quoted));
+                quoted = false;
+            } else if b == b'\n' || (b == b'\r' && buf.get(i) == Some(&b'\n')) {
+                continue;
+            } else {
+                field.push(b);
+            }
+        }
+        fields.push((field, quoted));
+
+        let fields = fields
+            .into_iter()
+            .map(|(bytes, quoted)| String::from_utf8(bytes).map(|text| Field { text, quoted }))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("Line {} is not valid UTF-8", start));
+        Ok(Some((start, fields)))
+    }
+}
+
+/// Decodes UTF-16 and Windows-1252 input to UTF-8 and drops byte order marks.
+struct Transcoder<R> {
+    input: R,
+    encoding: Encoding,
+    pending: Vec<u8>,
+    pos: usize,
+    started: bool,
+    eof: bool,
+}
+
+impl<R: Read> Transcoder<R> {
+    fn new(input: R, encoding: Encoding) -> Self {
+        Self {
+            input,
+            encoding,
+            pending: Vec::new(),
+            pos: 0,
+            started: false,
+            eof: false,
+        }
+    }
+
+    /// Reads the next chunk of input and decodes it into `pending`.
+    fn fill(&mut self) -> io::Result<()> {
+        let mut chunk = [0u8; 8192];
+        let mut raw = Vec::new();
+        // UTF-16 needs whole code units, and surrogate pairs need both units.
+        while !self.eof
+            && (raw.is_empty() || (self.encoding.is_utf16() && !whole_utf16(&raw, self.encoding)))
+        {
+            let n = self.input.read(&mut chunk)?;
+            if n == 0 {
+                self.eof = true;
+            }
+            raw.extend_from_slice(&chunk[..n]);
+        }
+        let mut raw = raw.as_slice();
+        if !self.started {
+            self.started = true;
+            raw = raw.strip_prefix(self.encoding.bom()).unwrap_or(raw);
+        }
+
+        self.pending.clear();
+        self.pos = 0;
+        match self.encoding {
+            Encoding::Utf8 => self.pending.extend_from_slice(raw),
+            Encoding::Windows1252 => {
+                let text: String = raw.iter().map(|&b| windows_1252(b)).collect();
+                self.pending.extend_from_slice(text.as_bytes());
+            }
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                let units = raw.chunks(2).map(|pair| match (self.encoding, pair) {
+                    (Encoding::Utf16Le, [lo, hi]) => u16::from_le_bytes([*lo, *hi]),
+                    (_, [hi, lo]) => u16::from_be_bytes([*hi, *lo]),
+                    _ => 0xFFFD,
+                });
+                let text: String = char::decode_utf16(units)
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect();
+                self.pending.extend_from_slice(text.as_bytes());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `raw` ends on a complete UTF-16 character.
+fn whole_utf16(raw: &[u8], encoding: Encoding) -> bool {
+    if raw.len() % 2 == 1 {
+        return false;
+    }
+    let last = match encoding {
+        Encoding::Utf16Le => u16::from_le_bytes([raw[raw.len() - 2], raw[raw.len() - 1]]),
+        _ => u16::from_be_bytes([raw[raw.len() - 2], raw[raw.len() - 1]]),
+    };
+    !(0xD800..0xDC00).contains(&last)
+}
+
+impl<R: Read> Read for Transcoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.pending.len() {
+            if self.eof {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+        let n = buf.len().min(self.pending.len() - self.pos);
+        buf[..n].copy_from_slice(&self.pending[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// Windows-1252 is Latin-1 except for 0x80-0x9F.
+fn windows_1252(b: u8) -> char {
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž',
+        '\u{8F}', '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}',
+        'ž', 'Ÿ',
+    ];
+    match b {
+        0x80..=0x9F => HIGH[(b - 0x80) as usize],
+        _ => b as char,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn read(bytes: &[u8], layout: &FileLayout) -> Vec<Result<Value, String>> {
+        let mut records = Vec::new();
+        read_from(bytes, layout, &["NULL".to_string()], |record| {
+            records.push(record.row.map(|row| match row {
+                Row::Fields(fields) => Value::Array(fields),
+                Row::Object(object) => Value::Object(object),
+            }));
+            true
+        })
+        .unwrap();
+        records
+    }
+
+    #[test]
+    fn reads_quoted_csv_and_transcodes() {
+        let layout = FileLayout {
+            format: ImportFormat::Csv,
+            encoding: Encoding::Utf8,
+            delimiter: ';',
+            quote: '"',
+            has_header: true,
+        };
+        let csv = "\u{FEFF}id;note\r\n1;\"a;\"\"b\"\"\r\nc\"\r\n\r\n2;\r\n3;\"\"\r\n4;NULL\n";
+        assert_eq!(
+            read(csv.as_bytes(), &layout),
+            vec![
+                Ok(json!(["1", "a;\"b\"\r\nc"])),
+                Ok(json!(["2", null])),
+                Ok(json!(["3", ""])),
+                Ok(json!(["4", null])),
+            ]
+        );
+
+        let utf16: Vec<u8> = [0xFEFF_u16]
+            .into_iter()
+            .chain("x\n\u{1F600}é\n".encode_utf16())
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let layout = FileLayout {
+            encoding: Encoding::Utf16Le,
+            ..layout
+        };
+        assert_eq!(read(&utf16, &layout), vec![Ok(json!(["\u{1F600}é"]))]);
+
+        let layout = FileLayout {
+            encoding: Encoding::Windows1252,
+            has_header: false,
+            ..layout
+        };
+        assert_eq!(read(b"\x80 5\n", &layout), vec![Ok(json!(["€ 5"]))]);
+    }
+
+    #[test]
+    fn reads_json_documents() {
+        let layout = FileLayout {
+            format: ImportFormat::Json,
+            encoding: Encoding::Utf8,
+            delimiter: ',',
+            quote: '"',
+            has_header: false,
+        };
+        assert_eq!(
+            read(br#"[{"a": 1}, [2, 3], 4]"#, &layout),
+            vec![
+                Ok(json!({ "a": 1 })),
+                Ok(json!([2, 3])),
+                Err("Expected an object or an array, found 4".to_string()),
+            ]
+        );
+
+        let layout = FileLayout {
+            format: ImportFormat::Ndjson,
+            ..layout
+        };
+        let rows = read(b"{\"a\": 1}\n\n{oops}\n", &layout);
+        assert_eq!(rows[0], Ok(json!({ "a": 1 })));
+        assert!(rows[1].is_err());
+    }
+}
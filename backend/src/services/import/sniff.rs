@@ -0,0 +1,170 @@
+//! Guesses how a file is laid out from its first bytes.
+
+use super::coerce::ColumnKind;
+use super::reader::CsvReader;
+use super::{Encoding, ImportFormat};
+use serde_json::Value;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Bytes read to guess the encoding, format, delimiter and header.
+pub(super) const SAMPLE_BYTES: usize = 64 * 1024;
+const DELIMITERS: [char; 4] = [',', '\t', ';', '|'];
+/// Records compared when guessing the delimiter and header.
+pub(super) const SAMPLE_RECORDS: usize = 50;
+
+pub(super) fn encoding(sample: &[u8]) -> Encoding {
+    if sample.starts_with(Encoding::Utf8.bom()) {
+        return Encoding::Utf8;
+    }
+    if sample.starts_with(Encoding::Utf16Le.bom()) {
+        return Encoding::Utf16Le;
+    }
+    if sample.starts_with(Encoding::Utf16Be.bom()) {
+        return Encoding::Utf16Be;
+    }
+    // ASCII text in UTF-16 has a zero byte in every other position.
+    let pairs = sample.len() / 2;
+    if pairs > 0 {
+        let zeros_at = |offset: usize| {
+            sample
+                .chunks_exact(2)
+                .filter(|pair| pair[offset] == 0)
+                .count()
+        };
+        if zeros_at(1) * 2 > pairs {
+            return Encoding::Utf16Le;
+        }
+        if zeros_at(0) * 2 > pairs {
+            return Encoding::Utf16Be;
+        }
+    }
+    match std::str::from_utf8(sample) {
+        Ok(_) => Encoding::Utf8,
+        // A full sample may end inside a character.
+        Err(e) if e.error_len().is_none() && sample.len() >= SAMPLE_BYTES => Encoding::Utf8,
+        Err(_) => Encoding::Windows1252,
+    }
+}
+
+/// The format named by the extension, else the one the text looks like.
+pub(super) fn format(path: &Path, text: &str) -> ImportFormat {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+    match extension.as_deref() {
+        Some("tsv" | "tab") => return ImportFormat::Tsv,
+        Some("ndjson" | "jsonl") => return ImportFormat::Ndjson,
+        Some("csv") => return ImportFormat::Csv,
+        _ => {}
+    }
+    match text.trim_start().chars().next() {
+        Some('[') => ImportFormat::Json,
+        // A single document spread over lines is still one JSON value.
+        Some('{') if extension.as_deref() == Some("json") => {
+            if serde_json::from_str::<Value>(text.trim()).is_ok() {
+                ImportFormat::Json
+            } else {
+                ImportFormat::Ndjson
+            }
+        }
+        Some('{') => ImportFormat::Ndjson,
+        _ if extension.as_deref() == Some("json") => ImportFormat::Json,
+        _ => ImportFormat::Csv,
+    }
+}
+
+/// The candidate that splits the sample into the most records of the same
+/// width, then into the most fields.
+pub(super) fn delimiter(text: &str, quote: char) -> char {
+    let mut best = (',', (0, 0));
+    for delimiter in DELIMITERS {
+        let widths = record_widths(text, delimiter, quote);
+        let Some(&first) = widths.first() else {
+            continue;
+        };
+        if first < 2 {
+            continue;
+        }
+        let score = (widths.iter().filter(|&&w| w == first).count(), first);
+        if score > best.1 {
+            best = (delimiter, score);
+        }
+    }
+    best.0
+}
+
+fn record_widths(text: &str, delimiter: char, quote: char) -> Vec<usize> {
+    let mut reader = CsvReader::new(BufReader::new(text.as_bytes()), delimiter, quote);
+    let mut widths = Vec::new();
+    while widths.len() < SAMPLE_RECORDS {
+        match reader.next_record() {
+            Ok(Some((_, Ok(fields)))) => widths.push(fields.len()),
+            Ok(Some((_, Err(_)))) => continue,
+            _ => break,
+        }
+    }
+    // The sample may cut the last record short.
+    if widths.len() > 1 {
+        widths.pop();
+    }
+    widths
+}
+
+/// A header row holds distinct, non-empty names that are not numbers, dates
+/// or booleans, and that do not turn up again as values of their column.
+pub(super) fn has_header(records: &[Vec<Value>]) -> bool {
+    let Some((first, rest)) = records.split_first() else {
+        return false;
+    };
+    let names: Vec<&str> = first.iter().filter_map(|v| v.as_str()).collect();
+    if names.len() != first.len() || names.iter().any(|n| n.trim().is_empty()) {
+        return false;
+    }
+    let mut distinct = names.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    if distinct.len() != names.len() {
+        return false;
+    }
+    if first
+        .iter()
+        .any(|name| ColumnKind::infer(std::iter::once(name), None) != ColumnKind::Text)
+    {
+        return false;
+    }
+    let repeated = names.iter().enumerate().any(|(i, name)| {
+        rest.iter()
+            .any(|row| row.get(i).and_then(|v| v.as_str()) == Some(name))
+    });
+    !repeated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn guesses_the_layout() {
+        assert_eq!(encoding(b"id,name\n1,\xc3\xa9"), Encoding::Utf8);
+        assert_eq!(encoding(b"id,name\n1,\xe9"), Encoding::Windows1252);
+        assert_eq!(encoding(b"i\0d\0,\0"), Encoding::Utf16Le);
+
+        let path = Path::new("people.txt");
+        assert_eq!(format(path, " [{\"a\": 1}]"), ImportFormat::Json);
+        assert_eq!(format(path, "{\"a\": 1}\n{\"a\": 2}"), ImportFormat::Ndjson);
+        assert_eq!(format(Path::new("a.TSV"), "a,b"), ImportFormat::Tsv);
+
+        assert_eq!(delimiter("a;b,c;d\n1;2,5;3\n4;5;6\n", '"'), ';');
+        assert_eq!(delimiter("a\tb\n\"x\ty\"\t2\n", '"'), '\t');
+        assert_eq!(delimiter("just one column\n", '"'), ',');
+
+        let rows = |value: Value| -> Vec<Vec<Value>> { serde_json::from_value(value).unwrap() };
+        assert!(has_header(&rows(json!([["id", "name"], ["1", "Ann"]]))));
+        assert!(!has_header(&rows(json!([["1", "Ann"], ["2", "Bob"]]))));
+        assert!(!has_header(&rows(json!([["yes", "Ann"], ["no", "Bob"]]))));
+        assert!(!has_header(&rows(json!([["red", "x"], ["red", "y"]]))));
+    }
+}
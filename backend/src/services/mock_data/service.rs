@@ -1,4 +1,5 @@
 use super::generator::{GenerationConfig, MockDataGenerator, MockDataType};
+use crate::services::bulk::{BulkReport, OnBulkProgress};
 use crate::services::connection_service::ConnectionService;
 use anyhow::Result;
 use sea_orm::DatabaseConnection;
@@ -87,6 +88,49 @@ impl MockDataService {
         Ok(sql)
     }
 
+    /// Generates the rows and writes them with the engine's bulk insert path,
+    /// through `connections` so that its cached drivers are reused.
+    pub async fn insert_data(
+        &self,
+        connections: &ConnectionService,
+        connection_id: Uuid,
+        schema: &str,
+        mut config: GenerationConfig,
+        on_progress: OnBulkProgress<'_>,
+    ) -> Result<BulkReport> {
+        self.resolve_foreign_keys(connection_id, &mut config)
+            .await?;
+
+        let generator = MockDataGenerator::new();
+        let data = generator.generate_data(&config)?;
+
+        let columns: Vec<String> = config
+            .rules
+            .iter()
+            .map(|rule| rule.column_name.clone())
+            .collect();
+        let rows: Vec<Vec<Value>> = data
+            .into_iter()
+            .map(|mut row| {
+                columns
+                    .iter()
+                    .map(|column| row.remove(column).unwrap_or(Value::Null))
+                    .collect()
+            })
+            .collect();
+
+        connections
+            .bulk_insert(
+                connection_id,
+                schema,
+                &config.table_name,
+                &columns,
+                &rows,
+                on_progress,
+            )
+            .await
+    }
+
     async fn resolve_foreign_keys(
         &self,
         connection_id: Uuid,
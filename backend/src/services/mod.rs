@@ -1,23 +1,43 @@
 pub mod autocomplete;
+pub mod bulk;
 pub mod clickhouse;
 pub mod connection_service;
 pub mod couchbase;
 pub mod credential_service;
 pub mod dashboard_service;
+pub mod data_diff;
 pub mod db_driver;
 pub mod ddl_generator;
 pub mod driver;
+pub mod driver_factory;
+pub mod driver_registry;
+pub mod dump;
 pub mod encryption_service;
+pub mod export;
 pub mod history_service;
+pub mod import;
 pub mod mock_data;
 pub mod mongo;
 pub mod mysql;
 pub mod pg_dump;
 pub mod postgres;
 pub mod postgres_driver;
+pub mod query_cancel;
+pub mod query_limits;
+pub mod query_paging;
+pub mod query_params;
+pub mod query_stream;
+pub mod reaper;
 pub mod saved_filter_service;
 pub mod saved_query_folder_service;
 pub mod saved_query_service;
 pub mod schema_diff;
+pub mod script_execution;
 pub mod snippet_service;
+pub mod sql_template;
 pub mod sqlite;
+pub mod ssh_tunnel;
+pub mod table_filter;
+pub mod tls;
+pub mod transaction_manager;
+pub mod transfer;
@@ -1,14 +1,18 @@
 use crate::models::entities::connection as ConnectionModel;
-use crate::services::driver::ConnectionDriver;
+use crate::services::driver::{ConnectionDriver, ConnectionPoolDriver};
+use crate::services::query_limits::QueryLimits;
+use crate::services::tls::{pem_file, read_pem, write_pem_file, PemFile, SslMode, TlsSettings};
 use anyhow::Result;
 use async_trait::async_trait;
-use mongodb::options::{ClientOptions, Compressor, ServerApi, ServerApiVersion};
+use mongodb::options::{ClientOptions, Compressor, ServerApi, ServerApiVersion, Tls, TlsOptions};
 use mongodb::Client;
 use std::time::Duration;
 
 pub struct MongoDriver {
     pub client: Client,
     pub database_name: Option<String>,
+    /// Sent as `maxTimeMS` with commands, finds and aggregations.
+    pub max_time: Option<Duration>,
 }
 
 impl MongoDriver {
@@ -39,6 +43,15 @@ impl MongoDriver {
 
         let mut client_options = ClientOptions::parse(&uri).await?;
 
+        let tls = TlsSettings::from_model(connection)?;
+        tls.ensure_supported("mongodb")?;
+        let mut pem_files = Vec::new();
+        if tls.is_enabled() {
+            let (options, files) = tls_options(&tls)?;
+            client_options.tls = Some(Tls::Enabled(options));
+            pem_files = files;
+        }
+
         // 🔥 OPTIMIZED: Connection pool configuration
         client_options.min_pool_size = Some(3); // Keep 3 connections warm
         client_options.max_pool_size = Some(20); // Max 20 concurrent connections
@@ -62,6 +75,8 @@ impl MongoDriver {
         client_options.server_api = Some(server_api);
 
         let client = Client::with_options(client_options)?;
+        // The driver has read the PEM files while building the client.
+        drop(pem_files);
 
         tracing::info!("[MongoConnection] Optimized pool: min=3, max=20, compression enabled");
 
@@ -72,10 +87,52 @@ impl MongoDriver {
             } else {
                 Some(connection.database.clone())
             },
+            max_time: QueryLimits::from_connection(connection).statement_timeout,
         })
     }
 }
 
+/// The driver only takes file paths, and wants the client certificate and
+/// key in a single PEM file. The returned files must outlive building the
+/// client.
+fn tls_options(tls: &TlsSettings) -> Result<(TlsOptions, Vec<PemFile>)> {
+    let ca_file = tls.ca.as_deref().map(pem_file).transpose()?;
+
+    let cert_key_file = match (&tls.client_cert, &tls.client_key) {
+        (Some(cert), Some(key)) => {
+            let mut combined = read_pem(cert)?;
+            combined.push(b'\n');
+            combined.extend(read_pem(key)?);
+            Some(write_pem_file(&combined)?)
+        }
+        _ => None,
+    };
+
+    let options = TlsOptions::builder()
+        .ca_file_path(ca_file.as_ref().map(PemFile::path))
+        .cert_key_file_path(cert_key_file.as_ref().map(PemFile::path))
+        .allow_invalid_certificates(tls.mode == SslMode::Require)
+        .build();
+    Ok((options, ca_file.into_iter().chain(cert_key_file).collect()))
+}
+
+#[async_trait]
+impl ConnectionPoolDriver for MongoDriver {
+    async fn get_pool_size(&self) -> Result<usize> {
+        Err(anyhow::anyhow!(
+            "The MongoDB driver does not expose pool statistics"
+        ))
+    }
+
+    async fn get_active_connections(&self) -> Result<usize> {
+        self.get_pool_size().await
+    }
+
+    async fn get_idle_connections(&self) -> Result<usize> {
+        self.get_pool_size().await
+    }
+}
+
 #[async_trait]
 impl ConnectionDriver for MongoDriver {
     async fn test_connection(&self) -> Result<()> {
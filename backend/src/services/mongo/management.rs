@@ -1,10 +1,16 @@
 use super::connection::MongoDriver;
-use super::{bson_to_json, json_to_bson_doc, json_to_bson_filter};
+use super::{
+    bson_to_json, filter_to_bson, json_to_bson_doc, json_to_bson_filter, json_value_to_bson,
+    sort_to_bson,
+};
+use crate::services::bulk::{BulkProgressFn, RowChange};
 use crate::services::db_driver::*;
 use crate::services::driver::nosql::{CollectionInfo, DocumentResult};
 use crate::services::driver::{
-    ColumnManagement, FunctionOperations, NoSQLOperations, TableOperations, ViewOperations,
+    BulkOperationsDriver, ColumnManagement, FunctionOperations, NoSQLOperations, TableOperations,
+    ViewOperations,
 };
+use crate::services::table_filter::{FilterNode, SortSpec};
 use anyhow::Result;
 use async_trait::async_trait;
 use futures_util::StreamExt;
@@ -20,7 +26,8 @@ impl TableOperations for MongoDriver {
         table: &str,
         limit: i64,
         offset: i64,
-        _filter: Option<String>,
+        filter: Option<FilterNode>,
+        sort: Vec<SortSpec>,
         document_id: Option<String>,
         _fields: Option<Vec<String>>,
     ) -> Result<QueryResult> {
@@ -30,6 +37,8 @@ impl TableOperations for MongoDriver {
         let find_options = mongodb::options::FindOptions::builder()
             .limit(limit)
             .skip(offset as u64)
+            .sort((!sort.is_empty()).then(|| sort_to_bson(&sort)))
+            .max_time(self.max_time)
             .build();
 
         let filter = if let Some(id) = document_id {
@@ -38,6 +47,8 @@ impl TableOperations for MongoDriver {
             } else {
                 doc! { "_id": id }
             }
+        } else if let Some(filter) = &filter {
+            filter_to_bson(filter)?
         } else {
             doc! {}
         };
@@ -89,6 +100,7 @@ impl TableOperations for MongoDriver {
             execution_time_ms: None,
             json: None,
             display_mode: Some("table".to_string()),
+            truncated: None,
         })
     }
 
@@ -298,7 +310,15 @@ impl NoSQLOperations for MongoDriver {
         offset: i64,
     ) -> Result<DocumentResult> {
         let res = TableOperations::get_table_data(
-            self, database, collection, limit, offset, None, None, None,
+            self,
+            database,
+            collection,
+            limit,
+            offset,
+            None,
+            Vec::new(),
+            None,
+            None,
         )
         .await?;
 
@@ -413,3 +433,104 @@ impl crate::services::driver::extension::DatabaseManagementDriver for MongoDrive
         ))
     }
 }
+
+#[async_trait]
+impl crate::services::driver::DdlExportDriver for MongoDriver {}
+
+#[async_trait]
+impl crate::services::driver::TransactionDriver for MongoDriver {}
+
+#[async_trait]
+impl crate::services::driver::PreparedStatementDriver for MongoDriver {}
+
+#[async_trait]
+impl crate::services::driver::DumpDriver for MongoDriver {}
+
+/// Documents per `insert_many` call or `$or` filter.
+const BULK_CHUNK: usize = 1000;
+
+/// Chunks are applied as they are sent; a failed chunk leaves the earlier
+/// ones in place.
+#[async_trait]
+impl BulkOperationsDriver for MongoDriver {
+    async fn bulk_insert(
+        &self,
+        schema: &str,
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<Value>],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let collection = self.client.database(schema).collection::<Document>(table);
+        let mut written = 0;
+        for chunk in rows.chunks(BULK_CHUNK) {
+            let documents: Vec<Document> = chunk
+                .iter()
+                .map(|row| {
+                    columns
+                        .iter()
+                        .zip(row)
+                        .map(|(column, value)| (column.clone(), json_value_to_bson(value)))
+                        .collect()
+                })
+                .collect();
+            let result = collection.insert_many(documents).await?;
+            written += result.inserted_ids.len() as u64;
+            progress(written);
+        }
+        Ok(written)
+    }
+
+    async fn bulk_update(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let collection = self.client.database(schema).collection::<Document>(table);
+        let mut affected = 0;
+        for (i, change) in changes.iter().enumerate() {
+            if change.primary_key.is_empty() || change.updates.is_empty() {
+                return Err(anyhow::anyhow!("Primary key and updates cannot be empty"));
+            }
+            let filter = json_to_bson_filter(&change.primary_key);
+            let update = doc! { "$set": json_to_bson_doc(&change.updates) };
+            affected += collection.update_one(filter, update).await?.modified_count;
+            if (i + 1) % BULK_CHUNK == 0 {
+                progress((i + 1) as u64);
+            }
+        }
+        progress(changes.len() as u64);
+        Ok(affected)
+    }
+
+    async fn bulk_delete(
+        &self,
+        schema: &str,
+        table: &str,
+        primary_keys: &[HashMap<String, Value>],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        if primary_keys.iter().any(|key| key.is_empty()) {
+            return Err(anyhow::anyhow!("Primary key cannot be empty"));
+        }
+        let collection = self.client.database(schema).collection::<Document>(table);
+        let mut affected = 0;
+        let mut written = 0;
+        for chunk in primary_keys.chunks(BULK_CHUNK) {
+            let filters: Vec<Document> = chunk.iter().map(json_to_bson_filter).collect();
+            affected += collection
+                .delete_many(doc! { "$or": filters })
+                .await?
+                .deleted_count;
+            written += chunk.len() as u64;
+            progress(written);
+        }
+        Ok(affected)
+    }
+
+    async fn bulk_insert_is_atomic(&self, _schema: &str, _table: &str) -> Result<bool> {
+        Ok(false)
+    }
+}
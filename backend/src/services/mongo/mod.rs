@@ -5,7 +5,11 @@ pub mod schema;
 
 pub use connection::MongoDriver;
 
-use mongodb::bson::{Bson, Document};
+use crate::services::table_filter::{
+    FilterCondition, FilterNode, FilterOp, SortDirection, SortSpec,
+};
+use anyhow::Result;
+use mongodb::bson::{doc, Bson, Document, Regex};
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -86,3 +90,152 @@ pub fn json_to_bson_filter(map: &HashMap<String, Value>) -> Document {
     }
     filter
 }
+
+/// Renders a table filter as a `find` filter document.
+pub fn filter_to_bson(node: &FilterNode) -> Result<Document> {
+    match node {
+        FilterNode::Condition(condition) => condition_to_bson(condition),
+        FilterNode::And { children } if children.is_empty() => Ok(Document::new()),
+        // `$or` rejects an empty array; match nothing instead.
+        FilterNode::Or { children } if children.is_empty() => Ok(doc! { "$expr": false }),
+        FilterNode::And { children } => Ok(doc! { "$and": filters_to_bson(children)? }),
+        FilterNode::Or { children } => Ok(doc! { "$or": filters_to_bson(children)? }),
+        FilterNode::Raw { sql } => raw_filter_to_bson(sql),
+    }
+}
+
+/// A filter document typed by the user, in (extended) JSON.
+fn raw_filter_to_bson(text: &str) -> Result<Document> {
+    let value: Value = serde_json::from_str(text.trim())
+        .map_err(|e| anyhow::anyhow!("The filter must be a JSON document: {}", e))?;
+    match Bson::try_from(value)? {
+        Bson::Document(filter) => Ok(filter),
+        _ => Err(anyhow::anyhow!("The filter must be a JSON document")),
+    }
+}
+
+fn filters_to_bson(children: &[FilterNode]) -> Result<Vec<Document>> {
+    children.iter().map(filter_to_bson).collect()
+}
+
+fn condition_to_bson(condition: &FilterCondition) -> Result<Document> {
+    let column = condition.column.as_str();
+    let value = |v: &Value| filter_value_to_bson(column, v);
+    let operator = match condition.op {
+        FilterOp::Eq => doc! { "$eq": value(condition.scalar()?) },
+        FilterOp::Ne => doc! { "$ne": value(condition.scalar()?) },
+        FilterOp::Lt => doc! { "$lt": value(condition.scalar()?) },
+        FilterOp::Lte => doc! { "$lte": value(condition.scalar()?) },
+        FilterOp::Gt => doc! { "$gt": value(condition.scalar()?) },
+        FilterOp::Gte => doc! { "$gte": value(condition.scalar()?) },
+        FilterOp::Like => doc! { "$regex": like_to_regex(condition)? },
+        FilterOp::NotLike => doc! { "$not": like_to_regex(condition)? },
+        FilterOp::In => {
+            doc! { "$in": condition.list()?.iter().map(value).collect::<Vec<_>>() }
+        }
+        FilterOp::NotIn => {
+            doc! { "$nin": condition.list()?.iter().map(value).collect::<Vec<_>>() }
+        }
+        // `null` also matches documents without the field.
+        FilterOp::IsNull => doc! { "$eq": Bson::Null },
+        FilterOp::IsNotNull => doc! { "$ne": Bson::Null },
+        FilterOp::Between => {
+            let (low, high) = condition.range()?;
+            doc! { "$gte": value(low), "$lte": value(high) }
+        }
+    };
+    let mut filter = Document::new();
+    filter.insert(column, operator);
+    Ok(filter)
+}
+
+/// Like `json_value_to_bson`, but turns ObjectId strings into ObjectIds for `_id`.
+fn filter_value_to_bson(column: &str, v: &Value) -> Bson {
+    if column == "_id" {
+        if let Some(oid) = v
+            .as_str()
+            .and_then(|s| mongodb::bson::oid::ObjectId::parse_str(s).ok())
+        {
+            return Bson::ObjectId(oid);
+        }
+    }
+    json_value_to_bson(v)
+}
+
+/// Translates a SQL LIKE pattern (`%`, `_`) into an anchored regex.
+fn like_to_regex(condition: &FilterCondition) -> Result<Regex> {
+    let pattern = match condition.scalar()? {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let mut regex = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '%' => regex.push_str(".*"),
+            '_' => regex.push('.'),
+            c if "\\.^$|?*+()[]{}".contains(c) => {
+                regex.push('\\');
+                regex.push(c);
+            }
+            c => regex.push(c),
+        }
+    }
+    regex.push('$');
+    Ok(Regex {
+        pattern: regex,
+        options: "s".to_string(),
+    })
+}
+
+pub fn sort_to_bson(sort: &[SortSpec]) -> Document {
+    let mut doc = Document::new();
+    for spec in sort {
+        let direction = match spec.direction {
+            SortDirection::Asc => 1,
+            SortDirection::Desc => -1,
+        };
+        doc.insert(spec.column.clone(), direction);
+    }
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn filter_tree_becomes_find_filter() {
+        let filter: FilterNode = serde_json::from_value(json!({
+            "type": "and",
+            "children": [
+                { "type": "condition", "column": "age", "op": "between", "value": [18, 65] },
+                { "type": "condition", "column": "name", "op": "like", "value": "J_n%" },
+                { "type": "condition", "column": "tags", "op": "in", "value": ["a", "b"] }
+            ]
+        }))
+        .unwrap();
+
+        let expected = doc! {
+            "$and": [
+                { "age": { "$gte": 18_i64, "$lte": 65_i64 } },
+                { "name": { "$regex": Regex { pattern: "^J.n.*$".to_string(), options: "s".to_string() } } },
+                { "tags": { "$in": ["a", "b"] } },
+            ]
+        };
+        assert_eq!(filter_to_bson(&filter).unwrap(), expected);
+    }
+
+    #[test]
+    fn raw_filter_is_parsed_as_extended_json() {
+        let filter = FilterNode::Raw {
+            sql: r#"{ "_id": { "$oid": "65a1b2c3d4e5f60718293a4b" }, "n": 2 }"#.into(),
+        };
+        let expected = doc! {
+            "_id": mongodb::bson::oid::ObjectId::parse_str("65a1b2c3d4e5f60718293a4b").unwrap(),
+            "n": 2,
+        };
+        assert_eq!(filter_to_bson(&filter).unwrap(), expected);
+        assert!(filter_to_bson(&FilterNode::Raw { sql: "[1]".into() }).is_err());
+    }
+}
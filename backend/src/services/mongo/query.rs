@@ -2,34 +2,34 @@ use super::bson_to_json;
 use super::connection::MongoDriver;
 use crate::services::db_driver::QueryResult;
 use crate::services::driver::QueryDriver;
+use crate::services::query_cancel::{QueryHandle, ServerCancel};
+use crate::services::query_stream::{QueryStreamSink, StreamOptions};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use futures_util::TryStreamExt;
 use mongodb::bson::{doc, Document};
+use mongodb::{Client, Cursor, Database};
+use std::sync::Arc;
+use std::time::Duration;
 
-#[async_trait]
-impl QueryDriver for MongoDriver {
-    async fn execute(&self, query: &str) -> Result<u64> {
-        let db_name = self.database_name.as_deref().unwrap_or("admin");
-        let db = self.client.database(db_name);
-
-        // Try to parse query as a command Document
-        let command_doc: Document = serde_json::from_str(query)
-            .map_err(|e| anyhow!("Failed to parse query as JSON command: {}", e))?;
-
-        let _result = db.run_command(command_doc).await?;
-        Ok(1)
-    }
-
-    async fn query(&self, query: &str) -> Result<QueryResult> {
-        self.execute_query(query).await
-    }
-
-    async fn execute_query(&self, query: &str) -> Result<QueryResult> {
+impl MongoDriver {
+    /// Runs a JSON command, tagged with `comment` so it can be found in `$currentOp`.
+    async fn run_command_query(&self, query: &str, comment: Option<&str>) -> Result<QueryResult> {
         let db_name = self.database_name.as_deref().unwrap_or("admin");
         let db = self.client.database(db_name);
 
-        let command_doc: Document = serde_json::from_str(query)
+        let mut command_doc: Document = serde_json::from_str(query)
             .map_err(|e| anyhow!("Failed to parse query as JSON command: {}", e))?;
+        if let Some(comment) = comment {
+            if !command_doc.contains_key("comment") {
+                command_doc.insert("comment", comment);
+            }
+        }
+        if let Some(max_time) = self.max_time {
+            if !command_doc.contains_key("maxTimeMS") {
+                command_doc.insert("maxTimeMS", max_time.as_millis() as i64);
+            }
+        }
 
         let result = db.run_command(command_doc).await?;
         let json_res = bson_to_json(&mongodb::bson::Bson::Document(result));
@@ -51,8 +51,190 @@ impl QueryDriver for MongoDriver {
             execution_time_ms: None,
             json: Some(json_res),
             display_mode: Some("json".to_string()),
+            truncated: None,
         })
     }
+}
+
+/// Collection cursor for `find` and `aggregate` commands, so their documents
+/// can be streamed instead of returning only the first batch.
+async fn open_cursor(
+    db: &Database,
+    command: &Document,
+    options: &StreamOptions,
+    max_time: Option<Duration>,
+) -> Result<Option<(Cursor<Document>, Option<i64>)>> {
+    let skip = options.offset.filter(|o| *o > 0).map(|o| o as u64);
+    let limit = options.limit.filter(|l| *l > 0);
+
+    if let Ok(name) = command.get_str("find") {
+        let collection = db.collection::<Document>(name);
+        let filter = command.get_document("filter").cloned().unwrap_or_default();
+        let total_count = if options.include_total_count {
+            Some(collection.count_documents(filter.clone()).await? as i64)
+        } else {
+            None
+        };
+
+        let mut find = collection.find(filter);
+        if let Ok(sort) = command.get_document("sort") {
+            find = find.sort(sort.clone());
+        }
+        if let Ok(projection) = command.get_document("projection") {
+            find = find.projection(projection.clone());
+        }
+        if let Some(skip) = skip {
+            find = find.skip(skip);
+        }
+        if let Some(limit) = limit {
+            find = find.limit(limit);
+        }
+        if let Some(max_time) = max_time {
+            find = find.max_time(max_time);
+        }
+        return Ok(Some((find.await?, total_count)));
+    }
+
+    if let Ok(name) = command.get_str("aggregate") {
+        let mut pipeline: Vec<Document> = command
+            .get_array("pipeline")
+            .map(|stages| {
+                stages
+                    .iter()
+                    .filter_map(|stage| stage.as_document().cloned())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(skip) = skip {
+            pipeline.push(doc! { "$skip": skip as i64 });
+        }
+        if let Some(limit) = limit {
+            pipeline.push(doc! { "$limit": limit });
+        }
+        let collection = db.collection::<Document>(name);
+        let mut aggregate = collection.aggregate(pipeline);
+        if let Some(max_time) = max_time {
+            aggregate = aggregate.max_time(max_time);
+        }
+        return Ok(Some((aggregate.await?, None)));
+    }
+
+    Ok(None)
+}
+
+/// Finds the operation by its comment and stops it with `killOp`.
+struct MongoKillOp {
+    client: Client,
+    comment: String,
+}
+
+#[async_trait]
+impl ServerCancel for MongoKillOp {
+    async fn cancel(&self) -> Result<()> {
+        let admin = self.client.database("admin");
+        let mut ops = admin
+            .aggregate(vec![
+                doc! { "$currentOp": {} },
+                doc! { "$match": { "command.comment": self.comment.as_str() } },
+            ])
+            .await?;
+
+        let mut killed = 0;
+        while let Some(op) = ops.try_next().await? {
+            if let Some(opid) = op.get("opid") {
+                admin
+                    .run_command(doc! { "killOp": 1, "op": opid.clone() })
+                    .await?;
+                killed += 1;
+            }
+        }
+
+        if killed == 0 {
+            return Err(anyhow!(
+                "Operation not found on the server; it may have already finished"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl QueryDriver for MongoDriver {
+    async fn execute(&self, query: &str) -> Result<u64> {
+        let db_name = self.database_name.as_deref().unwrap_or("admin");
+        let db = self.client.database(db_name);
+
+        // Try to parse query as a command Document
+        let command_doc: Document = serde_json::from_str(query)
+            .map_err(|e| anyhow!("Failed to parse query as JSON command: {}", e))?;
+
+        let _result = db.run_command(command_doc).await?;
+        Ok(1)
+    }
+
+    async fn query(&self, query: &str) -> Result<QueryResult> {
+        self.execute_query(query).await
+    }
+
+    async fn execute_query(&self, query: &str) -> Result<QueryResult> {
+        self.run_command_query(query, None).await
+    }
+
+    async fn execute_query_cancellable(
+        &self,
+        query: &str,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let comment = format!("dbplus:{}", uuid::Uuid::new_v4());
+        let kill = Arc::new(MongoKillOp {
+            client: self.client.clone(),
+            comment: comment.clone(),
+        });
+        handle
+            .run(kill, self.run_command_query(query, Some(&comment)))
+            .await
+    }
+
+    async fn stream_query(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+    ) -> Result<u64> {
+        let db_name = self.database_name.as_deref().unwrap_or("admin");
+        let db = self.client.database(db_name);
+        let command: Document = serde_json::from_str(query)
+            .map_err(|e| anyhow!("Failed to parse query as JSON command: {}", e))?;
+
+        let (mut cursor, total_count) =
+            match open_cursor(&db, &command, options, self.max_time).await? {
+                Some(opened) => opened,
+                None => {
+                    let result = self.run_command_query(query, None).await?;
+                    sink.send_result(result).await;
+                    return Ok(0);
+                }
+            };
+
+        if !sink
+            .meta(
+                vec!["document".to_string()],
+                total_count,
+                options.limit,
+                options.offset,
+            )
+            .await
+        {
+            return Ok(0);
+        }
+        while let Some(document) = cursor.try_next().await? {
+            let value = bson_to_json(&mongodb::bson::Bson::Document(document));
+            if !sink.row(vec![value]).await {
+                break;
+            }
+        }
+        Ok(0)
+    }
 
     async fn execute_script(&self, script: &str) -> Result<u64> {
         // Mongo doesn't have "scripts" in the SQL sense,
@@ -0,0 +1,187 @@
+use super::query::json_to_mysql_value;
+use super::MySqlDriver;
+use crate::services::bulk::{self, BulkProgressFn, RowChange};
+use crate::services::driver::BulkOperationsDriver;
+use crate::services::table_filter::{SqlDialect, SqlFragment};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use mysql_async::prelude::Queryable;
+use mysql_async::TxOpts;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Rows between progress reports for updates and deletes.
+const PROGRESS_ROWS: usize = 1000;
+/// Room left in each packet for the protocol header.
+const PACKET_HEADROOM: usize = 1024;
+/// Upper bound for one `INSERT`, however large the server allows packets.
+const MAX_STATEMENT_BYTES: usize = 16 << 20;
+
+#[async_trait]
+impl BulkOperationsDriver for MySqlDriver {
+    /// Multi-row `INSERT`s with inlined values, each as large as
+    /// `max_allowed_packet` allows, in one transaction.
+    async fn bulk_insert(
+        &self,
+        schema: &str,
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<Value>],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        let dialect = SqlDialect::MYSQL;
+        let names = columns
+            .iter()
+            .map(|column| dialect.quote_ident(column))
+            .collect::<Result<Vec<_>>>()?;
+        let prefix = format!(
+            "INSERT INTO {} ({}) VALUES ",
+            bulk::table_ref(dialect, schema, table)?,
+            names.join(", ")
+        );
+
+        let mut conn = self.pool.get_conn().await?;
+        let (max_packet, sql_mode): (u64, Option<String>) = conn
+            .query_first("SELECT @@max_allowed_packet, @@sql_mode")
+            .await?
+            .ok_or_else(|| anyhow!("Could not read max_allowed_packet"))?;
+        let limit = (max_packet as usize)
+            .saturating_sub(PACKET_HEADROOM)
+            .clamp(PACKET_HEADROOM, MAX_STATEMENT_BYTES);
+        // Literals are escaped the way the session parses them.
+        let no_backslash_escapes = sql_mode
+            .unwrap_or_default()
+            .to_uppercase()
+            .contains("NO_BACKSLASH_ESCAPES");
+
+        let mut tx = conn.start_transaction(TxOpts::default()).await?;
+        let mut sql = prefix.clone();
+        let mut pending = 0;
+        let mut affected = 0;
+        for (i, row) in rows.iter().enumerate() {
+            if row.len() != columns.len() {
+                return Err(anyhow!(
+                    "Row {} has {} values for {} columns",
+                    i + 1,
+                    row.len(),
+                    columns.len()
+                ));
+            }
+            let tuple = row_literal(row, no_backslash_escapes);
+            if pending > 0 && sql.len() + 2 + tuple.len() > limit {
+                tx.query_drop(sql.as_str()).await?;
+                affected += tx.affected_rows();
+                progress(i as u64);
+                sql.truncate(prefix.len());
+                pending = 0;
+            }
+            if pending > 0 {
+                sql.push_str(", ");
+            }
+            sql.push_str(&tuple);
+            pending += 1;
+        }
+        tx.query_drop(sql.as_str()).await?;
+        affected += tx.affected_rows();
+        tx.commit().await?;
+        progress(rows.len() as u64);
+        Ok(affected)
+    }
+
+    /// Only engines with transactions (InnoDB, NDB) roll a failed insert back;
+    /// MyISAM and the like keep what was written.
+    async fn bulk_insert_is_atomic(&self, schema: &str, table: &str) -> Result<bool> {
+        let mut conn = self.pool.get_conn().await?;
+        let engine: Option<Option<String>> = conn
+            .exec_first(
+                "SELECT ENGINE FROM information_schema.TABLES \
+                 WHERE TABLE_SCHEMA = COALESCE(NULLIF(?, ''), DATABASE()) AND TABLE_NAME = ?",
+                (schema, table),
+            )
+            .await?;
+        let engine = engine.flatten().unwrap_or_default().to_lowercase();
+        Ok(matches!(engine.as_str(), "innodb" | "ndbcluster" | "ndb"))
+    }
+
+    async fn bulk_update(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let table = bulk::table_ref(SqlDialect::MYSQL, schema, table)?;
+        let statements = changes
+            .iter()
+            .map(|change| bulk::update_statement(SqlDialect::MYSQL, &table, change))
+            .collect::<Result<Vec<_>>>()?;
+        self.execute_in_transaction(&statements, progress).await
+    }
+
+    async fn bulk_delete(
+        &self,
+        schema: &str,
+        table: &str,
+        primary_keys: &[HashMap<String, Value>],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let table = bulk::table_ref(SqlDialect::MYSQL, schema, table)?;
+        let statements = primary_keys
+            .iter()
+            .map(|key| bulk::delete_statement(SqlDialect::MYSQL, &table, key))
+            .collect::<Result<Vec<_>>>()?;
+        self.execute_in_transaction(&statements, progress).await
+    }
+}
+
+impl MySqlDriver {
+    /// Runs each statement with bound values, all in one transaction. The
+    /// connection caches the prepared statements, so repeated shapes are
+    /// prepared once.
+    async fn execute_in_transaction(
+        &self,
+        statements: &[SqlFragment],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let mut conn = self.pool.get_conn().await?;
+        let mut tx = conn.start_transaction(TxOpts::default()).await?;
+        let mut affected = 0;
+        for (i, statement) in statements.iter().enumerate() {
+            let values: Vec<mysql_async::Value> =
+                statement.params.iter().map(json_to_mysql_value).collect();
+            tx.exec_drop(statement.sql.as_str(), values).await?;
+            affected += tx.affected_rows();
+            if (i + 1) % PROGRESS_ROWS == 0 {
+                progress((i + 1) as u64);
+            }
+        }
+        tx.commit().await?;
+        progress(statements.len() as u64);
+        Ok(affected)
+    }
+}
+
+/// `(1, 'a', NULL)`
+fn row_literal(row: &[Value], no_backslash_escapes: bool) -> String {
+    let values: Vec<String> = row
+        .iter()
+        .map(|value| json_to_mysql_value(value).as_sql(no_backslash_escapes))
+        .collect();
+    format!("({})", values.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn inlines_row_values() {
+        let row = [json!(1), json!(null), json!(true), json!("it's \\ here")];
+        assert_eq!(row_literal(&row, false), r"(1, NULL, 1, 'it\'s \\ here')");
+        assert_eq!(row_literal(&row, true), r"(1, NULL, 1, 'it''s \ here')");
+    }
+}
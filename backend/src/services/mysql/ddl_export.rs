@@ -1,6 +1,7 @@
 use super::MySqlDriver;
 use crate::models::export_ddl::{DdlObjectType, DdlScope, ExportDdlOptions};
 use crate::services::driver::ddl_export::DdlExportDriver;
+use crate::services::schema_diff::{mysql_extractor::MySqlSchemaExtractor, SchemaSnapshot};
 use anyhow::Result;
 use async_trait::async_trait;
 use mysql_async::prelude::Queryable;
@@ -122,4 +123,11 @@ impl DdlExportDriver for MySqlDriver {
         let exporter = MySqlDdlExport::new(self.clone());
         exporter.export_ddl(options).await
     }
+
+    async fn schema_snapshot(&self, schema: &str) -> Result<SchemaSnapshot> {
+        MySqlSchemaExtractor::new(self.pool.clone())
+            .extract_schema(schema)
+            .await
+            .map_err(anyhow::Error::msg)
+    }
 }
@@ -1,19 +1,25 @@
+pub mod bulk;
 pub mod column;
 pub mod connection;
 pub mod ddl_export;
+pub mod dump;
 pub mod function;
 pub mod query;
 pub mod schema;
 pub mod table;
+pub mod transaction;
 pub mod view;
 
 use crate::models::entities::connection as connection_entity;
 use crate::services::db_driver::SessionInfo;
 use crate::services::driver::extension::DatabaseManagementDriver;
+use crate::services::query_limits::QueryLimits;
+use crate::services::tls::{read_pem, SslMode, TlsSettings};
 use anyhow::Result;
 use async_trait::async_trait;
 use mysql_async::prelude::Queryable;
-use mysql_async::{OptsBuilder, Pool, PoolConstraints, PoolOpts};
+use mysql_async::{ClientIdentity, OptsBuilder, Pool, PoolConstraints, PoolOpts, SslOpts};
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -65,19 +71,26 @@ impl MySqlDriver {
 
         opts = opts.pool_opts(pool_opts);
 
-        // SSL/TLS options support
-        if conn.ssl {
-            // Basic SSL enablement
+        let tls = TlsSettings::from_model(conn)?;
+        tls.ensure_supported(&conn.db_type)?;
+        if tls.is_enabled() {
+            opts = opts.ssl_opts(Some(ssl_opts(&tls)?));
         }
 
-        let pool = Pool::new(opts);
-
         let flavor = match conn.db_type.as_str() {
             "mariadb" => MySqlFamilyFlavor::MariaDb,
             "tidb" => MySqlFamilyFlavor::TiDb,
             _ => MySqlFamilyFlavor::Mysql,
         };
 
+        // `setup` runs again after the pool resets a connection, which would
+        // otherwise drop the session variable.
+        if let Some(ms) = QueryLimits::from_connection(conn).timeout_ms() {
+            opts = opts.setup(vec![statement_timeout_sql(flavor, ms)]);
+        }
+
+        let pool = Pool::new(opts);
+
         tracing::info!(
             "[MySqlDriver] Pool created: min=5, max=25, ttl=300s flavor={:?}",
             flavor
@@ -87,6 +100,66 @@ impl MySqlDriver {
     }
 }
 
+/// MariaDB has its own variable, in seconds; MySQL and TiDB take
+/// `MAX_EXECUTION_TIME` in milliseconds, which only bounds SELECTs.
+fn statement_timeout_sql(flavor: MySqlFamilyFlavor, ms: u64) -> String {
+    match flavor {
+        MySqlFamilyFlavor::MariaDb => {
+            format!("SET SESSION max_statement_time = {}", ms as f64 / 1000.0)
+        }
+        MySqlFamilyFlavor::Mysql | MySqlFamilyFlavor::TiDb => {
+            format!("SET SESSION MAX_EXECUTION_TIME = {}", ms)
+        }
+    }
+}
+
+fn ssl_opts(tls: &TlsSettings) -> Result<SslOpts> {
+    let mut ssl = SslOpts::default();
+
+    if let Some(ca) = &tls.ca {
+        ssl = ssl
+            .with_root_certs(vec![read_pem(ca)?.into()])
+            .with_disable_built_in_roots(true);
+    }
+
+    if let (Some(cert), Some(key)) = (&tls.client_cert, &tls.client_key) {
+        ssl = ssl.with_client_identity(Some(ClientIdentity::new(
+            read_pem(cert)?.into(),
+            read_pem(key)?.into(),
+        )));
+    }
+
+    ssl = match tls.mode {
+        SslMode::VerifyFull => {
+            ssl.with_danger_tls_hostname_override(Some(tls.server_name.clone()))
+        }
+        SslMode::VerifyCa => ssl.with_danger_skip_domain_validation(true),
+        _ => ssl
+            .with_danger_accept_invalid_certs(true)
+            .with_danger_skip_domain_validation(true),
+    };
+
+    Ok(ssl)
+}
+
+#[async_trait]
+impl crate::services::driver::ConnectionPoolDriver for MySqlDriver {
+    async fn get_pool_size(&self) -> Result<usize> {
+        Ok(self.pool.metrics().connection_count.load(Ordering::Relaxed))
+    }
+
+    async fn get_active_connections(&self) -> Result<usize> {
+        let metrics = self.pool.metrics();
+        let total = metrics.connection_count.load(Ordering::Relaxed);
+        let idle = metrics.connections_in_pool.load(Ordering::Relaxed);
+        Ok(total.saturating_sub(idle))
+    }
+
+    async fn get_idle_connections(&self) -> Result<usize> {
+        Ok(self.pool.metrics().connections_in_pool.load(Ordering::Relaxed))
+    }
+}
+
 #[async_trait]
 impl crate::services::driver::SessionOperations for MySqlDriver {
     async fn get_active_sessions(&self) -> Result<Vec<SessionInfo>> {
@@ -1,11 +1,19 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use mysql_async::prelude::Queryable;
+use mysql_async::{Conn, Pool};
 use serde_json::Value;
+use std::sync::Arc;
 
-use super::MySqlDriver;
+use super::{MySqlDriver, MySqlFamilyFlavor};
 use crate::services::db_driver::QueryResult;
-use crate::services::driver::QueryDriver;
+use crate::services::driver::{PreparedStatementDriver, QueryDriver, StatementSession};
+use crate::services::query_cancel::{QueryHandle, ServerCancel};
+use crate::services::query_limits;
+use crate::services::query_paging::count_sql;
+use crate::services::query_params::{self, QueryParams};
+use crate::services::query_stream::{base_query, is_select_like, QueryStreamSink, StreamOptions};
+use crate::services::table_filter::SqlDialect;
 
 #[async_trait]
 impl QueryDriver for MySqlDriver {
@@ -17,58 +25,64 @@ impl QueryDriver for MySqlDriver {
 
     async fn query(&self, query: &str) -> Result<QueryResult> {
         let mut conn = self.pool.get_conn().await?;
-        let mut result = conn.query_iter(query).await?;
-
-        // Capture columns
-        let columns = result
-            .columns()
-            .as_ref()
-            .map(|cols| cols.iter().map(|c| c.name_str().to_string()).collect())
-            .unwrap_or_default();
-
-        let rows_raw: Vec<mysql_async::Row> = result.collect().await?;
-        let affected_rows = conn.affected_rows();
-
-        let rows: Vec<Vec<Value>> = rows_raw
-            .into_iter()
-            .map(|row| {
-                (0..row.len())
-                    .map(|i| {
-                        // We use index access.
-                        // mysql_async::Row::get returns FromValue.
-                        // We want strictly the underlying Value to convert manually.
-                        // Actually Row structure allows interacting with columns.
-                        // let val: Option<mysql_async::Value> = row.get(i); // This tries to convert to T.
-                        // To get raw value, we might use `row.as_ref(i)` if available or `row.get_opt`.
-
-                        // `mysql_async::Row` implements `Index<usize>` returning `Value`.
-                        let v = &row[i];
-                        mysql_value_to_json(v.clone())
-                    })
-                    .collect()
-            })
-            .collect();
-
-        Ok(QueryResult {
-            columns,
-            rows,
-            affected_rows,
-            column_metadata: None,
-            total_count: None,
-            limit: None,
-            offset: None,
-            has_more: None,
-            row_metadata: None,
-            execution_time_ms: None,
-            json: None,
-            display_mode: None,
-        })
+        run_query(&mut conn, query).await
     }
 
     async fn execute_query(&self, query: &str) -> Result<QueryResult> {
         self.query(query).await
     }
 
+    async fn execute_query_cancellable(
+        &self,
+        query: &str,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let mut conn = self.pool.get_conn().await?;
+        let kill = Arc::new(MySqlKill {
+            pool: self.pool.clone(),
+            connection_id: conn.id(),
+            flavor: self.flavor,
+        });
+        handle.run(kill, run_query(&mut conn, query)).await
+    }
+
+    async fn stream_query(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+    ) -> Result<u64> {
+        let mut conn = self.pool.get_conn().await?;
+        stream_on_connection(&mut conn, query, options, sink).await
+    }
+
+    async fn stream_query_cancellable(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+        handle: &QueryHandle,
+    ) -> Result<u64> {
+        let mut conn = self.pool.get_conn().await?;
+        let kill = Arc::new(MySqlKill {
+            pool: self.pool.clone(),
+            connection_id: conn.id(),
+            flavor: self.flavor,
+        });
+        handle
+            .run(kill, stream_on_connection(&mut conn, query, options, sink))
+            .await
+    }
+
+    async fn open_session(&self) -> Result<Box<dyn StatementSession + '_>> {
+        let conn = self.pool.get_conn().await?;
+        Ok(Box::new(MySqlSession {
+            conn: Some(conn),
+            pool: self.pool.clone(),
+            flavor: self.flavor,
+        }))
+    }
+
     async fn execute_script(&self, script: &str) -> Result<u64> {
         let mut conn = self.pool.get_conn().await?;
         conn.query_drop(script).await?;
@@ -89,6 +103,227 @@ impl QueryDriver for MySqlDriver {
     }
 }
 
+/// Values are sent with the binary protocol; the server converts them to the
+/// column types they are compared with or stored in.
+#[async_trait]
+impl PreparedStatementDriver for MySqlDriver {
+    async fn execute_with_params(&self, query: &str, params: &QueryParams) -> Result<QueryResult> {
+        let bound = query_params::bind(query, params, SqlDialect::MYSQL)?;
+        let values = bound.params.iter().map(json_to_mysql_value).collect();
+        let mut conn = self.pool.get_conn().await?;
+        run_exec(&mut conn, &bound.sql, values).await
+    }
+
+    async fn execute_with_params_cancellable(
+        &self,
+        query: &str,
+        params: &QueryParams,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let bound = query_params::bind(query, params, SqlDialect::MYSQL)?;
+        let values = bound.params.iter().map(json_to_mysql_value).collect();
+        let mut conn = self.pool.get_conn().await?;
+        let kill = Arc::new(MySqlKill {
+            pool: self.pool.clone(),
+            connection_id: conn.id(),
+            flavor: self.flavor,
+        });
+        handle
+            .run(kill, run_exec(&mut conn, &bound.sql, values))
+            .await
+    }
+}
+
+/// Streams a SELECT's rows from `conn`; other statements are sent in one go.
+async fn stream_on_connection(
+    conn: &mut Conn,
+    query: &str,
+    options: &StreamOptions,
+    sink: &mut QueryStreamSink,
+) -> Result<u64> {
+    if !is_select_like(query) {
+        // SHOW/DESCRIBE and DML: small results, sent in one go.
+        let result = run_query(conn, query).await?;
+        let affected_rows = result.affected_rows;
+        sink.send_result(result).await;
+        return Ok(affected_rows);
+    }
+
+    let base = base_query(query);
+    let (sql_to_run, limit, offset) = options.paged_sql(base);
+    let total_count = if options.include_total_count {
+        conn.query_first::<i64, _>(count_sql(base)).await?
+    } else {
+        None
+    };
+
+    let mut result = conn.query_iter(sql_to_run).await?;
+    let columns = result
+        .columns()
+        .map(|cols| cols.iter().map(|c| c.name_str().to_string()).collect())
+        .unwrap_or_default();
+    if !sink.meta(columns, total_count, limit, offset).await {
+        return Ok(0);
+    }
+
+    while let Some(row) = result.next().await? {
+        let values = row.unwrap().into_iter().map(mysql_value_to_json).collect();
+        if !sink.row(values).await {
+            break;
+        }
+    }
+    Ok(0)
+}
+
+/// Kills the statement running on one connection from a second connection.
+struct MySqlKill {
+    pool: Pool,
+    connection_id: u32,
+    flavor: MySqlFamilyFlavor,
+}
+
+#[async_trait]
+impl ServerCancel for MySqlKill {
+    async fn cancel(&self) -> Result<()> {
+        let sql = match self.flavor {
+            MySqlFamilyFlavor::TiDb => format!("KILL TIDB QUERY {}", self.connection_id),
+            _ => format!("KILL QUERY {}", self.connection_id),
+        };
+        let mut conn = self.pool.get_conn().await?;
+        conn.query_drop(sql).await?;
+        Ok(())
+    }
+}
+
+/// A connection taken out of the pool for `open_session`.
+struct MySqlSession {
+    conn: Option<Conn>,
+    /// For killing the session's statements from a second connection.
+    pool: Pool,
+    flavor: MySqlFamilyFlavor,
+}
+
+impl Drop for MySqlSession {
+    fn drop(&mut self) {
+        // Session variables, temporary tables and open transactions must not
+        // reach other users of the pool, so the connection is closed.
+        if let Some(conn) = self.conn.take() {
+            if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                runtime.spawn(async move {
+                    let _ = conn.disconnect().await;
+                });
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StatementSession for MySqlSession {
+    async fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
+        let conn = self.conn.as_mut().expect("session already closed");
+        run_query(conn, query).await
+    }
+
+    async fn execute_query_cancellable(
+        &mut self,
+        query: &str,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let conn = self.conn.as_mut().expect("session already closed");
+        let kill = Arc::new(MySqlKill {
+            pool: self.pool.clone(),
+            connection_id: conn.id(),
+            flavor: self.flavor,
+        });
+        handle.run(kill, run_query(conn, query)).await
+    }
+}
+
+pub(super) async fn run_query(conn: &mut Conn, query: &str) -> Result<QueryResult> {
+    let result = conn.query_iter(query).await?;
+    let (columns, rows, truncated) = read_rows(result).await?;
+    let affected_rows = conn.affected_rows();
+    Ok(rows_to_result(columns, rows, affected_rows, truncated))
+}
+
+/// Like `run_query`, but as a prepared statement with positional `?` parameters.
+pub(super) async fn run_exec(
+    conn: &mut Conn,
+    query: &str,
+    params: Vec<mysql_async::Value>,
+) -> Result<QueryResult> {
+    let result = conn.exec_iter(query, params).await?;
+    let (columns, rows, truncated) = read_rows(result).await?;
+    let affected_rows = conn.affected_rows();
+    Ok(rows_to_result(columns, rows, affected_rows, truncated))
+}
+
+/// Columns and rows of the first result set, read up to the end of
+/// `query_limits::read_budget`. Past it, the rest of the result is discarded
+/// and the rows are marked truncated.
+async fn read_rows<P: mysql_async::prelude::Protocol>(
+    mut result: mysql_async::QueryResult<'_, '_, P>,
+) -> Result<(Vec<String>, Vec<Vec<Value>>, Option<bool>)> {
+    let columns = result
+        .columns()
+        .as_ref()
+        .map(|cols| cols.iter().map(|c| c.name_str().to_string()).collect())
+        .unwrap_or_default();
+
+    let mut budget = query_limits::read_budget();
+    let mut rows = Vec::new();
+    while let Some(row) = result.next().await? {
+        let values: Vec<Value> = row.unwrap().into_iter().map(mysql_value_to_json).collect();
+        if !budget.admit(&values) {
+            result.drop_result().await?;
+            return Ok((columns, rows, Some(true)));
+        }
+        rows.push(values);
+    }
+    Ok((columns, rows, None))
+}
+
+fn rows_to_result(
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+    affected_rows: u64,
+    truncated: Option<bool>,
+) -> QueryResult {
+    QueryResult {
+        columns,
+        rows,
+        affected_rows,
+        column_metadata: None,
+        total_count: None,
+        limit: None,
+        offset: None,
+        has_more: None,
+        row_metadata: None,
+        execution_time_ms: None,
+        json: None,
+        display_mode: None,
+        truncated,
+    }
+}
+
+pub(super) fn json_to_mysql_value(v: &Value) -> mysql_async::Value {
+    match v {
+        Value::Null => mysql_async::Value::NULL,
+        Value::Bool(b) => mysql_async::Value::Int(*b as i64),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                mysql_async::Value::Int(i)
+            } else if let Some(u) = n.as_u64() {
+                mysql_async::Value::UInt(u)
+            } else {
+                mysql_async::Value::Double(n.as_f64().unwrap_or_default())
+            }
+        }
+        Value::String(s) => mysql_async::Value::Bytes(s.clone().into_bytes()),
+        other => mysql_async::Value::Bytes(other.to_string().into_bytes()),
+    }
+}
+
 fn mysql_value_to_json(v: mysql_async::Value) -> Value {
     use mysql_async::Value::*;
     match v {
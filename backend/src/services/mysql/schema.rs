@@ -42,8 +42,11 @@ impl SchemaIntrospection for MySqlDriver {
 
     async fn get_columns(&self, schema: &str, table: &str) -> Result<Vec<TableColumn>> {
         let mut conn = self.pool.get_conn().await?;
+        // `tinyint(1)` keeps its width: it is how MySQL declares booleans.
         let query = r#"
-            SELECT COLUMN_NAME, DATA_TYPE, IS_NULLABLE, COLUMN_KEY, COLUMN_DEFAULT
+            SELECT COLUMN_NAME,
+                   IF(COLUMN_TYPE = 'tinyint(1)', COLUMN_TYPE, DATA_TYPE),
+                   IS_NULLABLE, COLUMN_KEY, COLUMN_DEFAULT
             FROM information_schema.COLUMNS
             WHERE TABLE_SCHEMA = ? AND TABLE_NAME = ?
             ORDER BY ORDINAL_POSITION
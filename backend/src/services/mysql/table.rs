@@ -2,12 +2,14 @@ use anyhow::Result;
 use async_trait::async_trait;
 use mysql_async::prelude::Queryable;
 
+use super::query::{json_to_mysql_value, run_exec};
 use super::MySqlDriver;
 use crate::services::db_driver::{
     ForeignKey, IndexInfo, PartitionInfo, QueryResult, RoleInfo, StorageBloatInfo, TableComment,
     TableConstraints, TableDependencies, TableGrant, TableStatistics, TriggerInfo,
 };
 use crate::services::driver::{QueryDriver, TableOperations};
+use crate::services::table_filter::{self, FilterNode, SortSpec, SqlDialect};
 
 #[async_trait]
 impl TableOperations for MySqlDriver {
@@ -17,7 +19,8 @@ impl TableOperations for MySqlDriver {
         table: &str,
         limit: i64,
         offset: i64,
-        _filter: Option<String>,
+        filter: Option<FilterNode>,
+        sort: Vec<SortSpec>,
         _document_id: Option<String>,
         _fields: Option<Vec<String>>,
     ) -> Result<QueryResult> {
@@ -34,11 +37,23 @@ impl TableOperations for MySqlDriver {
             "*".to_string()
         };
 
+        let where_clause = table_filter::where_clause(filter.as_ref(), SqlDialect::MYSQL)?;
+        let order_by = table_filter::order_by_clause(&sort, SqlDialect::MYSQL)?;
         let query = format!(
-            "SELECT {} FROM `{}`.`{}` LIMIT {} OFFSET {}",
-            select_clause, schema, table, limit, offset
+            "SELECT {} FROM `{}`.`{}`{}{} LIMIT {} OFFSET {}",
+            select_clause, schema, table, where_clause.sql, order_by, limit, offset
         );
-        self.query(&query).await
+        if where_clause.params.is_empty() {
+            return self.query(&query).await;
+        }
+
+        let params = where_clause
+            .params
+            .iter()
+            .map(json_to_mysql_value)
+            .collect();
+        let mut conn = self.pool.get_conn().await?;
+        run_exec(&mut conn, &query, params).await
     }
 
     async fn get_table_constraints(&self, schema: &str, table: &str) -> Result<TableConstraints> {
@@ -0,0 +1,71 @@
+use super::query::run_query;
+use super::MySqlDriver;
+use crate::services::db_driver::QueryResult;
+use crate::services::driver::{TransactionDriver, TransactionSession};
+use anyhow::Result;
+use async_trait::async_trait;
+use mysql_async::prelude::Queryable;
+use mysql_async::Conn;
+
+/// A transaction on a connection taken out of the pool.
+pub struct MySqlTransaction {
+    conn: Option<Conn>,
+}
+
+impl MySqlTransaction {
+    fn conn(&mut self) -> Result<&mut Conn> {
+        self.conn
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Transaction is already finished"))
+    }
+
+    async fn finish(mut self: Box<Self>, statement: &str) -> Result<()> {
+        let mut conn = self.conn.take().expect("transaction already finished");
+        match conn.query_drop(statement).await {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                // The transaction state is unknown; close the connection.
+                let _ = conn.disconnect().await;
+                Err(e.into())
+            }
+        }
+    }
+}
+
+impl Drop for MySqlTransaction {
+    fn drop(&mut self) {
+        // The pool only cleans up transactions it started itself, so close the
+        // connection instead of returning it; the server rolls back.
+        if let Some(conn) = self.conn.take() {
+            if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                runtime.spawn(async move {
+                    let _ = conn.disconnect().await;
+                });
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionSession for MySqlTransaction {
+    async fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
+        run_query(self.conn()?, query).await
+    }
+
+    async fn commit(self: Box<Self>) -> Result<()> {
+        self.finish("COMMIT").await
+    }
+
+    async fn rollback(self: Box<Self>) -> Result<()> {
+        self.finish("ROLLBACK").await
+    }
+}
+
+#[async_trait]
+impl TransactionDriver for MySqlDriver {
+    async fn begin_transaction(&self) -> Result<Box<dyn TransactionSession>> {
+        let mut conn = self.pool.get_conn().await?;
+        conn.query_drop("START TRANSACTION").await?;
+        Ok(Box::new(MySqlTransaction { conn: Some(conn) }))
+    }
+}
@@ -0,0 +1,272 @@
+use super::query::{binds_natively, json_param, PgParam};
+use super::PostgresDriver;
+use crate::services::bulk::{self, BulkProgressFn, RowChange};
+use crate::services::driver::BulkOperationsDriver;
+use crate::services::table_filter::{SqlDialect, SqlFragment};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use futures_util::{pin_mut, SinkExt};
+use serde_json::Value;
+use std::collections::HashMap;
+use tokio_postgres::binary_copy::BinaryCopyInWriter;
+use tokio_postgres::types::{ToSql, Type as PgType};
+use tokio_postgres::Client;
+
+/// Rows between progress reports.
+const PROGRESS_ROWS: usize = 1000;
+/// CSV data buffered before it is sent to the server.
+const CSV_CHUNK_BYTES: usize = 1 << 20;
+
+#[async_trait]
+impl BulkOperationsDriver for PostgresDriver {
+    /// Streams the rows with `COPY ... FROM STDIN`, in binary format when
+    /// every value can be encoded from JSON, as CSV otherwise. Either way
+    /// the rows are inserted all or nothing.
+    async fn bulk_insert(
+        &self,
+        schema: &str,
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<Value>],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        if let Some(row) = rows.iter().find(|row| row.len() != columns.len()) {
+            return Err(anyhow!(
+                "Row has {} values for {} columns",
+                row.len(),
+                columns.len()
+            ));
+        }
+        let dialect = SqlDialect::POSTGRES;
+        let table = bulk::table_ref(dialect, schema, table)?;
+        let names = columns
+            .iter()
+            .map(|column| dialect.quote_ident(column))
+            .collect::<Result<Vec<_>>>()?
+            .join(", ");
+
+        let client = self.connection.pool().get().await?;
+        let probe = client
+            .prepare(&format!("SELECT {} FROM {} LIMIT 0", names, table))
+            .await?;
+        let types: Vec<PgType> = probe
+            .columns()
+            .iter()
+            .map(|column| column.type_().clone())
+            .collect();
+
+        // Values the binary encoder cannot convert (say, a timestamp with an
+        // offset for a `timestamp` column) are left to the server to parse.
+        if let Some(values) = binary_values(rows, &types) {
+            let sql = format!("COPY {} ({}) FROM STDIN (FORMAT binary)", table, names);
+            copy_binary(&client, &sql, &types, &values, progress).await
+        } else {
+            let sql = format!("COPY {} ({}) FROM STDIN (FORMAT csv)", table, names);
+            copy_csv(&client, &sql, rows, progress).await
+        }
+    }
+
+    async fn bulk_update(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let table = bulk::table_ref(SqlDialect::POSTGRES, schema, table)?;
+        let statements = changes
+            .iter()
+            .map(|change| bulk::update_statement(SqlDialect::POSTGRES, &table, change))
+            .collect::<Result<Vec<_>>>()?;
+        self.execute_in_transaction(&statements, progress).await
+    }
+
+    async fn bulk_delete(
+        &self,
+        schema: &str,
+        table: &str,
+        primary_keys: &[HashMap<String, Value>],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let table = bulk::table_ref(SqlDialect::POSTGRES, schema, table)?;
+        let statements = primary_keys
+            .iter()
+            .map(|key| bulk::delete_statement(SqlDialect::POSTGRES, &table, key))
+            .collect::<Result<Vec<_>>>()?;
+        self.execute_in_transaction(&statements, progress).await
+    }
+}
+
+impl PostgresDriver {
+    /// Runs each statement as a cached prepared statement, all in one
+    /// transaction, and returns the total affected rows.
+    async fn execute_in_transaction(
+        &self,
+        statements: &[SqlFragment],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let mut client = self.connection.pool().get().await?;
+        let transaction = client.transaction().await?;
+        let mut affected = 0;
+        for (i, fragment) in statements.iter().enumerate() {
+            let statement = transaction.prepare_cached(&fragment.sql).await?;
+            let values = fragment
+                .params
+                .iter()
+                .zip(statement.params())
+                .map(|(value, ty)| json_param(value, ty))
+                .collect::<Result<Vec<_>>>()?;
+            affected += transaction
+                .execute(&statement, &param_refs(&values))
+                .await?;
+            if (i + 1) % PROGRESS_ROWS == 0 {
+                progress((i + 1) as u64);
+            }
+        }
+        transaction.commit().await?;
+        progress(statements.len() as u64);
+        Ok(affected)
+    }
+}
+
+type BinaryRow = Vec<PgParam>;
+
+/// The rows converted for binary `COPY`, or `None` when a column type or a
+/// value cannot be.
+fn binary_values(rows: &[Vec<Value>], types: &[PgType]) -> Option<Vec<BinaryRow>> {
+    if !types.iter().all(binds_natively) {
+        return None;
+    }
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .zip(types)
+                .map(|(value, ty)| json_param(value, ty).ok())
+                .collect()
+        })
+        .collect()
+}
+
+async fn copy_binary(
+    client: &Client,
+    sql: &str,
+    types: &[PgType],
+    rows: &[BinaryRow],
+    progress: &BulkProgressFn<'_>,
+) -> Result<u64> {
+    let sink = client.copy_in(sql).await?;
+    let writer = BinaryCopyInWriter::new(sink, types);
+    pin_mut!(writer);
+    for (i, row) in rows.iter().enumerate() {
+        // Dropping the writer on error aborts the COPY.
+        writer.as_mut().write(&param_refs(row)).await?;
+        if (i + 1) % PROGRESS_ROWS == 0 {
+            progress((i + 1) as u64);
+        }
+    }
+    let written = writer.finish().await?;
+    progress(written);
+    Ok(written)
+}
+
+async fn copy_csv(
+    client: &Client,
+    sql: &str,
+    rows: &[Vec<Value>],
+    progress: &BulkProgressFn<'_>,
+) -> Result<u64> {
+    let sink = client.copy_in::<_, Bytes>(sql).await?;
+    pin_mut!(sink);
+    let mut buffer = BytesMut::with_capacity(CSV_CHUNK_BYTES);
+    for (i, row) in rows.iter().enumerate() {
+        write_csv_row(&mut buffer, row);
+        if buffer.len() >= CSV_CHUNK_BYTES {
+            sink.as_mut().send(buffer.split().freeze()).await?;
+            progress((i + 1) as u64);
+        }
+    }
+    if !buffer.is_empty() {
+        sink.as_mut().send(buffer.freeze()).await?;
+    }
+    let written = sink.as_mut().finish().await?;
+    progress(written);
+    Ok(written)
+}
+
+fn param_refs(values: &[PgParam]) -> Vec<&(dyn ToSql + Sync)> {
+    values
+        .iter()
+        .map(|value| value.as_ref() as &(dyn ToSql + Sync))
+        .collect()
+}
+
+/// One CSV line; unquoted empty fields are NULL, quoted ones empty strings.
+fn write_csv_row(buffer: &mut BytesMut, row: &[Value]) {
+    for (i, value) in row.iter().enumerate() {
+        if i > 0 {
+            buffer.extend_from_slice(b",");
+        }
+        match value {
+            Value::Null => {}
+            Value::Bool(b) => buffer.extend_from_slice(b.to_string().as_bytes()),
+            Value::Number(n) => buffer.extend_from_slice(n.to_string().as_bytes()),
+            Value::String(s) => write_csv_quoted(buffer, s),
+            Value::Array(_) => write_csv_quoted(buffer, &array_literal(value)),
+            Value::Object(_) => write_csv_quoted(buffer, &value.to_string()),
+        }
+    }
+    buffer.extend_from_slice(b"\n");
+}
+
+fn write_csv_quoted(buffer: &mut BytesMut, text: &str) {
+    buffer.extend_from_slice(b"\"");
+    buffer.extend_from_slice(text.replace('"', "\"\"").as_bytes());
+    buffer.extend_from_slice(b"\"");
+}
+
+/// `{1,"a b",NULL}` for array columns.
+fn array_literal(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(array_literal).collect();
+            format!("{{{}}}", items.join(","))
+        }
+        Value::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+        Value::Object(_) => {
+            let text = value.to_string();
+            format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn writes_csv_rows_for_copy() {
+        let mut buffer = BytesMut::new();
+        write_csv_row(
+            &mut buffer,
+            &[
+                json!(1),
+                json!(null),
+                json!(""),
+                json!("say \"hi\""),
+                json!([1, null, ["a b"]]),
+                json!({ "k": true }),
+            ],
+        );
+        assert_eq!(
+            String::from_utf8(buffer.to_vec()).unwrap(),
+            "1,,\"\",\"say \"\"hi\"\"\",\"{1,NULL,{\"\"a b\"\"}}\",\"{\"\"k\"\":true}\"\n"
+        );
+    }
+}
@@ -1,13 +1,69 @@
 use crate::models::entities::connection as ConnectionModel;
+use crate::services::query_limits::QueryLimits;
+use crate::services::tls::{SslMode, TlsSettings};
 use anyhow::Result;
 use deadpool_postgres::{
     Config, ManagerConfig, Pool, PoolConfig, RecyclingMethod, Runtime, Timeouts,
 };
 use std::time::Duration;
 use tokio_postgres::NoTls;
+use tokio_postgres_rustls::MakeRustlsConnect;
+
+/// Creates a pool using the TLS connector that matches the connection's `ssl_mode`.
+/// Every place that builds a Postgres pool goes through here.
+pub fn create_pool(mut cfg: Config, connection: &ConnectionModel::Model) -> Result<Pool> {
+    let tls = TlsSettings::from_model(connection)?;
+
+    let pool = match tls.mode {
+        SslMode::Disable => {
+            cfg.ssl_mode = Some(deadpool_postgres::SslMode::Disable);
+            cfg.create_pool(Some(Runtime::Tokio1), NoTls)?
+        }
+        mode => {
+            tracing::debug!(
+                "[PostgresConnection] Using TLS mode {} (server name {})",
+                mode.as_str(),
+                tls.server_name
+            );
+            cfg.ssl_mode = Some(if mode == SslMode::Prefer {
+                deadpool_postgres::SslMode::Prefer
+            } else {
+                deadpool_postgres::SslMode::Require
+            });
+            let connector = MakeRustlsConnect::new(tls.rustls_client_config()?);
+            cfg.create_pool(Some(Runtime::Tokio1), connector)?
+        }
+    };
+
+    Ok(pool)
+}
+
+/// The rustls connector for `connection`, or `None` when `ssl_mode` is `disable`.
+/// Side connections such as cancel requests must use the same TLS as the pool.
+pub fn tls_connector(connection: &ConnectionModel::Model) -> Result<Option<MakeRustlsConnect>> {
+    let tls = TlsSettings::from_model(connection)?;
+    if tls.mode == SslMode::Disable {
+        return Ok(None);
+    }
+    Ok(Some(MakeRustlsConnect::new(tls.rustls_client_config()?)))
+}
+
+/// `-c` settings for every pooled session: read-only mode and the
+/// connection's statement timeout.
+fn startup_options(connection: &ConnectionModel::Model) -> Option<String> {
+    let mut options = Vec::new();
+    if connection.is_read_only {
+        options.push("-c default_transaction_read_only=on".to_string());
+    }
+    if let Some(ms) = QueryLimits::from_connection(connection).timeout_ms() {
+        options.push(format!("-c statement_timeout={}", ms));
+    }
+    (!options.is_empty()).then(|| options.join(" "))
+}
 
 pub struct PostgresConnection {
     pool: Pool,
+    tls: Option<MakeRustlsConnect>,
 }
 
 impl PostgresConnection {
@@ -15,6 +71,10 @@ impl PostgresConnection {
         &self.pool
     }
 
+    pub fn tls(&self) -> Option<&MakeRustlsConnect> {
+        self.tls.as_ref()
+    }
+
     pub async fn new(connection: &ConnectionModel::Model, password: &str) -> Result<Self> {
         tracing::info!(
             "[PostgresConnection] Creating optimized connection pool to {}:{}/{}",
@@ -45,26 +105,19 @@ impl PostgresConnection {
             ..PoolConfig::default()
         });
 
-        // Handle Read-Only
-        if connection.is_read_only {
-            cfg.options = Some("-c default_transaction_read_only=on".to_string());
-        }
-
-        // Handle SSL (Placeholder as dependencies are missing)
-        if connection.ssl || connection.ssl_mode.as_deref().unwrap_or("disable") != "disable" {
-            tracing::warn!(
-                "SSL/TLS requested but not fully supported in this build. Proceeding with NoTls."
-            );
-        }
+        cfg.options = startup_options(connection);
 
         tracing::debug!("[PostgresConnection] Pool config created, attempting to create pool...");
 
-        match cfg.create_pool(Some(Runtime::Tokio1), NoTls) {
+        match create_pool(cfg, connection) {
             Ok(pool) => {
                 tracing::info!(
                     "[PostgresConnection] Connection pool created successfully: max_size=20"
                 );
-                Ok(Self { pool })
+                Ok(Self {
+                    pool,
+                    tls: tls_connector(connection)?,
+                })
             }
             Err(e) => {
                 let error_msg = e.to_string();
@@ -106,20 +159,13 @@ impl PostgresConnection {
             recycling_method: RecyclingMethod::Fast,
         });
 
-        // Handle Read-Only
-        if connection.is_read_only {
-            cfg.options = Some("-c default_transaction_read_only=on".to_string());
-        }
-
-        // Handle SSL
-        if connection.ssl || connection.ssl_mode.as_deref().unwrap_or("disable") != "disable" {
-            tracing::warn!(
-                "SSL/TLS requested but not fully supported in this build. Proceeding with NoTls."
-            );
-        }
+        cfg.options = startup_options(connection);
 
-        match cfg.create_pool(Some(Runtime::Tokio1), NoTls) {
-            Ok(pool) => Ok(Self { pool }),
+        match create_pool(cfg, connection) {
+            Ok(pool) => Ok(Self {
+                pool,
+                tls: tls_connector(connection)?,
+            }),
             Err(e) => {
                 let error_msg = e.to_string();
                 let error_lower = error_msg.to_lowercase();
@@ -1,3 +1,4 @@
+mod bulk;
 mod column;
 mod connection;
 mod ddl_export;
@@ -7,24 +8,29 @@ mod query;
 mod schema;
 mod session;
 mod table;
+mod transaction;
 mod view;
 
 pub use column::PostgresColumn;
-pub use connection::PostgresConnection;
+pub use connection::{create_pool, PostgresConnection};
 pub use foreign_key::{ForeignKeyInfo, PostgresForeignKey};
 pub use function::PostgresFunction;
 pub use query::PostgresQuery;
 pub use schema::PostgresSchema;
 pub use table::PostgresTable;
+pub use transaction::PostgresTransaction;
 pub use view::PostgresView;
 
 use super::db_driver::QueryResult;
 use super::driver::{
-    ColumnManagement, ConnectionDriver, FunctionOperations, QueryDriver, SchemaIntrospection,
-    TableOperations, ViewOperations,
+    ColumnManagement, ConnectionDriver, ConnectionPoolDriver, FunctionOperations, QueryDriver,
+    SchemaIntrospection, StatementSession, TableOperations, ViewOperations,
 };
 use crate::models::entities::connection as ConnectionModel;
 use crate::services::driver::extension::DatabaseManagementDriver;
+use crate::services::query_cancel::QueryHandle;
+use crate::services::query_stream::{QueryStreamSink, StreamOptions};
+use crate::services::table_filter::{FilterNode, SortSpec};
 use anyhow::Result;
 use async_trait::async_trait;
 
@@ -44,10 +50,11 @@ impl PostgresDriver {
     pub async fn new(connection: &ConnectionModel::Model, password: &str) -> Result<Self> {
         let conn = PostgresConnection::new(connection, password).await?;
         let pool = conn.pool().clone();
+        let cancel_tls = conn.tls().cloned();
 
         Ok(Self {
             connection: conn,
-            query: PostgresQuery::new(pool.clone()),
+            query: PostgresQuery::new(pool.clone()).with_cancel_tls(cancel_tls),
             schema: PostgresSchema::new(pool.clone()),
             table: PostgresTable::new(pool.clone()),
             column: PostgresColumn::new(pool.clone()),
@@ -67,10 +74,11 @@ impl PostgresDriver {
     pub async fn new_for_test(connection: &ConnectionModel::Model, password: &str) -> Result<Self> {
         let conn = PostgresConnection::new_for_test(connection, password).await?;
         let pool = conn.pool().clone();
+        let cancel_tls = conn.tls().cloned();
 
         Ok(Self {
             connection: conn,
-            query: PostgresQuery::new(pool.clone()),
+            query: PostgresQuery::new(pool.clone()).with_cancel_tls(cancel_tls),
             schema: PostgresSchema::new(pool.clone()),
             table: PostgresTable::new(pool.clone()),
             column: PostgresColumn::new(pool.clone()),
@@ -92,6 +100,22 @@ impl PostgresDriver {
     }
 }
 
+#[async_trait]
+impl ConnectionPoolDriver for PostgresDriver {
+    async fn get_pool_size(&self) -> Result<usize> {
+        Ok(self.connection.pool().status().size)
+    }
+
+    async fn get_active_connections(&self) -> Result<usize> {
+        let status = self.connection.pool().status();
+        Ok(status.size.saturating_sub(status.available))
+    }
+
+    async fn get_idle_connections(&self) -> Result<usize> {
+        Ok(self.connection.pool().status().available)
+    }
+}
+
 #[async_trait]
 impl ConnectionDriver for PostgresDriver {
     async fn test_connection(&self) -> Result<()> {
@@ -113,6 +137,39 @@ impl QueryDriver for PostgresDriver {
         self.query.execute_query(query).await
     }
 
+    async fn execute_query_cancellable(
+        &self,
+        query: &str,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        self.query.execute_query_cancellable(query, handle).await
+    }
+
+    async fn stream_query(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+    ) -> Result<u64> {
+        self.query.stream_query(query, options, sink).await
+    }
+
+    async fn stream_query_cancellable(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+        handle: &QueryHandle,
+    ) -> Result<u64> {
+        self.query
+            .stream_query_cancellable(query, options, sink, handle)
+            .await
+    }
+
+    async fn open_session(&self) -> Result<Box<dyn StatementSession + '_>> {
+        self.query.open_session().await
+    }
+
     async fn execute_script(&self, script: &str) -> Result<u64> {
         self.query.execute_script(script).await
     }
@@ -175,12 +232,13 @@ impl TableOperations for PostgresDriver {
         table: &str,
         limit: i64,
         offset: i64,
-        filter: Option<String>,
+        filter: Option<FilterNode>,
+        sort: Vec<SortSpec>,
         document_id: Option<String>,
         fields: Option<Vec<String>>,
     ) -> Result<QueryResult> {
         self.table
-            .get_table_data(schema, table, limit, offset, filter, document_id, fields)
+            .get_table_data(schema, table, limit, offset, filter, sort, document_id, fields)
             .await
     }
 
@@ -373,8 +431,9 @@ fn quote_postgres_string_literal(value: &str) -> Result<String> {
     Ok(format!("'{}'", trimmed.replace("'", "''")))
 }
 
-impl PostgresDriver {
-    pub async fn create_database_with_options(
+#[async_trait]
+impl DatabaseManagementDriver for PostgresDriver {
+    async fn create_database_with_options(
         &self,
         name: &str,
         options: Option<crate::handlers::database::CreateDatabaseOptions>,
@@ -480,10 +539,7 @@ impl PostgresDriver {
         client.execute(&sql, &[]).await?;
         Ok(())
     }
-}
 
-#[async_trait]
-impl DatabaseManagementDriver for PostgresDriver {
     async fn create_database(&self, name: &str) -> Result<()> {
         self.create_database_with_options(name, None).await
     }
@@ -556,10 +612,52 @@ impl DatabaseManagementDriver for PostgresDriver {
 
 use crate::models::export_ddl::ExportDdlOptions;
 use crate::services::driver::ddl_export::DdlExportDriver;
+use crate::services::schema_diff::{postgres_extractor::PostgresSchemaExtractor, SchemaSnapshot};
 
 #[async_trait]
 impl DdlExportDriver for PostgresDriver {
     async fn export_ddl(&self, options: &ExportDdlOptions) -> Result<String> {
         self.ddl_export.export_ddl(options).await
     }
+
+    async fn schema_snapshot(&self, schema: &str) -> Result<SchemaSnapshot> {
+        PostgresSchemaExtractor::new(self.connection.pool().clone())
+            .extract_schema(schema)
+            .await
+            .map_err(anyhow::Error::msg)
+    }
+}
+
+#[async_trait]
+impl crate::services::driver::DumpDriver for PostgresDriver {}
+
+use crate::services::driver::{TransactionDriver, TransactionSession};
+
+#[async_trait]
+impl TransactionDriver for PostgresDriver {
+    async fn begin_transaction(&self) -> Result<Box<dyn TransactionSession>> {
+        let transaction = PostgresTransaction::begin(self.connection.pool()).await?;
+        Ok(Box::new(transaction))
+    }
+}
+
+use crate::services::driver::PreparedStatementDriver;
+use crate::services::query_params::QueryParams;
+
+#[async_trait]
+impl PreparedStatementDriver for PostgresDriver {
+    async fn execute_with_params(&self, query: &str, params: &QueryParams) -> Result<QueryResult> {
+        self.query.execute_with_params(query, params).await
+    }
+
+    async fn execute_with_params_cancellable(
+        &self,
+        query: &str,
+        params: &QueryParams,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        self.query
+            .execute_with_params_cancellable(query, params, handle)
+            .await
+    }
 }
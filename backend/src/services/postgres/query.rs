@@ -1,8 +1,15 @@
 use crate::services::db_driver::{ColumnMetadata, QueryResult};
-use crate::services::driver::{ConnectionDriver, QueryDriver};
+use crate::services::driver::{
+    ConnectionDriver, PreparedStatementDriver, QueryDriver, StatementSession,
+};
+use crate::services::query_cancel::{QueryHandle, ServerCancel};
+use crate::services::query_limits;
+use crate::services::query_paging::count_sql;
+use crate::services::query_params::{self, QueryParams};
+use crate::services::query_stream::{base_query, is_select_like, QueryStreamSink, StreamOptions};
+use crate::services::table_filter::SqlDialect;
 use anyhow::Result;
 use async_trait::async_trait;
-use bytes::Bytes;
 use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use deadpool_postgres::Pool;
 use futures_util::{pin_mut, StreamExt};
@@ -11,8 +18,11 @@ use rust_decimal::Decimal;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::net::IpAddr;
-use tokio::sync::mpsc;
+use std::str::FromStr;
+use std::sync::Arc;
 use tokio_postgres::types::ToSql;
+use tokio_postgres::{CancelToken, NoTls};
+use tokio_postgres_rustls::MakeRustlsConnect;
 use uuid::Uuid;
 
 #[derive(Debug)]
@@ -1512,74 +1522,239 @@ fn decode_with_decoder(
     }
 }
 
+/// Sends a Postgres cancel request for the backend running the statement.
+struct PostgresCancel {
+    token: CancelToken,
+    tls: Option<MakeRustlsConnect>,
+}
+
+#[async_trait]
+impl ServerCancel for PostgresCancel {
+    async fn cancel(&self) -> Result<()> {
+        match &self.tls {
+            Some(tls) => self.token.cancel_query(tls.clone()).await?,
+            None => self.token.cancel_query(NoTls).await?,
+        }
+        Ok(())
+    }
+}
+
+/// A connection taken out of the pool for `open_session`.
+struct PostgresSession {
+    client: Option<deadpool_postgres::Client>,
+    cancel_tls: Option<MakeRustlsConnect>,
+}
+
+impl Drop for PostgresSession {
+    fn drop(&mut self) {
+        // Session state (SET, temp tables, an open transaction) must not leak
+        // into the pool, so the connection is closed.
+        if let Some(client) = self.client.take() {
+            drop(deadpool_postgres::Client::take(client));
+        }
+    }
+}
+
+#[async_trait]
+impl StatementSession for PostgresSession {
+    async fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
+        let client = self.client.as_ref().expect("session already closed");
+        PostgresQuery::run_query(client, query).await
+    }
+
+    async fn execute_query_cancellable(
+        &mut self,
+        query: &str,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let client = self.client.as_ref().expect("session already closed");
+        let cancel = Arc::new(PostgresCancel {
+            token: client.cancel_token(),
+            tls: self.cancel_tls.clone(),
+        });
+        handle
+            .run(cancel, PostgresQuery::run_query(client, query))
+            .await
+    }
+}
+
 pub struct PostgresQuery {
     pool: Pool,
+    cancel_tls: Option<MakeRustlsConnect>,
 }
 
 impl PostgresQuery {
     pub fn new(pool: Pool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            cancel_tls: None,
+        }
     }
 
-    pub async fn stream_ndjson(
-        &self,
+    /// TLS used for cancel requests; must match the pool's connector.
+    pub fn with_cancel_tls(mut self, tls: Option<MakeRustlsConnect>) -> Self {
+        self.cancel_tls = tls;
+        self
+    }
+
+    pub(super) async fn run_query(
+        client: &deadpool_postgres::Client,
         query: &str,
-        limit: Option<i64>,
-        offset: Option<i64>,
-        include_total_count: bool,
-        tx: mpsc::Sender<Bytes>,
-    ) -> Result<()> {
-        fn is_select_like(sql: &str) -> bool {
-            let trimmed = sql.trim_start();
-            let upper = trimmed.to_uppercase();
-            upper.starts_with("SELECT") || upper.starts_with("WITH")
-        }
+    ) -> Result<QueryResult> {
+        // Attempt to prepare statement first (fast path + better types)
+        match client.prepare(query).await {
+            Ok(statement) => {
+                let is_select = query.trim_start().to_uppercase().starts_with("SELECT")
+                    || query.trim_start().to_uppercase().starts_with("WITH");
 
-        async fn send_line(tx: &mpsc::Sender<Bytes>, value: serde_json::Value) -> bool {
-            let mut buf = serde_json::to_vec(&value).unwrap_or_else(|_| {
-                b"{\"type\":\"error\",\"message\":\"serialization failed\"}".to_vec()
-            });
-            buf.push(b'\n');
-            tx.send(Bytes::from(buf)).await.is_ok()
-        }
+                if is_select {
+                    Self::read_rows(client, &statement, &[]).await
+                } else {
+                    let affected = client.execute(&statement, &[]).await?;
+                    Ok(QueryResult {
+                        columns: vec![],
+                        rows: vec![],
+                        affected_rows: affected,
+                        column_metadata: None,
+                        total_count: None,
+                        limit: None,
+                        offset: None,
+                        has_more: None,
+                        row_metadata: None,
+                        execution_time_ms: None,
+                        json: None,
+                        display_mode: None,
+                        truncated: None,
+                    })
+                }
+            }
+            Err(_) => {
+                // Fallback: Use simple_query for multiple statements or other prepare failures
+                let messages = client.simple_query_raw(query).await?;
+                pin_mut!(messages);
 
-        let client = self.pool.get().await?;
-        let trimmed = query.trim();
-        let is_select = is_select_like(trimmed);
-
-        if !is_select {
-            let affected = client.execute(trimmed, &[]).await?;
-            let _ = send_line(
-                &tx,
-                serde_json::json!({ "type": "done", "affected_rows": affected }),
-            )
-            .await;
-            return Ok(());
-        }
+                let mut last_result: Option<QueryResult> = None;
+                let mut current_rows: Vec<Vec<Value>> = Vec::new();
+                let mut current_columns: Vec<String> = Vec::new();
+                // Later statements still run, so rows past the budget are
+                // skipped rather than the stream dropped.
+                let mut budget = query_limits::read_budget();
+                let mut truncated = None;
+
+                while let Some(message) = messages.next().await {
+                    match message? {
+                        tokio_postgres::SimpleQueryMessage::Row(_) if truncated.is_some() => {}
+                        tokio_postgres::SimpleQueryMessage::Row(row) => {
+                            if current_columns.is_empty() {
+                                // Initialize columns from the first row of the set
+                                for col in row.columns() {
+                                    current_columns.push(col.name().to_string());
+                                }
+                            }
 
-        let base = trimmed.trim_end_matches(';').to_string();
-        let (sql_to_run, meta_limit, meta_offset, total_count) =
-            if limit.is_some() || offset.is_some() || include_total_count {
-                let meta_limit = limit.unwrap_or(1000).max(1);
-                let meta_offset = offset.unwrap_or(0).max(0);
-                let page_sql = format!(
-                    "SELECT * FROM ({}) AS __dbplus_subq LIMIT {} OFFSET {}",
-                    base, meta_limit, meta_offset
-                );
+                            let mut row_values = Vec::new();
+                            for i in 0..row.len() {
+                                let val_str = row.get(i);
+                                let val = match val_str {
+                                    Some(s) => {
+                                        // Simple query protocol returns strings.
+                                        // We attempt basic inference to provide better UX than just all strings.
+                                        if let Ok(n) = s.parse::<i64>() {
+                                            Value::Number(n.into())
+                                        } else if let Ok(n) = s.parse::<f64>() {
+                                            serde_json::Number::from_f64(n)
+                                                .map(Value::Number)
+                                                .unwrap_or(Value::String(s.to_string()))
+                                        } else if s == "t" {
+                                            Value::Bool(true)
+                                        } else if s == "f" {
+                                            Value::Bool(false)
+                                        } else if s.starts_with('{') || s.starts_with('[') {
+                                            if let Ok(v) = serde_json::from_str(s) {
+                                                v
+                                            } else {
+                                                Value::String(s.to_string())
+                                            }
+                                        } else {
+                                            Value::String(s.to_string())
+                                        }
+                                    }
+                                    None => Value::Null,
+                                };
+                                row_values.push(val);
+                            }
+                            if budget.admit(&row_values) {
+                                current_rows.push(row_values);
+                            } else {
+                                truncated = Some(true);
+                            }
+                        }
+                        tokio_postgres::SimpleQueryMessage::CommandComplete(affected) => {
+                            // End of a result set
+                            let result = QueryResult {
+                                columns: std::mem::take(&mut current_columns),
+                                rows: std::mem::take(&mut current_rows),
+                                affected_rows: affected,
+                                column_metadata: None,
+                                total_count: None,
+                                limit: None,
+                                offset: None,
+                                has_more: None,
+                                row_metadata: None,
+                                execution_time_ms: None,
+                                json: None,
+                                display_mode: None,
+                                truncated: truncated.take(),
+                            };
 
-                let total_count = if include_total_count {
-                    let count_sql =
-                        format!("SELECT COUNT(*) AS count FROM ({}) AS __dbplus_subq", base);
-                    let row = client.query_one(&count_sql, &[]).await?;
-                    Some(row.try_get::<_, i64>(0).unwrap_or(0))
-                } else {
-                    None
-                };
+                            // Reset type info and budget for next batch
+                            budget = query_limits::read_budget();
 
-                (page_sql, Some(meta_limit), Some(meta_offset), total_count)
-            } else {
-                (base, None, None, None)
-            };
+                            last_result = Some(result);
+                        }
+                        _ => {}
+                    }
+                }
+
+                Ok(last_result.unwrap_or_else(|| QueryResult {
+                    columns: vec![],
+                    rows: vec![],
+                    affected_rows: 0,
+                    column_metadata: None,
+                    total_count: None,
+                    limit: None,
+                    offset: None,
+                    has_more: None,
+                    row_metadata: None,
+                    execution_time_ms: None,
+                    json: None,
+                    display_mode: None,
+                    truncated: None,
+                }))
+            }
+        }
+    }
+
+    /// Streams a SELECT's rows from `client`; other statements are just
+    /// executed.
+    async fn stream_on_client(
+        client: &deadpool_postgres::Client,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+    ) -> Result<u64> {
+        if !is_select_like(query) {
+            return Ok(client.execute(query.trim(), &[]).await?);
+        }
+
+        let base = base_query(query);
+        let (sql_to_run, limit, offset) = options.paged_sql(base);
+        let total_count = if options.include_total_count {
+            let row = client.query_one(&count_sql(base), &[]).await?;
+            Some(row.try_get::<_, i64>(0).unwrap_or(0))
+        } else {
+            None
+        };
 
         let statement = client.prepare(&sql_to_run).await?;
         let columns: Vec<String> = statement
@@ -1588,54 +1763,141 @@ impl PostgresQuery {
             .map(|c| c.name().to_string())
             .collect();
         let decoders = build_column_decoders(statement.columns());
-
-        let _ = send_line(
-            &tx,
-            serde_json::json!({
-                "type": "meta",
-                "columns": columns,
-                "total_count": total_count,
-                "limit": meta_limit,
-                "offset": meta_offset,
-            }),
-        )
-        .await;
+        if !sink.meta(columns, total_count, limit, offset).await {
+            return Ok(0);
+        }
 
         let params = std::iter::empty::<&(dyn ToSql + Sync)>();
         let stream = client.query_raw(&statement, params).await?;
         pin_mut!(stream);
 
-        let mut row_count: u64 = 0;
-        while let Some(item) = stream.next().await {
-            let row = match item {
-                Ok(r) => r,
-                Err(e) => {
-                    let _ = send_line(
-                        &tx,
-                        serde_json::json!({ "type": "error", "message": e.to_string() }),
-                    )
-                    .await;
-                    break;
-                }
-            };
-
+        while let Some(row) = stream.next().await {
+            let row = row?;
             let mut out = Vec::with_capacity(decoders.len());
             for (i, decoder) in decoders.iter().enumerate() {
                 // Use empty column name/log prefix for hot path.
                 out.push(decode_with_decoder(decoder, &row, i, "", ""));
             }
-            row_count += 1;
-            if !send_line(&tx, serde_json::json!({ "type": "row", "row": out })).await {
+            if !sink.row(out).await {
                 break;
             }
         }
+        Ok(0)
+    }
 
-        let _ = send_line(
-            &tx,
-            serde_json::json!({ "type": "done", "row_count": row_count }),
-        )
-        .await;
-        Ok(())
+    /// `run_query` with bound parameters, converted to the types Postgres
+    /// inferred for their placeholders.
+    pub(super) async fn run_query_with_params(
+        client: &deadpool_postgres::Client,
+        query: &str,
+        params: &QueryParams,
+    ) -> Result<QueryResult> {
+        let bound = query_params::bind(query, params, SqlDialect::POSTGRES)?;
+        let statement = client.prepare(&bound.sql).await?;
+        let values = bound
+            .params
+            .iter()
+            .zip(statement.params())
+            .map(|(value, ty)| json_param(value, ty))
+            .collect::<Result<Vec<_>>>()?;
+        let value_refs: Vec<&(dyn ToSql + Sync)> = values
+            .iter()
+            .map(|v| v.as_ref() as &(dyn ToSql + Sync))
+            .collect();
+
+        if statement.columns().is_empty() {
+            let affected = client.execute(&statement, &value_refs).await?;
+            return Ok(QueryResult {
+                columns: vec![],
+                rows: vec![],
+                affected_rows: affected,
+                column_metadata: None,
+                total_count: None,
+                limit: None,
+                offset: None,
+                has_more: None,
+                row_metadata: None,
+                execution_time_ms: None,
+                json: None,
+                display_mode: None,
+                truncated: None,
+            });
+        }
+        Self::read_rows(client, &statement, &value_refs).await
+    }
+
+    /// Runs a prepared statement and decodes its rows, with column metadata.
+    /// Stops reading at the end of `query_limits::read_budget`.
+    async fn read_rows(
+        client: &deadpool_postgres::Client,
+        statement: &tokio_postgres::Statement,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<QueryResult> {
+        let decoders = build_column_decoders(statement.columns());
+        let columns: Vec<String> = statement
+            .columns()
+            .iter()
+            .map(|c| c.name().to_string())
+            .collect();
+        let mut budget = query_limits::read_budget();
+        let mut result_rows = Vec::new();
+        let mut truncated = None;
+        // The stream is dropped before `resolve_metadata` queries the client.
+        {
+            let stream = client.query_raw(statement, params.iter().copied()).await?;
+            pin_mut!(stream);
+            while let Some(row) = stream.next().await {
+                let row = row?;
+                let mut current_row = Vec::new();
+                for (i, col) in columns.iter().enumerate() {
+                    let value = decode_with_decoder(&decoders[i], &row, i, col, "[execute_query]");
+                    current_row.push(value);
+                }
+                if !budget.admit(&current_row) {
+                    truncated = Some(true);
+                    break;
+                }
+                result_rows.push(current_row);
+            }
+        }
+
+        if result_rows.is_empty() && truncated.is_none() {
+            return Ok(QueryResult {
+                columns: vec![],
+                rows: vec![],
+                affected_rows: 0,
+                column_metadata: None,
+                total_count: None,
+                limit: None,
+                offset: None,
+                has_more: None,
+                row_metadata: None,
+                execution_time_ms: None,
+                json: None,
+                display_mode: None,
+                truncated: None,
+            });
+        }
+
+        let column_metadata = Self::resolve_metadata(client, statement.columns())
+            .await
+            .unwrap_or(None);
+
+        Ok(QueryResult {
+            columns,
+            rows: result_rows,
+            affected_rows: 0,
+            column_metadata,
+            total_count: None,
+            limit: None,
+            offset: None,
+            has_more: None,
+            row_metadata: None,
+            execution_time_ms: None,
+            json: None,
+            display_mode: None,
+            truncated,
+        })
     }
 
     async fn resolve_metadata(
@@ -1747,6 +2009,7 @@ impl QueryDriver for PostgresQuery {
                 execution_time_ms: None,
                 json: None,
                 display_mode: None,
+                truncated: None,
             });
         }
 
@@ -1785,185 +2048,64 @@ impl QueryDriver for PostgresQuery {
             execution_time_ms: None,
             json: None,
             display_mode: None,
+            truncated: None,
         })
     }
 
     async fn execute_query(&self, query: &str) -> Result<QueryResult> {
         let client = self.pool.get().await?;
+        Self::run_query(&client, query).await
+    }
 
-        // Attempt to prepare statement first (fast path + better types)
-        match client.prepare(query).await {
-            Ok(statement) => {
-                let is_select = query.trim_start().to_uppercase().starts_with("SELECT")
-                    || query.trim_start().to_uppercase().starts_with("WITH");
-
-                if is_select {
-                    let rows = client.query(&statement, &[]).await?;
-
-                    if rows.is_empty() {
-                        return Ok(QueryResult {
-                            columns: vec![],
-                            rows: vec![],
-                            affected_rows: 0,
-                            column_metadata: None,
-                            total_count: None,
-                            limit: None,
-                            offset: None,
-                            has_more: None,
-                            row_metadata: None,
-                            execution_time_ms: None,
-                            json: None,
-                            display_mode: None,
-                        });
-                    }
-
-                    let columns: Vec<String> = rows[0]
-                        .columns()
-                        .iter()
-                        .map(|c| c.name().to_string())
-                        .collect();
-
-                    let decoders = build_column_decoders(rows[0].columns());
-
-                    let mut result_rows = Vec::new();
-                    for row in &rows {
-                        let mut current_row = Vec::new();
-                        for (i, col) in columns.iter().enumerate() {
-                            let value =
-                                decode_with_decoder(&decoders[i], row, i, col, "[execute_query]");
-                            current_row.push(value);
-                        }
-                        result_rows.push(current_row);
-                    }
-
-                    let column_metadata = Self::resolve_metadata(&client, rows[0].columns())
-                        .await
-                        .unwrap_or(None);
-
-                    Ok(QueryResult {
-                        columns,
-                        rows: result_rows,
-                        affected_rows: 0,
-                        column_metadata,
-                        total_count: None,
-                        limit: None,
-                        offset: None,
-                        has_more: None,
-                        row_metadata: None,
-                        execution_time_ms: None,
-                        json: None,
-                        display_mode: None,
-                    })
-                } else {
-                    let affected = client.execute(&statement, &[]).await?;
-                    Ok(QueryResult {
-                        columns: vec![],
-                        rows: vec![],
-                        affected_rows: affected,
-                        column_metadata: None,
-                        total_count: None,
-                        limit: None,
-                        offset: None,
-                        has_more: None,
-                        row_metadata: None,
-                        execution_time_ms: None,
-                        json: None,
-                        display_mode: None,
-                    })
-                }
-            }
-            Err(_) => {
-                // Fallback: Use simple_query for multiple statements or other prepare failures
-                let messages = client.simple_query(query).await?;
-
-                let mut last_result: Option<QueryResult> = None;
-                let mut current_rows: Vec<Vec<Value>> = Vec::new();
-                let mut current_columns: Vec<String> = Vec::new();
-
-                for message in messages {
-                    match message {
-                        tokio_postgres::SimpleQueryMessage::Row(row) => {
-                            if current_columns.is_empty() {
-                                // Initialize columns from the first row of the set
-                                for col in row.columns() {
-                                    current_columns.push(col.name().to_string());
-                                }
-                            }
-
-                            let mut row_values = Vec::new();
-                            for i in 0..row.len() {
-                                let val_str = row.get(i);
-                                let val = match val_str {
-                                    Some(s) => {
-                                        // Simple query protocol returns strings.
-                                        // We attempt basic inference to provide better UX than just all strings.
-                                        if let Ok(n) = s.parse::<i64>() {
-                                            Value::Number(n.into())
-                                        } else if let Ok(n) = s.parse::<f64>() {
-                                            serde_json::Number::from_f64(n)
-                                                .map(Value::Number)
-                                                .unwrap_or(Value::String(s.to_string()))
-                                        } else if s == "t" {
-                                            Value::Bool(true)
-                                        } else if s == "f" {
-                                            Value::Bool(false)
-                                        } else if s.starts_with('{') || s.starts_with('[') {
-                                            if let Ok(v) = serde_json::from_str(s) {
-                                                v
-                                            } else {
-                                                Value::String(s.to_string())
-                                            }
-                                        } else {
-                                            Value::String(s.to_string())
-                                        }
-                                    }
-                                    None => Value::Null,
-                                };
-                                row_values.push(val);
-                            }
-                            current_rows.push(row_values);
-                        }
-                        tokio_postgres::SimpleQueryMessage::CommandComplete(affected) => {
-                            // End of a result set
-                            let result = QueryResult {
-                                columns: std::mem::take(&mut current_columns),
-                                rows: std::mem::take(&mut current_rows),
-                                affected_rows: affected,
-                                column_metadata: None,
-                                total_count: None,
-                                limit: None,
-                                offset: None,
-                                has_more: None,
-                                row_metadata: None,
-                                execution_time_ms: None,
-                                json: None,
-                                display_mode: None,
-                            };
+    async fn execute_query_cancellable(
+        &self,
+        query: &str,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let client = self.pool.get().await?;
+        let cancel = Arc::new(PostgresCancel {
+            token: client.cancel_token(),
+            tls: self.cancel_tls.clone(),
+        });
+        handle.run(cancel, Self::run_query(&client, query)).await
+    }
 
-                            // Reset type info for next batch
+    async fn stream_query(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+    ) -> Result<u64> {
+        let client = self.pool.get().await?;
+        Self::stream_on_client(&client, query, options, sink).await
+    }
 
-                            last_result = Some(result);
-                        }
-                        _ => {}
-                    }
-                }
+    async fn stream_query_cancellable(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+        handle: &QueryHandle,
+    ) -> Result<u64> {
+        let client = self.pool.get().await?;
+        let cancel = Arc::new(PostgresCancel {
+            token: client.cancel_token(),
+            tls: self.cancel_tls.clone(),
+        });
+        handle
+            .run(
+                cancel,
+                Self::stream_on_client(&client, query, options, sink),
+            )
+            .await
+    }
 
-                Ok(last_result.unwrap_or_else(|| QueryResult {
-                    columns: vec![],
-                    rows: vec![],
-                    affected_rows: 0,
-                    column_metadata: None,
-                    total_count: None,
-                    limit: None,
-                    offset: None,
-                    has_more: None,
-                    row_metadata: None,
-                    execution_time_ms: None,
-                    json: None,
-                    display_mode: None,
-                }))
-            }
-        }
+    async fn open_session(&self) -> Result<Box<dyn StatementSession + '_>> {
+        let client = self.pool.get().await?;
+        Ok(Box::new(PostgresSession {
+            client: Some(client),
+            cancel_tls: self.cancel_tls.clone(),
+        }))
     }
 
     async fn execute_script(&self, script: &str) -> Result<u64> {
@@ -1991,6 +2133,121 @@ impl QueryDriver for PostgresQuery {
     }
 }
 
+#[async_trait]
+impl PreparedStatementDriver for PostgresQuery {
+    async fn execute_with_params(&self, query: &str, params: &QueryParams) -> Result<QueryResult> {
+        let client = self.pool.get().await?;
+        Self::run_query_with_params(&client, query, params).await
+    }
+
+    async fn execute_with_params_cancellable(
+        &self,
+        query: &str,
+        params: &QueryParams,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let client = self.pool.get().await?;
+        let cancel = Arc::new(PostgresCancel {
+            token: client.cancel_token(),
+            tls: self.cancel_tls.clone(),
+        });
+        handle
+            .run(cancel, Self::run_query_with_params(&client, query, params))
+            .await
+    }
+}
+
+/// SQL NULL for a parameter of any type.
+#[derive(Debug)]
+struct PgNull;
+
+impl ToSql for PgNull {
+    fn to_sql(
+        &self,
+        _: &PgType,
+        _: &mut bytes::BytesMut,
+    ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+        Ok(postgres_types::IsNull::Yes)
+    }
+
+    fn accepts(_: &PgType) -> bool {
+        true
+    }
+
+    postgres_types::to_sql_checked!();
+}
+
+/// A bound value. `Send` so that it can be held across awaits.
+pub(super) type PgParam = Box<dyn ToSql + Sync + Send>;
+
+/// Converts a JSON value to the Rust type matching the parameter type
+/// Postgres inferred, since values are sent in binary format.
+pub(super) fn json_param(value: &Value, ty: &PgType) -> Result<PgParam> {
+    let text = match value {
+        Value::Null => return Ok(Box::new(PgNull)),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    let invalid = || anyhow::anyhow!("Value {} is not a valid {}", value, ty.name());
+
+    let param: PgParam = match *ty {
+        PgType::BOOL => match value {
+            Value::Bool(b) => Box::new(*b),
+            _ => Box::new(text.parse::<bool>().map_err(|_| invalid())?),
+        },
+        PgType::INT2 => Box::new(text.parse::<i16>().map_err(|_| invalid())?),
+        PgType::INT4 => Box::new(text.parse::<i32>().map_err(|_| invalid())?),
+        PgType::INT8 => Box::new(text.parse::<i64>().map_err(|_| invalid())?),
+        PgType::FLOAT4 => Box::new(text.parse::<f32>().map_err(|_| invalid())?),
+        PgType::FLOAT8 => Box::new(text.parse::<f64>().map_err(|_| invalid())?),
+        PgType::NUMERIC => Box::new(Decimal::from_str(&text).map_err(|_| invalid())?),
+        PgType::UUID => Box::new(uuid::Uuid::parse_str(&text).map_err(|_| invalid())?),
+        PgType::JSON | PgType::JSONB => Box::new(value.clone()),
+        PgType::DATE => {
+            Box::new(NaiveDate::parse_from_str(&text, "%Y-%m-%d").map_err(|_| invalid())?)
+        }
+        PgType::TIMESTAMP => Box::new(
+            NaiveDateTime::from_str(&text)
+                .or_else(|_| NaiveDateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S%.f"))
+                .map_err(|_| invalid())?,
+        ),
+        PgType::TIMESTAMPTZ => Box::new(
+            DateTime::parse_from_rfc3339(&text)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|_| invalid())?,
+        ),
+        // Text-like types; anything else is reported by the driver.
+        _ => Box::new(text),
+    };
+    Ok(param)
+}
+
+/// Types `json_param` binds as themselves. Values of any other type (enums,
+/// domains, `inet`, ...) are sent as text, which the server and binary `COPY`
+/// reject unless the placeholder is cast from text.
+pub(super) fn binds_natively(ty: &PgType) -> bool {
+    matches!(
+        *ty,
+        PgType::BOOL
+            | PgType::INT2
+            | PgType::INT4
+            | PgType::INT8
+            | PgType::FLOAT4
+            | PgType::FLOAT8
+            | PgType::NUMERIC
+            | PgType::UUID
+            | PgType::JSON
+            | PgType::JSONB
+            | PgType::DATE
+            | PgType::TIMESTAMP
+            | PgType::TIMESTAMPTZ
+            | PgType::TEXT
+            | PgType::VARCHAR
+            | PgType::BPCHAR
+            | PgType::NAME
+    ) || ty.name() == "citext"
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
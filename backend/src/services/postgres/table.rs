@@ -1,13 +1,16 @@
+use super::query::{binds_natively, json_param};
 use crate::services::db_driver::{
     DependentRoutineInfo, DependentViewInfo, IndexInfo, PartitionChildInfo, PartitionInfo,
     QueryResult, ReferencingForeignKeyInfo, RoleInfo, StorageBloatInfo, TableComment,
     TableConstraints, TableDependencies, TableGrant, TableStatistics, TriggerInfo,
 };
 use crate::services::driver::TableOperations;
+use crate::services::table_filter::{self, FilterNode, SortSpec, SqlDialect};
 use anyhow::Result;
 use async_trait::async_trait;
 use deadpool_postgres::Pool;
 use serde_json::Value;
+use tokio_postgres::types::{ToSql, Type};
 
 pub struct PostgresTable {
     pool: Pool,
@@ -27,7 +30,8 @@ impl TableOperations for PostgresTable {
         table: &str,
         limit: i64,
         offset: i64,
-        _filter: Option<String>,
+        filter: Option<FilterNode>,
+        sort: Vec<SortSpec>,
         _document_id: Option<String>,
         _fields: Option<Vec<String>>,
     ) -> Result<QueryResult> {
@@ -61,13 +65,58 @@ impl TableOperations for PostgresTable {
             "*".to_string()
         };
 
-        let query = format!(
-            "SELECT {} FROM \"{}\".\"{}\" LIMIT $1 OFFSET $2",
-            select_clause, schema, table
-        );
+        let order_by = table_filter::order_by_clause(&sort, SqlDialect::POSTGRES)?;
+        // `casts[i]` is the type the text of parameter `i + 1` is cast to.
+        let build_query = |casts: &[Option<String>]| -> Result<(String, Vec<Value>)> {
+            let where_clause = table_filter::where_clause_with(
+                filter.as_ref(),
+                SqlDialect::POSTGRES,
+                &mut |index| match casts.get(index - 1).cloned().flatten() {
+                    Some(ty) => format!("${}::text::{}", index, ty),
+                    None => format!("${}", index),
+                },
+            )?;
+            let limit_param = where_clause.params.len() + 1;
+            let query = format!(
+                "SELECT {} FROM \"{}\".\"{}\"{}{} LIMIT ${} OFFSET ${}",
+                select_clause,
+                schema,
+                table,
+                where_clause.sql,
+                order_by,
+                limit_param,
+                limit_param + 1
+            );
+            Ok((query, where_clause.params))
+        };
 
         let client = self.pool.get().await?;
-        let rows = client.query(&query, &[&limit, &offset]).await?;
+        // Filter values are converted to the types Postgres inferred for them;
+        // values of types with no conversion are sent as text and cast.
+        let (query, values) = build_query(&[])?;
+        let mut statement = client.prepare(&query).await?;
+        let casts: Vec<Option<String>> = statement
+            .params()
+            .iter()
+            .take(values.len())
+            .map(|ty| (!binds_natively(ty)).then(|| qualified_type_name(ty)))
+            .collect();
+        if casts.iter().any(Option::is_some) {
+            let (query, _) = build_query(&casts)?;
+            statement = client.prepare(&query).await?;
+        }
+        let mut params = values
+            .iter()
+            .zip(statement.params())
+            .map(|(value, ty)| json_param(value, ty))
+            .collect::<Result<Vec<_>>>()?;
+        params.push(Box::new(limit));
+        params.push(Box::new(offset));
+        let param_refs: Vec<&(dyn ToSql + Sync)> = params
+            .iter()
+            .map(|p| p.as_ref() as &(dyn ToSql + Sync))
+            .collect();
+        let rows = client.query(&statement, &param_refs).await?;
 
         if rows.is_empty() {
             return Ok(QueryResult {
@@ -83,6 +132,7 @@ impl TableOperations for PostgresTable {
                 execution_time_ms: None,
                 json: None,
                 display_mode: None,
+                truncated: None,
             });
         }
 
@@ -142,6 +192,7 @@ impl TableOperations for PostgresTable {
             execution_time_ms: None,
             json: None,
             display_mode: None,
+            truncated: None,
         })
     }
 
@@ -1142,3 +1193,9 @@ impl TableOperations for PostgresTable {
         })
     }
 }
+
+/// `"schema"."name"` of a type, for a cast.
+fn qualified_type_name(ty: &Type) -> String {
+    let quote = |name: &str| format!("\"{}\"", name.replace('"', "\"\""));
+    format!("{}.{}", quote(ty.schema()), quote(ty.name()))
+}
@@ -0,0 +1,63 @@
+use super::PostgresQuery;
+use crate::services::db_driver::QueryResult;
+use crate::services::driver::TransactionSession;
+use anyhow::Result;
+use async_trait::async_trait;
+use deadpool_postgres::{Client, Pool};
+
+/// A transaction on a connection taken out of the pool.
+pub struct PostgresTransaction {
+    client: Option<Client>,
+}
+
+impl PostgresTransaction {
+    pub async fn begin(pool: &Pool) -> Result<Self> {
+        let client = pool.get().await?;
+        client.batch_execute("BEGIN").await?;
+        Ok(Self {
+            client: Some(client),
+        })
+    }
+
+    fn client(&self) -> Result<&Client> {
+        self.client
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Transaction is already finished"))
+    }
+
+    async fn finish(mut self: Box<Self>, statement: &str) -> Result<()> {
+        let client = self.client.take().expect("transaction already finished");
+        match client.batch_execute(statement).await {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                // The transaction state is unknown; close the connection.
+                drop(Client::take(client));
+                Err(e.into())
+            }
+        }
+    }
+}
+
+impl Drop for PostgresTransaction {
+    fn drop(&mut self) {
+        // Closing the connection rolls the transaction back on the server.
+        if let Some(client) = self.client.take() {
+            drop(Client::take(client));
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionSession for PostgresTransaction {
+    async fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
+        PostgresQuery::run_query(self.client()?, query).await
+    }
+
+    async fn commit(self: Box<Self>) -> Result<()> {
+        self.finish("COMMIT").await
+    }
+
+    async fn rollback(self: Box<Self>) -> Result<()> {
+        self.finish("ROLLBACK").await
+    }
+}
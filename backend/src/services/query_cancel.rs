@@ -0,0 +1,190 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// Engine-specific way to stop a statement that is running on the server.
+#[async_trait]
+pub trait ServerCancel: Send + Sync {
+    /// Returns once the server has accepted the cancel request.
+    async fn cancel(&self) -> Result<()>;
+}
+
+/// Result of a cancel request, reported back to the caller.
+#[derive(Debug, Clone, Serialize)]
+pub struct CancelOutcome {
+    pub found: bool,
+    /// Whether the database confirmed it received the cancel request. `false`
+    /// means only the client stopped waiting; the statement may still run.
+    pub server_cancelled: bool,
+    pub message: Option<String>,
+}
+
+impl CancelOutcome {
+    pub fn not_found() -> Self {
+        Self {
+            found: false,
+            server_cancelled: false,
+            message: Some("Query not found".to_string()),
+        }
+    }
+}
+
+/// A running query that can be cancelled. Registered in `AppState.queries`
+/// under the client-supplied query id.
+#[derive(Default)]
+pub struct QueryHandle {
+    token: CancellationToken,
+    /// Held for the whole server-side cancel, so that `run` cannot detach
+    /// (and give the connection back) while a cancel is on its way.
+    server: tokio::sync::Mutex<Option<Arc<dyn ServerCancel>>>,
+}
+
+impl QueryHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancelled after the server-side cancel (if any) has been sent.
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Runs `statement` with `cancel` as the way to stop it. Returns only
+    /// once no cancel can reach the statement's connection any more, so drop
+    /// that connection after this returns: a late cancel cannot hit the next
+    /// statement on it.
+    pub async fn run<T>(
+        &self,
+        cancel: Arc<dyn ServerCancel>,
+        statement: impl Future<Output = T>,
+    ) -> T {
+        *self.server.lock().await = Some(cancel);
+        let _detach = Detach { handle: self };
+        let output = statement.await;
+        // Waits for a cancel that is being sent right now.
+        *self.server.lock().await = None;
+        output
+    }
+
+    pub async fn cancel(&self) -> CancelOutcome {
+        let mut server = self.server.lock().await;
+        let outcome = match server.take() {
+            Some(server) => match server.cancel().await {
+                Ok(()) => CancelOutcome {
+                    found: true,
+                    server_cancelled: true,
+                    message: None,
+                },
+                Err(e) => {
+                    tracing::warn!("[QueryCancel] Server-side cancel failed: {}", e);
+                    CancelOutcome {
+                        found: true,
+                        server_cancelled: false,
+                        message: Some(format!("Server-side cancel failed: {}", e)),
+                    }
+                }
+            },
+            None => CancelOutcome {
+                found: true,
+                server_cancelled: false,
+                message: Some(
+                    "No server-side cancel available; stopped waiting for the result".to_string(),
+                ),
+            },
+        };
+        drop(server);
+        self.token.cancel();
+        outcome
+    }
+}
+
+/// Forgets the server-side cancel when the future of `QueryHandle::run` is
+/// dropped before the statement ends.
+struct Detach<'a> {
+    handle: &'a QueryHandle,
+}
+
+impl Drop for Detach<'_> {
+    fn drop(&mut self) {
+        // A cancel in flight holds the lock; it stops a statement that is
+        // being abandoned anyway.
+        if let Ok(mut server) = self.handle.server.try_lock() {
+            *server = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tokio::sync::Notify;
+
+    struct Flag(AtomicBool);
+
+    #[async_trait]
+    impl ServerCancel for Flag {
+        async fn cancel(&self) -> Result<()> {
+            self.0.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn cancel_reaches_running_statement_only() {
+        let handle = QueryHandle::new();
+        let flag = Arc::new(Flag(AtomicBool::new(false)));
+
+        handle.run(flag.clone(), async {}).await;
+        let outcome = handle.cancel().await;
+        assert!(!outcome.server_cancelled);
+        assert!(!flag.0.load(Ordering::SeqCst));
+
+        let handle = QueryHandle::new();
+        let outcome = handle.run(flag.clone(), handle.cancel()).await;
+        assert!(outcome.server_cancelled);
+        assert!(flag.0.load(Ordering::SeqCst));
+        assert!(handle.token().is_cancelled());
+    }
+
+    /// Finishes its cancel only once `release` is notified.
+    #[derive(Default)]
+    struct SlowCancel {
+        started: Notify,
+        release: Notify,
+        done: AtomicBool,
+    }
+
+    #[async_trait]
+    impl ServerCancel for SlowCancel {
+        async fn cancel(&self) -> Result<()> {
+            self.started.notify_one();
+            self.release.notified().await;
+            self.done.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn statement_ending_during_cancel_waits_for_it() {
+        let handle = Arc::new(QueryHandle::new());
+        let slow = Arc::new(SlowCancel::default());
+        let cancelling = tokio::spawn({
+            let handle = handle.clone();
+            async move { handle.cancel().await }
+        });
+
+        // The statement ends while the cancel is being sent.
+        handle
+            .run(slow.clone(), async {
+                slow.started.notified().await;
+                slow.release.notify_one();
+            })
+            .await;
+        assert!(slow.done.load(Ordering::SeqCst));
+        assert!(cancelling.await.unwrap().server_cancelled);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::models::entities::connection;
+use crate::services::db_driver::QueryResult;
+use serde_json::Value;
+use std::future::Future;
+use std::time::Duration;
+
+tokio::task_local! {
+    /// Limits of the enclosing `QueryLimits::enforce`.
+    static READ_LIMITS: QueryLimits;
+}
+
+/// How long a connection's statements may run and how much of a result is
+/// returned. `None` means unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryLimits {
+    pub statement_timeout: Option<Duration>,
+    pub max_rows: Option<usize>,
+    pub max_bytes: Option<usize>,
+}
+
+impl QueryLimits {
+    /// Zero and negative settings count as unset.
+    pub fn from_connection(connection: &connection::Model) -> Self {
+        let positive = |value: Option<i64>| value.filter(|v| *v > 0).map(|v| v as u64);
+        Self {
+            statement_timeout: positive(connection.statement_timeout_ms.map(i64::from))
+                .map(Duration::from_millis),
+            max_rows: positive(connection.max_result_rows.map(i64::from)).map(|v| v as usize),
+            max_bytes: positive(connection.max_result_bytes).map(|v| v as usize),
+        }
+    }
+
+    pub fn timeout_ms(&self) -> Option<u64> {
+        self.statement_timeout.map(|t| t.as_millis() as u64)
+    }
+
+    /// Most rows a result can have: `max_rows`, or fewer when `max_bytes`
+    /// cannot hold that many empty rows (`[]`, two bytes each).
+    pub fn row_cap(&self) -> Option<usize> {
+        let by_bytes = self.max_bytes.map(|max_bytes| max_bytes / 2);
+        match (self.max_rows, by_bytes) {
+            (Some(rows), Some(bytes)) => Some(rows.min(bytes)),
+            (rows, bytes) => rows.or(bytes),
+        }
+    }
+
+    /// `limit` lowered to `row_cap`.
+    pub fn page_limit(&self, limit: i64) -> i64 {
+        match self.row_cap() {
+            Some(cap) => limit.min(cap as i64),
+            None => limit,
+        }
+    }
+
+    pub fn budget(&self) -> ResultBudget {
+        ResultBudget {
+            max_rows: self.max_rows,
+            max_bytes: self.max_bytes,
+            rows: 0,
+            bytes: 0,
+        }
+    }
+
+    /// Runs `read` with the limits applied by the drivers while they read
+    /// rows; see `read_budget`.
+    pub async fn enforce<F: Future>(self, read: F) -> F::Output {
+        READ_LIMITS.scope(self, read).await
+    }
+
+    /// Drops the rows of `result` past the limits and marks it truncated.
+    pub fn apply(&self, result: &mut QueryResult) {
+        let mut budget = self.budget();
+        if let Some(kept) = result.rows.iter().position(|row| !budget.admit(row)) {
+            result.rows.truncate(kept);
+            result.truncated = Some(true);
+        }
+    }
+}
+
+/// Counts rows, and their size as JSON, against the limits while they are read.
+#[derive(Debug, Clone, Default)]
+pub struct ResultBudget {
+    max_rows: Option<usize>,
+    max_bytes: Option<usize>,
+    rows: usize,
+    bytes: usize,
+}
+
+impl ResultBudget {
+    /// Whether `row` still fits. Once a row is refused, later ones are too.
+    pub fn admit(&mut self, row: &[Value]) -> bool {
+        if self.max_rows.is_some_and(|max| self.rows >= max) {
+            return false;
+        }
+        let bytes = self.bytes + array_size(row);
+        if self.max_bytes.is_some_and(|max| bytes > max) {
+            self.max_rows = Some(self.rows);
+            return false;
+        }
+        self.rows += 1;
+        self.bytes = bytes;
+        true
+    }
+}
+
+/// Budget for one result set read by a driver: that of the enclosing
+/// `QueryLimits::enforce`, unbounded outside one. It admits one row past
+/// `max_rows` so that `QueryLimits::apply` notices the cut. Drivers stop
+/// reading at the first refused row and mark the result truncated.
+pub fn read_budget() -> ResultBudget {
+    READ_LIMITS
+        .try_with(|limits| ResultBudget {
+            max_rows: limits.max_rows.map(|max_rows| max_rows + 1),
+            ..limits.budget()
+        })
+        .unwrap_or_default()
+}
+
+/// Size of `values` serialized as a JSON array, ignoring string escapes.
+fn array_size(values: &[Value]) -> usize {
+    2 + values.len().saturating_sub(1) + values.iter().map(value_size).sum::<usize>()
+}
+
+fn value_size(value: &Value) -> usize {
+    match value {
+        Value::Null | Value::Bool(true) => 4,
+        Value::Bool(false) => 5,
+        Value::Number(n) => n.to_string().len(),
+        Value::String(s) => s.len() + 2,
+        Value::Array(values) => array_size(values),
+        Value::Object(map) => {
+            2 + map.len().saturating_sub(1)
+                + map
+                    .iter()
+                    .map(|(key, value)| key.len() + 3 + value_size(value))
+                    .sum::<usize>()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn result(rows: Vec<Vec<Value>>) -> QueryResult {
+        QueryResult {
+            columns: vec!["v".to_string()],
+            rows,
+            affected_rows: 0,
+            column_metadata: None,
+            total_count: None,
+            limit: None,
+            offset: None,
+            has_more: None,
+            row_metadata: None,
+            execution_time_ms: None,
+            json: None,
+            display_mode: None,
+            truncated: None,
+        }
+    }
+
+    #[test]
+    fn truncates_at_the_first_limit_reached() {
+        let rows = vec![
+            vec![json!("abc")],
+            vec![json!({ "k": [1, null] })],
+            vec![json!(1)],
+        ];
+        assert_eq!(
+            array_size(&rows[1]),
+            serde_json::to_string(&rows[1]).unwrap().len()
+        );
+
+        let mut within = result(rows.clone());
+        QueryLimits::default().apply(&mut within);
+        assert_eq!((within.rows.len(), within.truncated), (3, None));
+
+        let by_rows = QueryLimits {
+            max_rows: Some(2),
+            ..Default::default()
+        };
+        let mut capped = result(rows.clone());
+        by_rows.apply(&mut capped);
+        assert_eq!((capped.rows.len(), capped.truncated), (2, Some(true)));
+
+        // `["abc"]` is 7 bytes; the next row does not fit, nor does any after it.
+        let mut budget = QueryLimits {
+            max_bytes: Some(10),
+            ..Default::default()
+        }
+        .budget();
+        assert!(budget.admit(&rows[0]));
+        assert!(!budget.admit(&rows[1]));
+        assert!(!budget.admit(&rows[2]));
+    }
+
+    #[tokio::test]
+    async fn drivers_read_within_the_enforced_limits() {
+        let row = vec![json!(1)];
+        let mut unbounded = read_budget();
+        assert!((0..1000).all(|_| unbounded.admit(&row)));
+
+        let limits = QueryLimits {
+            max_rows: Some(2),
+            ..Default::default()
+        };
+        let admitted = limits
+            .enforce(async {
+                let mut budget = read_budget();
+                (0..10).take_while(|_| budget.admit(&row)).count()
+            })
+            .await;
+        assert_eq!(admitted, 3);
+
+        let by_bytes = QueryLimits {
+            max_rows: Some(100),
+            max_bytes: Some(20),
+            ..Default::default()
+        };
+        assert_eq!(by_bytes.row_cap(), Some(10));
+        assert_eq!(by_bytes.page_limit(50), 10);
+    }
+}
@@ -0,0 +1,261 @@
+use crate::services::db_driver::QueryResult;
+use crate::services::driver_factory;
+use crate::services::query_stream::is_select_like;
+use anyhow::Result;
+use serde_json::Value;
+use sqlparser::ast::{Query, SetExpr, Statement};
+use sqlparser::dialect::Dialect;
+use sqlparser::parser::Parser;
+use std::future::Future;
+
+/// Parser dialect of the engine's `DriverSpec`; `None` for engines whose
+/// queries are not parsed.
+pub fn sql_dialect(db_type: &str) -> Option<Box<dyn Dialect>> {
+    let dialect = driver_factory::spec_for(db_type).ok()?.parser_dialect?;
+    Some(dialect())
+}
+
+/// Wraps `base` so only one page of it is returned. The newline keeps a
+/// trailing `--` comment in `base` from swallowing the rest.
+pub fn wrap_page(base: &str, limit: i64, offset: i64) -> String {
+    format!(
+        "SELECT * FROM ({}\n) AS __dbplus_subq LIMIT {} OFFSET {}",
+        base, limit, offset
+    )
+}
+
+pub fn count_sql(base: &str) -> String {
+    format!(
+        "SELECT COUNT(*) AS count FROM ({}\n) AS __dbplus_subq",
+        base
+    )
+}
+
+/// Reads the value of a `COUNT(*)` column. ClickHouse returns 64-bit
+/// integers as JSON strings.
+pub fn count_value(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// A read-only statement that the database can page and count itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageableQuery<'a> {
+    base: &'a str,
+    /// Page through a subquery instead of appending LIMIT/OFFSET.
+    wrap: bool,
+}
+
+impl PageableQuery<'_> {
+    pub fn page_sql(&self, limit: i64, offset: i64) -> String {
+        if self.wrap {
+            wrap_page(self.base, limit, offset)
+        } else {
+            format!("{}\nLIMIT {} OFFSET {}", self.base, limit, offset)
+        }
+    }
+
+    pub fn count_sql(&self) -> String {
+        count_sql(self.base)
+    }
+
+    /// Runs one page with `run`. When the database rejects the page's
+    /// subquery, as MySQL does for duplicate column names, the query is run
+    /// as-is instead and the result comes back with `false`, to be paged by
+    /// the caller.
+    pub async fn run_page<F, Fut>(
+        &self,
+        limit: i64,
+        offset: i64,
+        mut run: F,
+    ) -> Result<(QueryResult, bool)>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<QueryResult>>,
+    {
+        match run(self.page_sql(limit, offset)).await {
+            Ok(result) => Ok((result, true)),
+            Err(e) if self.wrap => {
+                tracing::debug!("Paging the query in memory, its subquery failed: {}", e);
+                Ok((run(self.base.to_string()).await?, false))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Total rows of the query, or `None` when the database cannot count it
+    /// through a subquery.
+    pub async fn run_count<F, Fut>(&self, run: F) -> Option<i64>
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: Future<Output = Result<QueryResult>>,
+    {
+        match run(self.count_sql()).await {
+            Ok(counted) => counted
+                .rows
+                .first()
+                .and_then(|row| row.first())
+                .and_then(count_value),
+            Err(e) => {
+                tracing::debug!("Leaving the total count out, counting failed: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// Returns the statement as a `PageableQuery` when it is a single plain query
+/// (no locking clause, `SELECT INTO` or data-modifying CTE). Anything that does
+/// not parse with the engine's dialect is left to the caller to page in memory.
+pub fn pageable_query<'a>(db_type: &str, sql: &'a str) -> Option<PageableQuery<'a>> {
+    // Drivers only return rows for statements starting with SELECT or WITH.
+    if !is_select_like(sql) {
+        return None;
+    }
+    let spec = driver_factory::spec_for(db_type).ok()?;
+    let dialect = (spec.parser_dialect?)();
+    let mut statements = Parser::parse_sql(&*dialect, sql).ok()?;
+    if statements.len() != 1 {
+        return None;
+    }
+    let query = match statements.pop() {
+        Some(Statement::Query(query)) => query,
+        _ => return None,
+    };
+    if !is_plain_query(&query) {
+        return None;
+    }
+
+    let has_own_limit = query.limit.is_some() || query.offset.is_some() || query.fetch.is_some();
+    // ClickHouse applies a trailing LIMIT to the last SELECT of a UNION only.
+    let clickhouse_union =
+        spec.name == "clickhouse" && matches!(*query.body, SetExpr::SetOperation { .. });
+
+    Some(PageableQuery {
+        base: sql.trim().trim_end_matches(';').trim_end(),
+        wrap: has_own_limit || clickhouse_union,
+    })
+}
+
+fn is_plain_query(query: &Query) -> bool {
+    let plain_ctes = match &query.with {
+        Some(with) => with.cte_tables.iter().all(|cte| is_plain_query(&cte.query)),
+        None => true,
+    };
+    plain_ctes && query.locks.is_empty() && query.for_clause.is_none() && is_plain_body(&query.body)
+}
+
+fn is_plain_body(body: &SetExpr) -> bool {
+    match body {
+        SetExpr::Select(select) => select.into.is_none(),
+        SetExpr::Query(query) => is_plain_query(query),
+        SetExpr::SetOperation { left, right, .. } => is_plain_body(left) && is_plain_body(right),
+        SetExpr::Table(_) => true,
+        SetExpr::Values(_) | SetExpr::Insert(_) | SetExpr::Update(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    fn rows(rows: Vec<Vec<Value>>) -> QueryResult {
+        QueryResult {
+            columns: vec!["id".to_string(), "id".to_string()],
+            rows,
+            affected_rows: 0,
+            column_metadata: None,
+            total_count: None,
+            limit: None,
+            offset: None,
+            has_more: None,
+            row_metadata: None,
+            execution_time_ms: None,
+            json: None,
+            display_mode: None,
+            truncated: None,
+        }
+    }
+
+    #[test]
+    fn pages_plain_selects_only() {
+        let query = pageable_query("postgres", "SELECT * FROM t ORDER BY id;").unwrap();
+        assert_eq!(
+            query.page_sql(51, 100),
+            "SELECT * FROM t ORDER BY id\nLIMIT 51 OFFSET 100"
+        );
+        assert_eq!(
+            query.count_sql(),
+            "SELECT COUNT(*) AS count FROM (SELECT * FROM t ORDER BY id\n) AS __dbplus_subq"
+        );
+
+        let limited = pageable_query("mysql", "SELECT * FROM t LIMIT 10").unwrap();
+        assert!(limited
+            .page_sql(5, 0)
+            .starts_with("SELECT * FROM (SELECT * FROM t LIMIT 10\n)"));
+
+        assert!(pageable_query("postgres", "UPDATE t SET a = 1").is_none());
+        assert!(pageable_query("postgres", "SELECT 1; SELECT 2").is_none());
+        assert!(pageable_query("postgres", "SELECT * FROM t FOR UPDATE").is_none());
+        assert!(pageable_query("postgres", "SELECT * INTO t2 FROM t").is_none());
+        assert!(pageable_query("postgres", "(SELECT 1)").is_none());
+        assert!(pageable_query("mongodb", "SELECT 1").is_none());
+    }
+
+    #[test]
+    fn clickhouse_unions_are_wrapped() {
+        let query = pageable_query("clickhouse", "SELECT 1 UNION ALL SELECT 2").unwrap();
+        assert!(query.page_sql(10, 0).starts_with("SELECT * FROM ("));
+        let query = pageable_query("postgres", "SELECT 1 UNION ALL SELECT 2").unwrap();
+        assert!(query.page_sql(10, 0).ends_with("\nLIMIT 10 OFFSET 0"));
+    }
+
+    #[tokio::test]
+    async fn falls_back_when_subqueries_have_duplicate_columns() {
+        // MySQL rejects derived tables with two columns of one name (error 1060).
+        let ran = Mutex::new(Vec::new());
+        let mysql = |sql: String| {
+            ran.lock().unwrap().push(sql.clone());
+            async move {
+                if sql.contains("__dbplus_subq") {
+                    Err(anyhow::anyhow!("Duplicate column name 'id'"))
+                } else {
+                    Ok(rows(vec![
+                        vec![json!(1), json!(1)],
+                        vec![json!(2), json!(2)],
+                    ]))
+                }
+            }
+        };
+        let sql = "SELECT * FROM a JOIN b ON b.id = a.id LIMIT 100";
+
+        let limited = pageable_query("mysql", sql).unwrap();
+        let (result, by_database) = limited.run_page(51, 0, mysql).await.unwrap();
+        assert!(!by_database);
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(limited.run_count(mysql).await, None);
+        assert_eq!(
+            ran.lock().unwrap().last().map(String::as_str),
+            Some("SELECT COUNT(*) AS count FROM (SELECT * FROM a JOIN b ON b.id = a.id LIMIT 100\n) AS __dbplus_subq")
+        );
+
+        let unlimited = pageable_query("mysql", "SELECT * FROM a JOIN b ON b.id = a.id").unwrap();
+        let (_, by_database) = unlimited.run_page(51, 0, mysql).await.unwrap();
+        assert!(by_database);
+        assert_eq!(unlimited.run_count(mysql).await, None);
+
+        let failing = |_: String| async { Err::<QueryResult, _>(anyhow::anyhow!("gone")) };
+        assert!(unlimited.run_page(51, 0, failing).await.is_err());
+        assert_eq!(
+            unlimited
+                .run_count(|_| async { Ok::<_, anyhow::Error>(rows(vec![vec![json!("7")]])) })
+                .await,
+            Some(7)
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use crate::services::table_filter::{Placeholder, SqlDialect, SqlFragment};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Values for the placeholders of a query: an array for positional `$1` or
+/// `?` placeholders, an object for named `:name` ones.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum QueryParams {
+    Positional(Vec<Value>),
+    Named(Map<String, Value>),
+}
+
+impl QueryParams {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            QueryParams::Positional(values) => values.is_empty(),
+            QueryParams::Named(values) => values.is_empty(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Slot {
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    /// `$1`, `$2`, ...
+    Numbered(usize),
+    Question,
+    Named(String),
+}
+
+#[derive(Debug)]
+struct Found {
+    start: usize,
+    end: usize,
+    token: Token,
+}
+
+/// Rewrites the placeholders of `sql` to `dialect`'s syntax and lists their
+/// values in bind order. Repeated placeholders reuse one `$n` where the
+/// dialect numbers them and are bound once per use where it doesn't.
+pub fn bind(sql: &str, params: &QueryParams, dialect: SqlDialect) -> Result<SqlFragment> {
+    let mut values = Vec::new();
+    let mut numbers: HashMap<Slot, usize> = HashMap::new();
+    let sql = rewrite(
+        sql,
+        params,
+        dialect,
+        &mut |slot, value| match dialect.placeholder {
+            Placeholder::Numbered => {
+                let next = numbers.len() + 1;
+                let number = *numbers.entry(slot).or_insert_with(|| {
+                    values.push(value.clone());
+                    next
+                });
+                format!("${}", number)
+            }
+            Placeholder::Question => {
+                values.push(value.clone());
+                "?".to_string()
+            }
+        },
+    )?;
+    Ok(SqlFragment {
+        sql,
+        params: values,
+    })
+}
+
+/// Like `bind`, but writes each value into the SQL with `literal`, for clients
+/// that cannot send parameters separately from the statement.
+pub fn inline(
+    sql: &str,
+    params: &QueryParams,
+    dialect: SqlDialect,
+    literal: impl Fn(&Value) -> String,
+) -> Result<String> {
+    rewrite(sql, params, dialect, &mut |_, value| literal(value))
+}
+
+fn rewrite(
+    sql: &str,
+    params: &QueryParams,
+    dialect: SqlDialect,
+    emit: &mut dyn FnMut(Slot, &Value) -> String,
+) -> Result<String> {
+    let found = placeholders(sql, dialect, matches!(params, QueryParams::Named(_)));
+    let mut out = String::with_capacity(sql.len());
+    let mut copied = 0;
+
+    match params {
+        QueryParams::Positional(values) => {
+            let mut used = vec![false; values.len()];
+            let mut next_question = 0;
+            for f in &found {
+                let index = match f.token {
+                    Token::Numbered(number) => number.checked_sub(1),
+                    Token::Question => {
+                        next_question += 1;
+                        Some(next_question - 1)
+                    }
+                    Token::Named(_) => unreachable!("named placeholders are not scanned"),
+                };
+                let value = index
+                    .and_then(|index| values.get(index).map(|value| (index, value)))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("No value for parameter {}", &sql[f.start..f.end])
+                    });
+                let (index, value) = value?;
+                used[index] = true;
+                out.push_str(&sql[copied..f.start]);
+                out.push_str(&emit(Slot::Index(index), value));
+                copied = f.end;
+            }
+            if let Some(unused) = used.iter().position(|used| !used) {
+                return Err(anyhow::anyhow!(
+                    "Parameter {} is not used in the query",
+                    unused + 1
+                ));
+            }
+        }
+        QueryParams::Named(values) => {
+            for f in &found {
+                let Token::Named(name) = &f.token else {
+                    unreachable!("positional placeholders are not scanned");
+                };
+                let value = values
+                    .get(name)
+                    .ok_or_else(|| anyhow::anyhow!("No value for parameter :{}", name))?;
+                out.push_str(&sql[copied..f.start]);
+                out.push_str(&emit(Slot::Name(name.clone()), value));
+                copied = f.end;
+            }
+        }
+    }
+
+    out.push_str(&sql[copied..]);
+    Ok(out)
+}
+
+/// Finds placeholders outside of string literals, quoted identifiers and
+/// comments. `?` only counts in `?` dialects, where `$` can start a name; in
+/// Postgres it is the jsonb key-exists operator. `$n` only counts in
+/// numbered dialects.
+fn placeholders(sql: &str, dialect: SqlDialect, named: bool) -> Vec<Found> {
+    let bytes = sql.as_bytes();
+    let mut found = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let next = bytes.get(i + 1).copied();
+        match bytes[i] {
+            quote @ (b'\'' | b'"' | b'`') => i = skip_quoted(bytes, i, quote, dialect),
+            b'-' if next == Some(b'-') => i = skip_line(bytes, i),
+            b'#' if backslash_escapes(dialect) => i = skip_line(bytes, i),
+            b'/' if next == Some(b'*') => {
+                i = sql[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| i + 2 + end + 2);
+            }
+            // Identifiers such as `a$1` may go on with `$` and digits.
+            b'$' if i > 0 && is_ident_char(bytes[i - 1]) => {
+                i = scan_while(bytes, i + 1, |b| is_ident_char(b) || b == b'$');
+            }
+            b'$' if dialect.placeholder == Placeholder::Numbered
+                && next.is_some_and(|b| b.is_ascii_digit()) =>
+            {
+                let end = scan_while(bytes, i + 1, |b| b.is_ascii_digit());
+                if !named {
+                    found.push(Found {
+                        start: i,
+                        end,
+                        token: Token::Numbered(sql[i + 1..end].parse().unwrap_or(usize::MAX)),
+                    });
+                }
+                i = end;
+            }
+            b'$' if dialect.placeholder == Placeholder::Numbered => {
+                i = skip_dollar_quoted(sql, i);
+            }
+            b'?' if !named && dialect.placeholder == Placeholder::Question => {
+                found.push(Found {
+                    start: i,
+                    end: i + 1,
+                    token: Token::Question,
+                });
+                i += 1;
+            }
+            // `::type` casts and ClickHouse's `{name:Type}` are not placeholders.
+            b':' if next == Some(b':') => i += 2,
+            b':' if named
+                && next.is_some_and(is_ident_start)
+                && !(i > 0 && is_ident_char(bytes[i - 1])) =>
+            {
+                let end = scan_while(bytes, i + 1, is_ident_char);
+                found.push(Found {
+                    start: i,
+                    end,
+                    token: Token::Named(sql[i + 1..end].to_string()),
+                });
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+    found
+}
+
+/// MySQL, ClickHouse and N1QL (the backtick-quoting dialects) also escape
+/// quotes with a backslash and, apart from N1QL, take `#` comments.
+fn backslash_escapes(dialect: SqlDialect) -> bool {
+    dialect.quote == '`'
+}
+
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8, dialect: SqlDialect) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if quote != b'`' && backslash_escapes(dialect) => i += 2,
+            b if b == quote && bytes.get(i + 1) == Some(&quote) => i += 2,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn skip_line(bytes: &[u8], start: usize) -> usize {
+    bytes[start..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(bytes.len(), |end| start + end + 1)
+}
+
+/// Skips a Postgres `$tag$ ... $tag$` string, or just the `$` if none starts here.
+fn skip_dollar_quoted(sql: &str, start: usize) -> usize {
+    let bytes = sql.as_bytes();
+    let tag_end = scan_while(bytes, start + 1, is_ident_char);
+    if bytes.get(tag_end) != Some(&b'$') {
+        return start + 1;
+    }
+    let tag = &sql[start..=tag_end];
+    sql[tag_end + 1..]
+        .find(tag)
+        .map_or(bytes.len(), |end| tag_end + 1 + end + tag.len())
+}
+
+fn scan_while(bytes: &[u8], start: usize, pred: impl Fn(u8) -> bool) -> usize {
+    bytes[start..]
+        .iter()
+        .position(|&b| !pred(b))
+        .map_or(bytes.len(), |end| start + end)
+}
+
+fn is_ident_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_'
+}
+
+fn is_ident_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn params(value: Value) -> QueryParams {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn rewrites_to_numbered_placeholders() {
+        let bound = bind(
+            "SELECT * FROM t WHERE a = :a AND b = :b OR a = :a::int -- :c\n AND s = ':d' AND $x$ :e $x$ = '' AND j ? 'k'",
+            &params(json!({ "a": 1, "b": "two" })),
+            SqlDialect::POSTGRES,
+        )
+        .unwrap();
+        assert_eq!(
+            bound.sql,
+            "SELECT * FROM t WHERE a = $1 AND b = $2 OR a = $1::int -- :c\n AND s = ':d' AND $x$ :e $x$ = '' AND j ? 'k'"
+        );
+        assert_eq!(bound.params, vec![json!(1), json!("two")]);
+
+        let bound = bind(
+            "SELECT $2, $1, $2",
+            &params(json!(["a", "b"])),
+            SqlDialect::POSTGRES,
+        )
+        .unwrap();
+        assert_eq!(bound.sql, "SELECT $1, $2, $1");
+        assert_eq!(bound.params, vec![json!("b"), json!("a")]);
+    }
+
+    #[test]
+    fn rewrites_to_question_placeholders() {
+        let bound = bind(
+            r"SELECT `a?`, 'it\'s ?' FROM t WHERE x = ? AND y = :y AND z = ? # ?",
+            &params(json!([1, 2])),
+            SqlDialect::MYSQL,
+        )
+        .unwrap();
+        assert_eq!(
+            bound.sql,
+            r"SELECT `a?`, 'it\'s ?' FROM t WHERE x = ? AND y = :y AND z = ? # ?"
+        );
+        assert_eq!(bound.params, vec![json!(1), json!(2)]);
+
+        let inlined = inline(
+            "SELECT {id:UInt32}, :name",
+            &params(json!({ "name": "x" })),
+            SqlDialect::CLICKHOUSE,
+            |v| format!("<{}>", v),
+        )
+        .unwrap();
+        assert_eq!(inlined, "SELECT {id:UInt32}, <\"x\">");
+    }
+
+    #[test]
+    fn rejects_missing_unused_and_mixed_parameters() {
+        let mysql = SqlDialect::MYSQL;
+        assert!(bind("SELECT ?, ?", &params(json!([1])), mysql).is_err());
+        assert!(bind("SELECT ?", &params(json!([1, 2])), mysql).is_err());
+        assert!(bind("SELECT :a", &params(json!({ "b": 1 })), mysql).is_err());
+        // Extra named values are allowed; callers pass shared variable sets.
+        assert!(bind("SELECT :a", &params(json!({ "a": 1, "b": 2 })), mysql).is_ok());
+    }
+
+    #[test]
+    fn leaves_dollar_names_alone() {
+        for dialect in [
+            SqlDialect::MYSQL,
+            SqlDialect::CLICKHOUSE,
+            SqlDialect::SQLITE,
+        ] {
+            let bound = bind(
+                "SELECT col$1, $2 FROM t WHERE x = ?",
+                &params(json!([1])),
+                dialect,
+            )
+            .unwrap();
+            assert_eq!(bound.sql, "SELECT col$1, $2 FROM t WHERE x = ?");
+            assert_eq!(bound.params, vec![json!(1)]);
+        }
+
+        let bound = bind(
+            "SELECT a$1, b$1$2 FROM t WHERE x = $1",
+            &params(json!(["v"])),
+            SqlDialect::POSTGRES,
+        )
+        .unwrap();
+        assert_eq!(bound.sql, "SELECT a$1, b$1$2 FROM t WHERE x = $1");
+        assert_eq!(bound.params, vec![json!("v")]);
+    }
+}
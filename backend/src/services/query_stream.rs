@@ -0,0 +1,299 @@
+use crate::services::db_driver::QueryResult;
+use crate::services::query_limits::{QueryLimits, ResultBudget};
+use crate::services::query_paging::wrap_page;
+use serde::Serialize;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+pub const DEFAULT_BATCH_SIZE: usize = 500;
+const MAX_BATCH_SIZE: usize = 10_000;
+/// Rows sent per page when paging is requested without a limit.
+const DEFAULT_PAGE_LIMIT: i64 = 1000;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// One message of a streamed query. Serialized with a `type` tag, both as
+/// NDJSON lines over HTTP and as Tauri channel messages.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QueryStreamEvent {
+    Meta {
+        columns: Vec<String>,
+        total_count: Option<i64>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    },
+    Rows {
+        rows: Vec<Vec<Value>>,
+    },
+    Progress {
+        row_count: u64,
+        elapsed_ms: u64,
+    },
+    Done {
+        row_count: u64,
+        affected_rows: u64,
+        execution_time_ms: u64,
+        /// Rows were left out to stay within the connection's result limits.
+        truncated: bool,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StreamOptions {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub include_total_count: bool,
+}
+
+impl StreamOptions {
+    pub fn is_paged(&self) -> bool {
+        self.limit.is_some() || self.offset.is_some() || self.include_total_count
+    }
+
+    /// Wraps `base` in LIMIT/OFFSET when paging was requested. Returns the SQL
+    /// to run with the effective limit and offset.
+    pub fn paged_sql(&self, base: &str) -> (String, Option<i64>, Option<i64>) {
+        if !self.is_paged() {
+            return (base.to_string(), None, None);
+        }
+        let limit = self.limit.unwrap_or(DEFAULT_PAGE_LIMIT).max(1);
+        let offset = self.offset.unwrap_or(0).max(0);
+        (wrap_page(base, limit, offset), Some(limit), Some(offset))
+    }
+}
+
+pub fn is_select_like(sql: &str) -> bool {
+    let upper = sql.trim_start().to_uppercase();
+    upper.starts_with("SELECT") || upper.starts_with("WITH")
+}
+
+/// Trimmed statement without the trailing `;`, ready to be wrapped in a subquery.
+pub fn base_query(sql: &str) -> &str {
+    sql.trim().trim_end_matches(';')
+}
+
+/// Sends events to one consumer, batching rows. Methods return `false` once the
+/// consumer has gone away or a result limit is reached; drivers should stop
+/// reading rows at that point.
+pub struct QueryStreamSink {
+    tx: mpsc::Sender<QueryStreamEvent>,
+    batch: Vec<Vec<Value>>,
+    batch_size: usize,
+    budget: ResultBudget,
+    truncated: bool,
+    row_count: u64,
+    started: Instant,
+    last_progress: Instant,
+}
+
+impl QueryStreamSink {
+    pub fn new(tx: mpsc::Sender<QueryStreamEvent>, batch_size: Option<usize>) -> Self {
+        let batch_size = batch_size
+            .unwrap_or(DEFAULT_BATCH_SIZE)
+            .clamp(1, MAX_BATCH_SIZE);
+        let now = Instant::now();
+        Self {
+            tx,
+            batch: Vec::with_capacity(batch_size),
+            batch_size,
+            budget: ResultBudget::default(),
+            truncated: false,
+            row_count: 0,
+            started: now,
+            last_progress: now,
+        }
+    }
+
+    /// A sink plus the receiving end. The channel is bounded, so a slow consumer
+    /// slows down reading from the database instead of buffering the result.
+    pub fn channel(batch_size: Option<usize>) -> (Self, mpsc::Receiver<QueryStreamEvent>) {
+        let (tx, rx) = mpsc::channel(16);
+        (Self::new(tx, batch_size), rx)
+    }
+
+    pub fn row_count(&self) -> u64 {
+        self.row_count
+    }
+
+    /// Ends the rows once `limits.max_rows` or `limits.max_bytes` is reached.
+    pub fn set_limits(&mut self, limits: &QueryLimits) {
+        self.budget = limits.budget();
+    }
+
+    pub async fn meta(
+        &mut self,
+        columns: Vec<String>,
+        total_count: Option<i64>,
+        limit: Option<i64>,
+        offset: Option<i64>,
+    ) -> bool {
+        self.send(QueryStreamEvent::Meta {
+            columns,
+            total_count,
+            limit,
+            offset,
+        })
+        .await
+    }
+
+    pub async fn row(&mut self, row: Vec<Value>) -> bool {
+        if !self.budget.admit(&row) {
+            self.truncated = true;
+            return false;
+        }
+        self.batch.push(row);
+        self.row_count += 1;
+        if self.batch.len() >= self.batch_size {
+            return self.flush().await;
+        }
+        true
+    }
+
+    /// Flushes buffered rows and ends the stream.
+    pub async fn done(mut self, affected_rows: u64) -> bool {
+        if !self.flush().await {
+            return false;
+        }
+        let event = QueryStreamEvent::Done {
+            row_count: self.row_count,
+            affected_rows,
+            execution_time_ms: self.started.elapsed().as_millis() as u64,
+            truncated: self.truncated,
+        };
+        self.send(event).await
+    }
+
+    /// Flushes the rows read so far, then reports `message`.
+    pub async fn error(mut self, message: String) {
+        if self.flush().await {
+            self.send(QueryStreamEvent::Error { message }).await;
+        }
+    }
+
+    /// Sends the columns and rows of an already buffered result, for engines
+    /// without a row cursor.
+    pub async fn send_result(&mut self, result: QueryResult) -> bool {
+        if !result.columns.is_empty()
+            && !self
+                .meta(
+                    result.columns,
+                    result.total_count,
+                    result.limit,
+                    result.offset,
+                )
+                .await
+        {
+            return false;
+        }
+        for row in result.rows {
+            if !self.row(row).await {
+                return false;
+            }
+        }
+        true
+    }
+
+    async fn flush(&mut self) -> bool {
+        if self.batch.is_empty() {
+            return true;
+        }
+        let rows = std::mem::replace(&mut self.batch, Vec::with_capacity(self.batch_size));
+        if !self.send(QueryStreamEvent::Rows { rows }).await {
+            return false;
+        }
+        if self.last_progress.elapsed() >= PROGRESS_INTERVAL {
+            self.last_progress = Instant::now();
+            let event = QueryStreamEvent::Progress {
+                row_count: self.row_count,
+                elapsed_ms: self.started.elapsed().as_millis() as u64,
+            };
+            return self.send(event).await;
+        }
+        true
+    }
+
+    async fn send(&self, event: QueryStreamEvent) -> bool {
+        self.tx.send(event).await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rows_are_sent_in_batches() {
+        let (mut sink, mut rx) = QueryStreamSink::channel(Some(2));
+        let consumer = tokio::spawn(async move {
+            let mut events = Vec::new();
+            while let Some(event) = rx.recv().await {
+                events.push(event);
+            }
+            events
+        });
+
+        assert!(sink.meta(vec!["id".into()], None, None, None).await);
+        for i in 0..5 {
+            assert!(sink.row(vec![Value::from(i)]).await);
+        }
+        assert!(sink.done(0).await);
+
+        let batches: Vec<usize> = consumer
+            .await
+            .unwrap()
+            .into_iter()
+            .filter_map(|event| match event {
+                QueryStreamEvent::Rows { rows } => Some(rows.len()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(batches, vec![2, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn rows_stop_at_the_result_limit() {
+        let (mut sink, mut rx) = QueryStreamSink::channel(None);
+        sink.set_limits(&QueryLimits {
+            max_rows: Some(2),
+            ..Default::default()
+        });
+        assert!(sink.row(vec![Value::from(1)]).await);
+        assert!(sink.row(vec![Value::from(2)]).await);
+        assert!(!sink.row(vec![Value::from(3)]).await);
+        assert!(sink.done(0).await);
+
+        assert!(
+            matches!(rx.recv().await, Some(QueryStreamEvent::Rows { rows }) if rows.len() == 2)
+        );
+        assert!(matches!(
+            rx.recv().await,
+            Some(QueryStreamEvent::Done {
+                row_count: 2,
+                truncated: true,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn paging_wraps_the_base_query() {
+        let options = StreamOptions {
+            limit: Some(50),
+            offset: Some(100),
+            ..Default::default()
+        };
+        let (sql, limit, offset) = options.paged_sql(base_query("SELECT * FROM t;"));
+        assert_eq!(
+            sql,
+            "SELECT * FROM (SELECT * FROM t\n) AS __dbplus_subq LIMIT 50 OFFSET 100"
+        );
+        assert_eq!((limit, offset), (Some(50), Some(100)));
+
+        let (sql, limit, _) = StreamOptions::default().paged_sql("SELECT 1");
+        assert_eq!((sql.as_str(), limit), ("SELECT 1", None));
+    }
+}
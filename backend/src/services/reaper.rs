@@ -0,0 +1,29 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Runs `reap` on `owner` every `interval` from a background task, which
+/// ends once `owner` is dropped. Only the first call per `started` flag
+/// spawns the task.
+pub fn spawn_reaper<T, F, Fut>(started: &AtomicBool, owner: &Arc<T>, interval: Duration, reap: F)
+where
+    T: Send + Sync + 'static,
+    F: Fn(Arc<T>) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    if started.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let owner = Arc::downgrade(owner);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+            let Some(owner) = owner.upgrade() else {
+                break;
+            };
+            reap(owner).await;
+        }
+    });
+}
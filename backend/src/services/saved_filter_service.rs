@@ -1,4 +1,5 @@
 use crate::models::entities::saved_filter;
+use crate::services::table_filter::{FilterNode, SortSpec};
 use chrono::Utc;
 use sea_orm::*;
 use uuid::Uuid;
@@ -18,15 +19,37 @@ impl SavedFilterService {
         schema: String,
         table_ref: String,
         name: String,
-        filter: String,
+        filter: Option<FilterNode>,
+        sort: Vec<SortSpec>,
     ) -> Result<saved_filter::Model, DbErr> {
+        let description = match &filter {
+            Some(filter) => filter
+                .describe()
+                .map_err(|e| DbErr::Custom(e.to_string()))?,
+            None => String::new(),
+        };
+        let to_json = |value: serde_json::Result<serde_json::Value>| {
+            value.map_err(|e| DbErr::Custom(e.to_string()))
+        };
+        let filter_tree = match &filter {
+            Some(filter) => Some(to_json(serde_json::to_value(filter))?),
+            None => None,
+        };
+        let sort = if sort.is_empty() {
+            None
+        } else {
+            Some(to_json(serde_json::to_value(&sort))?)
+        };
+
         let saved_filter = saved_filter::ActiveModel {
             id: Set(Uuid::new_v4()),
             connection_id: Set(connection_id),
             schema: Set(schema),
             table_ref: Set(table_ref),
             name: Set(name),
-            filter: Set(filter),
+            filter: Set(description),
+            filter_tree: Set(filter_tree),
+            sort: Set(sort),
             created_at: Set(Utc::now().into()),
         };
 
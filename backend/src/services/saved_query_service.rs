@@ -1,6 +1,9 @@
 use crate::models::entities::saved_query;
+use crate::services::sql_template::{self, RenderMode};
+use crate::services::table_filter::{SqlDialect, SqlFragment};
 use chrono::Utc;
 use sea_orm::*;
+use serde_json::{Map, Value};
 use uuid::Uuid;
 
 pub struct SavedQueryService {
@@ -49,6 +52,32 @@ impl SavedQueryService {
             .await
     }
 
+    pub async fn get_saved_query(&self, id: Uuid) -> Result<saved_query::Model, DbErr> {
+        saved_query::Entity::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .ok_or(DbErr::RecordNotFound("Saved query not found".to_owned()))
+    }
+
+    /// The saved query's SQL with its `{{var}}` placeholders filled from
+    /// `values`, checked against the declarations in `metadata.variables`.
+    pub async fn render_saved_query(
+        &self,
+        id: Uuid,
+        values: &Map<String, Value>,
+        dialect: SqlDialect,
+        mode: RenderMode,
+    ) -> anyhow::Result<SqlFragment> {
+        let query = self.get_saved_query(id).await?;
+        let declared = sql_template::parse_variables(
+            query
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get("variables")),
+        )?;
+        sql_template::render(&query.sql, &declared, values, dialect, mode)
+    }
+
     pub async fn update_saved_query(
         &self,
         id: Uuid,
@@ -1,4 +1,7 @@
 use super::extractor::*;
+use super::renames::{
+    apply_renames, detect_column_renames, detect_table_renames, DetectedRename, RenameDecisions,
+};
 use serde::{Deserialize, Serialize};
 
 /// Represents all differences between two schemas
@@ -23,6 +26,22 @@ pub struct DiffStats {
     pub indexes_dropped: usize,
     pub foreign_keys_added: usize,
     pub foreign_keys_dropped: usize,
+    /// Check and unique constraints
+    #[serde(default)]
+    pub constraints_added: usize,
+    #[serde(default)]
+    pub constraints_dropped: usize,
+    /// Views, routines, triggers, sequences and types
+    #[serde(default)]
+    pub objects_added: usize,
+    #[serde(default)]
+    pub objects_dropped: usize,
+    #[serde(default)]
+    pub objects_modified: usize,
+    #[serde(default)]
+    pub tables_renamed: usize,
+    #[serde(default)]
+    pub columns_renamed: usize,
 }
 
 /// Types of schema differences
@@ -30,15 +49,133 @@ pub struct DiffStats {
 #[serde(tag = "type")]
 pub enum SchemaDiff {
     TableAdded {
-        table: TableDefinition,
+        #[serde(flatten)]
+        objects: TableObjects,
     },
     TableDropped {
         table_name: String,
     },
+    /// Comes before any diff of the table, which uses its new name
+    TableRenamed {
+        old_name: String,
+        new_name: String,
+        /// Found by the heuristics rather than accepted by the user
+        #[serde(default)]
+        suspected: bool,
+    },
     TableModified {
         table_name: String,
         changes: Vec<TableChange>,
+        /// The table as it is in the target, for databases that rebuild a
+        /// table rather than alter it
+        #[serde(default)]
+        target: Option<TableObjects>,
+    },
+    ViewAdded {
+        view: ViewDefinition,
+    },
+    ViewDropped {
+        view: ViewDefinition,
+    },
+    ViewModified {
+        old: ViewDefinition,
+        new: ViewDefinition,
+    },
+    /// An unchanged view that reads from a changed column or view, so it is
+    /// dropped before that change and created again after it
+    ViewRecreated {
+        view: ViewDefinition,
+    },
+    RoutineAdded {
+        routine: RoutineDefinition,
+    },
+    RoutineDropped {
+        routine: RoutineDefinition,
+    },
+    RoutineModified {
+        old: RoutineDefinition,
+        new: RoutineDefinition,
+    },
+    TriggerAdded {
+        trigger: TriggerDefinition,
+    },
+    TriggerDropped {
+        trigger: TriggerDefinition,
+    },
+    TriggerModified {
+        old: TriggerDefinition,
+        new: TriggerDefinition,
+    },
+    SequenceAdded {
+        sequence: SequenceDefinition,
+    },
+    SequenceDropped {
+        sequence: SequenceDefinition,
+    },
+    SequenceModified {
+        old: SequenceDefinition,
+        new: SequenceDefinition,
     },
+    EnumTypeAdded {
+        enum_type: EnumTypeDefinition,
+    },
+    EnumTypeDropped {
+        enum_type: EnumTypeDefinition,
+    },
+    EnumTypeModified {
+        old: EnumTypeDefinition,
+        new: EnumTypeDefinition,
+    },
+    DomainAdded {
+        domain: DomainDefinition,
+    },
+    DomainDropped {
+        domain: DomainDefinition,
+    },
+    DomainModified {
+        old: DomainDefinition,
+        new: DomainDefinition,
+    },
+}
+
+/// A table with the objects that belong to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableObjects {
+    pub table: TableDefinition,
+    pub indexes: Vec<IndexDefinition>,
+    pub foreign_keys: Vec<ForeignKeyDefinition>,
+    /// For a modified table, only the triggers that stay as they are; the
+    /// others come as trigger diffs
+    pub triggers: Vec<TriggerDefinition>,
+    #[serde(default)]
+    pub check_constraints: Vec<CheckConstraintDefinition>,
+    #[serde(default)]
+    pub unique_constraints: Vec<UniqueConstraintDefinition>,
+    /// For a modified table, the unchanged views reading it directly or
+    /// through other views, in the order they can be created; views that
+    /// change come as view diffs
+    #[serde(default)]
+    pub views: Vec<ViewDefinition>,
+}
+
+impl TableObjects {
+    fn from_snapshot(snapshot: &SchemaSnapshot, table: &TableDefinition) -> Self {
+        Self {
+            table: table.clone(),
+            indexes: snapshot.table_indexes(&table.name).cloned().collect(),
+            foreign_keys: snapshot.table_foreign_keys(&table.name).cloned().collect(),
+            triggers: snapshot.table_triggers(&table.name).cloned().collect(),
+            check_constraints: snapshot
+                .table_check_constraints(&table.name)
+                .cloned()
+                .collect(),
+            unique_constraints: snapshot
+                .table_unique_constraints(&table.name)
+                .cloned()
+                .collect(),
+            views: Vec::new(),
+        }
+    }
 }
 
 /// Types of table changes
@@ -51,6 +188,14 @@ pub enum TableChange {
     ColumnDropped {
         column_name: String,
     },
+    /// Comes before any other change of the column, which uses its new name
+    ColumnRenamed {
+        old_name: String,
+        new_name: String,
+        /// Found by the heuristics rather than accepted by the user
+        #[serde(default)]
+        suspected: bool,
+    },
     ColumnModified {
         column_name: String,
         old: ColumnDefinition,
@@ -79,6 +224,18 @@ pub enum TableChange {
     ForeignKeyDropped {
         constraint_name: String,
     },
+    CheckConstraintAdded {
+        constraint: CheckConstraintDefinition,
+    },
+    CheckConstraintDropped {
+        constraint_name: String,
+    },
+    UniqueConstraintAdded {
+        constraint: UniqueConstraintDefinition,
+    },
+    UniqueConstraintDropped {
+        constraint_name: String,
+    },
 }
 
 /// Specific column changes
@@ -100,6 +257,10 @@ pub enum ColumnChange {
         old: bool,
         new: bool,
     },
+    CommentChanged {
+        old: Option<String>,
+        new: Option<String>,
+    },
 }
 
 /// Schema differ
@@ -108,11 +269,67 @@ pub struct SchemaDiffer;
 impl SchemaDiffer {
     /// Compare two schema snapshots
     pub fn compare(source: &SchemaSnapshot, target: &SchemaSnapshot) -> SchemaDiffResult {
+        Self::compare_with_renames(source, target, &RenameDecisions::default())
+    }
+
+    /// Compare two schema snapshots, with suspected renames accepted or
+    /// rejected by the user.
+    ///
+    /// Tables and columns that look renamed come as `TableRenamed` and
+    /// `ColumnRenamed` rather than a drop and an add, flagged `suspected`
+    /// until accepted. The rest of the comparison goes by their new names.
+    pub fn compare_with_renames(
+        source: &SchemaSnapshot,
+        target: &SchemaSnapshot,
+        decisions: &RenameDecisions,
+    ) -> SchemaDiffResult {
+        Self::compare_renaming(source, target, decisions, true)
+    }
+
+    /// Compare two schema snapshots for a migration: only accepted renames
+    /// are renamed, and tables and columns that merely look renamed come as a
+    /// drop and an add until the user accepts them.
+    pub fn compare_for_migration(
+        source: &SchemaSnapshot,
+        target: &SchemaSnapshot,
+        decisions: &RenameDecisions,
+    ) -> SchemaDiffResult {
+        Self::compare_renaming(source, target, decisions, false)
+    }
+
+    fn compare_renaming(
+        source: &SchemaSnapshot,
+        target: &SchemaSnapshot,
+        decisions: &RenameDecisions,
+        suspect: bool,
+    ) -> SchemaDiffResult {
         let mut diffs = Vec::new();
         let mut stats = DiffStats::default();
 
+        let mut renames = detect_table_renames(source, target, decisions, suspect);
+        let renamed = apply_renames(source, renames.iter().map(|r| &r.rename));
+        let column_renames: Vec<DetectedRename> = target
+            .tables
+            .iter()
+            .filter_map(|table| Some((renamed.find_table(&table.name)?, table)))
+            .flat_map(|(old, new)| detect_column_renames(old, new, decisions, suspect))
+            .collect();
+        let source = &apply_renames(&renamed, column_renames.iter().map(|r| &r.rename));
+        renames.extend(column_renames);
+
+        // Compare types and sequences, which tables use
+        Self::compare_enum_types(source, target, &mut diffs, &mut stats);
+        Self::compare_domains(source, target, &mut diffs, &mut stats);
+        Self::compare_sequences(source, target, &mut diffs, &mut stats);
+
         // Compare tables
-        Self::compare_tables(source, target, &mut diffs, &mut stats);
+        Self::compare_tables(source, target, &renames, &mut diffs, &mut stats);
+
+        // Compare routines, views and triggers, which use tables
+        Self::compare_routines(source, target, &mut diffs, &mut stats);
+        Self::compare_views(source, target, &mut diffs, &mut stats);
+        Self::compare_triggers(source, target, &mut diffs, &mut stats);
+        Self::attach_dependent_views(source, target, &mut diffs);
 
         SchemaDiffResult {
             source_schema: source.schema_name.clone(),
@@ -125,15 +342,30 @@ impl SchemaDiffer {
     fn compare_tables(
         source: &SchemaSnapshot,
         target: &SchemaSnapshot,
+        renames: &[DetectedRename],
         diffs: &mut Vec<SchemaDiff>,
         stats: &mut DiffStats,
     ) {
+        // Renamed tables, which `source` already has under their new names
+        for detected in renames.iter().filter(|r| r.rename.table_name.is_none()) {
+            diffs.push(SchemaDiff::TableRenamed {
+                old_name: detected.rename.old_name.clone(),
+                new_name: detected.rename.new_name.clone(),
+                suspected: detected.suspected,
+            });
+            stats.tables_renamed += 1;
+        }
+
         // Find added tables (in target but not in source)
         for table in &target.tables {
             if source.find_table(&table.name).is_none() {
-                diffs.push(SchemaDiff::TableAdded {
-                    table: table.clone(),
-                });
+                let objects = TableObjects::from_snapshot(target, table);
+                stats.indexes_added += objects.indexes.len();
+                stats.foreign_keys_added += constraint_names(objects.foreign_keys.iter()).len();
+                stats.constraints_added +=
+                    objects.check_constraints.len() + objects.unique_constraints.len();
+                stats.objects_added += objects.triggers.len();
+                diffs.push(SchemaDiff::TableAdded { objects });
                 stats.tables_added += 1;
             }
         }
@@ -151,11 +383,33 @@ impl SchemaDiffer {
         // Find modified tables (in both)
         for source_table in &source.tables {
             if let Some(target_table) = target.find_table(&source_table.name) {
-                let changes = Self::compare_table_structure(source_table, target_table, stats);
+                let mut changes: Vec<TableChange> = renames
+                    .iter()
+                    .filter(|r| r.rename.table_name.as_ref() == Some(&source_table.name))
+                    .map(|detected| TableChange::ColumnRenamed {
+                        old_name: detected.rename.old_name.clone(),
+                        new_name: detected.rename.new_name.clone(),
+                        suspected: detected.suspected,
+                    })
+                    .collect();
+                stats.columns_renamed += changes.len();
+                changes.extend(Self::compare_table_structure(
+                    source_table,
+                    target_table,
+                    stats,
+                ));
+                Self::compare_indexes(source, target, &source_table.name, &mut changes, stats);
+                Self::compare_foreign_keys(source, target, &source_table.name, &mut changes, stats);
+                Self::compare_constraints(source, target, &source_table.name, &mut changes, stats);
                 if !changes.is_empty() {
+                    let mut objects = TableObjects::from_snapshot(target, target_table);
+                    objects
+                        .triggers
+                        .retain(|t| source.table_triggers(&t.table_name).any(|s| s == t));
                     diffs.push(SchemaDiff::TableModified {
                         table_name: source_table.name.clone(),
                         changes,
+                        target: Some(objects),
                     });
                     stats.tables_modified += 1;
                 }
@@ -255,6 +509,13 @@ impl SchemaDiffer {
             });
         }
 
+        if old.comment != new.comment {
+            changes.push(ColumnChange::CommentChanged {
+                old: old.comment.clone(),
+                new: new.comment.clone(),
+            });
+        }
+
         changes
     }
 
@@ -283,6 +544,500 @@ impl SchemaDiffer {
             _ => {}
         }
     }
+
+    /// A changed index is dropped and created again.
+    fn compare_indexes(
+        source: &SchemaSnapshot,
+        target: &SchemaSnapshot,
+        table_name: &str,
+        changes: &mut Vec<TableChange>,
+        stats: &mut DiffStats,
+    ) {
+        for old in source.table_indexes(table_name) {
+            let new = target
+                .table_indexes(table_name)
+                .find(|i| i.name == old.name);
+            if new.is_none_or(|new| new != old) {
+                changes.push(TableChange::IndexDropped {
+                    index_name: old.name.clone(),
+                });
+                stats.indexes_dropped += 1;
+            }
+        }
+
+        for new in target.table_indexes(table_name) {
+            let old = source
+                .table_indexes(table_name)
+                .find(|i| i.name == new.name);
+            if old.is_none_or(|old| old != new) {
+                changes.push(TableChange::IndexAdded { index: new.clone() });
+                stats.indexes_added += 1;
+            }
+        }
+    }
+
+    /// Foreign keys are compared by constraint, over all of their columns. A
+    /// changed constraint is dropped and added again.
+    fn compare_foreign_keys(
+        source: &SchemaSnapshot,
+        target: &SchemaSnapshot,
+        table_name: &str,
+        changes: &mut Vec<TableChange>,
+        stats: &mut DiffStats,
+    ) {
+        let constraint = |snapshot: &SchemaSnapshot, name: &str| -> Vec<ForeignKeyDefinition> {
+            snapshot
+                .table_foreign_keys(table_name)
+                .filter(|fk| fk.constraint_name == name)
+                .cloned()
+                .collect()
+        };
+
+        for name in constraint_names(source.table_foreign_keys(table_name)) {
+            if constraint(source, &name) != constraint(target, &name) {
+                changes.push(TableChange::ForeignKeyDropped {
+                    constraint_name: name,
+                });
+                stats.foreign_keys_dropped += 1;
+            }
+        }
+
+        for name in constraint_names(target.table_foreign_keys(table_name)) {
+            let new = constraint(target, &name);
+            if constraint(source, &name) != new {
+                changes.extend(
+                    new.into_iter()
+                        .map(|foreign_key| TableChange::ForeignKeyAdded { foreign_key }),
+                );
+                stats.foreign_keys_added += 1;
+            }
+        }
+    }
+
+    /// A changed constraint is dropped and added again.
+    fn compare_constraints(
+        source: &SchemaSnapshot,
+        target: &SchemaSnapshot,
+        table_name: &str,
+        changes: &mut Vec<TableChange>,
+        stats: &mut DiffStats,
+    ) {
+        let checks = ObjectChanges::between(
+            source.table_check_constraints(table_name),
+            target.table_check_constraints(table_name),
+            |c| c.name.clone(),
+        );
+        for constraint in checks.dropped_or_modified() {
+            changes.push(TableChange::CheckConstraintDropped {
+                constraint_name: constraint.name.clone(),
+            });
+            stats.constraints_dropped += 1;
+        }
+        for constraint in checks.added_or_modified() {
+            changes.push(TableChange::CheckConstraintAdded {
+                constraint: constraint.clone(),
+            });
+            stats.constraints_added += 1;
+        }
+
+        let uniques = ObjectChanges::between(
+            source.table_unique_constraints(table_name),
+            target.table_unique_constraints(table_name),
+            |c| c.name.clone(),
+        );
+        for constraint in uniques.dropped_or_modified() {
+            changes.push(TableChange::UniqueConstraintDropped {
+                constraint_name: constraint.name.clone(),
+            });
+            stats.constraints_dropped += 1;
+        }
+        for constraint in uniques.added_or_modified() {
+            changes.push(TableChange::UniqueConstraintAdded {
+                constraint: constraint.clone(),
+            });
+            stats.constraints_added += 1;
+        }
+    }
+
+    fn compare_enum_types(
+        source: &SchemaSnapshot,
+        target: &SchemaSnapshot,
+        diffs: &mut Vec<SchemaDiff>,
+        stats: &mut DiffStats,
+    ) {
+        let changes =
+            ObjectChanges::between(&source.enum_types, &target.enum_types, |t| t.name.clone());
+        for enum_type in changes.added {
+            diffs.push(SchemaDiff::EnumTypeAdded {
+                enum_type: enum_type.clone(),
+            });
+            stats.objects_added += 1;
+        }
+        for (old, new) in changes.modified {
+            diffs.push(SchemaDiff::EnumTypeModified {
+                old: old.clone(),
+                new: new.clone(),
+            });
+            stats.objects_modified += 1;
+        }
+        for enum_type in changes.dropped {
+            diffs.push(SchemaDiff::EnumTypeDropped {
+                enum_type: enum_type.clone(),
+            });
+            stats.objects_dropped += 1;
+        }
+    }
+
+    fn compare_domains(
+        source: &SchemaSnapshot,
+        target: &SchemaSnapshot,
+        diffs: &mut Vec<SchemaDiff>,
+        stats: &mut DiffStats,
+    ) {
+        let changes = ObjectChanges::between(&source.domains, &target.domains, |d| d.name.clone());
+        for domain in changes.added {
+            diffs.push(SchemaDiff::DomainAdded {
+                domain: domain.clone(),
+            });
+            stats.objects_added += 1;
+        }
+        for (old, new) in changes.modified {
+            diffs.push(SchemaDiff::DomainModified {
+                old: old.clone(),
+                new: new.clone(),
+            });
+            stats.objects_modified += 1;
+        }
+        for domain in changes.dropped {
+            diffs.push(SchemaDiff::DomainDropped {
+                domain: domain.clone(),
+            });
+            stats.objects_dropped += 1;
+        }
+    }
+
+    /// A sequence owned by a dropped table goes away with it.
+    fn compare_sequences(
+        source: &SchemaSnapshot,
+        target: &SchemaSnapshot,
+        diffs: &mut Vec<SchemaDiff>,
+        stats: &mut DiffStats,
+    ) {
+        let changes =
+            ObjectChanges::between(&source.sequences, &target.sequences, |s| s.name.clone());
+        for sequence in changes.added {
+            diffs.push(SchemaDiff::SequenceAdded {
+                sequence: sequence.clone(),
+            });
+            stats.objects_added += 1;
+        }
+        for (old, new) in changes.modified {
+            diffs.push(SchemaDiff::SequenceModified {
+                old: old.clone(),
+                new: new.clone(),
+            });
+            stats.objects_modified += 1;
+        }
+        for sequence in changes.dropped {
+            let owner_dropped = sequence
+                .owned_by
+                .as_ref()
+                .is_some_and(|table| target.find_table(table).is_none());
+            if !owner_dropped {
+                diffs.push(SchemaDiff::SequenceDropped {
+                    sequence: sequence.clone(),
+                });
+                stats.objects_dropped += 1;
+            }
+        }
+    }
+
+    fn compare_routines(
+        source: &SchemaSnapshot,
+        target: &SchemaSnapshot,
+        diffs: &mut Vec<SchemaDiff>,
+        stats: &mut DiffStats,
+    ) {
+        let changes = ObjectChanges::between(&source.routines, &target.routines, |r| {
+            format!("{}({})", r.name, r.arguments)
+        });
+        for routine in changes.added {
+            diffs.push(SchemaDiff::RoutineAdded {
+                routine: routine.clone(),
+            });
+            stats.objects_added += 1;
+        }
+        for (old, new) in changes.modified {
+            diffs.push(SchemaDiff::RoutineModified {
+                old: old.clone(),
+                new: new.clone(),
+            });
+            stats.objects_modified += 1;
+        }
+        for routine in changes.dropped {
+            diffs.push(SchemaDiff::RoutineDropped {
+                routine: routine.clone(),
+            });
+            stats.objects_dropped += 1;
+        }
+    }
+
+    /// Views come after the table diffs, in the order they can be created,
+    /// so that views which read from columns changing type or going away,
+    /// or from views being replaced, are recreated around those changes.
+    fn compare_views(
+        source: &SchemaSnapshot,
+        target: &SchemaSnapshot,
+        diffs: &mut Vec<SchemaDiff>,
+        stats: &mut DiffStats,
+    ) {
+        let changes = ObjectChanges::between(&source.views, &target.views, |v| v.name.clone());
+
+        // Relations whose change needs the views reading them out of the way
+        let mut replaced: Vec<String> = diffs
+            .iter()
+            .filter_map(|diff| match diff {
+                SchemaDiff::TableModified {
+                    table_name,
+                    changes,
+                    ..
+                } if changes.iter().any(drops_dependent_views) => Some(table_name.clone()),
+                _ => None,
+            })
+            .collect();
+        replaced.extend(changes.modified.iter().map(|(old, _)| old.name.clone()));
+        replaced.extend(changes.dropped.iter().map(|view| view.name.clone()));
+
+        // Unchanged views reading from any of them, and in turn their readers
+        let mut recreated: Vec<&ViewDefinition> = Vec::new();
+        loop {
+            let next: Vec<&ViewDefinition> = source
+                .views
+                .iter()
+                .filter(|view| target.views.contains(view) && !recreated.contains(view))
+                .filter(|view| view.depends_on.iter().any(|d| replaced.contains(d)))
+                .collect();
+            if next.is_empty() {
+                break;
+            }
+            replaced.extend(next.iter().map(|view| view.name.clone()));
+            recreated.extend(next);
+        }
+
+        for view in view_order(&source.views) {
+            if changes.dropped.contains(&view) {
+                diffs.push(SchemaDiff::ViewDropped { view: view.clone() });
+                stats.objects_dropped += 1;
+            }
+        }
+        for view in view_order(&target.views) {
+            if changes.added.contains(&view) {
+                diffs.push(SchemaDiff::ViewAdded { view: view.clone() });
+                stats.objects_added += 1;
+            } else if let Some((old, _)) = changes.modified.iter().find(|(_, new)| *new == view) {
+                diffs.push(SchemaDiff::ViewModified {
+                    old: (*old).clone(),
+                    new: view.clone(),
+                });
+                stats.objects_modified += 1;
+            } else if recreated.contains(&view) {
+                diffs.push(SchemaDiff::ViewRecreated { view: view.clone() });
+            }
+        }
+    }
+
+    /// Gives each modified table the views a rebuild of it has to drop and
+    /// create again, leaving out those a view diff already takes care of.
+    fn attach_dependent_views(
+        source: &SchemaSnapshot,
+        target: &SchemaSnapshot,
+        diffs: &mut [SchemaDiff],
+    ) {
+        let handled: Vec<String> = diffs
+            .iter()
+            .filter_map(|diff| match diff {
+                SchemaDiff::ViewAdded { view }
+                | SchemaDiff::ViewDropped { view }
+                | SchemaDiff::ViewRecreated { view }
+                | SchemaDiff::ViewModified { new: view, .. } => Some(view.name.clone()),
+                _ => None,
+            })
+            .collect();
+        for diff in diffs.iter_mut() {
+            let SchemaDiff::TableModified {
+                target: Some(objects),
+                ..
+            } = diff
+            else {
+                continue;
+            };
+            let mut read = vec![objects.table.name.clone()];
+            loop {
+                let next: Vec<String> = target
+                    .views
+                    .iter()
+                    .filter(|view| !read.contains(&view.name))
+                    .filter(|view| view.depends_on.iter().any(|d| read.contains(d)))
+                    .map(|view| view.name.clone())
+                    .collect();
+                if next.is_empty() {
+                    break;
+                }
+                read.extend(next);
+            }
+            objects.views = view_order(&target.views)
+                .into_iter()
+                .filter(|view| read.contains(&view.name) && !handled.contains(&view.name))
+                .filter(|view| source.views.contains(view))
+                .cloned()
+                .collect();
+        }
+    }
+
+    /// Triggers of added and dropped tables come and go with their table.
+    fn compare_triggers(
+        source: &SchemaSnapshot,
+        target: &SchemaSnapshot,
+        diffs: &mut Vec<SchemaDiff>,
+        stats: &mut DiffStats,
+    ) {
+        let on_kept_relation = |trigger: &&TriggerDefinition| {
+            source.find_table(&trigger.table_name).is_some()
+                == target.find_table(&trigger.table_name).is_some()
+        };
+        let changes = ObjectChanges::between(
+            source.triggers.iter().filter(on_kept_relation),
+            target.triggers.iter().filter(on_kept_relation),
+            |t| format!("{}.{}", t.table_name, t.name),
+        );
+        for trigger in changes.added {
+            diffs.push(SchemaDiff::TriggerAdded {
+                trigger: trigger.clone(),
+            });
+            stats.objects_added += 1;
+        }
+        for (old, new) in changes.modified {
+            diffs.push(SchemaDiff::TriggerModified {
+                old: old.clone(),
+                new: new.clone(),
+            });
+            stats.objects_modified += 1;
+        }
+        for trigger in changes.dropped {
+            diffs.push(SchemaDiff::TriggerDropped {
+                trigger: trigger.clone(),
+            });
+            stats.objects_dropped += 1;
+        }
+    }
+}
+
+/// Objects only in the target, only in the source, and in both but different,
+/// matched by a key
+struct ObjectChanges<'a, T> {
+    added: Vec<&'a T>,
+    dropped: Vec<&'a T>,
+    modified: Vec<(&'a T, &'a T)>,
+}
+
+impl<'a, T: PartialEq> ObjectChanges<'a, T> {
+    fn between(
+        source: impl IntoIterator<Item = &'a T>,
+        target: impl IntoIterator<Item = &'a T>,
+        key: impl Fn(&T) -> String,
+    ) -> Self {
+        let source: Vec<&T> = source.into_iter().collect();
+        let target: Vec<&T> = target.into_iter().collect();
+        let find = |objects: &[&'a T], wanted: &T| {
+            objects
+                .iter()
+                .copied()
+                .find(|object| key(object) == key(wanted))
+        };
+
+        let mut changes = Self {
+            added: Vec::new(),
+            dropped: Vec::new(),
+            modified: Vec::new(),
+        };
+        for &new in &target {
+            match find(&source, new) {
+                None => changes.added.push(new),
+                Some(old) if old != new => changes.modified.push((old, new)),
+                Some(_) => {}
+            }
+        }
+        for &old in &source {
+            if find(&target, old).is_none() {
+                changes.dropped.push(old);
+            }
+        }
+        changes
+    }
+
+    /// Objects to drop, with modified ones dropped to be added again
+    fn dropped_or_modified(&self) -> impl Iterator<Item = &'a T> + '_ {
+        self.dropped
+            .iter()
+            .copied()
+            .chain(self.modified.iter().map(|(old, _)| *old))
+    }
+
+    /// Objects to add, with modified ones added again after their drop
+    fn added_or_modified(&self) -> impl Iterator<Item = &'a T> + '_ {
+        self.added
+            .iter()
+            .copied()
+            .chain(self.modified.iter().map(|(_, new)| *new))
+    }
+}
+
+/// Whether views reading the table must be dropped for the change
+fn drops_dependent_views(change: &TableChange) -> bool {
+    match change {
+        TableChange::ColumnDropped { .. } => true,
+        TableChange::ColumnModified { changes, .. } => changes
+            .iter()
+            .any(|c| matches!(c, ColumnChange::DataTypeChanged { .. })),
+        _ => false,
+    }
+}
+
+/// Views in an order they can be created in, each after the views it reads
+/// from
+fn view_order(views: &[ViewDefinition]) -> Vec<&ViewDefinition> {
+    let mut ordered: Vec<&ViewDefinition> = Vec::new();
+    let mut remaining: Vec<&ViewDefinition> = views.iter().collect();
+    while !remaining.is_empty() {
+        let (ready, blocked): (Vec<&ViewDefinition>, Vec<&ViewDefinition>) =
+            remaining.into_iter().partition(|view| {
+                view.depends_on.iter().all(|name| {
+                    ordered.iter().any(|v| &v.name == name)
+                        || !views.iter().any(|v| &v.name == name && v.name != view.name)
+                })
+            });
+        if ready.is_empty() {
+            // A cycle, which the database would not have allowed
+            ordered.extend(blocked);
+            break;
+        }
+        ordered.extend(ready);
+        remaining = blocked;
+    }
+    ordered
+}
+
+/// Distinct constraint names, in order of first appearance.
+pub(crate) fn constraint_names<'a>(
+    foreign_keys: impl Iterator<Item = &'a ForeignKeyDefinition>,
+) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for fk in foreign_keys {
+        if !names.contains(&fk.constraint_name) {
+            names.push(fk.constraint_name.clone());
+        }
+    }
+    names
 }
 
 impl Default for DiffStats {
@@ -298,6 +1053,13 @@ impl Default for DiffStats {
             indexes_dropped: 0,
             foreign_keys_added: 0,
             foreign_keys_dropped: 0,
+            constraints_added: 0,
+            constraints_dropped: 0,
+            objects_added: 0,
+            objects_dropped: 0,
+            objects_modified: 0,
+            tables_renamed: 0,
+            columns_renamed: 0,
         }
     }
 }
@@ -8,6 +8,22 @@ pub struct SchemaSnapshot {
     pub tables: Vec<TableDefinition>,
     pub indexes: Vec<IndexDefinition>,
     pub foreign_keys: Vec<ForeignKeyDefinition>,
+    #[serde(default)]
+    pub triggers: Vec<TriggerDefinition>,
+    #[serde(default)]
+    pub check_constraints: Vec<CheckConstraintDefinition>,
+    #[serde(default)]
+    pub unique_constraints: Vec<UniqueConstraintDefinition>,
+    #[serde(default)]
+    pub views: Vec<ViewDefinition>,
+    #[serde(default)]
+    pub routines: Vec<RoutineDefinition>,
+    #[serde(default)]
+    pub sequences: Vec<SequenceDefinition>,
+    #[serde(default)]
+    pub enum_types: Vec<EnumTypeDefinition>,
+    #[serde(default)]
+    pub domains: Vec<DomainDefinition>,
 }
 
 /// Table definition
@@ -17,6 +33,11 @@ pub struct TableDefinition {
     pub name: String,
     pub columns: Vec<ColumnDefinition>,
     pub primary_key: Option<Vec<String>>,
+    /// SQLite's `CREATE TABLE` from the column list on, as declared: with
+    /// the checks, collations, generated columns and table options the
+    /// columns above do not describe
+    #[serde(default)]
+    pub definition: Option<String>,
 }
 
 /// Column definition
@@ -30,6 +51,8 @@ pub struct ColumnDefinition {
     pub character_maximum_length: Option<i32>,
     pub numeric_precision: Option<i32>,
     pub numeric_scale: Option<i32>,
+    #[serde(default)]
+    pub comment: Option<String>,
 }
 
 /// Index definition
@@ -54,6 +77,91 @@ pub struct ForeignKeyDefinition {
     pub on_update: Option<String>,
 }
 
+/// Trigger definition
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TriggerDefinition {
+    pub name: String,
+    pub table_name: String,
+    /// Full `CREATE TRIGGER` statement
+    pub definition: String,
+}
+
+/// Check constraint of a table, or of a domain (`table_name` is then the
+/// domain's name)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CheckConstraintDefinition {
+    pub name: String,
+    pub table_name: String,
+    /// `CHECK (...)` clause
+    pub definition: String,
+}
+
+/// Unique constraint definition
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UniqueConstraintDefinition {
+    pub name: String,
+    pub table_name: String,
+    pub columns: Vec<String>,
+}
+
+/// View or materialized view definition
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ViewDefinition {
+    pub name: String,
+    /// The view's query
+    pub definition: String,
+    pub is_materialized: bool,
+    /// Tables and views of the same schema the view reads from
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+/// Function or procedure definition
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RoutineDefinition {
+    pub name: String,
+    /// Argument types, which tell overloads apart
+    pub arguments: String,
+    pub is_procedure: bool,
+    /// Result type, with any `OUT` parameters; none for procedures
+    #[serde(default)]
+    pub result_type: Option<String>,
+    /// Full `CREATE OR REPLACE` statement
+    pub definition: String,
+}
+
+/// Sequence definition
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SequenceDefinition {
+    pub name: String,
+    pub data_type: String,
+    pub start_value: i64,
+    pub increment_by: i64,
+    pub min_value: i64,
+    pub max_value: i64,
+    pub cycle: bool,
+    /// Table with the column that owns the sequence, which drops it along
+    /// with the table
+    pub owned_by: Option<String>,
+}
+
+/// Enum type definition
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EnumTypeDefinition {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+/// Domain type definition
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DomainDefinition {
+    pub name: String,
+    pub data_type: String,
+    pub is_nullable: bool,
+    pub default_value: Option<String>,
+    pub constraints: Vec<CheckConstraintDefinition>,
+}
+
 impl SchemaSnapshot {
     pub fn new(schema_name: String) -> Self {
         Self {
@@ -61,6 +169,14 @@ impl SchemaSnapshot {
             tables: Vec::new(),
             indexes: Vec::new(),
             foreign_keys: Vec::new(),
+            triggers: Vec::new(),
+            check_constraints: Vec::new(),
+            unique_constraints: Vec::new(),
+            views: Vec::new(),
+            routines: Vec::new(),
+            sequences: Vec::new(),
+            enum_types: Vec::new(),
+            domains: Vec::new(),
         }
     }
 
@@ -74,6 +190,56 @@ impl SchemaSnapshot {
         self.indexes.iter().find(|i| i.name == name)
     }
 
+    /// Indexes of one table
+    pub fn table_indexes<'a>(
+        &'a self,
+        table_name: &'a str,
+    ) -> impl Iterator<Item = &'a IndexDefinition> + 'a {
+        self.indexes
+            .iter()
+            .filter(move |i| i.table_name == table_name)
+    }
+
+    /// Foreign key columns of one table
+    pub fn table_foreign_keys<'a>(
+        &'a self,
+        table_name: &'a str,
+    ) -> impl Iterator<Item = &'a ForeignKeyDefinition> + 'a {
+        self.foreign_keys
+            .iter()
+            .filter(move |fk| fk.table_name == table_name)
+    }
+
+    /// Triggers of one table
+    pub fn table_triggers<'a>(
+        &'a self,
+        table_name: &'a str,
+    ) -> impl Iterator<Item = &'a TriggerDefinition> + 'a {
+        self.triggers
+            .iter()
+            .filter(move |t| t.table_name == table_name)
+    }
+
+    /// Check constraints of one table
+    pub fn table_check_constraints<'a>(
+        &'a self,
+        table_name: &'a str,
+    ) -> impl Iterator<Item = &'a CheckConstraintDefinition> + 'a {
+        self.check_constraints
+            .iter()
+            .filter(move |c| c.table_name == table_name)
+    }
+
+    /// Unique constraints of one table
+    pub fn table_unique_constraints<'a>(
+        &'a self,
+        table_name: &'a str,
+    ) -> impl Iterator<Item = &'a UniqueConstraintDefinition> + 'a {
+        self.unique_constraints
+            .iter()
+            .filter(move |c| c.table_name == table_name)
+    }
+
     /// Find a foreign key by constraint name
     pub fn find_foreign_key(&self, name: &str) -> Option<&ForeignKeyDefinition> {
         self.foreign_keys
@@ -106,12 +272,17 @@ impl ColumnDefinition {
             && self.numeric_scale == other.numeric_scale
     }
 
-    /// Check if two columns are identical
-    pub fn is_identical(&self, other: &ColumnDefinition) -> bool {
-        self.name == other.name
-            && self.same_type(other)
+    /// Check if two columns are defined alike, whatever their names and
+    /// comments
+    pub fn same_definition(&self, other: &ColumnDefinition) -> bool {
+        self.same_type(other)
             && self.is_nullable == other.is_nullable
             && self.default_value == other.default_value
             && self.is_auto_increment == other.is_auto_increment
     }
+
+    /// Check if two columns are identical
+    pub fn is_identical(&self, other: &ColumnDefinition) -> bool {
+        self.name == other.name && self.same_definition(other) && self.comment == other.comment
+    }
 }
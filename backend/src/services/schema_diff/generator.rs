@@ -2,6 +2,16 @@ use super::differ::*;
 use super::extractor::*;
 use serde::{Deserialize, Serialize};
 
+/// Fails a SQLite rebuild before its `COMMIT` when rows break a foreign key:
+/// `PRAGMA foreign_key_check` only lists them, so their count goes through a
+/// `CHECK` that raises.
+const SQLITE_FOREIGN_KEY_GUARD: [&str; 3] = [
+    "CREATE TEMP TABLE \"foreign_key_check\" (\"violations\" INTEGER \
+     CONSTRAINT \"foreign_key_violations\" CHECK (\"violations\" = 0));",
+    "INSERT INTO temp.\"foreign_key_check\" SELECT count(*) FROM pragma_foreign_key_check;",
+    "DROP TABLE temp.\"foreign_key_check\";",
+];
+
 /// Migration script containing SQL statements
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MigrationScript {
@@ -25,8 +35,10 @@ pub struct MigrationStatement {
 pub enum StatementCategory {
     CreateTable,
     DropTable,
+    RenameTable,
     AddColumn,
     DropColumn,
+    RenameColumn,
     ModifyColumn,
     AddPrimaryKey,
     DropPrimaryKey,
@@ -34,6 +46,22 @@ pub enum StatementCategory {
     DropIndex,
     AddForeignKey,
     DropForeignKey,
+    RebuildTable,
+    AddConstraint,
+    DropConstraint,
+    CreateView,
+    DropView,
+    CreateRoutine,
+    DropRoutine,
+    CreateTrigger,
+    DropTrigger,
+    CreateSequence,
+    AlterSequence,
+    DropSequence,
+    CreateType,
+    AlterType,
+    DropType,
+    Comment,
 }
 
 /// Summary of migration
@@ -95,27 +123,244 @@ impl MigrationGenerator {
         options: &MigrationOptions,
     ) {
         match diff {
-            SchemaDiff::TableAdded { table } => {
-                Self::generate_create_table(table, statements, id_counter, options);
+            SchemaDiff::TableAdded { objects } => {
+                let table = &objects.table;
+                let foreign_keys: Vec<&ForeignKeyDefinition> =
+                    objects.foreign_keys.iter().collect();
+                // SQLite can only declare foreign keys in `CREATE TABLE`.
+                let (inline, separate) = match options.database_type {
+                    DatabaseType::SQLite => (foreign_keys.as_slice(), &[][..]),
+                    _ => (&[][..], foreign_keys.as_slice()),
+                };
+                Self::generate_create_table(objects, inline, statements, id_counter, options);
+                for column in table.columns.iter().filter(|col| col.comment.is_some()) {
+                    Self::generate_column_comment(
+                        &table.name,
+                        &column.name,
+                        column.comment.as_deref(),
+                        statements,
+                        id_counter,
+                        options,
+                    );
+                }
+                for index in &objects.indexes {
+                    Self::generate_create_index(index, statements, id_counter, options);
+                }
+                Self::generate_add_foreign_keys(
+                    &table.name,
+                    separate,
+                    statements,
+                    id_counter,
+                    options,
+                );
+                for trigger in &objects.triggers {
+                    Self::generate_create_trigger(trigger, statements, id_counter);
+                }
             }
             SchemaDiff::TableDropped { table_name } => {
                 if options.include_drops {
                     Self::generate_drop_table(table_name, statements, id_counter, options);
                 }
             }
+            SchemaDiff::TableRenamed {
+                old_name,
+                new_name,
+                suspected,
+            } => {
+                Self::push_statement(
+                    statements,
+                    id_counter,
+                    format!(
+                        "ALTER TABLE {} RENAME TO {};",
+                        Self::quote_ident(old_name, options),
+                        Self::quote_ident(new_name, options)
+                    ),
+                    format!(
+                        "Rename table '{}' to '{}'{}",
+                        old_name,
+                        new_name,
+                        if *suspected { " (suspected)" } else { "" }
+                    ),
+                    false,
+                    StatementCategory::RenameTable,
+                );
+            }
             SchemaDiff::TableModified {
                 table_name,
                 changes,
-            } => {
-                Self::generate_table_modifications(
+                target,
+            } => match target {
+                Some(target)
+                    if options.database_type == DatabaseType::SQLite
+                        && !changes.iter().all(Self::sqlite_can_alter) =>
+                {
+                    Self::generate_sqlite_rebuild(target, changes, statements, id_counter, options);
+                }
+                _ => Self::generate_table_modifications(
                     table_name, changes, statements, id_counter, options,
+                ),
+            },
+            SchemaDiff::ViewAdded { view } => {
+                Self::generate_create_view(view, statements, id_counter, options);
+            }
+            SchemaDiff::ViewDropped { view } => {
+                if options.include_drops {
+                    Self::generate_drop_view(view, statements, id_counter, options);
+                }
+            }
+            SchemaDiff::ViewModified { old, new } => {
+                Self::generate_drop_view(old, statements, id_counter, options);
+                Self::generate_create_view(new, statements, id_counter, options);
+            }
+            SchemaDiff::ViewRecreated { view } => {
+                Self::generate_drop_view(view, statements, id_counter, options);
+                Self::generate_create_view(view, statements, id_counter, options);
+            }
+            SchemaDiff::RoutineAdded { routine } => {
+                Self::generate_create_routine(routine, statements, id_counter);
+            }
+            SchemaDiff::RoutineDropped { routine } => {
+                if options.include_drops {
+                    Self::generate_drop_routine(routine, statements, id_counter, options);
+                }
+            }
+            SchemaDiff::RoutineModified { old, new } => {
+                if old.result_type == new.result_type {
+                    Self::generate_create_routine(new, statements, id_counter);
+                } else {
+                    Self::generate_recreate_routine(old, new, statements, id_counter, options);
+                }
+            }
+            SchemaDiff::TriggerAdded { trigger } => {
+                Self::generate_create_trigger(trigger, statements, id_counter);
+            }
+            SchemaDiff::TriggerDropped { trigger } => {
+                if options.include_drops {
+                    Self::generate_drop_trigger(trigger, statements, id_counter, options);
+                }
+            }
+            SchemaDiff::TriggerModified { old, new } => {
+                Self::generate_drop_trigger(old, statements, id_counter, options);
+                Self::generate_create_trigger(new, statements, id_counter);
+            }
+            SchemaDiff::SequenceAdded { sequence } => {
+                let if_not_exists = if options.safe_mode {
+                    "IF NOT EXISTS "
+                } else {
+                    ""
+                };
+                Self::push_statement(
+                    statements,
+                    id_counter,
+                    format!(
+                        "CREATE SEQUENCE {}{} AS {}{};",
+                        if_not_exists,
+                        Self::quote_ident(&sequence.name, options),
+                        sequence.data_type,
+                        Self::sequence_options(sequence)
+                    ),
+                    format!("Create sequence '{}'", sequence.name),
+                    false,
+                    StatementCategory::CreateSequence,
+                );
+            }
+            SchemaDiff::SequenceDropped { sequence } => {
+                if options.include_drops {
+                    let if_exists = if options.safe_mode { "IF EXISTS " } else { "" };
+                    Self::push_statement(
+                        statements,
+                        id_counter,
+                        format!(
+                            "DROP SEQUENCE {}{};",
+                            if_exists,
+                            Self::quote_ident(&sequence.name, options)
+                        ),
+                        format!("Drop sequence '{}'", sequence.name),
+                        true,
+                        StatementCategory::DropSequence,
+                    );
+                }
+            }
+            SchemaDiff::SequenceModified { new, .. } => {
+                Self::push_statement(
+                    statements,
+                    id_counter,
+                    format!(
+                        "ALTER SEQUENCE {} AS {}{};",
+                        Self::quote_ident(&new.name, options),
+                        new.data_type,
+                        Self::sequence_options(new)
+                    ),
+                    format!("Alter sequence '{}'", new.name),
+                    false,
+                    StatementCategory::AlterSequence,
+                );
+            }
+            SchemaDiff::EnumTypeAdded { enum_type } => {
+                Self::push_statement(
+                    statements,
+                    id_counter,
+                    format!(
+                        "CREATE TYPE {} AS ENUM ({});",
+                        Self::quote_ident(&enum_type.name, options),
+                        Self::quote_literals(&enum_type.values)
+                    ),
+                    format!("Create enum type '{}'", enum_type.name),
+                    false,
+                    StatementCategory::CreateType,
                 );
             }
+            SchemaDiff::EnumTypeDropped { enum_type } => {
+                if options.include_drops {
+                    Self::generate_drop_type(&enum_type.name, statements, id_counter, options);
+                }
+            }
+            SchemaDiff::EnumTypeModified { old, new } => {
+                Self::generate_alter_enum(old, new, statements, id_counter, options);
+            }
+            SchemaDiff::DomainAdded { domain } => {
+                let mut sql = format!(
+                    "CREATE DOMAIN {} AS {}",
+                    Self::quote_ident(&domain.name, options),
+                    domain.data_type
+                );
+                if let Some(default) = &domain.default_value {
+                    sql.push_str(&format!(" DEFAULT {}", default));
+                }
+                if !domain.is_nullable {
+                    sql.push_str(" NOT NULL");
+                }
+                for constraint in &domain.constraints {
+                    sql.push_str(&format!(
+                        " CONSTRAINT {} {}",
+                        Self::quote_ident(&constraint.name, options),
+                        constraint.definition
+                    ));
+                }
+                sql.push(';');
+                Self::push_statement(
+                    statements,
+                    id_counter,
+                    sql,
+                    format!("Create domain '{}'", domain.name),
+                    false,
+                    StatementCategory::CreateType,
+                );
+            }
+            SchemaDiff::DomainDropped { domain } => {
+                if options.include_drops {
+                    Self::generate_drop_type(&domain.name, statements, id_counter, options);
+                }
+            }
+            SchemaDiff::DomainModified { old, new } => {
+                Self::generate_alter_domain(old, new, statements, id_counter, options);
+            }
         }
     }
 
     fn generate_create_table(
-        table: &TableDefinition,
+        objects: &TableObjects,
+        foreign_keys: &[&ForeignKeyDefinition],
         statements: &mut Vec<MigrationStatement>,
         id_counter: &mut usize,
         options: &MigrationOptions,
@@ -125,8 +370,32 @@ impl MigrationGenerator {
         } else {
             ""
         };
+        let sql = format!(
+            "CREATE TABLE {}{}",
+            if_not_exists,
+            Self::create_table_body(&objects.table.name, objects, foreign_keys, options)
+        );
+
+        statements.push(MigrationStatement {
+            id: *id_counter,
+            sql,
+            description: format!("Create table '{}'", objects.table.name),
+            is_destructive: false,
+            category: StatementCategory::CreateTable,
+            dependencies: Vec::new(),
+        });
+        *id_counter += 1;
+    }
 
-        let mut sql = format!("CREATE TABLE {}{} (\n", if_not_exists, table.name);
+    /// Name, columns and constraints of `CREATE TABLE`, up to the final `;`
+    fn create_table_body(
+        name: &str,
+        objects: &TableObjects,
+        foreign_keys: &[&ForeignKeyDefinition],
+        options: &MigrationOptions,
+    ) -> String {
+        let table = &objects.table;
+        let mut sql = format!("{} (\n", Self::quote_ident(name, options));
 
         // Add columns
         let column_defs: Vec<String> = table
@@ -137,22 +406,47 @@ impl MigrationGenerator {
 
         sql.push_str(&format!("  {}", column_defs.join(",\n  ")));
 
-        // Add primary key
-        if let Some(pk) = &table.primary_key {
-            sql.push_str(&format!(",\n  PRIMARY KEY ({})", pk.join(", ")));
+        // Add primary key, which SQLite declares on an AUTOINCREMENT column
+        let inline_pk = options.database_type == DatabaseType::SQLite
+            && table.columns.iter().any(|col| col.is_auto_increment);
+        if let Some(pk) = table.primary_key.as_ref().filter(|_| !inline_pk) {
+            sql.push_str(&format!(
+                ",\n  PRIMARY KEY ({})",
+                Self::quote_idents(pk, options)
+            ));
         }
 
-        sql.push_str("\n);");
+        for name in constraint_names(foreign_keys.iter().copied()) {
+            let columns: Vec<&ForeignKeyDefinition> = foreign_keys
+                .iter()
+                .copied()
+                .filter(|fk| fk.constraint_name == name)
+                .collect();
+            sql.push_str(&format!(
+                ",\n  CONSTRAINT {} {}",
+                Self::quote_ident(&name, options),
+                Self::foreign_key_clause(&columns, options)
+            ));
+        }
 
-        statements.push(MigrationStatement {
-            id: *id_counter,
-            sql,
-            description: format!("Create table '{}'", table.name),
-            is_destructive: false,
-            category: StatementCategory::CreateTable,
-            dependencies: Vec::new(),
-        });
-        *id_counter += 1;
+        for constraint in &objects.unique_constraints {
+            sql.push_str(&format!(
+                ",\n  CONSTRAINT {} UNIQUE ({})",
+                Self::quote_ident(&constraint.name, options),
+                Self::quote_idents(&constraint.columns, options)
+            ));
+        }
+
+        for constraint in &objects.check_constraints {
+            sql.push_str(&format!(
+                ",\n  CONSTRAINT {} {}",
+                Self::quote_ident(&constraint.name, options),
+                constraint.definition
+            ));
+        }
+
+        sql.push_str("\n);");
+        sql
     }
 
     fn generate_drop_table(
@@ -162,7 +456,11 @@ impl MigrationGenerator {
         options: &MigrationOptions,
     ) {
         let if_exists = if options.safe_mode { "IF EXISTS " } else { "" };
-        let sql = format!("DROP TABLE {}{};", if_exists, table_name);
+        let sql = format!(
+            "DROP TABLE {}{};",
+            if_exists,
+            Self::quote_ident(table_name, options)
+        );
 
         statements.push(MigrationStatement {
             id: *id_counter,
@@ -182,12 +480,14 @@ impl MigrationGenerator {
         id_counter: &mut usize,
         options: &MigrationOptions,
     ) {
+        let table = Self::quote_ident(table_name, options);
+        let mut added_foreign_keys = Vec::new();
         for change in changes {
             match change {
                 TableChange::ColumnAdded { column } => {
                     let sql = format!(
                         "ALTER TABLE {} ADD COLUMN {};",
-                        table_name,
+                        table,
                         Self::format_column_definition(column, options)
                     );
                     statements.push(MigrationStatement {
@@ -199,11 +499,24 @@ impl MigrationGenerator {
                         dependencies: Vec::new(),
                     });
                     *id_counter += 1;
+                    if column.comment.is_some() {
+                        Self::generate_column_comment(
+                            table_name,
+                            &column.name,
+                            column.comment.as_deref(),
+                            statements,
+                            id_counter,
+                            options,
+                        );
+                    }
                 }
                 TableChange::ColumnDropped { column_name } => {
                     if options.include_drops {
-                        let sql =
-                            format!("ALTER TABLE {} DROP COLUMN {};", table_name, column_name);
+                        let sql = format!(
+                            "ALTER TABLE {} DROP COLUMN {};",
+                            table,
+                            Self::quote_ident(column_name, options)
+                        );
                         statements.push(MigrationStatement {
                             id: *id_counter,
                             sql,
@@ -215,29 +528,971 @@ impl MigrationGenerator {
                         *id_counter += 1;
                     }
                 }
+                TableChange::ColumnRenamed {
+                    old_name,
+                    new_name,
+                    suspected,
+                } => {
+                    Self::push_statement(
+                        statements,
+                        id_counter,
+                        format!(
+                            "ALTER TABLE {} RENAME COLUMN {} TO {};",
+                            table,
+                            Self::quote_ident(old_name, options),
+                            Self::quote_ident(new_name, options)
+                        ),
+                        format!(
+                            "Rename column '{}.{}' to '{}'{}",
+                            table_name,
+                            old_name,
+                            new_name,
+                            if *suspected { " (suspected)" } else { "" }
+                        ),
+                        false,
+                        StatementCategory::RenameColumn,
+                    );
+                }
                 TableChange::ColumnModified {
-                    column_name, new, ..
+                    column_name,
+                    new,
+                    changes,
+                    ..
                 } => {
-                    let sql = Self::generate_modify_column(table_name, column_name, new, options);
-                    statements.push(MigrationStatement {
-                        id: *id_counter,
+                    // Postgres sets comments apart from the column.
+                    let comment_only = changes
+                        .iter()
+                        .all(|c| matches!(c, ColumnChange::CommentChanged { .. }));
+                    if options.database_type != DatabaseType::PostgreSQL || !comment_only {
+                        let sql = Self::generate_modify_column(
+                            table_name,
+                            column_name,
+                            new,
+                            changes,
+                            options,
+                        );
+                        statements.push(MigrationStatement {
+                            id: *id_counter,
+                            sql,
+                            description: format!("Modify column '{}.{}'", table_name, column_name),
+                            is_destructive: true,
+                            category: StatementCategory::ModifyColumn,
+                            dependencies: Vec::new(),
+                        });
+                        *id_counter += 1;
+                    }
+                    if changes
+                        .iter()
+                        .any(|c| matches!(c, ColumnChange::CommentChanged { .. }))
+                    {
+                        Self::generate_column_comment(
+                            table_name,
+                            column_name,
+                            new.comment.as_deref(),
+                            statements,
+                            id_counter,
+                            options,
+                        );
+                    }
+                }
+                TableChange::IndexAdded { index } => {
+                    Self::generate_create_index(index, statements, id_counter, options);
+                }
+                TableChange::IndexDropped { index_name } => {
+                    let sql = match options.database_type {
+                        DatabaseType::MySQL => format!(
+                            "DROP INDEX {} ON {};",
+                            Self::quote_ident(index_name, options),
+                            table
+                        ),
+                        _ => format!(
+                            "DROP INDEX {}{};",
+                            if options.safe_mode { "IF EXISTS " } else { "" },
+                            Self::quote_ident(index_name, options)
+                        ),
+                    };
+                    Self::push_statement(
+                        statements,
+                        id_counter,
                         sql,
-                        description: format!("Modify column '{}.{}'", table_name, column_name),
-                        is_destructive: true,
-                        category: StatementCategory::ModifyColumn,
-                        dependencies: Vec::new(),
-                    });
-                    *id_counter += 1;
+                        format!("Drop index '{}' on '{}'", index_name, table_name),
+                        false,
+                        StatementCategory::DropIndex,
+                    );
+                }
+                TableChange::ForeignKeyAdded { foreign_key } => {
+                    added_foreign_keys.push(foreign_key);
+                }
+                TableChange::ForeignKeyDropped { constraint_name } => {
+                    let constraint = Self::quote_ident(constraint_name, options);
+                    let sql = match options.database_type {
+                        DatabaseType::PostgreSQL => format!(
+                            "ALTER TABLE {} DROP CONSTRAINT {}{};",
+                            table,
+                            if options.safe_mode { "IF EXISTS " } else { "" },
+                            constraint
+                        ),
+                        DatabaseType::MySQL => {
+                            format!("ALTER TABLE {} DROP FOREIGN KEY {};", table, constraint)
+                        }
+                        DatabaseType::SQLite => format!(
+                            "-- SQLite: Recreate table '{}' to drop foreign key '{}'",
+                            table_name, constraint_name
+                        ),
+                    };
+                    Self::push_statement(
+                        statements,
+                        id_counter,
+                        sql,
+                        format!("Drop foreign key '{}' on '{}'", constraint_name, table_name),
+                        false,
+                        StatementCategory::DropForeignKey,
+                    );
+                }
+                TableChange::CheckConstraintAdded { constraint } => {
+                    Self::push_statement(
+                        statements,
+                        id_counter,
+                        format!(
+                            "ALTER TABLE {} ADD CONSTRAINT {} {};",
+                            table,
+                            Self::quote_ident(&constraint.name, options),
+                            constraint.definition
+                        ),
+                        format!(
+                            "Add check constraint '{}' on '{}'",
+                            constraint.name, table_name
+                        ),
+                        false,
+                        StatementCategory::AddConstraint,
+                    );
+                }
+                TableChange::UniqueConstraintAdded { constraint } => {
+                    Self::push_statement(
+                        statements,
+                        id_counter,
+                        format!(
+                            "ALTER TABLE {} ADD CONSTRAINT {} UNIQUE ({});",
+                            table,
+                            Self::quote_ident(&constraint.name, options),
+                            Self::quote_idents(&constraint.columns, options)
+                        ),
+                        format!(
+                            "Add unique constraint '{}' on '{}'",
+                            constraint.name, table_name
+                        ),
+                        false,
+                        StatementCategory::AddConstraint,
+                    );
+                }
+                TableChange::CheckConstraintDropped { constraint_name }
+                | TableChange::UniqueConstraintDropped { constraint_name } => {
+                    let constraint = Self::quote_ident(constraint_name, options);
+                    let sql = match (&options.database_type, change) {
+                        (DatabaseType::MySQL, TableChange::CheckConstraintDropped { .. }) => {
+                            format!("ALTER TABLE {} DROP CHECK {};", table, constraint)
+                        }
+                        // MySQL keeps unique constraints as indexes.
+                        (DatabaseType::MySQL, _) => {
+                            format!("ALTER TABLE {} DROP INDEX {};", table, constraint)
+                        }
+                        _ => format!(
+                            "ALTER TABLE {} DROP CONSTRAINT {}{};",
+                            table,
+                            if options.safe_mode { "IF EXISTS " } else { "" },
+                            constraint
+                        ),
+                    };
+                    Self::push_statement(
+                        statements,
+                        id_counter,
+                        sql,
+                        format!("Drop constraint '{}' on '{}'", constraint_name, table_name),
+                        false,
+                        StatementCategory::DropConstraint,
+                    );
                 }
                 _ => {
                     // TODO: Handle other change types
                 }
             }
         }
+        Self::generate_add_foreign_keys(
+            table_name,
+            &added_foreign_keys,
+            statements,
+            id_counter,
+            options,
+        );
+    }
+
+    /// Whether SQLite's `ALTER TABLE` can make the change. Adding a column
+    /// needs a constant default when it is `NOT NULL`.
+    fn sqlite_can_alter(change: &TableChange) -> bool {
+        match change {
+            TableChange::ColumnAdded { column } => {
+                let constant_default = column.default_value.as_deref().is_some_and(|d| {
+                    !d.starts_with('(') && !d.to_uppercase().starts_with("CURRENT_")
+                });
+                !column.is_auto_increment
+                    && (constant_default || (column.is_nullable && column.default_value.is_none()))
+            }
+            TableChange::ColumnRenamed { .. }
+            | TableChange::IndexAdded { .. }
+            | TableChange::IndexDropped { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// SQLite's twelve-step table rebuild (https://sqlite.org/lang_altertable.html):
+    /// create the table as it should be under a new name, copy the rows,
+    /// drop the old table, rename the new one and recreate its indexes and
+    /// triggers, with foreign key checks off. Views reading the table are
+    /// dropped first and created again last, as SQLite checks them on the
+    /// rename. The new table takes the target's declared definition when
+    /// there is one, which keeps what the columns do not describe: checks,
+    /// collations, generated columns, `WITHOUT ROWID` and `STRICT`. The
+    /// rebuild is one statement, as its steps must run in order; the
+    /// `foreign_keys` toggles are statements of their own around it, since
+    /// SQLite ignores them inside a transaction. When a step fails, the
+    /// SQLite driver rolls the rebuild back and turns foreign keys on again.
+    fn generate_sqlite_rebuild(
+        target: &TableObjects,
+        changes: &[TableChange],
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+        options: &MigrationOptions,
+    ) {
+        let name = &target.table.name;
+        let table = Self::quote_ident(name, options);
+        let new_table = format!("new_{}", name);
+
+        // Added columns are left to their defaults, except that existing rows
+        // need a value in `NOT NULL` ones without a default; renamed columns
+        // are read under their old names.
+        let filled: Vec<&ColumnDefinition> = changes
+            .iter()
+            .filter_map(|change| match change {
+                TableChange::ColumnAdded { column }
+                    if !column.is_nullable
+                        && column.default_value.is_none()
+                        && !column.is_auto_increment =>
+                {
+                    Some(column)
+                }
+                _ => None,
+            })
+            .collect();
+        let (copied, selected): (Vec<String>, Vec<String>) = target
+            .table
+            .columns
+            .iter()
+            .filter(|col| {
+                !changes.iter().any(|change| {
+                    matches!(change, TableChange::ColumnAdded { column } if column.name == col.name)
+                })
+            })
+            .map(|col| {
+                let source_name = changes
+                    .iter()
+                    .find_map(|change| match change {
+                        TableChange::ColumnRenamed {
+                            old_name, new_name, ..
+                        } if *new_name == col.name => Some(old_name.clone()),
+                        _ => None,
+                    })
+                    .unwrap_or_else(|| col.name.clone());
+                (
+                    Self::quote_ident(&col.name, options),
+                    Self::quote_ident(&source_name, options),
+                )
+            })
+            .chain(filled.iter().map(|col| {
+                (
+                    Self::quote_ident(&col.name, options),
+                    Self::sqlite_fill_value(&col.data_type).to_string(),
+                )
+            }))
+            .unzip();
+        let copied = copied.join(", ");
+        let selected = selected.join(", ");
+
+        let create_table = match &target.table.definition {
+            Some(definition) => format!(
+                "CREATE TABLE {} {};",
+                Self::quote_ident(&new_table, options),
+                definition.trim().trim_end_matches(';')
+            ),
+            None => {
+                let foreign_keys: Vec<&ForeignKeyDefinition> = target.foreign_keys.iter().collect();
+                format!(
+                    "CREATE TABLE {}",
+                    Self::create_table_body(&new_table, target, &foreign_keys, options)
+                )
+            }
+        };
+        let mut steps = vec!["BEGIN TRANSACTION;".to_string()];
+        steps.extend(
+            target
+                .views
+                .iter()
+                .rev()
+                .map(|view| Self::drop_view_sql(view, options)),
+        );
+        steps.extend([
+            create_table,
+            format!(
+                "INSERT INTO {} ({}) SELECT {} FROM {};",
+                Self::quote_ident(&new_table, options),
+                copied,
+                selected,
+                table
+            ),
+            format!("DROP TABLE {};", table),
+            format!(
+                "ALTER TABLE {} RENAME TO {};",
+                Self::quote_ident(&new_table, options),
+                table
+            ),
+        ]);
+        steps.extend(
+            target
+                .indexes
+                .iter()
+                .map(|index| Self::create_index_sql(index, options)),
+        );
+        steps.extend(
+            target
+                .triggers
+                .iter()
+                .map(|trigger| format!("{};", trigger.definition.trim_end_matches(';'))),
+        );
+        steps.extend(
+            target
+                .views
+                .iter()
+                .map(|view| Self::create_view_sql(view, options)),
+        );
+        steps.extend(SQLITE_FOREIGN_KEY_GUARD.iter().map(|step| step.to_string()));
+        steps.push("COMMIT;".to_string());
+
+        Self::push_statement(
+            statements,
+            id_counter,
+            "PRAGMA foreign_keys = OFF;".to_string(),
+            format!("Turn off foreign key checks to rebuild table '{}'", name),
+            false,
+            StatementCategory::RebuildTable,
+        );
+        Self::push_statement(
+            statements,
+            id_counter,
+            steps.join("\n"),
+            match filled.as_slice() {
+                [] => format!("Rebuild table '{}'", name),
+                columns => format!(
+                    "Rebuild table '{}', filling existing rows of new NOT NULL {} with zero values",
+                    name,
+                    columns
+                        .iter()
+                        .map(|col| format!("'{}'", col.name))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            },
+            true,
+            StatementCategory::RebuildTable,
+        );
+        Self::push_statement(
+            statements,
+            id_counter,
+            "PRAGMA foreign_keys = ON;".to_string(),
+            format!(
+                "Turn foreign key checks back on after rebuilding table '{}'",
+                name
+            ),
+            false,
+            StatementCategory::RebuildTable,
+        );
+    }
+
+    /// Zero value of a column type's affinity
+    /// (https://sqlite.org/datatype3.html#determination_of_column_affinity).
+    fn sqlite_fill_value(data_type: &str) -> &'static str {
+        let data_type = data_type.to_uppercase();
+        let has = |names: &[&str]| names.iter().any(|name| data_type.contains(name));
+        if has(&["INT"]) {
+            "0"
+        } else if has(&["CHAR", "CLOB", "TEXT"]) {
+            "''"
+        } else if data_type.is_empty() || has(&["BLOB"]) {
+            "X''"
+        } else if has(&["REAL", "FLOA", "DOUB"]) {
+            "0.0"
+        } else {
+            "0"
+        }
+    }
+
+    fn generate_create_index(
+        index: &IndexDefinition,
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+        options: &MigrationOptions,
+    ) {
+        Self::push_statement(
+            statements,
+            id_counter,
+            Self::create_index_sql(index, options),
+            format!("Create index '{}' on '{}'", index.name, index.table_name),
+            false,
+            StatementCategory::AddIndex,
+        );
+    }
+
+    fn create_index_sql(index: &IndexDefinition, options: &MigrationOptions) -> String {
+        let index_type = index.index_type.as_deref().unwrap_or("").to_uppercase();
+        // MySQL spells full-text and spatial indexes like unique ones; other
+        // non-default methods go in `USING`.
+        let kind = match (&options.database_type, index_type.as_str()) {
+            (DatabaseType::MySQL, "FULLTEXT" | "SPATIAL") => format!("{} ", index_type),
+            _ if index.is_unique => "UNIQUE ".to_string(),
+            _ => String::new(),
+        };
+        let using = match (&options.database_type, index_type.as_str()) {
+            (_, "" | "BTREE" | "FULLTEXT" | "SPATIAL") | (DatabaseType::SQLite, _) => String::new(),
+            (DatabaseType::PostgreSQL, _) => format!(" USING {}", index_type.to_lowercase()),
+            (DatabaseType::MySQL, _) => format!(" USING {}", index_type),
+        };
+        let columns = Self::quote_idents(&index.columns, options);
+        match options.database_type {
+            DatabaseType::MySQL => format!(
+                "CREATE {}INDEX {} ON {} ({}){};",
+                kind,
+                Self::quote_ident(&index.name, options),
+                Self::quote_ident(&index.table_name, options),
+                columns,
+                using
+            ),
+            _ => format!(
+                "CREATE {}INDEX {}{} ON {}{} ({});",
+                kind,
+                if options.safe_mode {
+                    "IF NOT EXISTS "
+                } else {
+                    ""
+                },
+                Self::quote_ident(&index.name, options),
+                Self::quote_ident(&index.table_name, options),
+                using,
+                columns
+            ),
+        }
+    }
+
+    /// One statement per constraint; `foreign_keys` has a row per column.
+    fn generate_add_foreign_keys(
+        table_name: &str,
+        foreign_keys: &[&ForeignKeyDefinition],
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+        options: &MigrationOptions,
+    ) {
+        for name in constraint_names(foreign_keys.iter().copied()) {
+            let columns: Vec<&ForeignKeyDefinition> = foreign_keys
+                .iter()
+                .copied()
+                .filter(|fk| fk.constraint_name == name)
+                .collect();
+
+            let sql = if options.database_type == DatabaseType::SQLite {
+                format!(
+                    "-- SQLite: Recreate table '{}' to add foreign key '{}'",
+                    table_name, name
+                )
+            } else {
+                format!(
+                    "ALTER TABLE {} ADD CONSTRAINT {} {};",
+                    Self::quote_ident(table_name, options),
+                    Self::quote_ident(&name, options),
+                    Self::foreign_key_clause(&columns, options)
+                )
+            };
+
+            Self::push_statement(
+                statements,
+                id_counter,
+                sql,
+                format!("Add foreign key '{}' on '{}'", name, table_name),
+                false,
+                StatementCategory::AddForeignKey,
+            );
+        }
+    }
+
+    /// `FOREIGN KEY (...) REFERENCES ...` of one constraint, from its rows
+    fn foreign_key_clause(columns: &[&ForeignKeyDefinition], options: &MigrationOptions) -> String {
+        let first = columns[0];
+        let own: Vec<String> = columns.iter().map(|fk| fk.column_name.clone()).collect();
+        let referenced: Vec<String> = columns
+            .iter()
+            .map(|fk| fk.referenced_column_name.clone())
+            .collect();
+        let mut sql = format!(
+            "FOREIGN KEY ({}) REFERENCES {}",
+            Self::quote_idents(&own, options),
+            Self::quote_ident(&first.referenced_table_name, options)
+        );
+        // An empty column means the referenced table's primary key (SQLite).
+        if referenced.iter().all(|column| !column.is_empty()) {
+            sql.push_str(&format!(" ({})", Self::quote_idents(&referenced, options)));
+        }
+        if let Some(rule) = &first.on_delete {
+            sql.push_str(&format!(" ON DELETE {}", rule));
+        }
+        if let Some(rule) = &first.on_update {
+            sql.push_str(&format!(" ON UPDATE {}", rule));
+        }
+        sql
+    }
+
+    /// `COMMENT ON COLUMN` for Postgres; MySQL keeps comments in the column
+    /// definition and SQLite has none. `None` removes the comment.
+    fn generate_column_comment(
+        table_name: &str,
+        column_name: &str,
+        comment: Option<&str>,
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+        options: &MigrationOptions,
+    ) {
+        if options.database_type != DatabaseType::PostgreSQL {
+            return;
+        }
+        Self::push_statement(
+            statements,
+            id_counter,
+            format!(
+                "COMMENT ON COLUMN {}.{} IS {};",
+                Self::quote_ident(table_name, options),
+                Self::quote_ident(column_name, options),
+                comment.map_or_else(|| "NULL".to_string(), Self::quote_literal)
+            ),
+            format!("Comment on column '{}.{}'", table_name, column_name),
+            false,
+            StatementCategory::Comment,
+        );
+    }
+
+    fn generate_create_view(
+        view: &ViewDefinition,
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+        options: &MigrationOptions,
+    ) {
+        Self::push_statement(
+            statements,
+            id_counter,
+            Self::create_view_sql(view, options),
+            format!("Create view '{}'", view.name),
+            false,
+            StatementCategory::CreateView,
+        );
+    }
+
+    fn create_view_sql(view: &ViewDefinition, options: &MigrationOptions) -> String {
+        let kind = if view.is_materialized {
+            "MATERIALIZED VIEW"
+        } else {
+            "VIEW"
+        };
+        format!(
+            "CREATE {} {} AS\n{};",
+            kind,
+            Self::quote_ident(&view.name, options),
+            view.definition.trim().trim_end_matches(';')
+        )
+    }
+
+    fn generate_drop_view(
+        view: &ViewDefinition,
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+        options: &MigrationOptions,
+    ) {
+        Self::push_statement(
+            statements,
+            id_counter,
+            Self::drop_view_sql(view, options),
+            format!("Drop view '{}'", view.name),
+            false,
+            StatementCategory::DropView,
+        );
+    }
+
+    fn drop_view_sql(view: &ViewDefinition, options: &MigrationOptions) -> String {
+        let kind = if view.is_materialized {
+            "MATERIALIZED VIEW"
+        } else {
+            "VIEW"
+        };
+        let if_exists = if options.safe_mode { "IF EXISTS " } else { "" };
+        format!(
+            "DROP {} {}{};",
+            kind,
+            if_exists,
+            Self::quote_ident(&view.name, options)
+        )
+    }
+
+    /// The routine's own `CREATE OR REPLACE`, which also replaces a changed
+    /// body.
+    fn generate_create_routine(
+        routine: &RoutineDefinition,
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+    ) {
+        Self::push_statement(
+            statements,
+            id_counter,
+            format!("{};", routine.definition.trim().trim_end_matches(';')),
+            format!("Create routine '{}({})'", routine.name, routine.arguments),
+            false,
+            StatementCategory::CreateRoutine,
+        );
+    }
+
+    /// Postgres cannot `CREATE OR REPLACE` a function with another result
+    /// type, so the old one is dropped first. One statement, as routine drops
+    /// otherwise come last.
+    fn generate_recreate_routine(
+        old: &RoutineDefinition,
+        new: &RoutineDefinition,
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+        options: &MigrationOptions,
+    ) {
+        Self::push_statement(
+            statements,
+            id_counter,
+            format!(
+                "{}\n{};",
+                Self::drop_routine_sql(old, options),
+                new.definition.trim().trim_end_matches(';')
+            ),
+            format!(
+                "Recreate routine '{}({})' with its new result type",
+                new.name, new.arguments
+            ),
+            true,
+            StatementCategory::CreateRoutine,
+        );
+    }
+
+    fn generate_drop_routine(
+        routine: &RoutineDefinition,
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+        options: &MigrationOptions,
+    ) {
+        Self::push_statement(
+            statements,
+            id_counter,
+            Self::drop_routine_sql(routine, options),
+            format!("Drop routine '{}({})'", routine.name, routine.arguments),
+            true,
+            StatementCategory::DropRoutine,
+        );
+    }
+
+    fn drop_routine_sql(routine: &RoutineDefinition, options: &MigrationOptions) -> String {
+        let kind = if routine.is_procedure {
+            "PROCEDURE"
+        } else {
+            "FUNCTION"
+        };
+        let if_exists = if options.safe_mode { "IF EXISTS " } else { "" };
+        format!(
+            "DROP {} {}{}({});",
+            kind,
+            if_exists,
+            Self::quote_ident(&routine.name, options),
+            routine.arguments
+        )
+    }
+
+    fn generate_create_trigger(
+        trigger: &TriggerDefinition,
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+    ) {
+        Self::push_statement(
+            statements,
+            id_counter,
+            format!("{};", trigger.definition.trim().trim_end_matches(';')),
+            format!(
+                "Create trigger '{}' on '{}'",
+                trigger.name, trigger.table_name
+            ),
+            false,
+            StatementCategory::CreateTrigger,
+        );
+    }
+
+    fn generate_drop_trigger(
+        trigger: &TriggerDefinition,
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+        options: &MigrationOptions,
+    ) {
+        let if_exists = if options.safe_mode { "IF EXISTS " } else { "" };
+        let sql = match options.database_type {
+            DatabaseType::PostgreSQL => format!(
+                "DROP TRIGGER {}{} ON {};",
+                if_exists,
+                Self::quote_ident(&trigger.name, options),
+                Self::quote_ident(&trigger.table_name, options)
+            ),
+            _ => format!(
+                "DROP TRIGGER {}{};",
+                if_exists,
+                Self::quote_ident(&trigger.name, options)
+            ),
+        };
+        Self::push_statement(
+            statements,
+            id_counter,
+            sql,
+            format!(
+                "Drop trigger '{}' on '{}'",
+                trigger.name, trigger.table_name
+            ),
+            false,
+            StatementCategory::DropTrigger,
+        );
+    }
+
+    /// `INCREMENT BY ... [NO] CYCLE` options of a sequence
+    fn sequence_options(sequence: &SequenceDefinition) -> String {
+        format!(
+            " INCREMENT BY {} MINVALUE {} MAXVALUE {} START WITH {} {}",
+            sequence.increment_by,
+            sequence.min_value,
+            sequence.max_value,
+            sequence.start_value,
+            if sequence.cycle { "CYCLE" } else { "NO CYCLE" }
+        )
+    }
+
+    fn generate_drop_type(
+        name: &str,
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+        options: &MigrationOptions,
+    ) {
+        let if_exists = if options.safe_mode { "IF EXISTS " } else { "" };
+        Self::push_statement(
+            statements,
+            id_counter,
+            format!(
+                "DROP TYPE {}{};",
+                if_exists,
+                Self::quote_ident(name, options)
+            ),
+            format!("Drop type '{}'", name),
+            true,
+            StatementCategory::DropType,
+        );
+    }
+
+    /// Postgres can add enum values in place but not remove or reorder them;
+    /// those changes are left as a note.
+    fn generate_alter_enum(
+        old: &EnumTypeDefinition,
+        new: &EnumTypeDefinition,
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+        options: &MigrationOptions,
+    ) {
+        let name = Self::quote_ident(&new.name, options);
+        let kept: Vec<&String> = new
+            .values
+            .iter()
+            .filter(|v| old.values.contains(v))
+            .collect();
+        if !kept.iter().copied().eq(old.values.iter()) {
+            Self::push_statement(
+                statements,
+                id_counter,
+                format!(
+                    "-- Enum type '{}' drops or reorders values; recreate it as ({})",
+                    new.name,
+                    Self::quote_literals(&new.values)
+                ),
+                format!("Alter enum type '{}'", new.name),
+                true,
+                StatementCategory::AlterType,
+            );
+            return;
+        }
+
+        for (position, value) in new.values.iter().enumerate() {
+            if old.values.contains(value) {
+                continue;
+            }
+            let placement = match position.checked_sub(1).map(|p| &new.values[p]) {
+                Some(previous) => format!(" AFTER {}", Self::quote_literal(previous)),
+                None => old
+                    .values
+                    .first()
+                    .map(|first| format!(" BEFORE {}", Self::quote_literal(first)))
+                    .unwrap_or_default(),
+            };
+            Self::push_statement(
+                statements,
+                id_counter,
+                format!(
+                    "ALTER TYPE {} ADD VALUE {}{}{};",
+                    name,
+                    if options.safe_mode {
+                        "IF NOT EXISTS "
+                    } else {
+                        ""
+                    },
+                    Self::quote_literal(value),
+                    placement
+                ),
+                format!("Add value '{}' to enum type '{}'", value, new.name),
+                false,
+                StatementCategory::AlterType,
+            );
+        }
+    }
+
+    /// A domain's base type cannot be changed in place, which is left as a
+    /// note.
+    fn generate_alter_domain(
+        old: &DomainDefinition,
+        new: &DomainDefinition,
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+        options: &MigrationOptions,
+    ) {
+        let name = Self::quote_ident(&new.name, options);
+        let mut alter = |sql: String, is_destructive: bool| {
+            Self::push_statement(
+                statements,
+                id_counter,
+                sql,
+                format!("Alter domain '{}'", new.name),
+                is_destructive,
+                StatementCategory::AlterType,
+            );
+        };
+
+        if old.data_type != new.data_type {
+            alter(
+                format!(
+                    "-- Domain '{}' changes type from {} to {}; recreate it",
+                    new.name, old.data_type, new.data_type
+                ),
+                true,
+            );
+        }
+        if old.default_value != new.default_value {
+            alter(
+                match &new.default_value {
+                    Some(default) => format!("ALTER DOMAIN {} SET DEFAULT {};", name, default),
+                    None => format!("ALTER DOMAIN {} DROP DEFAULT;", name),
+                },
+                false,
+            );
+        }
+        if old.is_nullable != new.is_nullable {
+            let action = if new.is_nullable { "DROP" } else { "SET" };
+            alter(format!("ALTER DOMAIN {} {} NOT NULL;", name, action), false);
+        }
+        for constraint in &old.constraints {
+            if !new.constraints.contains(constraint) {
+                alter(
+                    format!(
+                        "ALTER DOMAIN {} DROP CONSTRAINT {};",
+                        name,
+                        Self::quote_ident(&constraint.name, options)
+                    ),
+                    false,
+                );
+            }
+        }
+        for constraint in &new.constraints {
+            if !old.constraints.contains(constraint) {
+                alter(
+                    format!(
+                        "ALTER DOMAIN {} ADD CONSTRAINT {} {};",
+                        name,
+                        Self::quote_ident(&constraint.name, options),
+                        constraint.definition
+                    ),
+                    false,
+                );
+            }
+        }
+    }
+
+    fn push_statement(
+        statements: &mut Vec<MigrationStatement>,
+        id_counter: &mut usize,
+        sql: String,
+        description: String,
+        is_destructive: bool,
+        category: StatementCategory,
+    ) {
+        statements.push(MigrationStatement {
+            id: *id_counter,
+            sql,
+            description,
+            is_destructive,
+            category,
+            dependencies: Vec::new(),
+        });
+        *id_counter += 1;
+    }
+
+    /// Quote an identifier for the target database
+    fn quote_ident(name: &str, options: &MigrationOptions) -> String {
+        match options.database_type {
+            DatabaseType::MySQL => format!("`{}`", name.replace('`', "``")),
+            _ => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+
+    /// Quote a string literal
+    fn quote_literal(value: &str) -> String {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+
+    fn quote_literals(values: &[String]) -> String {
+        values
+            .iter()
+            .map(|value| Self::quote_literal(value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn quote_idents(names: &[String], options: &MigrationOptions) -> String {
+        names
+            .iter()
+            .map(|name| Self::quote_ident(name, options))
+            .collect::<Vec<_>>()
+            .join(", ")
     }
 
     fn format_column_definition(col: &ColumnDefinition, options: &MigrationOptions) -> String {
-        let mut def = format!("{} {}", col.name, col.data_type);
+        let mut def = format!(
+            "{} {}",
+            Self::quote_ident(&col.name, options),
+            col.data_type
+        );
 
         if !col.is_nullable {
             def.push_str(" NOT NULL");
@@ -251,7 +1506,13 @@ impl MigrationGenerator {
             match options.database_type {
                 DatabaseType::PostgreSQL => def.push_str(" GENERATED ALWAYS AS IDENTITY"),
                 DatabaseType::MySQL => def.push_str(" AUTO_INCREMENT"),
-                DatabaseType::SQLite => def.push_str(" AUTOINCREMENT"),
+                DatabaseType::SQLite => def.push_str(" PRIMARY KEY AUTOINCREMENT"),
+            }
+        }
+
+        if let Some(comment) = col.comment.as_deref() {
+            if options.database_type == DatabaseType::MySQL {
+                def.push_str(&format!(" COMMENT {}", Self::quote_literal(comment)));
             }
         }
 
@@ -262,19 +1523,54 @@ impl MigrationGenerator {
         table_name: &str,
         column_name: &str,
         new_col: &ColumnDefinition,
+        changes: &[ColumnChange],
         options: &MigrationOptions,
     ) -> String {
         match options.database_type {
             DatabaseType::PostgreSQL => {
+                // One action per changed attribute
+                let column = Self::quote_ident(column_name, options);
+                let actions: Vec<String> = changes
+                    .iter()
+                    .filter_map(|change| match change {
+                        ColumnChange::DataTypeChanged { .. } => Some(format!(
+                            "TYPE {} USING {}::{}",
+                            new_col.data_type, column, new_col.data_type
+                        )),
+                        ColumnChange::NullabilityChanged { new, .. } => Some(
+                            if *new {
+                                "DROP NOT NULL"
+                            } else {
+                                "SET NOT NULL"
+                            }
+                            .to_string(),
+                        ),
+                        ColumnChange::DefaultValueChanged { new, .. } => Some(match new {
+                            Some(default) => format!("SET DEFAULT {}", default),
+                            None => "DROP DEFAULT".to_string(),
+                        }),
+                        ColumnChange::AutoIncrementChanged { new, .. } => Some(
+                            if *new {
+                                "ADD GENERATED ALWAYS AS IDENTITY"
+                            } else {
+                                "DROP IDENTITY IF EXISTS"
+                            }
+                            .to_string(),
+                        ),
+                        ColumnChange::CommentChanged { .. } => None,
+                    })
+                    .map(|action| format!("ALTER COLUMN {} {}", column, action))
+                    .collect();
                 format!(
-                    "ALTER TABLE {} ALTER COLUMN {} TYPE {};",
-                    table_name, column_name, new_col.data_type
+                    "ALTER TABLE {} {};",
+                    Self::quote_ident(table_name, options),
+                    actions.join(", ")
                 )
             }
             DatabaseType::MySQL => {
                 format!(
                     "ALTER TABLE {} MODIFY COLUMN {};",
-                    table_name,
+                    Self::quote_ident(table_name, options),
                     Self::format_column_definition(new_col, options)
                 )
             }
@@ -291,25 +1587,53 @@ impl MigrationGenerator {
     fn sort_by_dependencies(statements: &mut Vec<MigrationStatement>) {
         // Simple topological sort based on dependencies
         // For now, use category-based ordering:
-        // 1. DROP foreign keys
-        // 2. DROP indexes
-        // 3. DROP/MODIFY columns
-        // 4. ADD columns
-        // 5. CREATE indexes
-        // 6. ADD foreign keys
-
-        statements.sort_by_key(|s| match s.category {
-            StatementCategory::DropForeignKey => 0,
-            StatementCategory::DropIndex => 1,
-            StatementCategory::DropColumn => 2,
-            StatementCategory::ModifyColumn => 3,
-            StatementCategory::DropTable => 4,
-            StatementCategory::CreateTable => 5,
-            StatementCategory::AddColumn => 6,
-            StatementCategory::AddPrimaryKey => 7,
-            StatementCategory::AddIndex => 8,
-            StatementCategory::AddForeignKey => 9,
-            _ => 10,
+        // 1. RENAME tables and columns, which later statements name as renamed
+        // 2. DROP triggers and views, dependent views first
+        // 3. DROP foreign keys, constraints and indexes
+        // 4. DROP columns
+        // 5. CREATE/ALTER types, sequences and routines tables may use
+        // 6. MODIFY columns, DROP/CREATE/rebuild tables, ADD columns
+        // 7. CREATE indexes, ADD constraints and foreign keys
+        // 8. ALTER sequences, CREATE views and triggers, comments
+        // 9. DROP routines, sequences and types once nothing uses them
+
+        statements.sort_by_key(|s| {
+            let rank = match s.category {
+                StatementCategory::RenameTable => 0,
+                StatementCategory::RenameColumn => 1,
+                StatementCategory::DropTrigger => 2,
+                StatementCategory::DropView => 3,
+                StatementCategory::DropForeignKey => 4,
+                StatementCategory::DropConstraint => 5,
+                StatementCategory::DropIndex => 6,
+                StatementCategory::DropColumn => 7,
+                StatementCategory::CreateType | StatementCategory::AlterType => 8,
+                StatementCategory::CreateSequence => 9,
+                StatementCategory::CreateRoutine => 10,
+                StatementCategory::ModifyColumn => 11,
+                StatementCategory::DropTable => 12,
+                StatementCategory::CreateTable => 13,
+                StatementCategory::RebuildTable => 14,
+                StatementCategory::AddColumn => 15,
+                StatementCategory::AddPrimaryKey => 16,
+                StatementCategory::AddIndex => 17,
+                StatementCategory::AddConstraint => 18,
+                StatementCategory::AddForeignKey => 19,
+                StatementCategory::AlterSequence => 20,
+                StatementCategory::CreateView => 21,
+                StatementCategory::CreateTrigger => 22,
+                StatementCategory::Comment => 23,
+                StatementCategory::DropRoutine => 24,
+                StatementCategory::DropSequence => 25,
+                StatementCategory::DropType => 26,
+                _ => 27,
+            };
+            // Views are created in dependency order, so dropped in reverse
+            let order = match s.category {
+                StatementCategory::DropView => usize::MAX - s.id,
+                _ => s.id,
+            };
+            (rank, order)
         });
     }
 }
@@ -323,3 +1647,402 @@ impl Default for MigrationOptions {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::schema_diff::renames::{Rename, RenameDecisions};
+
+    fn column(name: &str, data_type: &str) -> ColumnDefinition {
+        ColumnDefinition {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable: false,
+            default_value: None,
+            is_auto_increment: false,
+            character_maximum_length: None,
+            numeric_precision: None,
+            numeric_scale: None,
+            comment: None,
+        }
+    }
+
+    fn foreign_key(column_name: &str, referenced_column_name: &str) -> ForeignKeyDefinition {
+        ForeignKeyDefinition {
+            constraint_name: "fk_order_item".to_string(),
+            table_name: "line".to_string(),
+            column_name: column_name.to_string(),
+            referenced_table_name: "order".to_string(),
+            referenced_column_name: referenced_column_name.to_string(),
+            on_delete: Some("CASCADE".to_string()),
+            on_update: None,
+        }
+    }
+
+    #[test]
+    fn generates_mysql_migration() {
+        let table = TableDefinition {
+            name: "line".to_string(),
+            columns: vec![column("order_id", "int"), column("item", "int")],
+            primary_key: None,
+            definition: None,
+        };
+        let mut source = SchemaSnapshot::new("shop".to_string());
+        source.tables.push(table.clone());
+        source.indexes.push(IndexDefinition {
+            name: "idx_item".to_string(),
+            table_name: "line".to_string(),
+            columns: vec!["item".to_string()],
+            is_unique: false,
+            index_type: Some("BTREE".to_string()),
+        });
+
+        let mut target = SchemaSnapshot::new("shop".to_string());
+        let mut changed = table;
+        changed.columns[1] = column("item", "bigint");
+        target.tables.push(changed);
+        target
+            .foreign_keys
+            .extend([foreign_key("order_id", "id"), foreign_key("item", "item")]);
+
+        let diff = SchemaDiffer::compare(&source, &target);
+        assert_eq!(diff.stats.indexes_dropped, 1);
+        assert_eq!(diff.stats.foreign_keys_added, 1);
+
+        let options = MigrationOptions {
+            database_type: DatabaseType::MySQL,
+            ..Default::default()
+        };
+        let sql: Vec<String> = MigrationGenerator::generate(&diff.diffs, &options)
+            .statements
+            .into_iter()
+            .map(|s| s.sql)
+            .collect();
+        assert_eq!(
+            sql,
+            vec![
+                "DROP INDEX `idx_item` ON `line`;",
+                "ALTER TABLE `line` MODIFY COLUMN `item` bigint NOT NULL;",
+                "ALTER TABLE `line` ADD CONSTRAINT `fk_order_item` FOREIGN KEY (`order_id`, `item`) \
+                 REFERENCES `order` (`id`, `item`) ON DELETE CASCADE;",
+            ]
+        );
+    }
+
+    #[test]
+    fn rebuilds_sqlite_tables_it_cannot_alter() {
+        let mut source = SchemaSnapshot::new("main".to_string());
+        source.tables.push(TableDefinition {
+            name: "line".to_string(),
+            columns: vec![column("id", "INTEGER"), column("qty", "TEXT")],
+            primary_key: Some(vec!["id".to_string()]),
+            definition: None,
+        });
+        let mut target = source.clone();
+        target.tables[0].columns[1] = column("qty", "INTEGER");
+        target.tables[0].columns.push(ColumnDefinition {
+            default_value: Some("0".to_string()),
+            ..column("price", "REAL")
+        });
+        target.tables[0].columns.push(column("note", "VARCHAR(20)"));
+        target.check_constraints.push(CheckConstraintDefinition {
+            name: "qty_positive".to_string(),
+            table_name: "line".to_string(),
+            definition: "CHECK (qty > 0)".to_string(),
+        });
+        target.unique_constraints.push(UniqueConstraintDefinition {
+            name: "line_note".to_string(),
+            table_name: "line".to_string(),
+            columns: vec!["note".to_string()],
+        });
+        target.indexes.push(IndexDefinition {
+            name: "idx_qty".to_string(),
+            table_name: "line".to_string(),
+            columns: vec!["qty".to_string()],
+            is_unique: false,
+            index_type: None,
+        });
+
+        let diff = SchemaDiffer::compare(&source, &target);
+        let options = MigrationOptions {
+            database_type: DatabaseType::SQLite,
+            safe_mode: false,
+            ..Default::default()
+        };
+        let script = MigrationGenerator::generate(&diff.diffs, &options);
+        let sql: Vec<&str> = script.statements.iter().map(|s| s.sql.as_str()).collect();
+        assert_eq!(sql.len(), 3);
+        assert_eq!(sql[0], "PRAGMA foreign_keys = OFF;");
+        assert_eq!(sql[2], "PRAGMA foreign_keys = ON;");
+        assert!(script
+            .statements
+            .iter()
+            .all(|s| s.category == StatementCategory::RebuildTable));
+        assert_eq!(
+            script.statements[1].description,
+            "Rebuild table 'line', filling existing rows of new NOT NULL 'note' with zero values"
+        );
+        assert_eq!(
+            sql[1],
+            [
+                "BEGIN TRANSACTION;",
+                "CREATE TABLE \"new_line\" (",
+                "  \"id\" INTEGER NOT NULL,",
+                "  \"qty\" INTEGER NOT NULL,",
+                "  \"price\" REAL NOT NULL DEFAULT 0,",
+                "  \"note\" VARCHAR(20) NOT NULL,",
+                "  PRIMARY KEY (\"id\"),",
+                "  CONSTRAINT \"line_note\" UNIQUE (\"note\"),",
+                "  CONSTRAINT \"qty_positive\" CHECK (qty > 0)",
+                ");",
+                "INSERT INTO \"new_line\" (\"id\", \"qty\", \"note\") SELECT \"id\", \"qty\", '' FROM \"line\";",
+                "DROP TABLE \"line\";",
+                "ALTER TABLE \"new_line\" RENAME TO \"line\";",
+                "CREATE INDEX \"idx_qty\" ON \"line\" (\"qty\");",
+                "CREATE TEMP TABLE \"foreign_key_check\" (\"violations\" INTEGER \
+                 CONSTRAINT \"foreign_key_violations\" CHECK (\"violations\" = 0));",
+                "INSERT INTO temp.\"foreign_key_check\" SELECT count(*) FROM pragma_foreign_key_check;",
+                "DROP TABLE temp.\"foreign_key_check\";",
+                "COMMIT;",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn rebuilds_sqlite_tables_as_declared_around_their_views() {
+        let view = |name: &str, definition: &str, depends_on: &str| ViewDefinition {
+            name: name.to_string(),
+            definition: definition.to_string(),
+            is_materialized: false,
+            depends_on: vec![depends_on.to_string()],
+        };
+        let mut source = SchemaSnapshot::new("main".to_string());
+        source.tables.push(TableDefinition {
+            name: "line".to_string(),
+            columns: vec![column("id", "INTEGER"), column("note", "TEXT")],
+            primary_key: Some(vec!["id".to_string()]),
+            definition: Some(
+                "(id INTEGER PRIMARY KEY, note TEXT NOT NULL COLLATE NOCASE) STRICT".to_string(),
+            ),
+        });
+        source.views.extend([
+            view("noted", "SELECT * FROM totals WHERE n > 0", "totals"),
+            view("totals", "SELECT count(note) AS n FROM line", "line"),
+        ]);
+        let mut target = source.clone();
+        target.tables[0].columns[1].is_nullable = true;
+        target.tables[0].definition = Some(
+            "(id INTEGER PRIMARY KEY, note TEXT COLLATE NOCASE CHECK (length(note) < 80)) STRICT"
+                .to_string(),
+        );
+
+        let diff = SchemaDiffer::compare(&source, &target);
+        let options = MigrationOptions {
+            database_type: DatabaseType::SQLite,
+            safe_mode: false,
+            ..Default::default()
+        };
+        let script = MigrationGenerator::generate(&diff.diffs, &options);
+        assert_eq!(script.statements.len(), 3);
+        assert_eq!(
+            script.statements[1].sql,
+            [
+                "BEGIN TRANSACTION;",
+                "DROP VIEW \"noted\";",
+                "DROP VIEW \"totals\";",
+                "CREATE TABLE \"new_line\" (id INTEGER PRIMARY KEY, note TEXT COLLATE NOCASE \
+                 CHECK (length(note) < 80)) STRICT;",
+                "INSERT INTO \"new_line\" (\"id\", \"note\") SELECT \"id\", \"note\" FROM \"line\";",
+                "DROP TABLE \"line\";",
+                "ALTER TABLE \"new_line\" RENAME TO \"line\";",
+                "CREATE VIEW \"totals\" AS\nSELECT count(note) AS n FROM line;",
+                "CREATE VIEW \"noted\" AS\nSELECT * FROM totals WHERE n > 0;",
+                "CREATE TEMP TABLE \"foreign_key_check\" (\"violations\" INTEGER \
+                 CONSTRAINT \"foreign_key_violations\" CHECK (\"violations\" = 0));",
+                "INSERT INTO temp.\"foreign_key_check\" SELECT count(*) FROM pragma_foreign_key_check;",
+                "DROP TABLE temp.\"foreign_key_check\";",
+                "COMMIT;",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn recreates_views_around_postgres_column_changes() {
+        let view = |name: &str, definition: &str, depends_on: &str| ViewDefinition {
+            name: name.to_string(),
+            definition: definition.to_string(),
+            is_materialized: false,
+            depends_on: vec![depends_on.to_string()],
+        };
+        let mut source = SchemaSnapshot::new("public".to_string());
+        source.tables.push(TableDefinition {
+            name: "line".to_string(),
+            columns: vec![column("id", "integer"), column("qty", "integer")],
+            primary_key: Some(vec!["id".to_string()]),
+            definition: None,
+        });
+        source.views.extend([
+            view(
+                "big_lines",
+                " SELECT * FROM totals WHERE qty > 10;",
+                "totals",
+            ),
+            view("totals", " SELECT id, qty FROM line;", "line"),
+        ]);
+        let mut target = source.clone();
+        target.tables[0].columns[1] = ColumnDefinition {
+            comment: Some("Units ordered".to_string()),
+            ..column("qty", "bigint")
+        };
+
+        let diff = SchemaDiffer::compare(&source, &target);
+        let options = MigrationOptions {
+            safe_mode: false,
+            ..Default::default()
+        };
+        let sql: Vec<String> = MigrationGenerator::generate(&diff.diffs, &options)
+            .statements
+            .into_iter()
+            .map(|s| s.sql)
+            .collect();
+        assert_eq!(
+            sql,
+            vec![
+                "DROP VIEW \"big_lines\";",
+                "DROP VIEW \"totals\";",
+                "ALTER TABLE \"line\" ALTER COLUMN \"qty\" TYPE bigint USING \"qty\"::bigint;",
+                "CREATE VIEW \"totals\" AS\nSELECT id, qty FROM line;",
+                "CREATE VIEW \"big_lines\" AS\nSELECT * FROM totals WHERE qty > 10;",
+                "COMMENT ON COLUMN \"line\".\"qty\" IS 'Units ordered';",
+            ]
+        );
+    }
+
+    #[test]
+    fn drops_routines_whose_result_type_changes() {
+        let routine = |arguments: &str, result_type: &str, body: &str| RoutineDefinition {
+            name: "total".to_string(),
+            arguments: arguments.to_string(),
+            is_procedure: false,
+            result_type: Some(result_type.to_string()),
+            definition: format!(
+                "CREATE OR REPLACE FUNCTION public.total({arguments})\n RETURNS {result_type}\n \
+                 LANGUAGE sql\nAS $function$ {body} $function$\n"
+            ),
+        };
+        let mut source = SchemaSnapshot::new("public".to_string());
+        source.routines.extend([
+            routine("a integer", "integer", "SELECT a"),
+            routine("a text", "text", "SELECT a"),
+        ]);
+        let mut target = SchemaSnapshot::new("public".to_string());
+        target.routines.extend([
+            routine("a integer", "bigint", "SELECT a"),
+            routine("a text", "text", "SELECT upper(a)"),
+        ]);
+
+        let diff = SchemaDiffer::compare(&source, &target);
+        let script = MigrationGenerator::generate(&diff.diffs, &MigrationOptions::default());
+        let sql: Vec<String> = script.statements.into_iter().map(|s| s.sql).collect();
+        assert_eq!(
+            sql,
+            vec![
+                "DROP FUNCTION IF EXISTS \"total\"(a integer);\n\
+                 CREATE OR REPLACE FUNCTION public.total(a integer)\n RETURNS bigint\n \
+                 LANGUAGE sql\nAS $function$ SELECT a $function$;",
+                "CREATE OR REPLACE FUNCTION public.total(a text)\n RETURNS text\n \
+                 LANGUAGE sql\nAS $function$ SELECT upper(a) $function$;",
+            ]
+        );
+    }
+
+    #[test]
+    fn renames_tables_and_columns_once_accepted() {
+        let mut source = SchemaSnapshot::new("public".to_string());
+        source.tables.push(TableDefinition {
+            name: "customer".to_string(),
+            columns: vec![column("id", "integer"), column("mail", "text")],
+            primary_key: Some(vec!["id".to_string()]),
+            definition: None,
+        });
+        source.tables.push(TableDefinition {
+            name: "line".to_string(),
+            columns: vec![column("id", "integer"), column("qty", "integer")],
+            primary_key: Some(vec!["id".to_string()]),
+            definition: None,
+        });
+        let mut target = source.clone();
+        target.tables[0].name = "customers".to_string();
+        target.tables[1].columns[1].name = "quantity".to_string();
+
+        let options = MigrationOptions {
+            safe_mode: false,
+            ..Default::default()
+        };
+        let migrate = |decisions: &RenameDecisions| -> Vec<String> {
+            let diff = SchemaDiffer::compare_for_migration(&source, &target, decisions);
+            MigrationGenerator::generate(&diff.diffs, &options)
+                .statements
+                .into_iter()
+                .map(|s| s.sql)
+                .collect()
+        };
+
+        let diff = SchemaDiffer::compare(&source, &target);
+        assert_eq!(diff.stats.tables_renamed, 1);
+        assert_eq!(diff.stats.columns_renamed, 1);
+        assert!(matches!(
+            &diff.diffs[0],
+            SchemaDiff::TableRenamed {
+                suspected: true,
+                ..
+            }
+        ));
+        assert_eq!(
+            migrate(&RenameDecisions::default()),
+            vec![
+                "ALTER TABLE \"line\" DROP COLUMN \"qty\";",
+                "DROP TABLE \"customer\";",
+                "CREATE TABLE \"customers\" (\n  \"id\" integer NOT NULL,\n  \"mail\" text NOT NULL,\n  PRIMARY KEY (\"id\")\n);",
+                "ALTER TABLE \"line\" ADD COLUMN \"quantity\" integer NOT NULL;",
+            ]
+        );
+
+        let table_rename = Rename {
+            table_name: None,
+            old_name: "customer".to_string(),
+            new_name: "customers".to_string(),
+        };
+        let column_rename = Rename {
+            table_name: Some("line".to_string()),
+            old_name: "qty".to_string(),
+            new_name: "quantity".to_string(),
+        };
+        let accepted = RenameDecisions {
+            accepted: vec![table_rename.clone(), column_rename.clone()],
+            rejected: Vec::new(),
+        };
+        assert_eq!(
+            migrate(&accepted),
+            vec![
+                "ALTER TABLE \"customer\" RENAME TO \"customers\";",
+                "ALTER TABLE \"line\" RENAME COLUMN \"qty\" TO \"quantity\";",
+            ]
+        );
+
+        let rejected = RenameDecisions {
+            accepted: vec![table_rename],
+            rejected: vec![column_rename],
+        };
+        assert_eq!(
+            migrate(&rejected),
+            vec![
+                "ALTER TABLE \"customer\" RENAME TO \"customers\";",
+                "ALTER TABLE \"line\" DROP COLUMN \"qty\";",
+                "ALTER TABLE \"line\" ADD COLUMN \"quantity\" integer NOT NULL;",
+            ]
+        );
+    }
+}
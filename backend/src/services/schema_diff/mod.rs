@@ -1,8 +1,12 @@
 pub mod differ;
 pub mod extractor;
 pub mod generator;
+pub mod mysql_extractor;
 pub mod postgres_extractor;
+pub mod renames;
+pub mod sqlite_extractor;
 
 pub use differ::*;
 pub use extractor::*;
 pub use generator::*;
+pub use renames::{Rename, RenameDecisions};
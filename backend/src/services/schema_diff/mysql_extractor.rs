@@ -0,0 +1,348 @@
+use crate::services::schema_diff::extractor::*;
+use mysql_async::prelude::{FromValue, Queryable};
+use mysql_async::{Conn, Pool, Row};
+use std::collections::HashMap;
+
+/// Data types whose defaults are written without quotes.
+const NUMERIC_TYPES: &[&str] = &[
+    "tinyint",
+    "smallint",
+    "mediumint",
+    "int",
+    "integer",
+    "bigint",
+    "decimal",
+    "numeric",
+    "float",
+    "double",
+    "real",
+    "bit",
+    "year",
+];
+
+pub struct MySqlSchemaExtractor {
+    pool: Pool,
+}
+
+impl MySqlSchemaExtractor {
+    pub fn new(pool: Pool) -> Self {
+        Self { pool }
+    }
+
+    /// Extract complete schema snapshot from a MySQL or MariaDB database
+    pub async fn extract_schema(&self, schema_name: &str) -> Result<SchemaSnapshot, String> {
+        let mut conn = self.pool.get_conn().await.map_err(|e| e.to_string())?;
+        let version: Option<String> = conn
+            .query_first("SELECT VERSION()")
+            .await
+            .map_err(|e| e.to_string())?;
+        let mariadb = version.is_some_and(|v| v.contains("MariaDB"));
+
+        let mut snapshot = SchemaSnapshot::new(schema_name.to_string());
+
+        // Extract tables
+        snapshot.tables = Self::extract_tables(&mut conn, schema_name, mariadb).await?;
+
+        // Extract indexes
+        snapshot.indexes = Self::extract_indexes(&mut conn, schema_name).await?;
+
+        // Extract foreign keys
+        snapshot.foreign_keys = Self::extract_foreign_keys(&mut conn, schema_name).await?;
+
+        Ok(snapshot)
+    }
+
+    async fn extract_tables(
+        conn: &mut Conn,
+        schema_name: &str,
+        mariadb: bool,
+    ) -> Result<Vec<TableDefinition>, String> {
+        let table_names: Vec<String> = conn
+            .exec(
+                r#"
+                SELECT TABLE_NAME
+                FROM information_schema.TABLES
+                WHERE TABLE_SCHEMA = ?
+                AND TABLE_TYPE = 'BASE TABLE'
+                ORDER BY TABLE_NAME
+                "#,
+                (schema_name,),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut columns = Self::extract_columns(conn, schema_name, mariadb).await?;
+        let mut primary_keys = Self::extract_primary_keys(conn, schema_name).await?;
+
+        Ok(table_names
+            .into_iter()
+            .map(|name| TableDefinition {
+                columns: columns.remove(&name).unwrap_or_default(),
+                primary_key: primary_keys.remove(&name),
+                name,
+                definition: None,
+            })
+            .collect())
+    }
+
+    /// Columns of every table in the schema, by table name.
+    ///
+    /// `data_type` is the full `COLUMN_TYPE` (`varchar(255)`, `int unsigned`,
+    /// `enum('a','b')`), which is what `CREATE TABLE` and `MODIFY COLUMN` need.
+    async fn extract_columns(
+        conn: &mut Conn,
+        schema_name: &str,
+        mariadb: bool,
+    ) -> Result<HashMap<String, Vec<ColumnDefinition>>, String> {
+        let rows: Vec<Row> = conn
+            .exec(
+                r#"
+                SELECT
+                    TABLE_NAME,
+                    COLUMN_NAME,
+                    COLUMN_TYPE,
+                    DATA_TYPE,
+                    IS_NULLABLE,
+                    COLUMN_DEFAULT,
+                    EXTRA,
+                    CHARACTER_MAXIMUM_LENGTH,
+                    NUMERIC_PRECISION,
+                    NUMERIC_SCALE,
+                    COLUMN_COMMENT
+                FROM information_schema.COLUMNS
+                WHERE TABLE_SCHEMA = ?
+                ORDER BY TABLE_NAME, ORDINAL_POSITION
+                "#,
+                (schema_name,),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut columns: HashMap<String, Vec<ColumnDefinition>> = HashMap::new();
+        for mut row in rows {
+            let table_name: String = take(&mut row, 0)?;
+            let column_name: String = take(&mut row, 1)?;
+            let column_type: String = take(&mut row, 2)?;
+            let data_type: String = take(&mut row, 3)?;
+            let is_nullable: String = take(&mut row, 4)?;
+            let column_default: Option<String> = take(&mut row, 5)?;
+            let extra: String = take(&mut row, 6)?;
+            // LONGTEXT and friends report lengths past `i32::MAX`.
+            let character_maximum_length: Option<u64> = take(&mut row, 7)?;
+            let numeric_precision: Option<u64> = take(&mut row, 8)?;
+            let numeric_scale: Option<u64> = take(&mut row, 9)?;
+            let comment: String = take(&mut row, 10)?;
+
+            let extra = extra.to_lowercase();
+            let default_value = default_expression(column_default, &data_type, &extra, mariadb);
+
+            columns
+                .entry(table_name)
+                .or_default()
+                .push(ColumnDefinition {
+                    name: column_name,
+                    data_type: column_type,
+                    is_nullable: is_nullable == "YES",
+                    default_value,
+                    is_auto_increment: extra.contains("auto_increment"),
+                    character_maximum_length: character_maximum_length
+                        .and_then(|n| i32::try_from(n).ok()),
+                    numeric_precision: numeric_precision.and_then(|n| i32::try_from(n).ok()),
+                    numeric_scale: numeric_scale.and_then(|n| i32::try_from(n).ok()),
+                    comment: (!comment.is_empty()).then_some(comment),
+                });
+        }
+
+        Ok(columns)
+    }
+
+    async fn extract_primary_keys(
+        conn: &mut Conn,
+        schema_name: &str,
+    ) -> Result<HashMap<String, Vec<String>>, String> {
+        let rows: Vec<(String, String)> = conn
+            .exec(
+                r#"
+                SELECT TABLE_NAME, COLUMN_NAME
+                FROM information_schema.STATISTICS
+                WHERE TABLE_SCHEMA = ?
+                AND INDEX_NAME = 'PRIMARY'
+                ORDER BY TABLE_NAME, SEQ_IN_INDEX
+                "#,
+                (schema_name,),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut primary_keys: HashMap<String, Vec<String>> = HashMap::new();
+        for (table_name, column_name) in rows {
+            primary_keys
+                .entry(table_name)
+                .or_default()
+                .push(column_name);
+        }
+
+        Ok(primary_keys)
+    }
+
+    /// Indexes other than primary keys. Key parts on expressions have no
+    /// column name and are left out.
+    async fn extract_indexes(
+        conn: &mut Conn,
+        schema_name: &str,
+    ) -> Result<Vec<IndexDefinition>, String> {
+        let rows: Vec<Row> = conn
+            .exec(
+                r#"
+                SELECT TABLE_NAME, INDEX_NAME, NON_UNIQUE, INDEX_TYPE, COLUMN_NAME
+                FROM information_schema.STATISTICS
+                WHERE TABLE_SCHEMA = ?
+                AND INDEX_NAME <> 'PRIMARY'
+                ORDER BY TABLE_NAME, INDEX_NAME, SEQ_IN_INDEX
+                "#,
+                (schema_name,),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut indexes: Vec<IndexDefinition> = Vec::new();
+        for mut row in rows {
+            let table_name: String = take(&mut row, 0)?;
+            let index_name: String = take(&mut row, 1)?;
+            let non_unique: i64 = take(&mut row, 2)?;
+            let index_type: String = take(&mut row, 3)?;
+            let column_name: Option<String> = take(&mut row, 4)?;
+
+            let same_index = indexes
+                .last()
+                .is_some_and(|last| last.table_name == table_name && last.name == index_name);
+            if !same_index {
+                indexes.push(IndexDefinition {
+                    name: index_name,
+                    table_name,
+                    columns: Vec::new(),
+                    is_unique: non_unique == 0,
+                    index_type: Some(index_type),
+                });
+            }
+            if let Some(index) = indexes.last_mut() {
+                index.columns.extend(column_name);
+            }
+        }
+        indexes.retain(|index| !index.columns.is_empty());
+
+        Ok(indexes)
+    }
+
+    async fn extract_foreign_keys(
+        conn: &mut Conn,
+        schema_name: &str,
+    ) -> Result<Vec<ForeignKeyDefinition>, String> {
+        let rows: Vec<Row> = conn
+            .exec(
+                r#"
+                SELECT
+                    kcu.CONSTRAINT_NAME,
+                    kcu.TABLE_NAME,
+                    kcu.COLUMN_NAME,
+                    kcu.REFERENCED_TABLE_NAME,
+                    kcu.REFERENCED_COLUMN_NAME,
+                    rc.DELETE_RULE,
+                    rc.UPDATE_RULE
+                FROM information_schema.KEY_COLUMN_USAGE AS kcu
+                JOIN information_schema.REFERENTIAL_CONSTRAINTS AS rc
+                    ON rc.CONSTRAINT_SCHEMA = kcu.CONSTRAINT_SCHEMA
+                    AND rc.CONSTRAINT_NAME = kcu.CONSTRAINT_NAME
+                    AND rc.TABLE_NAME = kcu.TABLE_NAME
+                WHERE kcu.TABLE_SCHEMA = ?
+                AND kcu.REFERENCED_TABLE_NAME IS NOT NULL
+                ORDER BY kcu.TABLE_NAME, kcu.CONSTRAINT_NAME, kcu.ORDINAL_POSITION
+                "#,
+                (schema_name,),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        rows.into_iter()
+            .map(|mut row| {
+                Ok(ForeignKeyDefinition {
+                    constraint_name: take(&mut row, 0)?,
+                    table_name: take(&mut row, 1)?,
+                    column_name: take(&mut row, 2)?,
+                    referenced_table_name: take(&mut row, 3)?,
+                    referenced_column_name: take(&mut row, 4)?,
+                    on_delete: take(&mut row, 5)?,
+                    on_update: take(&mut row, 6)?,
+                })
+            })
+            .collect()
+    }
+}
+
+fn take<T: FromValue>(row: &mut Row, index: usize) -> Result<T, String> {
+    row.take_opt(index)
+        .ok_or_else(|| format!("Missing column {} in schema query", index))?
+        .map_err(|e| e.to_string())
+}
+
+/// `COLUMN_DEFAULT` as it would be written after `DEFAULT`.
+///
+/// MariaDB reports defaults as SQL already, with `NULL` for no default.
+/// MySQL reports string defaults unquoted and marks expression defaults
+/// with `DEFAULT_GENERATED` in `EXTRA`; those other than `CURRENT_TIMESTAMP`
+/// must be written in parentheses.
+fn default_expression(
+    default: Option<String>,
+    data_type: &str,
+    extra: &str,
+    mariadb: bool,
+) -> Option<String> {
+    let default = default?;
+    if mariadb {
+        return (default != "NULL").then_some(default);
+    }
+    if default.to_uppercase().starts_with("CURRENT_TIMESTAMP") {
+        Some(default)
+    } else if extra.contains("default_generated") {
+        Some(format!("({})", default))
+    } else if NUMERIC_TYPES.contains(&data_type.to_lowercase().as_str()) {
+        Some(default)
+    } else {
+        Some(format!(
+            "'{}'",
+            default.replace('\\', "\\\\").replace('\'', "''")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_defaults_as_sql() {
+        let mysql = |default: &str, data_type: &str, extra: &str| {
+            default_expression(Some(default.to_string()), data_type, extra, false)
+        };
+        assert_eq!(mysql("0", "int", ""), Some("0".into()));
+        assert_eq!(mysql("it's", "varchar", ""), Some("'it''s'".into()));
+        assert_eq!(
+            mysql("CURRENT_TIMESTAMP", "timestamp", "default_generated"),
+            Some("CURRENT_TIMESTAMP".into())
+        );
+        assert_eq!(
+            mysql("uuid()", "char", "default_generated"),
+            Some("(uuid())".into())
+        );
+        assert_eq!(default_expression(None, "int", "", false), None);
+
+        assert_eq!(
+            default_expression(Some("NULL".into()), "varchar", "", true),
+            None
+        );
+        assert_eq!(
+            default_expression(Some("'abc'".into()), "varchar", "", true),
+            Some("'abc'".into())
+        );
+    }
+}
@@ -25,6 +25,20 @@ impl PostgresSchemaExtractor {
         // Extract foreign keys
         snapshot.foreign_keys = self.extract_foreign_keys(schema_name).await?;
 
+        // Extract check and unique constraints
+        snapshot.check_constraints = self.extract_check_constraints(schema_name).await?;
+        snapshot.unique_constraints = self.extract_unique_constraints(schema_name).await?;
+
+        // Extract views, routines and triggers
+        snapshot.views = self.extract_views(schema_name).await?;
+        snapshot.routines = self.extract_routines(schema_name).await?;
+        snapshot.triggers = self.extract_triggers(schema_name).await?;
+
+        // Extract sequences and types
+        snapshot.sequences = self.extract_sequences(schema_name).await?;
+        snapshot.enum_types = self.extract_enum_types(schema_name).await?;
+        snapshot.domains = self.extract_domains(schema_name).await?;
+
         Ok(snapshot)
     }
 
@@ -54,6 +68,7 @@ impl PostgresSchemaExtractor {
                 name: table_name,
                 columns,
                 primary_key,
+                definition: None,
             });
         }
 
@@ -67,19 +82,26 @@ impl PostgresSchemaExtractor {
     ) -> Result<Vec<ColumnDefinition>, String> {
         let client = self.pool.get().await.map_err(|e| e.to_string())?;
 
+        // `format_type` gives the type as it is declared, with its length
+        // and the names of enum and domain types.
         let columns_query = r#"
-            SELECT 
-                column_name,
-                data_type,
-                is_nullable,
-                column_default,
-                character_maximum_length,
-                numeric_precision,
-                numeric_scale
-            FROM information_schema.columns
-            WHERE table_schema = $1
-            AND table_name = $2
-            ORDER BY ordinal_position
+            SELECT
+                c.column_name,
+                format_type(a.atttypid, a.atttypmod) AS data_type,
+                c.is_nullable,
+                c.column_default,
+                c.character_maximum_length,
+                c.numeric_precision,
+                c.numeric_scale,
+                col_description(a.attrelid, a.attnum) AS comment,
+                c.is_identity = 'YES' AS is_identity
+            FROM information_schema.columns c
+            JOIN pg_attribute a
+                ON a.attrelid = format('%I.%I', c.table_schema, c.table_name)::regclass
+                AND a.attname = c.column_name
+            WHERE c.table_schema = $1
+            AND c.table_name = $2
+            ORDER BY c.ordinal_position
         "#;
 
         let rows = client
@@ -97,11 +119,14 @@ impl PostgresSchemaExtractor {
                 let character_maximum_length: Option<i32> = row.get(4);
                 let numeric_precision: Option<i32> = row.get(5);
                 let numeric_scale: Option<i32> = row.get(6);
+                let comment: Option<String> = row.get(7);
+                let is_identity: bool = row.get(8);
 
-                let is_auto_increment = column_default
-                    .as_ref()
-                    .map(|d| d.starts_with("nextval("))
-                    .unwrap_or(false);
+                let is_auto_increment = is_identity
+                    || column_default
+                        .as_ref()
+                        .map(|d| d.starts_with("nextval("))
+                        .unwrap_or(false);
 
                 ColumnDefinition {
                     name: column_name,
@@ -112,6 +137,7 @@ impl PostgresSchemaExtractor {
                     character_maximum_length,
                     numeric_precision,
                     numeric_scale,
+                    comment,
                 }
             })
             .collect();
@@ -166,6 +192,12 @@ impl PostgresSchemaExtractor {
             JOIN pg_namespace n ON t.relnamespace = n.oid
             WHERE n.nspname = $1
             AND NOT ix.indisprimary
+            AND NOT EXISTS (
+                SELECT 1 FROM pg_constraint c
+                WHERE c.conindid = ix.indexrelid
+                AND c.conrelid = ix.indrelid
+                AND c.contype IN ('u', 'x')
+            )
             GROUP BY i.relname, t.relname, ix.indisunique, am.amname
             ORDER BY t.relname, i.relname
         "#;
@@ -257,4 +289,335 @@ impl PostgresSchemaExtractor {
 
         Ok(foreign_keys)
     }
+
+    async fn extract_check_constraints(
+        &self,
+        schema_name: &str,
+    ) -> Result<Vec<CheckConstraintDefinition>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+
+        let checks_query = r#"
+            SELECT c.conname, t.relname, pg_get_constraintdef(c.oid, true)
+            FROM pg_constraint c
+            JOIN pg_class t ON t.oid = c.conrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            WHERE n.nspname = $1
+            AND c.contype = 'c'
+            ORDER BY t.relname, c.conname
+        "#;
+
+        let rows = client
+            .query(checks_query, &[&schema_name])
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(rows
+            .iter()
+            .map(|row| CheckConstraintDefinition {
+                name: row.get(0),
+                table_name: row.get(1),
+                definition: row.get(2),
+            })
+            .collect())
+    }
+
+    async fn extract_unique_constraints(
+        &self,
+        schema_name: &str,
+    ) -> Result<Vec<UniqueConstraintDefinition>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+
+        let uniques_query = r#"
+            SELECT
+                c.conname,
+                t.relname,
+                ARRAY(
+                    SELECT a.attname::text
+                    FROM unnest(c.conkey) WITH ORDINALITY AS k(attnum, ord)
+                    JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.attnum
+                    ORDER BY k.ord
+                )
+            FROM pg_constraint c
+            JOIN pg_class t ON t.oid = c.conrelid
+            JOIN pg_namespace n ON n.oid = t.relnamespace
+            WHERE n.nspname = $1
+            AND c.contype = 'u'
+            ORDER BY t.relname, c.conname
+        "#;
+
+        let rows = client
+            .query(uniques_query, &[&schema_name])
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(rows
+            .iter()
+            .map(|row| UniqueConstraintDefinition {
+                name: row.get(0),
+                table_name: row.get(1),
+                columns: row.get(2),
+            })
+            .collect())
+    }
+
+    async fn extract_views(&self, schema_name: &str) -> Result<Vec<ViewDefinition>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+
+        // Dependencies are those of the view's rewrite rule on other
+        // relations of the same schema.
+        let views_query = r#"
+            SELECT
+                v.relname,
+                pg_get_viewdef(v.oid, true),
+                v.relkind = 'm' AS is_materialized,
+                ARRAY(
+                    SELECT DISTINCT d_ref.relname::text
+                    FROM pg_rewrite r
+                    JOIN pg_depend d
+                        ON d.classid = 'pg_rewrite'::regclass
+                        AND d.objid = r.oid
+                        AND d.refclassid = 'pg_class'::regclass
+                    JOIN pg_class d_ref ON d_ref.oid = d.refobjid
+                    WHERE r.ev_class = v.oid
+                    AND d_ref.oid <> v.oid
+                    AND d_ref.relnamespace = v.relnamespace
+                    ORDER BY 1
+                ) AS depends_on
+            FROM pg_class v
+            JOIN pg_namespace n ON n.oid = v.relnamespace
+            WHERE n.nspname = $1
+            AND v.relkind IN ('v', 'm')
+            ORDER BY v.relname
+        "#;
+
+        let rows = client
+            .query(views_query, &[&schema_name])
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ViewDefinition {
+                name: row.get(0),
+                definition: row.get(1),
+                is_materialized: row.get(2),
+                depends_on: row.get(3),
+            })
+            .collect())
+    }
+
+    /// Functions and procedures, without aggregates and those that belong to
+    /// extensions
+    async fn extract_routines(&self, schema_name: &str) -> Result<Vec<RoutineDefinition>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+
+        let routines_query = r#"
+            SELECT
+                p.proname,
+                pg_get_function_identity_arguments(p.oid),
+                p.prokind = 'p' AS is_procedure,
+                pg_get_function_result(p.oid),
+                pg_get_functiondef(p.oid)
+            FROM pg_proc p
+            JOIN pg_namespace n ON n.oid = p.pronamespace
+            WHERE n.nspname = $1
+            AND p.prokind IN ('f', 'p')
+            AND NOT EXISTS (
+                SELECT 1 FROM pg_depend d
+                WHERE d.classid = 'pg_proc'::regclass
+                AND d.objid = p.oid
+                AND d.deptype = 'e'
+            )
+            ORDER BY p.proname, 2
+        "#;
+
+        let rows = client
+            .query(routines_query, &[&schema_name])
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(rows
+            .iter()
+            .map(|row| RoutineDefinition {
+                name: row.get(0),
+                arguments: row.get(1),
+                is_procedure: row.get(2),
+                result_type: row.get(3),
+                definition: row.get(4),
+            })
+            .collect())
+    }
+
+    async fn extract_triggers(&self, schema_name: &str) -> Result<Vec<TriggerDefinition>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+
+        let triggers_query = r#"
+            SELECT t.tgname, c.relname, pg_get_triggerdef(t.oid, true)
+            FROM pg_trigger t
+            JOIN pg_class c ON c.oid = t.tgrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = $1
+            AND NOT t.tgisinternal
+            ORDER BY c.relname, t.tgname
+        "#;
+
+        let rows = client
+            .query(triggers_query, &[&schema_name])
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(rows
+            .iter()
+            .map(|row| TriggerDefinition {
+                name: row.get(0),
+                table_name: row.get(1),
+                definition: row.get(2),
+            })
+            .collect())
+    }
+
+    /// Sequences, without those behind identity columns, which come and go
+    /// with their column
+    async fn extract_sequences(
+        &self,
+        schema_name: &str,
+    ) -> Result<Vec<SequenceDefinition>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+
+        let sequences_query = r#"
+            SELECT
+                c.relname,
+                format_type(s.seqtypid, NULL),
+                s.seqstart,
+                s.seqincrement,
+                s.seqmin,
+                s.seqmax,
+                s.seqcycle,
+                owner.relname AS owned_by
+            FROM pg_sequence s
+            JOIN pg_class c ON c.oid = s.seqrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            LEFT JOIN pg_depend d
+                ON d.classid = 'pg_class'::regclass
+                AND d.objid = c.oid
+                AND d.refclassid = 'pg_class'::regclass
+                AND d.deptype IN ('a', 'i')
+            LEFT JOIN pg_class owner ON owner.oid = d.refobjid
+            WHERE n.nspname = $1
+            AND d.deptype IS DISTINCT FROM 'i'
+            ORDER BY c.relname
+        "#;
+
+        let rows = client
+            .query(sequences_query, &[&schema_name])
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(rows
+            .iter()
+            .map(|row| SequenceDefinition {
+                name: row.get(0),
+                data_type: row.get(1),
+                start_value: row.get(2),
+                increment_by: row.get(3),
+                min_value: row.get(4),
+                max_value: row.get(5),
+                cycle: row.get(6),
+                owned_by: row.get(7),
+            })
+            .collect())
+    }
+
+    async fn extract_enum_types(
+        &self,
+        schema_name: &str,
+    ) -> Result<Vec<EnumTypeDefinition>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+
+        let enums_query = r#"
+            SELECT t.typname, ARRAY_AGG(e.enumlabel::text ORDER BY e.enumsortorder)
+            FROM pg_type t
+            JOIN pg_enum e ON e.enumtypid = t.oid
+            JOIN pg_namespace n ON n.oid = t.typnamespace
+            WHERE n.nspname = $1
+            GROUP BY t.typname
+            ORDER BY t.typname
+        "#;
+
+        let rows = client
+            .query(enums_query, &[&schema_name])
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(rows
+            .iter()
+            .map(|row| EnumTypeDefinition {
+                name: row.get(0),
+                values: row.get(1),
+            })
+            .collect())
+    }
+
+    async fn extract_domains(&self, schema_name: &str) -> Result<Vec<DomainDefinition>, String> {
+        let client = self.pool.get().await.map_err(|e| e.to_string())?;
+
+        let domains_query = r#"
+            SELECT
+                t.typname,
+                format_type(t.typbasetype, t.typtypmod),
+                NOT t.typnotnull AS is_nullable,
+                t.typdefault
+            FROM pg_type t
+            JOIN pg_namespace n ON n.oid = t.typnamespace
+            WHERE n.nspname = $1
+            AND t.typtype = 'd'
+            ORDER BY t.typname
+        "#;
+
+        let constraints_query = r#"
+            SELECT c.conname, t.typname, pg_get_constraintdef(c.oid, true)
+            FROM pg_constraint c
+            JOIN pg_type t ON t.oid = c.contypid
+            JOIN pg_namespace n ON n.oid = t.typnamespace
+            WHERE n.nspname = $1
+            AND c.contype = 'c'
+            ORDER BY t.typname, c.conname
+        "#;
+
+        let rows = client
+            .query(domains_query, &[&schema_name])
+            .await
+            .map_err(|e| e.to_string())?;
+        let constraint_rows = client
+            .query(constraints_query, &[&schema_name])
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let constraints: Vec<CheckConstraintDefinition> = constraint_rows
+            .iter()
+            .map(|row| CheckConstraintDefinition {
+                name: row.get(0),
+                table_name: row.get(1),
+                definition: row.get(2),
+            })
+            .collect();
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let name: String = row.get(0);
+                DomainDefinition {
+                    constraints: constraints
+                        .iter()
+                        .filter(|c| c.table_name == name)
+                        .cloned()
+                        .collect(),
+                    name,
+                    data_type: row.get(1),
+                    is_nullable: row.get(2),
+                    default_value: row.get(3),
+                }
+            })
+            .collect())
+    }
 }
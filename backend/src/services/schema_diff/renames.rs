@@ -0,0 +1,317 @@
+use super::extractor::*;
+use serde::{Deserialize, Serialize};
+
+/// A renamed table, or a renamed column of a table
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Rename {
+    /// Table of a renamed column, by its name in the target schema; unset
+    /// for a renamed table
+    #[serde(default)]
+    pub table_name: Option<String>,
+    pub old_name: String,
+    pub new_name: String,
+}
+
+/// Suspected renames a user has accepted or rejected. An accepted rename is
+/// applied even when the heuristics would not have suspected it; a rejected
+/// one is compared as a drop and an add.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RenameDecisions {
+    #[serde(default)]
+    pub accepted: Vec<Rename>,
+    #[serde(default)]
+    pub rejected: Vec<Rename>,
+}
+
+/// A rename found between two snapshots, `suspected` unless accepted
+#[derive(Debug, Clone)]
+pub(crate) struct DetectedRename {
+    pub rename: Rename,
+    pub suspected: bool,
+}
+
+/// Tables only in the source matched to tables only in the target with the
+/// same columns and primary key, preferring a similar name. Only accepted
+/// renames unless `suspect`.
+pub(crate) fn detect_table_renames(
+    source: &SchemaSnapshot,
+    target: &SchemaSnapshot,
+    decisions: &RenameDecisions,
+    suspect: bool,
+) -> Vec<DetectedRename> {
+    let dropped: Vec<&TableDefinition> = source
+        .tables
+        .iter()
+        .filter(|t| target.find_table(&t.name).is_none())
+        .collect();
+    let added: Vec<&TableDefinition> = target
+        .tables
+        .iter()
+        .filter(|t| source.find_table(&t.name).is_none())
+        .collect();
+
+    match_renames(
+        None,
+        &dropped,
+        &added,
+        decisions,
+        suspect,
+        |old, new| {
+            let same_columns = !old.columns.is_empty()
+                && old.columns.len() == new.columns.len()
+                && old
+                    .columns
+                    .iter()
+                    .zip(&new.columns)
+                    .all(|(a, b)| a.name == b.name && a.same_definition(b));
+            (same_columns && old.primary_key == new.primary_key)
+                .then(|| similar_names(&old.name, &new.name))
+        },
+        |table| &table.name,
+    )
+}
+
+/// Columns only in the source table matched to columns only in the target
+/// table with the same type, nullability and default, and either the same
+/// position or a similar name. Only accepted renames unless `suspect`.
+pub(crate) fn detect_column_renames(
+    source: &TableDefinition,
+    target: &TableDefinition,
+    decisions: &RenameDecisions,
+    suspect: bool,
+) -> Vec<DetectedRename> {
+    let dropped: Vec<&ColumnDefinition> = source
+        .columns
+        .iter()
+        .filter(|c| target.find_column(&c.name).is_none())
+        .collect();
+    let added: Vec<&ColumnDefinition> = target
+        .columns
+        .iter()
+        .filter(|c| source.find_column(&c.name).is_none())
+        .collect();
+    let position =
+        |table: &TableDefinition, name: &str| table.columns.iter().position(|c| c.name == name);
+
+    match_renames(
+        Some(&target.name),
+        &dropped,
+        &added,
+        decisions,
+        suspect,
+        |old, new| {
+            let same_position = position(source, &old.name) == position(target, &new.name);
+            let similar = similar_names(&old.name, &new.name);
+            (old.same_definition(new) && (same_position || similar)).then_some(similar)
+        },
+        |column| &column.name,
+    )
+}
+
+/// Pairs `dropped` with `added`: accepted renames first, then, if `suspect`,
+/// each dropped object with the first unclaimed candidate, one with a
+/// similar name if any. `candidate` tells whether two objects may be one renamed, and if so
+/// whether their names are similar.
+fn match_renames<T>(
+    table_name: Option<&String>,
+    dropped: &[&T],
+    added: &[&T],
+    decisions: &RenameDecisions,
+    suspect: bool,
+    candidate: impl Fn(&T, &T) -> Option<bool>,
+    name: impl Fn(&T) -> &String,
+) -> Vec<DetectedRename> {
+    let rename = |old: &T, new: &T| Rename {
+        table_name: table_name.cloned(),
+        old_name: name(old).clone(),
+        new_name: name(new).clone(),
+    };
+    let mut renames: Vec<DetectedRename> = Vec::new();
+    let claimed = |renames: &[DetectedRename], old: &T, new: &T| {
+        renames
+            .iter()
+            .any(|r| &r.rename.old_name == name(old) || &r.rename.new_name == name(new))
+    };
+
+    for &old in dropped {
+        for &new in added {
+            let accepted = decisions.accepted.contains(&rename(old, new));
+            if accepted && !claimed(&renames, old, new) {
+                renames.push(DetectedRename {
+                    rename: rename(old, new),
+                    suspected: false,
+                });
+            }
+        }
+    }
+
+    if !suspect {
+        return renames;
+    }
+    for &old in dropped {
+        let candidates: Vec<(&T, bool)> = added
+            .iter()
+            .copied()
+            .filter(|&new| !claimed(&renames, old, new))
+            .filter(|&new| !decisions.rejected.contains(&rename(old, new)))
+            .filter_map(|new| candidate(old, new).map(|similar| (new, similar)))
+            .collect();
+        let best = candidates
+            .iter()
+            .find(|(_, similar)| *similar)
+            .or(candidates.first());
+        if let Some(&(new, _)) = best {
+            renames.push(DetectedRename {
+                rename: rename(old, new),
+                suspected: true,
+            });
+        }
+    }
+
+    renames
+}
+
+/// Whether two names differ only in case and separators, one contains the
+/// other, or they are at most a third apart in edits.
+fn similar_names(a: &str, b: &str) -> bool {
+    let normalize = |name: &str| -> Vec<char> {
+        name.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (normalize(a), normalize(b));
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    let (shorter, longer) = if a.len() <= b.len() {
+        (&a, &b)
+    } else {
+        (&b, &a)
+    };
+    if shorter.len() >= 3
+        && longer
+            .windows(shorter.len())
+            .any(|w| w == shorter.as_slice())
+    {
+        return true;
+    }
+
+    // Levenshtein distance, one row at a time
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()] * 3 <= longer.len()
+}
+
+/// The snapshot with tables and columns renamed, and the objects referring
+/// to them following along, so it compares with the target by new names.
+pub(crate) fn apply_renames<'a>(
+    snapshot: &SchemaSnapshot,
+    renames: impl IntoIterator<Item = &'a Rename>,
+) -> SchemaSnapshot {
+    let mut snapshot = snapshot.clone();
+    for rename in renames {
+        match &rename.table_name {
+            None => rename_table(&mut snapshot, &rename.old_name, &rename.new_name),
+            Some(table) => rename_column(&mut snapshot, table, &rename.old_name, &rename.new_name),
+        }
+    }
+    snapshot
+}
+
+fn rename_table(snapshot: &mut SchemaSnapshot, old: &str, new: &str) {
+    let rename = |name: &mut String| {
+        if name == old {
+            *name = new.to_string();
+        }
+    };
+    snapshot.tables.iter_mut().for_each(|t| rename(&mut t.name));
+    snapshot
+        .indexes
+        .iter_mut()
+        .for_each(|i| rename(&mut i.table_name));
+    for fk in &mut snapshot.foreign_keys {
+        rename(&mut fk.table_name);
+        rename(&mut fk.referenced_table_name);
+    }
+    snapshot
+        .triggers
+        .iter_mut()
+        .for_each(|t| rename(&mut t.table_name));
+    snapshot
+        .check_constraints
+        .iter_mut()
+        .for_each(|c| rename(&mut c.table_name));
+    snapshot
+        .unique_constraints
+        .iter_mut()
+        .for_each(|c| rename(&mut c.table_name));
+    snapshot
+        .sequences
+        .iter_mut()
+        .filter_map(|s| s.owned_by.as_mut())
+        .for_each(rename);
+    snapshot
+        .views
+        .iter_mut()
+        .flat_map(|v| v.depends_on.iter_mut())
+        .for_each(rename);
+}
+
+fn rename_column(snapshot: &mut SchemaSnapshot, table_name: &str, old: &str, new: &str) {
+    let rename = |name: &mut String| {
+        if name == old {
+            *name = new.to_string();
+        }
+    };
+    for table in snapshot.tables.iter_mut().filter(|t| t.name == table_name) {
+        table.columns.iter_mut().for_each(|c| rename(&mut c.name));
+        table.primary_key.iter_mut().flatten().for_each(rename);
+    }
+    snapshot
+        .indexes
+        .iter_mut()
+        .filter(|i| i.table_name == table_name)
+        .flat_map(|i| i.columns.iter_mut())
+        .for_each(rename);
+    for fk in &mut snapshot.foreign_keys {
+        if fk.table_name == table_name {
+            rename(&mut fk.column_name);
+        }
+        if fk.referenced_table_name == table_name {
+            rename(&mut fk.referenced_column_name);
+        }
+    }
+    snapshot
+        .unique_constraints
+        .iter_mut()
+        .filter(|c| c.table_name == table_name)
+        .flat_map(|c| c.columns.iter_mut())
+        .for_each(rename);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_names_loosely() {
+        assert!(similar_names("user_name", "UserName"));
+        assert!(similar_names("email", "email_address"));
+        assert!(similar_names("colour", "color"));
+        assert!(!similar_names("price", "quantity"));
+        assert!(!similar_names("id", "name"));
+    }
+}
@@ -0,0 +1,389 @@
+use crate::services::schema_diff::extractor::*;
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+
+pub struct SqliteSchemaExtractor {
+    pool: SqlitePool,
+}
+
+impl SqliteSchemaExtractor {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Extract complete schema snapshot from a SQLite database (`main` or an
+    /// attached one)
+    pub async fn extract_schema(&self, schema_name: &str) -> Result<SchemaSnapshot, String> {
+        let mut snapshot = SchemaSnapshot::new(schema_name.to_string());
+
+        let master = format!("\"{}\".sqlite_master", schema_name.replace('"', "\"\""));
+        let rows = sqlx::query(&format!(
+            "SELECT type, name, tbl_name, sql FROM {} \
+             WHERE type IN ('table', 'view', 'trigger') AND name NOT LIKE 'sqlite_%' \
+             ORDER BY name",
+            master
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut relations = Vec::new();
+        let mut views = Vec::new();
+        for row in rows {
+            let object_type: String = get(&row, 0)?;
+            let name: String = get(&row, 1)?;
+            let table_name: String = get(&row, 2)?;
+            let sql: Option<String> = get(&row, 3)?;
+
+            if object_type == "trigger" {
+                snapshot.triggers.push(TriggerDefinition {
+                    name,
+                    table_name,
+                    definition: sql.unwrap_or_default(),
+                });
+                continue;
+            }
+            relations.push(name.clone());
+            if object_type == "view" {
+                views.push((name, sql.unwrap_or_default()));
+                continue;
+            }
+
+            // `AUTOINCREMENT` is only allowed on an `INTEGER PRIMARY KEY`, so
+            // it appearing in the table's SQL marks that column.
+            let autoincrement = sql
+                .as_deref()
+                .is_some_and(|sql| sql.to_uppercase().contains("AUTOINCREMENT"));
+            let mut table = self
+                .extract_table(schema_name, &name, autoincrement)
+                .await?;
+            table.definition = sql.as_deref().and_then(after_name).map(str::to_string);
+            snapshot.tables.push(table);
+            let (indexes, unique_constraints) = self.extract_indexes(schema_name, &name).await?;
+            snapshot.indexes.extend(indexes);
+            snapshot.unique_constraints.extend(unique_constraints);
+            snapshot
+                .foreign_keys
+                .extend(self.extract_foreign_keys(schema_name, &name).await?);
+        }
+
+        for (name, sql) in views {
+            let definition = after_name(&sql)
+                .and_then(view_query)
+                .unwrap_or(&sql)
+                .to_string();
+            let names = identifiers(&definition);
+            let depends_on = relations
+                .iter()
+                .filter(|relation| {
+                    **relation != name && names.iter().any(|n| n.eq_ignore_ascii_case(relation))
+                })
+                .cloned()
+                .collect();
+            snapshot.views.push(ViewDefinition {
+                name,
+                definition,
+                is_materialized: false,
+                depends_on,
+            });
+        }
+
+        Ok(snapshot)
+    }
+
+    async fn extract_table(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+        autoincrement: bool,
+    ) -> Result<TableDefinition, String> {
+        let rows = sqlx::query(
+            "SELECT name, type, \"notnull\", dflt_value, pk FROM pragma_table_info(?, ?) ORDER BY cid",
+        )
+        .bind(table_name)
+        .bind(schema_name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut columns = Vec::new();
+        let mut primary_key: Vec<(i64, String)> = Vec::new();
+        for row in &rows {
+            let name: String = get(row, 0)?;
+            let data_type: String = get(row, 1)?;
+            let not_null: i64 = get(row, 2)?;
+            let default_value: Option<String> = get(row, 3)?;
+            let pk: i64 = get(row, 4)?;
+
+            if pk > 0 {
+                primary_key.push((pk, name.clone()));
+            }
+            columns.push(ColumnDefinition {
+                name,
+                is_auto_increment: autoincrement
+                    && pk > 0
+                    && data_type.eq_ignore_ascii_case("INTEGER"),
+                data_type,
+                is_nullable: not_null == 0,
+                default_value,
+                character_maximum_length: None,
+                numeric_precision: None,
+                numeric_scale: None,
+                comment: None,
+            });
+        }
+        primary_key.sort();
+
+        Ok(TableDefinition {
+            name: table_name.to_string(),
+            columns,
+            primary_key: (!primary_key.is_empty())
+                .then(|| primary_key.into_iter().map(|(_, name)| name).collect()),
+            definition: None,
+        })
+    }
+
+    /// Indexes created with `CREATE INDEX`, and the `UNIQUE` constraints of
+    /// the table, which SQLite keeps as indexes too. Constraints are named
+    /// after their table and columns, as SQLite names their indexes by
+    /// position. Key parts on expressions have no column name and are left
+    /// out.
+    async fn extract_indexes(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+    ) -> Result<(Vec<IndexDefinition>, Vec<UniqueConstraintDefinition>), String> {
+        let rows = sqlx::query(
+            "SELECT name, \"unique\", origin FROM pragma_index_list(?, ?) \
+             WHERE origin IN ('c', 'u') ORDER BY name",
+        )
+        .bind(table_name)
+        .bind(schema_name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut indexes = Vec::new();
+        let mut unique_constraints = Vec::new();
+        for row in &rows {
+            let name: String = get(row, 0)?;
+            let unique: i64 = get(row, 1)?;
+            let origin: String = get(row, 2)?;
+
+            let columns: Vec<Option<String>> =
+                sqlx::query_scalar("SELECT name FROM pragma_index_info(?, ?) ORDER BY seqno")
+                    .bind(&name)
+                    .bind(schema_name)
+                    .fetch_all(&self.pool)
+                    .await
+                    .map_err(|e| e.to_string())?;
+            let columns: Vec<String> = columns.into_iter().flatten().collect();
+            if columns.is_empty() {
+                continue;
+            }
+
+            if origin == "u" {
+                unique_constraints.push(UniqueConstraintDefinition {
+                    name: format!("uq_{}_{}", table_name, columns.join("_")),
+                    table_name: table_name.to_string(),
+                    columns,
+                });
+                continue;
+            }
+            indexes.push(IndexDefinition {
+                name,
+                table_name: table_name.to_string(),
+                columns,
+                is_unique: unique != 0,
+                index_type: None,
+            });
+        }
+
+        Ok((indexes, unique_constraints))
+    }
+
+    /// SQLite does not report constraint names, so each foreign key is named
+    /// after its table and columns, which keeps the name stable between
+    /// databases.
+    async fn extract_foreign_keys(
+        &self,
+        schema_name: &str,
+        table_name: &str,
+    ) -> Result<Vec<ForeignKeyDefinition>, String> {
+        let rows = sqlx::query(
+            "SELECT id, \"table\", \"from\", \"to\", on_update, on_delete \
+             FROM pragma_foreign_key_list(?, ?) ORDER BY id, seq",
+        )
+        .bind(table_name)
+        .bind(schema_name)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut foreign_keys: Vec<(i64, ForeignKeyDefinition)> = Vec::new();
+        for row in &rows {
+            let id: i64 = get(row, 0)?;
+            let referenced_table_name: String = get(row, 1)?;
+            let column_name: String = get(row, 2)?;
+            // Unset when the key references the other table's primary key;
+            // left empty then, and written without a column list.
+            let referenced_column_name: Option<String> = get(row, 3)?;
+            let on_update: String = get(row, 4)?;
+            let on_delete: String = get(row, 5)?;
+
+            foreign_keys.push((
+                id,
+                ForeignKeyDefinition {
+                    constraint_name: String::new(),
+                    table_name: table_name.to_string(),
+                    column_name,
+                    referenced_column_name: referenced_column_name.unwrap_or_default(),
+                    referenced_table_name,
+                    on_delete: Some(on_delete),
+                    on_update: Some(on_update),
+                },
+            ));
+        }
+
+        let names: Vec<String> = foreign_keys
+            .iter()
+            .map(|(id, _)| {
+                let columns: Vec<&str> = foreign_keys
+                    .iter()
+                    .filter(|(other, _)| other == id)
+                    .map(|(_, fk)| fk.column_name.as_str())
+                    .collect();
+                format!("fk_{}_{}", table_name, columns.join("_"))
+            })
+            .collect();
+        for ((_, fk), name) in foreign_keys.iter_mut().zip(names) {
+            fk.constraint_name = name;
+        }
+
+        Ok(foreign_keys.into_iter().map(|(_, fk)| fk).collect())
+    }
+}
+
+fn get<'r, T>(row: &'r SqliteRow, index: usize) -> Result<T, String>
+where
+    T: sqlx::Decode<'r, sqlx::Sqlite> + sqlx::Type<sqlx::Sqlite>,
+{
+    row.try_get(index).map_err(|e| e.to_string())
+}
+
+/// The rest of a `CREATE TABLE` or `CREATE VIEW` statement after the name of
+/// the object.
+fn after_name(sql: &str) -> Option<&str> {
+    let mut rest = keyword(sql, "CREATE")?;
+    rest = keyword(rest, "TEMPORARY")
+        .or_else(|| keyword(rest, "TEMP"))
+        .unwrap_or(rest);
+    rest = keyword(rest, "TABLE").or_else(|| keyword(rest, "VIEW"))?;
+    if let Some(after_if) = keyword(rest, "IF") {
+        rest = keyword(keyword(after_if, "NOT")?, "EXISTS")?;
+    }
+    rest = identifier(rest)?.1;
+    if let Some(after_schema) = rest.trim_start().strip_prefix('.') {
+        rest = identifier(after_schema)?.1;
+    }
+    Some(rest.trim())
+}
+
+/// The query of a view, after its column list if it has one.
+fn view_query(after_name: &str) -> Option<&str> {
+    let mut rest = after_name;
+    if rest.starts_with('(') {
+        let mut depth = 0;
+        let close = rest.find(|c| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            depth == 0
+        })?;
+        rest = &rest[close + 1..];
+    }
+    Some(keyword(rest, "AS")?.trim())
+}
+
+/// `sql` past `word` if it starts with that keyword, leading whitespace aside.
+fn keyword<'a>(sql: &'a str, word: &str) -> Option<&'a str> {
+    let sql = sql.trim_start();
+    let rest = sql.get(word.len()..)?;
+    let is_word = sql[..word.len()].eq_ignore_ascii_case(word)
+        && !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_');
+    is_word.then_some(rest)
+}
+
+/// The identifier `sql` starts with, unquoted, and the rest of `sql`.
+fn identifier(sql: &str) -> Option<(String, &str)> {
+    let sql = sql.trim_start();
+    let close = match sql.chars().next()? {
+        '"' => '"',
+        '`' => '`',
+        '[' => ']',
+        _ => {
+            let end = sql
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(sql.len());
+            return (end > 0).then(|| (sql[..end].to_string(), &sql[end..]));
+        }
+    };
+    // A doubled quote stands for the quote itself.
+    let mut name = String::new();
+    let mut chars = sql.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != close {
+            name.push(c);
+        } else if close != ']' && chars.peek().is_some_and(|(_, next)| *next == close) {
+            name.push(c);
+            chars.next();
+        } else {
+            return Some((name, &sql[i + 1..]));
+        }
+    }
+    None
+}
+
+/// The identifiers in `sql`, quoted or not, leaving out string literals.
+fn identifiers(sql: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = sql;
+    while let Some(c) = rest.chars().next() {
+        if c == '\'' {
+            // Doubled quotes inside the literal come out as two literals.
+            rest = rest[1..].find('\'').map_or("", |end| &rest[end + 2..]);
+        } else if let Some((name, after)) = identifier(rest) {
+            names.push(name);
+            rest = after;
+        } else {
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_names_and_bodies_of_create_statements() {
+        let table =
+            r#"CREATE TABLE IF NOT EXISTS main."line ""x" (qty INT CHECK (qty > 0)) STRICT"#;
+        assert_eq!(after_name(table), Some("(qty INT CHECK (qty > 0)) STRICT"));
+
+        let view =
+            "create view [totals](n) as SELECT count(*) FROM \"line \"\"x\" WHERE note = 'orders'";
+        let query = after_name(view).and_then(view_query).unwrap();
+        assert_eq!(
+            query,
+            "SELECT count(*) FROM \"line \"\"x\" WHERE note = 'orders'"
+        );
+        assert_eq!(
+            identifiers(query),
+            ["SELECT", "count", "FROM", "line \"x", "WHERE", "note"]
+        );
+    }
+}
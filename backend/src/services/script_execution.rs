@@ -0,0 +1,302 @@
+use crate::services::db_driver::QueryResult;
+use crate::services::driver::StatementSession;
+use crate::services::query_cancel::QueryHandle;
+use crate::utils::sql_script::{self, ScriptStatement};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tokio_postgres::error::ErrorPosition;
+
+/// What to do with the rest of a script after a statement fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorPolicy {
+    #[default]
+    StopOnError,
+    ContinueOnError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatementStatus {
+    Success,
+    Error,
+    /// Not run because an earlier statement failed.
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatementError {
+    pub message: String,
+    /// 1-based position of the error in the script: where the database
+    /// reports it, else the start of the statement.
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatementResult {
+    pub index: usize,
+    pub sql: String,
+    /// 1-based position of the statement in the script.
+    pub line: usize,
+    pub column: usize,
+    pub status: StatementStatus,
+    /// Rows for queries, `affected_rows` for everything else.
+    pub result: Option<QueryResult>,
+    pub execution_time_ms: Option<u64>,
+    pub error: Option<StatementError>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptResult {
+    pub statements: Vec<StatementResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub execution_time_ms: u64,
+}
+
+/// Splits `script` into statements and runs them in order on `session`,
+/// reporting the outcome of each one. With a `handle`, cancelling it also
+/// stops the running statement on the server.
+pub async fn run_script(
+    session: &mut dyn StatementSession,
+    script: &str,
+    policy: ErrorPolicy,
+    handle: Option<&QueryHandle>,
+) -> ScriptResult {
+    let started = Instant::now();
+    let mut statements = Vec::new();
+    let mut failed = false;
+
+    for (index, statement) in sql_script::split_sql_statements_with_positions(script)
+        .into_iter()
+        .enumerate()
+    {
+        if failed && policy == ErrorPolicy::StopOnError {
+            statements.push(outcome(index, statement, StatementStatus::Skipped));
+            continue;
+        }
+
+        let statement_started = Instant::now();
+        let result = match handle {
+            Some(handle) => {
+                session
+                    .execute_query_cancellable(&statement.sql, handle)
+                    .await
+            }
+            None => session.execute_query(&statement.sql).await,
+        };
+        let elapsed = statement_started.elapsed().as_millis() as u64;
+
+        let mut entry = match result {
+            Ok(mut result) => {
+                result.execution_time_ms = Some(elapsed);
+                let mut entry = outcome(index, statement, StatementStatus::Success);
+                entry.result = Some(result);
+                entry
+            }
+            Err(e) => {
+                failed = true;
+                let (line, column) = match error_position(&e, &statement.sql) {
+                    Some((1, column)) => (statement.line, statement.column + column - 1),
+                    Some((line, column)) => (statement.line + line - 1, column),
+                    None => (statement.line, statement.column),
+                };
+                let mut entry = outcome(index, statement, StatementStatus::Error);
+                entry.error = Some(StatementError {
+                    message: e.to_string(),
+                    line,
+                    column,
+                });
+                entry
+            }
+        };
+        entry.execution_time_ms = Some(elapsed);
+        statements.push(entry);
+    }
+
+    let count = |status| statements.iter().filter(|s| s.status == status).count();
+    ScriptResult {
+        succeeded: count(StatementStatus::Success),
+        failed: count(StatementStatus::Error),
+        skipped: count(StatementStatus::Skipped),
+        statements,
+        execution_time_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+fn outcome(index: usize, statement: ScriptStatement, status: StatementStatus) -> StatementResult {
+    StatementResult {
+        index,
+        sql: statement.sql,
+        line: statement.line,
+        column: statement.column,
+        status,
+        result: None,
+        execution_time_ms: None,
+        error: None,
+    }
+}
+
+/// 1-based line and column of the error within `statement`, when the
+/// database reports one: Postgres gives a character position, MySQL
+/// "... at line N" and ClickHouse "(line N, col M)".
+fn error_position(error: &anyhow::Error, statement: &str) -> Option<(usize, usize)> {
+    for cause in error.chain() {
+        let position = cause
+            .downcast_ref::<tokio_postgres::Error>()
+            .and_then(|e| e.as_db_error())
+            .and_then(|db| db.position());
+        if let Some(ErrorPosition::Original(position)) = position {
+            return Some(line_and_column(statement, *position as usize));
+        }
+    }
+
+    let message = error.to_string();
+    if let Some(rest) = message.split("(line ").nth(1) {
+        let line = leading_number(rest)?;
+        let column = rest
+            .split(", col ")
+            .nth(1)
+            .and_then(leading_number)
+            .unwrap_or(1);
+        return Some((line, column));
+    }
+    let rest = message.split(" at line ").nth(1)?;
+    Some((leading_number(rest)?, 1))
+}
+
+fn leading_number(s: &str) -> Option<usize> {
+    let digits: String = s.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok().filter(|n| *n > 0)
+}
+
+/// Line and column of the `position`th (1-based) character of `text`.
+fn line_and_column(text: &str, position: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut column = 1;
+    for c in text.chars().take(position.saturating_sub(1)) {
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use async_trait::async_trait;
+
+    /// Fails statements containing `boom` the way MySQL reports syntax errors.
+    struct Fake(Vec<String>);
+
+    #[async_trait]
+    impl StatementSession for Fake {
+        async fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
+            self.0.push(query.to_string());
+            if query.contains("boom") {
+                return Err(anyhow::anyhow!(
+                    "You have an error in your SQL syntax near 'boom' at line 2"
+                ));
+            }
+            Ok(affected(1))
+        }
+    }
+
+    /// Runs statements only through the cancellable path.
+    struct Attached(Vec<String>);
+
+    #[async_trait]
+    impl StatementSession for Attached {
+        async fn execute_query(&mut self, _query: &str) -> Result<QueryResult> {
+            Err(anyhow::anyhow!("run without the query handle"))
+        }
+
+        async fn execute_query_cancellable(
+            &mut self,
+            query: &str,
+            _handle: &QueryHandle,
+        ) -> Result<QueryResult> {
+            self.0.push(query.to_string());
+            Ok(affected(1))
+        }
+    }
+
+    fn affected(rows: u64) -> QueryResult {
+        QueryResult {
+            columns: vec![],
+            rows: vec![],
+            affected_rows: rows,
+            column_metadata: None,
+            total_count: None,
+            limit: None,
+            offset: None,
+            has_more: None,
+            row_metadata: None,
+            execution_time_ms: None,
+            json: None,
+            display_mode: None,
+            truncated: None,
+        }
+    }
+
+    const SCRIPT: &str = "INSERT INTO t VALUES (1);\nUPDATE t\n  SET boom = 1;\nDELETE FROM t";
+
+    #[tokio::test]
+    async fn stops_after_the_first_error() {
+        let mut session = Fake(Vec::new());
+        let result = run_script(&mut session, SCRIPT, ErrorPolicy::StopOnError, None).await;
+
+        assert_eq!(session.0.len(), 2);
+        assert_eq!((result.succeeded, result.failed, result.skipped), (1, 1, 1));
+        let failed = &result.statements[1];
+        assert_eq!(failed.status, StatementStatus::Error);
+        assert_eq!((failed.line, failed.column), (2, 1));
+        let error = failed.error.as_ref().unwrap();
+        assert_eq!((error.line, error.column), (3, 1));
+        assert_eq!(result.statements[2].status, StatementStatus::Skipped);
+    }
+
+    #[tokio::test]
+    async fn continues_after_errors() {
+        let mut session = Fake(Vec::new());
+        let result = run_script(&mut session, SCRIPT, ErrorPolicy::ContinueOnError, None).await;
+
+        assert_eq!(
+            session.0,
+            vec![
+                "INSERT INTO t VALUES (1)",
+                "UPDATE t\n  SET boom = 1",
+                "DELETE FROM t"
+            ]
+        );
+        assert_eq!((result.succeeded, result.failed, result.skipped), (2, 1, 0));
+        assert_eq!(
+            result.statements[2].result.as_ref().unwrap().affected_rows,
+            1
+        );
+        assert_eq!(line_and_column("SELECT\n  x", 10), (2, 3));
+    }
+
+    #[tokio::test]
+    async fn attaches_the_query_handle_to_every_statement() {
+        let mut session = Attached(Vec::new());
+        let handle = QueryHandle::new();
+        let result = run_script(
+            &mut session,
+            "SET x = 1; SELECT 1",
+            ErrorPolicy::StopOnError,
+            Some(&handle),
+        )
+        .await;
+
+        assert_eq!(session.0, vec!["SET x = 1", "SELECT 1"]);
+        assert_eq!(result.succeeded, 2);
+    }
+}
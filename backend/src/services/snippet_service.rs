@@ -1,6 +1,9 @@
 use crate::models::entities::query_snippet;
+use crate::services::sql_template::{self, RenderMode, TemplateVariable, VariableType};
+use crate::services::table_filter::SqlDialect;
 use chrono::Utc;
 use sea_orm::*;
+use serde_json::{json, Map, Value};
 use uuid::Uuid;
 
 pub struct SnippetService {
@@ -74,6 +77,34 @@ fn default_snippets() -> Vec<DefaultSnippet> {
     ]
 }
 
+/// Declaration for a placeholder of the built-in snippets, by naming convention.
+fn default_variable(name: &str) -> TemplateVariable {
+    let (kind, default) = match name {
+        "limit" => (VariableType::Number, Some(json!(100))),
+        "start_date" | "end_date" => (VariableType::Date, None),
+        "query" => (VariableType::Raw, None),
+        "id" | "value" => (VariableType::String, None),
+        _ => (VariableType::Identifier, None),
+    };
+    TemplateVariable {
+        name: name.to_string(),
+        kind,
+        required: default.is_none(),
+        default,
+        choices: None,
+        description: None,
+    }
+}
+
+fn default_variables(sql: &str) -> Option<Value> {
+    let variables: Vec<TemplateVariable> =
+        sql_template::placeholder_names(sql, SqlDialect::POSTGRES)
+            .iter()
+            .map(|name| default_variable(name))
+            .collect();
+    Some(serde_json::to_value(variables).unwrap())
+}
+
 impl SnippetService {
     pub fn new(db: DatabaseConnection) -> Self {
         Self { db }
@@ -85,6 +116,7 @@ impl SnippetService {
         description: Option<String>,
         sql: String,
         tags: Option<Vec<String>>,
+        variables: Option<Vec<TemplateVariable>>,
     ) -> Result<query_snippet::Model, DbErr> {
         let snippet = query_snippet::ActiveModel {
             id: Set(Uuid::new_v4()),
@@ -92,7 +124,7 @@ impl SnippetService {
             description: Set(description),
             sql: Set(sql),
             tags: Set(tags.map(|t| serde_json::to_value(t).unwrap())),
-            variables: Set(None),
+            variables: Set(variables.map(|v| serde_json::to_value(v).unwrap())),
             created_at: Set(Utc::now().into()),
             updated_at: Set(Utc::now().into()),
         };
@@ -114,9 +146,9 @@ impl SnippetService {
                     id: Set(Uuid::new_v4()),
                     name: Set(name),
                     description: Set(description),
+                    variables: Set(default_variables(&sql)),
                     sql: Set(sql),
                     tags: Set(tags.map(|t| serde_json::to_value(t).unwrap())),
-                    variables: Set(None),
                     created_at: Set(now),
                     updated_at: Set(now),
                 },
@@ -146,6 +178,7 @@ impl SnippetService {
         description: Option<String>,
         sql: Option<String>,
         tags: Option<Vec<String>>,
+        variables: Option<Vec<TemplateVariable>>,
     ) -> Result<query_snippet::Model, DbErr> {
         let mut snippet: query_snippet::ActiveModel = query_snippet::Entity::find_by_id(id)
             .one(&self.db)
@@ -165,12 +198,38 @@ impl SnippetService {
         if let Some(tags) = tags {
             snippet.tags = Set(Some(serde_json::to_value(tags).unwrap()));
         }
+        if let Some(variables) = variables {
+            snippet.variables = Set(Some(serde_json::to_value(variables).unwrap()));
+        }
 
         snippet.updated_at = Set(Utc::now().into());
 
         snippet.update(&self.db).await
     }
 
+    /// The snippet's SQL with `values` written in as literals of `dialect`,
+    /// ready to paste into an editor.
+    pub async fn render_snippet(
+        &self,
+        id: Uuid,
+        values: &Map<String, Value>,
+        dialect: SqlDialect,
+    ) -> anyhow::Result<String> {
+        let snippet = query_snippet::Entity::find_by_id(id)
+            .one(&self.db)
+            .await?
+            .ok_or(DbErr::RecordNotFound("Snippet not found".to_owned()))?;
+        let declared = sql_template::parse_variables(snippet.variables.as_ref())?;
+        let rendered = sql_template::render(
+            &snippet.sql,
+            &declared,
+            values,
+            dialect,
+            RenderMode::Display,
+        )?;
+        Ok(rendered.sql)
+    }
+
     pub async fn delete_snippet(&self, id: Uuid) -> Result<(), DbErr> {
         let result = query_snippet::Entity::delete_by_id(id)
             .exec(&self.db)
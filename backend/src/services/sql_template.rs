@@ -0,0 +1,587 @@
+use crate::services::table_filter::{Placeholder, SqlDialect, SqlFragment};
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// How a variable is checked and written into the SQL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableType {
+    #[default]
+    String,
+    Number,
+    Boolean,
+    /// `YYYY-MM-DD`, `YYYY-MM-DD HH:MM:SS` or RFC 3339; passed on as text.
+    Date,
+    /// A table, column or schema name, quoted for the dialect. Dotted names
+    /// are quoted part by part.
+    Identifier,
+    /// SQL text inserted as-is, such as a sort direction. Must list its
+    /// `choices`, except in text rendered for display.
+    Raw,
+}
+
+/// A variable declared by a snippet (its `variables` column) or a saved query
+/// (`metadata.variables`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateVariable {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub kind: VariableType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(default)]
+    pub required: bool,
+    /// Allowed values; anything else is rejected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub choices: Option<Vec<Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl TemplateVariable {
+    /// Placeholders without a declaration are required strings.
+    fn undeclared(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: VariableType::String,
+            default: None,
+            required: true,
+            choices: None,
+            description: None,
+        }
+    }
+}
+
+/// Whether values become placeholders or literals in the rendered SQL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderMode {
+    /// `$n`/`?` placeholders with the values in `SqlFragment::params`.
+    Bind,
+    /// Quoted literals, for drivers without parameters.
+    Inline,
+    /// Quoted literals, for text shown to the user rather than run, such as
+    /// a snippet pasted into the editor. Raw variables take any value.
+    Display,
+}
+
+/// Reads declarations stored as a JSON array; `null` means none.
+pub fn parse_variables(value: Option<&Value>) -> Result<Vec<TemplateVariable>> {
+    match value {
+        None | Some(Value::Null) => Ok(Vec::new()),
+        Some(value) => serde_json::from_value(value.clone())
+            .map_err(|e| anyhow::anyhow!("Invalid variable declarations: {}", e)),
+    }
+}
+
+/// Names of the `{{var}}` placeholders of `sql`, in order of first use.
+pub fn placeholder_names(sql: &str, dialect: SqlDialect) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for found in scan(sql, dialect) {
+        if !names.contains(&found.name) {
+            names.push(found.name);
+        }
+    }
+    names
+}
+
+/// Replaces the `{{var}}` placeholders of `sql` with `values`, falling back
+/// to declared defaults. Every variable is validated against its declaration
+/// first and all problems are reported together.
+///
+/// A placeholder wrapped in single quotes (`'{{day}}'`) stands for the whole
+/// string literal. One inside a longer quoted string or identifier is written
+/// into it escaped, whatever the mode.
+pub fn render(
+    sql: &str,
+    declared: &[TemplateVariable],
+    values: &Map<String, Value>,
+    dialect: SqlDialect,
+    mode: RenderMode,
+) -> Result<SqlFragment> {
+    let found = scan(sql, dialect);
+
+    let mut resolved: HashMap<&str, (&TemplateVariable, Value)> = HashMap::new();
+    let mut undeclared = Vec::new();
+    let mut errors = Vec::new();
+    for f in &found {
+        if resolved.contains_key(f.name.as_str()) {
+            continue;
+        }
+        let variable = match declared.iter().find(|v| v.name == f.name) {
+            Some(variable) => variable,
+            None => {
+                if !undeclared
+                    .iter()
+                    .any(|v: &TemplateVariable| v.name == f.name)
+                {
+                    undeclared.push(TemplateVariable::undeclared(&f.name));
+                }
+                continue;
+            }
+        };
+        match resolve(variable, values.get(&variable.name), mode) {
+            Ok(value) => {
+                resolved.insert(&variable.name, (variable, value));
+            }
+            Err(e) => errors.push(e.to_string()),
+        }
+    }
+    for variable in &undeclared {
+        match resolve(variable, values.get(&variable.name), mode) {
+            Ok(value) => {
+                resolved.insert(&variable.name, (variable, value));
+            }
+            Err(e) => errors.push(e.to_string()),
+        }
+    }
+    if !errors.is_empty() {
+        return Err(anyhow::anyhow!("{}", errors.join("; ")));
+    }
+
+    let mut out = String::with_capacity(sql.len());
+    let mut params = Vec::new();
+    let mut numbers: HashMap<&str, usize> = HashMap::new();
+    let mut copied = 0;
+    for f in &found {
+        let (variable, value) = &resolved[f.name.as_str()];
+        out.push_str(&sql[copied..f.start]);
+        copied = f.end;
+
+        if let Some(quote) = f.inside {
+            out.push_str(&escape_in_quotes(&text(value), quote, dialect));
+            continue;
+        }
+        let value = match variable.kind {
+            VariableType::Identifier => {
+                out.push_str(&quote_identifier(&text(value), dialect)?);
+                continue;
+            }
+            VariableType::Raw => {
+                out.push_str(&text(value));
+                continue;
+            }
+            _ if f.quoted && !value.is_null() => Value::String(text(value)),
+            _ => value.clone(),
+        };
+        match mode {
+            RenderMode::Inline | RenderMode::Display => out.push_str(&literal(&value, dialect)),
+            RenderMode::Bind => match dialect.placeholder {
+                // Each variable is bound once and reused.
+                Placeholder::Numbered => {
+                    let next = numbers.len() + 1;
+                    let number = *numbers.entry(&variable.name).or_insert_with(|| {
+                        params.push(value);
+                        next
+                    });
+                    out.push_str(&dialect.placeholder(number));
+                }
+                Placeholder::Question => {
+                    params.push(value);
+                    out.push('?');
+                }
+            },
+        }
+    }
+    out.push_str(&sql[copied..]);
+
+    Ok(SqlFragment { sql: out, params })
+}
+
+/// The value to use for `variable`: the supplied one, else the default,
+/// checked and converted to the declared type.
+fn resolve(
+    variable: &TemplateVariable,
+    supplied: Option<&Value>,
+    mode: RenderMode,
+) -> Result<Value> {
+    let name = &variable.name;
+    // Free-form SQL from an input form would be injected into what is run.
+    let has_choices = variable
+        .choices
+        .as_ref()
+        .is_some_and(|choices| !choices.is_empty());
+    if variable.kind == VariableType::Raw && mode != RenderMode::Display && !has_choices {
+        return Err(anyhow::anyhow!(
+            "Variable {} is raw SQL and must list its choices",
+            name
+        ));
+    }
+    let value = supplied
+        .filter(|v| !is_blank(v))
+        .or(variable.default.as_ref().filter(|v| !is_blank(v)));
+    let Some(value) = value else {
+        return match variable.kind {
+            _ if variable.required => Err(anyhow::anyhow!("Variable {} is required", name)),
+            VariableType::Identifier => Err(anyhow::anyhow!("Variable {} needs a name", name)),
+            VariableType::Raw => Ok(Value::String(String::new())),
+            _ => Ok(Value::Null),
+        };
+    };
+
+    let invalid = || {
+        anyhow::anyhow!(
+            "Variable {} must be a {:?}, got {}",
+            name,
+            variable.kind,
+            value
+        )
+    };
+    let value = coerce(value, variable.kind).ok_or_else(invalid)?;
+
+    if let Some(choices) = &variable.choices {
+        let allowed = choices
+            .iter()
+            .any(|choice| coerce(choice, variable.kind).as_ref() == Some(&value));
+        if !allowed {
+            return Err(anyhow::anyhow!(
+                "Variable {} must be one of {}",
+                name,
+                Value::Array(choices.clone())
+            ));
+        }
+    }
+    Ok(value)
+}
+
+fn coerce(value: &Value, kind: VariableType) -> Option<Value> {
+    match (kind, value) {
+        (VariableType::Number, Value::Number(_)) => Some(value.clone()),
+        (VariableType::Number, Value::String(s)) => {
+            let s = s.trim();
+            s.parse::<i64>()
+                .map(Value::from)
+                .ok()
+                .or_else(|| serde_json::Number::from_f64(s.parse().ok()?).map(Value::Number))
+        }
+        (VariableType::Boolean, Value::Bool(_)) => Some(value.clone()),
+        (VariableType::Boolean, Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" => Some(Value::Bool(true)),
+            "false" | "0" | "no" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        (VariableType::Date, Value::String(s)) => is_date(s.trim()).then(|| Value::from(s.trim())),
+        (VariableType::Identifier, Value::String(s)) => {
+            (!s.trim().is_empty()).then(|| Value::from(s.trim()))
+        }
+        (VariableType::String | VariableType::Raw, Value::String(_)) => Some(value.clone()),
+        (VariableType::String | VariableType::Raw, Value::Number(_) | Value::Bool(_)) => {
+            Some(Value::String(value.to_string()))
+        }
+        _ => None,
+    }
+}
+
+fn is_blank(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(s) => s.is_empty(),
+        _ => false,
+    }
+}
+
+fn is_date(s: &str) -> bool {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok()
+        || NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f").is_ok()
+        || NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f").is_ok()
+        || DateTime::parse_from_rfc3339(s).is_ok()
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Quotes `name` for `dialect`, part by part when it is dotted.
+pub fn quote_identifier(name: &str, dialect: SqlDialect) -> Result<String> {
+    let parts = name
+        .split('.')
+        .map(|part| dialect.quote_ident(part.trim()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(parts.join("."))
+}
+
+/// MySQL and ClickHouse treat a backslash in a string as an escape.
+fn backslash_escapes(dialect: SqlDialect) -> bool {
+    dialect.quote == '`'
+}
+
+fn escape_in_quotes(text: &str, quote: u8, dialect: SqlDialect) -> String {
+    let quote = quote as char;
+    let mut escaped = text.replace(quote, &quote.to_string().repeat(2));
+    if quote != '`' && backslash_escapes(dialect) {
+        escaped = escaped.replace('\\', "\\\\");
+    }
+    escaped
+}
+
+/// A SQL literal for `value` in `dialect`.
+pub fn literal(value: &Value, dialect: SqlDialect) -> String {
+    literal_in_mode(value, dialect, false)
+}
+
+/// Like `literal`, but leaves backslashes alone when the MySQL session runs
+/// with `NO_BACKSLASH_ESCAPES` in its `sql_mode`.
+pub fn literal_in_mode(value: &Value, dialect: SqlDialect, no_backslash_escapes: bool) -> String {
+    let quoted = |text: &str| {
+        if no_backslash_escapes {
+            format!("'{}'", text.replace('\'', "''"))
+        } else {
+            format!("'{}'", escape_in_quotes(text, b'\'', dialect))
+        }
+    };
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Bool(true) => "TRUE".to_string(),
+        Value::Bool(false) => "FALSE".to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => quoted(s),
+        other => quoted(&other.to_string()),
+    }
+}
+
+struct Found {
+    start: usize,
+    end: usize,
+    name: String,
+    /// Quote character of the string or identifier the placeholder is inside.
+    inside: Option<u8>,
+    /// The placeholder is a whole single-quoted literal; `start..end` covers the quotes.
+    quoted: bool,
+}
+
+/// Finds `{{ name }}` placeholders outside of comments.
+fn scan(sql: &str, dialect: SqlDialect) -> Vec<Found> {
+    let bytes = sql.as_bytes();
+    let mut found = Vec::new();
+    let mut inside: Option<u8> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'{' && bytes.get(i + 1) == Some(&b'{') {
+            if let Some((name, end)) = placeholder_at(sql, i) {
+                let quoted = inside == Some(b'\'')
+                    && i > 0
+                    && bytes[i - 1] == b'\''
+                    && bytes.get(end) == Some(&b'\'')
+                    && bytes.get(end + 1) != Some(&b'\'');
+                if quoted {
+                    found.push(Found {
+                        start: i - 1,
+                        end: end + 1,
+                        name,
+                        inside: None,
+                        quoted: true,
+                    });
+                    inside = None;
+                    i = end + 1;
+                } else {
+                    found.push(Found {
+                        start: i,
+                        end,
+                        name,
+                        inside,
+                        quoted: false,
+                    });
+                    i = end;
+                }
+                continue;
+            }
+        }
+
+        let next = bytes.get(i + 1).copied();
+        match (inside, bytes[i]) {
+            (Some(quote), b'\\') if quote != b'`' && backslash_escapes(dialect) => i += 2,
+            (Some(quote), b) if b == quote && next == Some(quote) => i += 2,
+            (Some(quote), b) if b == quote => {
+                inside = None;
+                i += 1;
+            }
+            (Some(_), _) => i += 1,
+            (None, quote @ (b'\'' | b'"' | b'`')) => {
+                inside = Some(quote);
+                i += 1;
+            }
+            (None, b'-') if next == Some(b'-') => {
+                i = bytes[i..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(bytes.len(), |end| i + end + 1);
+            }
+            (None, b'/') if next == Some(b'*') => {
+                i = sql[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |end| i + 2 + end + 2);
+            }
+            _ => i += 1,
+        }
+    }
+    found
+}
+
+/// Parses `{{ name }}` starting at `start`, returning the name and the end offset.
+fn placeholder_at(sql: &str, start: usize) -> Option<(String, usize)> {
+    let close = sql[start + 2..].find("}}")? + start + 2;
+    let name = sql[start + 2..close].trim();
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then(|| (name.to_string(), close + 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn declared(value: Value) -> Vec<TemplateVariable> {
+        parse_variables(Some(&value)).unwrap()
+    }
+
+    fn values(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn binds_values_and_quotes_identifiers() {
+        let variables = declared(json!([
+            { "name": "table", "type": "identifier", "required": true },
+            { "name": "since", "type": "date", "required": true },
+            { "name": "limit", "type": "number", "default": "50", "required": false },
+        ]));
+        let sql = "SELECT * FROM {{table}} WHERE created_at >= '{{since}}' AND note LIKE '%{{ since }}%' -- {{ignored}}\nLIMIT {{limit}} OFFSET {{limit}}";
+        let input = values(json!({ "table": "public.users", "since": "2024-01-31" }));
+
+        let bound = render(
+            sql,
+            &variables,
+            &input,
+            SqlDialect::POSTGRES,
+            RenderMode::Bind,
+        )
+        .unwrap();
+        assert_eq!(
+            bound.sql,
+            "SELECT * FROM \"public\".\"users\" WHERE created_at >= $1 AND note LIKE '%2024-01-31%' -- {{ignored}}\nLIMIT $2 OFFSET $2"
+        );
+        assert_eq!(bound.params, vec![json!("2024-01-31"), json!(50)]);
+
+        let bound = render(sql, &variables, &input, SqlDialect::MYSQL, RenderMode::Bind).unwrap();
+        assert!(bound.sql.contains("LIMIT ? OFFSET ?"));
+        assert_eq!(bound.params.len(), 3);
+    }
+
+    #[test]
+    fn inlines_escaped_literals() {
+        let variables = declared(json!([
+            { "name": "name", "required": true },
+            { "name": "active", "type": "boolean", "required": false },
+        ]));
+        let input = values(json!({ "name": r"O'Brien \", "active": "yes" }));
+        let sql = "SELECT {{name}}, {{active}}, {{missing}}";
+
+        let inlined = render(
+            sql,
+            &variables,
+            &input,
+            SqlDialect::MYSQL,
+            RenderMode::Inline,
+        );
+        assert_eq!(
+            inlined.unwrap_err().to_string(),
+            "Variable missing is required"
+        );
+
+        let mut input = input;
+        input.insert("missing".to_string(), json!(7));
+        let inlined = render(
+            sql,
+            &variables,
+            &input,
+            SqlDialect::MYSQL,
+            RenderMode::Inline,
+        )
+        .unwrap();
+        assert_eq!(inlined.sql, r"SELECT 'O''Brien \\', TRUE, '7'");
+        let inlined = render(
+            sql,
+            &variables,
+            &input,
+            SqlDialect::SQLITE,
+            RenderMode::Inline,
+        )
+        .unwrap();
+        assert_eq!(inlined.sql, r"SELECT 'O''Brien \', TRUE, '7'");
+    }
+
+    #[test]
+    fn reports_every_invalid_value() {
+        let variables = declared(json!([
+            { "name": "n", "type": "number", "required": true },
+            { "name": "day", "type": "date", "required": true },
+            { "name": "dir", "type": "raw", "required": true, "choices": ["ASC", "DESC"] },
+        ]));
+        let input = values(json!({ "n": "ten", "day": "31/01/2024", "dir": "UP" }));
+        let err = render(
+            "SELECT {{n}}, {{day}} ORDER BY 1 {{dir}}",
+            &variables,
+            &input,
+            SqlDialect::SQLITE,
+            RenderMode::Bind,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("Variable n must be a Number"));
+        assert!(err.contains("Variable day must be a Date"));
+        assert!(err.contains("Variable dir must be one of [\"ASC\",\"DESC\"]"));
+    }
+
+    #[test]
+    fn takes_raw_sql_only_from_its_choices() {
+        let sql = "SELECT * FROM t ORDER BY id {{dir}}";
+        let input = values(json!({ "dir": "; DROP TABLE t" }));
+
+        let listed = declared(json!([
+            { "name": "dir", "type": "raw", "required": true, "choices": ["ASC", "DESC"] },
+        ]));
+        let err = render(sql, &listed, &input, SqlDialect::POSTGRES, RenderMode::Bind).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Variable dir must be one of [\"ASC\",\"DESC\"]"
+        );
+        let rendered = render(
+            sql,
+            &listed,
+            &values(json!({ "dir": "DESC" })),
+            SqlDialect::POSTGRES,
+            RenderMode::Bind,
+        )
+        .unwrap();
+        assert_eq!(rendered.sql, "SELECT * FROM t ORDER BY id DESC");
+
+        let unlisted = declared(json!([{ "name": "dir", "type": "raw", "required": true }]));
+        for mode in [RenderMode::Bind, RenderMode::Inline] {
+            let err = render(sql, &unlisted, &input, SqlDialect::MYSQL, mode).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "Variable dir is raw SQL and must list its choices"
+            );
+        }
+        let shown = render(
+            sql,
+            &unlisted,
+            &input,
+            SqlDialect::MYSQL,
+            RenderMode::Display,
+        )
+        .unwrap();
+        assert_eq!(shown.sql, "SELECT * FROM t ORDER BY id ; DROP TABLE t");
+    }
+}
@@ -0,0 +1,124 @@
+use super::query::bind_json_value;
+use super::SQLiteDriver;
+use crate::services::bulk::{self, BulkProgressFn, RowChange};
+use crate::services::driver::BulkOperationsDriver;
+use crate::services::table_filter::{SqlDialect, SqlFragment};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// `SQLITE_MAX_VARIABLE_NUMBER` of SQLite 3.32 and later.
+const MAX_PARAMS: usize = 32_766;
+/// Rows between progress reports for updates and deletes.
+const PROGRESS_ROWS: usize = 1000;
+
+#[async_trait]
+impl BulkOperationsDriver for SQLiteDriver {
+    /// Multi-row prepared `INSERT`s in one transaction. Full chunks share
+    /// one statement, which the connection prepares once.
+    async fn bulk_insert(
+        &self,
+        schema: &str,
+        table: &str,
+        columns: &[String],
+        rows: &[Vec<Value>],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        if rows.is_empty() {
+            return Ok(0);
+        }
+        if columns.is_empty() {
+            return Err(anyhow!("No columns to insert"));
+        }
+        if let Some(row) = rows.iter().find(|row| row.len() != columns.len()) {
+            return Err(anyhow!(
+                "Row has {} values for {} columns",
+                row.len(),
+                columns.len()
+            ));
+        }
+        let dialect = SqlDialect::SQLITE;
+        let names = columns
+            .iter()
+            .map(|column| dialect.quote_ident(column))
+            .collect::<Result<Vec<_>>>()?;
+        let prefix = format!(
+            "INSERT INTO {} ({}) VALUES ",
+            bulk::table_ref(dialect, schema, table)?,
+            names.join(", ")
+        );
+        let tuple = format!("({})", vec!["?"; columns.len()].join(", "));
+        let rows_per_statement = (MAX_PARAMS / columns.len()).max(1);
+
+        let mut tx = self.pool().begin().await?;
+        let mut affected = 0;
+        let mut written = 0;
+        for chunk in rows.chunks(rows_per_statement) {
+            let sql = format!("{}{}", prefix, vec![tuple.as_str(); chunk.len()].join(", "));
+            let mut statement = sqlx::query(&sql);
+            for value in chunk.iter().flatten() {
+                statement = bind_json_value(statement, value);
+            }
+            affected += statement.execute(&mut *tx).await?.rows_affected();
+            written += chunk.len();
+            progress(written as u64);
+        }
+        tx.commit().await?;
+        Ok(affected)
+    }
+
+    async fn bulk_update(
+        &self,
+        schema: &str,
+        table: &str,
+        changes: &[RowChange],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let table = bulk::table_ref(SqlDialect::SQLITE, schema, table)?;
+        let statements = changes
+            .iter()
+            .map(|change| bulk::update_statement(SqlDialect::SQLITE, &table, change))
+            .collect::<Result<Vec<_>>>()?;
+        self.execute_in_transaction(&statements, progress).await
+    }
+
+    async fn bulk_delete(
+        &self,
+        schema: &str,
+        table: &str,
+        primary_keys: &[HashMap<String, Value>],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let table = bulk::table_ref(SqlDialect::SQLITE, schema, table)?;
+        let statements = primary_keys
+            .iter()
+            .map(|key| bulk::delete_statement(SqlDialect::SQLITE, &table, key))
+            .collect::<Result<Vec<_>>>()?;
+        self.execute_in_transaction(&statements, progress).await
+    }
+}
+
+impl SQLiteDriver {
+    async fn execute_in_transaction(
+        &self,
+        statements: &[SqlFragment],
+        progress: &BulkProgressFn<'_>,
+    ) -> Result<u64> {
+        let mut tx = self.pool().begin().await?;
+        let mut affected = 0;
+        for (i, fragment) in statements.iter().enumerate() {
+            let mut statement = sqlx::query(&fragment.sql);
+            for value in &fragment.params {
+                statement = bind_json_value(statement, value);
+            }
+            affected += statement.execute(&mut *tx).await?.rows_affected();
+            if (i + 1) % PROGRESS_ROWS == 0 {
+                progress((i + 1) as u64);
+            }
+        }
+        tx.commit().await?;
+        progress(statements.len() as u64);
+        Ok(affected)
+    }
+}
@@ -1,6 +1,7 @@
 use super::SQLiteDriver;
 use crate::models::export_ddl::{DdlObjectType, DdlScope, ExportDdlOptions};
 use crate::services::driver::ddl_export::DdlExportDriver;
+use crate::services::schema_diff::{sqlite_extractor::SqliteSchemaExtractor, SchemaSnapshot};
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use sqlx::{Row, SqlitePool};
@@ -78,4 +79,11 @@ impl DdlExportDriver for SQLiteDriver {
         let exporter = SQLiteDdlExport::new(self.pool().clone());
         exporter.export_ddl(options).await
     }
+
+    async fn schema_snapshot(&self, schema: &str) -> Result<SchemaSnapshot> {
+        SqliteSchemaExtractor::new(self.pool().clone())
+            .extract_schema(schema)
+            .await
+            .map_err(anyhow::Error::msg)
+    }
 }
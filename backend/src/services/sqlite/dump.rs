@@ -0,0 +1,100 @@
+use super::SQLiteDriver;
+use crate::services::bulk;
+use crate::services::driver::DumpDriver;
+use crate::services::dump::{self, DumpEvent, DUMP_BATCH_SIZE};
+use crate::services::table_filter::SqlDialect;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::TryStreamExt;
+use sqlx::Row;
+use tokio::sync::mpsc;
+
+#[async_trait]
+impl DumpDriver for SQLiteDriver {
+    /// Reads every table in one read transaction. Values are written by
+    /// SQLite's own `quote()`, which gives blobs as `X'..'` and reals with
+    /// enough digits to read back the same.
+    async fn dump_rows(
+        &self,
+        schema: &str,
+        tables: &[String],
+        tx: &mpsc::Sender<DumpEvent>,
+    ) -> Result<u64> {
+        let dialect = SqlDialect::SQLITE;
+        let mut conn = self.pool().begin().await?;
+
+        let mut sent = 0;
+        for table in tables {
+            // `hidden` is 2 or 3 for generated columns.
+            let columns: Vec<String> = sqlx::query_scalar(
+                "SELECT name FROM pragma_table_xinfo(?, ?) WHERE hidden = 0 ORDER BY cid",
+            )
+            .bind(table)
+            .bind(schema)
+            .fetch_all(&mut *conn)
+            .await?;
+            if columns.is_empty() {
+                continue;
+            }
+            let names = columns
+                .iter()
+                .map(|column| dialect.quote_ident(column))
+                .collect::<Result<Vec<_>>>()?;
+            dump::send(
+                tx,
+                DumpEvent::Table {
+                    table: dialect.quote_ident(table)?,
+                    columns: names.clone(),
+                },
+            )
+            .await?;
+
+            let quoted: Vec<String> = names
+                .iter()
+                .map(|name| format!("quote({})", name))
+                .collect();
+            let select = format!(
+                "SELECT {} FROM {}",
+                quoted.join(", "),
+                bulk::table_ref(dialect, schema, table)?
+            );
+            let mut rows = sqlx::query(&select).fetch(&mut *conn);
+            let mut batch = Vec::with_capacity(DUMP_BATCH_SIZE);
+            while let Some(row) = rows.try_next().await? {
+                let values = (0..names.len())
+                    .map(|i| row.try_get::<String, _>(i))
+                    .collect::<Result<Vec<_>, _>>()?;
+                batch.push(values);
+                if batch.len() == DUMP_BATCH_SIZE {
+                    sent += batch.len() as u64;
+                    dump::send(tx, DumpEvent::Rows(std::mem::take(&mut batch))).await?;
+                }
+            }
+            if !batch.is_empty() {
+                sent += batch.len() as u64;
+                dump::send(tx, DumpEvent::Rows(batch)).await?;
+            }
+        }
+        conn.rollback().await?;
+        Ok(sent)
+    }
+
+    /// `CREATE TABLE` covers neither indexes nor triggers. Indexes SQLite
+    /// creates for constraints have no SQL and come back with the table.
+    async fn dump_table_extras(&self, schema: &str, table: &str) -> Result<Vec<String>> {
+        let sql = format!(
+            "SELECT sql FROM {}.sqlite_master \
+             WHERE tbl_name = ? AND type IN ('index', 'trigger') AND sql IS NOT NULL \
+             ORDER BY type, name",
+            SqlDialect::SQLITE.quote_ident(schema)?
+        );
+        let statements: Vec<String> = sqlx::query_scalar(&sql)
+            .bind(table)
+            .fetch_all(self.pool())
+            .await?;
+        Ok(statements
+            .into_iter()
+            .map(|statement| format!("{};", statement))
+            .collect())
+    }
+}
@@ -1,10 +1,13 @@
+mod bulk;
 mod column;
 mod connection;
 mod ddl_export;
+mod dump;
 mod function;
 mod query;
 mod schema;
 mod table;
+mod transaction;
 mod view;
 
 pub use column::SQLiteColumn;
@@ -13,15 +16,20 @@ pub use function::SQLiteFunction;
 pub use query::SQLiteQuery;
 pub use schema::SQLiteSchema;
 pub use table::SQLiteTable;
+pub use transaction::SQLiteTransaction;
 pub use view::SQLiteView;
 
 use super::db_driver::QueryResult;
 use super::driver::{
-    ColumnManagement, ConnectionDriver, FunctionOperations, QueryDriver, SchemaIntrospection,
-    TableOperations, ViewOperations,
+    ColumnManagement, ConnectionDriver, ConnectionPoolDriver, FunctionOperations, QueryDriver,
+    SchemaIntrospection, TableOperations, ViewOperations,
 };
 use crate::models::entities::connection as ConnectionModel;
 use crate::services::driver::extension::DatabaseManagementDriver;
+use crate::services::query_cancel::QueryHandle;
+use crate::services::query_limits::QueryLimits;
+use crate::services::query_stream::{QueryStreamSink, StreamOptions};
+use crate::services::table_filter::{FilterNode, SortSpec};
 use anyhow::Result;
 use async_trait::async_trait;
 use sqlx::SqlitePool;
@@ -57,7 +65,9 @@ impl SQLiteDriver {
         let pool = conn.pool().clone();
 
         Ok(Self {
-            query: SQLiteQuery::new(pool.clone()),
+            query: SQLiteQuery::new(pool.clone()).with_statement_timeout(
+                QueryLimits::from_connection(connection).statement_timeout,
+            ),
             schema: SQLiteSchema::new(pool.clone()),
             table: SQLiteTable::new(pool.clone()),
             column: SQLiteColumn::new(pool.clone()),
@@ -71,6 +81,21 @@ impl SQLiteDriver {
     }
 }
 
+#[async_trait]
+impl ConnectionPoolDriver for SQLiteDriver {
+    async fn get_pool_size(&self) -> Result<usize> {
+        Ok(self.pool().size() as usize)
+    }
+
+    async fn get_active_connections(&self) -> Result<usize> {
+        Ok((self.pool().size() as usize).saturating_sub(self.pool().num_idle()))
+    }
+
+    async fn get_idle_connections(&self) -> Result<usize> {
+        Ok(self.pool().num_idle())
+    }
+}
+
 #[async_trait]
 impl ConnectionDriver for SQLiteDriver {
     async fn test_connection(&self) -> Result<()> {
@@ -92,6 +117,39 @@ impl QueryDriver for SQLiteDriver {
         self.query.execute_query(query).await
     }
 
+    async fn execute_query_cancellable(
+        &self,
+        query: &str,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        self.query.execute_query_cancellable(query, handle).await
+    }
+
+    async fn stream_query(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+    ) -> Result<u64> {
+        self.query.stream_query(query, options, sink).await
+    }
+
+    async fn stream_query_cancellable(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+        handle: &QueryHandle,
+    ) -> Result<u64> {
+        self.query
+            .stream_query_cancellable(query, options, sink, handle)
+            .await
+    }
+
+    async fn open_session(&self) -> Result<Box<dyn super::driver::StatementSession + '_>> {
+        self.query.open_session().await
+    }
+
     async fn execute_script(&self, script: &str) -> Result<u64> {
         self.query.execute_script(script).await
     }
@@ -154,12 +212,13 @@ impl TableOperations for SQLiteDriver {
         table: &str,
         limit: i64,
         offset: i64,
-        filter: Option<String>,
+        filter: Option<FilterNode>,
+        sort: Vec<SortSpec>,
         document_id: Option<String>,
         fields: Option<Vec<String>>,
     ) -> Result<QueryResult> {
         self.table
-            .get_table_data(schema, table, limit, offset, filter, document_id, fields)
+            .get_table_data(schema, table, limit, offset, filter, sort, document_id, fields)
             .await
     }
 
@@ -379,3 +438,38 @@ impl super::driver::SessionOperations for SQLiteDriver {
         ))
     }
 }
+
+#[async_trait]
+impl super::driver::TransactionDriver for SQLiteDriver {
+    async fn begin_transaction(&self) -> Result<Box<dyn super::driver::TransactionSession>> {
+        Ok(Box::new(
+            SQLiteTransaction::begin(self.pool(), self.query.statement_timeout()).await?,
+        ))
+    }
+}
+
+#[async_trait]
+impl super::driver::PreparedStatementDriver for SQLiteDriver {
+    async fn execute_with_params(
+        &self,
+        query: &str,
+        params: &crate::services::query_params::QueryParams,
+    ) -> Result<QueryResult> {
+        super::driver::PreparedStatementDriver::execute_with_params(&self.query, query, params).await
+    }
+
+    async fn execute_with_params_cancellable(
+        &self,
+        query: &str,
+        params: &crate::services::query_params::QueryParams,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        super::driver::PreparedStatementDriver::execute_with_params_cancellable(
+            &self.query,
+            query,
+            params,
+            handle,
+        )
+        .await
+    }
+}
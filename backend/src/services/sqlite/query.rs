@@ -1,204 +1,231 @@
 use crate::services::db_driver::QueryResult;
-use crate::services::driver::{ConnectionDriver, QueryDriver};
+use crate::services::driver::{
+    ConnectionDriver, PreparedStatementDriver, QueryDriver, StatementSession,
+};
+use crate::services::query_cancel::{QueryHandle, ServerCancel};
+use crate::services::query_limits;
+use crate::services::query_paging::count_sql;
+use crate::services::query_params::{self, QueryParams};
+use crate::services::query_stream::{base_query, is_select_like, QueryStreamSink, StreamOptions};
+use crate::services::table_filter::SqlDialect;
 use anyhow::Result;
 use async_trait::async_trait;
-use bytes::Bytes;
 use futures_util::StreamExt;
+use libsqlite3_sys::sqlite3;
 use serde_json::Value;
-use sqlx::Column;
-use sqlx::{sqlite::SqlitePool, Row};
-use tokio::sync::mpsc;
+use sqlx::pool::PoolConnection;
+use sqlx::sqlite::{Sqlite, SqliteArguments, SqliteConnection, SqlitePool, SqliteRow};
+use sqlx::{Column, Row};
+use std::ptr::NonNull;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 pub struct SQLiteQuery {
     pool: SqlitePool,
+    statement_timeout: Option<Duration>,
 }
 
 impl SQLiteQuery {
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            statement_timeout: None,
+        }
+    }
+
+    /// Statements running longer than `timeout` are interrupted.
+    pub fn with_statement_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.statement_timeout = timeout;
+        self
     }
 
     pub fn pool(&self) -> &SqlitePool {
         &self.pool
     }
 
-    pub async fn stream_ndjson(
-        &self,
-        query: &str,
-        limit: Option<i64>,
-        offset: Option<i64>,
-        include_total_count: bool,
-        tx: mpsc::Sender<Bytes>,
-    ) -> Result<()> {
-        fn is_select_like(sql: &str) -> bool {
-            let trimmed = sql.trim_start();
-            let upper = trimmed.to_uppercase();
-            upper.starts_with("SELECT") || upper.starts_with("WITH")
-        }
+    pub fn statement_timeout(&self) -> Option<Duration> {
+        self.statement_timeout
+    }
+}
+
+#[async_trait]
+impl ConnectionDriver for SQLiteQuery {
+    async fn test_connection(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl QueryDriver for SQLiteQuery {
+    async fn execute(&self, query: &str) -> Result<u64> {
+        let result = sqlx::query(query).execute(&self.pool).await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn query(&self, query: &str) -> Result<QueryResult> {
+        let query_result = sqlx::query(query).fetch_all(&self.pool).await?;
+        Ok(rows_to_result(query_result))
+    }
 
-        async fn send_line(tx: &mpsc::Sender<Bytes>, value: serde_json::Value) -> bool {
-            let mut buf = serde_json::to_vec(&value).unwrap_or_else(|_| {
-                b"{\"type\":\"error\",\"message\":\"serialization failed\"}".to_vec()
-            });
-            buf.push(b'\n');
-            tx.send(Bytes::from(buf)).await.is_ok()
+    async fn execute_query(&self, query: &str) -> Result<QueryResult> {
+        let mut conn = self.pool.acquire().await?;
+        let result = run_on_connection(&mut conn, query, self.statement_timeout).await;
+        if result.is_err() {
+            end_open_transaction(&mut conn).await;
         }
+        result
+    }
 
-        let trimmed = query.trim();
-        let is_select = is_select_like(trimmed);
-        if !is_select {
-            let result = sqlx::query(trimmed).execute(&self.pool).await?;
-            let _ = send_line(
-                &tx,
-                serde_json::json!({ "type": "done", "affected_rows": result.rows_affected() }),
+    async fn execute_query_cancellable(
+        &self,
+        query: &str,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let mut conn = self.pool.acquire().await?;
+        let interrupt = interrupt_for(&mut conn).await?;
+        // Declared after `conn`, so it is dropped (and the handle forgotten)
+        // before the connection goes back to the pool.
+        let _interrupt_guard = InterruptGuard(interrupt.clone());
+        let result = handle
+            .run(
+                interrupt,
+                run_on_connection(&mut conn, query, self.statement_timeout),
             )
             .await;
-            return Ok(());
+        if result.is_err() {
+            end_open_transaction(&mut conn).await;
         }
+        result
+    }
 
-        let base = trimmed.trim_end_matches(';').to_string();
-        let (sql_to_run, meta_limit, meta_offset, total_count) =
-            if limit.is_some() || offset.is_some() || include_total_count {
-                let meta_limit = limit.unwrap_or(1000).max(1);
-                let meta_offset = offset.unwrap_or(0).max(0);
-                let page_sql = format!(
-                    "SELECT * FROM ({}) AS __dbplus_subq LIMIT {} OFFSET {}",
-                    base, meta_limit, meta_offset
-                );
-
-                let total_count = if include_total_count {
-                    let count_sql =
-                        format!("SELECT COUNT(*) AS count FROM ({}) AS __dbplus_subq", base);
-                    let row = sqlx::query(&count_sql).fetch_one(&self.pool).await?;
-                    row.try_get::<i64, _>(0).ok()
-                } else {
-                    None
-                };
-
-                (page_sql, Some(meta_limit), Some(meta_offset), total_count)
-            } else {
-                (base, None, None, None)
-            };
-
-        let mut stream = sqlx::query(&sql_to_run).fetch(&self.pool);
-        let first = match stream.next().await {
-            None => {
-                let _ = send_line(&tx, serde_json::json!({ "type": "meta", "columns": [] })).await;
-                let _ = send_line(&tx, serde_json::json!({ "type": "done", "row_count": 0 })).await;
-                return Ok(());
-            }
-            Some(Err(e)) => return Err(e.into()),
-            Some(Ok(row)) => row,
+    async fn stream_query(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+    ) -> Result<u64> {
+        let mut conn = self.pool.acquire().await?;
+        let deadline = StatementDeadline::arm(&mut conn, self.statement_timeout).await?;
+        let result = stream_on_connection(&mut conn, query, options, sink).await;
+        StatementDeadline::check(deadline, result)
+    }
+
+    async fn stream_query_cancellable(
+        &self,
+        query: &str,
+        options: &StreamOptions,
+        sink: &mut QueryStreamSink,
+        handle: &QueryHandle,
+    ) -> Result<u64> {
+        let mut conn = self.pool.acquire().await?;
+        let interrupt = interrupt_for(&mut conn).await?;
+        let _interrupt_guard = InterruptGuard(interrupt.clone());
+        let statement = async {
+            let deadline = StatementDeadline::arm(&mut conn, self.statement_timeout).await?;
+            let result = stream_on_connection(&mut conn, query, options, sink).await;
+            StatementDeadline::check(deadline, result)
         };
+        handle.run(interrupt, statement).await
+    }
 
-        let column_count = first.len();
-        let columns: Vec<String> = first
-            .columns()
-            .iter()
-            .enumerate()
-            .map(|(i, c)| {
-                let name = c.name();
-                if name.is_empty() {
-                    format!("column_{}", i)
-                } else {
-                    name.to_string()
-                }
-            })
-            .collect();
-
-        let _ = send_line(
-            &tx,
-            serde_json::json!({
-                "type": "meta",
-                "columns": columns,
-                "total_count": total_count,
-                "limit": meta_limit,
-                "offset": meta_offset,
-            }),
-        )
-        .await;
-
-        async fn emit_row(
-            tx: &mpsc::Sender<Bytes>,
-            column_count: usize,
-            row: sqlx::sqlite::SqliteRow,
-        ) -> bool {
-            let mut out = Vec::with_capacity(column_count);
-            for i in 0..column_count {
-                let value: Value = if let Ok(Some(v)) = row.try_get::<Option<i64>, _>(i) {
-                    Value::Number(v.into())
-                } else if let Ok(Some(v)) = row.try_get::<Option<f64>, _>(i) {
-                    serde_json::Number::from_f64(v)
-                        .map(Value::Number)
-                        .unwrap_or(Value::Null)
-                } else if let Ok(Some(v)) = row.try_get::<Option<String>, _>(i) {
-                    Value::String(v)
-                } else if let Ok(Some(v)) = row.try_get::<Option<bool>, _>(i) {
-                    Value::Bool(v)
-                } else {
-                    Value::Null
-                };
-                out.push(value);
-            }
-            send_line(tx, serde_json::json!({ "type": "row", "row": out })).await
+    async fn open_session(&self) -> Result<Box<dyn StatementSession + '_>> {
+        let conn = self.pool.acquire().await?;
+        Ok(Box::new(SQLiteSession {
+            conn: Some(conn),
+            statement_timeout: self.statement_timeout,
+        }))
+    }
+
+    async fn execute_script(&self, script: &str) -> Result<u64> {
+        let statements = crate::utils::sql_script::split_sql_statements(script);
+        if statements.is_empty() {
+            return Ok(0);
         }
 
-        let mut row_count: u64 = 0;
-        if !emit_row(&tx, column_count, first).await {
-            return Ok(());
+        let mut tx = self.pool.begin().await?;
+        let mut count = 0u64;
+        for stmt in statements {
+            let trimmed = stmt.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            sqlx::query(trimmed).execute(&mut *tx).await?;
+            count += 1;
         }
-        row_count += 1;
-
-        while let Some(item) = stream.next().await {
-            let row = match item {
-                Ok(r) => r,
-                Err(e) => {
-                    let _ = send_line(
-                        &tx,
-                        serde_json::json!({ "type": "error", "message": e.to_string() }),
-                    )
-                    .await;
-                    break;
+        tx.commit().await?;
+        Ok(count)
+    }
+
+    async fn explain(&self, query: &str, _analyze: bool) -> Result<Value> {
+        let explain_query = format!("EXPLAIN QUERY PLAN {}", query);
+        let result = self.query(&explain_query).await?;
+
+        let mut plan = Vec::new();
+        for row in result.rows {
+            let mut node = serde_json::Map::new();
+            for (i, col) in result.columns.iter().enumerate() {
+                if let Some(val) = row.get(i) {
+                    node.insert(col.clone(), val.clone());
                 }
-            };
-            if !emit_row(&tx, column_count, row).await {
-                break;
             }
-            row_count += 1;
+            plan.push(Value::Object(node));
         }
 
-        let _ = send_line(
-            &tx,
-            serde_json::json!({ "type": "done", "row_count": row_count }),
-        )
-        .await;
-        Ok(())
+        Ok(Value::Array(plan))
     }
 }
 
+/// SQLite converts bound values by the affinity of the columns they are
+/// stored in or compared with.
 #[async_trait]
-impl ConnectionDriver for SQLiteQuery {
-    async fn test_connection(&self) -> Result<()> {
-        sqlx::query("SELECT 1").execute(&self.pool).await?;
-        Ok(())
+impl PreparedStatementDriver for SQLiteQuery {
+    async fn execute_with_params(&self, query: &str, params: &QueryParams) -> Result<QueryResult> {
+        let mut conn = self.pool.acquire().await?;
+        run_with_params(&mut conn, query, params, self.statement_timeout).await
     }
-}
 
-#[async_trait]
-impl QueryDriver for SQLiteQuery {
-    async fn execute(&self, query: &str) -> Result<u64> {
-        let result = sqlx::query(query).execute(&self.pool).await?;
-        Ok(result.rows_affected())
+    async fn execute_with_params_cancellable(
+        &self,
+        query: &str,
+        params: &QueryParams,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let mut conn = self.pool.acquire().await?;
+        let interrupt = interrupt_for(&mut conn).await?;
+        let _interrupt_guard = InterruptGuard(interrupt.clone());
+        handle
+            .run(
+                interrupt,
+                run_with_params(&mut conn, query, params, self.statement_timeout),
+            )
+            .await
     }
+}
 
-    async fn query(&self, query: &str) -> Result<QueryResult> {
-        let query_result = sqlx::query(query).fetch_all(&self.pool).await?;
+async fn run_with_params(
+    conn: &mut SqliteConnection,
+    query: &str,
+    params: &QueryParams,
+    timeout: Option<Duration>,
+) -> Result<QueryResult> {
+    let bound = query_params::bind(query, params, SqlDialect::SQLITE)?;
+    let mut statement = sqlx::query(&bound.sql);
+    for value in &bound.params {
+        statement = bind_json_value(statement, value);
+    }
 
-        if query_result.is_empty() {
-            return Ok(QueryResult {
+    let deadline = StatementDeadline::arm(conn, timeout).await?;
+    let result = if is_select_like(query) {
+        read_rows(statement, conn).await
+    } else {
+        statement
+            .execute(&mut *conn)
+            .await
+            .map(|result| QueryResult {
                 columns: vec![],
                 rows: vec![],
-                affected_rows: 0,
+                affected_rows: result.rows_affected(),
                 column_metadata: None,
                 total_count: None,
                 limit: None,
@@ -208,45 +235,182 @@ impl QueryDriver for SQLiteQuery {
                 execution_time_ms: None,
                 json: None,
                 display_mode: None,
-            });
-        }
+                truncated: None,
+            })
+    };
+    StatementDeadline::check(deadline, result.map_err(Into::into))
+}
+
+/// Streams a SELECT's rows from `conn`; other statements are just executed.
+async fn stream_on_connection(
+    conn: &mut SqliteConnection,
+    query: &str,
+    options: &StreamOptions,
+    sink: &mut QueryStreamSink,
+) -> Result<u64> {
+    if !is_select_like(query) {
+        let result = sqlx::query(query.trim()).execute(&mut *conn).await?;
+        return Ok(result.rows_affected());
+    }
 
-        let first_row = &query_result[0];
-        let column_count = first_row.len();
-        let mut columns = Vec::new();
+    let base = base_query(query);
+    let (sql_to_run, limit, offset) = options.paged_sql(base);
+    let total_count = if options.include_total_count {
+        let row = sqlx::query(&count_sql(base)).fetch_one(&mut *conn).await?;
+        row.try_get::<i64, _>(0).ok()
+    } else {
+        None
+    };
 
-        for i in 0..column_count {
-            columns.push(format!("column_{}", i));
+    let mut stream = sqlx::query(&sql_to_run).fetch(&mut *conn);
+    let first = match stream.next().await {
+        None => {
+            sink.meta(vec![], total_count, limit, offset).await;
+            return Ok(0);
         }
+        Some(row) => row?,
+    };
 
-        let mut result_rows = Vec::new();
-        for row in query_result {
-            let mut current_row = Vec::new();
-            for i in 0..column_count {
-                let value: Value = if let Ok(v) = row.try_get::<i32, _>(i) {
-                    Value::Number(v.into())
-                } else if let Ok(v) = row.try_get::<i64, _>(i) {
-                    Value::Number(v.into())
-                } else if let Ok(v) = row.try_get::<f64, _>(i) {
-                    serde_json::Number::from_f64(v)
-                        .map(Value::Number)
-                        .unwrap_or(Value::Null)
-                } else if let Ok(v) = row.try_get::<String, _>(i) {
-                    Value::String(v)
-                } else if let Ok(v) = row.try_get::<bool, _>(i) {
-                    Value::Bool(v)
-                } else {
-                    Value::Null
-                };
-                current_row.push(value);
+    let column_count = first.len();
+    let columns: Vec<String> = first
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let name = c.name();
+            if name.is_empty() {
+                format!("column_{}", i)
+            } else {
+                name.to_string()
             }
-            result_rows.push(current_row);
+        })
+        .collect();
+    if !sink.meta(columns, total_count, limit, offset).await
+        || !sink.row(decode_stream_row(&first, column_count)).await
+    {
+        return Ok(0);
+    }
+
+    while let Some(row) = stream.next().await {
+        if !sink.row(decode_stream_row(&row?, column_count)).await {
+            break;
         }
+    }
+    Ok(0)
+}
 
+/// Decodes by value rather than declared type, since SQLite columns are dynamically typed.
+fn decode_stream_row(row: &SqliteRow, column_count: usize) -> Vec<Value> {
+    let mut out = Vec::with_capacity(column_count);
+    for i in 0..column_count {
+        let value: Value = if let Ok(Some(v)) = row.try_get::<Option<i64>, _>(i) {
+            Value::Number(v.into())
+        } else if let Ok(Some(v)) = row.try_get::<Option<f64>, _>(i) {
+            serde_json::Number::from_f64(v)
+                .map(Value::Number)
+                .unwrap_or(Value::Null)
+        } else if let Ok(Some(v)) = row.try_get::<Option<String>, _>(i) {
+            Value::String(v)
+        } else if let Ok(Some(v)) = row.try_get::<Option<bool>, _>(i) {
+            Value::Bool(v)
+        } else {
+            Value::Null
+        };
+        out.push(value);
+    }
+    out
+}
+
+/// A connection taken out of the pool for `open_session`.
+struct SQLiteSession {
+    conn: Option<PoolConnection<Sqlite>>,
+    statement_timeout: Option<Duration>,
+}
+
+impl Drop for SQLiteSession {
+    fn drop(&mut self) {
+        // Temporary tables, PRAGMAs and open transactions must not leak into
+        // the pool, so the connection is closed.
+        if let Some(conn) = self.conn.take() {
+            drop(conn.detach());
+        }
+    }
+}
+
+#[async_trait]
+impl StatementSession for SQLiteSession {
+    async fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
+        let conn = self.conn.as_mut().expect("session already closed");
+        run_on_connection(conn, query, self.statement_timeout).await
+    }
+
+    async fn execute_query_cancellable(
+        &mut self,
+        query: &str,
+        handle: &QueryHandle,
+    ) -> Result<QueryResult> {
+        let conn = self.conn.as_mut().expect("session already closed");
+        let interrupt = interrupt_for(conn).await?;
+        // Forgets the handle once the statement ends; the connection stays
+        // open for the next statement of the session.
+        let _interrupt_guard = InterruptGuard(interrupt.clone());
+        handle
+            .run(
+                interrupt,
+                run_on_connection(conn, query, self.statement_timeout),
+            )
+            .await
+    }
+}
+
+/// Runs `query` on one specific connection instead of any pooled one,
+/// interrupting it after `timeout`.
+pub(super) async fn run_on_connection(
+    conn: &mut SqliteConnection,
+    query: &str,
+    timeout: Option<Duration>,
+) -> Result<QueryResult> {
+    let deadline = StatementDeadline::arm(conn, timeout).await?;
+    let result = run_statement(conn, query).await;
+    StatementDeadline::check(deadline, result)
+}
+
+/// Rolls back a transaction that a failed statement left open on a pooled
+/// connection, such as a schema migration's table rebuild that stopped
+/// between `BEGIN` and `COMMIT`, and turns foreign keys back on, as the
+/// rebuild turns them off first. Otherwise the next user of the connection
+/// would run inside that transaction without foreign key checks.
+async fn end_open_transaction(conn: &mut SqliteConnection) {
+    let in_transaction = match conn.lock_handle().await {
+        // SAFETY: the handle is locked, so the connection is alive.
+        Ok(mut locked) => unsafe {
+            libsqlite3_sys::sqlite3_get_autocommit(locked.as_raw_handle().as_ptr()) == 0
+        },
+        Err(_) => false,
+    };
+    if !in_transaction {
+        return;
+    }
+    for sql in ["ROLLBACK", "PRAGMA foreign_keys = ON"] {
+        if let Err(e) = sqlx::query(sql).execute(&mut *conn).await {
+            tracing::warn!(
+                "[SQLiteQuery] {} after a failed statement failed: {}",
+                sql,
+                e
+            );
+        }
+    }
+}
+
+async fn run_statement(conn: &mut SqliteConnection, query: &str) -> Result<QueryResult> {
+    if is_select_like(query) {
+        Ok(read_rows(sqlx::query(query), conn).await?)
+    } else {
+        let result = sqlx::query(query).execute(&mut *conn).await?;
         Ok(QueryResult {
-            columns,
-            rows: result_rows,
-            affected_rows: 0,
+            columns: vec![],
+            rows: vec![],
+            affected_rows: result.rows_affected(),
             column_metadata: None,
             total_count: None,
             limit: None,
@@ -256,69 +420,242 @@ impl QueryDriver for SQLiteQuery {
             execution_time_ms: None,
             json: None,
             display_mode: None,
+            truncated: None,
         })
     }
+}
 
-    async fn execute_query(&self, query: &str) -> Result<QueryResult> {
-        let query_upper = query.trim_start().to_uppercase();
-        let is_select = query_upper.starts_with("SELECT") || query_upper.starts_with("WITH");
+/// Binds a JSON value as the closest SQLite storage class.
+pub(super) fn bind_json_value<'q>(
+    query: sqlx::query::Query<'q, Sqlite, SqliteArguments<'q>>,
+    value: &Value,
+) -> sqlx::query::Query<'q, Sqlite, SqliteArguments<'q>> {
+    match value {
+        Value::Null => query.bind(None::<String>),
+        Value::Bool(b) => query.bind(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => query.bind(i),
+            None => query.bind(n.as_f64()),
+        },
+        Value::String(s) => query.bind(s.clone()),
+        other => query.bind(other.to_string()),
+    }
+}
 
-        if is_select {
-            self.query(query).await
-        } else {
-            let affected = self.execute(query).await?;
-            Ok(QueryResult {
-                columns: vec![],
-                rows: vec![],
-                affected_rows: affected,
-                column_metadata: None,
-                total_count: None,
-                limit: None,
-                offset: None,
-                has_more: None,
-                row_metadata: None,
-                execution_time_ms: None,
-                json: None,
-                display_mode: None,
-            })
+fn rows_to_result(query_result: Vec<SqliteRow>) -> QueryResult {
+    let column_count = query_result.first().map_or(0, |row| row.len());
+    let rows = query_result.iter().map(row_values).collect();
+    values_to_result(column_count, rows, None)
+}
+
+/// Reads the rows of `statement` up to the end of `query_limits::read_budget`.
+async fn read_rows<'q>(
+    statement: sqlx::query::Query<'q, Sqlite, SqliteArguments<'q>>,
+    conn: &mut SqliteConnection,
+) -> Result<QueryResult, sqlx::Error> {
+    let mut budget = query_limits::read_budget();
+    let mut column_count = 0;
+    let mut rows = Vec::new();
+    let mut truncated = None;
+    let mut stream = statement.fetch(&mut *conn);
+    while let Some(row) = stream.next().await {
+        let row = row?;
+        column_count = row.len();
+        let values = row_values(&row);
+        if !budget.admit(&values) {
+            truncated = Some(true);
+            break;
         }
+        rows.push(values);
     }
+    Ok(values_to_result(column_count, rows, truncated))
+}
 
-    async fn execute_script(&self, script: &str) -> Result<u64> {
-        let statements = crate::utils::sql_script::split_sql_statements(script);
-        if statements.is_empty() {
-            return Ok(0);
+fn row_values(row: &SqliteRow) -> Vec<Value> {
+    (0..row.len())
+        .map(|i| {
+            if let Ok(v) = row.try_get::<i32, _>(i) {
+                Value::Number(v.into())
+            } else if let Ok(v) = row.try_get::<i64, _>(i) {
+                Value::Number(v.into())
+            } else if let Ok(v) = row.try_get::<f64, _>(i) {
+                serde_json::Number::from_f64(v)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null)
+            } else if let Ok(v) = row.try_get::<String, _>(i) {
+                Value::String(v)
+            } else if let Ok(v) = row.try_get::<bool, _>(i) {
+                Value::Bool(v)
+            } else {
+                Value::Null
+            }
+        })
+        .collect()
+}
+
+fn values_to_result(
+    column_count: usize,
+    rows: Vec<Vec<Value>>,
+    truncated: Option<bool>,
+) -> QueryResult {
+    QueryResult {
+        columns: (0..column_count).map(|i| format!("column_{}", i)).collect(),
+        rows,
+        affected_rows: 0,
+        column_metadata: None,
+        total_count: None,
+        limit: None,
+        offset: None,
+        has_more: None,
+        row_metadata: None,
+        execution_time_ms: None,
+        json: None,
+        display_mode: None,
+        truncated,
+    }
+}
+
+/// Calls `sqlite3_interrupt` on a pooled connection while it runs a statement.
+struct SqliteInterrupt {
+    handle: Mutex<Option<RawHandle>>,
+}
+
+struct RawHandle(NonNull<sqlite3>);
+
+// SAFETY: `sqlite3_interrupt` may be called from any thread, and the pointer is
+// only used while `InterruptGuard` keeps the connection checked out.
+unsafe impl Send for RawHandle {}
+
+impl SqliteInterrupt {
+    fn new(handle: NonNull<sqlite3>) -> Self {
+        Self {
+            handle: Mutex::new(Some(RawHandle(handle))),
         }
+    }
+}
 
-        let mut tx = self.pool.begin().await?;
-        let mut count = 0u64;
-        for stmt in statements {
-            let trimmed = stmt.trim();
-            if trimmed.is_empty() {
-                continue;
+#[async_trait]
+impl ServerCancel for SqliteInterrupt {
+    async fn cancel(&self) -> Result<()> {
+        let handle = self.handle.lock().unwrap();
+        match handle.as_ref() {
+            Some(raw) => {
+                // SAFETY: the connection is alive while the handle is set (see `InterruptGuard`).
+                unsafe { libsqlite3_sys::sqlite3_interrupt(raw.0.as_ptr()) };
+                Ok(())
             }
-            sqlx::query(trimmed).execute(&mut *tx).await?;
-            count += 1;
+            None => Err(anyhow::anyhow!("Statement already finished")),
         }
-        tx.commit().await?;
-        Ok(count)
     }
+}
 
-    async fn explain(&self, query: &str, _analyze: bool) -> Result<Value> {
-        let explain_query = format!("EXPLAIN QUERY PLAN {}", query);
-        let result = self.query(&explain_query).await?;
+/// Forgets the raw handle; waits for an in-flight interrupt to finish.
+struct InterruptGuard(Arc<SqliteInterrupt>);
 
-        let mut plan = Vec::new();
-        for row in result.rows {
-            let mut node = serde_json::Map::new();
-            for (i, col) in result.columns.iter().enumerate() {
-                if let Some(val) = row.get(i) {
-                    node.insert(col.clone(), val.clone());
-                }
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        if let Ok(mut handle) = self.0.handle.lock() {
+            *handle = None;
+        }
+    }
+}
+
+/// An interrupt for the statements run on `conn`; wrap it in an
+/// `InterruptGuard` declared after `conn`.
+async fn interrupt_for(conn: &mut SqliteConnection) -> Result<Arc<SqliteInterrupt>> {
+    let mut locked = conn.lock_handle().await?;
+    Ok(Arc::new(SqliteInterrupt::new(locked.as_raw_handle())))
+}
+
+/// Interrupts the statement running on a connection once `timeout` passes,
+/// as SQLite has no statement timeout of its own. Must be dropped before the
+/// connection goes back to the pool.
+struct StatementDeadline {
+    timeout: Duration,
+    timer: tokio::task::JoinHandle<()>,
+    _guard: InterruptGuard,
+}
+
+impl StatementDeadline {
+    async fn arm(conn: &mut SqliteConnection, timeout: Option<Duration>) -> Result<Option<Self>> {
+        let Some(timeout) = timeout else {
+            return Ok(None);
+        };
+        let interrupt = {
+            let mut locked = conn.lock_handle().await?;
+            Arc::new(SqliteInterrupt::new(locked.as_raw_handle()))
+        };
+        let timer = tokio::spawn({
+            let interrupt = interrupt.clone();
+            async move {
+                tokio::time::sleep(timeout).await;
+                let _ = interrupt.cancel().await;
             }
-            plan.push(Value::Object(node));
+        });
+        Ok(Some(Self {
+            timeout,
+            timer,
+            _guard: InterruptGuard(interrupt),
+        }))
+    }
+
+    /// Reports an error caused by the deadline as a timeout rather than as
+    /// SQLite's "interrupted".
+    fn check<T>(deadline: Option<Self>, result: Result<T>) -> Result<T> {
+        match (deadline, result) {
+            (Some(deadline), Err(_)) if deadline.timer.is_finished() => Err(anyhow::anyhow!(
+                "Statement timed out after {} ms",
+                deadline.timeout.as_millis()
+            )),
+            (_, result) => result,
         }
+    }
+}
 
-        Ok(Value::Array(plan))
+impl Drop for StatementDeadline {
+    fn drop(&mut self) {
+        // Stop the timer first; `_guard` then forgets the handle.
+        self.timer.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SQLiteQuery;
+    use crate::services::driver::QueryDriver;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn failed_rebuild_does_not_leave_its_transaction_open() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE t (id INTEGER PRIMARY KEY)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let query = SQLiteQuery::new(pool.clone());
+
+        query
+            .execute_query("PRAGMA foreign_keys = OFF")
+            .await
+            .unwrap();
+        query
+            .execute_query("BEGIN; INSERT INTO t VALUES (1); INSERT INTO t VALUES (1); COMMIT;")
+            .await
+            .unwrap_err();
+
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM t")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let foreign_keys: i64 = sqlx::query_scalar("PRAGMA foreign_keys")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!((rows, foreign_keys), (0, 1));
+        query.execute_query("BEGIN").await.unwrap();
     }
 }
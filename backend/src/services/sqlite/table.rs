@@ -1,8 +1,10 @@
+use super::query::bind_json_value;
 use crate::services::db_driver::{
     IndexInfo, PartitionInfo, QueryResult, StorageBloatInfo, TableComment, TableConstraints,
     TableDependencies, TableGrant, TableStatistics, TriggerInfo,
 };
 use crate::services::driver::TableOperations;
+use crate::services::table_filter::{self, FilterNode, SortSpec, SqlDialect};
 use anyhow::Result;
 use async_trait::async_trait;
 use serde_json::Value;
@@ -26,7 +28,8 @@ impl TableOperations for SQLiteTable {
         table: &str,
         limit: i64,
         offset: i64,
-        _filter: Option<String>,
+        filter: Option<FilterNode>,
+        sort: Vec<SortSpec>,
         _document_id: Option<String>,
         _fields: Option<Vec<String>>,
     ) -> Result<QueryResult> {
@@ -39,13 +42,21 @@ impl TableOperations for SQLiteTable {
         );
 
         let schema = normalize_schema(schema);
+        let where_clause = table_filter::where_clause(filter.as_ref(), SqlDialect::SQLITE)?;
+        let order_by = table_filter::order_by_clause(&sort, SqlDialect::SQLITE)?;
         let query = format!(
-            "SELECT * FROM {}.{} LIMIT ? OFFSET ?",
+            "SELECT * FROM {}.{}{}{} LIMIT ? OFFSET ?",
             quote_ident(&schema),
-            quote_ident(table)
+            quote_ident(table),
+            where_clause.sql,
+            order_by
         );
 
-        let rows = sqlx::query(&query)
+        let mut statement = sqlx::query(&query);
+        for value in &where_clause.params {
+            statement = bind_json_value(statement, value);
+        }
+        let rows = statement
             .bind(limit)
             .bind(offset)
             .fetch_all(&self.pool)
@@ -65,6 +76,7 @@ impl TableOperations for SQLiteTable {
                 execution_time_ms: None,
                 json: None,
                 display_mode: None,
+                truncated: None,
             });
         }
 
@@ -120,6 +132,7 @@ impl TableOperations for SQLiteTable {
             execution_time_ms: None,
             json: None,
             display_mode: None,
+            truncated: None,
         })
     }
 
@@ -412,6 +425,8 @@ fn quote_ident(s: &str) -> String {
 mod tests {
     use super::SQLiteTable;
     use crate::services::driver::TableOperations;
+    use crate::services::table_filter::{FilterNode, SortDirection, SortSpec};
+    use serde_json::json;
     use sqlx::sqlite::SqlitePool;
 
     #[tokio::test]
@@ -440,5 +455,38 @@ mod tests {
             .iter()
             .any(|uc| uc.constraint_name == "idx_t_email" && uc.columns == vec!["email"]));
     }
-}
 
+    #[tokio::test]
+    async fn table_data_applies_filter_and_sort() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT, score REAL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO t VALUES (1, 'a', 1.5), (2, 'b', 2.5), (3, NULL, 3.5)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let filter: FilterNode = serde_json::from_value(json!({
+            "type": "or",
+            "children": [
+                { "type": "condition", "column": "score", "op": "gte", "value": 3 },
+                { "type": "condition", "column": "name", "op": "in", "value": ["a", "b"] }
+            ]
+        }))
+        .unwrap();
+        let sort = vec![SortSpec {
+            column: "id".to_string(),
+            direction: SortDirection::Desc,
+        }];
+
+        let table = SQLiteTable::new(pool);
+        let result = table
+            .get_table_data("main", "t", 2, 0, Some(filter), sort, None, None)
+            .await
+            .unwrap();
+        let ids: Vec<_> = result.rows.iter().map(|row| row[0].clone()).collect();
+        assert_eq!(ids, vec![json!(3), json!(2)]);
+    }
+}
@@ -0,0 +1,66 @@
+use super::query::run_on_connection;
+use crate::services::db_driver::QueryResult;
+use crate::services::driver::TransactionSession;
+use anyhow::Result;
+use async_trait::async_trait;
+use sqlx::pool::PoolConnection;
+use sqlx::{Sqlite, SqlitePool};
+use std::time::Duration;
+
+/// A transaction on a connection taken out of the pool. Other connections
+/// wait on SQLite's lock once this one has written.
+pub struct SQLiteTransaction {
+    conn: Option<PoolConnection<Sqlite>>,
+    statement_timeout: Option<Duration>,
+}
+
+impl SQLiteTransaction {
+    pub async fn begin(pool: &SqlitePool, statement_timeout: Option<Duration>) -> Result<Self> {
+        let mut conn = pool.acquire().await?;
+        sqlx::query("BEGIN").execute(&mut *conn).await?;
+        Ok(Self {
+            conn: Some(conn),
+            statement_timeout,
+        })
+    }
+
+    async fn finish(mut self: Box<Self>, statement: &str) -> Result<()> {
+        let mut conn = self.conn.take().expect("transaction already finished");
+        match sqlx::query(statement).execute(&mut *conn).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                // The transaction state is unknown; close the connection.
+                drop(conn.detach());
+                Err(e.into())
+            }
+        }
+    }
+}
+
+impl Drop for SQLiteTransaction {
+    fn drop(&mut self) {
+        // Closing the connection rolls the transaction back.
+        if let Some(conn) = self.conn.take() {
+            drop(conn.detach());
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionSession for SQLiteTransaction {
+    async fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
+        let conn = self
+            .conn
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Transaction is already finished"))?;
+        run_on_connection(conn, query, self.statement_timeout).await
+    }
+
+    async fn commit(self: Box<Self>) -> Result<()> {
+        self.finish("COMMIT").await
+    }
+
+    async fn rollback(self: Box<Self>) -> Result<()> {
+        self.finish("ROLLBACK").await
+    }
+}
@@ -0,0 +1,758 @@
+use crate::models::entities::connection as ConnectionModel;
+use crate::services::reaper::spawn_reaper;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use russh::client;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Tunnels with no forwarded sockets are closed after this long.
+const TUNNEL_IDLE_TIMEOUT: Duration = Duration::from_secs(600);
+const REAPER_INTERVAL: Duration = Duration::from_secs(60);
+const SSH_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Pause after a failed `accept`, so that persistent errors (EMFILE) do not spin.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(200);
+/// Failed `accept`s in a row after which the tunnel is closed.
+const MAX_ACCEPT_FAILURES: u32 = 50;
+
+#[derive(Clone)]
+pub enum SshAuth {
+    Password(String),
+    Key {
+        path: String,
+        passphrase: Option<String>,
+    },
+}
+
+#[derive(Clone)]
+pub struct SshTunnelConfig {
+    pub ssh_host: String,
+    pub ssh_port: u16,
+    pub ssh_user: String,
+    pub auth: SshAuth,
+    pub remote_host: String,
+    pub remote_port: u16,
+}
+
+impl SshTunnelConfig {
+    /// Builds the tunnel config from a connection. Secrets are passed in already
+    /// decrypted because they live in the keychain, not on the model.
+    pub fn from_model(
+        connection: &ConnectionModel::Model,
+        ssh_password: Option<String>,
+        ssh_key_passphrase: Option<String>,
+    ) -> Result<Self> {
+        let ssh_host = connection
+            .ssh_host
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("SSH host is required when SSH tunneling is enabled"))?
+            .to_string();
+        let ssh_user = connection
+            .ssh_user
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("SSH user is required when SSH tunneling is enabled"))?
+            .to_string();
+        let ssh_port = connection.ssh_port.filter(|p| *p > 0).unwrap_or(22) as u16;
+
+        let auth = match connection.ssh_auth_type.as_deref().unwrap_or("password") {
+            "key" | "private_key" | "publickey" => {
+                let path = connection
+                    .ssh_key_file
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .ok_or_else(|| anyhow::anyhow!("SSH private key file is required"))?;
+                SshAuth::Key {
+                    path: expand_home(path),
+                    passphrase: ssh_key_passphrase.filter(|p| !p.is_empty()),
+                }
+            }
+            _ => SshAuth::Password(ssh_password.unwrap_or_default()),
+        };
+
+        let remote_host = if connection.host.trim().is_empty() {
+            "127.0.0.1".to_string()
+        } else {
+            connection.host.trim().to_string()
+        };
+
+        Ok(Self {
+            ssh_host,
+            ssh_port,
+            ssh_user,
+            auth,
+            remote_host,
+            remote_port: connection.port as u16,
+        })
+    }
+
+    /// Identifies the tunnel endpoints so an edited connection gets a fresh tunnel.
+    fn fingerprint(&self) -> String {
+        let auth = match &self.auth {
+            SshAuth::Password(_) => "password".to_string(),
+            SshAuth::Key { path, .. } => format!("key:{}", path),
+        };
+        format!(
+            "{}@{}:{}|{}|{}:{}",
+            self.ssh_user, self.ssh_host, self.ssh_port, auth, self.remote_host, self.remote_port
+        )
+    }
+}
+
+fn expand_home(path: &str) -> String {
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Some(home) = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")) {
+            return std::path::Path::new(&home)
+                .join(rest)
+                .to_string_lossy()
+                .to_string();
+        }
+    }
+    path.to_string()
+}
+
+/// Checks the server's host key against the user's `known_hosts`.
+struct TunnelHandler {
+    host: String,
+    port: u16,
+}
+
+#[async_trait]
+impl client::Handler for TunnelHandler {
+    type Error = anyhow::Error;
+
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &russh_keys::key::PublicKey,
+    ) -> Result<bool, Self::Error> {
+        let known_hosts = known_hosts_path().ok_or_else(|| {
+            anyhow::anyhow!("Cannot verify the SSH host key: no home directory for known_hosts")
+        })?;
+        verify_host_key(&known_hosts, &self.host, self.port, server_public_key)?;
+        Ok(true)
+    }
+}
+
+fn known_hosts_path() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".ssh").join("known_hosts"))
+}
+
+/// Accepts a host key recorded in `known_hosts`. A host seen for the first
+/// time has its key recorded and accepted, like OpenSSH's
+/// `StrictHostKeyChecking=accept-new`; a host whose key changed is refused.
+fn verify_host_key(
+    known_hosts: &Path,
+    host: &str,
+    port: u16,
+    key: &russh_keys::key::PublicKey,
+) -> Result<()> {
+    match russh_keys::check_known_hosts_path(host, port, key, known_hosts) {
+        Ok(true) => Ok(()),
+        Ok(false) => {
+            tracing::info!(
+                "[SshTunnel] Adding host key {} of {}:{} to {}",
+                key.fingerprint(),
+                host,
+                port,
+                known_hosts.display()
+            );
+            russh_keys::learn_known_hosts_path(host, port, key, known_hosts).with_context(|| {
+                format!("Failed to record the host key in {}", known_hosts.display())
+            })
+        }
+        Err(russh_keys::Error::KeyChanged { line }) => Err(anyhow::anyhow!(
+            "The SSH host key of {}:{} does not match the one in {} (line {}). \
+             If the server's key was changed on purpose, remove that line and connect again.",
+            host,
+            port,
+            known_hosts.display(),
+            line
+        )),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", known_hosts.display())),
+    }
+}
+
+/// What the manager needs of an open tunnel, so that its bookkeeping can be
+/// tested without an SSH server.
+#[async_trait]
+trait Tunnel: Send + Sync {
+    fn local_port(&self) -> u16;
+    fn remote_host(&self) -> &str;
+    fn fingerprint(&self) -> &str;
+    fn is_alive(&self) -> bool;
+    fn touch(&self);
+    fn is_idle(&self, timeout: Duration) -> bool;
+    async fn close(&self);
+}
+
+/// A local port-forward `127.0.0.1:<local_port> -> remote_host:remote_port` over SSH.
+pub struct SshTunnel {
+    local_port: u16,
+    remote_host: String,
+    fingerprint: String,
+    session: Arc<client::Handle<TunnelHandler>>,
+    accept_task: JoinHandle<()>,
+    active_connections: Arc<AtomicUsize>,
+    last_used: Arc<Mutex<Instant>>,
+}
+
+impl SshTunnel {
+    pub async fn open(config: &SshTunnelConfig) -> Result<Self> {
+        tracing::info!(
+            "[SshTunnel] Opening tunnel via {}@{}:{} to {}:{}",
+            config.ssh_user,
+            config.ssh_host,
+            config.ssh_port,
+            config.remote_host,
+            config.remote_port
+        );
+
+        let ssh_config = Arc::new(client::Config {
+            inactivity_timeout: None,
+            keepalive_interval: Some(Duration::from_secs(30)),
+            ..Default::default()
+        });
+
+        let mut session = tokio::time::timeout(
+            SSH_CONNECT_TIMEOUT,
+            client::connect(
+                ssh_config,
+                (config.ssh_host.as_str(), config.ssh_port),
+                TunnelHandler {
+                    host: config.ssh_host.clone(),
+                    port: config.ssh_port,
+                },
+            ),
+        )
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "Timed out connecting to SSH server {}:{}",
+                config.ssh_host,
+                config.ssh_port
+            )
+        })?
+        .with_context(|| {
+            format!(
+                "Failed to connect to SSH server {}:{}",
+                config.ssh_host, config.ssh_port
+            )
+        })?;
+
+        let authentication = async {
+            match &config.auth {
+                SshAuth::Password(password) => session
+                    .authenticate_password(&config.ssh_user, password)
+                    .await
+                    .context("SSH password authentication failed"),
+                SshAuth::Key { path, passphrase } => {
+                    let key = russh_keys::load_secret_key(path, passphrase.as_deref())
+                        .with_context(|| format!("Failed to load SSH private key '{}'", path))?;
+                    session
+                        .authenticate_publickey(&config.ssh_user, Arc::new(key))
+                        .await
+                        .context("SSH public key authentication failed")
+                }
+            }
+        };
+        let authenticated = tokio::time::timeout(SSH_CONNECT_TIMEOUT, authentication)
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "Timed out authenticating to SSH server {}:{}",
+                    config.ssh_host,
+                    config.ssh_port
+                )
+            })??;
+
+        if !authenticated {
+            return Err(anyhow::anyhow!(
+                "SSH authentication failed for user '{}'",
+                config.ssh_user
+            ));
+        }
+
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .context("Failed to bind local port for SSH tunnel")?;
+        let local_port = listener.local_addr()?.port();
+
+        let session = Arc::new(session);
+        let active_connections = Arc::new(AtomicUsize::new(0));
+        let last_used = Arc::new(Mutex::new(Instant::now()));
+
+        let accept_task = tokio::spawn(Self::accept_loop(
+            listener,
+            session.clone(),
+            config.remote_host.clone(),
+            config.remote_port,
+            active_connections.clone(),
+            last_used.clone(),
+        ));
+
+        tracing::info!(
+            "[SshTunnel] Tunnel ready on 127.0.0.1:{} -> {}:{}",
+            local_port,
+            config.remote_host,
+            config.remote_port
+        );
+
+        Ok(Self {
+            local_port,
+            remote_host: config.remote_host.clone(),
+            fingerprint: config.fingerprint(),
+            session,
+            accept_task,
+            active_connections,
+            last_used,
+        })
+    }
+
+    async fn accept_loop(
+        listener: TcpListener,
+        session: Arc<client::Handle<TunnelHandler>>,
+        remote_host: String,
+        remote_port: u16,
+        active_connections: Arc<AtomicUsize>,
+        last_used: Arc<Mutex<Instant>>,
+    ) {
+        let mut failures = 0;
+        loop {
+            let (mut socket, peer) = match listener.accept().await {
+                Ok(v) => v,
+                Err(e) => {
+                    failures += 1;
+                    if failures >= MAX_ACCEPT_FAILURES {
+                        // Ends the task, so the tunnel is no longer alive and gets reopened.
+                        tracing::error!(
+                            "[SshTunnel] Closing tunnel after {} failed accepts: {}",
+                            failures,
+                            e
+                        );
+                        return;
+                    }
+                    tracing::warn!("[SshTunnel] Failed to accept local connection: {}", e);
+                    tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                    continue;
+                }
+            };
+            failures = 0;
+
+            let session = session.clone();
+            let remote_host = remote_host.clone();
+            let active_connections = active_connections.clone();
+            let last_used = last_used.clone();
+
+            tokio::spawn(async move {
+                active_connections.fetch_add(1, Ordering::SeqCst);
+                let result = async {
+                    let channel = session
+                        .channel_open_direct_tcpip(
+                            remote_host.as_str(),
+                            remote_port as u32,
+                            peer.ip().to_string(),
+                            peer.port() as u32,
+                        )
+                        .await?;
+                    let mut stream = channel.into_stream();
+                    tokio::io::copy_bidirectional(&mut socket, &mut stream).await?;
+                    Ok::<(), anyhow::Error>(())
+                }
+                .await;
+
+                if let Err(e) = result {
+                    tracing::debug!("[SshTunnel] Forwarded connection closed: {}", e);
+                }
+                if let Ok(mut t) = last_used.lock() {
+                    *t = Instant::now();
+                }
+                active_connections.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+    }
+}
+
+#[async_trait]
+impl Tunnel for SshTunnel {
+    fn local_port(&self) -> u16 {
+        self.local_port
+    }
+
+    fn remote_host(&self) -> &str {
+        &self.remote_host
+    }
+
+    fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    fn is_alive(&self) -> bool {
+        !self.session.is_closed() && !self.accept_task.is_finished()
+    }
+
+    fn touch(&self) {
+        if let Ok(mut t) = self.last_used.lock() {
+            *t = Instant::now();
+        }
+    }
+
+    fn is_idle(&self, timeout: Duration) -> bool {
+        if self.active_connections.load(Ordering::SeqCst) > 0 {
+            return false;
+        }
+        self.last_used
+            .lock()
+            .map(|t| t.elapsed() >= timeout)
+            .unwrap_or(false)
+    }
+
+    async fn close(&self) {
+        self.accept_task.abort();
+        let _ = self
+            .session
+            .disconnect(russh::Disconnect::ByApplication, "", "en")
+            .await;
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+    }
+}
+
+/// Process-wide registry of open tunnels, keyed by connection id.
+pub struct SshTunnelManager {
+    tunnels: DashMap<Uuid, Arc<dyn Tunnel>>,
+    /// One per connection, so that a slow SSH server only holds up its own tunnel.
+    open_locks: DashMap<Uuid, Arc<tokio::sync::Mutex<()>>>,
+    reaper_started: AtomicBool,
+}
+
+impl SshTunnelManager {
+    fn new() -> Self {
+        Self {
+            tunnels: DashMap::new(),
+            open_locks: DashMap::new(),
+            reaper_started: AtomicBool::new(false),
+        }
+    }
+
+    pub fn global() -> &'static Arc<Self> {
+        static MANAGER: OnceLock<Arc<SshTunnelManager>> = OnceLock::new();
+        MANAGER.get_or_init(|| Arc::new(SshTunnelManager::new()))
+    }
+
+    /// Returns the local port of a live tunnel for the connection, opening one if needed.
+    pub async fn get_or_open(
+        self: &Arc<Self>,
+        connection_id: Uuid,
+        config: &SshTunnelConfig,
+    ) -> Result<u16> {
+        self.get_or_open_with(connection_id, &config.fingerprint(), || async {
+            let tunnel = SshTunnel::open(config).await?;
+            Ok::<Arc<dyn Tunnel>, anyhow::Error>(Arc::new(tunnel))
+        })
+        .await
+    }
+
+    /// Reuses the connection's tunnel while it is alive and has the same
+    /// fingerprint, else closes it and calls `open`.
+    async fn get_or_open_with<F, Fut>(
+        self: &Arc<Self>,
+        connection_id: Uuid,
+        fingerprint: &str,
+        open: F,
+    ) -> Result<u16>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Arc<dyn Tunnel>>>,
+    {
+        self.start_reaper();
+
+        if let Some(port) = self.live_port(connection_id, fingerprint) {
+            return Ok(port);
+        }
+
+        let open_lock = self.open_locks.entry(connection_id).or_default().clone();
+        let _guard = open_lock.lock().await;
+        // Another caller may have opened it while we waited.
+        if let Some(port) = self.live_port(connection_id, fingerprint) {
+            return Ok(port);
+        }
+
+        if let Some((_, stale)) = self.tunnels.remove(&connection_id) {
+            stale.close().await;
+        }
+
+        let tunnel = open().await?;
+        let port = tunnel.local_port();
+        self.tunnels.insert(connection_id, tunnel);
+        Ok(port)
+    }
+
+    fn live_port(&self, connection_id: Uuid, fingerprint: &str) -> Option<u16> {
+        let tunnel = self.tunnels.get(&connection_id)?;
+        if tunnel.fingerprint() == fingerprint && tunnel.is_alive() {
+            tunnel.touch();
+            Some(tunnel.local_port())
+        } else {
+            None
+        }
+    }
+
+    /// Maps a tunnel's local port back to the database host it forwards to, so TLS can
+    /// verify the certificate against the real server name.
+    pub fn remote_host_for_local_port(&self, local_port: u16) -> Option<String> {
+        self.tunnels
+            .iter()
+            .find(|entry| entry.value().local_port() == local_port)
+            .map(|entry| entry.value().remote_host().to_string())
+    }
+
+    /// Closes the tunnel for a connection (e.g. after it was edited or deleted).
+    pub async fn close(&self, connection_id: Uuid) {
+        if let Some((_, tunnel)) = self.tunnels.remove(&connection_id) {
+            tracing::info!(
+                "[SshTunnel] Closing tunnel for connection {}",
+                connection_id
+            );
+            tunnel.close().await;
+        }
+        self.open_locks
+            .retain(|id, lock| *id != connection_id || Arc::strong_count(lock) > 1);
+    }
+
+    fn start_reaper(self: &Arc<Self>) {
+        spawn_reaper(
+            &self.reaper_started,
+            self,
+            REAPER_INTERVAL,
+            |manager| async move { manager.close_idle(TUNNEL_IDLE_TIMEOUT).await },
+        );
+    }
+
+    async fn close_idle(&self, timeout: Duration) {
+        let expired: Vec<Uuid> = self
+            .tunnels
+            .iter()
+            .filter(|entry| !entry.value().is_alive() || entry.value().is_idle(timeout))
+            .map(|entry| *entry.key())
+            .collect();
+
+        for connection_id in expired {
+            if let Some((_, tunnel)) = self.tunnels.remove(&connection_id) {
+                tracing::info!(
+                    "[SshTunnel] Closing idle tunnel for connection {}",
+                    connection_id
+                );
+                tunnel.close().await;
+            }
+        }
+        self.open_locks
+            .retain(|id, lock| Arc::strong_count(lock) > 1 || self.tunnels.contains_key(id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use russh_keys::key::KeyPair;
+    use std::sync::atomic::AtomicU16;
+
+    fn public_key() -> russh_keys::key::PublicKey {
+        KeyPair::generate_ed25519()
+            .unwrap()
+            .clone_public_key()
+            .unwrap()
+    }
+
+    #[test]
+    fn pins_host_keys_on_first_use() {
+        let dir = tempfile::tempdir().unwrap();
+        let known_hosts = dir.path().join(".ssh").join("known_hosts");
+        let key = public_key();
+
+        verify_host_key(&known_hosts, "db.example.com", 2222, &key).unwrap();
+        let recorded = std::fs::read_to_string(&known_hosts).unwrap();
+        assert!(recorded.contains("[db.example.com]:2222 ssh-ed25519 "));
+
+        verify_host_key(&known_hosts, "db.example.com", 2222, &key).unwrap();
+        assert_eq!(std::fs::read_to_string(&known_hosts).unwrap(), recorded);
+
+        let changed = verify_host_key(&known_hosts, "db.example.com", 2222, &public_key());
+        assert!(changed.unwrap_err().to_string().contains("does not match"));
+
+        verify_host_key(&known_hosts, "other.example.com", 22, &public_key()).unwrap();
+    }
+
+    fn config() -> SshTunnelConfig {
+        SshTunnelConfig {
+            ssh_host: "bastion.example.com".to_string(),
+            ssh_port: 22,
+            ssh_user: "deploy".to_string(),
+            auth: SshAuth::Password("secret".to_string()),
+            remote_host: "db.internal".to_string(),
+            remote_port: 5432,
+        }
+    }
+
+    #[test]
+    fn fingerprints_endpoints_but_not_secrets() {
+        let base = config().fingerprint();
+
+        let mut other_password = config();
+        other_password.auth = SshAuth::Password("changed".to_string());
+        assert_eq!(other_password.fingerprint(), base);
+
+        let key = |passphrase: &str| SshAuth::Key {
+            path: "/keys/id_ed25519".to_string(),
+            passphrase: Some(passphrase.to_string()),
+        };
+        let (mut with_key, mut other_passphrase) = (config(), config());
+        with_key.auth = key("one");
+        other_passphrase.auth = key("two");
+        assert_eq!(with_key.fingerprint(), other_passphrase.fingerprint());
+        assert_ne!(with_key.fingerprint(), base);
+
+        let changes: [fn(&mut SshTunnelConfig); 5] = [
+            |c| c.ssh_host = "bastion2.example.com".to_string(),
+            |c| c.ssh_port = 2222,
+            |c| c.ssh_user = "admin".to_string(),
+            |c| c.remote_host = "db2.internal".to_string(),
+            |c| c.remote_port = 5433,
+        ];
+        for change in changes {
+            let mut changed = config();
+            change(&mut changed);
+            assert_ne!(changed.fingerprint(), base);
+        }
+    }
+
+    struct FakeTunnel {
+        port: u16,
+        fingerprint: String,
+        closed: AtomicBool,
+    }
+
+    #[async_trait]
+    impl Tunnel for FakeTunnel {
+        fn local_port(&self) -> u16 {
+            self.port
+        }
+
+        fn remote_host(&self) -> &str {
+            "db.internal"
+        }
+
+        fn fingerprint(&self) -> &str {
+            &self.fingerprint
+        }
+
+        fn is_alive(&self) -> bool {
+            !self.closed.load(Ordering::SeqCst)
+        }
+
+        fn touch(&self) {}
+
+        fn is_idle(&self, _timeout: Duration) -> bool {
+            false
+        }
+
+        async fn close(&self) {
+            self.closed.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// Opens fake tunnels on ports 1, 2, ... and keeps them for inspection.
+    #[derive(Default)]
+    struct Opener {
+        next_port: AtomicU16,
+        opened: Mutex<Vec<Arc<FakeTunnel>>>,
+    }
+
+    impl Opener {
+        async fn open(
+            &self,
+            manager: &Arc<SshTunnelManager>,
+            connection_id: Uuid,
+            config: &SshTunnelConfig,
+        ) -> u16 {
+            let fingerprint = config.fingerprint();
+            manager
+                .get_or_open_with(connection_id, &fingerprint, || async {
+                    let tunnel = Arc::new(FakeTunnel {
+                        port: self.next_port.fetch_add(1, Ordering::SeqCst) + 1,
+                        fingerprint: fingerprint.clone(),
+                        closed: AtomicBool::new(false),
+                    });
+                    self.opened.lock().unwrap().push(tunnel.clone());
+                    Ok::<Arc<dyn Tunnel>, anyhow::Error>(tunnel)
+                })
+                .await
+                .unwrap()
+        }
+
+        fn closed(&self) -> Vec<bool> {
+            let opened = self.opened.lock().unwrap();
+            opened.iter().map(|t| !t.is_alive()).collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn reuses_tunnels_until_their_endpoints_change() {
+        let manager = Arc::new(SshTunnelManager::new());
+        let opener = Opener::default();
+        let id = Uuid::new_v4();
+
+        assert_eq!(opener.open(&manager, id, &config()).await, 1);
+        let mut other_password = config();
+        other_password.auth = SshAuth::Password("changed".to_string());
+        assert_eq!(opener.open(&manager, id, &other_password).await, 1);
+        assert_eq!(opener.closed(), vec![false]);
+
+        let mut moved = config();
+        moved.remote_port = 5433;
+        assert_eq!(opener.open(&manager, id, &moved).await, 2);
+        assert_eq!(opener.closed(), vec![true, false]);
+        assert_eq!(manager.tunnels.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn closes_tunnels_of_updated_or_deleted_connections() {
+        let manager = Arc::new(SshTunnelManager::new());
+        let opener = Opener::default();
+        let (edited, kept) = (Uuid::new_v4(), Uuid::new_v4());
+
+        assert_eq!(opener.open(&manager, edited, &config()).await, 1);
+        assert_eq!(opener.open(&manager, kept, &config()).await, 2);
+
+        // What `update_connection` and `delete_connection` do.
+        manager.close(edited).await;
+        assert_eq!(opener.closed(), vec![true, false]);
+        assert!(!manager.tunnels.contains_key(&edited));
+        assert!(!manager.open_locks.contains_key(&edited));
+        assert_eq!(manager.remote_host_for_local_port(1), None);
+        assert_eq!(
+            manager.remote_host_for_local_port(2).as_deref(),
+            Some("db.internal")
+        );
+
+        // The next use opens a fresh tunnel, even with unchanged settings.
+        assert_eq!(opener.open(&manager, edited, &config()).await, 3);
+        assert_eq!(opener.closed(), vec![true, false, false]);
+    }
+}
@@ -0,0 +1,448 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Filter for table data, built by the client and rendered by each driver.
+/// Condition values never end up in the SQL text; they are bound as
+/// parameters. `Raw` holds a condition typed by the user in the engine's own
+/// language (a SQL or N1QL `WHERE` expression, or a JSON filter document for
+/// MongoDB) and is used as written.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FilterNode {
+    Condition(FilterCondition),
+    And { children: Vec<FilterNode> },
+    Or { children: Vec<FilterNode> },
+    Raw { sql: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterCondition {
+    pub column: String,
+    pub op: FilterOp,
+    /// A scalar, an array for `in`/`not_in`, a `[low, high]` pair for
+    /// `between`, unused for `is_null`/`is_not_null`.
+    #[serde(default)]
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+    Like,
+    NotLike,
+    In,
+    NotIn,
+    IsNull,
+    IsNotNull,
+    Between,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SortSpec {
+    pub column: String,
+    #[serde(default)]
+    pub direction: SortDirection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+    /// `$1`, `$2`, ... (Postgres, N1QL)
+    Numbered,
+    /// `?` (MySQL, SQLite, ClickHouse)
+    Question,
+}
+
+/// Identifier quoting and placeholder syntax of one engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqlDialect {
+    pub quote: char,
+    pub placeholder: Placeholder,
+    /// How a `?` that is not a placeholder is written in `Raw` conditions.
+    /// The ClickHouse client takes every `?` of a query as a bind.
+    pub literal_question_mark: &'static str,
+}
+
+impl SqlDialect {
+    pub const POSTGRES: Self = Self {
+        quote: '"',
+        placeholder: Placeholder::Numbered,
+        literal_question_mark: "?",
+    };
+    pub const MYSQL: Self = Self {
+        quote: '`',
+        placeholder: Placeholder::Question,
+        literal_question_mark: "?",
+    };
+    pub const SQLITE: Self = Self {
+        quote: '"',
+        placeholder: Placeholder::Question,
+        literal_question_mark: "?",
+    };
+    pub const CLICKHOUSE: Self = Self {
+        quote: '`',
+        placeholder: Placeholder::Question,
+        literal_question_mark: "??",
+    };
+    pub const N1QL: Self = Self {
+        quote: '`',
+        placeholder: Placeholder::Numbered,
+        literal_question_mark: "?",
+    };
+
+    pub fn quote_ident(&self, name: &str) -> Result<String> {
+        if name.trim().is_empty() {
+            return Err(anyhow::anyhow!("Filter column name is empty"));
+        }
+        let q = self.quote.to_string();
+        Ok(format!("{q}{}{q}", name.replace(&q, &q.repeat(2))))
+    }
+
+    pub fn placeholder(&self, index: usize) -> String {
+        match self.placeholder {
+            Placeholder::Numbered => format!("${}", index),
+            Placeholder::Question => "?".to_string(),
+        }
+    }
+}
+
+/// SQL text plus the values of its placeholders, in order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SqlFragment {
+    pub sql: String,
+    pub params: Vec<Value>,
+}
+
+impl FilterNode {
+    /// Renders to a boolean SQL expression. Numbered placeholders start at `$1`.
+    pub fn to_sql(&self, dialect: SqlDialect) -> Result<SqlFragment> {
+        self.to_sql_with(dialect, &mut |index| dialect.placeholder(index))
+    }
+
+    /// Like `to_sql`, with `placeholder` writing the placeholder of the
+    /// parameter at a 1-based index (e.g. to add a cast).
+    pub fn to_sql_with(
+        &self,
+        dialect: SqlDialect,
+        placeholder: &mut dyn FnMut(usize) -> String,
+    ) -> Result<SqlFragment> {
+        let mut params = Vec::new();
+        let sql = self.render(dialect, &mut |value: &Value| {
+            params.push(value.clone());
+            placeholder(params.len())
+        })?;
+        Ok(SqlFragment { sql, params })
+    }
+
+    /// Readable form with the values inlined, for display only.
+    pub fn describe(&self) -> Result<String> {
+        match self {
+            // As typed, without the parentheses it gets inside a group.
+            FilterNode::Raw { sql } if !sql.trim().is_empty() => Ok(sql.trim().to_string()),
+            _ => self.render(SqlDialect::POSTGRES, &mut literal),
+        }
+    }
+
+    fn render(
+        &self,
+        dialect: SqlDialect,
+        bind: &mut dyn FnMut(&Value) -> String,
+    ) -> Result<String> {
+        match self {
+            FilterNode::Condition(condition) => condition.render(dialect, bind),
+            FilterNode::And { children } => render_group(children, " AND ", "1 = 1", dialect, bind),
+            FilterNode::Or { children } => render_group(children, " OR ", "1 = 0", dialect, bind),
+            FilterNode::Raw { sql } => match sql.trim() {
+                "" => Err(anyhow::anyhow!("The filter expression is empty")),
+                sql => Ok(format!(
+                    "({})",
+                    sql.replace('?', dialect.literal_question_mark)
+                )),
+            },
+        }
+    }
+}
+
+fn render_group(
+    children: &[FilterNode],
+    separator: &str,
+    empty: &str,
+    dialect: SqlDialect,
+    bind: &mut dyn FnMut(&Value) -> String,
+) -> Result<String> {
+    if children.is_empty() {
+        return Ok(empty.to_string());
+    }
+    let parts = children
+        .iter()
+        .map(|child| child.render(dialect, bind))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(format!("({})", parts.join(separator)))
+}
+
+impl FilterCondition {
+    /// The value of a comparison or LIKE.
+    pub fn scalar(&self) -> Result<&Value> {
+        match &self.value {
+            Value::Bool(_) | Value::Number(_) | Value::String(_) => Ok(&self.value),
+            Value::Null => Err(anyhow::anyhow!(
+                "Filter on {} compares with NULL; use is_null instead",
+                self.column
+            )),
+            _ => Err(anyhow::anyhow!(
+                "Filter on {} needs a single value",
+                self.column
+            )),
+        }
+    }
+
+    /// The values of `in`/`not_in`.
+    pub fn list(&self) -> Result<&[Value]> {
+        match &self.value {
+            Value::Array(values) if !values.is_empty() && values.iter().all(is_scalar) => {
+                Ok(values)
+            }
+            _ => Err(anyhow::anyhow!(
+                "Filter on {} needs a non-empty list of values",
+                self.column
+            )),
+        }
+    }
+
+    /// The bounds of `between`.
+    pub fn range(&self) -> Result<(&Value, &Value)> {
+        match &self.value {
+            Value::Array(values) if values.len() == 2 && values.iter().all(is_scalar) => {
+                Ok((&values[0], &values[1]))
+            }
+            _ => Err(anyhow::anyhow!(
+                "Filter on {} needs a [low, high] pair",
+                self.column
+            )),
+        }
+    }
+
+    fn render(
+        &self,
+        dialect: SqlDialect,
+        bind: &mut dyn FnMut(&Value) -> String,
+    ) -> Result<String> {
+        let column = dialect.quote_ident(&self.column)?;
+        let comparison = match self.op {
+            FilterOp::Eq => "=",
+            FilterOp::Ne => "<>",
+            FilterOp::Lt => "<",
+            FilterOp::Lte => "<=",
+            FilterOp::Gt => ">",
+            FilterOp::Gte => ">=",
+            FilterOp::Like => "LIKE",
+            FilterOp::NotLike => "NOT LIKE",
+            FilterOp::In | FilterOp::NotIn => {
+                let keyword = if self.op == FilterOp::In {
+                    "IN"
+                } else {
+                    "NOT IN"
+                };
+                let placeholders: Vec<String> = self.list()?.iter().map(&mut *bind).collect();
+                return Ok(format!(
+                    "{} {} ({})",
+                    column,
+                    keyword,
+                    placeholders.join(", ")
+                ));
+            }
+            FilterOp::IsNull => return Ok(format!("{} IS NULL", column)),
+            FilterOp::IsNotNull => return Ok(format!("{} IS NOT NULL", column)),
+            FilterOp::Between => {
+                let (low, high) = self.range()?;
+                return Ok(format!(
+                    "{} BETWEEN {} AND {}",
+                    column,
+                    bind(low),
+                    bind(high)
+                ));
+            }
+        };
+        Ok(format!(
+            "{} {} {}",
+            column,
+            comparison,
+            bind(self.scalar()?)
+        ))
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    matches!(value, Value::Bool(_) | Value::Number(_) | Value::String(_))
+}
+
+fn literal(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("'{}'", s.replace('\'', "''")),
+        other => other.to_string(),
+    }
+}
+
+/// ` WHERE ...`, or nothing without a filter.
+pub fn where_clause(filter: Option<&FilterNode>, dialect: SqlDialect) -> Result<SqlFragment> {
+    where_clause_with(filter, dialect, &mut |index| dialect.placeholder(index))
+}
+
+/// `where_clause` with placeholders written by `placeholder`, as in
+/// `FilterNode::to_sql_with`.
+pub fn where_clause_with(
+    filter: Option<&FilterNode>,
+    dialect: SqlDialect,
+    placeholder: &mut dyn FnMut(usize) -> String,
+) -> Result<SqlFragment> {
+    match filter {
+        Some(filter) => {
+            let fragment = filter.to_sql_with(dialect, placeholder)?;
+            Ok(SqlFragment {
+                sql: format!(" WHERE {}", fragment.sql),
+                params: fragment.params,
+            })
+        }
+        None => Ok(SqlFragment::default()),
+    }
+}
+
+/// ` ORDER BY ...`, or nothing when `sort` is empty.
+pub fn order_by_clause(sort: &[SortSpec], dialect: SqlDialect) -> Result<String> {
+    if sort.is_empty() {
+        return Ok(String::new());
+    }
+    let columns = sort
+        .iter()
+        .map(|spec| {
+            let direction = match spec.direction {
+                SortDirection::Asc => "ASC",
+                SortDirection::Desc => "DESC",
+            };
+            Ok(format!(
+                "{} {}",
+                dialect.quote_ident(&spec.column)?,
+                direction
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(format!(" ORDER BY {}", columns.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> FilterNode {
+        serde_json::from_value(json!({
+            "type": "and",
+            "children": [
+                { "type": "condition", "column": "status", "op": "eq", "value": "active" },
+                {
+                    "type": "or",
+                    "children": [
+                        { "type": "condition", "column": "age", "op": "between", "value": [18, 65] },
+                        { "type": "condition", "column": "role", "op": "in", "value": ["admin", "owner"] },
+                        { "type": "condition", "column": "deleted_at", "op": "is_null" }
+                    ]
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn renders_parameterized_sql_per_dialect() {
+        let pg = sample().to_sql(SqlDialect::POSTGRES).unwrap();
+        assert_eq!(
+            pg.sql,
+            r#"("status" = $1 AND ("age" BETWEEN $2 AND $3 OR "role" IN ($4, $5) OR "deleted_at" IS NULL))"#
+        );
+        assert_eq!(
+            pg.params,
+            vec![
+                json!("active"),
+                json!(18),
+                json!(65),
+                json!("admin"),
+                json!("owner")
+            ]
+        );
+
+        let mysql = sample().to_sql(SqlDialect::MYSQL).unwrap();
+        assert!(mysql
+            .sql
+            .starts_with("(`status` = ? AND (`age` BETWEEN ? AND ?"));
+        assert_eq!(mysql.params.len(), 5);
+    }
+
+    #[test]
+    fn quotes_identifiers_and_rejects_bad_values() {
+        let filter = FilterNode::Condition(FilterCondition {
+            column: "we\"ird".to_string(),
+            op: FilterOp::Eq,
+            value: Value::Null,
+        });
+        assert!(filter.to_sql(SqlDialect::POSTGRES).is_err());
+
+        let sort = vec![SortSpec {
+            column: "we\"ird".to_string(),
+            direction: SortDirection::Desc,
+        }];
+        assert_eq!(
+            order_by_clause(&sort, SqlDialect::POSTGRES).unwrap(),
+            r#" ORDER BY "we""ird" DESC"#
+        );
+    }
+
+    #[test]
+    fn describes_with_inlined_values() {
+        assert_eq!(
+            sample().describe().unwrap(),
+            r#"("status" = 'active' AND ("age" BETWEEN 18 AND 65 OR "role" IN ('admin', 'owner') OR "deleted_at" IS NULL))"#
+        );
+    }
+
+    #[test]
+    fn keeps_raw_text_next_to_typed_conditions() {
+        let filter: FilterNode = serde_json::from_value(json!({
+            "type": "and",
+            "children": [
+                { "type": "raw", "sql": "age > 18 OR vip" },
+                { "type": "condition", "column": "status", "op": "eq", "value": "active" }
+            ]
+        }))
+        .unwrap();
+        let pg = filter
+            .to_sql_with(SqlDialect::POSTGRES, &mut |i| format!("${}::text", i))
+            .unwrap();
+        assert_eq!(pg.sql, r#"((age > 18 OR vip) AND "status" = $1::text)"#);
+        assert_eq!(pg.params, vec![json!("active")]);
+
+        let raw = FilterNode::Raw {
+            sql: "match(s, 'a?b')".into(),
+        };
+        let clickhouse = raw.to_sql(SqlDialect::CLICKHOUSE).unwrap();
+        assert_eq!(clickhouse.sql, "(match(s, 'a??b'))");
+        let mysql = raw.to_sql(SqlDialect::MYSQL).unwrap();
+        assert_eq!(mysql.sql, "(match(s, 'a?b'))");
+
+        let empty = FilterNode::Raw { sql: "  ".into() };
+        assert!(empty.to_sql(SqlDialect::MYSQL).is_err());
+    }
+}
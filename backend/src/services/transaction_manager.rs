@@ -0,0 +1,352 @@
+use crate::services::db_driver::QueryResult;
+use crate::services::driver::TransactionSession;
+use crate::services::reaper::spawn_reaper;
+use anyhow::Result;
+use dashmap::DashMap;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Open transactions without a statement for this long are rolled back.
+pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+pub const MAX_IDLE_TIMEOUT: Duration = Duration::from_secs(3600);
+const REAPER_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionInfo {
+    pub transaction_id: String,
+    pub connection_id: Uuid,
+    pub database: String,
+    pub db_type: String,
+    pub statement_count: u64,
+    pub age_secs: u64,
+    pub idle_secs: u64,
+    pub idle_timeout_secs: u64,
+    /// A statement is running in the transaction right now.
+    pub busy: bool,
+}
+
+struct OpenTransaction {
+    connection_id: Uuid,
+    database: String,
+    db_type: String,
+    started_at: Instant,
+    idle_timeout: Duration,
+    last_used: Mutex<Instant>,
+    statement_count: AtomicU64,
+    /// `None` once committed or rolled back. Held for the duration of a
+    /// statement, so statements of one transaction never overlap.
+    session: tokio::sync::Mutex<Option<Box<dyn TransactionSession>>>,
+}
+
+impl OpenTransaction {
+    fn touch(&self) {
+        *self.last_used.lock().unwrap() = Instant::now();
+    }
+
+    fn idle_for(&self) -> Duration {
+        self.last_used.lock().unwrap().elapsed()
+    }
+
+    fn is_busy(&self) -> bool {
+        self.session.try_lock().is_err()
+    }
+
+    fn is_expired(&self) -> bool {
+        !self.is_busy() && self.idle_for() >= self.idle_timeout
+    }
+
+    fn info(&self, transaction_id: &str) -> TransactionInfo {
+        TransactionInfo {
+            transaction_id: transaction_id.to_string(),
+            connection_id: self.connection_id,
+            database: self.database.clone(),
+            db_type: self.db_type.clone(),
+            statement_count: self.statement_count.load(Ordering::Relaxed),
+            age_secs: self.started_at.elapsed().as_secs(),
+            idle_secs: self.idle_for().as_secs(),
+            idle_timeout_secs: self.idle_timeout.as_secs(),
+            busy: self.is_busy(),
+        }
+    }
+}
+
+/// Manual-commit transactions that span several requests. Each one owns a
+/// connection until it is committed, rolled back, or rolled back for idling.
+pub struct TransactionManager {
+    open: DashMap<String, Arc<OpenTransaction>>,
+    reaper_started: AtomicBool,
+}
+
+impl Default for TransactionManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransactionManager {
+    pub fn new() -> Self {
+        Self {
+            open: DashMap::new(),
+            reaper_started: AtomicBool::new(false),
+        }
+    }
+
+    /// Tracks a session returned by `TransactionDriver::begin_transaction`.
+    /// `idle_timeout` is capped at `MAX_IDLE_TIMEOUT`.
+    pub fn register(
+        self: &Arc<Self>,
+        connection_id: Uuid,
+        database: String,
+        db_type: String,
+        session: Box<dyn TransactionSession>,
+        idle_timeout: Option<Duration>,
+    ) -> TransactionInfo {
+        self.start_reaper();
+
+        let transaction_id = Uuid::new_v4().to_string();
+        let now = Instant::now();
+        let open = OpenTransaction {
+            connection_id,
+            database,
+            db_type,
+            started_at: now,
+            idle_timeout: idle_timeout
+                .unwrap_or(DEFAULT_IDLE_TIMEOUT)
+                .min(MAX_IDLE_TIMEOUT),
+            last_used: Mutex::new(now),
+            statement_count: AtomicU64::new(0),
+            session: tokio::sync::Mutex::new(Some(session)),
+        };
+        let info = open.info(&transaction_id);
+        tracing::info!(
+            "[Transactions] Began {} transaction {} on connection {}",
+            open.db_type,
+            transaction_id,
+            connection_id
+        );
+        self.open.insert(transaction_id, Arc::new(open));
+        info
+    }
+
+    pub async fn execute(
+        &self,
+        connection_id: Uuid,
+        transaction_id: &str,
+        query: &str,
+    ) -> Result<QueryResult> {
+        let open = self.get(connection_id, transaction_id)?;
+        let mut session = open.session.lock().await;
+        let session = session
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Transaction {} is already finished", transaction_id))?;
+
+        open.touch();
+        let result = session.execute_query(query).await;
+        open.touch();
+        open.statement_count.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+
+    pub async fn commit(&self, connection_id: Uuid, transaction_id: &str) -> Result<()> {
+        let session = self.take(connection_id, transaction_id).await?;
+        session.commit().await
+    }
+
+    pub async fn rollback(&self, connection_id: Uuid, transaction_id: &str) -> Result<()> {
+        let session = self.take(connection_id, transaction_id).await?;
+        session.rollback().await
+    }
+
+    /// Open transactions of one connection, oldest first.
+    pub fn list(&self, connection_id: Uuid) -> Vec<TransactionInfo> {
+        let mut infos: Vec<TransactionInfo> = self
+            .open
+            .iter()
+            .filter(|entry| entry.value().connection_id == connection_id)
+            .map(|entry| entry.value().info(entry.key()))
+            .collect();
+        infos.sort_by_key(|info| std::cmp::Reverse(info.age_secs));
+        infos
+    }
+
+    fn get(&self, connection_id: Uuid, transaction_id: &str) -> Result<Arc<OpenTransaction>> {
+        match self.open.get(transaction_id) {
+            Some(entry) if entry.value().connection_id == connection_id => {
+                Ok(entry.value().clone())
+            }
+            _ => Err(not_found(transaction_id)),
+        }
+    }
+
+    /// Stops tracking the transaction and returns its session, waiting for a
+    /// running statement to finish first.
+    async fn take(
+        &self,
+        connection_id: Uuid,
+        transaction_id: &str,
+    ) -> Result<Box<dyn TransactionSession>> {
+        let (_, open) = self
+            .open
+            .remove_if(transaction_id, |_, open| {
+                open.connection_id == connection_id
+            })
+            .ok_or_else(|| not_found(transaction_id))?;
+        let session = open.session.lock().await.take();
+        session.ok_or_else(|| anyhow::anyhow!("Transaction {} is already finished", transaction_id))
+    }
+
+    fn start_reaper(self: &Arc<Self>) {
+        spawn_reaper(
+            &self.reaper_started,
+            self,
+            REAPER_INTERVAL,
+            |manager| async move { manager.rollback_idle().await },
+        );
+    }
+
+    async fn rollback_idle(&self) {
+        let expired: Vec<String> = self
+            .open
+            .iter()
+            .filter(|entry| entry.value().is_expired())
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        for transaction_id in expired {
+            let open = match self
+                .open
+                .remove_if(&transaction_id, |_, open| open.is_expired())
+            {
+                Some((_, open)) => open,
+                None => continue,
+            };
+            tracing::info!(
+                "[Transactions] Rolling back transaction {} on connection {} after {}s idle",
+                transaction_id,
+                open.connection_id,
+                open.idle_for().as_secs()
+            );
+            let session = open.session.lock().await.take();
+            if let Some(session) = session {
+                if let Err(e) = session.rollback().await {
+                    tracing::warn!(
+                        "[Transactions] Rollback of idle transaction {} failed: {}",
+                        transaction_id,
+                        e
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn not_found(transaction_id: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "Transaction {} not found; it may have been rolled back after idling",
+        transaction_id
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    #[async_trait]
+    impl TransactionSession for Recorder {
+        async fn execute_query(&mut self, query: &str) -> Result<QueryResult> {
+            self.0.lock().unwrap().push(query.to_string());
+            Ok(QueryResult {
+                columns: vec![],
+                rows: vec![],
+                affected_rows: 1,
+                column_metadata: None,
+                total_count: None,
+                limit: None,
+                offset: None,
+                has_more: None,
+                row_metadata: None,
+                execution_time_ms: None,
+                json: None,
+                display_mode: None,
+                truncated: None,
+            })
+        }
+
+        async fn commit(self: Box<Self>) -> Result<()> {
+            self.0.lock().unwrap().push("COMMIT".to_string());
+            Ok(())
+        }
+
+        async fn rollback(self: Box<Self>) -> Result<()> {
+            self.0.lock().unwrap().push("ROLLBACK".to_string());
+            Ok(())
+        }
+    }
+
+    fn begin(
+        manager: &Arc<TransactionManager>,
+        connection_id: Uuid,
+        idle_timeout: Duration,
+    ) -> (String, Arc<Mutex<Vec<String>>>) {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let info = manager.register(
+            connection_id,
+            "main".to_string(),
+            "sqlite".to_string(),
+            Box::new(Recorder(log.clone())),
+            Some(idle_timeout),
+        );
+        (info.transaction_id, log)
+    }
+
+    #[tokio::test]
+    async fn statements_run_on_the_session_until_commit() {
+        let manager = Arc::new(TransactionManager::new());
+        let connection_id = Uuid::new_v4();
+        let (id, log) = begin(&manager, connection_id, DEFAULT_IDLE_TIMEOUT);
+
+        manager
+            .execute(connection_id, &id, "UPDATE t SET a = 1")
+            .await
+            .unwrap();
+        assert_eq!(manager.list(connection_id)[0].statement_count, 1);
+        // Other connections cannot reach it.
+        assert!(manager
+            .execute(Uuid::new_v4(), &id, "SELECT 1")
+            .await
+            .is_err());
+
+        manager.commit(connection_id, &id).await.unwrap();
+        assert!(manager.list(connection_id).is_empty());
+        assert!(manager.rollback(connection_id, &id).await.is_err());
+        assert_eq!(*log.lock().unwrap(), vec!["UPDATE t SET a = 1", "COMMIT"]);
+    }
+
+    #[tokio::test]
+    async fn idle_transactions_are_rolled_back() {
+        let manager = Arc::new(TransactionManager::new());
+        let connection_id = Uuid::new_v4();
+        let (idle, idle_log) = begin(&manager, connection_id, Duration::ZERO);
+        let (active, _) = begin(&manager, connection_id, DEFAULT_IDLE_TIMEOUT);
+
+        manager.rollback_idle().await;
+
+        let open: Vec<String> = manager
+            .list(connection_id)
+            .into_iter()
+            .map(|info| info.transaction_id)
+            .collect();
+        assert_eq!(open, vec![active]);
+        assert_eq!(*idle_log.lock().unwrap(), vec!["ROLLBACK"]);
+        assert!(manager
+            .execute(connection_id, &idle, "SELECT 1")
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,480 @@
+mod types;
+
+use crate::services::bulk;
+use crate::services::db_driver::{TableColumn, UniqueConstraint};
+use crate::services::table_filter::{FilterNode, SqlDialect};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use types::{classify, column_type, ColumnKind};
+use uuid::Uuid;
+
+/// Rows read from the source and written to the target per batch.
+pub const TRANSFER_BATCH_SIZE: usize = 1000;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// What happens to rows already in a target table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferMode {
+    /// Empty the tables first, all of them before any is copied.
+    Truncate,
+    /// Keep them and add the copied rows.
+    #[default]
+    Append,
+    /// Insert new rows and overwrite those whose primary key already exists.
+    Upsert,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransferSource {
+    pub connection_id: Uuid,
+    #[serde(default)]
+    pub schema: String,
+    pub tables: Vec<String>,
+    /// Applied to every table, like the data grid filter.
+    #[serde(default)]
+    pub filter: Option<FilterNode>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransferTarget {
+    pub connection_id: Uuid,
+    /// Schema (or database) to write to. Defaults to the source schema on the
+    /// same engine and to the target's default schema across engines.
+    #[serde(default)]
+    pub schema: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransferRequest {
+    pub source: TransferSource,
+    pub target: TransferTarget,
+    #[serde(default)]
+    pub mode: TransferMode,
+    /// Create target tables that do not exist yet instead of failing.
+    #[serde(default = "create_tables_default")]
+    pub create_tables: bool,
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+}
+
+fn create_tables_default() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferProgress {
+    pub table: String,
+    /// Position of `table` among the copied tables, from 1.
+    pub table_index: usize,
+    pub table_count: usize,
+    /// Rows of `table` written so far.
+    pub rows_written: u64,
+    pub elapsed_ms: u64,
+    pub rows_per_second: f64,
+}
+
+/// Receives throttled progress of a transfer.
+pub type OnTransferProgress<'a> = Option<&'a (dyn Fn(TransferProgress) + Send + Sync)>;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableTransfer {
+    pub table: String,
+    /// The table did not exist in the target and was created.
+    pub created: bool,
+    pub rows_written: u64,
+    pub execution_time_ms: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TransferReport {
+    pub tables: Vec<TableTransfer>,
+    pub rows_written: u64,
+    pub execution_time_ms: u64,
+}
+
+/// One message of a transfer streamed over HTTP, serialized with a `type`
+/// tag as an NDJSON line.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransferEvent {
+    Progress(TransferProgress),
+    Done(TransferReport),
+    Error { message: String },
+}
+
+/// Throttles the progress of one table.
+pub struct TransferMeter<'a> {
+    table: String,
+    table_index: usize,
+    table_count: usize,
+    started: Instant,
+    last_sent: Option<Instant>,
+    on_progress: OnTransferProgress<'a>,
+}
+
+impl<'a> TransferMeter<'a> {
+    pub fn new(
+        table: &str,
+        table_index: usize,
+        table_count: usize,
+        on_progress: OnTransferProgress<'a>,
+    ) -> Self {
+        Self {
+            table: table.to_string(),
+            table_index,
+            table_count,
+            started: Instant::now(),
+            last_sent: None,
+            on_progress,
+        }
+    }
+
+    /// Reports `rows_written` unless progress was sent recently. `force`
+    /// sends it regardless, for the last batch of a table.
+    pub fn tick(&mut self, rows_written: u64, force: bool) {
+        let Some(on_progress) = self.on_progress else {
+            return;
+        };
+        let now = Instant::now();
+        let due = !matches!(self.last_sent, Some(at) if now - at < PROGRESS_INTERVAL);
+        if !due && !force {
+            return;
+        }
+        self.last_sent = Some(now);
+        let elapsed = self.started.elapsed();
+        on_progress(TransferProgress {
+            table: self.table.clone(),
+            table_index: self.table_index,
+            table_count: self.table_count,
+            rows_written,
+            elapsed_ms: elapsed.as_millis() as u64,
+            rows_per_second: bulk::rows_per_second(rows_written, elapsed),
+        });
+    }
+
+    pub fn finish(&self, created: bool, rows_written: u64) -> TableTransfer {
+        TableTransfer {
+            table: self.table.clone(),
+            created,
+            rows_written,
+            execution_time_ms: self.started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+/// Orders `tables` so that tables referenced by a foreign key come before
+/// the tables referencing them. `references` maps a table to the tables it
+/// references; tables outside the transfer and cycles keep the given order.
+pub fn dependency_order(
+    tables: &[String],
+    references: &HashMap<String, Vec<String>>,
+) -> Vec<String> {
+    let mut remaining: Vec<&String> = tables.iter().collect();
+    let mut ordered: Vec<String> = Vec::with_capacity(tables.len());
+    while !remaining.is_empty() {
+        let ready = remaining
+            .iter()
+            .position(|table| {
+                let waiting = |other: &String| {
+                    other != *table && tables.contains(other) && !ordered.contains(other)
+                };
+                !references
+                    .get(*table)
+                    .is_some_and(|referenced| referenced.iter().any(waiting))
+            })
+            .unwrap_or(0);
+        ordered.push(remaining.remove(ready).clone());
+    }
+    ordered
+}
+
+/// `CREATE TABLE` for the target engine from the source's columns. Types
+/// are mapped between engines; defaults, checks and foreign keys are left
+/// out since their expressions do not carry over.
+pub fn create_table_sql(
+    source_engine: &str,
+    target_engine: &str,
+    dialect: SqlDialect,
+    table_ref: &str,
+    columns: &[TableColumn],
+    unique_constraints: &[UniqueConstraint],
+) -> Result<String> {
+    if columns.is_empty() {
+        return Err(anyhow!("Table has no columns"));
+    }
+    let primary_key: Vec<&str> = columns
+        .iter()
+        .filter(|column| column.is_primary_key)
+        .map(|column| column.name.as_str())
+        .collect();
+    let is_key = |name: &str| {
+        primary_key.contains(&name)
+            || unique_constraints
+                .iter()
+                .any(|unique| unique.columns.iter().any(|column| column == name))
+    };
+
+    let mut lines = Vec::with_capacity(columns.len() + 1);
+    for column in columns {
+        let key = is_key(&column.name);
+        let mut line = format!(
+            "    {} {}",
+            dialect.quote_ident(&column.name)?,
+            column_type(source_engine, target_engine, column, key)
+        );
+        if !column.is_nullable && target_engine != "clickhouse" {
+            line.push_str(" NOT NULL");
+        }
+        lines.push(line);
+    }
+    let key_list = |names: &[&str]| -> Result<String> {
+        Ok(names
+            .iter()
+            .map(|name| dialect.quote_ident(name))
+            .collect::<Result<Vec<_>>>()?
+            .join(", "))
+    };
+
+    if target_engine == "clickhouse" {
+        let order_by = if primary_key.is_empty() {
+            "tuple()".to_string()
+        } else {
+            format!("({})", key_list(&primary_key)?)
+        };
+        return Ok(format!(
+            "CREATE TABLE {} (\n{}\n) ENGINE = MergeTree ORDER BY {}",
+            table_ref,
+            lines.join(",\n"),
+            order_by
+        ));
+    }
+    if !primary_key.is_empty() {
+        lines.push(format!("    PRIMARY KEY ({})", key_list(&primary_key)?));
+    }
+    for unique in unique_constraints {
+        let names: Vec<&str> = unique.columns.iter().map(String::as_str).collect();
+        if !names.is_empty() && names != primary_key {
+            lines.push(format!("    UNIQUE ({})", key_list(&names)?));
+        }
+    }
+    Ok(format!(
+        "CREATE TABLE {} (\n{}\n)",
+        table_ref,
+        lines.join(",\n")
+    ))
+}
+
+/// Whether `data_type` of the `engine` is a boolean column.
+pub fn is_boolean(engine: &str, data_type: &str) -> bool {
+    classify(engine, data_type) == ColumnKind::Boolean
+}
+
+/// Turns the 0 and 1 read from MySQL's `tinyint(1)` and SQLite booleans into
+/// `false` and `true`, so that engines with a real boolean accept them.
+pub fn boolean_value(value: Value) -> Value {
+    match value {
+        Value::Number(n) => Value::Bool(n.as_f64() != Some(0.0)),
+        other => other,
+    }
+}
+
+/// Statements that remove every row of `table_refs`, given children first.
+/// Postgres empties them in one `TRUNCATE`, which foreign keys between them
+/// do not hold up. MySQL refuses to truncate a referenced table, so there
+/// (as in SQLite) rows are deleted table by table, children first.
+pub fn truncate_sql(engine: &str, table_refs: &[String]) -> Vec<String> {
+    match engine {
+        _ if table_refs.is_empty() => Vec::new(),
+        "postgres" => vec![format!("TRUNCATE TABLE {}", table_refs.join(", "))],
+        "mysql" | "sqlite" => table_refs
+            .iter()
+            .map(|table_ref| format!("DELETE FROM {}", table_ref))
+            .collect(),
+        _ => table_refs
+            .iter()
+            .map(|table_ref| format!("TRUNCATE TABLE {}", table_ref))
+            .collect(),
+    }
+}
+
+/// Bound values one statement may carry.
+pub fn max_params(engine: &str) -> usize {
+    match engine {
+        "sqlite" => 32_766,
+        _ => 65_535,
+    }
+}
+
+/// Multi-row `INSERT` of `rows` rows that overwrites rows with the same
+/// `keys`, written in the engine's own syntax.
+pub fn upsert_sql(
+    engine: &str,
+    dialect: SqlDialect,
+    table_ref: &str,
+    columns: &[String],
+    keys: &[String],
+    rows: usize,
+) -> Result<String> {
+    if keys.is_empty() {
+        return Err(anyhow!("Upsert needs a primary key on the target table"));
+    }
+    let quoted = columns
+        .iter()
+        .map(|column| dialect.quote_ident(column))
+        .collect::<Result<Vec<_>>>()?;
+    let mut next = 0;
+    let tuples: Vec<String> = (0..rows)
+        .map(|_| {
+            let placeholders: Vec<String> = (0..columns.len())
+                .map(|_| {
+                    next += 1;
+                    dialect.placeholder(next)
+                })
+                .collect();
+            format!("({})", placeholders.join(", "))
+        })
+        .collect();
+    let updated: Vec<&String> = quoted
+        .iter()
+        .zip(columns)
+        .filter(|(_, column)| !keys.contains(column))
+        .map(|(quoted, _)| quoted)
+        .collect();
+
+    let conflict = match engine {
+        "postgres" | "sqlite" => {
+            let keys = keys
+                .iter()
+                .map(|key| dialect.quote_ident(key))
+                .collect::<Result<Vec<_>>>()?;
+            if updated.is_empty() {
+                format!("ON CONFLICT ({}) DO NOTHING", keys.join(", "))
+            } else {
+                let assignments: Vec<String> = updated
+                    .iter()
+                    .map(|column| format!("{0} = excluded.{0}", column))
+                    .collect();
+                format!(
+                    "ON CONFLICT ({}) DO UPDATE SET {}",
+                    keys.join(", "),
+                    assignments.join(", ")
+                )
+            }
+        }
+        "mysql" => {
+            let assignments: Vec<String> = if updated.is_empty() {
+                vec![format!("{0} = {0}", dialect.quote_ident(&keys[0])?)]
+            } else {
+                updated
+                    .iter()
+                    .map(|column| format!("{0} = VALUES({0})", column))
+                    .collect()
+            };
+            format!("ON DUPLICATE KEY UPDATE {}", assignments.join(", "))
+        }
+        _ => return Err(anyhow!("Upsert is not supported for {} targets", engine)),
+    };
+    Ok(format!(
+        "INSERT INTO {} ({}) VALUES {} {}",
+        table_ref,
+        quoted.join(", "),
+        tuples.join(", "),
+        conflict
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str, is_nullable: bool, is_primary_key: bool) -> TableColumn {
+        TableColumn {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            is_nullable,
+            is_primary_key,
+            is_foreign_key: false,
+            default_value: None,
+        }
+    }
+
+    #[test]
+    fn creates_tables_in_the_target_dialect() {
+        let columns = vec![
+            column("id", "bigint", false, true),
+            column("email", "character varying(200)", false, false),
+            column("note", "text", true, false),
+        ];
+        let unique = vec![UniqueConstraint {
+            constraint_name: "users_email_key".to_string(),
+            columns: vec!["email".to_string()],
+        }];
+        assert_eq!(
+            create_table_sql("postgres", "mysql", SqlDialect::MYSQL, "`users`", &columns, &unique)
+                .unwrap(),
+            "CREATE TABLE `users` (\n    `id` bigint NOT NULL,\n    `email` varchar(200) NOT NULL,\n    `note` longtext,\n    PRIMARY KEY (`id`),\n    UNIQUE (`email`)\n)"
+        );
+        assert_eq!(
+            create_table_sql("postgres", "clickhouse", SqlDialect::CLICKHOUSE, "`users`", &columns, &[])
+                .unwrap(),
+            "CREATE TABLE `users` (\n    `id` Int64,\n    `email` String,\n    `note` Nullable(String)\n) ENGINE = MergeTree ORDER BY (`id`)"
+        );
+    }
+
+    #[test]
+    fn builds_upserts_per_engine() {
+        let columns = vec!["id".to_string(), "name".to_string()];
+        let keys = vec!["id".to_string()];
+        assert_eq!(
+            upsert_sql(
+                "postgres",
+                SqlDialect::POSTGRES,
+                "\"t\"",
+                &columns,
+                &keys,
+                2
+            )
+            .unwrap(),
+            r#"INSERT INTO "t" ("id", "name") VALUES ($1, $2), ($3, $4) ON CONFLICT ("id") DO UPDATE SET "name" = excluded."name""#
+        );
+        assert_eq!(
+            upsert_sql("mysql", SqlDialect::MYSQL, "`t`", &columns, &keys, 1).unwrap(),
+            "INSERT INTO `t` (`id`, `name`) VALUES (?, ?) ON DUPLICATE KEY UPDATE `name` = VALUES(`name`)"
+        );
+        assert!(upsert_sql("mysql", SqlDialect::MYSQL, "`t`", &columns, &[], 1).is_err());
+    }
+
+    #[test]
+    fn orders_referenced_tables_first() {
+        let tables: Vec<String> = ["orders", "items", "users"].map(String::from).to_vec();
+        let references = HashMap::from([
+            ("orders".to_string(), vec!["users".to_string()]),
+            (
+                "items".to_string(),
+                vec!["orders".to_string(), "products".to_string()],
+            ),
+        ]);
+        assert_eq!(
+            dependency_order(&tables, &references),
+            vec!["users", "orders", "items"]
+        );
+    }
+
+    #[test]
+    fn truncates_children_first() {
+        let tables: Vec<String> = ["\"items\"", "\"orders\""].map(String::from).to_vec();
+        assert_eq!(
+            truncate_sql("postgres", &tables),
+            vec![r#"TRUNCATE TABLE "items", "orders""#]
+        );
+        assert_eq!(
+            truncate_sql("mysql", &tables),
+            vec![r#"DELETE FROM "items""#, r#"DELETE FROM "orders""#]
+        );
+        assert!(truncate_sql("postgres", &[]).is_empty());
+    }
+}
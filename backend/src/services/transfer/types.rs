@@ -0,0 +1,262 @@
+use crate::services::db_driver::TableColumn;
+
+/// Engine-neutral column type, read from the source's type name and written
+/// in the target's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnKind {
+    Boolean,
+    SmallInt,
+    Integer,
+    BigInt,
+    Real,
+    Double,
+    /// Precision and scale, when the source has them.
+    Decimal(Option<(u32, u32)>),
+    Char(Option<u32>),
+    Varchar(Option<u32>),
+    Text,
+    Binary,
+    Date,
+    Time,
+    Timestamp,
+    TimestampTz,
+    Uuid,
+    Json,
+}
+
+/// Reads `data_type` as reported by `get_columns` of the `engine` driver.
+/// Unknown types are copied as text.
+pub fn classify(engine: &str, data_type: &str) -> ColumnKind {
+    let lower = unwrap_clickhouse(data_type.trim()).to_lowercase();
+    if lower.ends_with("[]") || lower.starts_with("array(") {
+        return ColumnKind::Json;
+    }
+    let (name, args) = split_args(&lower);
+    let name = name.trim();
+    let first = args.first().copied();
+
+    match (engine, name) {
+        // SQLite, ClickHouse and Postgres disagree on how wide these are.
+        ("clickhouse", "int8" | "uint8" | "int16") => ColumnKind::SmallInt,
+        ("clickhouse", "uint16" | "int32") => ColumnKind::Integer,
+        ("clickhouse", "uint32" | "int64") => ColumnKind::BigInt,
+        ("clickhouse", "uint64") => ColumnKind::Decimal(Some((20, 0))),
+        ("clickhouse", "fixedstring") => ColumnKind::Char(first),
+        ("clickhouse", "datetime" | "datetime64") => ColumnKind::Timestamp,
+        ("mysql", "float") => ColumnKind::Real,
+        ("mysql", "timestamp") => ColumnKind::Timestamp,
+        ("mysql", "tinyint") if first == Some(1) => ColumnKind::Boolean,
+        ("sqlite", "integer" | "int") => ColumnKind::BigInt,
+        (_, "bool" | "boolean") => ColumnKind::Boolean,
+        (_, "tinyint" | "smallint" | "int2" | "smallserial") => ColumnKind::SmallInt,
+        (_, "integer" | "int" | "int4" | "mediumint" | "serial" | "int32") => ColumnKind::Integer,
+        (_, "bigint" | "int8" | "bigserial" | "int64" | "long") => ColumnKind::BigInt,
+        (_, "real" | "float4" | "float32") => ColumnKind::Real,
+        (_, "double precision" | "double" | "float8" | "float" | "float64") => ColumnKind::Double,
+        (_, "numeric" | "decimal" | "money" | "decimal128") => {
+            ColumnKind::Decimal(match args.as_slice() {
+                [precision, scale] => Some((*precision, *scale)),
+                [precision] => Some((*precision, 0)),
+                _ => None,
+            })
+        }
+        (_, "char" | "character" | "bpchar" | "nchar") => ColumnKind::Char(first),
+        (_, "varchar" | "character varying" | "nvarchar" | "varying character") => {
+            ColumnKind::Varchar(first)
+        }
+        (_, "bytea" | "blob" | "tinyblob" | "mediumblob" | "longblob" | "binary" | "varbinary") => {
+            ColumnKind::Binary
+        }
+        (_, "date" | "date32") => ColumnKind::Date,
+        (_, "time" | "time without time zone") => ColumnKind::Time,
+        (_, "timestamp" | "timestamp without time zone" | "datetime") => ColumnKind::Timestamp,
+        (_, "timestamptz" | "timestamp with time zone") => ColumnKind::TimestampTz,
+        (_, "uuid" | "uniqueidentifier") => ColumnKind::Uuid,
+        (_, "json" | "jsonb" | "object" | "array" | "map" | "tuple") => ColumnKind::Json,
+        ("sqlite", _) => sqlite_affinity(name),
+        _ => ColumnKind::Text,
+    }
+}
+
+/// Type name of `kind` in `engine`. Key columns of MySQL get a bounded
+/// type, since it cannot index `TEXT` and `BLOB` without a prefix length.
+pub fn render(engine: &str, kind: ColumnKind, is_key: bool) -> String {
+    use ColumnKind::*;
+    match engine {
+        "postgres" => match kind {
+            Boolean => "boolean".into(),
+            SmallInt => "smallint".into(),
+            Integer => "integer".into(),
+            BigInt => "bigint".into(),
+            Real => "real".into(),
+            Double => "double precision".into(),
+            Decimal(Some((p, s))) => format!("numeric({}, {})", p, s),
+            Decimal(None) => "numeric".into(),
+            Char(Some(n)) => format!("char({})", n),
+            Char(None) => "char".into(),
+            Varchar(Some(n)) => format!("varchar({})", n),
+            Varchar(None) | Text => "text".into(),
+            Binary => "bytea".into(),
+            Date => "date".into(),
+            Time => "time".into(),
+            Timestamp => "timestamp".into(),
+            TimestampTz => "timestamptz".into(),
+            Uuid => "uuid".into(),
+            Json => "jsonb".into(),
+        },
+        "mysql" => match kind {
+            Boolean => "tinyint(1)".into(),
+            SmallInt => "smallint".into(),
+            Integer => "int".into(),
+            BigInt => "bigint".into(),
+            Real => "float".into(),
+            Double => "double".into(),
+            Decimal(Some((p, s))) => format!("decimal({}, {})", p.min(65), s.min(30)),
+            Decimal(None) => "decimal(65, 30)".into(),
+            Char(n) => format!("char({})", n.unwrap_or(1).min(255)),
+            Varchar(Some(n)) if n <= 16_383 => format!("varchar({})", n),
+            Varchar(_) | Text if is_key => "varchar(255)".into(),
+            Varchar(_) | Text => "longtext".into(),
+            Binary if is_key => "varbinary(255)".into(),
+            Binary => "longblob".into(),
+            Date => "date".into(),
+            Time => "time(6)".into(),
+            Timestamp | TimestampTz => "datetime(6)".into(),
+            Uuid => "char(36)".into(),
+            Json => "json".into(),
+        },
+        "clickhouse" => match kind {
+            Boolean => "Bool".into(),
+            SmallInt => "Int16".into(),
+            Integer => "Int32".into(),
+            BigInt => "Int64".into(),
+            Real => "Float32".into(),
+            Double => "Float64".into(),
+            Decimal(Some((p, s))) => format!("Decimal({}, {})", p.clamp(1, 76), s.min(p)),
+            Decimal(None) => "Decimal(38, 10)".into(),
+            Char(_) | Varchar(_) | Text | Binary | Time | Json => "String".into(),
+            Date => "Date32".into(),
+            Timestamp => "DateTime64(6)".into(),
+            TimestampTz => "DateTime64(6, 'UTC')".into(),
+            Uuid => "UUID".into(),
+        },
+        // SQLite keeps the declared name for tools to read; only the
+        // affinity it implies matters for storage.
+        _ => match kind {
+            Boolean => "BOOLEAN".into(),
+            SmallInt | Integer | BigInt => "INTEGER".into(),
+            Real | Double => "REAL".into(),
+            Decimal(_) => "NUMERIC".into(),
+            Char(_) | Varchar(_) | Text | Uuid | Json => "TEXT".into(),
+            Binary => "BLOB".into(),
+            Date => "DATE".into(),
+            Time => "TIME".into(),
+            Timestamp | TimestampTz => "DATETIME".into(),
+        },
+    }
+}
+
+/// Type of `column` in the `target` engine, wrapped in `Nullable(...)`
+/// where ClickHouse needs it.
+pub fn column_type(source: &str, target: &str, column: &TableColumn, is_key: bool) -> String {
+    let rendered = render(target, classify(source, &column.data_type), is_key);
+    if target == "clickhouse" && column.is_nullable && !is_key {
+        format!("Nullable({})", rendered)
+    } else {
+        rendered
+    }
+}
+
+/// Strips `Nullable(...)` and `LowCardinality(...)`.
+fn unwrap_clickhouse(data_type: &str) -> &str {
+    let mut inner = data_type;
+    for wrapper in ["LowCardinality(", "Nullable("] {
+        if let Some(rest) = inner.strip_prefix(wrapper) {
+            inner = rest.strip_suffix(')').unwrap_or(rest);
+        }
+    }
+    inner
+}
+
+/// `numeric(10, 2)` into `numeric` and `[10, 2]`, and `timestamp(3) with
+/// time zone` into `timestamp with time zone` and `[3]`. Arguments that are
+/// not numbers (time zones, enum values) are dropped.
+fn split_args(data_type: &str) -> (String, Vec<u32>) {
+    let Some(open) = data_type.find('(') else {
+        return (data_type.to_string(), Vec::new());
+    };
+    let close = data_type
+        .rfind(')')
+        .unwrap_or(data_type.len())
+        .max(open + 1);
+    let args = data_type[open + 1..close]
+        .split(',')
+        .filter_map(|arg| arg.trim().parse().ok())
+        .collect();
+    let suffix = data_type.get(close + 1..).unwrap_or("").trim();
+    let name = format!("{} {}", data_type[..open].trim(), suffix);
+    (name.trim().to_string(), args)
+}
+
+/// Type affinity rules of SQLite for declared names it does not share with
+/// the other engines.
+fn sqlite_affinity(name: &str) -> ColumnKind {
+    if name.contains("int") {
+        ColumnKind::BigInt
+    } else if name.contains("char") || name.contains("clob") || name.contains("text") {
+        ColumnKind::Text
+    } else if name.contains("blob") {
+        ColumnKind::Binary
+    } else if name.contains("real") || name.contains("floa") || name.contains("doub") {
+        ColumnKind::Double
+    } else if name.is_empty() {
+        ColumnKind::Text
+    } else {
+        ColumnKind::Decimal(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_types_between_engines() {
+        let kind = classify("postgres", "character varying(120)");
+        assert_eq!(kind, ColumnKind::Varchar(Some(120)));
+        assert_eq!(render("mysql", kind, false), "varchar(120)");
+        assert_eq!(render("sqlite", kind, false), "TEXT");
+
+        let kind = classify("postgres", "numeric(10,2)");
+        assert_eq!(render("clickhouse", kind, false), "Decimal(10, 2)");
+        assert_eq!(
+            classify("postgres", "timestamp(3) with time zone"),
+            ColumnKind::TimestampTz
+        );
+        assert_eq!(classify("postgres", "integer[]"), ColumnKind::Json);
+
+        assert_eq!(
+            classify("clickhouse", "Nullable(UInt32)"),
+            ColumnKind::BigInt
+        );
+        assert_eq!(
+            classify("clickhouse", "DateTime64(3, 'UTC')"),
+            ColumnKind::Timestamp
+        );
+        assert_eq!(
+            render("postgres", classify("mysql", "tinyint"), false),
+            "smallint"
+        );
+        assert_eq!(
+            render("postgres", classify("mysql", "tinyint(1)"), false),
+            "boolean"
+        );
+        assert_eq!(
+            render("mysql", classify("sqlite", "TEXT"), true),
+            "varchar(255)"
+        );
+        assert_eq!(classify("sqlite", "UNSIGNED BIG INT"), ColumnKind::BigInt);
+        assert_eq!(classify("sqlite", ""), ColumnKind::Text);
+        assert_eq!(classify("mongodb", "objectId"), ColumnKind::Text);
+    }
+}
@@ -0,0 +1,74 @@
+// Files sent to and from the HTTP API. Requests never name paths on this
+// machine: uploads and downloads go through private temporary files.
+use axum::body::Body;
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tempfile::TempPath;
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
+
+/// A new empty temporary file ending in `suffix` (e.g. `.csv`), readable only
+/// by the current user and removed when the returned path is dropped.
+pub fn temp_path(suffix: &str) -> std::io::Result<TempPath> {
+    Ok(tempfile::Builder::new()
+        .prefix("dbplus-")
+        .suffix(suffix)
+        .tempfile()?
+        .into_temp_path())
+}
+
+/// Writes a request body to a temporary file ending in `suffix`.
+pub async fn receive(body: Body, suffix: &str) -> anyhow::Result<TempPath> {
+    let path = temp_path(suffix)?;
+    let mut file = tokio::fs::File::create(&path).await?;
+    let mut chunks = body.into_data_stream();
+    while let Some(chunk) = chunks.next().await {
+        file.write_all(&chunk?).await?;
+    }
+    file.flush().await?;
+    Ok(path)
+}
+
+/// Sends `path` as an attachment named `file_name`; the file is removed once
+/// the response body is dropped.
+pub async fn download(
+    path: TempPath,
+    file_name: &str,
+    content_type: &'static str,
+) -> anyhow::Result<Response> {
+    let file = tokio::fs::File::open(&path).await?;
+    let length = file.metadata().await?.len();
+    let body = Body::from_stream(TempFileStream {
+        chunks: ReaderStream::new(file),
+        _path: path,
+    });
+
+    let mut response = (StatusCode::OK, body).into_response();
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(length));
+    if let Ok(disposition) =
+        HeaderValue::from_str(&format!("attachment; filename=\"{}\"", file_name))
+    {
+        headers.insert(header::CONTENT_DISPOSITION, disposition);
+    }
+    Ok(response)
+}
+
+/// Fields drop in order, so the file is closed before it is removed.
+struct TempFileStream {
+    chunks: ReaderStream<tokio::fs::File>,
+    _path: TempPath,
+}
+
+impl Stream for TempFileStream {
+    type Item = std::io::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.chunks).poll_next(cx)
+    }
+}
@@ -1,3 +1,4 @@
+pub mod http_file;
 pub mod pg_dump_finder;
 pub mod request;
 pub mod sql_parser;
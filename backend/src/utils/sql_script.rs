@@ -3,8 +3,42 @@ pub fn count_sql_statements(script: &str) -> u64 {
 }
 
 pub fn split_sql_statements(script: &str) -> Vec<String> {
+    split_sql_statements_with_positions(script)
+        .into_iter()
+        .map(|statement| statement.sql)
+        .collect()
+}
+
+/// One statement of a script and where it starts in the script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptStatement {
+    pub sql: String,
+    /// Byte offset of the statement's first character.
+    pub offset: usize,
+    /// 1-based line of the first character.
+    pub line: usize,
+    /// 1-based column (in characters) of the first character.
+    pub column: usize,
+}
+
+pub fn split_sql_statements_with_positions(script: &str) -> Vec<ScriptStatement> {
     let mut out = Vec::new();
     let mut buf = String::new();
+    // Char index of the first character in `buf`.
+    let mut buf_start = 0usize;
+    let byte_offsets: Vec<usize> = script.char_indices().map(|(offset, _)| offset).collect();
+    let mut push = |buf: &str, buf_start: usize| {
+        let stmt = buf.trim();
+        if !stmt.is_empty() {
+            let leading = buf.len() - buf.trim_start().len();
+            out.push(ScriptStatement {
+                sql: stmt.to_string(),
+                offset: byte_offsets[buf_start] + leading,
+                line: 0,
+                column: 0,
+            });
+        }
+    };
 
     let mut in_single = false;
     let mut in_double = false;
@@ -131,12 +165,10 @@ pub fn split_sql_statements(script: &str) -> Vec<String> {
         }
 
         if !in_single && !in_double && c == ';' {
-            let stmt = buf.trim();
-            if !stmt.is_empty() {
-                out.push(stmt.to_string());
-            }
+            push(&buf, buf_start);
             buf.clear();
             i += 1;
+            buf_start = i;
             continue;
         }
 
@@ -144,11 +176,56 @@ pub fn split_sql_statements(script: &str) -> Vec<String> {
         i += 1;
     }
 
-    let last = buf.trim();
-    if !last.is_empty() {
-        out.push(last.to_string());
-    }
+    push(&buf, buf_start);
 
+    set_line_and_column(script, &mut out);
     out
 }
 
+/// Fills in `line` and `column` from `offset` in one pass over the script.
+fn set_line_and_column(script: &str, statements: &mut [ScriptStatement]) {
+    let mut line = 1;
+    let mut line_start = 0;
+    let mut scanned = 0;
+    for statement in statements {
+        for (offset, c) in script[scanned..statement.offset].char_indices() {
+            if c == '\n' {
+                line += 1;
+                line_start = scanned + offset + 1;
+            }
+        }
+        scanned = statement.offset;
+        statement.line = line;
+        statement.column = script[line_start..statement.offset].chars().count() + 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_with_positions() {
+        let script =
+            "SELECT 1;\n  -- note; still a comment\nINSERT INTO t VALUES ('a;b');\tSELECT $$;$$";
+        let statements = split_sql_statements_with_positions(script);
+        let positions: Vec<(&str, usize, usize)> = statements
+            .iter()
+            .map(|s| (s.sql.as_str(), s.line, s.column))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![
+                ("SELECT 1", 1, 1),
+                (
+                    "-- note; still a comment\nINSERT INTO t VALUES ('a;b')",
+                    2,
+                    3
+                ),
+                ("SELECT $$;$$", 3, 31),
+            ]
+        );
+        assert_eq!(&script[statements[2].offset..], "SELECT $$;$$");
+    }
+}
+
@@ -2,7 +2,6 @@ use serde::{Deserialize, Serialize};
 use tauri::State;
 use dbplus_backend::AppState;
 use std::sync::Arc;
-use dbplus_backend::services::connection_service::ConnectionService;
 use dbplus_backend::services::postgres_driver::PostgresDriver;
 use dbplus_backend::services::db_driver::DatabaseDriver;
 use dbplus_backend::services::autocomplete::{AutocompleteEngine, AutocompleteRequest};
@@ -36,7 +35,7 @@ pub async fn autocomplete_suggest(
     let uuid = uuid::Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
     
     // 1. Get Connection Service
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     // 2. Get Connection & Password
@@ -117,7 +116,7 @@ pub async fn schema_refresh(
         },
     };
 
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let (connection, password) = conn_service
@@ -55,6 +55,12 @@ pub struct CreateConnectionRequest {
     #[serde(default)]
     pub tags: Option<String>,
     #[serde(default)]
+    pub statement_timeout_ms: Option<i32>,
+    #[serde(default)]
+    pub max_result_rows: Option<i32>,
+    #[serde(default)]
+    pub max_result_bytes: Option<i64>,
+    #[serde(default)]
     pub id: Option<String>,
 }
 
@@ -73,9 +79,7 @@ pub struct TestConnectionResponse {
 pub async fn list_connections(
     state: State<'_, AppState>,
 ) -> Result<Vec<dbplus_backend::models::entities::connection::Model>, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
     
     let connections = service.get_all_connections()
@@ -99,10 +103,8 @@ pub async fn get_connection(
     state: State<'_, AppState>,
     id: String,
 ) -> Result<Option<dbplus_backend::models::entities::connection::Model>, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
     let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
     
     let connection = service.get_connection_by_id(uuid)
@@ -121,13 +123,12 @@ pub async fn create_connection(
     state: State<'_, AppState>,
     request: CreateConnectionRequest,
 ) -> Result<dbplus_backend::models::entities::connection::Model, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
     use dbplus_backend::models::entities::connection;
     use chrono::Utc;
     
     println!("[Create Connection] Request received: name={}, type={}", request.name, request.db_type);
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| {
             println!("[Create Connection] Service init failed: {}", e);
             e.to_string()
@@ -200,6 +201,9 @@ pub async fn create_connection(
         is_read_only: request.is_read_only,
         environment: request.environment.unwrap_or_else(|| "development".to_string()),
         safe_mode_level: request.safe_mode_level.unwrap_or(1),
+        statement_timeout_ms: request.statement_timeout_ms,
+        max_result_rows: request.max_result_rows,
+        max_result_bytes: request.max_result_bytes,
         last_used: None,
         created_at: Utc::now().into(),
         updated_at: Utc::now().into(),
@@ -223,12 +227,11 @@ pub async fn update_connection(
     id: String,
     request: CreateConnectionRequest,
 ) -> Result<dbplus_backend::models::entities::connection::Model, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
     use dbplus_backend::models::entities::connection;
     use chrono::Utc;
     
     let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let (host, port, username, password) = if request.db_type.as_str() == "sqlite" {
@@ -276,6 +279,9 @@ pub async fn update_connection(
         is_read_only: request.is_read_only,
         environment: request.environment.unwrap_or_else(|| "development".to_string()),
         safe_mode_level: request.safe_mode_level.unwrap_or(1),
+        statement_timeout_ms: request.statement_timeout_ms,
+        max_result_rows: request.max_result_rows,
+        max_result_bytes: request.max_result_bytes,
         last_used: None,
         created_at: Utc::now().into(),
         updated_at: Utc::now().into(),
@@ -291,10 +297,8 @@ pub async fn delete_connection(
     state: State<'_, AppState>,
     id: String,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
     let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
     
     service.delete_connection(uuid)
@@ -307,11 +311,10 @@ pub async fn test_connection(
     state: State<'_, AppState>,
     request: CreateConnectionRequest,
 ) -> Result<TestConnectionResponse, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
     use dbplus_backend::models::entities::connection;
     use chrono::Utc;
     
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let connection_name = request.name.clone();
@@ -356,6 +359,9 @@ pub async fn test_connection(
         is_read_only: request.is_read_only,
         environment: request.environment.unwrap_or_else(|| "development".to_string()),
         safe_mode_level: request.safe_mode_level.unwrap_or(1),
+        statement_timeout_ms: request.statement_timeout_ms,
+        max_result_rows: request.max_result_rows,
+        max_result_bytes: request.max_result_bytes,
         last_used: None,
         created_at: Utc::now().into(),
         updated_at: Utc::now().into(),
@@ -420,10 +426,8 @@ pub async fn test_connection_by_id(
     state: State<'_, AppState>,
     id: String,
 ) -> Result<TestConnectionResponse, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
     let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let (connection, password) = service.get_connection_with_password(uuid)
@@ -477,8 +481,6 @@ pub async fn switch_database(
     id: String,
     request: SwitchDatabaseRequest,
 ) -> Result<dbplus_backend::models::entities::connection::Model, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
     let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
     let database = request.database.trim().to_string();
     
@@ -489,10 +491,23 @@ pub async fn switch_database(
         return Err("Database name is too long (max 63 chars)".to_string());
     }
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
     
     service.update_connection_database(uuid, database)
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+pub async fn get_tls_support(
+) -> Result<Vec<dbplus_backend::services::tls::TlsSupport>, String> {
+    Ok(dbplus_backend::services::tls::tls_support().to_vec())
+}
+
+#[tauri::command]
+pub async fn get_pool_stats(
+    state: State<'_, AppState>,
+) -> Result<Vec<dbplus_backend::services::driver_registry::DriverPoolStats>, String> {
+    Ok(state.drivers.stats().await)
+}
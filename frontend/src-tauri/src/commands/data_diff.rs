@@ -0,0 +1,16 @@
+use tauri::State;
+use dbplus_backend::AppState;
+use dbplus_backend::services::data_diff::{DataDiffReport, DataDiffRequest};
+
+/// Compares the rows of two tables, possibly on different engines, and
+/// returns the statements syncing the target.
+#[tauri::command]
+pub async fn compare_table_data(
+    state: State<'_, AppState>,
+    request: DataDiffRequest,
+) -> Result<DataDiffReport, String> {
+    let service = state.connection_service().map_err(|e| e.to_string())?;
+    service.compare_table_data(&request)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,24 +1,33 @@
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use tauri::State;
 use dbplus_backend::AppState;
+use dbplus_backend::services::dump::{DumpOptions, DumpSummary};
 use uuid::Uuid;
-use dbplus_backend::services::connection_service::ConnectionService;
 
 #[derive(Debug, Deserialize)]
 pub struct ExecuteScriptRequest {
     pub script: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DumpDatabaseRequest {
+    /// File to write; replaced if it exists.
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub options: DumpOptions,
+    #[serde(default)]
+    pub database: Option<String>,
+}
+
 #[tauri::command]
 pub async fn execute_script(
     state: State<'_, AppState>,
     connection_id: String,
     request: ExecuteScriptRequest,
 ) -> Result<serde_json::Value, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let rows_affected = service.execute_script(uuid, &request.script)
@@ -35,11 +44,10 @@ pub async fn backup_postgres_sql(
     state: State<'_, AppState>,
     connection_id: String,
 ) -> Result<String, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
     use dbplus_backend::models::export_ddl::{ExportDdlOptions, DdlScope};
 
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let options = ExportDdlOptions {
@@ -60,3 +68,20 @@ pub async fn backup_postgres_sql(
         .await
         .map_err(|e| e.to_string())
 }
+
+/// Writes a dump of a MySQL or SQLite database to a file, without external tools.
+#[tauri::command]
+pub async fn dump_database(
+    state: State<'_, AppState>,
+    connection_id: String,
+    request: DumpDatabaseRequest,
+) -> Result<DumpSummary, String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let service = state.connection_service()
+        .map_err(|e| e.to_string())?
+        .with_database_override(request.database);
+
+    service.dump_database(uuid, request.path, request.options)
+        .await
+        .map_err(|e| e.to_string())
+}
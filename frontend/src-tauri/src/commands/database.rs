@@ -16,10 +16,8 @@ pub async fn list_databases(
     state: State<'_, AppState>,
     connection_id: String,
 ) -> Result<Vec<String>, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     service.get_databases(uuid)
@@ -33,10 +31,8 @@ pub async fn create_database(
     connection_id: String,
     request: DatabaseRequest,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     service.create_database(uuid, &request.name, request.options)
@@ -50,10 +46,8 @@ pub async fn drop_database(
     connection_id: String,
     name: String,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     service.drop_database(uuid, &name)
@@ -0,0 +1,85 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+use tauri::State;
+use dbplus_backend::AppState;
+use dbplus_backend::services::export::{ExportOptions, ExportSummary, TableExport};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQueryRequest {
+    pub sql: String,
+    /// File to write; replaced if it exists.
+    pub path: PathBuf,
+    #[serde(default)]
+    pub options: ExportOptions,
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default)]
+    pub query_id: Option<String>,
+    #[serde(default)]
+    pub confirmed_unsafe: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportTableRequest {
+    #[serde(flatten)]
+    pub source: TableExport,
+    pub path: PathBuf,
+    #[serde(default)]
+    pub options: ExportOptions,
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default)]
+    pub query_id: Option<String>,
+}
+
+/// Streams the result of a query into a file.
+#[tauri::command]
+pub async fn export_query(
+    state: State<'_, AppState>,
+    connection_id: String,
+    request: ExportQueryRequest,
+) -> Result<ExportSummary, String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let cancellation_token = request.query_id.as_deref()
+        .map(|id| state.register_query(id).token().clone())
+        .unwrap_or_default();
+    let service = state.connection_service()
+        .map_err(|e| e.to_string())?
+        .with_database_override(request.database.clone());
+
+    // Cancelling stops reading rows and removes the partial file.
+    let result = tokio::select! {
+        res = service.export_query(uuid, &request.sql, request.confirmed_unsafe, request.path, request.options) => res.map_err(|e| e.to_string()),
+        _ = cancellation_token.cancelled() => Err("Export cancelled".to_string()),
+    };
+    if let Some(id) = &request.query_id {
+        state.finish_query(id);
+    }
+    result
+}
+
+/// Streams a table, filtered and sorted like the data grid, into a file.
+#[tauri::command]
+pub async fn export_table(
+    state: State<'_, AppState>,
+    connection_id: String,
+    request: ExportTableRequest,
+) -> Result<ExportSummary, String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let cancellation_token = request.query_id.as_deref()
+        .map(|id| state.register_query(id).token().clone())
+        .unwrap_or_default();
+    let service = state.connection_service()
+        .map_err(|e| e.to_string())?
+        .with_database_override(request.database.clone());
+
+    let result = tokio::select! {
+        res = service.export_table(uuid, request.source, request.path, request.options) => res.map_err(|e| e.to_string()),
+        _ = cancellation_token.cancelled() => Err("Export cancelled".to_string()),
+    };
+    if let Some(id) = &request.query_id {
+        state.finish_query(id);
+    }
+    result
+}
@@ -2,7 +2,6 @@ use serde::{Deserialize, Serialize};
 use tauri::State;
 use dbplus_backend::AppState;
 use uuid::Uuid;
-use dbplus_backend::services::connection_service::ConnectionService;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -40,7 +39,7 @@ pub async fn export_postgres_ddl(
     request: ExportDdlOptions,
 ) -> Result<ExportDdlResult, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let (connection, _password) = conn_service
@@ -2,7 +2,6 @@ use serde::{Deserialize, Serialize};
 use tauri::State;
 use dbplus_backend::AppState;
 use uuid::Uuid;
-use dbplus_backend::services::connection_service::ConnectionService;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ExtensionInfo {
@@ -17,10 +16,8 @@ pub async fn list_extensions(
     state: State<'_, AppState>,
     connection_id: String,
 ) -> Result<Vec<ExtensionInfo>, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let extensions = service.list_extensions(uuid)
@@ -48,10 +45,8 @@ pub async fn install_extension(
     connection_id: String,
     request: InstallExtensionRequest,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     service.install_extension(uuid, &request.name, request.schema.as_deref(), request.version.as_deref())
@@ -65,10 +60,8 @@ pub async fn drop_extension(
     connection_id: String,
     name: String,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     service.drop_extension(uuid, &name)
@@ -0,0 +1,62 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+use tauri::State;
+use dbplus_backend::AppState;
+use dbplus_backend::services::import::{ImportOptions, ImportPreview, ImportReport, ImportSettings, ImportTarget};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct PreviewImportRequest {
+    #[serde(flatten)]
+    pub target: ImportTarget,
+    pub path: PathBuf,
+    #[serde(default)]
+    pub options: ImportOptions,
+    #[serde(default)]
+    pub database: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportFileRequest {
+    #[serde(flatten)]
+    pub target: ImportTarget,
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub settings: ImportSettings,
+    #[serde(default)]
+    pub database: Option<String>,
+}
+
+/// Sniffs a file and suggests how its columns map to the table.
+#[tauri::command]
+pub async fn preview_import(
+    state: State<'_, AppState>,
+    connection_id: String,
+    request: PreviewImportRequest,
+) -> Result<ImportPreview, String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let service = state.connection_service()
+        .map_err(|e| e.to_string())?
+        .with_database_override(request.database);
+
+    service.preview_import(uuid, request.path, request.target, request.options)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Loads a file into a table and reports the rows that failed.
+#[tauri::command]
+pub async fn import_file(
+    state: State<'_, AppState>,
+    connection_id: String,
+    request: ImportFileRequest,
+) -> Result<ImportReport, String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let service = state.connection_service()
+        .map_err(|e| e.to_string())?
+        .with_database_override(request.database);
+
+    service.import_file(uuid, request.path, request.target, request.settings)
+        .await
+        .map_err(|e| e.to_string())
+}
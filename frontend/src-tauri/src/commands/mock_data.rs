@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
+use tauri::ipc::Channel;
 use tauri::State;
 use dbplus_backend::AppState;
+use dbplus_backend::services::bulk::{BulkProgress, BulkReport};
+use dbplus_backend::services::mock_data::generator::GenerationConfig;
+use dbplus_backend::services::mock_data::service::MockDataService;
 use uuid::Uuid;
 use dbplus_backend::services::connection_service::ConnectionService;
 
@@ -38,3 +42,32 @@ pub async fn generate_mock_data_sql(
 ) -> Result<String, String> {
     Ok("-- Mock data SQL placeholder".to_string())
 }
+
+#[derive(Debug, Deserialize)]
+pub struct InsertMockDataRequest {
+    pub connection_id: String,
+    #[serde(default)]
+    pub schema: String,
+    pub config: GenerationConfig,
+}
+
+/// Generates the rows and bulk-inserts them, sending progress through
+/// `on_progress` while they are written.
+#[tauri::command]
+pub async fn insert_mock_data(
+    state: State<'_, AppState>,
+    request: InsertMockDataRequest,
+    on_progress: Channel<BulkProgress>,
+) -> Result<BulkReport, String> {
+    let uuid = Uuid::parse_str(&request.connection_id).map_err(|e| e.to_string())?;
+    let service = MockDataService::new(state.db.clone());
+    let connections = state.connection_service()
+        .map_err(|e| e.to_string())?;
+    let progress = |event: BulkProgress| {
+        let _ = on_progress.send(event);
+    };
+
+    service.insert_data(&connections, uuid, &request.schema, request.config, Some(&progress))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -4,11 +4,14 @@
 pub mod autocomplete;
 pub mod connection;
 pub mod dashboards;
+pub mod data_diff;
 pub mod data_tools;
 pub mod database;
+pub mod export;
 pub mod export_ddl;
 pub mod extensions;
 pub mod history;
+pub mod import;
 pub mod mock_data;
 pub mod query;
 pub mod result_edit;
@@ -22,16 +25,21 @@ pub mod snippets;
 pub mod sqlite_tools;
 pub mod table_info;
 pub mod table_ops;
+pub mod transaction;
+pub mod transfer;
 
 // Re-export all commands for easy registration
 pub use autocomplete::*;
 pub use connection::*;
 pub use dashboards::*;
+pub use data_diff::*;
 pub use data_tools::*;
 pub use database::*;
+pub use export::*;
 pub use export_ddl::*;
 pub use extensions::*;
 pub use history::*;
+pub use import::*;
 pub use mock_data::*;
 pub use query::*;
 pub use result_edit::*;
@@ -45,3 +53,5 @@ pub use snippets::*;
 pub use sqlite_tools::*;
 pub use table_info::*;
 pub use table_ops::*;
+pub use transaction::*;
+pub use transfer::*;
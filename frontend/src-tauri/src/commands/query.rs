@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
+use tauri::ipc::Channel;
 use tauri::State;
 use dbplus_backend::AppState;
+use dbplus_backend::services::connection_service::QueryOptions;
+use dbplus_backend::services::query_cancel::CancelOutcome;
+use dbplus_backend::services::query_params::QueryParams;
+use dbplus_backend::services::query_stream::{QueryStreamEvent, QueryStreamSink, StreamOptions};
+use dbplus_backend::services::script_execution::{ErrorPolicy, ScriptResult};
 use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -8,6 +14,12 @@ pub struct ExecuteQueryRequest {
     pub sql: String,
     #[serde(default)]
     pub database: Option<String>,
+    /// Client-generated id that `cancel_query` can refer to.
+    #[serde(default)]
+    pub query_id: Option<String>,
+    /// Values for `$1`/`?` (an array) or `:name` (an object) placeholders.
+    #[serde(default)]
+    pub params: Option<QueryParams>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,6 +36,7 @@ pub struct QueryResult {
     pub execution_time_ms: Option<u64>,
     pub json: Option<serde_json::Value>,
     pub display_mode: Option<String>,
+    pub truncated: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -44,16 +57,29 @@ pub async fn execute_query(
     connection_id: String,
     request: ExecuteQueryRequest,
 ) -> Result<QueryResult, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
-        .map_err(|e| e.to_string())?;
+    let query_handle = request.query_id.as_deref().map(|id| state.register_query(id));
+    let cancellation_token = query_handle.as_ref().map(|h| h.token().clone()).unwrap_or_default();
+    let service = state.connection_service()
+        .map_err(|e| e.to_string())?
+        .with_query_handle(query_handle);
+
+    let result = tokio::select! {
+        res = service.execute_query_with_options(
+            uuid,
+            &request.sql,
+            QueryOptions {
+                params: request.params.as_ref(),
+                ..QueryOptions::default()
+            },
+        ) => res.map_err(|e| e.to_string()),
+        _ = cancellation_token.cancelled() => Err("Query cancelled".to_string()),
+    };
+    if let Some(id) = &request.query_id {
+        state.finish_query(id);
+    }
+    let backend_result = result?;
 
-    let backend_result = service.execute_query(uuid, &request.sql)
-        .await
-        .map_err(|e| e.to_string())?;
-    
     Ok(QueryResult {
         columns: backend_result.columns,
         rows: backend_result.rows,
@@ -67,9 +93,114 @@ pub async fn execute_query(
         execution_time_ms: backend_result.execution_time_ms,
         json: backend_result.json,
         display_mode: backend_result.display_mode,
+        truncated: backend_result.truncated,
     })
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExecuteQueryStreamRequest {
+    pub sql: String,
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default)]
+    pub query_id: Option<String>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+    #[serde(default)]
+    pub include_total_count: bool,
+    #[serde(default)]
+    pub confirmed_unsafe: bool,
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+}
+
+/// Sends the result through `on_event` as it is read (meta, rows batches,
+/// progress, then done or error) instead of returning it in one piece.
+#[tauri::command]
+pub async fn execute_query_stream(
+    state: State<'_, AppState>,
+    connection_id: String,
+    request: ExecuteQueryStreamRequest,
+    on_event: Channel<QueryStreamEvent>,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let query_handle = request.query_id.as_deref().map(|id| state.register_query(id));
+    let cancellation_token = query_handle.as_ref().map(|h| h.token().clone()).unwrap_or_default();
+    let service = state.connection_service()
+        .map_err(|e| e.to_string())?
+        .with_database_override(request.database.clone())
+        .with_query_handle(query_handle);
+
+    let (sink, mut rx) = QueryStreamSink::channel(request.batch_size);
+    let options = StreamOptions {
+        limit: request.limit,
+        offset: request.offset,
+        include_total_count: request.include_total_count,
+    };
+
+    let produce = service.stream_query(uuid, &request.sql, &options, request.confirmed_unsafe, sink);
+    let events = &on_event;
+    let forward = async move {
+        while let Some(event) = rx.recv().await {
+            if events.send(event).is_err() {
+                // Webview is gone; dropping `rx` stops the producer.
+                break;
+            }
+        }
+    };
+
+    tokio::select! {
+        _ = async { tokio::join!(produce, forward) } => {}
+        _ = cancellation_token.cancelled() => {
+            let _ = on_event.send(QueryStreamEvent::Error { message: "Query cancelled".to_string() });
+        }
+    }
+    if let Some(id) = &request.query_id {
+        state.finish_query(id);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExecuteStatementsRequest {
+    pub script: String,
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default)]
+    pub query_id: Option<String>,
+    #[serde(default)]
+    pub on_error: ErrorPolicy,
+    #[serde(default)]
+    pub confirmed_unsafe: bool,
+}
+
+/// Runs a script statement by statement and returns one result per statement.
+#[tauri::command]
+pub async fn execute_statements(
+    state: State<'_, AppState>,
+    connection_id: String,
+    request: ExecuteStatementsRequest,
+) -> Result<ScriptResult, String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let query_handle = request.query_id.as_deref().map(|id| state.register_query(id));
+    let cancellation_token = query_handle.as_ref().map(|h| h.token().clone()).unwrap_or_default();
+    let service = state.connection_service()
+        .map_err(|e| e.to_string())?
+        .with_database_override(request.database.clone())
+        .with_query_handle(query_handle);
+
+    let result = tokio::select! {
+        res = service.execute_statements(uuid, &request.script, request.on_error, request.confirmed_unsafe) => res.map_err(|e| e.to_string()),
+        _ = cancellation_token.cancelled() => Err("Query cancelled".to_string()),
+    };
+    if let Some(id) = &request.query_id {
+        state.finish_query(id);
+    }
+    result
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CancelQueryRequest {
     pub query_id: String,
@@ -79,10 +210,10 @@ pub struct CancelQueryRequest {
 pub async fn cancel_query(
     state: State<'_, AppState>,
     request: CancelQueryRequest,
-) -> Result<(), String> {
-    if let Some(token) = state.queries.get(&request.query_id) {
-        token.cancel();
-        Ok(())
+) -> Result<CancelOutcome, String> {
+    let outcome = state.cancel_query(&request.query_id).await;
+    if outcome.found {
+        Ok(outcome)
     } else {
         Err("Query not found".to_string())
     }
@@ -99,10 +230,8 @@ pub async fn explain_query(
     connection_id: String,
     request: ExplainQueryRequest,
 ) -> Result<QueryResult, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let json_result = service.explain_query(uuid, &request.sql, false) // Default analyze to false for basic explain
@@ -122,5 +251,6 @@ pub async fn explain_query(
         execution_time_ms: None,
         json: Some(json_result),
         display_mode: Some("json".to_string()),
+        truncated: None,
     })
 }
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use dbplus_backend::AppState;
+use dbplus_backend::services::bulk::{BulkReport, RowChanges};
 use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,10 +25,8 @@ pub async fn update_result_row(
     connection_id: String,
     request: UpdateRowRequest,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     service.update_row(
@@ -49,10 +48,8 @@ pub async fn delete_result_row(
     connection_id: String,
     request: DeleteRowRequest,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     service.delete_row(
@@ -76,3 +73,30 @@ fn format_value(value: &serde_json::Value) -> String {
         _ => format!("'{}'", value.to_string().replace("'", "''")),
     }
 }
+
+#[derive(Debug, Deserialize)]
+pub struct SaveChangesRequest {
+    pub schema: String,
+    pub table: String,
+    #[serde(flatten)]
+    pub changes: RowChanges,
+    #[serde(default)]
+    pub database: Option<String>,
+}
+
+/// Saves all edited and deleted rows of a result in one call.
+#[tauri::command]
+pub async fn save_result_changes(
+    state: State<'_, AppState>,
+    connection_id: String,
+    request: SaveChangesRequest,
+) -> Result<BulkReport, String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let service = state.connection_service()
+        .map_err(|e| e.to_string())?
+        .with_database_override(request.database);
+
+    service.save_row_changes(uuid, &request.schema, &request.table, &request.changes, None)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use dbplus_backend::AppState;
+use dbplus_backend::services::connection_service::QueryOptions;
+use dbplus_backend::services::table_filter::{FilterNode, SortSpec};
 use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -178,6 +180,49 @@ pub async fn delete_saved_query(
     Ok(())
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RunSavedQueryRequest {
+    #[serde(default)]
+    pub variables: serde_json::Map<String, serde_json::Value>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub include_total_count: Option<bool>,
+    pub confirmed_unsafe: Option<bool>,
+    pub database: Option<String>,
+}
+
+#[tauri::command]
+pub async fn run_saved_query(
+    state: State<'_, AppState>,
+    connection_id: String,
+    query_id: String,
+    request: RunSavedQueryRequest,
+) -> Result<serde_json::Value, String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let query_uuid = Uuid::parse_str(&query_id).map_err(|e| e.to_string())?;
+    let conn_service = state.connection_service()
+        .map_err(|e| e.to_string())?
+        .with_database_override(request.database);
+
+    let result = conn_service
+        .run_saved_query(
+            uuid,
+            query_uuid,
+            &request.variables,
+            QueryOptions {
+                limit: request.limit,
+                offset: request.offset,
+                include_total_count: request.include_total_count.unwrap_or(false),
+                confirmed_unsafe: request.confirmed_unsafe.unwrap_or(false),
+                params: None,
+            },
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(serde_json::to_value(result).map_err(|e| e.to_string())?)
+}
+
 // Saved Query Folders
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -331,15 +376,37 @@ pub struct SavedFilter {
     pub table_ref: String,
     pub name: String,
     pub filter: String,
+    pub filter_tree: Option<FilterNode>,
+    pub sort: Vec<SortSpec>,
     pub created_at: String,
 }
 
+impl From<dbplus_backend::models::entities::saved_filter::Model> for SavedFilter {
+    fn from(f: dbplus_backend::models::entities::saved_filter::Model) -> Self {
+        SavedFilter {
+            id: f.id.to_string(),
+            connection_id: f.connection_id.to_string(),
+            schema: f.schema,
+            table_ref: f.table_ref,
+            name: f.name,
+            filter: f.filter,
+            // Rows saved before filters were structured only have the raw text.
+            filter_tree: f.filter_tree.and_then(|v| serde_json::from_value(v).ok()),
+            sort: f.sort.and_then(|v| serde_json::from_value(v).ok()).unwrap_or_default(),
+            created_at: f.created_at.to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateSavedFilterRequest {
     pub schema: String,
     pub table_ref: String,
     pub name: String,
-    pub filter: String,
+    #[serde(default)]
+    pub filter: Option<FilterNode>,
+    #[serde(default)]
+    pub sort: Vec<SortSpec>,
 }
 
 #[tauri::command]
@@ -359,15 +426,7 @@ pub async fn list_saved_filters(
         .await
         .map_err(|e| e.to_string())?;
 
-    Ok(filters.into_iter().map(|f| SavedFilter {
-        id: f.id.to_string(),
-        connection_id: f.connection_id.to_string(),
-        schema: f.schema,
-        table_ref: f.table_ref,
-        name: f.name,
-        filter: f.filter,
-        created_at: f.created_at.to_string(),
-    }).collect())
+    Ok(filters.into_iter().map(SavedFilter::from).collect())
 }
 
 #[tauri::command]
@@ -376,35 +435,16 @@ pub async fn create_saved_filter(
     connection_id: String,
     request: CreateSavedFilterRequest,
 ) -> Result<SavedFilter, String> {
-    use dbplus_backend::models::entities::saved_filter;
-    use sea_orm::{ActiveModelTrait, Set};
-    use chrono::Utc;
+    use dbplus_backend::services::saved_filter_service::SavedFilterService;
     
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
     
-    let new_filter = saved_filter::ActiveModel {
-        id: sea_orm::ActiveValue::NotSet,
-        connection_id: Set(uuid),
-        schema: Set(request.schema),
-        table_ref: Set(request.table_ref),
-        name: Set(request.name),
-        filter: Set(request.filter),
-        created_at: Set(Utc::now().into()),
-    };
-
-    let result = new_filter.insert(&state.db)
+    let result = SavedFilterService::new(state.db.clone())
+        .create_saved_filter(uuid, request.schema, request.table_ref, request.name, request.filter, request.sort)
         .await
         .map_err(|e| e.to_string())?;
 
-    Ok(SavedFilter {
-        id: result.id.to_string(),
-        connection_id: result.connection_id.to_string(),
-        schema: result.schema,
-        table_ref: result.table_ref,
-        name: result.name,
-        filter: result.filter,
-        created_at: result.created_at.to_string(),
-    })
+    Ok(SavedFilter::from(result))
 }
 
 #[tauri::command]
@@ -25,10 +25,8 @@ pub async fn schema_list_schemas(
     state: State<'_, AppState>,
     connection_id: String,
 ) -> Result<Vec<String>, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     conn_service.get_schemas(uuid)
@@ -42,11 +40,9 @@ pub async fn schema_list_tables(
     connection_id: String,
     schema: String,
 ) -> Result<Vec<TableRef>, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let tables = conn_service.get_tables(uuid, &schema)
@@ -73,11 +69,9 @@ pub async fn schema_get_columns(
     schema: String,
     table: String,
 ) -> Result<Vec<ColumnRef>, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let columns = conn_service.get_columns(uuid, &schema, &table)
@@ -106,11 +100,9 @@ pub async fn schema_list_functions(
     connection_id: String,
     schema: String,
 ) -> Result<serde_json::Value, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.list_functions(uuid, &schema)
@@ -126,11 +118,9 @@ pub async fn schema_list_views(
     connection_id: String,
     schema: String,
 ) -> Result<serde_json::Value, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.list_views(uuid, &schema)
@@ -147,11 +137,9 @@ pub async fn schema_get_view_definition(
     schema: String,
     view: String,
 ) -> Result<String, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_view_definition(uuid, &schema, &view)
@@ -168,11 +156,9 @@ pub async fn schema_get_function_definition(
     schema: String,
     function: String,
 ) -> Result<String, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_function_definition(uuid, &schema, &function)
@@ -188,11 +174,9 @@ pub async fn schema_get_schema_foreign_keys(
     connection_id: String,
     schema: String,
 ) -> Result<serde_json::Value, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_schema_foreign_keys(uuid, &schema)
@@ -208,10 +192,8 @@ pub async fn schema_list_schema_metadata(
     connection_id: String,
     schema: String,
 ) -> Result<serde_json::Value, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let metadata = conn_service.get_schema_metadata(uuid, &schema)
@@ -1,8 +1,8 @@
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use dbplus_backend::AppState;
+use dbplus_backend::services::schema_diff::{MigrationOptions, RenameDecisions};
 use uuid::Uuid;
-use dbplus_backend::services::connection_service::ConnectionService;
 
 #[derive(Debug, Deserialize)]
 pub struct SchemaDiffRequest {
@@ -10,6 +10,12 @@ pub struct SchemaDiffRequest {
     pub target_connection_id: String,
     pub source_schema: String,
     pub target_schema: String,
+    /// Suspected renames accepted or rejected by the user
+    #[serde(default)]
+    pub renames: RenameDecisions,
+    /// Migration options; drops in safe mode when unset
+    #[serde(default)]
+    pub options: Option<MigrationOptions>,
 }
 
 #[tauri::command]
@@ -17,19 +23,18 @@ pub async fn compare_schemas(
     state: State<'_, AppState>,
     request: SchemaDiffRequest,
 ) -> Result<serde_json::Value, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let source_uuid = Uuid::parse_str(&request.source_connection_id).map_err(|e| e.to_string())?;
     let target_uuid = Uuid::parse_str(&request.target_connection_id).map_err(|e| e.to_string())?;
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let diff = service.compare_schemas(
         source_uuid,
         target_uuid,
         request.source_schema,
-        request.target_schema
+        request.target_schema,
+        &request.renames
     ).await.map_err(|e| e.to_string())?;
 
     Ok(serde_json::to_value(diff).map_err(|e| e.to_string())?)
@@ -40,18 +45,18 @@ pub async fn generate_migration(
     state: State<'_, AppState>,
     request: SchemaDiffRequest,
 ) -> Result<String, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let source_uuid = Uuid::parse_str(&request.source_connection_id).map_err(|e| e.to_string())?;
     let target_uuid = Uuid::parse_str(&request.target_connection_id).map_err(|e| e.to_string())?;
 
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     service.generate_migration(
         source_uuid,
         target_uuid,
         request.source_schema,
-        request.target_schema
+        request.target_schema,
+        &request.renames,
+        request.options
     ).await.map_err(|e| e.to_string())
 }
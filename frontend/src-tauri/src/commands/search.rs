@@ -2,7 +2,6 @@ use serde::{Deserialize, Serialize};
 use tauri::State;
 use dbplus_backend::AppState;
 use uuid::Uuid;
-use dbplus_backend::services::connection_service::ConnectionService;
 
 #[derive(Debug, Deserialize)]
 pub struct SearchRequest {
@@ -17,7 +16,7 @@ pub async fn search_objects(
     request: SearchRequest,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.search_objects(uuid, &request.query)
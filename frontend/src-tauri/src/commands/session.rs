@@ -1,7 +1,6 @@
 use tauri::State;
 use dbplus_backend::AppState;
 use uuid::Uuid;
-use dbplus_backend::services::connection_service::ConnectionService;
 
 #[tauri::command]
 pub async fn list_sessions(
@@ -9,7 +8,7 @@ pub async fn list_sessions(
     id: String,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let sessions = conn_service
@@ -27,7 +26,7 @@ pub async fn kill_session(
     pid: i32,
 ) -> Result<(), String> {
     let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     conn_service
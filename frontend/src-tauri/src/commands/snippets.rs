@@ -143,3 +143,32 @@ pub async fn delete_snippet(
 
     Ok(())
 }
+
+#[derive(Debug, Deserialize)]
+pub struct RenderSnippetRequest {
+    pub db_type: String,
+    #[serde(default)]
+    pub variables: serde_json::Map<String, serde_json::Value>,
+}
+
+#[tauri::command]
+pub async fn render_snippet(
+    state: State<'_, AppState>,
+    id: String,
+    request: RenderSnippetRequest,
+) -> Result<serde_json::Value, String> {
+    use dbplus_backend::services::driver_factory;
+    use dbplus_backend::services::snippet_service::SnippetService;
+
+    let uuid = Uuid::parse_str(&id).map_err(|e| e.to_string())?;
+    let spec = driver_factory::spec_for(&request.db_type).map_err(|e| e.to_string())?;
+    let dialect = spec.sql_dialect
+        .ok_or_else(|| format!("Snippets are not supported for {}", spec.name))?;
+
+    let sql = SnippetService::new(state.db.clone())
+        .render_snippet(uuid, &request.variables, dialect)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(serde_json::json!({ "sql": sql }))
+}
@@ -2,7 +2,6 @@ use serde::{Deserialize, Serialize};
 use tauri::State;
 use dbplus_backend::AppState;
 use uuid::Uuid;
-use dbplus_backend::services::connection_service::ConnectionService;
 
 #[derive(Debug, Deserialize)]
 pub struct SqliteAttachmentRequest {
@@ -16,10 +15,8 @@ pub async fn list_sqlite_attachments(
     state: State<'_, AppState>,
     connection_id: String,
 ) -> Result<serde_json::Value, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let attachments = service.list_sqlite_attachments(uuid)
@@ -35,10 +32,8 @@ pub async fn attach_sqlite_database(
     connection_id: String,
     request: SqliteAttachmentRequest,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     service.attach_sqlite_database(uuid, request.name, request.file_path, request.read_only)
@@ -52,10 +47,8 @@ pub async fn detach_sqlite_database(
     connection_id: String,
     name: String,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let service = ConnectionService::new(state.db.clone())
+    let service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     service.detach_sqlite_database(uuid, name)
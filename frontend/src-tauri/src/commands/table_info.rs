@@ -2,7 +2,6 @@ use serde::{Deserialize, Serialize};
 use tauri::State;
 use dbplus_backend::AppState;
 use uuid::Uuid;
-use dbplus_backend::services::connection_service::ConnectionService;
 
 #[derive(Debug, Deserialize)]
 pub struct TableParams {
@@ -17,7 +16,7 @@ pub async fn get_table_constraints(
     params: TableParams,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_table_constraints(uuid, &params.schema, &params.table)
@@ -34,7 +33,7 @@ pub async fn get_table_statistics(
     params: TableParams,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_table_statistics(uuid, &params.schema, &params.table)
@@ -51,7 +50,7 @@ pub async fn get_table_indexes(
     params: TableParams,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_table_indexes(uuid, &params.schema, &params.table)
@@ -68,7 +67,7 @@ pub async fn get_table_triggers(
     params: TableParams,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_table_triggers(uuid, &params.schema, &params.table)
@@ -85,7 +84,7 @@ pub async fn get_table_comment(
     params: TableParams,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_table_comment(uuid, &params.schema, &params.table)
@@ -104,7 +103,7 @@ pub async fn set_table_comment(
     comment: Option<String>,
 ) -> Result<(), String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     conn_service.set_table_comment(uuid, &schema, &table, comment)
@@ -121,7 +120,7 @@ pub async fn get_table_permissions(
     params: TableParams,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_table_permissions(uuid, &params.schema, &params.table)
@@ -138,7 +137,7 @@ pub async fn get_table_dependencies(
     params: TableParams,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_table_dependencies(uuid, &params.schema, &params.table)
@@ -155,7 +154,7 @@ pub async fn get_storage_bloat_info(
     params: TableParams,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_storage_bloat_info(uuid, &params.schema, &params.table)
@@ -172,7 +171,7 @@ pub async fn get_partitions(
     params: TableParams,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_partitions(uuid, &params.schema, &params.table)
@@ -189,7 +188,7 @@ pub async fn get_fk_orphans(
     params: TableParams,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.detect_fk_orphans(uuid, &params.schema, &params.table)
@@ -205,7 +204,7 @@ pub async fn list_roles(
     connection_id: String,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.list_roles(uuid)
@@ -227,7 +226,7 @@ pub async fn get_schema_permissions(
     params: SchemaPermissionParams,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_schema_permissions(uuid, &params.schema)
@@ -250,7 +249,7 @@ pub async fn get_function_permissions(
     params: FunctionPermissionParams,
 ) -> Result<serde_json::Value, String> {
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let result = conn_service.get_function_permissions(uuid, &params.schema, &params.function)
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use dbplus_backend::AppState;
+use dbplus_backend::services::table_filter::{FilterNode, SortSpec};
 use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -57,10 +58,8 @@ pub async fn create_table(
     connection_id: String,
     request: CreateTableRequest,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     // Convert to backend column definition
@@ -84,10 +83,8 @@ pub async fn drop_table(
     connection_id: String,
     request: DropTableRequest,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     conn_service.drop_table(uuid, &request.schema, &request.table_name)
@@ -101,10 +98,8 @@ pub async fn add_column(
     connection_id: String,
     request: AddColumnRequest,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let column_def = dbplus_backend::services::db_driver::ColumnDefinition {
@@ -125,10 +120,8 @@ pub async fn drop_column(
     connection_id: String,
     request: DropColumnRequest,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     conn_service.drop_column(uuid, &request.schema, &request.table, &request.column_name)
@@ -142,10 +135,8 @@ pub async fn create_schema(
     connection_id: String,
     schema_name: String,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     conn_service.create_schema(uuid, &schema_name)
@@ -161,10 +152,8 @@ pub async fn drop_schema(
     connection_id: String,
     schema_name: String,
 ) -> Result<(), String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     conn_service.drop_schema(uuid, &schema_name)
@@ -180,6 +169,10 @@ pub struct GetTableDataRequest {
     pub table: String,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    #[serde(default)]
+    pub filter: Option<FilterNode>,
+    #[serde(default)]
+    pub sort: Vec<SortSpec>,
 }
 
 #[tauri::command]
@@ -188,16 +181,14 @@ pub async fn get_table_data(
     connection_id: String,
     request: GetTableDataRequest,
 ) -> Result<serde_json::Value, String> {
-    use dbplus_backend::services::connection_service::ConnectionService;
-    
     let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
-    let conn_service = ConnectionService::new(state.db.clone())
+    let conn_service = state.connection_service()
         .map_err(|e| e.to_string())?;
 
     let limit = request.limit.unwrap_or(100);
     let offset = request.offset.unwrap_or(0);
 
-    let result = conn_service.get_table_data(uuid, &request.schema, &request.table, limit, offset, None, None, None)
+    let result = conn_service.get_table_data(uuid, &request.schema, &request.table, limit, offset, request.filter, request.sort, None, None)
         .await
         .map_err(|e| e.to_string())?;
 
@@ -0,0 +1,110 @@
+use serde::Deserialize;
+use tauri::State;
+use dbplus_backend::AppState;
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct BeginTransactionRequest {
+    #[serde(default)]
+    pub database: Option<String>,
+    #[serde(default)]
+    pub idle_timeout_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExecuteInTransactionRequest {
+    pub query: String,
+    #[serde(default)]
+    pub confirmed_unsafe: bool,
+}
+
+#[tauri::command]
+pub async fn begin_transaction(
+    state: State<'_, AppState>,
+    connection_id: String,
+    request: Option<BeginTransactionRequest>,
+) -> Result<serde_json::Value, String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let (database, idle_timeout_secs) = match request {
+        Some(r) => (r.database, r.idle_timeout_secs),
+        None => (None, None),
+    };
+    let conn_service = state.connection_service()
+        .map_err(|e| e.to_string())?
+        .with_database_override(database);
+
+    let info = conn_service
+        .begin_transaction(uuid, idle_timeout_secs.map(Duration::from_secs))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(serde_json::to_value(info).map_err(|e| e.to_string())?)
+}
+
+#[tauri::command]
+pub async fn list_transactions(
+    state: State<'_, AppState>,
+    connection_id: String,
+) -> Result<serde_json::Value, String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let conn_service = state.connection_service()
+        .map_err(|e| e.to_string())?;
+
+    let transactions = conn_service
+        .list_transactions(uuid)
+        .map_err(|e| e.to_string())?;
+
+    Ok(serde_json::to_value(transactions).map_err(|e| e.to_string())?)
+}
+
+#[tauri::command]
+pub async fn execute_in_transaction(
+    state: State<'_, AppState>,
+    connection_id: String,
+    transaction_id: String,
+    request: ExecuteInTransactionRequest,
+) -> Result<serde_json::Value, String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let conn_service = state.connection_service()
+        .map_err(|e| e.to_string())?;
+
+    let result = conn_service
+        .execute_in_transaction(uuid, &transaction_id, &request.query, request.confirmed_unsafe)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(serde_json::to_value(result).map_err(|e| e.to_string())?)
+}
+
+#[tauri::command]
+pub async fn commit_transaction(
+    state: State<'_, AppState>,
+    connection_id: String,
+    transaction_id: String,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let conn_service = state.connection_service()
+        .map_err(|e| e.to_string())?;
+
+    conn_service
+        .commit_transaction(uuid, &transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rollback_transaction(
+    state: State<'_, AppState>,
+    connection_id: String,
+    transaction_id: String,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&connection_id).map_err(|e| e.to_string())?;
+    let conn_service = state.connection_service()
+        .map_err(|e| e.to_string())?;
+
+    conn_service
+        .rollback_transaction(uuid, &transaction_id)
+        .await
+        .map_err(|e| e.to_string())
+}
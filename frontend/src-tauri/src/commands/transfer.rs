@@ -0,0 +1,32 @@
+use tauri::ipc::Channel;
+use tauri::State;
+use dbplus_backend::AppState;
+use dbplus_backend::services::transfer::{TransferProgress, TransferReport, TransferRequest};
+
+/// Copies tables from one connection to another, reporting each table's
+/// progress on `on_progress`. Cancelling `query_id` stops after the batch
+/// being written.
+#[tauri::command]
+pub async fn transfer_tables(
+    state: State<'_, AppState>,
+    request: TransferRequest,
+    on_progress: Channel<TransferProgress>,
+    query_id: Option<String>,
+) -> Result<TransferReport, String> {
+    let cancellation_token = query_id.as_deref()
+        .map(|id| state.register_query(id).token().clone())
+        .unwrap_or_default();
+    let service = state.connection_service().map_err(|e| e.to_string())?;
+    let progress = |event: TransferProgress| {
+        let _ = on_progress.send(event);
+    };
+
+    let result = tokio::select! {
+        res = service.transfer_tables(&request, Some(&progress)) => res.map_err(|e| e.to_string()),
+        _ = cancellation_token.cancelled() => Err("Transfer cancelled".to_string()),
+    };
+    if let Some(id) = &query_id {
+        state.finish_query(id);
+    }
+    result
+}
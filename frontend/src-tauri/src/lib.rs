@@ -47,6 +47,8 @@ pub fn run() {
             commands::test_connection,
             commands::test_connection_by_id,
             commands::switch_database,
+            commands::get_tls_support,
+            commands::get_pool_stats,
             // Schema commands
             commands::schema_list_schemas,
             commands::schema_list_tables,
@@ -56,8 +58,22 @@ pub fn run() {
             commands::schema_refresh,
             // Query commands
             commands::execute_query,
+            commands::execute_query_stream,
+            commands::execute_statements,
             commands::cancel_query,
             commands::explain_query,
+            // Export commands
+            commands::export_query,
+            commands::export_table,
+            // Import commands
+            commands::preview_import,
+            commands::import_file,
+            // Transaction commands
+            commands::begin_transaction,
+            commands::list_transactions,
+            commands::execute_in_transaction,
+            commands::commit_transaction,
+            commands::rollback_transaction,
             // Database commands
             commands::list_databases,
             commands::create_database,
@@ -79,11 +95,13 @@ pub fn run() {
             commands::create_snippet,
             commands::update_snippet,
             commands::delete_snippet,
+            commands::render_snippet,
             // Saved query commands
             commands::list_saved_queries,
             commands::create_saved_query,
             commands::update_saved_query,
             commands::delete_saved_query,
+            commands::run_saved_query,
             commands::list_folders,
             commands::create_folder,
             commands::update_folder,
@@ -110,6 +128,7 @@ pub fn run() {
             // Result editing
             commands::update_result_row,
             commands::delete_result_row,
+            commands::save_result_changes,
             // Session commands
             commands::list_sessions,
             commands::kill_session,
@@ -131,6 +150,7 @@ pub fn run() {
             // Data tools
             commands::execute_script,
             commands::backup_postgres_sql,
+            commands::dump_database,
             // Export DDL
             commands::export_postgres_ddl,
             // Schema diff
@@ -149,6 +169,11 @@ pub fn run() {
             // Mock data
             commands::preview_mock_data,
             commands::generate_mock_data_sql,
+            commands::insert_mock_data,
+            // Transfer
+            commands::transfer_tables,
+            // Data diff
+            commands::compare_table_data,
             // Extra Schema commands
             commands::schema_list_functions,
             commands::schema_list_views,
@@ -10,6 +10,7 @@ import TableDataTab from './table-data/TableDataTab';
 import { TableColumn, QueryResult, EditState, TableDataViewProps } from '../types';
 import { useConstraints } from '../hooks/useDatabase';
 import { useTabStateStore } from '../store/tabStateStore';
+import { AppliedFilter, combineFilters } from '../utils/tableFilter';
 import { useConnectionStore } from '../store/connectionStore';
 import { useDialog } from '../context/DialogContext';
 import { extractApiErrorDetails } from '../utils/apiError';
@@ -113,6 +114,7 @@ export default function TableDataView({ schema: schemaProp, table: tableProp, ta
   }, [connectionId, schema, table]);
 
   const [filter, setFilter] = useState('');
+  const [appliedFilter, setAppliedFilter] = useState<AppliedFilter | null>(null);
   const [documentId, setDocumentId] = useState('');
   const [bucket, setBucket] = useState('');
   const [fields, setFields] = useState<string[]>([]);
@@ -133,7 +135,8 @@ export default function TableDataView({ schema: schemaProp, table: tableProp, ta
       const d = customDocId !== undefined ? customDocId : documentId;
 
       let url = `/api/connections/${connectionId}/query?schema=${schema}&table=${table}&limit=${pageSize}&offset=${offset}`;
-      if (f) url += `&filter=${encodeURIComponent(f)}`;
+      const tree = combineFilters(f, appliedFilter);
+      if (tree) url += `&filter=${encodeURIComponent(JSON.stringify(tree))}`;
       if (d) url += `&document_id=${encodeURIComponent(d)}`;
       if (bucket) url += `&database=${encodeURIComponent(bucket)}`;
       if (fields.length > 0) url += `&fields=${encodeURIComponent(JSON.stringify(fields))}`;
@@ -160,7 +163,7 @@ export default function TableDataView({ schema: schemaProp, table: tableProp, ta
         }
       }
     }
-  }, [connectionId, schema, table, page, pageSize, filter, documentId, bucket, fields]);
+  }, [connectionId, schema, table, page, pageSize, filter, appliedFilter, documentId, bucket, fields]);
 
   useEffect(() => {
     const cacheKey = `${connectionId}-${schema}-${table}`;
@@ -439,6 +442,8 @@ export default function TableDataView({ schema: schemaProp, table: tableProp, ta
             isCouchbase={isCouchbase}
             filter={filter}
             setFilter={setFilter}
+            appliedFilter={appliedFilter}
+            setAppliedFilter={setAppliedFilter}
             documentId={documentId}
             setDocumentId={setDocumentId}
             bucket={bucket}
@@ -39,6 +39,9 @@ const DEFAULT_FORM_DATA = {
     ssl: false,
     environment: 'development',
     safe_mode_level: '1',
+    statement_timeout_ms: '',
+    max_result_rows: '',
+    max_result_bytes: '',
     id: undefined as string | undefined,
 };
 
@@ -119,6 +122,9 @@ export const ConnectionFormModal: React.FC<ConnectionFormModalProps> = ({ isOpen
                     user: initialValues.username || '',
                     environment: initialValues.environment || 'development',
                     safe_mode_level: String(initialValues.safe_mode_level ?? 1),
+                    statement_timeout_ms: String(initialValues.statement_timeout_ms ?? ''),
+                    max_result_rows: String(initialValues.max_result_rows ?? ''),
+                    max_result_bytes: String(initialValues.max_result_bytes ?? ''),
                     id: (initialValues as any).id,
                 }));
             } else {
@@ -145,6 +151,10 @@ export const ConnectionFormModal: React.FC<ConnectionFormModalProps> = ({ isOpen
         port: parseInt(formData.port) || 0,
         username: formData.user,
         safe_mode_level: parseInt(formData.safe_mode_level) || 1,
+        // Empty or zero means no limit
+        statement_timeout_ms: parseInt(formData.statement_timeout_ms) || null,
+        max_result_rows: parseInt(formData.max_result_rows) || null,
+        max_result_bytes: parseInt(formData.max_result_bytes) || null,
     });
 
     const handleChange = (field: keyof typeof formData, value: any) => {
@@ -460,6 +470,39 @@ export const ConnectionFormModal: React.FC<ConnectionFormModalProps> = ({ isOpen
                             />
                         </div>
                     </div>
+
+                    <div className="grid grid-cols-3 gap-4">
+                        <div className="space-y-2">
+                            <label className="text-[11px] font-bold text-text-secondary ml-1">Statement Timeout (ms)</label>
+                            <Input
+                                type="number"
+                                min={0}
+                                value={formData.statement_timeout_ms}
+                                onChange={(e) => handleChange('statement_timeout_ms', e.target.value)}
+                                placeholder="No limit"
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <label className="text-[11px] font-bold text-text-secondary ml-1">Max Rows</label>
+                            <Input
+                                type="number"
+                                min={0}
+                                value={formData.max_result_rows}
+                                onChange={(e) => handleChange('max_result_rows', e.target.value)}
+                                placeholder="No limit"
+                            />
+                        </div>
+                        <div className="space-y-2">
+                            <label className="text-[11px] font-bold text-text-secondary ml-1">Max Result Bytes</label>
+                            <Input
+                                type="number"
+                                min={0}
+                                value={formData.max_result_bytes}
+                                onChange={(e) => handleChange('max_result_bytes', e.target.value)}
+                                placeholder="No limit"
+                            />
+                        </div>
+                    </div>
                 </div>
             </form>
         </Modal>
@@ -1,8 +1,9 @@
 import React, { useState, useEffect } from 'react';
 import * as Dialog from '@radix-ui/react-dialog';
-import { X, Sparkles, FileCode, Play, Loader2, Settings2 } from 'lucide-react';
+import { X, Sparkles, FileCode, Play, Loader2, Settings2, DatabaseZap } from 'lucide-react';
 import { TableColumn } from '../../types';
 import api from '../../services/api';
+import { bulkApi, BulkProgress } from '../../services/bulkApi';
 import { useColumns } from '../../hooks/useDatabase';
 import Input from '../ui/Input';
 import Checkbox from '../ui/Checkbox';
@@ -37,6 +38,8 @@ export const MockDataModal: React.FC<MockDataModalProps> = ({
     const [previewData, setPreviewData] = useState<any[]>([]);
     const [loading, setLoading] = useState(false);
     const [error, setError] = useState<string | null>(null);
+    const [insertProgress, setInsertProgress] = useState<BulkProgress | null>(null);
+    const [insertSummary, setInsertSummary] = useState<string | null>(null);
 
     const { data: fetchedColumns } = useColumns(connectionId, schema, table);
     const columns = initialColumns || fetchedColumns || [];
@@ -124,6 +127,28 @@ export const MockDataModal: React.FC<MockDataModalProps> = ({
         }
     };
 
+    const handleInsert = async () => {
+        setLoading(true);
+        setError(null);
+        setInsertSummary(null);
+        setInsertProgress(null);
+        try {
+            const report = await bulkApi.insertMockData(
+                { connection_id: connectionId, schema, config: buildConfig(rowCount) },
+                setInsertProgress
+            );
+            setInsertSummary(
+                `Inserted ${report.rows_affected.toLocaleString()} rows in ${report.execution_time_ms} ms ` +
+                `(${Math.round(report.rows_per_second).toLocaleString()} rows/s)`
+            );
+        } catch (err: any) {
+            setError(err?.message || String(err) || 'Failed to insert rows');
+        } finally {
+            setInsertProgress(null);
+            setLoading(false);
+        }
+    };
+
     const buildConfig = (count: number) => {
         return {
             table_name: table,
@@ -358,7 +383,16 @@ export const MockDataModal: React.FC<MockDataModalProps> = ({
                             </div>
 
                             {/* Footer Actions */}
-                            <div className="p-4 border-t border-border flex justify-end gap-3 bg-background-secondary/10">
+                            <div className="p-4 border-t border-border flex justify-end items-center gap-3 bg-background-secondary/10">
+                                {insertProgress && (
+                                    <span className="text-xs text-text-secondary mr-auto">
+                                        {insertProgress.rows_written.toLocaleString()} / {insertProgress.total_rows.toLocaleString()} rows
+                                        {' '}({Math.round(insertProgress.rows_per_second).toLocaleString()} rows/s)
+                                    </span>
+                                )}
+                                {!insertProgress && insertSummary && (
+                                    <span className="text-xs text-text-secondary mr-auto">{insertSummary}</span>
+                                )}
                                 <button
                                     onClick={handleDownloadSql}
                                     disabled={loading}
@@ -367,6 +401,14 @@ export const MockDataModal: React.FC<MockDataModalProps> = ({
                                     {loading ? <Loader2 className="w-4 h-4 animate-spin" /> : <FileCode className="w-4 h-4" />}
                                     Download Insert SQL
                                 </button>
+                                <button
+                                    onClick={handleInsert}
+                                    disabled={loading}
+                                    className="px-4 py-2 bg-primary text-white hover:bg-primary-hover rounded-md text-sm font-medium flex items-center gap-2 transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+                                >
+                                    {loading ? <Loader2 className="w-4 h-4 animate-spin" /> : <DatabaseZap className="w-4 h-4" />}
+                                    Insert Rows
+                                </button>
                             </div>
                         </div>
                     </div>
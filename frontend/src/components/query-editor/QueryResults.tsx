@@ -16,7 +16,7 @@ import {
 } from '@tanstack/react-table';
 import { useVirtualizer } from '@tanstack/react-virtual';
 import { EditableCell } from './EditableCell';
-import { useUpdateQueryResult, useDeleteQueryResult } from '../../hooks/useQuery';
+import { useSaveQueryResultChanges, useDeleteQueryResult } from '../../hooks/useQuery';
 import { useToast } from '../../context/ToastContext';
 import { useDialog } from '../../context/DialogContext';
 import { ArrowRight, ChevronLeft, ChevronRight, Check, Minus, Copy, Trash2, BarChart3, Maximize2, Search } from 'lucide-react';
@@ -154,11 +154,11 @@ export const QueryResults: React.FC<QueryResultsProps> = ({
     }, [result]);
 
     // Custom Hooks
-    const updateQueryResult = useUpdateQueryResult(connectionId);
+    const saveQueryResultChanges = useSaveQueryResultChanges(connectionId);
     const deleteQueryResult = useDeleteQueryResult(connectionId);
 
     // Check if saving is in progress
-    const saving = updateQueryResult.isPending || deleteQueryResult.isPending;
+    const saving = saveQueryResultChanges.isPending || deleteQueryResult.isPending;
 
     const hasEditableColumns = useMemo(() => {
         return result?.column_metadata?.some(c => c.is_editable) ?? false;
@@ -227,16 +227,15 @@ export const QueryResults: React.FC<QueryResultsProps> = ({
                     }
                 });
 
-                return {
-                    schema: tableMeta.schema_name || 'public',
-                    table: tableMeta.table_name!,
-                    primary_key: primaryKey,
-                    updates: rowUpdates
-                };
+                return { primary_key: primaryKey, updates: rowUpdates };
             });
 
-            // Better: Promise.all
-            await Promise.all(updates.map(u => updateQueryResult.mutateAsync(u)));
+            // One request for all edited rows
+            await saveQueryResultChanges.mutateAsync({
+                schema: tableMeta.schema_name || 'public',
+                table: tableMeta.table_name!,
+                updates,
+            });
 
             setEdits({});
             if (onRefresh) onRefresh();
@@ -98,6 +98,18 @@ function DiffItem({ diff }: { diff: any }) {
         );
     }
 
+    if (type === 'TableRenamed') {
+        return (
+            <div className="bg-bg-1 border border-border rounded-lg p-3">
+                <div className="flex items-center gap-2 text-sm font-medium text-text-primary">
+                    <ChevronRight size={16} />
+                    <span>Table Renamed: {diff.old_name} → {diff.new_name}</span>
+                    {diff.suspected && <span className="text-xs text-yellow-500">(Suspected)</span>}
+                </div>
+            </div>
+        );
+    }
+
     if (type === 'TableModified') {
         return (
             <div className="bg-bg-1 border border-border rounded-lg p-3">
@@ -114,9 +126,51 @@ function DiffItem({ diff }: { diff: any }) {
         );
     }
 
+    const object = objectDiff(diff);
+    if (object) {
+        const colors = {
+            Added: 'bg-green-500/5 border-green-500/20 text-green-500',
+            Dropped: 'bg-red-500/5 border-red-500/20 text-red-500',
+            Modified: 'bg-yellow-500/5 border-yellow-500/20 text-yellow-500',
+            Recreated: 'bg-bg-1 border-border text-text-secondary',
+        };
+        return (
+            <div className={`border rounded-lg p-3 ${colors[object.action]}`}>
+                <div className="flex items-center gap-2 text-sm font-medium">
+                    {object.action === 'Added' && <Plus size={16} />}
+                    {object.action === 'Dropped' && <Minus size={16} />}
+                    {object.action !== 'Added' && object.action !== 'Dropped' && <ChevronRight size={16} />}
+                    <span>{object.kind} {object.action}: {object.name}</span>
+                </div>
+            </div>
+        );
+    }
+
     return null;
 }
 
+const OBJECT_KINDS: Record<string, string> = {
+    View: 'View',
+    Routine: 'Routine',
+    Trigger: 'Trigger',
+    Sequence: 'Sequence',
+    EnumType: 'Enum Type',
+    Domain: 'Domain',
+};
+
+function objectDiff(diff: any): { kind: string; action: 'Added' | 'Dropped' | 'Modified' | 'Recreated'; name: string } | null {
+    const match = /^(View|Routine|Trigger|Sequence|EnumType|Domain)(Added|Dropped|Modified|Recreated)$/.exec(diff.type);
+    if (!match) {
+        return null;
+    }
+    const object = diff.new ?? diff.view ?? diff.routine ?? diff.trigger ?? diff.sequence ?? diff.enum_type ?? diff.domain;
+    return {
+        kind: OBJECT_KINDS[match[1]],
+        action: match[2] as 'Added' | 'Dropped' | 'Modified' | 'Recreated',
+        name: object?.name ?? '',
+    };
+}
+
 function ChangeItem({ change }: { change: any }) {
     const { type } = change;
 
@@ -142,6 +196,16 @@ function ChangeItem({ change }: { change: any }) {
         );
     }
 
+    if (type === 'ColumnRenamed') {
+        return (
+            <div className="flex items-center gap-2 text-xs text-text-secondary">
+                <ChevronRight size={12} />
+                <span>{change.old_name} → {change.new_name}</span>
+                {change.suspected && <span className="text-yellow-500">(Suspected)</span>}
+            </div>
+        );
+    }
+
     if (type === 'ColumnModified') {
         return (
             <div className="text-xs text-yellow-500">
@@ -158,6 +222,9 @@ function ChangeItem({ change }: { change: any }) {
                             {c.type === 'NullabilityChanged' && (
                                 <span>Nullable: {c.old ? 'YES' : 'NO'} → {c.new ? 'YES' : 'NO'}</span>
                             )}
+                            {c.type === 'CommentChanged' && (
+                                <span>Comment: {c.old ?? '(none)'} → {c.new ?? '(none)'}</span>
+                            )}
                         </div>
                     ))}
                 </div>
@@ -165,5 +232,23 @@ function ChangeItem({ change }: { change: any }) {
         );
     }
 
+    if (type === 'CheckConstraintAdded' || type === 'UniqueConstraintAdded') {
+        return (
+            <div className="flex items-center gap-2 text-xs text-green-500">
+                <Plus size={12} />
+                <span>Constraint {change.constraint.name}</span>
+            </div>
+        );
+    }
+
+    if (type === 'CheckConstraintDropped' || type === 'UniqueConstraintDropped') {
+        return (
+            <div className="flex items-center gap-2 text-xs text-red-500">
+                <Minus size={12} />
+                <span>Constraint {change.constraint_name}</span>
+            </div>
+        );
+    }
+
     return null;
 }
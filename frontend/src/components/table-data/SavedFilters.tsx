@@ -3,11 +3,15 @@ import { Filter, Trash2, Plus, ChevronDown } from 'lucide-react';
 import api from '../../services/api';
 import { useToast } from '../../context/ToastContext';
 import Button from '../ui/Button';
+import { AppliedFilter, combineFilters } from '../../utils/tableFilter';
 
 interface SavedFilter {
     id: string;
     name: string;
+    // Readable form of `filter_tree`, or the raw text of filters saved before it existed.
     filter: string;
+    filter_tree: unknown | null;
+    sort: unknown[] | null;
 }
 
 interface SavedFiltersProps {
@@ -15,7 +19,8 @@ interface SavedFiltersProps {
     schema: string;
     table: string;
     currentFilter: string;
-    onApplyFilter: (filter: string) => void;
+    appliedFilter: AppliedFilter | null;
+    onApplyFilter: (text: string, applied: AppliedFilter | null) => void;
 }
 
 export default function SavedFilters({
@@ -23,6 +28,7 @@ export default function SavedFilters({
     schema,
     table,
     currentFilter,
+    appliedFilter,
     onApplyFilter,
 }: SavedFiltersProps) {
     const [isOpen, setIsOpen] = useState(false);
@@ -61,13 +67,14 @@ export default function SavedFilters({
     }, [isOpen, connectionId, schema, table]);
 
     const handleSave = async () => {
-        if (!newFilterName.trim()) return;
+        const filterTree = combineFilters(currentFilter, appliedFilter);
+        if (!newFilterName.trim() || !filterTree) return;
         try {
             await api.post(`/api/connections/${connectionId}/saved-filters`, {
                 schema,
                 table,
                 name: newFilterName,
-                filter: currentFilter
+                filter: filterTree
             });
             showToast('Filter saved', 'success');
             setNewFilterName('');
@@ -78,6 +85,18 @@ export default function SavedFilters({
         }
     };
 
+    // Text filters go back into the WHERE box; typed trees are applied as is.
+    const handleApply = (f: SavedFilter) => {
+        const tree = f.filter_tree as { type?: string; sql?: string } | null;
+        if (!tree) {
+            onApplyFilter(f.filter, null);
+        } else if (tree.type === 'raw') {
+            onApplyFilter(tree.sql ?? '', null);
+        } else {
+            onApplyFilter('', { label: f.filter, tree });
+        }
+    };
+
     const handleDelete = async (id: string, e: React.MouseEvent) => {
         e.stopPropagation();
         try {
@@ -108,7 +127,7 @@ export default function SavedFilters({
                         <span className="text-xs font-bold text-text-secondary">Saved Filters</span>
                         <button
                             onClick={() => setIsSaving(true)}
-                            disabled={!currentFilter}
+                            disabled={!currentFilter.trim() && !appliedFilter}
                             className="text-[10px] flex items-center gap-1 text-accent hover:underline disabled:opacity-50 disabled:no-underline"
                         >
                             <Plus size={10} /> Save Current
@@ -143,7 +162,7 @@ export default function SavedFilters({
                                     key={f.id}
                                     className="group flex items-center justify-between p-1.5 hover:bg-bg-2 rounded cursor-pointer"
                                     onClick={() => {
-                                        onApplyFilter(f.filter);
+                                        handleApply(f);
                                         setIsOpen(false);
                                     }}
                                 >
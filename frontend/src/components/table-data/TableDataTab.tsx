@@ -13,6 +13,7 @@ import { useTablePage } from '../../context/TablePageContext';
 import { TableColumn, QueryResult, EditState, SchemaForeignKey } from '../../types';
 import Button from '../ui/Button';
 import SavedFilters from './SavedFilters';
+import { AppliedFilter } from '../../utils/tableFilter';
 import JsonEditorModal from '../ui/JsonEditorModal';
 import Checkbox from '../ui/Checkbox';
 import { formatCellValue, isComplexType } from '../../utils/cellFormatters';
@@ -41,6 +42,8 @@ interface TableDataTabProps {
   isCouchbase?: boolean;
   filter?: string;
   setFilter?: (val: string) => void;
+  appliedFilter?: AppliedFilter | null;
+  setAppliedFilter?: (val: AppliedFilter | null) => void;
   documentId?: string;
   setDocumentId?: (val: string) => void;
   bucket?: string;
@@ -75,6 +78,8 @@ export default function TableDataTab({
   isCouchbase = false,
   filter = '',
   setFilter,
+  appliedFilter = null,
+  setAppliedFilter,
   documentId = '',
   setDocumentId,
   bucket = '',
@@ -270,6 +275,8 @@ export default function TableDataTab({
     return [indexColumn, actionsColumn, ...dataColumns];
   }, [data?.columns, _columnsInfo, edits, onEdit, foreignKeys, connectionId, page, pageSize, inlineEditingEnabled, onDelete, onDuplicate, setSelectedRow, schema, table]);
 
+  const whereLabel = [appliedFilter?.label, filter.trim()].filter(Boolean).join(' AND ');
+
   const filteredRows = useMemo(() => {
     if (!localSearch) return data?.rows || [];
     const term = localSearch.toLowerCase();
@@ -334,8 +341,26 @@ export default function TableDataTab({
             schema={schema}
             table={table}
             currentFilter={filter}
-            onApplyFilter={(f) => setFilter?.(f)}
+            appliedFilter={appliedFilter}
+            onApplyFilter={(text, applied) => {
+              setFilter?.(text);
+              setAppliedFilter?.(applied);
+            }}
           />
+          {appliedFilter && (
+            <span
+              className="h-7 flex items-center gap-1 px-2 bg-accent/10 border border-accent/20 rounded-md text-[11px] text-accent font-mono max-w-[200px]"
+              title={appliedFilter.label}
+            >
+              <span className="truncate">{appliedFilter.label}</span>
+              <button
+                onClick={() => setAppliedFilter?.(null)}
+                className="text-accent/70 hover:text-accent"
+              >
+                <X size={12} />
+              </button>
+            </span>
+          )}
           <label className="text-[10px] font-bold text-text-tertiary uppercase tracking-tight whitespace-nowrap">
             {isCouchbase ? 'N1QL WHERE' : 'SQL WHERE'}
           </label>
@@ -405,11 +430,11 @@ export default function TableDataTab({
               {schema}.{table}
             </code>
           )}
-          {filter && (
+          {(filter || appliedFilter) && (
             <>
               <span className="opacity-40">where</span>
-              <code className="text-accent/80 font-mono font-bold bg-accent/5 px-1 rounded truncate max-w-[200px]" title={filter}>
-                {filter}
+              <code className="text-accent/80 font-mono font-bold bg-accent/5 px-1 rounded truncate max-w-[200px]" title={whereLabel}>
+                {whereLabel}
               </code>
             </>
           )}
@@ -1,5 +1,6 @@
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
 import api from '../services/api';
+import { bulkApi, SaveResultChangesRequest } from '../services/bulkApi';
 import { QueryResult, SavedQuery, SavedQueryFolder } from '../types';
 
 export const useExecuteQuery = (connectionId: string | undefined) => {
@@ -39,7 +40,7 @@ export const useExecuteQuery = (connectionId: string | undefined) => {
 
             const { data } = await api.post<QueryResult>(
                 url, 
-                { query, limit, offset, include_total_count, confirmed_unsafe },
+                { query, limit, offset, include_total_count, confirmed_unsafe, query_id },
                 { headers }
             );
             return data;
@@ -185,6 +186,19 @@ export const useUpdateQueryResult = (connectionId: string | undefined) => {
     });
 };
 
+export const useSaveQueryResultChanges = (connectionId: string | undefined) => {
+    const queryClient = useQueryClient();
+    return useMutation({
+        mutationFn: async (request: SaveResultChangesRequest) => {
+            if (!connectionId) throw new Error("Connection ID is required");
+            return bulkApi.saveResultChanges(connectionId, request);
+        },
+        onSuccess: () => {
+            queryClient.invalidateQueries({ queryKey: ['tableData', connectionId] });
+        }
+    });
+};
+
 export const useDeleteQueryResult = (connectionId: string | undefined) => {
     const queryClient = useQueryClient();
     return useMutation({
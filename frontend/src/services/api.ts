@@ -29,6 +29,17 @@ const SCHEMAS_COMMANDS: Record<string, string> = {
     'foreign-keys': 'schema_get_schema_foreign_keys'
 };
 
+// Helper to read a JSON-encoded query param, e.g. the table data filter
+const parseJsonParam = (urlObj: URL, key: string) => {
+    const raw = urlObj.searchParams.get(key);
+    if (!raw) return null;
+    try {
+        return JSON.parse(raw);
+    } catch {
+        throw new Error(`Invalid ${key}: expected JSON`);
+    }
+};
+
 // Helper to extract schema/table params
 const extractTableParams = (url: string, data: any) => {
     const urlObj = new URL('http://d' + url);
@@ -51,7 +62,14 @@ const handleConnectionRoutes = (method: string, url: string, data: any, connecti
     if (path.endsWith('/version')) return { command: 'get_connection', args: { id: connectionId } };
     if (path.endsWith('/test') && method === 'POST') return { command: 'test_connection_by_id', args: { id: connectionId } };
     if (path.endsWith('/switch-database') && method === 'POST') return { command: 'switch_database', args: { id: connectionId, request: data } };
-    if (path.endsWith('/execute')) return { command: 'execute_query', args: { connection_id: connectionId, request: { sql: data?.query || data?.sql, database: data?.database } } };
+    if (path.endsWith('/execute/statements')) return { command: 'execute_statements', args: { connection_id: connectionId, request: { script: data?.script, database: data?.database, query_id: data?.query_id, on_error: data?.on_error, confirmed_unsafe: data?.confirmed_unsafe } } };
+    if (path.endsWith('/export/query')) return { command: 'export_query', args: { connection_id: connectionId, request: { ...data, sql: data?.query || data?.sql } } };
+    if (path.endsWith('/export/table')) return { command: 'export_table', args: { connection_id: connectionId, request: data } };
+    if (path.endsWith('/dump') && method === 'POST') return { command: 'dump_database', args: { connection_id: connectionId, request: data } };
+    if (path.endsWith('/query-results/batch')) return { command: 'save_result_changes', args: { connection_id: connectionId, request: data } };
+    if (path.endsWith('/import/preview')) return { command: 'preview_import', args: { connection_id: connectionId, request: data } };
+    if (path.endsWith('/import')) return { command: 'import_file', args: { connection_id: connectionId, request: data } };
+    if (path.endsWith('/execute')) return { command: 'execute_query', args: { connection_id: connectionId, request: { sql: data?.query || data?.sql, database: data?.database, query_id: data?.query_id, params: data?.params } } };
     if (path.endsWith('/export-ddl')) return { command: 'export_postgres_ddl', args: { connection_id: connectionId, request: data } };
     if (path.endsWith('/search')) return { command: 'search_objects', args: { connection_id: connectionId, request: { query: data?.params?.q ?? data?.q } } };
 
@@ -95,7 +113,9 @@ const handleConnectionRoutes = (method: string, url: string, data: any, connecti
                         schema: params.schema,
                         table: params.table,
                         limit: urlObj.searchParams.get('limit') ? parseInt(urlObj.searchParams.get('limit')!) : null,
-                        offset: urlObj.searchParams.get('offset') ? parseInt(urlObj.searchParams.get('offset')!) : null
+                        offset: urlObj.searchParams.get('offset') ? parseInt(urlObj.searchParams.get('offset')!) : null,
+                        filter: parseJsonParam(urlObj, 'filter'),
+                        sort: parseJsonParam(urlObj, 'sort') ?? []
                     }
                 }
             };
@@ -123,6 +143,22 @@ const handleConnectionRoutes = (method: string, url: string, data: any, connecti
     const sessionMatch = path.match(/\/sessions\/([^/]+)$/);
     if (sessionMatch && method === 'DELETE') return { command: 'kill_session', args: { connection_id: connectionId, pid: parseInt(sessionMatch[1]) } };
 
+    if (path.endsWith('/transactions')) {
+        if (method === 'GET') return { command: 'list_transactions', args: { connection_id: connectionId } };
+        if (method === 'POST') return { command: 'begin_transaction', args: { connection_id: connectionId, request: data ?? null } };
+    }
+    const transactionMatch = path.match(/\/transactions\/([^/]+)\/(execute|commit|rollback)$/);
+    if (transactionMatch && method === 'POST') {
+        const [, transactionId, action] = transactionMatch;
+        if (action === 'execute') return { command: 'execute_in_transaction', args: { connection_id: connectionId, transaction_id: transactionId, request: data } };
+        return { command: `${action}_transaction`, args: { connection_id: connectionId, transaction_id: transactionId } };
+    }
+
+    const savedQueryRunMatch = path.match(/\/saved-queries\/([^/]+)\/run$/);
+    if (savedQueryRunMatch && method === 'POST') {
+        return { command: 'run_saved_query', args: { connection_id: connectionId, query_id: savedQueryRunMatch[1], request: data ?? {} } };
+    }
+
     if (path.endsWith('/history')) {
         if (method === 'GET') {
             const urlObj = new URL('http://d' + url);
@@ -182,6 +218,7 @@ const routeToCommand = (method: string, url: string, data?: any): { command: str
         if (method === 'POST') return { command: 'create_connection', args: { request: data } };
     }
     if (path === '/api/autocomplete' && method === 'POST') return { command: 'autocomplete_suggest', args: { request: data } };
+    if (path === '/api/queries/cancel' && method === 'POST') return { command: 'cancel_query', args: { request: data } };
 
     // Settings API
     if (path === '/api/settings') {
@@ -201,6 +238,8 @@ const routeToCommand = (method: string, url: string, data?: any): { command: str
         if (method === 'GET') return { command: 'list_snippets', args: {} };
         if (method === 'POST') return { command: 'create_snippet', args: { request: data } };
     }
+    const snippetRenderMatch = path.match(/\/api\/snippets\/([^/]+)\/render$/);
+    if (snippetRenderMatch && method === 'POST') return { command: 'render_snippet', args: { id: snippetRenderMatch[1], request: data } };
     const snippetMatch = path.match(/\/api\/snippets\/([^/]+)$/);
     if (snippetMatch) {
         const id = parseInt(snippetMatch[1]);
@@ -280,13 +319,15 @@ const api = {
             const isExecuteQuery = route.command === 'execute_query';
             const sql = data?.query || data?.sql;
             const isSelect = isExecuteQuery && sql?.trim()?.toLowerCase()?.startsWith('select');
+            // The same SQL with other parameter values is a different query.
+            const cacheKey = data?.params ? `${sql}\n${JSON.stringify(data.params)}` : sql;
 
             const [path] = url.split('?');
             const connMatch = path.match(/\/api\/connections\/([^/]+)/);
             const connectionId = connMatch ? connMatch[1] : null;
 
             if (isSelect && connectionId) {
-                const cached = queryCache.get(connectionId, sql);
+                const cached = queryCache.get(connectionId, cacheKey);
                 if (cached) {
                     log('response', `200 OK ${url} (CACHE HIT)`, cached);
                     return { data: cached as unknown as T, status: 200, statusText: 'OK (Cached)' };
@@ -294,10 +335,10 @@ const api = {
             }
 
             try {
-                const res = await requestDeduplicator.execute(`query:${connectionId}:${sql}`, async () => {
+                const res = await requestDeduplicator.execute(`query:${connectionId}:${cacheKey}`, async () => {
                     const result = await invoke(route.command, route.args);
                     if (isSelect && connectionId && result) {
-                        queryCache.set(connectionId, sql, result as any);
+                        queryCache.set(connectionId, cacheKey, result as any);
                     }
                     return result;
                 });
@@ -0,0 +1,53 @@
+import { Channel, invoke } from '@tauri-apps/api/core';
+import api from './api';
+
+export interface BulkProgress {
+    rows_written: number;
+    total_rows: number;
+    elapsed_ms: number;
+    rows_per_second: number;
+}
+
+export interface BulkReport {
+    rows_affected: number;
+    execution_time_ms: number;
+    rows_per_second: number;
+}
+
+export interface RowChange {
+    primary_key: Record<string, unknown>;
+    updates: Record<string, unknown>;
+}
+
+export interface SaveResultChangesRequest {
+    schema: string;
+    table: string;
+    updates?: RowChange[];
+    // Primary keys of the rows to delete.
+    deletes?: Record<string, unknown>[];
+    database?: string;
+}
+
+export interface InsertMockDataRequest {
+    connection_id: string;
+    schema: string;
+    // Same shape as the `/mock/preview` config.
+    config: unknown;
+}
+
+export const bulkApi = {
+    // Updates run before deletes; engines with a bulk path apply each in one transaction.
+    saveResultChanges: async (connectionId: string, request: SaveResultChangesRequest): Promise<BulkReport> => {
+        const { data } = await api.post<BulkReport>(`/api/connections/${connectionId}/query-results/batch`, request);
+        return data;
+    },
+
+    insertMockData: async (
+        request: InsertMockDataRequest,
+        onProgress?: (progress: BulkProgress) => void,
+    ): Promise<BulkReport> => {
+        const channel = new Channel<BulkProgress>();
+        if (onProgress) channel.onmessage = onProgress;
+        return invoke<BulkReport>('insert_mock_data', { request, onProgress: channel });
+    },
+};
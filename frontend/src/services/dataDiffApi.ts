@@ -0,0 +1,56 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type DataDiffStrategy = 'auto' | 'stream' | 'chunk_hash';
+
+export interface DataDiffTable {
+    connection_id: string;
+    schema: string;
+    table: string;
+}
+
+export interface DataDiffRequest {
+    source: DataDiffTable;
+    target: DataDiffTable;
+    // Defaults to the source table's primary key.
+    key_columns?: string[];
+    // Defaults to every column both tables have.
+    columns?: string[] | null;
+    strategy?: DataDiffStrategy;
+    chunk_size?: number | null;
+}
+
+export interface ValueChange {
+    column: string;
+    source: unknown;
+    target: unknown;
+}
+
+// Keys and values are named as in the target table.
+export type RowDiff =
+    | { kind: 'inserted'; key: Record<string, unknown>; values: Record<string, unknown> }
+    | { kind: 'updated'; key: Record<string, unknown>; changes: ValueChange[] }
+    | { kind: 'deleted'; key: Record<string, unknown> };
+
+export interface DataDiffReport {
+    key_columns: string[];
+    columns: string[];
+    strategy: DataDiffStrategy;
+    source_rows: number;
+    target_rows: number;
+    inserted: number;
+    updated: number;
+    deleted: number;
+    unchanged: number;
+    rows: RowDiff[];
+    // More rows differ than are listed; the counts cover all of them.
+    truncated: boolean;
+    sync_sql: string[];
+    execution_time_ms: number;
+}
+
+export const dataDiffApi = {
+    // Chunk hashes need both tables on Postgres or both on MySQL.
+    compareTableData: async (request: DataDiffRequest): Promise<DataDiffReport> => {
+        return invoke<DataDiffReport>('compare_table_data', { request });
+    },
+};
@@ -0,0 +1,82 @@
+import api from './api';
+
+export type ExportFormat = 'csv' | 'tsv' | 'json' | 'ndjson' | 'sql_insert' | 'markdown' | 'xlsx';
+
+export interface ExportOptions {
+    format?: ExportFormat;
+    delimiter?: string | null;
+    quote?: string;
+    quote_style?: 'necessary' | 'always' | 'non_numeric' | 'never';
+    header?: boolean;
+    null_value?: string;
+    // chrono format, e.g. '%d/%m/%Y %H:%M'
+    date_format?: string | null;
+    table_name?: string | null;
+}
+
+export interface ExportSummary {
+    path: string;
+    format: ExportFormat;
+    row_count: number;
+    bytes_written: number;
+    execution_time_ms: number;
+}
+
+export interface ExportQueryRequest {
+    query: string;
+    path: string;
+    options?: ExportOptions;
+    database?: string;
+    query_id?: string;
+    confirmed_unsafe?: boolean;
+}
+
+export interface ExportTableRequest {
+    schema: string;
+    table: string;
+    path: string;
+    filter?: unknown;
+    sort?: { column: string; direction: 'asc' | 'desc' }[];
+    fields?: string[];
+    options?: ExportOptions;
+    database?: string;
+    query_id?: string;
+}
+
+export interface DumpDatabaseRequest {
+    path: string;
+    schema_only?: boolean;
+    data_only?: boolean;
+    // Tables and views to dump; all of them when unset.
+    tables?: string[] | null;
+    include_drop?: boolean;
+    gzip?: boolean;
+    database?: string;
+}
+
+export interface DumpSummary {
+    path: string;
+    tables: number;
+    row_count: number;
+    bytes_written: number;
+    execution_time_ms: number;
+}
+
+export const exportApi = {
+    // Rows are written to `path` as they are read; cancel with `/api/queries/cancel` and the same `query_id`.
+    exportQuery: async (connectionId: string, request: ExportQueryRequest): Promise<ExportSummary> => {
+        const { data } = await api.post<ExportSummary>(`/api/connections/${connectionId}/export/query`, request);
+        return data;
+    },
+
+    exportTable: async (connectionId: string, request: ExportTableRequest): Promise<ExportSummary> => {
+        const { data } = await api.post<ExportSummary>(`/api/connections/${connectionId}/export/table`, request);
+        return data;
+    },
+
+    // MySQL and SQLite only; written by the app, without mysqldump or sqlite3.
+    dumpDatabase: async (connectionId: string, request: DumpDatabaseRequest): Promise<DumpSummary> => {
+        const { data } = await api.post<DumpSummary>(`/api/connections/${connectionId}/dump`, request);
+        return data;
+    },
+};
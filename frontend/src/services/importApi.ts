@@ -0,0 +1,101 @@
+import api from './api';
+
+export type ImportFormat = 'csv' | 'tsv' | 'json' | 'ndjson';
+export type ImportEncoding = 'utf8' | 'utf16_le' | 'utf16_be' | 'windows1252';
+export type ColumnKind =
+    | 'boolean' | 'integer' | 'float' | 'decimal' | 'date' | 'time'
+    | 'timestamp' | 'timestamp_tz' | 'uuid' | 'json' | 'text';
+
+// Unset fields are guessed from the file.
+export interface ImportOptions {
+    format?: ImportFormat | null;
+    encoding?: ImportEncoding | null;
+    delimiter?: string | null;
+    quote?: string;
+    has_header?: boolean | null;
+    null_values?: string[];
+    // chrono format, e.g. '%d/%m/%Y'
+    date_format?: string | null;
+}
+
+export interface FileLayout {
+    format: ImportFormat;
+    encoding: ImportEncoding;
+    delimiter: string;
+    quote: string;
+    has_header: boolean;
+}
+
+export interface SourceColumn {
+    name: string;
+    index: number;
+    kind: ColumnKind;
+}
+
+export interface ColumnMapping {
+    source: string;
+    target: string;
+}
+
+export interface ImportPreview {
+    layout: FileLayout;
+    columns: SourceColumn[];
+    rows: unknown[][];
+    table_exists: boolean;
+    target_columns: { name: string; data_type: string; is_nullable: boolean }[];
+    mappings: ColumnMapping[];
+    create_table_sql: string | null;
+}
+
+export interface ImportRowError {
+    row: number;
+    line: number | null;
+    column: string | null;
+    message: string;
+}
+
+export interface ImportReport {
+    rows_read: number;
+    rows_imported: number;
+    rows_failed: number;
+    errors: ImportRowError[];
+    errors_truncated: boolean;
+    created_table: boolean;
+    create_table_sql: string | null;
+    dry_run: boolean;
+    execution_time_ms: number;
+}
+
+export interface PreviewImportRequest {
+    schema: string;
+    table: string;
+    path: string;
+    options?: ImportOptions;
+    database?: string;
+}
+
+export interface ImportFileRequest {
+    schema: string;
+    table: string;
+    path: string;
+    options?: ImportOptions;
+    // The suggested mappings when unset.
+    mappings?: ColumnMapping[];
+    create_table?: boolean;
+    dry_run?: boolean;
+    on_error?: 'stop_on_error' | 'continue_on_error';
+    batch_size?: number;
+    database?: string;
+}
+
+export const importApi = {
+    preview: async (connectionId: string, request: PreviewImportRequest): Promise<ImportPreview> => {
+        const { data } = await api.post<ImportPreview>(`/api/connections/${connectionId}/import/preview`, request);
+        return data;
+    },
+
+    importFile: async (connectionId: string, request: ImportFileRequest): Promise<ImportReport> => {
+        const { data } = await api.post<ImportReport>(`/api/connections/${connectionId}/import`, request);
+        return data;
+    },
+};
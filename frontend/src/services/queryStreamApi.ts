@@ -0,0 +1,33 @@
+import { Channel, invoke } from '@tauri-apps/api/core';
+
+export type QueryStreamEvent =
+    | { type: 'meta'; columns: string[]; total_count: number | null; limit: number | null; offset: number | null }
+    | { type: 'rows'; rows: unknown[][] }
+    | { type: 'progress'; row_count: number; elapsed_ms: number }
+    | { type: 'done'; row_count: number; affected_rows: number; execution_time_ms: number; truncated: boolean }
+    | { type: 'error'; message: string };
+
+export interface QueryStreamRequest {
+    sql: string;
+    database?: string;
+    query_id?: string;
+    limit?: number;
+    offset?: number;
+    include_total_count?: boolean;
+    confirmed_unsafe?: boolean;
+    batch_size?: number;
+}
+
+export const queryStreamApi = {
+    // Resolves once the stream has ended; rows arrive through `onEvent` in batches.
+    // Cancel with the regular `/api/queries/cancel` route and the same `query_id`.
+    execute: async (
+        connectionId: string,
+        request: QueryStreamRequest,
+        onEvent: (event: QueryStreamEvent) => void,
+    ): Promise<void> => {
+        const channel = new Channel<QueryStreamEvent>();
+        channel.onmessage = onEvent;
+        await invoke('execute_query_stream', { connectionId, request, onEvent: channel });
+    },
+};
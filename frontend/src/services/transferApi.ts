@@ -0,0 +1,57 @@
+import { Channel, invoke } from '@tauri-apps/api/core';
+
+export type TransferMode = 'truncate' | 'append' | 'upsert';
+
+export interface TransferRequest {
+    source: {
+        connection_id: string;
+        schema: string;
+        tables: string[];
+        // Same shape as the data grid filter; applied to every table.
+        filter?: unknown;
+    };
+    target: {
+        connection_id: string;
+        // Defaults to the source schema on the same engine, else the target's default.
+        schema?: string | null;
+    };
+    mode?: TransferMode;
+    create_tables?: boolean;
+    batch_size?: number | null;
+}
+
+export interface TransferProgress {
+    table: string;
+    table_index: number;
+    table_count: number;
+    rows_written: number;
+    elapsed_ms: number;
+    rows_per_second: number;
+}
+
+export interface TableTransfer {
+    table: string;
+    created: boolean;
+    rows_written: number;
+    execution_time_ms: number;
+}
+
+export interface TransferReport {
+    tables: TableTransfer[];
+    rows_written: number;
+    execution_time_ms: number;
+}
+
+export const transferApi = {
+    // Tables are copied parents first when the source reports foreign keys.
+    // Cancel with the regular `/api/queries/cancel` route and the same `queryId`.
+    transferTables: async (
+        request: TransferRequest,
+        onProgress?: (progress: TransferProgress) => void,
+        queryId?: string,
+    ): Promise<TransferReport> => {
+        const channel = new Channel<TransferProgress>();
+        if (onProgress) channel.onmessage = onProgress;
+        return invoke<TransferReport>('transfer_tables', { request, onProgress: channel, queryId });
+    },
+};
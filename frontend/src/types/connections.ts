@@ -34,6 +34,9 @@ export interface Connection {
   tags?: string;
   environment?: string;
   safe_mode_level?: number;
+  statement_timeout_ms?: number | null;
+  max_result_rows?: number | null;
+  max_result_bytes?: number | null;
   ssh_enabled?: boolean;
   ssh_host?: string;
   ssh_port?: number;
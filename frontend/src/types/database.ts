@@ -20,6 +20,7 @@ export interface QueryResult {
   execution_time_ms?: number;
   json?: any;
   display_mode?: 'table' | 'json';
+  truncated?: boolean;
 }
 
 export type EditState = Record<string, Record<number, unknown>>;
@@ -1,8 +1,9 @@
 export interface SnippetVariable {
     name: string;
-    type: 'string' | 'number' | 'boolean' | 'date';
+    type: 'string' | 'number' | 'boolean' | 'date' | 'identifier' | 'raw';
     default?: any;
     required: boolean;
+    choices?: any[];
     description?: string;
 }
 
@@ -0,0 +1,13 @@
+// Filters sent to the table data and saved filter endpoints: the text typed in
+// the WHERE box travels as a `raw` node next to an applied typed filter tree.
+
+export interface AppliedFilter {
+  label: string;
+  tree: unknown;
+}
+
+export function combineFilters(text: string, applied: AppliedFilter | null): unknown | null {
+  const raw = text.trim() ? { type: 'raw', sql: text.trim() } : null;
+  if (raw && applied) return { type: 'and', children: [applied.tree, raw] };
+  return raw ?? applied?.tree ?? null;
+}